@@ -7,7 +7,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Compile the proto files we need for the MVP
     // Start with manager.proto which imports device, instance, protocol_settings, rpc_options
     tonic_build::configure()
-        .build_server(false) // We're only a client
+        // Server stubs back the mock MinKNOW server used by the integration tests
+        .build_server(true)
+        .generate_default_stubs(true)
         .build_client(true)
         .compile_protos(
             &[
@@ -17,6 +19,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "proto/minknow_api/instance.proto",
                 "proto/minknow_api/data.proto",
                 "proto/minknow_api/device.proto",
+                "proto/minknow_api/log.proto",
             ],
             &["proto/"],
         )?;
@@ -0,0 +1,236 @@
+//! Threshold alert rules.
+//!
+//! A rule such as `throughput_gbph < 0.5` compares one run metric against a
+//! fixed threshold. Rules come from the `[alerts]` config section and are
+//! evaluated by the TUI whenever the metric they watch is updated.
+
+use crate::client::StatsSnapshot;
+use std::fmt;
+
+/// Run metric an alert rule can watch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertMetric {
+    /// Throughput in gigabases per hour.
+    ThroughputGbph,
+    /// Pores currently sequencing.
+    ActivePores,
+    /// Percentage of reads passing filters.
+    PassRate,
+    /// Mean read quality score.
+    MeanQuality,
+    /// Mean read length in bases.
+    MeanReadLength,
+}
+
+impl AlertMetric {
+    pub const ALL: [AlertMetric; 5] = [
+        AlertMetric::ThroughputGbph,
+        AlertMetric::ActivePores,
+        AlertMetric::PassRate,
+        AlertMetric::MeanQuality,
+        AlertMetric::MeanReadLength,
+    ];
+
+    /// Name used in rules.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertMetric::ThroughputGbph => "throughput_gbph",
+            AlertMetric::ActivePores => "active_pores",
+            AlertMetric::PassRate => "pass_rate",
+            AlertMetric::MeanQuality => "mean_quality",
+            AlertMetric::MeanReadLength => "mean_read_length",
+        }
+    }
+
+    /// Current value of the metric, or `None` while no reads have been
+    /// counted for metrics that are meaningless without them.
+    pub fn value(&self, stats: &StatsSnapshot) -> Option<f64> {
+        match self {
+            AlertMetric::ThroughputGbph => Some(stats.throughput_gbph),
+            AlertMetric::ActivePores => Some(stats.active_pores as f64),
+            AlertMetric::PassRate if stats.reads_passed + stats.reads_failed == 0 => None,
+            AlertMetric::PassRate => Some(stats.pass_rate()),
+            AlertMetric::MeanQuality | AlertMetric::MeanReadLength
+                if stats.reads_processed == 0 =>
+            {
+                None
+            }
+            AlertMetric::MeanQuality => Some(stats.mean_quality),
+            AlertMetric::MeanReadLength => Some(stats.mean_read_length),
+        }
+    }
+}
+
+/// Direction of a threshold comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Below,
+    Above,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Below => "<",
+            Comparison::Above => ">",
+        }
+    }
+}
+
+/// A threshold on one metric, e.g. `active_pores < 200`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    pub threshold: f64,
+}
+
+impl AlertRule {
+    /// Returns the metric value if it breaches the threshold.
+    pub fn check(&self, stats: &StatsSnapshot) -> Option<f64> {
+        let value = self.metric.value(stats)?;
+        let breached = match self.comparison {
+            Comparison::Below => value < self.threshold,
+            Comparison::Above => value > self.threshold,
+        };
+        breached.then_some(value)
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.metric.name(),
+            self.comparison.symbol(),
+            self.threshold
+        )
+    }
+}
+
+impl std::str::FromStr for AlertRule {
+    type Err = ();
+
+    /// Parses `<metric> <|> <number>`; whitespace around the operator is optional.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (idx, comparison) = s
+            .char_indices()
+            .find_map(|(i, c)| match c {
+                '<' => Some((i, Comparison::Below)),
+                '>' => Some((i, Comparison::Above)),
+                _ => None,
+            })
+            .ok_or(())?;
+        let name = s[..idx].trim();
+        let metric = AlertMetric::ALL
+            .into_iter()
+            .find(|m| m.name().eq_ignore_ascii_case(name))
+            .ok_or(())?;
+        let threshold: f64 = s[idx + 1..].trim().parse().map_err(|_| ())?;
+        if !threshold.is_finite() {
+            return Err(());
+        }
+        Ok(AlertRule {
+            metric,
+            comparison,
+            threshold,
+        })
+    }
+}
+
+/// A rule currently breached on a position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breach {
+    pub rule: AlertRule,
+    /// Metric value when last evaluated.
+    pub value: f64,
+}
+
+impl Breach {
+    /// Short description for tables and toasts, e.g. `throughput_gbph 0.31 < 0.5`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {} {} {}",
+            self.rule.metric.name(),
+            format_value(self.value),
+            self.rule.comparison.symbol(),
+            self.rule.threshold
+        )
+    }
+}
+
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+/// Evaluates the rules that watch any of `metrics`, returning those breached.
+pub fn evaluate(
+    rules: &[AlertRule],
+    metrics: &[AlertMetric],
+    stats: &StatsSnapshot,
+) -> Vec<Breach> {
+    rules
+        .iter()
+        .filter(|rule| metrics.contains(&rule.metric))
+        .filter_map(|rule| rule.check(stats).map(|value| Breach { rule: *rule, value }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let rule: AlertRule = "throughput_gbph < 0.5".parse().unwrap();
+        assert_eq!(rule.metric, AlertMetric::ThroughputGbph);
+        assert_eq!(rule.comparison, Comparison::Below);
+        assert_eq!(rule.threshold, 0.5);
+        assert_eq!(rule.to_string(), "throughput_gbph < 0.5");
+
+        let rule: AlertRule = "mean_read_length>20000".parse().unwrap();
+        assert_eq!(rule.metric, AlertMetric::MeanReadLength);
+        assert_eq!(rule.comparison, Comparison::Above);
+
+        assert!("throughput < 0.5".parse::<AlertRule>().is_err());
+        assert!("active_pores = 200".parse::<AlertRule>().is_err());
+        assert!("active_pores < many".parse::<AlertRule>().is_err());
+        assert!("pass_rate < NaN".parse::<AlertRule>().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_only_watched_metrics() {
+        let rules: Vec<AlertRule> = ["active_pores < 200", "pass_rate < 80"]
+            .iter()
+            .map(|r| r.parse().unwrap())
+            .collect();
+        let stats = StatsSnapshot {
+            reads_processed: 100,
+            reads_passed: 70,
+            reads_failed: 30,
+            active_pores: 150,
+            ..Default::default()
+        };
+
+        let breaches = evaluate(&rules, &[AlertMetric::ActivePores], &stats);
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].summary(), "active_pores 150 < 200");
+
+        let breaches = evaluate(&rules, &AlertMetric::ALL, &stats);
+        assert_eq!(breaches.len(), 2);
+        assert_eq!(breaches[1].summary(), "pass_rate 70 < 80");
+    }
+
+    #[test]
+    fn test_read_metrics_skipped_without_reads() {
+        let rule: AlertRule = "pass_rate < 80".parse().unwrap();
+        assert_eq!(rule.check(&StatsSnapshot::default()), None);
+        let rule: AlertRule = "mean_quality < 9".parse().unwrap();
+        assert_eq!(rule.check(&StatsSnapshot::default()), None);
+    }
+}
@@ -0,0 +1,277 @@
+//! Run yield projections and output rates.
+//!
+//! Extrapolates a run's cumulative yield, in bases or reads, to the end of
+//! the run. Two models are offered: a linear one that assumes the recent
+//! output rate holds, and a decay one that fits the exponential fall-off in
+//! output as pores are used up. [`rates`] turns the same cumulative samples
+//! into output per hour over time.
+
+/// Window the linear model takes its output rate from.
+const LINEAR_WINDOW: u32 = 3600;
+
+/// Width of the bins the decay model measures output rates over.
+const DECAY_BIN: u32 = 1800;
+
+/// Age at which a bin counts half as much in the decay fit.
+const DECAY_HALF_LIFE: f64 = 6.0 * 3600.0;
+
+/// Points in a projected curve, including both ends.
+const CURVE_POINTS: u32 = 48;
+
+/// Shortest bin [`rates`] measures over, so per-minute samples are not
+/// charted as noise.
+const MIN_RATE_BIN: u32 = 300;
+
+/// How the output rate is extrapolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionModel {
+    /// The rate of the last hour continues until the run ends.
+    Linear,
+    /// The rate keeps falling off exponentially, fitted to the run so far
+    /// with recent output weighted most.
+    Decay,
+}
+
+impl ProjectionModel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectionModel::Linear => "linear",
+            ProjectionModel::Decay => "decay",
+        }
+    }
+}
+
+/// A projected end of run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub model: ProjectionModel,
+    /// Cumulative total expected by the end of the run.
+    pub final_total: u64,
+    /// Seconds from the latest yield sample until the run ends.
+    pub remaining_seconds: u32,
+    /// Projected cumulative total from the latest sample to the end of the
+    /// run, as `(seconds, total)` pairs.
+    pub curve: Vec<(u32, u64)>,
+}
+
+/// Projects the yield of a run expected to last `run_length` seconds from
+/// cumulative `(seconds, total)` samples in time order.
+///
+/// Returns `None` until there is enough history for the model: a few
+/// minutes for the linear model and three rate bins for the decay model.
+pub fn project(
+    points: &[(u32, u64)],
+    run_length: u32,
+    model: ProjectionModel,
+) -> Option<Projection> {
+    let &(first, _) = points.first()?;
+    let &(last, total) = points.last()?;
+    if last.saturating_sub(first) < 300 {
+        return None;
+    }
+
+    // Output produced in the `elapsed` seconds after the latest sample.
+    let produced: Box<dyn Fn(f64) -> f64> = match model {
+        ProjectionModel::Linear => {
+            let since = last.saturating_sub(LINEAR_WINDOW).max(first);
+            let gained = total as f64 - total_at(points, since);
+            let rate = gained / (last - since) as f64;
+            Box::new(move |elapsed| rate * elapsed)
+        }
+        ProjectionModel::Decay => {
+            let (rate, k) = fit_decay(points)?;
+            if k > 0.0 {
+                Box::new(move |elapsed| -rate * (-k * elapsed).exp_m1() / k)
+            } else {
+                Box::new(move |elapsed| rate * elapsed)
+            }
+        }
+    };
+
+    let remaining_seconds = run_length.saturating_sub(last);
+    let curve = (0..CURVE_POINTS)
+        .map(|i| {
+            let elapsed = remaining_seconds as f64 * i as f64 / (CURVE_POINTS - 1) as f64;
+            let projected = total as f64 + produced(elapsed).max(0.0);
+            (last + elapsed.round() as u32, projected.round() as u64)
+        })
+        .collect::<Vec<_>>();
+
+    Some(Projection {
+        model,
+        final_total: curve.last().map(|&(_, total)| total).unwrap_or(total),
+        remaining_seconds,
+        curve,
+    })
+}
+
+/// Output per hour over time from cumulative `(seconds, total)` samples in
+/// time order, measured over up to `bins` equal bins (none shorter than
+/// five minutes). Bins are aligned to the latest sample so the newest is
+/// always full; each rate is paired with the middle of its bin.
+pub fn rates(points: &[(u32, u64)], bins: u32) -> Vec<(u32, f64)> {
+    let (Some(&(first, _)), Some(&(last, _))) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+    let width = ((last - first) / bins.max(1)).max(MIN_RATE_BIN);
+    let mut rates: Vec<(u32, f64)> = (1..=(last - first) / width)
+        .map(|i| {
+            let end = last - (i - 1) * width;
+            let start = end - width;
+            let gained = total_at(points, end) - total_at(points, start);
+            (start + width / 2, gained.max(0.0) * 3600.0 / width as f64)
+        })
+        .collect();
+    rates.reverse();
+    rates
+}
+
+/// Cumulative total at `seconds`, interpolated between samples.
+fn total_at(points: &[(u32, u64)], seconds: u32) -> f64 {
+    let after = points.partition_point(|&(s, _)| s < seconds);
+    match (after.checked_sub(1).map(|i| points[i]), points.get(after)) {
+        (Some((s0, v0)), Some(&(s1, v1))) if s1 > s0 => {
+            let fraction = (seconds - s0) as f64 / (s1 - s0) as f64;
+            v0 as f64 + (v1 as f64 - v0 as f64) * fraction
+        }
+        (_, Some(&(_, v))) | (Some((_, v)), None) => v as f64,
+        (None, None) => 0.0,
+    }
+}
+
+/// Fits `rate = r0 · e^(-k·t)` to the output rate of the run, weighting
+/// recent bins most. Returns the fitted rate at the latest sample and the
+/// decay constant, which is zero if output is not falling.
+fn fit_decay(points: &[(u32, u64)]) -> Option<(f64, f64)> {
+    let first = points.first()?.0;
+    let last = points.last()?.0;
+
+    // Bins are aligned to the latest sample so the newest is always full.
+    let bins: Vec<(f64, f64)> = (1..=(last - first) / DECAY_BIN)
+        .map(|i| {
+            let end = last - (i - 1) * DECAY_BIN;
+            let start = end - DECAY_BIN;
+            let rate = (total_at(points, end) - total_at(points, start)) / DECAY_BIN as f64;
+            ((start + end) as f64 / 2.0, rate)
+        })
+        .filter(|&(_, rate)| rate > 0.0)
+        .collect();
+    if bins.len() < 3 {
+        return None;
+    }
+
+    // Weighted least squares of ln(rate) against time.
+    let weight = |t: f64| 0.5f64.powf((last as f64 - t) / DECAY_HALF_LIFE);
+    let (mut sw, mut st, mut sy) = (0.0, 0.0, 0.0);
+    for &(t, rate) in &bins {
+        let w = weight(t);
+        sw += w;
+        st += w * t;
+        sy += w * rate.ln();
+    }
+    let (mean_t, mean_y) = (st / sw, sy / sw);
+    let (mut stt, mut sty) = (0.0, 0.0);
+    for &(t, rate) in &bins {
+        let w = weight(t);
+        stt += w * (t - mean_t).powi(2);
+        sty += w * (t - mean_t) * (rate.ln() - mean_y);
+    }
+    let slope = sty / stt;
+    let k = (-slope).max(0.0);
+    let rate = (mean_y + slope.min(0.0) * (last as f64 - mean_t)).exp();
+    Some((rate, k))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yield_curve(hours: u32, total: impl Fn(f64) -> f64) -> Vec<(u32, u64)> {
+        (0..=hours * 60)
+            .map(|minute| (minute * 60, total(minute as f64 * 60.0) as u64))
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_projection_extends_recent_rate() {
+        // Slow start, then a steady 1000 bases per second.
+        let points = yield_curve(4, |t| {
+            if t < 7200.0 {
+                t * 100.0
+            } else {
+                720_000.0 + (t - 7200.0) * 1000.0
+            }
+        });
+        let projection = project(&points, 10 * 3600, ProjectionModel::Linear).unwrap();
+
+        assert_eq!(projection.remaining_seconds, 6 * 3600);
+        let expected = 720_000 + 2 * 3600 * 1000 + 6 * 3600 * 1000;
+        assert!((projection.final_total as i64 - expected as i64).abs() < 10);
+        assert_eq!(projection.curve.first(), points.last());
+        assert_eq!(projection.curve.last().unwrap().0, 10 * 3600);
+    }
+
+    #[test]
+    fn test_decay_projection_fits_falling_rate() {
+        // Output halves every four hours.
+        let k = std::f64::consts::LN_2 / (4.0 * 3600.0);
+        let total = |t: f64| 2000.0 * (1.0 - (-k * t).exp()) / k;
+        let points = yield_curve(12, total);
+
+        let decay = project(&points, 24 * 3600, ProjectionModel::Decay).unwrap();
+        let linear = project(&points, 24 * 3600, ProjectionModel::Linear).unwrap();
+
+        let expected = total(24.0 * 3600.0);
+        assert!((decay.final_total as f64 - expected).abs() / expected < 0.01);
+        assert!(linear.final_total > decay.final_total);
+    }
+
+    #[test]
+    fn test_decay_without_decline_is_linear() {
+        let points = yield_curve(3, |t| t * 500.0);
+        let decay = project(&points, 5 * 3600, ProjectionModel::Decay).unwrap();
+        let linear = project(&points, 5 * 3600, ProjectionModel::Linear).unwrap();
+        assert!((decay.final_total as i64 - linear.final_total as i64).abs() < 10);
+    }
+
+    #[test]
+    fn test_projection_needs_history() {
+        let points = yield_curve(1, |t| t * 500.0);
+        assert!(project(&points[..3], 3600, ProjectionModel::Linear).is_none());
+        assert!(project(&points, 3600, ProjectionModel::Linear).is_some());
+        // An hour makes only two decay bins.
+        assert!(project(&points, 3600, ProjectionModel::Decay).is_none());
+        assert!(project(&[], 3600, ProjectionModel::Linear).is_none());
+    }
+
+    #[test]
+    fn test_rates_follow_output_per_hour() {
+        // 1000 an hour for two hours, then 500 an hour.
+        let points = yield_curve(4, |t| {
+            if t < 7200.0 {
+                t * 1000.0 / 3600.0
+            } else {
+                2000.0 + (t - 7200.0) * 500.0 / 3600.0
+            }
+        });
+        let rates = rates(&points, 8);
+
+        assert_eq!(rates.len(), 8);
+        assert_eq!(rates.first().unwrap().0, 900);
+        assert_eq!(rates.last().unwrap().0, 4 * 3600 - 900);
+        assert!((rates[0].1 - 1000.0).abs() < 5.0);
+        assert!((rates[7].1 - 500.0).abs() < 5.0);
+
+        // Bins never get shorter than five minutes.
+        assert_eq!(super::rates(&points[..11], 60).len(), 2);
+        assert!(super::rates(&[], 60).is_empty());
+    }
+
+    #[test]
+    fn test_projection_past_run_length_is_current_yield() {
+        let points = yield_curve(2, |t| t * 500.0);
+        let projection = project(&points, 3600, ProjectionModel::Linear).unwrap();
+        assert_eq!(projection.remaining_seconds, 0);
+        assert_eq!(projection.final_total, points.last().unwrap().1);
+    }
+}
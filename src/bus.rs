@@ -0,0 +1,537 @@
+//! Internal event bus.
+//!
+//! The network layer publishes typed [`BusEvent`]s describing what it learned
+//! from MinKNOW; the TUI and any other subsystem subscribe with an
+//! [`EventFilter`] and react to the events they care about. Publishers never
+//! touch subscriber state directly, so new subsystems can be added without
+//! changing the render loop.
+//!
+//! Built on [`tokio::sync::broadcast`]: every subscriber sees every matching
+//! event, in publish order. A subscriber that falls too far behind skips the
+//! events it missed rather than blocking publishers.
+
+use crate::client::diagnostics::ErrorClass;
+use crate::client::{
+    BarcodeYield, ChannelLayout, ChannelStatesSnapshot, ClientError, DiskSpaceInfo,
+    DutyTimeSnapshot, FlowCellInfo, LastRun, MuxScanResult, OutputInfo, PhaseEvent, Position,
+    ProtocolInfo, QScoreHistogram, ReadLengthHistogram, RunInfo, RunState, RunSummary,
+    RunUntilCriteria, ServerInfo, SignalChunk, SpeedDataPoint, StatsSnapshot, YieldDataPoint,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// Default number of events buffered per subscriber.
+pub const DEFAULT_CAPACITY: usize = 4096;
+
+/// Broad category of a [`BusEvent`], used for subscription filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// Connection to the MinKNOW manager changed.
+    Connection,
+    /// A position's run or flow cell state changed.
+    State,
+    /// New metrics, history, or chart data arrived.
+    Data,
+    /// Something the user should be told about.
+    Alert,
+}
+
+/// An event published on the bus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BusEvent {
+    Connected,
+    Disconnected {
+        reason: String,
+        class: Option<ErrorClass>,
+    },
+    Reconnecting {
+        attempt: u32,
+    },
+    /// The manager is retried after `delay` unless a refresh comes first.
+    ReconnectScheduled {
+        attempt: u32,
+        delay: Duration,
+    },
+    PositionsUpdated(Vec<Position>),
+    /// One of several watched hosts connected. With a single host,
+    /// [`BusEvent::Connected`] is published instead.
+    HostConnected {
+        host: String,
+    },
+    /// One of several watched hosts failed; it is retried with backoff.
+    HostDisconnected {
+        host: String,
+        reason: String,
+    },
+    /// The positions of one of several watched hosts, replacing those
+    /// previously listed for it.
+    HostPositions {
+        host: String,
+        positions: Vec<Position>,
+    },
+    /// MinKNOW refused a control request for lack of permission, so the
+    /// connection is read-only.
+    ReadOnly {
+        reason: String,
+    },
+    /// The manager connected to, published on each connection. Only
+    /// published with a single host.
+    ServerInfo {
+        info: ServerInfo,
+    },
+    /// Round-trip time of a lightweight request to the manager, measured
+    /// every refresh interval. Only published with a single host.
+    Latency {
+        rtt: Duration,
+    },
+    /// Positions that appeared or changed, and names of positions that went
+    /// away, since the last position list.
+    PositionsChanged {
+        updated: Vec<Position>,
+        removed: Vec<String>,
+    },
+    RunStateChanged {
+        position: String,
+        state: RunState,
+    },
+    FlowCellInfo {
+        position: String,
+        info: FlowCellInfo,
+    },
+    RunInfo {
+        position: String,
+        info: RunInfo,
+    },
+    Stats {
+        position: String,
+        stats: StatsSnapshot,
+    },
+    /// Throughput derived from the two most recent yield points.
+    Throughput {
+        position: String,
+        bases_per_second: f64,
+    },
+    /// Channels with more than 10% pore occupancy in the duty time stream.
+    /// Published for every position with a run, whichever view is open, so
+    /// its history is one metric throughout.
+    ActivePores {
+        position: String,
+        count: u32,
+    },
+    MeanQuality {
+        position: String,
+        quality: f64,
+    },
+    /// Yield history for a run. When `merge` is set the points should be
+    /// merged into existing history rather than replacing it.
+    YieldHistory {
+        position: String,
+        points: Vec<YieldDataPoint>,
+        merge: bool,
+    },
+    /// Yield history per barcode. `merge` has the same meaning as for
+    /// [`BusEvent::YieldHistory`].
+    BarcodeYield {
+        position: String,
+        barcodes: Vec<BarcodeYield>,
+        merge: bool,
+    },
+    PhaseHistory {
+        position: String,
+        events: Vec<PhaseEvent>,
+    },
+    Histogram {
+        position: String,
+        histogram: ReadLengthHistogram,
+    },
+    /// Usage of the fullest file-system MinKNOW writes to.
+    DiskSpace {
+        position: String,
+        info: DiskSpaceInfo,
+    },
+    /// Output location and writer progress of the current run.
+    Output {
+        position: String,
+        info: OutputInfo,
+    },
+    /// Finished protocol runs on a position, newest first.
+    RunHistory {
+        position: String,
+        runs: Vec<RunSummary>,
+    },
+    /// The most recent finished run of an idle position, or `None` if it
+    /// has none.
+    LastRun {
+        position: String,
+        run: Option<Box<LastRun>>,
+    },
+    /// Protocols that can be started on a position.
+    Protocols {
+        position: String,
+        protocols: Vec<ProtocolInfo>,
+    },
+    DutyTime {
+        position: String,
+        duty_time: DutyTimeSnapshot,
+    },
+    ChannelStates {
+        position: String,
+        states: ChannelStatesSnapshot,
+    },
+    ChannelLayout {
+        position: String,
+        layout: ChannelLayout,
+    },
+    QScoreHistogram {
+        position: String,
+        histogram: QScoreHistogram,
+    },
+    /// Pore scan results of the current run, oldest first.
+    MuxScans {
+        position: String,
+        scans: Vec<MuxScanResult>,
+    },
+    /// Translocation speed of the current run, oldest first.
+    TranslocationSpeed {
+        position: String,
+        points: Vec<SpeedDataPoint>,
+    },
+    /// Run-until criteria that will stop the current run.
+    RunUntilTargets {
+        position: String,
+        targets: RunUntilCriteria,
+    },
+    /// Progress of the current run towards its run-until criteria.
+    RunUntilProgress {
+        position: String,
+        progress: RunUntilCriteria,
+    },
+    Signal {
+        position: String,
+        chunk: SignalChunk,
+    },
+    Alert {
+        position: Option<String>,
+        message: String,
+    },
+}
+
+impl BusEvent {
+    /// Builds a [`BusEvent::Disconnected`] from a connection error.
+    pub fn connection_failed(err: &ClientError) -> Self {
+        BusEvent::Disconnected {
+            reason: err.display_message(),
+            class: Some(ErrorClass::classify(err)),
+        }
+    }
+
+    /// Builds a [`BusEvent::Alert`] that is not tied to a position.
+    pub fn alert(message: impl Into<String>) -> Self {
+        BusEvent::Alert {
+            position: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn kind(&self) -> EventKind {
+        match self {
+            BusEvent::Connected
+            | BusEvent::Disconnected { .. }
+            | BusEvent::Reconnecting { .. }
+            | BusEvent::ReconnectScheduled { .. }
+            | BusEvent::HostConnected { .. }
+            | BusEvent::HostDisconnected { .. }
+            | BusEvent::ReadOnly { .. }
+            | BusEvent::ServerInfo { .. }
+            | BusEvent::Latency { .. } => EventKind::Connection,
+            BusEvent::PositionsUpdated(_)
+            | BusEvent::HostPositions { .. }
+            | BusEvent::PositionsChanged { .. }
+            | BusEvent::RunStateChanged { .. }
+            | BusEvent::FlowCellInfo { .. }
+            | BusEvent::RunInfo { .. } => EventKind::State,
+            BusEvent::Stats { .. }
+            | BusEvent::Throughput { .. }
+            | BusEvent::ActivePores { .. }
+            | BusEvent::MeanQuality { .. }
+            | BusEvent::YieldHistory { .. }
+            | BusEvent::BarcodeYield { .. }
+            | BusEvent::PhaseHistory { .. }
+            | BusEvent::Histogram { .. }
+            | BusEvent::DiskSpace { .. }
+            | BusEvent::Output { .. }
+            | BusEvent::RunHistory { .. }
+            | BusEvent::LastRun { .. }
+            | BusEvent::Protocols { .. }
+            | BusEvent::DutyTime { .. }
+            | BusEvent::ChannelStates { .. }
+            | BusEvent::ChannelLayout { .. }
+            | BusEvent::QScoreHistogram { .. }
+            | BusEvent::MuxScans { .. }
+            | BusEvent::TranslocationSpeed { .. }
+            | BusEvent::RunUntilTargets { .. }
+            | BusEvent::RunUntilProgress { .. }
+            | BusEvent::Signal { .. } => EventKind::Data,
+            BusEvent::Alert { .. } => EventKind::Alert,
+        }
+    }
+
+    /// Returns the position this event concerns, if it is position-specific.
+    pub fn position(&self) -> Option<&str> {
+        match self {
+            BusEvent::Connected
+            | BusEvent::Disconnected { .. }
+            | BusEvent::Reconnecting { .. }
+            | BusEvent::ReconnectScheduled { .. }
+            | BusEvent::HostConnected { .. }
+            | BusEvent::HostDisconnected { .. }
+            | BusEvent::ReadOnly { .. }
+            | BusEvent::ServerInfo { .. }
+            | BusEvent::Latency { .. }
+            | BusEvent::PositionsUpdated(_)
+            | BusEvent::HostPositions { .. }
+            | BusEvent::PositionsChanged { .. } => None,
+            BusEvent::RunStateChanged { position, .. }
+            | BusEvent::FlowCellInfo { position, .. }
+            | BusEvent::RunInfo { position, .. }
+            | BusEvent::Stats { position, .. }
+            | BusEvent::Throughput { position, .. }
+            | BusEvent::ActivePores { position, .. }
+            | BusEvent::MeanQuality { position, .. }
+            | BusEvent::YieldHistory { position, .. }
+            | BusEvent::BarcodeYield { position, .. }
+            | BusEvent::PhaseHistory { position, .. }
+            | BusEvent::Histogram { position, .. }
+            | BusEvent::DiskSpace { position, .. }
+            | BusEvent::Output { position, .. }
+            | BusEvent::RunHistory { position, .. }
+            | BusEvent::LastRun { position, .. }
+            | BusEvent::Protocols { position, .. }
+            | BusEvent::DutyTime { position, .. }
+            | BusEvent::ChannelStates { position, .. }
+            | BusEvent::ChannelLayout { position, .. }
+            | BusEvent::QScoreHistogram { position, .. }
+            | BusEvent::MuxScans { position, .. }
+            | BusEvent::TranslocationSpeed { position, .. }
+            | BusEvent::RunUntilTargets { position, .. }
+            | BusEvent::RunUntilProgress { position, .. }
+            | BusEvent::Signal { position, .. } => Some(position),
+            BusEvent::Alert { position, .. } => position.as_deref(),
+        }
+    }
+}
+
+/// Selects which events a subscriber receives.
+///
+/// An empty filter matches everything. Kind and position constraints are
+/// combined with AND; events that are not position-specific always pass the
+/// position constraint.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    kinds: Option<HashSet<EventKind>>,
+    positions: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    /// A filter that matches every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the filter to the given event kinds.
+    pub fn kinds(mut self, kinds: &[EventKind]) -> Self {
+        self.kinds
+            .get_or_insert_with(HashSet::new)
+            .extend(kinds.iter().copied());
+        self
+    }
+
+    /// Restricts position-specific events to the given position.
+    pub fn position(mut self, name: impl Into<String>) -> Self {
+        self.positions
+            .get_or_insert_with(HashSet::new)
+            .insert(name.into());
+        self
+    }
+
+    pub fn matches(&self, event: &BusEvent) -> bool {
+        if let Some(kinds) = &self.kinds {
+            if !kinds.contains(&event.kind()) {
+                return false;
+            }
+        }
+        match (&self.positions, event.position()) {
+            (Some(positions), Some(position)) => positions.contains(position),
+            _ => true,
+        }
+    }
+}
+
+/// Publishing half of the bus. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<Arc<BusEvent>>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    /// Publishes an event to all current subscribers.
+    ///
+    /// Events published while there are no subscribers are dropped.
+    pub fn publish(&self, event: BusEvent) {
+        let _ = self.tx.send(Arc::new(event));
+    }
+
+    pub fn subscribe(&self, filter: EventFilter) -> Subscription {
+        Subscription {
+            rx: self.tx.subscribe(),
+            filter,
+            dropped: 0,
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+/// Receiving half of the bus, yielding only events that match its filter.
+#[derive(Debug)]
+pub struct Subscription {
+    rx: broadcast::Receiver<Arc<BusEvent>>,
+    filter: EventFilter,
+    dropped: u64,
+}
+
+impl Subscription {
+    /// Waits for the next matching event. Returns `None` once the bus is gone.
+    pub async fn recv(&mut self) -> Option<Arc<BusEvent>> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Event subscriber lagged; events dropped");
+                    self.dropped += skipped;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Returns the next matching event without waiting, if one is queued.
+    pub fn try_recv(&mut self) -> Option<Arc<BusEvent>> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => continue,
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Event subscriber lagged; events dropped");
+                    self.dropped += skipped;
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
+    /// Number of events queued for this subscriber, including ones its
+    /// filter will skip.
+    pub fn pending(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// Number of events skipped so far because this subscriber fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats_event(position: &str) -> BusEvent {
+        BusEvent::Stats {
+            position: position.to_string(),
+            stats: StatsSnapshot::default(),
+        }
+    }
+
+    #[test]
+    fn test_event_kind_and_position() {
+        assert_eq!(BusEvent::Connected.kind(), EventKind::Connection);
+        assert_eq!(BusEvent::Connected.position(), None);
+
+        let event = stats_event("X1");
+        assert_eq!(event.kind(), EventKind::Data);
+        assert_eq!(event.position(), Some("X1"));
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = EventFilter::all();
+        assert!(filter.matches(&BusEvent::Connected));
+        assert!(filter.matches(&stats_event("X1")));
+    }
+
+    #[test]
+    fn test_filter_by_kind() {
+        let filter = EventFilter::all().kinds(&[EventKind::Connection]);
+        assert!(filter.matches(&BusEvent::Reconnecting { attempt: 1 }));
+        assert!(!filter.matches(&stats_event("X1")));
+    }
+
+    #[test]
+    fn test_filter_by_position_passes_global_events() {
+        let filter = EventFilter::all().position("X1");
+        assert!(filter.matches(&stats_event("X1")));
+        assert!(!filter.matches(&stats_event("X2")));
+        assert!(filter.matches(&BusEvent::Connected));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_receives_matching_events_in_order() {
+        let bus = EventBus::new(16);
+        let mut data = bus.subscribe(EventFilter::all().kinds(&[EventKind::Data]));
+        let mut everything = bus.subscribe(EventFilter::all());
+
+        bus.publish(BusEvent::Connected);
+        bus.publish(stats_event("X1"));
+        bus.publish(stats_event("X2"));
+
+        assert_eq!(data.recv().await.unwrap().position(), Some("X1"));
+        assert_eq!(data.try_recv().unwrap().position(), Some("X2"));
+        assert!(data.try_recv().is_none());
+
+        assert!(matches!(
+            *everything.recv().await.unwrap(),
+            BusEvent::Connected
+        ));
+    }
+
+    #[test]
+    fn test_lagged_subscriber_skips_missed_events() {
+        let bus = EventBus::new(2);
+        let mut sub = bus.subscribe(EventFilter::all());
+
+        for name in ["X1", "X2", "X3", "X4"] {
+            bus.publish(stats_event(name));
+        }
+
+        assert_eq!(sub.try_recv().unwrap().position(), Some("X3"));
+        assert_eq!(sub.pending(), 1);
+        assert_eq!(sub.try_recv().unwrap().position(), Some("X4"));
+        assert!(sub.try_recv().is_none());
+        assert_eq!(sub.dropped(), 2);
+        assert_eq!(sub.pending(), 0);
+    }
+}
@@ -0,0 +1,140 @@
+//! `termion config` command implementation.
+
+use super::{Cli, ConfigCommand};
+use crate::config::{Config, ConfigReport, DEFAULT_CONFIG};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Widest setting that sources are aligned after in `config show`.
+const MAX_ALIGNED_WIDTH: usize = 48;
+
+pub fn run(cli: &Cli, action: &ConfigCommand) -> anyhow::Result<()> {
+    match action {
+        ConfigCommand::Init { force } => init(cli, *force),
+        ConfigCommand::Show => show(cli),
+    }
+}
+
+/// Writes the commented default config file, refusing to replace an
+/// existing one unless `force` is set.
+fn init(cli: &Cli, force: bool) -> anyhow::Result<()> {
+    let path = Config::file_path(cli)
+        .ok_or_else(|| anyhow::anyhow!("No config directory found; pass --config <PATH>"))?;
+    write_default(&path, force)?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn write_default(path: &Path, force: bool) -> anyhow::Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, DEFAULT_CONFIG)?;
+    Ok(())
+}
+
+/// Prints every setting's effective value and where it came from.
+fn show(cli: &Cli) -> anyhow::Result<()> {
+    let report = Config::report(cli)?;
+    write_report(&mut io::stdout().lock(), &report)?;
+    Ok(())
+}
+
+fn write_report(out: &mut impl Write, report: &ConfigReport) -> io::Result<()> {
+    match &report.path {
+        Some(path) => writeln!(out, "# Config file: {}", path.display())?,
+        None => writeln!(out, "# Config file: none")?,
+    }
+    if let Some(profile) = &report.profile {
+        writeln!(out, "# Profile: {}", profile)?;
+    }
+    writeln!(out)?;
+
+    let rows: Vec<(String, String)> = report
+        .entries
+        .iter()
+        .map(|entry| {
+            let value = entry
+                .value
+                .as_ref()
+                .map_or_else(|| "(unset)".to_string(), |v| v.to_string());
+            (
+                format!("{} = {}", entry.key, value),
+                entry.source.to_string(),
+            )
+        })
+        .collect();
+    // Long lists and paths are not padded, so they do not push every
+    // source far to the right.
+    let width = rows
+        .iter()
+        .map(|(setting, _)| setting.len())
+        .filter(|&len| len <= MAX_ALIGNED_WIDTH)
+        .max()
+        .unwrap_or(0);
+    for (setting, source) in rows {
+        writeln!(out, "{:<width$}  # {}", setting, source, width = width)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_default_refuses_to_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("termion/config.toml");
+
+        write_default(&path, false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), DEFAULT_CONFIG);
+
+        std::fs::write(&path, "[tui]\ntheme = \"nord\"\n").unwrap();
+        let err = write_default(&path, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert!(std::fs::read_to_string(&path).unwrap().contains("nord"));
+
+        write_default(&path, true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), DEFAULT_CONFIG);
+    }
+
+    #[test]
+    fn test_write_report_stops_at_closed_pipe() {
+        use crate::config::{ConfigEntry, ConfigSource};
+
+        let report = ConfigReport {
+            path: None,
+            profile: None,
+            entries: vec![ConfigEntry {
+                key: "connection.port",
+                value: Some(toml::Value::Integer(9600)),
+                source: ConfigSource::Cli("--port"),
+            }],
+        };
+        let mut out = Vec::new();
+        write_report(&mut out, &report).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "# Config file: none\n\nconnection.port = 9600  # --port\n"
+        );
+
+        struct ClosedPipe;
+        impl Write for ClosedPipe {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::BrokenPipe.into())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        let err = write_report(&mut ClosedPipe, &report).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    }
+}
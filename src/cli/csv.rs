@@ -0,0 +1,74 @@
+//! CSV output for `--format csv`.
+//!
+//! Rows follow RFC 4180: fields are comma-separated, lines end in CRLF, and
+//! a field is quoted, with inner quotes doubled, only when it contains a
+//! comma, quote or line break. Spreadsheets read this without an import
+//! dialog. Each command fixes its own column order so that scripts and
+//! sheets can rely on it.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+/// Writes one row.
+pub(crate) fn write_row<W: Write, S: AsRef<str>>(out: &mut W, fields: &[S]) -> io::Result<()> {
+    let line: Vec<Cow<'_, str>> = fields.iter().map(|f| quote(f.as_ref())).collect();
+    write!(out, "{}\r\n", line.join(","))
+}
+
+/// Writes a header row followed by one row per item.
+pub(crate) fn write_table<W: Write, T, const N: usize>(
+    out: &mut W,
+    header: [&str; N],
+    items: impl IntoIterator<Item = T>,
+    row: impl Fn(T) -> [String; N],
+) -> io::Result<()> {
+    write_row(out, &header)?;
+    for item in items {
+        write_row(out, &row(item))?;
+    }
+    Ok(())
+}
+
+/// Text of an optional field, empty when absent.
+pub(crate) fn optional(value: Option<impl ToString>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn quote(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_row_quotes_when_needed() {
+        let mut out = Vec::new();
+        write_row(&mut out, &["plain", "a,b", "say \"hi\"", "two\nlines", ""]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n"
+        );
+    }
+
+    #[test]
+    fn test_write_table() {
+        let mut out = Vec::new();
+        write_table(
+            &mut out,
+            ["name", "reads"],
+            [("X1", 10), ("X2", 0)],
+            |(name, reads)| [name.to_string(), reads.to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "name,reads\r\nX1,10\r\nX2,0\r\n"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! `termion doctor` command implementation.
+
+use crate::client::diagnostics::{run_checks, CheckResult, CheckStatus};
+use crate::config::Config;
+
+pub async fn run(config: &Config, json: bool) -> anyhow::Result<()> {
+    let results = run_checks(
+        &config.connection.host,
+        config.connection.port,
+        &config.connection.connect_options(),
+    )
+    .await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print!("{}", format_report(&results));
+    }
+
+    let failed = count(&results, CheckStatus::Fail);
+    if failed > 0 {
+        anyhow::bail!("{} of {} checks failed", failed, results.len());
+    }
+    Ok(())
+}
+
+/// Formats one line per check, each failure or warning followed by its
+/// remediation hints, and a closing summary.
+fn format_report(results: &[CheckResult]) -> String {
+    let name_width = results.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let mut report = String::new();
+    for result in results {
+        let label = match result.status {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skipped => "SKIP",
+        };
+        report.push_str(&format!(
+            "{}  {:<width$}  {}\n",
+            label,
+            result.name,
+            result.detail,
+            width = name_width
+        ));
+        for hint in &result.hints {
+            report.push_str(&format!("      → {}\n", hint));
+        }
+    }
+    report.push_str(&format!(
+        "\n{} passed, {} warnings, {} failed, {} skipped\n",
+        count(results, CheckStatus::Pass),
+        count(results, CheckStatus::Warn),
+        count(results, CheckStatus::Fail),
+        count(results, CheckStatus::Skipped)
+    ));
+    report
+}
+
+fn count(results: &[CheckResult], status: CheckStatus) -> usize {
+    results.iter().filter(|r| r.status == status).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_hints_under_failures() {
+        let results = vec![
+            CheckResult {
+                name: "Host".into(),
+                status: CheckStatus::Pass,
+                detail: "localhost is local".into(),
+                hints: vec![],
+            },
+            CheckResult {
+                name: "CA certificate".into(),
+                status: CheckStatus::Fail,
+                detail: "not found".into(),
+                hints: vec!["Set MINKNOW_TRUSTED_CA".into()],
+            },
+        ];
+        let report = format_report(&results);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[0], "PASS  Host            localhost is local");
+        assert_eq!(lines[1], "FAIL  CA certificate  not found");
+        assert_eq!(lines[2], "      → Set MINKNOW_TRUSTED_CA");
+        assert_eq!(
+            lines.last(),
+            Some(&"1 passed, 0 warnings, 1 failed, 0 skipped")
+        );
+    }
+}
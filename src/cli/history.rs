@@ -0,0 +1,178 @@
+//! `termion history` command implementation.
+
+use super::{csv, HistoryCommand};
+use crate::config::Config;
+use crate::history::{HistoryRecord, HistoryStore, RetentionPolicy};
+#[cfg(feature = "storage")]
+use crate::storage::Storage;
+use std::collections::HashSet;
+use std::io::Write;
+use std::time::{Duration, SystemTime};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Columns of `history export --format csv`, in order.
+const CSV_HEADER: [&str; 12] = [
+    "timestamp",
+    "position",
+    "run_id",
+    "reads_processed",
+    "reads_passed",
+    "reads_failed",
+    "bases_called",
+    "bases_passed",
+    "bases_failed",
+    "throughput_bps",
+    "mean_quality",
+    "active_pores",
+];
+
+/// Output format of `history export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+pub fn run(config: &Config, action: HistoryCommand) -> anyhow::Result<()> {
+    let store = HistoryStore::new(&config.history.path);
+
+    match action {
+        HistoryCommand::Prune {
+            dry_run,
+            max_age_days,
+            max_size_mb,
+        } => {
+            let mut policy = RetentionPolicy::from(&config.history);
+            if let Some(days) = max_age_days {
+                policy.max_age = Some(Duration::from_secs(days * SECS_PER_DAY));
+            }
+            if let Some(mb) = max_size_mb {
+                policy.max_size = Some(mb * BYTES_PER_MB);
+            }
+            prune(&store, &policy, dry_run)
+        }
+        HistoryCommand::Export {
+            position,
+            run,
+            output,
+            format,
+        } => {
+            let records = if config.storage.enabled {
+                stored_records(config, position.as_deref(), run.as_deref())?
+            } else {
+                let mut runs = store.runs()?;
+                runs.retain(|r| {
+                    position.as_ref().is_none_or(|p| &r.position == p)
+                        && run.as_ref().is_none_or(|id| &r.run_id == id)
+                });
+
+                let mut records = Vec::new();
+                for run in &runs {
+                    records.extend(store.read_run(run)?);
+                }
+                records
+            };
+            let runs: HashSet<_> = records.iter().map(|r| (&r.position, &r.run_id)).collect();
+
+            let mut data = Vec::new();
+            match format {
+                ExportFormat::Json => {
+                    serde_json::to_writer_pretty(&mut data, &records)?;
+                    data.push(b'\n');
+                }
+                ExportFormat::Csv => csv::write_table(&mut data, CSV_HEADER, &records, csv_row)?,
+            }
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, data)?;
+                    eprintln!(
+                        "Exported {} records from {} runs to {}",
+                        records.len(),
+                        runs.len(),
+                        path.display()
+                    );
+                }
+                None => std::io::stdout().lock().write_all(&data)?,
+            }
+            Ok(())
+        }
+    }
+}
+
+/// One `--format csv` row, in [`CSV_HEADER`] order.
+/// Reads records from the run metrics database, optionally only those of
+/// one position or run.
+#[cfg(feature = "storage")]
+fn stored_records(
+    config: &Config,
+    position: Option<&str>,
+    run: Option<&str>,
+) -> anyhow::Result<Vec<HistoryRecord>> {
+    Ok(Storage::open(&config.storage.path)?.records(position, run)?)
+}
+
+/// Fails: this build has no run metrics database.
+#[cfg(not(feature = "storage"))]
+fn stored_records(
+    _config: &Config,
+    _position: Option<&str>,
+    _run: Option<&str>,
+) -> anyhow::Result<Vec<HistoryRecord>> {
+    anyhow::bail!("storage.enabled needs a build with the storage feature")
+}
+
+fn csv_row(record: &HistoryRecord) -> [String; 12] {
+    [
+        record
+            .timestamp
+            .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+        record.position.clone(),
+        record.run_id.clone(),
+        record.reads_processed.to_string(),
+        record.reads_passed.to_string(),
+        record.reads_failed.to_string(),
+        record.bases_called.to_string(),
+        record.bases_passed.to_string(),
+        record.bases_failed.to_string(),
+        record.throughput_bps.to_string(),
+        record.mean_quality.to_string(),
+        record.active_pores.to_string(),
+    ]
+}
+
+fn prune(store: &HistoryStore, policy: &RetentionPolicy, dry_run: bool) -> anyhow::Result<()> {
+    let report = store.prune(policy, SystemTime::now(), dry_run)?;
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+
+    for run in &report.removed {
+        println!(
+            "{} {}/{} ({})",
+            verb,
+            run.position,
+            run.run_id,
+            format_size(run.size)
+        );
+    }
+    println!(
+        "{} {} runs, {} freed, {} remaining in {}",
+        verb,
+        report.removed.len(),
+        format_size(report.bytes_freed),
+        format_size(report.bytes_remaining),
+        store.root().display()
+    );
+    Ok(())
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes >= BYTES_PER_MB {
+        format!("{:.1} MiB", bytes as f64 / BYTES_PER_MB as f64)
+    } else if bytes >= 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
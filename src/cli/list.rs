@@ -1,23 +1,56 @@
 //! `termion list` command implementation.
 
-use crate::client::Client;
+use super::{csv, OutputFormat};
+use crate::client::diagnostics::version_warning;
+use crate::client::{Client, Device, VersionInfo};
 use crate::config::Config;
 
-pub async fn run(config: &Config, json: bool) -> anyhow::Result<()> {
-    let mut client = Client::connect_with_timeouts(
+#[derive(Debug, serde::Serialize)]
+struct ListOutput<'a> {
+    /// `None` if the manager would not say.
+    minknow_version: Option<&'a VersionInfo>,
+    devices: &'a [Device],
+}
+
+pub async fn run(config: &Config, format: OutputFormat) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
         &config.connection.host,
         config.connection.port,
-        config.connection.connect_timeout,
-        config.connection.request_timeout,
+        config.connection.connect_options(),
     )
     .await?;
     let devices = client.list_devices().await?;
+    let version = client.get_version_info().await.ok();
+    if let Some(warning) = version.as_ref().and_then(version_warning) {
+        eprintln!("Warning: {}", warning);
+    }
 
-    if json {
-        println!("{}", serde_json::to_string_pretty(&devices)?);
-    } else if devices.is_empty() {
-        println!("No devices found");
+    if format == OutputFormat::Json {
+        let output = ListOutput {
+            minknow_version: version.as_ref(),
+            devices: &devices,
+        };
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else if format == OutputFormat::Csv {
+        csv::write_table(
+            &mut std::io::stdout().lock(),
+            ["id", "name", "state"],
+            &devices,
+            |device| {
+                [
+                    device.id.clone(),
+                    device.name.clone(),
+                    format!("{:?}", device.state),
+                ]
+            },
+        )?;
     } else {
+        if let Some(version) = &version {
+            println!("MinKNOW {}", version.label());
+        }
+        if devices.is_empty() {
+            println!("No devices found");
+        }
         for device in devices {
             println!("{}: {} ({:?})", device.id, device.name, device.state);
         }
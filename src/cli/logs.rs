@@ -0,0 +1,160 @@
+//! `termion logs` command implementation.
+//!
+//! Prints the messages MinKNOW shows its users — warnings, errors and the
+//! reasons a run paused or stopped — for one or all positions. Without
+//! `--follow`, prints the messages MinKNOW kept and exits; with it, keeps
+//! printing new messages until interrupted.
+
+use crate::client::{Client, ClientError, MessageSeverity, UserMessage};
+use crate::config::{Config, DisplayTimezone};
+use futures::stream::{self, StreamExt};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// MinKNOW does not mark the end of the messages it replays, so they are
+/// taken to be over once none has arrived for this long.
+const HISTORY_IDLE: Duration = Duration::from_millis(500);
+
+#[derive(serde::Serialize)]
+struct PositionMessage<'a> {
+    position: &'a str,
+    #[serde(flatten)]
+    message: &'a UserMessage,
+}
+
+pub async fn run(
+    config: &Config,
+    position_filter: Option<String>,
+    follow: bool,
+    json: bool,
+    level: MessageSeverity,
+) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
+        &config.connection.host,
+        config.connection.port,
+        config.connection.connect_options(),
+    )
+    .await?;
+    let positions: Vec<_> = client
+        .list_positions()
+        .await?
+        .into_iter()
+        .filter(|p| position_filter.as_ref().is_none_or(|f| p.name == *f))
+        .collect();
+    if let (Some(name), true) = (&position_filter, positions.is_empty()) {
+        return Err(ClientError::NotFound {
+            resource: "Position".into(),
+            id: name.clone(),
+        }
+        .into());
+    }
+
+    // Positions that are not running have no services to ask.
+    let mut streams = Vec::new();
+    for position in positions.into_iter().filter(|p| p.grpc_port != 0) {
+        let name = position.name.clone();
+        let mut pos_client = client.connect_position(position).await?;
+        let messages = pos_client.stream_user_messages(true).await?;
+        streams.push(messages.map(move |message| (name.clone(), message)).boxed());
+    }
+    let mut messages = stream::select_all(streams);
+    let timezone = config.tui.timezone;
+
+    if !follow {
+        let mut history = Vec::new();
+        while let Ok(Some((position, message))) =
+            tokio::time::timeout(HISTORY_IDLE, messages.next()).await
+        {
+            history.push((position, message?));
+        }
+        history.sort_by_key(|(_, message)| message.time);
+        let mut out = io::stdout().lock();
+        for (position, message) in history.iter().filter(|(_, m)| m.severity >= level) {
+            writeln!(
+                out,
+                "{}",
+                format_message(position, message, json, timezone)?
+            )?;
+        }
+        return Ok(());
+    }
+
+    loop {
+        let (position, message) = tokio::select! {
+            next = messages.next() => match next {
+                Some(next) => next,
+                None => return Ok(()),
+            },
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        let message = message?;
+        if message.severity >= level {
+            let mut out = io::stdout().lock();
+            writeln!(
+                out,
+                "{}",
+                format_message(&position, &message, json, timezone)?
+            )?;
+            out.flush()?;
+        }
+    }
+}
+
+/// Formats a message as one JSON object, or as a line of text with the
+/// time in the display time zone.
+fn format_message(
+    position: &str,
+    message: &UserMessage,
+    json: bool,
+    timezone: DisplayTimezone,
+) -> anyhow::Result<String> {
+    if json {
+        return Ok(serde_json::to_string(&PositionMessage {
+            position,
+            message,
+        })?);
+    }
+    let time = message.time.map_or_else(
+        || "-".to_string(),
+        |t| timezone.format(t, "%Y-%m-%d %H:%M:%S"),
+    );
+    Ok(format!(
+        "{}  {}  {:<7}  {}",
+        time,
+        position,
+        message.severity.label().to_uppercase(),
+        message.message
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn message() -> UserMessage {
+        UserMessage {
+            time: Some(Utc.with_ymd_and_hms(2026, 10, 16, 8, 30, 0).unwrap()),
+            severity: MessageSeverity::Warning,
+            identifier: "protocol_paused".into(),
+            message: "Run paused: flow cell temperature too high".into(),
+            extra_data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_format_message() {
+        let text = format_message("X1", &message(), false, DisplayTimezone::Utc).unwrap();
+        assert_eq!(
+            text,
+            "2026-10-16 08:30:00  X1  WARNING  Run paused: flow cell temperature too high"
+        );
+
+        let json = format_message("X1", &message(), true, DisplayTimezone::Utc).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["position"], "X1");
+        assert_eq!(value["severity"], "warning");
+        assert_eq!(value["identifier"], "protocol_paused");
+        assert!(value.get("extra_data").is_none());
+    }
+}
@@ -2,9 +2,18 @@
 
 use clap::{Parser, Subcommand};
 
+pub mod config;
+pub mod csv;
+pub mod doctor;
 pub mod exit;
+pub mod history;
 pub mod list;
+pub mod logs;
+pub mod report;
+pub mod snapshot;
+pub mod start;
 pub mod status;
+pub mod watch;
 
 pub use exit::{exit_code_for_error, Exit};
 
@@ -25,6 +34,10 @@ pub struct Cli {
     #[arg(long, short = 'c', env = "TERMION_CONFIG")]
     pub config: Option<std::path::PathBuf>,
 
+    /// Connection profile from the config file's [profiles] table
+    #[arg(long, env = "TERMION_PROFILE")]
+    pub profile: Option<String>,
+
     /// Increase logging verbosity (-v, -vv, -vvv)
     #[arg(long, short = 'v', action = clap::ArgAction::Count)]
     pub verbose: u8,
@@ -33,6 +46,22 @@ pub struct Cli {
     #[arg(long)]
     pub log: Option<std::path::PathBuf>,
 
+    /// Run the TUI on simulated positions instead of connecting to MinKNOW
+    #[arg(long)]
+    pub demo: bool,
+
+    /// Record everything the TUI receives to a session file
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["demo", "replay"])]
+    pub record: Option<std::path::PathBuf>,
+
+    /// Run the TUI on a recorded session instead of connecting to MinKNOW
+    #[arg(long, value_name = "PATH", conflicts_with = "demo")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Replay speed relative to the recording, e.g. 10 for ten times faster
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.0, requires = "replay", value_parser = parse_speed)]
+    pub replay_speed: f64,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -43,18 +72,243 @@ pub enum Commands {
     /// List devices and positions
     List {
         /// Output as JSON
-        #[arg(long)]
+        #[arg(long, conflicts_with = "format")]
         json: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t)]
+        format: OutputFormat,
     },
 
     /// Show run status and metrics
     Status {
+        /// Output as JSON
+        #[arg(long, conflicts_with_all = ["ndjson", "format"])]
+        json: bool,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t, conflicts_with = "ndjson")]
+        format: OutputFormat,
+
+        /// Print one JSON object per position per line
+        #[arg(long)]
+        ndjson: bool,
+
+        /// Keep printing statuses every interval until interrupted
+        #[arg(long, short = 'f', requires = "ndjson")]
+        follow: bool,
+
+        /// Seconds between updates with --follow
+        #[arg(long, short = 'n', default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Filter by position ID
+        #[arg(long, short = 'P')]
+        position: Option<String>,
+    },
+
+    /// Print run status repeatedly without entering the TUI
+    Watch {
+        /// Print one JSON snapshot per line instead of a table
+        #[arg(long)]
+        ndjson: bool,
+
+        /// Seconds between updates
+        #[arg(long, short = 'n', default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Filter by position ID
+        #[arg(long, short = 'P')]
+        position: Option<String>,
+    },
+
+    /// Print MinKNOW's messages to users, such as why a run paused
+    Logs {
+        /// Filter by position ID
+        #[arg(long, short = 'P')]
+        position: Option<String>,
+
+        /// Keep printing new messages until interrupted
+        #[arg(long, short = 'f')]
+        follow: bool,
+
+        /// Print one JSON object per line
+        #[arg(long)]
+        json: bool,
+
+        /// Lowest severity to print: trace, info, warning or error
+        #[arg(long, default_value = "info")]
+        level: crate::client::MessageSeverity,
+    },
+
+    /// Start a protocol on a position
+    Start {
+        /// Position to start on
+        #[arg(long, short = 'P')]
+        position: String,
+
+        /// Sequencing kit; picks the protocol matching the kit and flow cell
+        #[arg(long, required_unless_present = "protocol")]
+        kit: Option<String>,
+
+        /// Protocol identifier, overriding the kit lookup
+        #[arg(long)]
+        protocol: Option<String>,
+
+        /// Sample ID for the run
+        #[arg(long, default_value = "")]
+        sample_id: String,
+
+        /// Experiment group for the run
+        #[arg(long, default_value = "")]
+        experiment_group: String,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
+    },
 
+    /// Poll in the background without the TUI, recording history, sending
+    /// webhook notifications and serving the HTTP JSON API until interrupted
+    Daemon {
+        /// Serve the HTTP JSON API on this address, e.g. 127.0.0.1:9510
+        #[arg(long, value_name = "ADDR")]
+        listen: Option<std::net::SocketAddr>,
+    },
+
+    /// Write a Markdown or HTML report of a run
+    Report {
+        /// Position the run is on
+        #[arg(long, short = 'P')]
+        position: String,
+
+        /// Protocol or acquisition run ID; defaults to the current run, or
+        /// the latest finished one
+        #[arg(long)]
+        run: Option<String>,
+
+        /// Report format; defaults to HTML for `.html` output files and
+        /// Markdown otherwise
+        #[arg(long, value_enum)]
+        format: Option<report::ReportFormat>,
+
+        /// Write to a file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Dump everything the detail view shows as one JSON document
+    Snapshot {
         /// Filter by position ID
         #[arg(long, short = 'P')]
         position: Option<String>,
+
+        /// Write to a file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<std::path::PathBuf>,
+    },
+
+    /// Check the connection to MinKNOW and suggest fixes for common
+    /// certificate, auth and version problems
+    Doctor {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Manage the local run history store
+    History {
+        #[command(subcommand)]
+        action: HistoryCommand,
+    },
+
+    /// Create or inspect the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
     },
 }
+
+/// `termion config` subcommands
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Write a commented config file with every default to the config path
+    Init {
+        /// Overwrite an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Print the effective value of every setting and where it came from
+    Show,
+}
+
+/// `termion history` subcommands
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// Remove recorded runs that exceed the retention limits
+    Prune {
+        /// Show what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Override the configured maximum age, in days
+        #[arg(long)]
+        max_age_days: Option<u64>,
+
+        /// Override the configured maximum store size, in MiB
+        #[arg(long)]
+        max_size_mb: Option<u64>,
+    },
+
+    /// Export recorded runs as JSON or CSV
+    Export {
+        /// Only export runs from this position
+        #[arg(long, short = 'P')]
+        position: Option<String>,
+
+        /// Only export this run ID
+        #[arg(long)]
+        run: Option<String>,
+
+        /// Write to a file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<std::path::PathBuf>,
+
+        /// Export format
+        #[arg(long, value_enum, default_value_t)]
+        format: history::ExportFormat,
+    },
+}
+
+/// Output format of `list` and `status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// Pretty-printed JSON, the same as `--json`
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+}
+
+impl OutputFormat {
+    /// The format chosen by `--format`, or JSON if `--json` was given.
+    pub fn or_json(self, json: bool) -> Self {
+        if json {
+            Self::Json
+        } else {
+            self
+        }
+    }
+}
+
+/// Parses `--replay-speed`, which must be a positive, finite factor.
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        Ok(_) => Err("must be greater than 0".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
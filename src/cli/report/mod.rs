@@ -0,0 +1,236 @@
+//! `termion report` command implementation.
+//!
+//! Collects the statistics of one run and renders them as a self-contained
+//! Markdown or HTML report, for attaching to sample hand-offs. The run is
+//! the current one on the position, or the latest finished one, unless
+//! `--run` names another.
+
+mod render;
+pub(crate) mod svg;
+
+use crate::client::subscription::{HistogramOptions, DEFAULT_HISTOGRAM_INTERVAL};
+use crate::client::{
+    Client, ClientError, MuxScanResult, PositionClient, QScoreHistogram, ReadLengthHistogram,
+    RunInfo, YieldDataPoint,
+};
+use crate::config::Config;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub use render::{html, markdown};
+
+/// How long to wait for the first message of a statistics stream.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Output format of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Picks the format from an output file extension, defaulting to
+    /// Markdown.
+    fn from_path(path: Option<&Path>) -> Self {
+        match path.and_then(|p| p.extension()).and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm") => {
+                ReportFormat::Html
+            }
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+/// Everything a report shows about one run.
+#[derive(Debug, Clone)]
+pub struct RunReport {
+    pub position: String,
+    pub info: RunInfo,
+    /// The acquisition that produced the sequencing data, if any.
+    pub acquisition_run_id: Option<String>,
+    /// Why the run ended, or `None` while it is still in progress.
+    pub end_reason: Option<String>,
+    pub yield_history: Vec<YieldDataPoint>,
+    pub read_lengths: Option<ReadLengthHistogram>,
+    pub qscores: Option<QScoreHistogram>,
+    pub mean_quality: Option<f32>,
+    /// Pore scans, oldest first. MinKNOW only reports these for the
+    /// current run.
+    pub mux_scans: Vec<MuxScanResult>,
+    /// When the report was generated (UTC).
+    pub generated: DateTime<Utc>,
+}
+
+impl RunReport {
+    /// Heading for the report: the sample ID, else the run ID.
+    fn title(&self) -> String {
+        self.info
+            .sample_id
+            .clone()
+            .filter(|s| !s.is_empty())
+            .or_else(|| self.info.run_id.clone())
+            .unwrap_or_else(|| self.position.clone())
+    }
+}
+
+pub async fn run(
+    config: &Config,
+    position_name: &str,
+    run_id: Option<&str>,
+    format: Option<ReportFormat>,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
+        &config.connection.host,
+        config.connection.port,
+        config.connection.connect_options(),
+    )
+    .await?;
+    let position = client
+        .list_positions()
+        .await?
+        .into_iter()
+        .find(|p| p.name == position_name)
+        .ok_or_else(|| ClientError::NotFound {
+            resource: "Position".into(),
+            id: position_name.to_string(),
+        })?;
+    let mut pos_client = client.connect_position(position).await?;
+
+    let report = collect(&mut pos_client, run_id).await?;
+    let tz = &config.tui.timezone;
+    let rendered = match format.unwrap_or_else(|| ReportFormat::from_path(output.as_deref())) {
+        ReportFormat::Markdown => markdown(&report, tz),
+        ReportFormat::Html => html(&report, tz),
+    };
+
+    match output {
+        Some(path) => {
+            std::fs::write(&path, rendered)?;
+            eprintln!("Wrote report for {} to {}", report.title(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Gathers the report data for `run_id`, matched against protocol and
+/// acquisition run IDs, or for the current or latest run.
+///
+/// Statistics are best effort: a run that was not basecalled, or whose
+/// statistics MinKNOW no longer has, gets a report without them.
+async fn collect(client: &mut PositionClient, run_id: Option<&str>) -> anyhow::Result<RunReport> {
+    let matches = |info: &RunInfo, acquisition: Option<&String>| {
+        run_id.is_none_or(|id| {
+            info.run_id.as_deref() == Some(id) || acquisition.is_some_and(|a| a == id)
+        })
+    };
+
+    let current = if client.get_run_state().await?.is_active() {
+        match client.get_run_info().await? {
+            Some(info) => Some((info, client.get_current_run_id().await?)),
+            None => None,
+        }
+    } else {
+        None
+    };
+
+    let (info, acquisition_run_id, end_reason) = match current {
+        Some((info, acquisition)) if matches(&info, acquisition.as_ref()) => {
+            (info, acquisition, None)
+        }
+        _ => {
+            let summary = client
+                .list_run_history()
+                .await?
+                .into_iter()
+                .find(|run| matches(&run.info, run.acquisition_run_id.as_ref()))
+                .ok_or_else(|| ClientError::NotFound {
+                    resource: "Run".into(),
+                    id: run_id.unwrap_or("latest").to_string(),
+                })?;
+            (
+                summary.info,
+                summary.acquisition_run_id,
+                Some(summary.end_reason),
+            )
+        }
+    };
+
+    let mut report = RunReport {
+        position: client.position.name.clone(),
+        info,
+        acquisition_run_id,
+        end_reason,
+        yield_history: Vec::new(),
+        read_lengths: None,
+        qscores: None,
+        mean_quality: None,
+        mux_scans: Vec::new(),
+        generated: Utc::now(),
+    };
+    let Some(acquisition) = report.acquisition_run_id.clone() else {
+        return Ok(report);
+    };
+
+    report.yield_history = client
+        .get_yield_history(&acquisition)
+        .await
+        .unwrap_or_default();
+    report.read_lengths = first(async {
+        let stream = client
+            .stream_read_length_histogram(
+                &acquisition,
+                HistogramOptions::default(),
+                DEFAULT_HISTOGRAM_INTERVAL,
+            )
+            .await?;
+        Box::pin(stream).next().await.transpose()
+    })
+    .await;
+    report.qscores = first(async {
+        let stream = client
+            .stream_qscore_histogram(&acquisition, DEFAULT_HISTOGRAM_INTERVAL)
+            .await?;
+        Box::pin(stream).next().await.transpose()
+    })
+    .await;
+    report.mean_quality = first(client.get_mean_quality(&acquisition)).await;
+    if report.end_reason.is_none() {
+        report.mux_scans = client.get_mux_scan_results().await.unwrap_or_default();
+    }
+
+    Ok(report)
+}
+
+/// Awaits an optional statistic, giving up after `STREAM_TIMEOUT` or on
+/// error.
+async fn first<T>(
+    fetch: impl std::future::Future<Output = Result<Option<T>, ClientError>>,
+) -> Option<T> {
+    match tokio::time::timeout(STREAM_TIMEOUT, fetch).await {
+        Ok(Ok(value)) => value,
+        Ok(Err(e)) => {
+            tracing::debug!(error = %e, "Statistic unavailable for report");
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_output_extension() {
+        let format = |path: &str| ReportFormat::from_path(Some(Path::new(path)));
+        assert_eq!(format("run.html"), ReportFormat::Html);
+        assert_eq!(format("run.HTM"), ReportFormat::Html);
+        assert_eq!(format("run.md"), ReportFormat::Markdown);
+        assert_eq!(ReportFormat::from_path(None), ReportFormat::Markdown);
+    }
+}
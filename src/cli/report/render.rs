@@ -0,0 +1,369 @@
+//! Markdown and HTML rendering of run reports.
+
+use super::svg::{self, merge_buckets, Series, BAR_COLOR, FAILED_COLOR, PASSED_COLOR};
+use super::RunReport;
+use crate::config::DisplayTimezone;
+use crate::format::{format_bases, format_number};
+use std::fmt::Write;
+
+/// Renders the report as Markdown, with charts embedded as base64 SVG
+/// images so the file stands on its own.
+pub fn markdown(report: &RunReport, tz: &DisplayTimezone) -> String {
+    use base64::Engine;
+
+    let mut out = format!("# Run report: {}\n\n", report.title());
+    out.push_str("| | |\n|---|---|\n");
+    for (field, value) in summary(report, tz) {
+        let _ = writeln!(out, "| {} | {} |", field, value.replace('|', "\\|"));
+    }
+
+    for (heading, chart) in charts(report) {
+        let data = base64::engine::general_purpose::STANDARD.encode(chart);
+        let _ = write!(
+            out,
+            "\n## {heading}\n\n![{heading}](data:image/svg+xml;base64,{data})\n"
+        );
+    }
+
+    if !report.mux_scans.is_empty() {
+        let (categories, rows) = pore_scan_table(report);
+        let _ = write!(
+            out,
+            "\n## Pore Scans\n\n| Time | {} |\n|---|{}\n",
+            categories.join(" | "),
+            "---|".repeat(categories.len())
+        );
+        for row in rows {
+            let _ = writeln!(out, "| {} |", row.join(" | "));
+        }
+    }
+
+    let _ = write!(
+        out,
+        "\n---\n\nGenerated by Termion {} on {}.\n",
+        env!("CARGO_PKG_VERSION"),
+        tz.format_display(report.generated)
+    );
+    out
+}
+
+/// Renders the report as a single HTML page with inline SVG charts.
+pub fn html(report: &RunReport, tz: &DisplayTimezone) -> String {
+    let title = svg::escape(&report.title());
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Run report: {title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; max-width: 720px; margin: 2em auto; color: #222; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 4px 10px; text-align: left; }}\n\
+         footer {{ color: #777; font-size: 0.9em; margin-top: 2em; }}\n\
+         </style>\n</head>\n<body>\n<h1>Run report: {title}</h1>\n<table>\n"
+    );
+    for (field, value) in summary(report, tz) {
+        let _ = writeln!(
+            out,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            field,
+            svg::escape(&value)
+        );
+    }
+    out.push_str("</table>\n");
+
+    for (heading, chart) in charts(report) {
+        let _ = write!(out, "<h2>{heading}</h2>\n{chart}\n");
+    }
+
+    if !report.mux_scans.is_empty() {
+        let (categories, rows) = pore_scan_table(report);
+        out.push_str("<h2>Pore Scans</h2>\n<table>\n<tr><th>Time</th>");
+        for category in &categories {
+            let _ = write!(out, "<th>{}</th>", svg::escape(category));
+        }
+        out.push_str("</tr>\n");
+        for row in rows {
+            let _ = writeln!(out, "<tr><td>{}</td></tr>", row.join("</td><td>"));
+        }
+        out.push_str("</table>\n");
+    }
+
+    let _ = write!(
+        out,
+        "<footer>Generated by Termion {} on {}.</footer>\n</body>\n</html>\n",
+        env!("CARGO_PKG_VERSION"),
+        svg::escape(&tz.format_display(report.generated))
+    );
+    out
+}
+
+/// Field and value rows for the summary table. Fields without data are
+/// left out.
+fn summary(report: &RunReport, tz: &DisplayTimezone) -> Vec<(&'static str, String)> {
+    let info = &report.info;
+    let last = report.yield_history.last();
+    let bases_passed = last.map(|p| p.bases_passed);
+    let bases_failed = last.map(|p| p.bases_failed);
+    let pass_rate = last
+        .map(|p| (p.bases_passed, p.bases_passed + p.bases_failed))
+        .filter(|&(_, total)| total > 0)
+        .map(|(passed, total)| format!("{:.1}%", passed as f64 / total as f64 * 100.0));
+    let duration = match (info.start_time, info.end_time) {
+        (Some(start), Some(end)) => Some(end - start),
+        (Some(_), None) => last.map(|p| chrono::Duration::seconds(p.seconds as i64)),
+        _ => None,
+    };
+    let pores = report.mux_scans.first().map(|first| {
+        let last = report.mux_scans.last().unwrap_or(first);
+        if report.mux_scans.len() > 1 {
+            format!(
+                "{} at start, {} at last scan",
+                first.available_pores(),
+                last.available_pores()
+            )
+        } else {
+            first.available_pores().to_string()
+        }
+    });
+
+    [
+        ("Position", Some(report.position.clone())),
+        ("Sample", info.sample_id.clone().filter(|s| !s.is_empty())),
+        (
+            "Experiment group",
+            info.experiment_group.clone().filter(|g| !g.is_empty()),
+        ),
+        ("Protocol", info.protocol_name.clone()),
+        ("Flow cell", info.flow_cell_product_code.clone()),
+        ("Run ID", info.run_id.clone()),
+        ("Acquisition ID", report.acquisition_run_id.clone()),
+        ("Started", info.start_time.map(|t| tz.format_display(t))),
+        ("Ended", info.end_time.map(|t| tz.format_display(t))),
+        ("Duration", duration.map(format_duration)),
+        (
+            "Outcome",
+            Some(
+                report
+                    .end_reason
+                    .clone()
+                    .unwrap_or_else(|| "In progress".to_string()),
+            ),
+        ),
+        ("Reads", last.map(|p| format_number(p.reads))),
+        ("Bases passed", bases_passed.map(format_bases)),
+        ("Bases failed", bases_failed.map(format_bases)),
+        ("Pass rate", pass_rate),
+        (
+            "Read N50",
+            report
+                .read_lengths
+                .as_ref()
+                .map(|h| h.n50 as u64)
+                .filter(|&n50| n50 > 0)
+                .map(format_bases),
+        ),
+        (
+            "Mean Q-score",
+            report.mean_quality.map(|q| format!("{:.1}", q)),
+        ),
+        (
+            "Modal Q-score",
+            report
+                .qscores
+                .as_ref()
+                .filter(|h| h.total_count() > 0)
+                .map(|h| format!("{:.1}", h.modal_q_score)),
+        ),
+        ("Pores available", pores),
+    ]
+    .into_iter()
+    .filter_map(|(field, value)| Some((field, value?)))
+    .collect()
+}
+
+/// Headed SVG charts for the data the report has.
+fn charts(report: &RunReport) -> Vec<(&'static str, String)> {
+    let mut charts = Vec::new();
+
+    if report.yield_history.len() > 1 {
+        let series = |label, color, bases: fn(&crate::client::YieldDataPoint) -> u64| Series {
+            label,
+            color,
+            points: report
+                .yield_history
+                .iter()
+                .map(|p| (p.seconds as f64 / 3600.0, bases(p) as f64))
+                .collect(),
+        };
+        charts.push((
+            "Yield",
+            svg::line_chart(
+                "Cumulative yield",
+                "Hours",
+                "Bases",
+                &[
+                    series("Passed", PASSED_COLOR, |p| p.bases_passed),
+                    series("Failed", FAILED_COLOR, |p| p.bases_failed),
+                ],
+            ),
+        ));
+    }
+
+    if let Some(histogram) = report.read_lengths.as_ref().filter(|h| h.total_count() > 0) {
+        let buckets = histogram
+            .bucket_ranges
+            .iter()
+            .zip(&histogram.bucket_values)
+            .map(|(&(start, end), &count)| (start as f64, end as f64, count));
+        let bars = merge_buckets(buckets, |start, _| format_bases(start as u64));
+        charts.push((
+            "Read Lengths",
+            svg::bar_chart(
+                "Read length distribution",
+                "Read length",
+                "Bases",
+                &bars,
+                BAR_COLOR,
+            ),
+        ));
+    }
+
+    if let Some(histogram) = report.qscores.as_ref().filter(|h| h.total_count() > 0) {
+        let buckets = histogram
+            .bucket_ranges
+            .iter()
+            .zip(&histogram.bucket_values)
+            .map(|(&(start, end), &count)| (start as f64, end as f64, count));
+        let bars = merge_buckets(buckets, |start, _| format!("Q{:.0}", start));
+        charts.push((
+            "Quality",
+            svg::bar_chart(
+                "Read q-score distribution",
+                "Mean q-score",
+                "Reads",
+                &bars,
+                BAR_COLOR,
+            ),
+        ));
+    }
+
+    charts
+}
+
+/// Column headings and rows for the pore scan table. Categories are the
+/// union over all scans.
+fn pore_scan_table(report: &RunReport) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut categories: Vec<String> = report
+        .mux_scans
+        .iter()
+        .flat_map(|scan| scan.counts.keys().cloned())
+        .collect();
+    categories.sort();
+    categories.dedup();
+
+    let rows = report
+        .mux_scans
+        .iter()
+        .map(|scan| {
+            let time = format_duration(chrono::Duration::seconds(scan.seconds as i64));
+            std::iter::once(time)
+                .chain(
+                    categories
+                        .iter()
+                        .map(|c| scan.counts.get(c).copied().unwrap_or(0).to_string()),
+                )
+                .collect()
+        })
+        .collect();
+    (categories, rows)
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{MuxScanResult, QScoreHistogram, RunInfo, YieldDataPoint};
+    use chrono::{TimeZone, Utc};
+
+    fn report() -> RunReport {
+        let start = Utc.with_ymd_and_hms(2026, 10, 1, 8, 0, 0).unwrap();
+        RunReport {
+            position: "X1".into(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                experiment_group: Some("G1".into()),
+                sample_id: Some("S<1>".into()),
+                protocol_name: Some("Ligation Sequencing Kit V14".into()),
+                flow_cell_product_code: Some("FLO-MIN114".into()),
+                start_time: Some(start),
+                end_time: Some(start + chrono::Duration::minutes(150)),
+            },
+            acquisition_run_id: Some("acq-1".into()),
+            end_reason: Some("Completed".into()),
+            yield_history: (0..=3)
+                .map(|hour| YieldDataPoint {
+                    seconds: hour * 3600,
+                    reads: hour as u64 * 1000,
+                    bases_passed: hour as u64 * 900_000_000,
+                    bases_failed: hour as u64 * 100_000_000,
+                    ..Default::default()
+                })
+                .collect(),
+            read_lengths: None,
+            qscores: Some(QScoreHistogram {
+                bucket_ranges: (0..60)
+                    .map(|q| (q as f32 / 2.0, (q + 1) as f32 / 2.0))
+                    .collect(),
+                bucket_values: (0..60).map(|q| q as u64).collect(),
+                modal_q_score: 14.5,
+            }),
+            mean_quality: Some(13.2),
+            mux_scans: vec![
+                MuxScanResult {
+                    seconds: 0,
+                    counts: [("single_pore".to_string(), 1400)].into(),
+                },
+                MuxScanResult {
+                    seconds: 5400,
+                    counts: [
+                        ("single_pore".to_string(), 1100),
+                        ("unavailable".to_string(), 300),
+                    ]
+                    .into(),
+                },
+            ],
+            generated: start + chrono::Duration::hours(4),
+        }
+    }
+
+    #[test]
+    fn test_markdown_summary_and_embedded_charts() {
+        let md = markdown(&report(), &DisplayTimezone::Utc);
+        assert!(md.starts_with("# Run report: S<1>\n"));
+        assert!(md.contains("| Duration | 2h 30m |"));
+        assert!(md.contains("| Bases passed | 2.70 Gb |"));
+        assert!(md.contains("| Pass rate | 90.0% |"));
+        assert!(md.contains("| Mean Q-score | 13.2 |"));
+        assert!(md.contains("| Pores available | 1400 at start, 1100 at last scan |"));
+        assert!(!md.contains("Read N50"));
+        assert!(md.contains("![Yield](data:image/svg+xml;base64,"));
+        assert!(md.contains("![Quality](data:image/svg+xml;base64,"));
+        assert!(!md.contains("## Read Lengths"));
+        assert!(md.contains("| Time | single_pore | unavailable |"));
+        assert!(md.contains("| 1h 30m | 1100 | 300 |"));
+    }
+
+    #[test]
+    fn test_html_inlines_charts_and_escapes_text() {
+        let mut report = report();
+        report.end_reason = None;
+        report.info.end_time = None;
+        let html = html(&report, &DisplayTimezone::Utc);
+        assert!(html.contains("<h1>Run report: S&lt;1&gt;</h1>"));
+        assert!(html.contains("<tr><th>Outcome</th><td>In progress</td></tr>"));
+        assert!(html.contains("<tr><th>Duration</th><td>3h 00m</td></tr>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
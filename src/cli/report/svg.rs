@@ -0,0 +1,313 @@
+//! Self-contained SVG charts for run reports and TUI chart exports.
+//!
+//! Charts are plain SVG with no scripts or external fonts, so they render
+//! the same in a browser, a mail client, or a Markdown viewer.
+
+use std::fmt::Write;
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 16.0;
+const MARGIN_TOP: f64 = 32.0;
+const MARGIN_BOTTOM: f64 = 44.0;
+
+/// Number of intervals between axis ticks.
+const TICKS: u32 = 4;
+
+const AXIS_COLOR: &str = "#444";
+const GRID_COLOR: &str = "#ddd";
+
+pub const PASSED_COLOR: &str = "#2a9d8f";
+pub const FAILED_COLOR: &str = "#e76f51";
+pub const BAR_COLOR: &str = "#264653";
+
+/// Most buckets drawn in a histogram; neighbours are merged beyond this.
+const MAX_BARS: usize = 40;
+
+/// One line on a line chart.
+pub struct Series<'a> {
+    pub label: &'a str,
+    pub color: &'a str,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Draws series of `(x, y)` points as lines, with a legend when there is
+/// more than one series. Both axes start at zero.
+pub fn line_chart(title: &str, x_label: &str, y_label: &str, series: &[Series]) -> String {
+    let points = || series.iter().flat_map(|s| s.points.iter());
+    let x_max = points().map(|&(x, _)| x).fold(0.0, f64::max);
+    let y_max = points().map(|&(_, y)| y).fold(0.0, f64::max);
+    let plot = Plot::new(x_max, y_max);
+
+    let mut svg = plot.frame(title, x_label, y_label);
+    plot.x_ticks(&mut svg, |i| format_tick(x_max * i as f64 / TICKS as f64));
+    for s in series.iter().filter(|s| !s.points.is_empty()) {
+        let path: Vec<String> = s
+            .points
+            .iter()
+            .map(|&(x, y)| format!("{:.1},{:.1}", plot.x(x), plot.y(y)))
+            .collect();
+        let _ = write!(
+            svg,
+            r#"<polyline fill="none" stroke="{}" stroke-width="2" points="{}"/>"#,
+            s.color,
+            path.join(" ")
+        );
+    }
+    if series.len() > 1 {
+        for (i, s) in series.iter().enumerate() {
+            let y = MARGIN_TOP + 8.0 + i as f64 * 16.0;
+            let x = MARGIN_LEFT + 12.0;
+            let _ = write!(
+                svg,
+                r#"<rect x="{x}" y="{}" width="10" height="10" fill="{}"/><text x="{}" y="{}" font-size="11">{}</text>"#,
+                y - 9.0,
+                s.color,
+                x + 14.0,
+                y,
+                escape(s.label)
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Draws one bar per `(label, value)`, labelling at most a handful of bars
+/// so the axis stays readable.
+pub fn bar_chart(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    bars: &[(String, f64)],
+    color: &str,
+) -> String {
+    let y_max = bars.iter().map(|&(_, v)| v).fold(0.0, f64::max);
+    let plot = Plot::new(bars.len() as f64, y_max);
+
+    let mut svg = plot.frame(title, x_label, y_label);
+    let label_every = bars.len().div_ceil(TICKS as usize + 1).max(1);
+    let bar_width = plot.width() / bars.len().max(1) as f64;
+    for (i, (label, value)) in bars.iter().enumerate() {
+        let x = plot.x(i as f64);
+        let y = plot.y(*value);
+        let _ = write!(
+            svg,
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}"/>"#,
+            x + 1.0,
+            y,
+            (bar_width - 2.0).max(1.0),
+            plot.y(0.0) - y,
+            color
+        );
+        if i % label_every == 0 {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" font-size="11" text-anchor="middle">{}</text>"#,
+                x + bar_width / 2.0,
+                HEIGHT - MARGIN_BOTTOM + 16.0,
+                escape(label)
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Turns `(start, end, count)` buckets into labelled bars, merging runs of
+/// neighbours so there are at most `MAX_BARS`.
+pub fn merge_buckets(
+    buckets: impl ExactSizeIterator<Item = (f64, f64, u64)>,
+    label: impl Fn(f64, f64) -> String,
+) -> Vec<(String, f64)> {
+    let group = buckets.len().div_ceil(MAX_BARS).max(1);
+    let buckets: Vec<_> = buckets.collect();
+    buckets
+        .chunks(group)
+        .map(|chunk| {
+            let start = chunk[0].0;
+            let end = chunk[chunk.len() - 1].1;
+            let count: u64 = chunk.iter().map(|&(_, _, count)| count).sum();
+            (label(start, end), count as f64)
+        })
+        .collect()
+}
+
+/// Maps data coordinates onto the plot area.
+struct Plot {
+    x_max: f64,
+    y_max: f64,
+}
+
+impl Plot {
+    fn new(x_max: f64, y_max: f64) -> Self {
+        // An empty or flat chart still gets a sensible axis.
+        Self {
+            x_max: if x_max > 0.0 { x_max } else { 1.0 },
+            y_max: if y_max > 0.0 { y_max } else { 1.0 },
+        }
+    }
+
+    fn width(&self) -> f64 {
+        WIDTH - MARGIN_LEFT - MARGIN_RIGHT
+    }
+
+    fn height(&self) -> f64 {
+        HEIGHT - MARGIN_TOP - MARGIN_BOTTOM
+    }
+
+    fn x(&self, value: f64) -> f64 {
+        MARGIN_LEFT + value / self.x_max * self.width()
+    }
+
+    fn y(&self, value: f64) -> f64 {
+        MARGIN_TOP + (1.0 - value / self.y_max) * self.height()
+    }
+
+    /// Opens the `<svg>` element and draws the title, axes, horizontal grid
+    /// lines and y tick labels.
+    fn frame(&self, title: &str, x_label: &str, y_label: &str) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif"><rect width="100%" height="100%" fill="white"/>"#
+        );
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="20" font-size="14" font-weight="bold" text-anchor="middle">{}</text>"#,
+            WIDTH / 2.0,
+            escape(title)
+        );
+        for i in 0..=TICKS {
+            let value = self.y_max * i as f64 / TICKS as f64;
+            let y = self.y(value);
+            let _ = write!(
+                svg,
+                r#"<line x1="{MARGIN_LEFT}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="{GRID_COLOR}"/><text x="{:.1}" y="{:.1}" font-size="11" text-anchor="end">{}</text>"#,
+                WIDTH - MARGIN_RIGHT,
+                MARGIN_LEFT - 6.0,
+                y + 4.0,
+                format_tick(value)
+            );
+        }
+        let _ = write!(
+            svg,
+            r#"<polyline fill="none" stroke="{AXIS_COLOR}" points="{MARGIN_LEFT},{MARGIN_TOP} {MARGIN_LEFT},{:.1} {:.1},{:.1}"/>"#,
+            self.y(0.0),
+            WIDTH - MARGIN_RIGHT,
+            self.y(0.0)
+        );
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" font-size="12" text-anchor="middle">{}</text>"#,
+            MARGIN_LEFT + self.width() / 2.0,
+            HEIGHT - 6.0,
+            escape(x_label)
+        );
+        let _ = write!(
+            svg,
+            r#"<text x="14" y="{0:.1}" font-size="12" text-anchor="middle" transform="rotate(-90 14 {0:.1})">{1}</text>"#,
+            MARGIN_TOP + self.height() / 2.0,
+            escape(y_label)
+        );
+        svg
+    }
+
+    /// Labels evenly spaced x ticks with `label(i)` for tick `i`.
+    fn x_ticks(&self, svg: &mut String, label: impl Fn(u32) -> String) {
+        for i in 0..=TICKS {
+            let x = MARGIN_LEFT + self.width() * i as f64 / TICKS as f64;
+            let _ = write!(
+                svg,
+                r#"<text x="{x:.1}" y="{:.1}" font-size="11" text-anchor="middle">{}</text>"#,
+                HEIGHT - MARGIN_BOTTOM + 16.0,
+                label(i)
+            );
+        }
+    }
+}
+
+/// Formats an axis value with an SI suffix, e.g. `1.5G` or `250k`.
+fn format_tick(value: f64) -> String {
+    let (scaled, suffix) = if value >= 1e9 {
+        (value / 1e9, "G")
+    } else if value >= 1e6 {
+        (value / 1e6, "M")
+    } else if value >= 1e3 {
+        (value / 1e3, "k")
+    } else {
+        (value, "")
+    };
+    if scaled.fract() == 0.0 || scaled >= 100.0 {
+        format!("{:.0}{}", scaled, suffix)
+    } else {
+        format!("{:.1}{}", scaled, suffix)
+    }
+}
+
+/// Escapes text for use in SVG and HTML content.
+pub(super) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_chart_scales_points_to_plot_area() {
+        let svg = line_chart(
+            "Yield",
+            "Hours",
+            "Bases",
+            &[Series {
+                label: "Passed",
+                color: "#2a9d8f",
+                points: vec![(0.0, 0.0), (2.0, 1e9)],
+            }],
+        );
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        // The last point sits at the top right corner of the plot.
+        let top_right = format!("{:.1},{:.1}", WIDTH - MARGIN_RIGHT, MARGIN_TOP);
+        assert!(svg.contains(&top_right));
+        assert!(svg.contains(">1G<"));
+        // A single series needs no legend.
+        assert!(!svg.contains(">Passed<"));
+    }
+
+    #[test]
+    fn test_bar_chart_escapes_labels_and_handles_empty_data() {
+        let svg = bar_chart(
+            "Lengths",
+            "Bases",
+            "Reads",
+            &[("<1k".to_string(), 5.0), ("1k-2k".to_string(), 10.0)],
+            "#264653",
+        );
+        assert!(svg.contains("&lt;1k"));
+        assert_eq!(svg.matches("<rect x=").count(), 2);
+
+        let empty = bar_chart("Lengths", "Bases", "Reads", &[], "#264653");
+        assert!(empty.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_merge_buckets_caps_bar_count() {
+        let buckets = (0..100).map(|i| (i as f64, (i + 1) as f64, 1));
+        let bars = merge_buckets(buckets, |start, end| format!("{start}-{end}"));
+        assert_eq!(bars.len(), 34);
+        assert_eq!(bars[0], ("0-3".to_string(), 3.0));
+        assert_eq!(bars[33], ("99-100".to_string(), 1.0));
+    }
+
+    #[test]
+    fn test_format_tick() {
+        assert_eq!(format_tick(0.0), "0");
+        assert_eq!(format_tick(2500.0), "2.5k");
+        assert_eq!(format_tick(1.5e9), "1.5G");
+        assert_eq!(format_tick(250e6), "250M");
+    }
+}
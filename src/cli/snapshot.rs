@@ -0,0 +1,273 @@
+//! `termion snapshot` command implementation.
+//!
+//! Collects everything the TUI's detail view shows for one or all positions
+//! into a single JSON document, to attach to bug reports or feed to ad-hoc
+//! scripts. Every section is fetched independently: one that MinKNOW cannot
+//! provide is left out and its error recorded in the position's `errors`,
+//! so a snapshot of a misbehaving position is still useful.
+
+use crate::client::subscription::{HistogramOptions, DEFAULT_HISTOGRAM_INTERVAL};
+use crate::client::{
+    ChannelStatesSnapshot, Client, ClientError, DiskSpaceInfo, DutyTimeSnapshot, FlowCellInfo,
+    MuxScanResult, OutputInfo, PhaseEvent, Position, PositionClient, QScoreHistogram,
+    ReadLengthHistogram, RunInfo, RunState, StatsSnapshot, VersionInfo, YieldDataPoint,
+};
+use crate::config::Config;
+use futures::stream::{self, StreamExt};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long to wait for any one section, including the first message of a
+/// statistics stream.
+const SECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, serde::Serialize)]
+struct Snapshot {
+    /// When the snapshot was taken, in the display time zone.
+    generated: String,
+    termion_version: &'static str,
+    endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    minknow_version: Option<VersionInfo>,
+    positions: Vec<PositionSnapshot>,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+struct PositionSnapshot {
+    #[serde(flatten)]
+    position: Option<Position>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_state: Option<RunState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    acquisition_run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run: Option<RunInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flow_cell: Option<FlowCellInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<OutputInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    disk_space: Vec<DiskSpaceInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    phase_history: Vec<PhaseEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<StatsSnapshot>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    yield_history: Vec<YieldDataPoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    read_lengths: Option<ReadLengthHistogram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    qscores: Option<QScoreHistogram>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duty_time: Option<DutyTimeSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_states: Option<ChannelStatesSnapshot>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mux_scans: Vec<MuxScanResult>,
+    /// Why each missing section could not be fetched, by section name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    errors: BTreeMap<&'static str, String>,
+}
+
+impl PositionSnapshot {
+    /// Awaits one section, recording its error under `name` on failure.
+    async fn section<T>(
+        &mut self,
+        name: &'static str,
+        fetch: impl Future<Output = Result<T, ClientError>>,
+    ) -> Option<T> {
+        let result = match tokio::time::timeout(SECTION_TIMEOUT, fetch).await {
+            Ok(result) => result,
+            Err(_) => Err(ClientError::Timeout {
+                operation: name.to_string(),
+            }),
+        };
+        result
+            .map_err(|e| {
+                self.errors.insert(name, e.display_message());
+            })
+            .ok()
+    }
+}
+
+pub async fn run(
+    config: &Config,
+    position_filter: Option<String>,
+    output: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
+        &config.connection.host,
+        config.connection.port,
+        config.connection.connect_options(),
+    )
+    .await?;
+    let positions: Vec<_> = client
+        .list_positions()
+        .await?
+        .into_iter()
+        .filter(|p| position_filter.as_ref().is_none_or(|f| p.name == *f))
+        .collect();
+    if let (Some(name), true) = (&position_filter, positions.is_empty()) {
+        return Err(ClientError::NotFound {
+            resource: "Position".into(),
+            id: name.clone(),
+        }
+        .into());
+    }
+
+    let minknow_version = client.get_version_info().await.ok();
+    let client = &client;
+    let positions = stream::iter(positions)
+        .map(|position| position_snapshot(client, position))
+        .buffered(config.connection.max_concurrent_positions)
+        .collect()
+        .await;
+    let snapshot = Snapshot {
+        generated: config.tui.timezone.to_rfc3339(chrono::Utc::now()),
+        termion_version: env!("CARGO_PKG_VERSION"),
+        endpoint: client.endpoint().to_string(),
+        minknow_version,
+        positions,
+    };
+
+    let json = serde_json::to_string_pretty(&snapshot)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, json + "\n")?;
+            eprintln!(
+                "Wrote snapshot of {} positions to {}",
+                snapshot.positions.len(),
+                path.display()
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Fetches every section for one position. Positions that are not running
+/// have no services and only report what the manager listed.
+async fn position_snapshot(client: &Client, position: Position) -> PositionSnapshot {
+    let mut snapshot = PositionSnapshot {
+        position: Some(position.clone()),
+        ..Default::default()
+    };
+    if position.grpc_port == 0 {
+        return snapshot;
+    }
+    let Some(mut pos_client) = snapshot
+        .section("connection", client.connect_position(position))
+        .await
+    else {
+        return snapshot;
+    };
+
+    collect(&mut snapshot, &mut pos_client).await;
+    snapshot
+}
+
+async fn collect(snapshot: &mut PositionSnapshot, client: &mut PositionClient) {
+    snapshot.run_state = snapshot.section("run_state", client.get_run_state()).await;
+    snapshot.flow_cell = snapshot
+        .section("flow_cell", client.get_flow_cell_info())
+        .await;
+    snapshot.disk_space = snapshot
+        .section("disk_space", client.get_disk_space_info())
+        .await
+        .unwrap_or_default();
+    if !snapshot.run_state.as_ref().is_some_and(RunState::is_active) {
+        return;
+    }
+
+    snapshot.run = snapshot
+        .section("run", client.get_run_info())
+        .await
+        .flatten();
+    snapshot.output = snapshot
+        .section("output", client.get_output_info())
+        .await
+        .flatten();
+    snapshot.phase_history = snapshot
+        .section("phase_history", client.get_phase_history())
+        .await
+        .unwrap_or_default();
+    snapshot.stats = snapshot.section("stats", client.get_stats()).await;
+    snapshot.mux_scans = snapshot
+        .section("mux_scans", client.get_mux_scan_results())
+        .await
+        .unwrap_or_default();
+    if let Some(channel_count) = snapshot.flow_cell.as_ref().map(|f| f.channel_count) {
+        snapshot.channel_states = snapshot
+            .section("channel_states", client.get_channel_states(channel_count))
+            .await;
+    }
+
+    snapshot.acquisition_run_id = snapshot
+        .section("acquisition_run_id", client.get_current_run_id())
+        .await
+        .flatten();
+    let Some(acquisition) = snapshot.acquisition_run_id.clone() else {
+        return;
+    };
+    snapshot.yield_history = snapshot
+        .section("yield_history", client.get_yield_history(&acquisition))
+        .await
+        .unwrap_or_default();
+    snapshot.read_lengths = snapshot
+        .section("read_lengths", async {
+            let stream = client
+                .stream_read_length_histogram(
+                    &acquisition,
+                    HistogramOptions::default(),
+                    DEFAULT_HISTOGRAM_INTERVAL,
+                )
+                .await?;
+            Box::pin(stream).next().await.transpose()
+        })
+        .await
+        .flatten();
+    snapshot.qscores = snapshot
+        .section("qscores", async {
+            let stream = client
+                .stream_qscore_histogram(&acquisition, DEFAULT_HISTOGRAM_INTERVAL)
+                .await?;
+            Box::pin(stream).next().await.transpose()
+        })
+        .await
+        .flatten();
+    snapshot.duty_time = snapshot
+        .section("duty_time", async {
+            let stream = client.stream_duty_time(&acquisition).await?;
+            Box::pin(stream).next().await.transpose()
+        })
+        .await
+        .flatten();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_section_records_errors() {
+        let mut snapshot = PositionSnapshot::default();
+        let stats = snapshot
+            .section("stats", async { Err::<(), _>(ClientError::Disconnected) })
+            .await;
+        let flow_cell = snapshot
+            .section("flow_cell", async { Ok(FlowCellInfo::default()) })
+            .await;
+
+        assert!(stats.is_none());
+        assert!(flow_cell.is_some());
+        assert_eq!(snapshot.errors.len(), 1);
+        assert_eq!(snapshot.errors["stats"], "Connection lost");
+
+        let json = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(json["errors"]["stats"], "Connection lost");
+        assert!(json.get("stats").is_none());
+        assert!(json.get("disk_space").is_none());
+    }
+}
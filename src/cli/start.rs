@@ -0,0 +1,260 @@
+//! `termion start` command implementation.
+//!
+//! Starts a protocol on one position. The protocol is either named with
+//! `--protocol` or chosen from the position's protocols by kit and the
+//! product code of the inserted flow cell.
+
+use crate::client::{Client, ClientError, MinknowApi, PositionApi, ProtocolInfo};
+use crate::config::Config;
+
+#[derive(serde::Serialize)]
+struct Started<'a> {
+    position: &'a str,
+    protocol: &'a str,
+    run_id: &'a str,
+}
+
+pub async fn run(
+    config: &Config,
+    position_name: &str,
+    kit: Option<&str>,
+    protocol: Option<&str>,
+    sample_id: &str,
+    experiment_group: &str,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
+        &config.connection.host,
+        config.connection.port,
+        config.connection.connect_options(),
+    )
+    .await?;
+    let (identifier, run_id) = start(
+        &mut client,
+        position_name,
+        kit,
+        protocol,
+        sample_id,
+        experiment_group,
+    )
+    .await?;
+
+    let started = Started {
+        position: position_name,
+        protocol: &identifier,
+        run_id: &run_id,
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&started)?);
+    } else {
+        println!(
+            "Started {} on {} (run {})",
+            started.protocol, started.position, started.run_id
+        );
+    }
+
+    Ok(())
+}
+
+/// Starts the selected protocol on an idle position with a flow cell.
+/// Returns the protocol identifier and the new run ID.
+async fn start(
+    client: &mut impl MinknowApi,
+    position_name: &str,
+    kit: Option<&str>,
+    protocol: Option<&str>,
+    sample_id: &str,
+    experiment_group: &str,
+) -> anyhow::Result<(String, String)> {
+    let position = client
+        .list_positions()
+        .await?
+        .into_iter()
+        .find(|p| p.name == position_name)
+        .ok_or_else(|| ClientError::NotFound {
+            resource: "Position".into(),
+            id: position_name.to_string(),
+        })?;
+    let mut pos_client = client.connect_position(position).await?;
+
+    let flow_cell = pos_client.get_flow_cell_info().await?;
+    if !flow_cell.has_flow_cell {
+        anyhow::bail!("No flow cell inserted in position {}", position_name);
+    }
+    if pos_client.get_run_state().await?.is_active() {
+        anyhow::bail!("Position {} already has a run in progress", position_name);
+    }
+
+    let protocols = pos_client.list_protocols().await?;
+    let selected = select_protocol(&protocols, kit, protocol, flow_cell.product_code.as_deref())?;
+    let run_id = pos_client
+        .start_protocol(&selected.identifier, sample_id, experiment_group)
+        .await?;
+    Ok((selected.identifier.clone(), run_id))
+}
+
+/// Picks the protocol to start.
+///
+/// An explicit identifier wins. Otherwise the protocol must list `kit` among
+/// its kits and, when both are known, match the flow cell product code;
+/// sequencing protocols are preferred when several match.
+fn select_protocol<'a>(
+    protocols: &'a [ProtocolInfo],
+    kit: Option<&str>,
+    identifier: Option<&str>,
+    product_code: Option<&str>,
+) -> anyhow::Result<&'a ProtocolInfo> {
+    if let Some(identifier) = identifier {
+        return protocols
+            .iter()
+            .find(|p| p.identifier == identifier)
+            .ok_or_else(|| {
+                ClientError::NotFound {
+                    resource: "Protocol".into(),
+                    id: identifier.to_string(),
+                }
+                .into()
+            });
+    }
+    let Some(kit) = kit else {
+        anyhow::bail!("Invalid arguments: pass --kit or --protocol");
+    };
+
+    // Kit and flow cell tags may list several values separated by spaces.
+    let lists = |tag: &Option<String>, value: &str| {
+        tag.as_deref()
+            .is_some_and(|t| t.split_whitespace().any(|v| v.eq_ignore_ascii_case(value)))
+    };
+    let mut matches: Vec<&ProtocolInfo> = protocols
+        .iter()
+        .filter(|p| lists(&p.kit, kit))
+        .filter(|p| match product_code {
+            Some(code) if p.flow_cell.is_some() => lists(&p.flow_cell, code),
+            _ => true,
+        })
+        .collect();
+    if matches.len() > 1
+        && matches
+            .iter()
+            .any(|p| p.experiment_type.as_deref() == Some("sequencing"))
+    {
+        matches.retain(|p| p.experiment_type.as_deref() == Some("sequencing"));
+    }
+
+    match matches.as_slice() {
+        [protocol] => Ok(protocol),
+        [] => Err(ClientError::NotFound {
+            resource: "Protocol".into(),
+            id: match product_code {
+                Some(code) => format!("kit {} on {}", kit, code),
+                None => format!("kit {}", kit),
+            },
+        }
+        .into()),
+        several => {
+            let ids: Vec<&str> = several.iter().map(|p| p.identifier.as_str()).collect();
+            anyhow::bail!(
+                "Several protocols match kit {}: {}. Choose one with --protocol",
+                kit,
+                ids.join(", ")
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::{MockClient, ScriptedPosition};
+    use crate::client::RunState;
+
+    fn protocol(identifier: &str, kit: &str, flow_cell: &str, kind: &str) -> ProtocolInfo {
+        ProtocolInfo {
+            identifier: identifier.to_string(),
+            name: identifier.to_string(),
+            experiment_type: Some(kind.to_string()),
+            kit: Some(kit.to_string()),
+            flow_cell: Some(flow_cell.to_string()),
+        }
+    }
+
+    fn protocols() -> Vec<ProtocolInfo> {
+        vec![
+            protocol(
+                "seq_min",
+                "SQK-LSK114 SQK-NBD114-24",
+                "FLO-MIN114",
+                "sequencing",
+            ),
+            protocol("seq_pro", "SQK-LSK114", "FLO-PRO114M", "sequencing"),
+            protocol("qc_min", "SQK-LSK114", "FLO-MIN114", "control"),
+        ]
+    }
+
+    #[test]
+    fn test_select_by_kit_and_flow_cell() {
+        let protocols = protocols();
+        let selected =
+            select_protocol(&protocols, Some("sqk-nbd114-24"), None, Some("FLO-MIN114")).unwrap();
+        assert_eq!(selected.identifier, "seq_min");
+
+        // The control protocol also matches but sequencing is preferred.
+        let selected =
+            select_protocol(&protocols, Some("SQK-LSK114"), None, Some("FLO-MIN114")).unwrap();
+        assert_eq!(selected.identifier, "seq_min");
+    }
+
+    #[test]
+    fn test_select_ambiguous_or_missing() {
+        let protocols = protocols();
+        let err = select_protocol(&protocols, Some("SQK-LSK114"), None, None).unwrap_err();
+        assert!(err.to_string().contains("seq_min, seq_pro"));
+
+        let err = select_protocol(&protocols, Some("SQK-RAD114"), None, None).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ClientError>(),
+            Some(ClientError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_select_by_identifier() {
+        let protocols = protocols();
+        let selected = select_protocol(&protocols, None, Some("qc_min"), None).unwrap();
+        assert_eq!(selected.identifier, "qc_min");
+        assert!(select_protocol(&protocols, None, Some("missing"), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_start_checks_position_before_starting() {
+        let protocols = [("seq_min", "SQK-LSK114")];
+        let mut client = MockClient::new()
+            .with_position(
+                ScriptedPosition::new("X1", "MN12345")
+                    .with_flow_cell("FAX00001")
+                    .with_protocols(&protocols),
+            )
+            .with_position(ScriptedPosition::new("X2", "MN12345").with_protocols(&protocols));
+
+        let (identifier, run_id) = start(&mut client, "X1", Some("SQK-LSK114"), None, "S1", "G1")
+            .await
+            .unwrap();
+        assert_eq!(identifier, "seq_min");
+        let x1 = client.position("X1").unwrap();
+        assert_eq!(x1.run_state, RunState::Starting);
+        assert_eq!(x1.run_info.unwrap().run_id, Some(run_id));
+
+        let err = start(&mut client, "X1", Some("SQK-LSK114"), None, "S1", "G1")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("already has a run"));
+        let err = start(&mut client, "X2", Some("SQK-LSK114"), None, "S1", "G1")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No flow cell"));
+        assert!(!client
+            .calls()
+            .iter()
+            .any(|c| c.position.as_deref() == Some("X2") && c.method == "start_protocol"));
+    }
+}
@@ -1,28 +1,72 @@
 //! `termion status` command implementation.
 
-use crate::client::Client;
+use super::{csv, OutputFormat};
+use crate::client::{Client, ClientError, MinknowApi, OutputInfo, Position, PositionApi};
 use crate::config::Config;
+use crate::format::{format_bases, format_number, format_storage};
+use futures::stream::{self, StreamExt};
+use std::io::{self, Write};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// Version of the `--ndjson` line format. Bumped when a field is removed or
+/// changes meaning; new fields may be added without a bump.
+pub const NDJSON_SCHEMA_VERSION: u32 = 1;
+
+/// Columns of `--format csv`, in order.
+const CSV_HEADER: [&str; 17] = [
+    "name",
+    "state",
+    "run_id",
+    "started_at",
+    "sample_id",
+    "experiment_group",
+    "protocol",
+    "flow_cell_product_code",
+    "reads",
+    "bases_passed",
+    "bases_failed",
+    "simulated",
+    "reads_directory",
+    "formats",
+    "bytes_written",
+    "bytes_pending",
+    "bytes_failed",
+];
+
+/// One `--ndjson` line: a position's status, or the error that kept the
+/// manager from listing positions.
+#[derive(serde::Serialize)]
+struct StatusLine<'a> {
+    schema_version: u32,
+    timestamp: String,
+    #[serde(flatten)]
+    status: Option<&'a PositionStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
 
 pub async fn run(
     config: &Config,
-    json: bool,
+    format: OutputFormat,
+    ndjson: bool,
+    follow: Option<Duration>,
     position_filter: Option<String>,
 ) -> anyhow::Result<()> {
-    let mut client = Client::connect_with_timeouts(
+    let mut client = Client::connect_with_options(
         &config.connection.host,
         config.connection.port,
-        config.connection.connect_timeout,
-        config.connection.request_timeout,
+        config.connection.connect_options(),
     )
     .await?;
+    if ndjson {
+        return print_ndjson(&mut client, config, position_filter.as_deref(), follow).await;
+    }
     let positions = client.list_positions().await?;
 
-    if positions.is_empty() {
-        if json {
-            println!("[]");
-        } else {
-            println!("No positions found");
-        }
+    // JSON and CSV print an empty array or a lone header instead.
+    if positions.is_empty() && format == OutputFormat::Text {
+        println!("No positions found");
         return Ok(());
     }
 
@@ -35,77 +79,24 @@ pub async fn run(
         positions
     };
 
-    if positions_to_check.is_empty() {
-        if json {
-            println!("[]");
-        } else {
-            println!(
-                "Position '{}' not found",
-                position_filter.unwrap_or_default()
-            );
-        }
+    if positions_to_check.is_empty() && format == OutputFormat::Text {
+        println!(
+            "Position '{}' not found",
+            position_filter.unwrap_or_default()
+        );
         return Ok(());
     }
 
-    let mut results = Vec::new();
-
-    for position in positions_to_check {
-        let status = if position.grpc_port > 0 {
-            match client.connect_position(position.clone()).await {
-                Ok(mut pos_client) => match pos_client.get_acquisition_info().await {
-                    Ok(info) => {
-                        let is_active = info.state.is_active();
-                        PositionStatus {
-                            name: position.name.clone(),
-                            state: info.state.label().to_string(),
-                            run_id: if info.run_id.is_empty() || !is_active {
-                                None
-                            } else {
-                                Some(info.run_id)
-                            },
-                            reads: if is_active { info.reads_processed } else { 0 },
-                            bases_passed: if is_active { info.bases_passed } else { 0 },
-                            bases_failed: if is_active { info.bases_failed } else { 0 },
-                            simulated: position.is_simulated,
-                        }
-                    }
-                    Err(e) => PositionStatus {
-                        name: position.name.clone(),
-                        state: format!("Error: {}", e),
-                        run_id: None,
-                        reads: 0,
-                        bases_passed: 0,
-                        bases_failed: 0,
-                        simulated: position.is_simulated,
-                    },
-                },
-                Err(e) => PositionStatus {
-                    name: position.name.clone(),
-                    state: format!("Connection error: {}", e),
-                    run_id: None,
-                    reads: 0,
-                    bases_passed: 0,
-                    bases_failed: 0,
-                    simulated: position.is_simulated,
-                },
-            }
-        } else {
-            PositionStatus {
-                name: position.name.clone(),
-                state: "Not running".to_string(),
-                run_id: None,
-                reads: 0,
-                bases_passed: 0,
-                bases_failed: 0,
-                simulated: position.is_simulated,
-            }
-        };
-
-        results.push(status);
-    }
+    let results: Vec<PositionStatus> = stream::iter(positions_to_check)
+        .map(|position| position_status(&client, config, position))
+        .buffered(config.connection.max_concurrent_positions)
+        .collect()
+        .await;
 
-    if json {
+    if format == OutputFormat::Json {
         println!("{}", serde_json::to_string_pretty(&results)?);
+    } else if format == OutputFormat::Csv {
+        csv::write_table(&mut io::stdout().lock(), CSV_HEADER, &results, csv_row)?;
     } else {
         for status in results {
             println!(
@@ -117,6 +108,31 @@ pub async fn run(
             if let Some(run_id) = &status.run_id {
                 println!("  Run ID: {}", run_id);
             }
+            if let Some(sample_id) = &status.sample_id {
+                println!("  Sample: {}", sample_id);
+            }
+            if let Some(group) = &status.experiment_group {
+                println!("  Experiment group: {}", group);
+            }
+            if let Some(protocol) = &status.protocol {
+                println!("  Protocol: {}", protocol);
+            }
+            if let Some(product_code) = &status.flow_cell_product_code {
+                println!("  Flow cell: {}", product_code);
+            }
+            if let Some(started_at) = &status.started_at {
+                println!("  Started: {}", started_at);
+            }
+            if let Some(output) = &status.output {
+                if let Some(dir) = &output.reads_directory {
+                    println!("  Output: {} ({})", dir, output.formats.join(", "));
+                }
+                println!(
+                    "  Written: {} ({} pending)",
+                    format_storage(output.bytes_written),
+                    format_storage(output.bytes_pending)
+                );
+            }
             println!("  Reads: {}", format_number(status.reads));
             println!("  Bases passed: {}", format_bases(status.bases_passed));
             println!("  Bases failed: {}", format_bases(status.bases_failed));
@@ -127,36 +143,298 @@ pub async fn run(
     Ok(())
 }
 
-#[derive(serde::Serialize)]
-struct PositionStatus {
-    name: String,
-    state: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    run_id: Option<String>,
-    reads: u64,
-    bases_passed: u64,
-    bases_failed: u64,
-    simulated: bool,
+/// One `--format csv` row, in [`CSV_HEADER`] order.
+fn csv_row(status: &PositionStatus) -> [String; 17] {
+    let output = status.output.as_ref();
+    [
+        status.name.clone(),
+        status.state.clone(),
+        csv::optional(status.run_id.as_ref()),
+        csv::optional(status.started_at.as_ref()),
+        csv::optional(status.sample_id.as_ref()),
+        csv::optional(status.experiment_group.as_ref()),
+        csv::optional(status.protocol.as_ref()),
+        csv::optional(status.flow_cell_product_code.as_ref()),
+        status.reads.to_string(),
+        status.bases_passed.to_string(),
+        status.bases_failed.to_string(),
+        status.simulated.to_string(),
+        csv::optional(output.and_then(|o| o.reads_directory.as_ref())),
+        output.map(|o| o.formats.join(";")).unwrap_or_default(),
+        csv::optional(output.map(|o| o.bytes_written)),
+        csv::optional(output.map(|o| o.bytes_pending)),
+        csv::optional(output.map(|o| o.bytes_failed)),
+    ]
 }
 
-fn format_number(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.2}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.2}K", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
+/// Prints one [`StatusLine`] per position, once, or every `interval` until
+/// interrupted. While following, a manager that cannot be reached is
+/// reported as an error line and retried on the next tick.
+async fn print_ndjson(
+    client: &mut Client,
+    config: &Config,
+    position_filter: Option<&str>,
+    interval: Option<Duration>,
+) -> anyhow::Result<()> {
+    let mut ticker = interval.map(|interval| {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+
+    loop {
+        if let Some(ticker) = &mut ticker {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+
+        let timestamp = config.tui.timezone.to_rfc3339(chrono::Utc::now());
+        let line = |status, error| StatusLine {
+            schema_version: NDJSON_SCHEMA_VERSION,
+            timestamp: timestamp.clone(),
+            status,
+            error,
+        };
+        let result = statuses(client, config, position_filter).await;
+        let mut out = io::stdout().lock();
+        match result {
+            Ok(statuses) => {
+                for status in &statuses {
+                    writeln!(out, "{}", serde_json::to_string(&line(Some(status), None))?)?;
+                }
+            }
+            Err(e) if ticker.is_some() => {
+                let error = line(None, Some(e.display_message()));
+                writeln!(out, "{}", serde_json::to_string(&error)?)?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+        out.flush()?;
+
+        if ticker.is_none() {
+            return Ok(());
+        }
     }
 }
 
-fn format_bases(n: u64) -> String {
-    if n >= 1_000_000_000 {
-        format!("{:.2} Gb", n as f64 / 1_000_000_000.0)
-    } else if n >= 1_000_000 {
-        format!("{:.2} Mb", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.2} Kb", n as f64 / 1_000.0)
+/// Lists the manager's positions, optionally only the one named, and
+/// queries each one's status.
+pub(super) async fn statuses(
+    client: &mut Client,
+    config: &Config,
+    position_filter: Option<&str>,
+) -> Result<Vec<PositionStatus>, ClientError> {
+    let positions = client
+        .list_positions()
+        .await?
+        .into_iter()
+        .filter(|p| position_filter.is_none_or(|f| p.name == f));
+    let client = &*client;
+    Ok(stream::iter(positions)
+        .map(|position| position_status(client, config, position))
+        .buffered(config.connection.max_concurrent_positions)
+        .collect()
+        .await)
+}
+
+/// Queries one position's acquisition state.
+pub(super) async fn position_status(
+    client: &impl MinknowApi,
+    config: &Config,
+    position: Position,
+) -> PositionStatus {
+    if position.grpc_port > 0 {
+        match client.connect_position(position.clone()).await {
+            Ok(mut pos_client) => match pos_client.get_acquisition_info().await {
+                Ok(info) => {
+                    let is_active = info.state.is_active();
+                    let run = if is_active {
+                        pos_client.get_run_info().await.ok().flatten()
+                    } else {
+                        None
+                    }
+                    .unwrap_or_default();
+                    let output = if is_active {
+                        pos_client.get_output_info().await.ok().flatten()
+                    } else {
+                        None
+                    };
+                    PositionStatus {
+                        name: position.name.clone(),
+                        state: info.state.label().to_string(),
+                        run_id: if info.run_id.is_empty() || !is_active {
+                            None
+                        } else {
+                            Some(info.run_id)
+                        },
+                        started_at: run.start_time.map(|ts| config.tui.timezone.to_rfc3339(ts)),
+                        sample_id: run.sample_id,
+                        experiment_group: run.experiment_group,
+                        protocol: run.protocol_name,
+                        flow_cell_product_code: run.flow_cell_product_code,
+                        output,
+                        reads: if is_active { info.reads_processed } else { 0 },
+                        bases_passed: if is_active { info.bases_passed } else { 0 },
+                        bases_failed: if is_active { info.bases_failed } else { 0 },
+                        simulated: position.is_simulated,
+                    }
+                }
+                Err(e) => PositionStatus {
+                    name: position.name.clone(),
+                    state: format!("Error: {}", e),
+                    simulated: position.is_simulated,
+                    ..Default::default()
+                },
+            },
+            Err(e) => PositionStatus {
+                name: position.name.clone(),
+                state: format!("Connection error: {}", e),
+                simulated: position.is_simulated,
+                ..Default::default()
+            },
+        }
     } else {
-        format!("{} b", n)
+        PositionStatus {
+            name: position.name.clone(),
+            state: "Not running".to_string(),
+            simulated: position.is_simulated,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Default, serde::Serialize)]
+pub(super) struct PositionStatus {
+    pub(super) name: String,
+    pub(super) state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) run_id: Option<String>,
+    /// Run start time as RFC 3339 in the configured display time zone.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) sample_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) experiment_group: Option<String>,
+    /// Protocol display name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) protocol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) flow_cell_product_code: Option<String>,
+    /// Where the run writes its reads, and how much it has written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) output: Option<OutputInfo>,
+    pub(super) reads: u64,
+    pub(super) bases_passed: u64,
+    pub(super) bases_failed: u64,
+    pub(super) simulated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::{MockClient, ScriptedPosition};
+    use crate::client::ClientError;
+
+    #[tokio::test]
+    async fn test_position_status_reports_run_and_errors() {
+        let client = MockClient::new()
+            .with_position(
+                ScriptedPosition::new("X1", "MN12345")
+                    .with_flow_cell("FAX00001")
+                    .with_run("run-1"),
+            )
+            .with_position(ScriptedPosition::new("X2", "MN12345"));
+        let config = Config::default();
+        let position = |name: &str| client.position(name).unwrap().position;
+
+        let status = position_status(&client, &config, position("X1")).await;
+        assert_eq!(status.state, "Running");
+        assert_eq!(status.run_id.as_deref(), Some("run-1"));
+        assert_eq!(status.bases_passed, 90_000_000);
+        assert_eq!(status.sample_id.as_deref(), Some("sample-1"));
+        assert_eq!(status.flow_cell_product_code.as_deref(), Some("FLO-MIN114"));
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["protocol"], "Ligation Sequencing Kit V14");
+        assert_eq!(json["experiment_group"], "experiment-1");
+        assert_eq!(
+            json["output"]["reads_directory"],
+            "/data/experiment-1/sample-1/run-1"
+        );
+        assert_eq!(json["output"]["formats"][0], "pod5");
+
+        let status = position_status(&client, &config, position("X2")).await;
+        assert_eq!(status.run_id, None);
+        assert_eq!(status.reads, 0);
+        let json = serde_json::to_value(&status).unwrap();
+        assert!(json.get("sample_id").is_none());
+        assert!(json.get("output").is_none());
+
+        client.fail("get_acquisition_info", ClientError::Disconnected);
+        let status = position_status(&client, &config, position("X1")).await;
+        assert_eq!(status.state, "Error: Connection lost");
+    }
+
+    #[test]
+    fn test_csv_row_matches_header() {
+        let status = PositionStatus {
+            name: "X1".to_string(),
+            state: "Running".to_string(),
+            run_id: Some("run-1".to_string()),
+            sample_id: Some("sample, 1".to_string()),
+            output: Some(OutputInfo {
+                formats: vec!["pod5".to_string(), "fastq".to_string()],
+                bytes_written: 2_000,
+                ..Default::default()
+            }),
+            reads: 10,
+            ..Default::default()
+        };
+        let row = csv_row(&status);
+        let field = |name: &str| &row[CSV_HEADER.iter().position(|h| *h == name).unwrap()];
+        assert_eq!(field("run_id"), "run-1");
+        assert_eq!(field("sample_id"), "sample, 1");
+        assert_eq!(field("experiment_group"), "");
+        assert_eq!(field("reads"), "10");
+        assert_eq!(field("formats"), "pod5;fastq");
+        assert_eq!(field("bytes_written"), "2000");
+
+        let mut out = Vec::new();
+        csv::write_table(&mut out, CSV_HEADER, [&status], csv_row).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("name,state,run_id,started_at,"));
+        assert!(text.contains("\"sample, 1\""));
+    }
+
+    #[test]
+    fn test_status_line_flattens_status() {
+        let status = PositionStatus {
+            name: "X1".to_string(),
+            state: "Running".to_string(),
+            reads: 10,
+            ..Default::default()
+        };
+        let line = StatusLine {
+            schema_version: NDJSON_SCHEMA_VERSION,
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            status: Some(&status),
+            error: None,
+        };
+        let json = serde_json::to_value(&line).unwrap();
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["name"], "X1");
+        assert_eq!(json["reads"], 10);
+        assert!(json.get("error").is_none());
+
+        let line = StatusLine {
+            status: None,
+            error: Some("Connection refused".to_string()),
+            ..line
+        };
+        let json = serde_json::to_value(&line).unwrap();
+        assert_eq!(json["error"], "Connection refused");
+        assert!(json.get("name").is_none());
     }
 }
@@ -0,0 +1,182 @@
+//! `termion watch` command implementation.
+//!
+//! Prints a status table every interval without taking over the terminal,
+//! or one JSON snapshot per line with `--ndjson`. When stdout is a terminal
+//! the table is redrawn in place; otherwise each table is appended, so the
+//! output can be logged to a file.
+
+use super::status::{statuses, PositionStatus};
+use crate::client::Client;
+use crate::config::Config;
+use crate::format::{format_bases, format_number};
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// Clears the screen and moves the cursor home.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+#[derive(serde::Serialize)]
+struct Snapshot<'a> {
+    timestamp: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    positions: Option<&'a [PositionStatus]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub async fn run(
+    config: &Config,
+    ndjson: bool,
+    interval: Duration,
+    position_filter: Option<String>,
+) -> anyhow::Result<()> {
+    let mut client = Client::connect_with_options(
+        &config.connection.host,
+        config.connection.port,
+        config.connection.connect_options(),
+    )
+    .await?;
+
+    let redraw = !ndjson && io::stdout().is_terminal();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+
+        let timestamp = config.tui.timezone.to_rfc3339(chrono::Utc::now());
+        let result = statuses(&mut client, config, position_filter.as_deref())
+            .await
+            .map_err(|e| e.display_message());
+
+        let mut out = io::stdout().lock();
+        if ndjson {
+            let snapshot = match &result {
+                Ok(statuses) => Snapshot {
+                    timestamp,
+                    positions: Some(statuses),
+                    error: None,
+                },
+                Err(message) => Snapshot {
+                    timestamp,
+                    positions: None,
+                    error: Some(message.clone()),
+                },
+            };
+            writeln!(out, "{}", serde_json::to_string(&snapshot)?)?;
+        } else {
+            if redraw {
+                write!(out, "{}", CLEAR)?;
+            }
+            writeln!(
+                out,
+                "Every {}s  {}  {}\n",
+                interval.as_secs(),
+                client.endpoint(),
+                timestamp
+            )?;
+            match &result {
+                Ok(statuses) if statuses.is_empty() => match &position_filter {
+                    Some(name) => writeln!(out, "Position '{}' not found", name)?,
+                    None => writeln!(out, "No positions found")?,
+                },
+                Ok(statuses) => write!(out, "{}", render_table(statuses))?,
+                Err(message) => writeln!(out, "Error: {}", message)?,
+            }
+            if !redraw {
+                writeln!(out)?;
+            }
+        }
+        out.flush()?;
+    }
+}
+
+/// Formats statuses as an aligned table with a header row.
+fn render_table(statuses: &[PositionStatus]) -> String {
+    let header = ["POSITION", "STATE", "RUN ID", "READS", "PASSED", "FAILED"];
+    let rows: Vec<[String; 6]> = statuses
+        .iter()
+        .map(|s| {
+            [
+                s.name.clone(),
+                s.state.clone(),
+                s.run_id.clone().unwrap_or_else(|| "-".to_string()),
+                format_number(s.reads),
+                format_bases(s.bases_passed),
+                format_bases(s.bases_failed),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut table = String::new();
+    let header = header.map(String::from);
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(name: &str, run_id: Option<&str>, reads: u64) -> PositionStatus {
+        PositionStatus {
+            name: name.to_string(),
+            state: if run_id.is_some() { "Running" } else { "Idle" }.to_string(),
+            run_id: run_id.map(String::from),
+            started_at: None,
+            reads,
+            bases_passed: reads * 9_000,
+            bases_failed: reads * 1_000,
+            simulated: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let table = render_table(&[
+            status("X1", Some("run-with-long-id"), 10_000),
+            status("X2", None, 0),
+        ]);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("POSITION  STATE    RUN ID            READS"));
+        assert!(lines[1].starts_with("X1        Running  run-with-long-id  10.00K"));
+        assert!(lines[2].starts_with("X2        Idle     -                 0"));
+        let reads_column = lines[0].find("READS").unwrap();
+        assert_eq!(lines[2].find('0'), Some(reads_column));
+    }
+
+    #[test]
+    fn test_snapshot_error_omits_positions() {
+        let snapshot = Snapshot {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            positions: None,
+            error: Some("Connection refused".to_string()),
+        };
+        let json: serde_json::Value = serde_json::to_value(&snapshot).expect("snapshot serializes");
+
+        assert_eq!(json["error"], "Connection refused");
+        assert!(json.get("positions").is_none());
+    }
+}
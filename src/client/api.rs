@@ -0,0 +1,195 @@
+//! Traits over the request/response part of the MinKNOW API.
+//!
+//! [`MinknowApi`] and [`PositionApi`] cover the unary calls that the CLI
+//! commands and the TUI poller make, so that code can run against the
+//! scripted [`MockClient`](super::mock::MockClient) in tests as well as the
+//! gRPC [`Client`]. Statistics streams are not part of the traits; they stay
+//! on [`PositionClient`] and [`RunSubscription`](super::subscription::RunSubscription).
+
+use super::{
+    AcquisitionInfo, Client, ClientError, Device, DiskSpaceInfo, FlowCellInfo, OutputInfo,
+    Position, PositionClient, ProtocolInfo, RunInfo, RunState, RunSummary, RunUntilCriteria,
+    StatsSnapshot, StopOptions,
+};
+use std::future::Future;
+
+/// Connection to a MinKNOW manager.
+pub trait MinknowApi: Clone + Send + Sync + 'static {
+    type Position: PositionApi;
+
+    /// Address of the manager, e.g. `localhost:9501`.
+    fn endpoint(&self) -> &str;
+
+    fn list_positions(&mut self)
+        -> impl Future<Output = Result<Vec<Position>, ClientError>> + Send;
+
+    fn list_devices(&mut self) -> impl Future<Output = Result<Vec<Device>, ClientError>> + Send;
+
+    fn connect_position(
+        &self,
+        position: Position,
+    ) -> impl Future<Output = Result<Self::Position, ClientError>> + Send;
+}
+
+/// Connection to one flow cell position.
+pub trait PositionApi: Send + 'static {
+    fn position(&self) -> &Position;
+
+    fn get_run_state(&mut self) -> impl Future<Output = Result<RunState, ClientError>> + Send;
+
+    /// Run info for the current protocol run, or `None` when there is none.
+    fn get_run_info(&mut self)
+        -> impl Future<Output = Result<Option<RunInfo>, ClientError>> + Send;
+
+    fn get_acquisition_info(
+        &mut self,
+    ) -> impl Future<Output = Result<AcquisitionInfo, ClientError>> + Send;
+
+    /// Acquisition run ID, or `None` when no acquisition is running.
+    fn get_current_run_id(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<String>, ClientError>> + Send;
+
+    fn get_flow_cell_info(
+        &mut self,
+    ) -> impl Future<Output = Result<FlowCellInfo, ClientError>> + Send;
+
+    fn get_disk_space_info(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<DiskSpaceInfo>, ClientError>> + Send;
+
+    fn get_stats(&mut self) -> impl Future<Output = Result<StatsSnapshot, ClientError>> + Send;
+
+    /// Output location and writer progress of the current acquisition, or
+    /// `None` when there is none.
+    fn get_output_info(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<OutputInfo>, ClientError>> + Send;
+
+    fn list_run_history(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<RunSummary>, ClientError>> + Send;
+
+    fn list_protocols(
+        &mut self,
+    ) -> impl Future<Output = Result<Vec<ProtocolInfo>, ClientError>> + Send;
+
+    /// Starts a protocol and returns the new protocol run ID.
+    fn start_protocol(
+        &mut self,
+        identifier: &str,
+        sample_id: &str,
+        experiment_group: &str,
+    ) -> impl Future<Output = Result<String, ClientError>> + Send;
+
+    fn pause(&mut self) -> impl Future<Output = Result<(), ClientError>> + Send;
+
+    fn resume(&mut self) -> impl Future<Output = Result<(), ClientError>> + Send;
+
+    fn stop_protocol(
+        &mut self,
+        options: StopOptions,
+    ) -> impl Future<Output = Result<(), ClientError>> + Send;
+
+    /// Replaces the run-until criteria that stop acquisition `run_id`.
+    fn set_run_until_targets(
+        &mut self,
+        run_id: &str,
+        targets: &RunUntilCriteria,
+    ) -> impl Future<Output = Result<(), ClientError>> + Send;
+}
+
+impl MinknowApi for Client {
+    type Position = PositionClient;
+
+    fn endpoint(&self) -> &str {
+        Client::endpoint(self)
+    }
+
+    async fn list_positions(&mut self) -> Result<Vec<Position>, ClientError> {
+        Client::list_positions(self).await
+    }
+
+    async fn list_devices(&mut self) -> Result<Vec<Device>, ClientError> {
+        Client::list_devices(self).await
+    }
+
+    async fn connect_position(&self, position: Position) -> Result<PositionClient, ClientError> {
+        Client::connect_position(self, position).await
+    }
+}
+
+impl PositionApi for PositionClient {
+    fn position(&self) -> &Position {
+        &self.position
+    }
+
+    async fn get_run_state(&mut self) -> Result<RunState, ClientError> {
+        PositionClient::get_run_state(self).await
+    }
+
+    async fn get_run_info(&mut self) -> Result<Option<RunInfo>, ClientError> {
+        PositionClient::get_run_info(self).await
+    }
+
+    async fn get_acquisition_info(&mut self) -> Result<AcquisitionInfo, ClientError> {
+        PositionClient::get_acquisition_info(self).await
+    }
+
+    async fn get_current_run_id(&mut self) -> Result<Option<String>, ClientError> {
+        PositionClient::get_current_run_id(self).await
+    }
+
+    async fn get_flow_cell_info(&mut self) -> Result<FlowCellInfo, ClientError> {
+        PositionClient::get_flow_cell_info(self).await
+    }
+
+    async fn get_disk_space_info(&mut self) -> Result<Vec<DiskSpaceInfo>, ClientError> {
+        PositionClient::get_disk_space_info(self).await
+    }
+
+    async fn get_stats(&mut self) -> Result<StatsSnapshot, ClientError> {
+        PositionClient::get_stats(self).await
+    }
+
+    async fn get_output_info(&mut self) -> Result<Option<OutputInfo>, ClientError> {
+        PositionClient::get_output_info(self).await
+    }
+
+    async fn list_run_history(&mut self) -> Result<Vec<RunSummary>, ClientError> {
+        PositionClient::list_run_history(self).await
+    }
+
+    async fn list_protocols(&mut self) -> Result<Vec<ProtocolInfo>, ClientError> {
+        PositionClient::list_protocols(self).await
+    }
+
+    async fn start_protocol(
+        &mut self,
+        identifier: &str,
+        sample_id: &str,
+        experiment_group: &str,
+    ) -> Result<String, ClientError> {
+        PositionClient::start_protocol(self, identifier, sample_id, experiment_group).await
+    }
+
+    async fn pause(&mut self) -> Result<(), ClientError> {
+        PositionClient::pause(self).await
+    }
+
+    async fn resume(&mut self) -> Result<(), ClientError> {
+        PositionClient::resume(self).await
+    }
+
+    async fn stop_protocol(&mut self, options: StopOptions) -> Result<(), ClientError> {
+        PositionClient::stop_protocol(self, options).await
+    }
+
+    async fn set_run_until_targets(
+        &mut self,
+        run_id: &str,
+        targets: &RunUntilCriteria,
+    ) -> Result<(), ClientError> {
+        PositionClient::set_run_until_targets(self, run_id, targets).await
+    }
+}
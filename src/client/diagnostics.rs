@@ -0,0 +1,565 @@
+//! Connection diagnostics and troubleshooting hints.
+//!
+//! Classifies connection failures into broad categories and runs a series of
+//! step-by-step checks against the configured MinKNOW endpoint, used by the
+//! TUI's diagnostics overlay and `termion doctor`.
+
+use super::{
+    is_localhost, load_ca_cert, Client, ClientError, ConnectOptions, VersionInfo,
+    MINKNOW_TRUSTED_CA_ENV,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Broad category of a connection failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorClass {
+    /// Certificate missing, unreadable, or rejected during the TLS handshake.
+    Tls,
+    /// Nothing is listening on the endpoint.
+    Refused,
+    /// The endpoint did not respond in time.
+    Timeout,
+    /// The auth token could not be read or was rejected.
+    Auth,
+    /// The configuration asks for something Termion cannot do (e.g., remote hosts).
+    Unsupported,
+    /// Anything else.
+    Other,
+}
+
+impl ErrorClass {
+    /// Classifies a client error by inspecting its variant and source chain.
+    pub fn classify(err: &ClientError) -> Self {
+        match err {
+            ClientError::Auth { .. } => return ErrorClass::Auth,
+            ClientError::Timeout { .. } => return ErrorClass::Timeout,
+            ClientError::Grpc { status, .. } => {
+                use tonic::Code;
+                return match status.code() {
+                    Code::Unauthenticated | Code::PermissionDenied => ErrorClass::Auth,
+                    Code::DeadlineExceeded => ErrorClass::Timeout,
+                    Code::Unavailable => ErrorClass::Refused,
+                    _ => ErrorClass::Other,
+                };
+            }
+            _ => {}
+        }
+
+        let mut chain = String::new();
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+        while let Some(e) = source {
+            if let Some(io) = e.downcast_ref::<std::io::Error>() {
+                match io.kind() {
+                    std::io::ErrorKind::ConnectionRefused => return ErrorClass::Refused,
+                    std::io::ErrorKind::TimedOut => return ErrorClass::Timeout,
+                    _ => {}
+                }
+            }
+            chain.push_str(&e.to_string().to_lowercase());
+            chain.push('\n');
+            source = e.source();
+        }
+
+        if chain.contains("remote hosts are not supported") {
+            ErrorClass::Unsupported
+        } else if chain.contains("certificate") || chain.contains("tls") {
+            ErrorClass::Tls
+        } else if chain.contains("refused") {
+            ErrorClass::Refused
+        } else if chain.contains("timed out") || chain.contains("timeout") {
+            ErrorClass::Timeout
+        } else {
+            ErrorClass::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ErrorClass::Tls => "TLS",
+            ErrorClass::Refused => "Connection refused",
+            ErrorClass::Timeout => "Timeout",
+            ErrorClass::Auth => "Authentication",
+            ErrorClass::Unsupported => "Unsupported host",
+            ErrorClass::Other => "Connection error",
+        }
+    }
+
+    /// Returns concrete next steps for resolving this class of failure.
+    pub fn troubleshooting_steps(&self, host: &str, port: u16) -> Vec<String> {
+        match self {
+            ErrorClass::Tls => vec![
+                "Check that MinKNOW is installed and its CA certificate exists".to_string(),
+                format!(
+                    "Set {} to the path of MinKNOW's ca.crt if it is in a custom location",
+                    MINKNOW_TRUSTED_CA_ENV
+                ),
+                "Make sure the certificate file is readable by the current user".to_string(),
+            ],
+            ErrorClass::Refused => vec![
+                "Check that the MinKNOW service is running (e.g., systemctl status minknow)"
+                    .to_string(),
+                format!("Verify MinKNOW's manager is listening on port {}", port),
+                "Check --port / TERMION_PORT if MinKNOW uses a non-default port".to_string(),
+            ],
+            ErrorClass::Timeout => vec![
+                format!(
+                    "Check that {}:{} is reachable from this machine",
+                    host, port
+                ),
+                "MinKNOW may be starting up; wait a moment and press R".to_string(),
+                "Increase connect_timeout in the [connection] config section".to_string(),
+            ],
+            ErrorClass::Auth => vec![
+                "Check that the local auth token file is readable by the current user".to_string(),
+                "Enable guest mode in MinKNOW if running as a different user".to_string(),
+            ],
+            ErrorClass::Unsupported => vec![
+                "Termion only supports connecting to MinKNOW on localhost".to_string(),
+                "Run Termion on the sequencing machine, or tunnel the port over SSH".to_string(),
+            ],
+            ErrorClass::Other => vec![
+                "Check that the MinKNOW service is running".to_string(),
+                "Run with -v and --log to capture details".to_string(),
+            ],
+        }
+    }
+}
+
+/// Oldest MinKNOW release, as (major, minor), that Termion supports.
+pub const MIN_MINKNOW_VERSION: (i32, i32) = (6, 0);
+
+/// Newest MinKNOW major release the bundled API definitions come from.
+/// Later releases usually work, but have not been tested.
+pub const LATEST_MINKNOW_MAJOR: i32 = 6;
+
+/// How far MinKNOW's timestamps may run ahead of the local clock before the
+/// clocks are reported as out of sync.
+const CLOCK_SKEW_TOLERANCE: Duration = Duration::from_secs(60);
+
+/// Checks that only run once the manager is reachable.
+const MANAGER_CHECKS: [&str; 5] = [
+    "MinKNOW manager",
+    "Auth token",
+    "MinKNOW version",
+    "Positions",
+    "Clock",
+];
+
+/// Outcome of a single diagnostic check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but something looks likely to cause trouble.
+    Warn,
+    Fail,
+    Skipped,
+}
+
+/// Result of a single diagnostic check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Steps that may fix a failure or warning.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<String>,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hints: Vec::new(),
+        }
+    }
+
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Pass, detail)
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Warn, detail)
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Fail, detail)
+    }
+
+    fn skipped(name: impl Into<String>) -> Self {
+        Self::new(name, CheckStatus::Skipped, "skipped due to earlier failure")
+    }
+
+    fn with_hints(mut self, hints: Vec<String>) -> Self {
+        self.hints = hints;
+        self
+    }
+}
+
+/// Runs connection checks against a MinKNOW endpoint, in dependency order.
+///
+/// Each check only runs if the checks it depends on passed. Once the
+/// manager is reachable, every position's port is probed as well.
+pub async fn run_checks(host: &str, port: u16, options: &ConnectOptions) -> Vec<CheckResult> {
+    let connect_timeout = options.connect_timeout;
+    let mut results = Vec::new();
+    let endpoint = format!("https://{}:{}", host, port);
+
+    let host_ok = is_localhost(host);
+    results.push(if host_ok {
+        CheckResult::pass("Host", format!("{} is local", host))
+    } else {
+        CheckResult::fail("Host", "remote hosts are not supported; use localhost")
+            .with_hints(ErrorClass::Unsupported.troubleshooting_steps(host, port))
+    });
+
+    let ca_ok = match load_ca_cert(&endpoint, options.ca_cert.as_deref()).await {
+        Ok(_) => {
+            let source = match &options.ca_cert {
+                Some(path) => format!("loaded (from {})", path.display()),
+                None => std::env::var(MINKNOW_TRUSTED_CA_ENV)
+                    .map(|path| format!("loaded (from {})", path))
+                    .unwrap_or_else(|_| "loaded from default location".to_string()),
+            };
+            results.push(CheckResult::pass("CA certificate", source));
+            true
+        }
+        Err(e) => {
+            results.push(
+                CheckResult::fail("CA certificate", error_detail(&e))
+                    .with_hints(ErrorClass::Tls.troubleshooting_steps(host, port)),
+            );
+            false
+        }
+    };
+
+    let tcp_ok = if host_ok {
+        let result = check_port("Port", host, port, connect_timeout).await;
+        let ok = result.status == CheckStatus::Pass;
+        results.push(result);
+        ok
+    } else {
+        results.push(CheckResult::skipped("Port"));
+        false
+    };
+
+    if !(ca_ok && tcp_ok) {
+        results.extend(MANAGER_CHECKS.map(CheckResult::skipped));
+        return results;
+    }
+
+    let mut client = match Client::connect_with_options(host, port, options.clone()).await {
+        Ok(client) => {
+            results.push(CheckResult::pass("MinKNOW manager", "connected"));
+            results.push(CheckResult::pass(
+                "Auth token",
+                if client.has_auth_token() {
+                    "loaded"
+                } else {
+                    "none required (guest mode)"
+                },
+            ));
+            client
+        }
+        Err(e) => {
+            let class = ErrorClass::classify(&e);
+            if class == ErrorClass::Auth {
+                // The token path comes from the manager, so it answered.
+                results.push(CheckResult::pass("MinKNOW manager", "reachable"));
+                results.push(
+                    CheckResult::fail("Auth token", error_detail(&e))
+                        .with_hints(class.troubleshooting_steps(host, port)),
+                );
+            } else {
+                results.push(
+                    CheckResult::fail(
+                        "MinKNOW manager",
+                        format!("{}: {}", class.label(), error_detail(&e)),
+                    )
+                    .with_hints(class.troubleshooting_steps(host, port)),
+                );
+                results.push(CheckResult::skipped("Auth token"));
+            }
+            results.extend(
+                MANAGER_CHECKS[2..]
+                    .iter()
+                    .map(|&name| CheckResult::skipped(name)),
+            );
+            return results;
+        }
+    };
+
+    results.push(match client.get_version_info().await {
+        Ok(version) => check_version(&version),
+        Err(e) => CheckResult::fail("MinKNOW version", e.display_message()),
+    });
+
+    let positions = match client.list_positions().await {
+        Ok(positions) => {
+            results.push(CheckResult::pass(
+                "Positions",
+                format!("{} found", positions.len()),
+            ));
+            positions
+        }
+        Err(e) => {
+            results.push(CheckResult::fail("Positions", e.display_message()));
+            results.push(CheckResult::skipped("Clock"));
+            return results;
+        }
+    };
+
+    // MinKNOW stamps runs with its own clock, so the latest timestamp any
+    // position reports shows whether that clock is ahead of ours.
+    let mut latest: Option<(String, DateTime<Utc>)> = None;
+    for position in positions {
+        let name = format!("Position {}", position.name);
+        if position.grpc_port == 0 {
+            results.push(CheckResult::new(
+                name,
+                CheckStatus::Skipped,
+                "not running, so it has no port",
+            ));
+            continue;
+        }
+        let result = check_port(&name, host, position.grpc_port, connect_timeout).await;
+        if result.status != CheckStatus::Pass {
+            results.push(result);
+            continue;
+        }
+        match client.connect_position(position.clone()).await {
+            Ok(mut position_client) => {
+                results.push(result);
+                if let Ok(Some(info)) = position_client.get_run_info().await {
+                    let stamp = info.end_time.into_iter().chain(info.start_time).max();
+                    if let Some(stamp) = stamp.filter(|s| latest.as_ref().is_none_or(|l| *s > l.1))
+                    {
+                        latest = Some((position.name.clone(), stamp));
+                    }
+                }
+            }
+            Err(e) => {
+                let class = ErrorClass::classify(&e);
+                results.push(
+                    CheckResult::fail(name, format!("{}: {}", class.label(), error_detail(&e)))
+                        .with_hints(class.troubleshooting_steps(host, position.grpc_port)),
+                );
+            }
+        }
+    }
+
+    results.push(match latest {
+        Some((position, stamp)) => check_clock(&position, stamp, Utc::now()),
+        None => CheckResult::new(
+            "Clock",
+            CheckStatus::Skipped,
+            "no run timestamps to compare against",
+        ),
+    });
+
+    results
+}
+
+/// Checks that something is listening on `host:port`.
+async fn check_port(name: &str, host: &str, port: u16, timeout: Duration) -> CheckResult {
+    match tokio::time::timeout(timeout, tokio::net::TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => CheckResult::pass(name, format!("{} is open", port)),
+        Ok(Err(e)) => CheckResult::fail(name, e.to_string())
+            .with_hints(ErrorClass::Refused.troubleshooting_steps(host, port)),
+        Err(_) => CheckResult::fail(name, format!("no response within {}s", timeout.as_secs()))
+            .with_hints(ErrorClass::Timeout.troubleshooting_steps(host, port)),
+    }
+}
+
+/// Checks that the MinKNOW release is one Termion supports.
+fn check_version(version: &VersionInfo) -> CheckResult {
+    let (min_major, min_minor) = MIN_MINKNOW_VERSION;
+    let detail = version.label();
+
+    if (version.major, version.minor) < MIN_MINKNOW_VERSION {
+        CheckResult::fail(
+            "MinKNOW version",
+            format!("{} is older than {}.{}", detail, min_major, min_minor),
+        )
+        .with_hints(vec![format!(
+            "Upgrade MinKNOW to {}.{} or later",
+            min_major, min_minor
+        )])
+    } else if version.major > LATEST_MINKNOW_MAJOR {
+        CheckResult::warn(
+            "MinKNOW version",
+            format!("{} is newer than Termion was tested with", detail),
+        )
+        .with_hints(vec![
+            "Check for a newer Termion release if values are missing or wrong".to_string(),
+        ])
+    } else {
+        CheckResult::pass("MinKNOW version", detail)
+    }
+}
+
+/// Why Termion may not work with `version`, if it is outside the range the
+/// bundled API definitions cover.
+pub fn version_warning(version: &VersionInfo) -> Option<String> {
+    let (min_major, min_minor) = MIN_MINKNOW_VERSION;
+    if (version.major, version.minor) < MIN_MINKNOW_VERSION {
+        Some(format!(
+            "MinKNOW {} is older than {}.{}, the oldest release Termion supports",
+            version.full, min_major, min_minor
+        ))
+    } else if version.major > LATEST_MINKNOW_MAJOR {
+        Some(format!(
+            "MinKNOW {} is newer than Termion was tested with; some values may be missing",
+            version.full
+        ))
+    } else {
+        None
+    }
+}
+
+/// Compares the latest timestamp MinKNOW reported, from `position`, against
+/// the local clock.
+fn check_clock(position: &str, stamp: DateTime<Utc>, now: DateTime<Utc>) -> CheckResult {
+    let ahead = (stamp - now).to_std().unwrap_or_default();
+    if ahead <= CLOCK_SKEW_TOLERANCE {
+        return CheckResult::pass(
+            "Clock",
+            "MinKNOW's timestamps are not ahead of this machine",
+        );
+    }
+    CheckResult::fail(
+        "Clock",
+        format!(
+            "{} reports a time {}s ahead of this machine",
+            position,
+            ahead.as_secs()
+        ),
+    )
+    .with_hints(vec![
+        "Synchronise both clocks, e.g. with NTP (timedatectl set-ntp true)".to_string(),
+        "Run durations and ETAs are off until the clocks agree".to_string(),
+    ])
+}
+
+/// Returns the most specific message available for an error.
+fn error_detail(err: &ClientError) -> String {
+    match err {
+        ClientError::Connection { source, .. } => source.to_string(),
+        other => other.display_message(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connection_error(source: Box<dyn std::error::Error + Send + Sync>) -> ClientError {
+        ClientError::Connection {
+            endpoint: "https://localhost:9501".into(),
+            source,
+        }
+    }
+
+    #[test]
+    fn test_classify_refused() {
+        let io = std::io::Error::new(std::io::ErrorKind::ConnectionRefused, "refused");
+        assert_eq!(
+            ErrorClass::classify(&connection_error(Box::new(io))),
+            ErrorClass::Refused
+        );
+    }
+
+    #[test]
+    fn test_classify_missing_certificate() {
+        let err = connection_error("MinKNOW CA certificate not found".into());
+        assert_eq!(ErrorClass::classify(&err), ErrorClass::Tls);
+    }
+
+    #[test]
+    fn test_classify_remote_host() {
+        let err = connection_error("Remote hosts are not supported; use localhost".into());
+        assert_eq!(ErrorClass::classify(&err), ErrorClass::Unsupported);
+    }
+
+    #[test]
+    fn test_classify_auth_and_timeout() {
+        let auth = ClientError::Auth {
+            message: "bad token".into(),
+        };
+        assert_eq!(ErrorClass::classify(&auth), ErrorClass::Auth);
+
+        let grpc = ClientError::Grpc {
+            method: "list".into(),
+            status: tonic::Status::deadline_exceeded("slow"),
+        };
+        assert_eq!(ErrorClass::classify(&grpc), ErrorClass::Timeout);
+    }
+
+    #[test]
+    fn test_tls_steps_mention_env_var() {
+        let steps = ErrorClass::Tls.troubleshooting_steps("localhost", 9501);
+        assert!(steps.iter().any(|s| s.contains(MINKNOW_TRUSTED_CA_ENV)));
+    }
+
+    #[test]
+    fn test_check_version_range() {
+        let version = |major, minor| VersionInfo {
+            major,
+            minor,
+            full: format!("{}.{}.0", major, minor),
+            distribution: "unknown".into(),
+            ..Default::default()
+        };
+        let supported = check_version(&version(6, 2));
+        assert_eq!(supported.status, CheckStatus::Pass);
+        assert_eq!(supported.detail, "6.2.0");
+
+        let old = check_version(&version(5, 9));
+        assert_eq!(old.status, CheckStatus::Fail);
+        assert!(old.hints[0].contains("6.0"));
+
+        assert_eq!(check_version(&version(7, 0)).status, CheckStatus::Warn);
+
+        assert_eq!(version_warning(&version(6, 2)), None);
+        assert!(version_warning(&version(5, 9))
+            .unwrap()
+            .contains("older than 6.0"));
+        assert!(version_warning(&version(7, 0)).unwrap().contains("newer"));
+    }
+
+    #[test]
+    fn test_check_clock_flags_minknow_ahead() {
+        let now = Utc::now();
+        let ok = check_clock("X1", now + chrono::Duration::seconds(30), now);
+        assert_eq!(ok.status, CheckStatus::Pass);
+        assert_eq!(
+            check_clock("X1", now - chrono::Duration::days(2), now).status,
+            CheckStatus::Pass
+        );
+
+        let ahead = check_clock("X1", now + chrono::Duration::minutes(10), now);
+        assert_eq!(ahead.status, CheckStatus::Fail);
+        assert!(ahead.detail.contains("600s"));
+        assert!(!ahead.hints.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_checks_remote_host_skips_network() {
+        let options = ConnectOptions {
+            connect_timeout: Duration::from_millis(100),
+            request_timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let results = run_checks("example.com", 9501, &options).await;
+        assert_eq!(results[0].status, CheckStatus::Fail);
+        assert!(results
+            .iter()
+            .filter(|r| r.name == "Port" || r.name == "MinKNOW manager")
+            .all(|r| r.status == CheckStatus::Skipped));
+    }
+}
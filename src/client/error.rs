@@ -57,6 +57,20 @@ impl ClientError {
         }
     }
 
+    /// Returns true if MinKNOW does not offer the RPC, e.g. because it is
+    /// older than the RPC.
+    pub fn is_unimplemented(&self) -> bool {
+        matches!(self, ClientError::Grpc { status, .. } if status.code() == tonic::Code::Unimplemented)
+    }
+
+    /// Returns true if MinKNOW refused the request for lack of permission,
+    /// as it does for control requests from guests without a local
+    /// authentication token.
+    pub fn is_permission_denied(&self) -> bool {
+        matches!(self, ClientError::Grpc { status, .. }
+            if matches!(status.code(), tonic::Code::PermissionDenied | tonic::Code::Unauthenticated))
+    }
+
     /// Returns a human-readable error message suitable for display.
     pub fn display_message(&self) -> String {
         match self {
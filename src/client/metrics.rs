@@ -0,0 +1,163 @@
+//! Latency and error counts of the unary gRPC calls made to MinKNOW.
+//!
+//! Every client in the process records into one table keyed by RPC method,
+//! so the TUI's debug overlay can show which calls are slow without the
+//! clients being threaded through to it. Latencies are kept in fixed
+//! buckets, which bounds memory however long the process runs; percentiles
+//! are reported as the upper bound of the bucket they fall in.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds of the latency buckets in milliseconds. Slower calls fall
+/// into a final, unbounded bucket.
+const BUCKET_BOUNDS_MS: [u64; 12] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000];
+
+static METHODS: Mutex<BTreeMap<String, Histogram>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+    errors: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl Histogram {
+    fn record(&mut self, elapsed: Duration, ok: bool) {
+        let ms = elapsed.as_millis();
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| ms <= bound as u128)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+        self.errors += u64::from(!ok);
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    fn calls(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Upper bound of the bucket holding the `quantile` of calls, or the
+    /// slowest call for the unbounded bucket.
+    fn quantile(&self, quantile: f64) -> Duration {
+        let rank = (self.calls() as f64 * quantile).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return match BUCKET_BOUNDS_MS.get(i) {
+                    Some(&bound) => Duration::from_millis(bound).min(self.max),
+                    None => self.max,
+                };
+            }
+        }
+        self.max
+    }
+}
+
+/// Summary of the calls made to one RPC method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcStats {
+    pub method: String,
+    pub calls: u64,
+    /// Calls that ended in a gRPC error, including expected ones such as
+    /// `FailedPrecondition` on an idle position.
+    pub errors: u64,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+/// Records one call to `method`.
+fn record(method: &str, elapsed: Duration, ok: bool) {
+    let mut methods = METHODS.lock().unwrap_or_else(|e| e.into_inner());
+    match methods.get_mut(method) {
+        Some(histogram) => histogram.record(elapsed, ok),
+        None => {
+            let mut histogram = Histogram::default();
+            histogram.record(elapsed, ok);
+            methods.insert(method.to_string(), histogram);
+        }
+    }
+}
+
+/// Awaits a gRPC call, recording how long it took and whether it failed.
+pub(crate) async fn timed<T>(
+    method: &str,
+    call: impl Future<Output = Result<T, tonic::Status>>,
+) -> Result<T, tonic::Status> {
+    let start = Instant::now();
+    let result = call.await;
+    record(method, start.elapsed(), result.is_ok());
+    result
+}
+
+/// Returns the calls recorded so far, slowest 95th percentile first.
+pub fn snapshot() -> Vec<RpcStats> {
+    let methods = METHODS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut stats: Vec<RpcStats> = methods
+        .iter()
+        .map(|(method, histogram)| {
+            let calls = histogram.calls();
+            RpcStats {
+                method: method.clone(),
+                calls,
+                errors: histogram.errors,
+                mean: histogram.total / calls.max(1) as u32,
+                p50: histogram.quantile(0.5),
+                p95: histogram.quantile(0.95),
+                max: histogram.max,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.p95.cmp(&a.p95).then_with(|| a.method.cmp(&b.method)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_quantiles() {
+        let mut histogram = Histogram::default();
+        for _ in 0..90 {
+            histogram.record(Duration::from_millis(3), true);
+        }
+        for _ in 0..9 {
+            histogram.record(Duration::from_millis(150), true);
+        }
+        histogram.record(Duration::from_secs(7), false);
+
+        assert_eq!(histogram.calls(), 100);
+        assert_eq!(histogram.errors, 1);
+        assert_eq!(histogram.quantile(0.5), Duration::from_millis(5));
+        assert_eq!(histogram.quantile(0.95), Duration::from_millis(200));
+        assert_eq!(histogram.quantile(1.0), Duration::from_secs(7));
+
+        // A bucket's bound never exceeds the slowest call seen.
+        let mut fast = Histogram::default();
+        fast.record(Duration::from_micros(300), true);
+        assert_eq!(fast.quantile(0.5), Duration::from_micros(300));
+    }
+
+    #[tokio::test]
+    async fn test_timed_records_calls_and_errors() {
+        let method = "test_timed_records_calls_and_errors";
+        let _ = timed(method, async { Ok::<_, tonic::Status>(()) }).await;
+        let _ = timed(method, async {
+            Err::<(), _>(tonic::Status::unavailable("down"))
+        })
+        .await;
+
+        let stats = snapshot().into_iter().find(|s| s.method == method).unwrap();
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.errors, 1);
+    }
+}
@@ -0,0 +1,563 @@
+//! Scripted in-memory MinKNOW for tests.
+//!
+//! A [`MockClient`] implements [`MinknowApi`] over [`ScriptedPosition`]s set
+//! up in advance. Run control and protocol starts change the scripted state
+//! the way MinKNOW would, every call is recorded, and failures can be
+//! injected per method or for the whole connection, so code written against
+//! the traits can be tested without a server.
+
+// Scripted calls return `ClientError` just as the real client does.
+#![allow(clippy::result_large_err)]
+
+use super::api::{MinknowApi, PositionApi};
+use super::{
+    AcquisitionInfo, ClientError, Device, DeviceState, DeviceType, DiskSpaceInfo, FlowCellInfo,
+    OutputInfo, Position, PositionState, ProtocolInfo, RunInfo, RunState, RunSummary,
+    RunUntilCriteria, StatsSnapshot, StopOptions,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// State of one position as the mock reports it.
+#[derive(Debug, Clone)]
+pub struct ScriptedPosition {
+    pub position: Position,
+    pub flow_cell: FlowCellInfo,
+    pub run_state: RunState,
+    pub run_info: Option<RunInfo>,
+    pub stats: StatsSnapshot,
+    pub disk_space: Vec<DiskSpaceInfo>,
+    pub run_history: Vec<RunSummary>,
+    pub protocols: Vec<ProtocolInfo>,
+    /// Run-until criteria that stop the current run.
+    pub run_until: RunUntilCriteria,
+    pub output: Option<OutputInfo>,
+    /// Options of the last stop request.
+    pub stopped_with: Option<StopOptions>,
+}
+
+impl ScriptedPosition {
+    /// An idle position with no flow cell inserted.
+    pub fn new(name: &str, device: &str) -> Self {
+        Self {
+            position: Position {
+                id: name.to_string(),
+                name: name.to_string(),
+                device_id: device.to_string(),
+                state: PositionState::Running,
+                grpc_port: 8000,
+                is_simulated: true,
+                device_type: DeviceType::MinION,
+                host: None,
+            },
+            flow_cell: FlowCellInfo::default(),
+            run_state: RunState::Idle,
+            run_info: None,
+            stats: StatsSnapshot::default(),
+            disk_space: vec![DiskSpaceInfo {
+                filesystem_id: "/data".to_string(),
+                bytes_available: 500_000_000_000,
+                bytes_capacity: 1_000_000_000_000,
+                ..Default::default()
+            }],
+            run_history: Vec::new(),
+            protocols: Vec::new(),
+            run_until: RunUntilCriteria::default(),
+            output: None,
+            stopped_with: None,
+        }
+    }
+
+    pub fn with_flow_cell(mut self, flow_cell_id: &str) -> Self {
+        self.flow_cell = FlowCellInfo {
+            has_flow_cell: true,
+            flow_cell_id: Some(flow_cell_id.to_string()),
+            product_code: Some("FLO-MIN114".to_string()),
+            has_adapter: false,
+            channel_count: 512,
+        };
+        self
+    }
+
+    /// A running acquisition with 10,000 reads.
+    pub fn with_run(mut self, run_id: &str) -> Self {
+        self.run_state = RunState::Running;
+        self.run_info = Some(RunInfo {
+            run_id: Some(run_id.to_string()),
+            sample_id: Some("sample-1".to_string()),
+            experiment_group: Some("experiment-1".to_string()),
+            protocol_name: Some("Ligation Sequencing Kit V14".to_string()),
+            flow_cell_product_code: self.flow_cell.product_code.clone(),
+            ..Default::default()
+        });
+        self.stats = StatsSnapshot {
+            reads_processed: 10_000,
+            bases_called: 100_000_000,
+            reads_passed: 9_000,
+            reads_failed: 1_000,
+            bases_passed: 90_000_000,
+            bases_failed: 10_000_000,
+            ..Default::default()
+        };
+        self.output = Some(OutputInfo {
+            reads_directory: Some(format!("/data/experiment-1/sample-1/{}", run_id)),
+            formats: vec!["pod5".to_string(), "fastq".to_string()],
+            bytes_written: 180_000_000,
+            bytes_pending: 20_000_000,
+            bytes_failed: 0,
+        });
+        self
+    }
+
+    /// Protocols for `FLO-MIN114` flow cells with the given identifiers and
+    /// kits.
+    pub fn with_protocols(mut self, protocols: &[(&str, &str)]) -> Self {
+        self.protocols = protocols
+            .iter()
+            .map(|&(identifier, kit)| ProtocolInfo {
+                identifier: identifier.to_string(),
+                name: identifier.to_string(),
+                experiment_type: Some("sequencing".to_string()),
+                kit: Some(kit.to_string()),
+                flow_cell: Some("FLO-MIN114".to_string()),
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_run_history(mut self, runs: Vec<RunSummary>) -> Self {
+        self.run_history = runs;
+        self
+    }
+}
+
+/// A call made through a [`MockClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Position the call was made on, or `None` for manager calls.
+    pub position: Option<String>,
+    pub method: &'static str,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    positions: Vec<ScriptedPosition>,
+    failures: HashMap<&'static str, VecDeque<ClientError>>,
+    disconnected: bool,
+    calls: Vec<MockCall>,
+    runs_started: u32,
+}
+
+/// In-memory [`MinknowApi`] over scripted positions. Clones share state.
+#[derive(Debug, Clone, Default)]
+pub struct MockClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_position(self, position: ScriptedPosition) -> Self {
+        self.state().positions.push(position);
+        self
+    }
+
+    /// Makes the next call to `method` fail with `error`. Queued failures
+    /// are used up in order.
+    pub fn fail(&self, method: &'static str, error: ClientError) {
+        self.state()
+            .failures
+            .entry(method)
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Fails every call with [`ClientError::Disconnected`] until
+    /// [`reconnect`](Self::reconnect) is called.
+    pub fn disconnect(&self) {
+        self.state().disconnected = true;
+    }
+
+    pub fn reconnect(&self) {
+        self.state().disconnected = false;
+    }
+
+    /// Current scripted state of the position named `name`.
+    pub fn position(&self, name: &str) -> Option<ScriptedPosition> {
+        self.state()
+            .positions
+            .iter()
+            .find(|p| p.position.name == name)
+            .cloned()
+    }
+
+    /// Changes the scripted state of a position, e.g. to end its run.
+    pub fn update_position(&self, name: &str, update: impl FnOnce(&mut ScriptedPosition)) {
+        if let Some(position) = self
+            .state()
+            .positions
+            .iter_mut()
+            .find(|p| p.position.name == name)
+        {
+            update(position);
+        }
+    }
+
+    /// Every call made so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records a call and returns the error it should fail with, if any.
+    fn begin(
+        state: &mut MockState,
+        position: Option<&str>,
+        method: &'static str,
+    ) -> Result<(), ClientError> {
+        state.calls.push(MockCall {
+            position: position.map(str::to_string),
+            method,
+        });
+        if state.disconnected {
+            return Err(ClientError::Disconnected);
+        }
+        match state.failures.get_mut(method).and_then(VecDeque::pop_front) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs a position call against the scripted state of `name`.
+    fn with_scripted<T>(
+        &self,
+        name: &str,
+        method: &'static str,
+        f: impl FnOnce(&mut ScriptedPosition, &mut u32) -> Result<T, ClientError>,
+    ) -> Result<T, ClientError> {
+        let mut state = self.state();
+        Self::begin(&mut state, Some(name), method)?;
+        let MockState {
+            positions,
+            runs_started,
+            ..
+        } = &mut *state;
+        let position = positions
+            .iter_mut()
+            .find(|p| p.position.name == name)
+            .ok_or_else(|| not_found("Position", name))?;
+        f(position, runs_started)
+    }
+}
+
+fn not_found(resource: &str, id: &str) -> ClientError {
+    ClientError::NotFound {
+        resource: resource.to_string(),
+        id: id.to_string(),
+    }
+}
+
+fn rejected(method: &str, message: &str) -> ClientError {
+    ClientError::Grpc {
+        method: method.to_string(),
+        status: tonic::Status::failed_precondition(message),
+    }
+}
+
+impl MinknowApi for MockClient {
+    type Position = MockPositionClient;
+
+    fn endpoint(&self) -> &str {
+        "mock"
+    }
+
+    async fn list_positions(&mut self) -> Result<Vec<Position>, ClientError> {
+        let mut state = self.state();
+        Self::begin(&mut state, None, "list_positions")?;
+        Ok(state.positions.iter().map(|p| p.position.clone()).collect())
+    }
+
+    async fn list_devices(&mut self) -> Result<Vec<Device>, ClientError> {
+        let mut state = self.state();
+        Self::begin(&mut state, None, "list_devices")?;
+        let mut devices: Vec<Device> = Vec::new();
+        for p in &state.positions {
+            if !devices.iter().any(|d| d.id == p.position.device_id) {
+                devices.push(Device {
+                    id: p.position.device_id.clone(),
+                    name: p.position.device_id.clone(),
+                    state: DeviceState::default(),
+                });
+            }
+        }
+        Ok(devices)
+    }
+
+    async fn connect_position(
+        &self,
+        position: Position,
+    ) -> Result<MockPositionClient, ClientError> {
+        self.with_scripted(&position.name, "connect_position", |_, _| Ok(()))?;
+        Ok(MockPositionClient {
+            client: self.clone(),
+            position,
+        })
+    }
+}
+
+/// [`PositionApi`] for one position of a [`MockClient`].
+#[derive(Debug, Clone)]
+pub struct MockPositionClient {
+    client: MockClient,
+    position: Position,
+}
+
+impl MockPositionClient {
+    fn call<T>(
+        &self,
+        method: &'static str,
+        f: impl FnOnce(&mut ScriptedPosition, &mut u32) -> Result<T, ClientError>,
+    ) -> Result<T, ClientError> {
+        self.client.with_scripted(&self.position.name, method, f)
+    }
+}
+
+impl PositionApi for MockPositionClient {
+    fn position(&self) -> &Position {
+        &self.position
+    }
+
+    async fn get_run_state(&mut self) -> Result<RunState, ClientError> {
+        self.call("get_run_state", |p, _| Ok(p.run_state.clone()))
+    }
+
+    async fn get_run_info(&mut self) -> Result<Option<RunInfo>, ClientError> {
+        self.call("get_run_info", |p, _| Ok(p.run_info.clone()))
+    }
+
+    async fn get_acquisition_info(&mut self) -> Result<AcquisitionInfo, ClientError> {
+        self.call("get_acquisition_info", |p, _| {
+            Ok(AcquisitionInfo {
+                run_id: p
+                    .run_info
+                    .as_ref()
+                    .and_then(|i| i.run_id.clone())
+                    .unwrap_or_default(),
+                state: p.run_state.clone(),
+                start_time: p.stats.acquisition_start,
+                reads_processed: p.stats.reads_processed,
+                reads_passed: p.stats.reads_passed,
+                reads_failed: p.stats.reads_failed,
+                bases_passed: p.stats.bases_passed,
+                bases_failed: p.stats.bases_failed,
+            })
+        })
+    }
+
+    async fn get_current_run_id(&mut self) -> Result<Option<String>, ClientError> {
+        self.call("get_current_run_id", |p, _| {
+            Ok(p.run_info
+                .as_ref()
+                .filter(|_| p.run_state.is_active())
+                .and_then(|i| i.run_id.clone()))
+        })
+    }
+
+    async fn get_flow_cell_info(&mut self) -> Result<FlowCellInfo, ClientError> {
+        self.call("get_flow_cell_info", |p, _| Ok(p.flow_cell.clone()))
+    }
+
+    async fn get_disk_space_info(&mut self) -> Result<Vec<DiskSpaceInfo>, ClientError> {
+        self.call("get_disk_space_info", |p, _| Ok(p.disk_space.clone()))
+    }
+
+    async fn get_stats(&mut self) -> Result<StatsSnapshot, ClientError> {
+        self.call("get_stats", |p, _| Ok(p.stats.clone()))
+    }
+
+    async fn get_output_info(&mut self) -> Result<Option<OutputInfo>, ClientError> {
+        self.call("get_output_info", |p, _| Ok(p.output.clone()))
+    }
+
+    async fn list_run_history(&mut self) -> Result<Vec<RunSummary>, ClientError> {
+        self.call("list_run_history", |p, _| Ok(p.run_history.clone()))
+    }
+
+    async fn list_protocols(&mut self) -> Result<Vec<ProtocolInfo>, ClientError> {
+        self.call("list_protocols", |p, _| Ok(p.protocols.clone()))
+    }
+
+    async fn start_protocol(
+        &mut self,
+        identifier: &str,
+        sample_id: &str,
+        experiment_group: &str,
+    ) -> Result<String, ClientError> {
+        self.call("start_protocol", |p, runs_started| {
+            let Some(protocol) = p.protocols.iter().find(|pr| pr.identifier == identifier) else {
+                return Err(not_found("Protocol", identifier));
+            };
+            let protocol_name = Some(protocol.name.clone());
+            if p.run_state.is_active() {
+                return Err(rejected("start_protocol", "a protocol is already running"));
+            }
+            *runs_started += 1;
+            let run_id = format!("mock-run-{}", runs_started);
+            let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+            p.run_state = RunState::Starting;
+            p.stats = StatsSnapshot::default();
+            p.run_info = Some(RunInfo {
+                run_id: Some(run_id.clone()),
+                sample_id: non_empty(sample_id),
+                experiment_group: non_empty(experiment_group),
+                protocol_name,
+                flow_cell_product_code: p.flow_cell.product_code.clone(),
+                ..Default::default()
+            });
+            Ok(run_id)
+        })
+    }
+
+    async fn pause(&mut self) -> Result<(), ClientError> {
+        self.call("pause", |p, _| match p.run_state {
+            RunState::Running | RunState::MuxScanning => {
+                p.run_state = RunState::Paused;
+                Ok(())
+            }
+            _ => Err(rejected("pause", "acquisition is not running")),
+        })
+    }
+
+    async fn resume(&mut self) -> Result<(), ClientError> {
+        self.call("resume", |p, _| match p.run_state {
+            RunState::Paused => {
+                p.run_state = RunState::Running;
+                Ok(())
+            }
+            _ => Err(rejected("resume", "acquisition is not paused")),
+        })
+    }
+
+    async fn stop_protocol(&mut self, options: StopOptions) -> Result<(), ClientError> {
+        self.call("stop_protocol", |p, _| {
+            if !p.run_state.is_active() {
+                return Err(rejected("stop_protocol", "no protocol is running"));
+            }
+            p.stopped_with = Some(options);
+            p.run_state = RunState::Idle;
+            p.output = None;
+            if let Some(info) = p.run_info.take() {
+                p.run_history.insert(
+                    0,
+                    RunSummary {
+                        info,
+                        end_reason: "Stopped by user".to_string(),
+                        reads: p.stats.reads_processed,
+                        bases_passed: p.stats.bases_passed,
+                        bases_failed: p.stats.bases_failed,
+                        ..Default::default()
+                    },
+                );
+            }
+            Ok(())
+        })
+    }
+
+    async fn set_run_until_targets(
+        &mut self,
+        run_id: &str,
+        targets: &RunUntilCriteria,
+    ) -> Result<(), ClientError> {
+        self.call("set_run_until_targets", |p, _| {
+            let current = p
+                .run_info
+                .as_ref()
+                .filter(|_| p.run_state.is_active())
+                .and_then(|i| i.run_id.as_deref());
+            if current != Some(run_id) {
+                return Err(not_found("Acquisition run", run_id));
+            }
+            p.run_until = *targets;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::StopDataAction;
+
+    fn client() -> MockClient {
+        MockClient::new().with_position(
+            ScriptedPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run("run-1"),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_control_changes_scripted_state() {
+        let client = client();
+        let position = client.position("X1").unwrap().position;
+        let mut pos_client = client.connect_position(position).await.unwrap();
+
+        pos_client.pause().await.unwrap();
+        assert_eq!(pos_client.get_run_state().await.unwrap(), RunState::Paused);
+        assert!(pos_client.pause().await.is_err());
+        pos_client.resume().await.unwrap();
+        pos_client
+            .stop_protocol(StopOptions {
+                data_action: StopDataAction::KeepAllData,
+            })
+            .await
+            .unwrap();
+
+        let x1 = client.position("X1").unwrap();
+        assert_eq!(x1.run_state, RunState::Idle);
+        assert_eq!(
+            x1.stopped_with.map(|o| o.data_action),
+            Some(StopDataAction::KeepAllData)
+        );
+        assert_eq!(x1.run_history[0].info.run_id.as_deref(), Some("run-1"));
+        let methods: Vec<_> = client.calls().iter().map(|c| c.method).collect();
+        assert_eq!(
+            methods,
+            [
+                "connect_position",
+                "pause",
+                "get_run_state",
+                "pause",
+                "resume",
+                "stop_protocol"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_injected_failures() {
+        let mut client = client();
+        client.fail("list_positions", ClientError::Disconnected);
+        assert!(client.list_positions().await.is_err());
+        assert_eq!(client.list_positions().await.unwrap().len(), 1);
+
+        client.disconnect();
+        assert!(matches!(
+            client.list_devices().await,
+            Err(ClientError::Disconnected)
+        ));
+        client.reconnect();
+        assert_eq!(client.list_devices().await.unwrap()[0].id, "MN12345");
+
+        let missing = ScriptedPosition::new("X9", "MN12345").position;
+        assert!(matches!(
+            client.connect_position(missing).await,
+            Err(ClientError::NotFound { .. })
+        ));
+    }
+}
@@ -15,29 +15,44 @@
 //!   ├── AcquisitionClient — Run state and control (per-position)
 //!   └── StatisticsClient  — Streaming metrics (per-position)
 //! ```
+//!
+//! Long-lived statistics streams are managed by [`subscription::RunSubscription`].
+//!
+//! Code that only needs unary calls can be written against the
+//! [`MinknowApi`] and [`PositionApi`] traits and tested with
+//! [`mock::MockClient`].
 
+mod api;
+pub mod diagnostics;
 mod error;
+pub mod metrics;
+pub mod mock;
 mod position;
+pub mod subscription;
 mod types;
 
+pub use api::{MinknowApi, PositionApi};
 pub use error::ClientError;
 pub use position::{AcquisitionInfo, PositionClient};
 pub use types::{
-    ChannelLayout, ChannelState, ChannelStatesSnapshot, Device, DeviceState, DeviceType,
-    DutyTimeSnapshot, FlowCellInfo, PoreCategory, PoreCounts, Position, PositionState,
-    ReadLengthHistogram, RunInfo, RunState, StatsSnapshot, YieldDataPoint,
+    BarcodeYield, ChannelLayout, ChannelState, ChannelStatesSnapshot, Device, DeviceState,
+    DeviceType, DiskSpaceInfo, DutyTimeBucket, DutyTimeSnapshot, FlowCellInfo, LastRun,
+    MessageSeverity, MuxScanResult, OutputInfo, PhaseEvent, PhaseKind, PoreCategory, PoreCounts,
+    Position, PositionChanges, PositionState, ProtocolInfo, QScoreHistogram, ReadLengthHistogram,
+    RunInfo, RunState, RunSummary, RunUntilCriteria, ServerInfo, SignalChunk, SpeedDataPoint,
+    StatsSnapshot, StopDataAction, StopOptions, UserMessage, VersionInfo, YieldDataPoint,
 };
 
 use crate::proto::minknow_api::manager::{
-    manager_service_client::ManagerServiceClient, FlowCellPositionsRequest,
-    LocalAuthenticationTokenPathRequest,
+    manager_service_client::ManagerServiceClient, FlowCellPositionsRequest, GetVersionInfoRequest,
+    LocalAuthenticationTokenPathRequest, WatchFlowCellPositionsRequest,
 };
 use rand::Rng;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
-use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint};
 
 /// Default CA certificate search paths for Linux, in order of priority.
 /// Matches the paths used by the official Python minknow_api library.
@@ -57,6 +72,8 @@ pub(crate) const MINKNOW_TRUSTED_CA_ENV: &str = "MINKNOW_TRUSTED_CA";
 
 pub(crate) const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
 pub(crate) const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+pub(crate) const DEFAULT_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub(crate) fn is_localhost(host: &str) -> bool {
     matches!(host, "localhost" | "127.0.0.1" | "::1")
@@ -76,12 +93,30 @@ pub(crate) fn tls_domain_for_host(endpoint: &str, host: &str) -> Result<&'static
 
 /// Load the MinKNOW CA certificate for TLS connections.
 ///
-/// Search order (matches official Python minknow_api library):
+/// A configured `ca_cert` is used as is. Otherwise the search order matches
+/// the official Python minknow_api library:
 /// 1. `MINKNOW_TRUSTED_CA` environment variable (custom path)
 /// 2. Platform-specific default paths:
 ///    - Linux: `/data/rpc-certs/minknow/ca.crt`, `/var/lib/minknow/data/rpc-certs/minknow/ca.crt`
 ///    - macOS: `/Library/MinKNOW/data/rpc-certs/minknow/ca.crt`
-pub(crate) async fn load_ca_cert(endpoint: &str) -> Result<String, ClientError> {
+pub(crate) async fn load_ca_cert(
+    endpoint: &str,
+    ca_cert: Option<&Path>,
+) -> Result<String, ClientError> {
+    if let Some(path) = ca_cert {
+        return tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| ClientError::Connection {
+                endpoint: endpoint.to_string(),
+                source: format!(
+                    "Failed to read configured CA certificate {}: {}",
+                    path.display(),
+                    e
+                )
+                .into(),
+            });
+    }
+
     // First, check environment variable for custom cert path
     if let Ok(custom_path) = std::env::var(MINKNOW_TRUSTED_CA_ENV) {
         let path = Path::new(&custom_path);
@@ -165,6 +200,56 @@ impl Default for ReconnectPolicy {
 }
 
 impl ReconnectPolicy {
+    /// Policy for retrying idempotent unary calls: a couple of quick
+    /// retries, enough to ride out a MinKNOW restart without holding the
+    /// caller up for long.
+    pub fn for_requests() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(2),
+            multiplier: 2.0,
+            jitter_fraction: 0.1,
+            max_attempts: Some(2),
+        }
+    }
+
+    /// Runs `call` until it succeeds or fails with something other than
+    /// `Unavailable` or `DeadlineExceeded`, waiting between attempts as
+    /// for reconnecting. Only use this for calls that are safe to repeat.
+    pub(crate) async fn retry<T, F, Fut>(
+        &self,
+        method: &str,
+        mut call: F,
+    ) -> Result<T, tonic::Status>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, tonic::Status>>,
+    {
+        let mut attempt = 0u32;
+        loop {
+            match call().await {
+                Err(status)
+                    if matches!(
+                        status.code(),
+                        tonic::Code::Unavailable | tonic::Code::DeadlineExceeded
+                    ) && self.max_attempts.is_none_or(|max| attempt < max) =>
+                {
+                    let delay = self.delay_for_attempt(attempt);
+                    tracing::debug!(
+                        method,
+                        attempt,
+                        delay_ms = delay.as_millis(),
+                        error = %status.message(),
+                        "Request failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     fn delay_for_attempt(&self, attempt: u32) -> Duration {
         let base_delay = self.initial_delay.as_secs_f64() * self.multiplier.powi(attempt as i32);
         let capped_delay = base_delay.min(self.max_delay.as_secs_f64());
@@ -181,19 +266,70 @@ impl ReconnectPolicy {
     }
 }
 
+/// Settings shared by the manager connection and the position connections
+/// made through it.
+#[derive(Debug, Clone)]
+pub struct ConnectOptions {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    /// CA certificate to trust instead of searching `MINKNOW_TRUSTED_CA`
+    /// and the default locations.
+    pub ca_cert: Option<PathBuf>,
+    /// How often HTTP/2 pings are sent, including while no request is in
+    /// flight, or `None` to never send them. A ping that goes unanswered
+    /// for `keepalive_timeout` closes the connection, failing its streams.
+    pub keepalive_interval: Option<Duration>,
+    pub keepalive_timeout: Duration,
+    /// Whether to disable Nagle's algorithm on the TCP connection.
+    pub tcp_nodelay: bool,
+    /// How idempotent unary calls on positions are retried when MinKNOW is
+    /// briefly unavailable.
+    pub request_retry: ReconnectPolicy,
+}
+
+impl ConnectOptions {
+    /// Applies the timeout, keepalive and TCP settings to a channel.
+    pub(crate) fn configure(&self, endpoint: Endpoint) -> Endpoint {
+        let endpoint = endpoint
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.request_timeout)
+            .tcp_nodelay(self.tcp_nodelay);
+        match self.keepalive_interval {
+            Some(interval) => endpoint
+                .http2_keep_alive_interval(interval)
+                .keep_alive_timeout(self.keepalive_timeout)
+                .keep_alive_while_idle(true),
+            None => endpoint,
+        }
+    }
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            ca_cert: None,
+            keepalive_interval: Some(DEFAULT_KEEPALIVE_INTERVAL),
+            keepalive_timeout: DEFAULT_KEEPALIVE_TIMEOUT,
+            tcp_nodelay: true,
+            request_retry: ReconnectPolicy::for_requests(),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Client {
     endpoint: String,
     host: String,
     manager: ManagerServiceClient<Channel>,
     auth_token: Option<Arc<str>>,
-    connect_timeout: Duration,
-    request_timeout: Duration,
+    options: ConnectOptions,
 }
 
 impl Client {
     pub async fn connect(host: &str, port: u16) -> Result<Self, ClientError> {
-        Self::connect_with_timeouts(host, port, DEFAULT_CONNECT_TIMEOUT, DEFAULT_REQUEST_TIMEOUT)
-            .await
+        Self::connect_with_options(host, port, ConnectOptions::default()).await
     }
 
     pub async fn connect_with_timeouts(
@@ -201,12 +337,25 @@ impl Client {
         port: u16,
         connect_timeout: Duration,
         request_timeout: Duration,
+    ) -> Result<Self, ClientError> {
+        let options = ConnectOptions {
+            connect_timeout,
+            request_timeout,
+            ..Default::default()
+        };
+        Self::connect_with_options(host, port, options).await
+    }
+
+    pub async fn connect_with_options(
+        host: &str,
+        port: u16,
+        options: ConnectOptions,
     ) -> Result<Self, ClientError> {
         let endpoint = format!("https://{}:{}", host, port);
         tracing::info!(endpoint = %endpoint, "Connecting to MinKNOW manager");
 
         let tls_domain = tls_domain_for_host(&endpoint, host)?;
-        let ca_cert = load_ca_cert(&endpoint).await?;
+        let ca_cert = load_ca_cert(&endpoint, options.ca_cert.as_deref()).await?;
 
         let tls_config = ClientTlsConfig::new()
             .ca_certificate(Certificate::from_pem(&ca_cert))
@@ -218,18 +367,19 @@ impl Client {
                 source: Box::new(e),
             })?
             .tls_config(tls_config)
-            .map_err(|e| ClientError::Connection {
-                endpoint: endpoint.clone(),
-                source: Box::new(e),
-            })?
-            .connect_timeout(connect_timeout)
-            .timeout(request_timeout)
-            .connect()
-            .await
             .map_err(|e| ClientError::Connection {
                 endpoint: endpoint.clone(),
                 source: Box::new(e),
             })?;
+        let channel =
+            options
+                .configure(channel)
+                .connect()
+                .await
+                .map_err(|e| ClientError::Connection {
+                    endpoint: endpoint.clone(),
+                    source: Box::new(e),
+                })?;
 
         let mut manager = ManagerServiceClient::new(channel);
 
@@ -241,22 +391,23 @@ impl Client {
             host: host.to_string(),
             manager,
             auth_token,
-            connect_timeout,
-            request_timeout,
+            options,
         })
     }
 
     async fn fetch_auth_token(
         manager: &mut ManagerServiceClient<Channel>,
     ) -> Result<Option<Arc<str>>, ClientError> {
-        let response = manager
-            .local_authentication_token_path(LocalAuthenticationTokenPathRequest {})
-            .await
-            .map_err(|status| ClientError::Grpc {
-                method: "local_authentication_token_path".into(),
-                status,
-            })?
-            .into_inner();
+        let response = metrics::timed(
+            "local_authentication_token_path",
+            manager.local_authentication_token_path(LocalAuthenticationTokenPathRequest {}),
+        )
+        .await
+        .map_err(|status| ClientError::Grpc {
+            method: "local_authentication_token_path".into(),
+            status,
+        })?
+        .into_inner();
 
         if response.path.is_empty() {
             tracing::debug!("No local auth token path returned (guest mode enabled?)");
@@ -301,16 +452,82 @@ impl Client {
         &self.host
     }
 
+    /// Whether a local auth token was loaded. Without one, requests rely on
+    /// MinKNOW's guest mode.
+    pub fn has_auth_token(&self) -> bool {
+        self.auth_token.is_some()
+    }
+
+    pub async fn get_version_info(&mut self) -> Result<VersionInfo, ClientError> {
+        let manager = &self.manager;
+        let response = self
+            .options
+            .request_retry
+            .retry("get_version_info", || {
+                let mut manager = manager.clone();
+                metrics::timed("get_version_info", async move {
+                    manager.get_version_info(GetVersionInfoRequest {}).await
+                })
+            })
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "get_version_info".into(),
+                status,
+            })?
+            .into_inner();
+
+        let minknow = response.minknow.unwrap_or_default();
+        Ok(VersionInfo {
+            major: minknow.major,
+            minor: minknow.minor,
+            patch: minknow.patch,
+            full: minknow.full,
+            distribution: response.distribution_version,
+        })
+    }
+
+    /// Round-trip time of a version request to the manager, which is cheap
+    /// for MinKNOW to answer. Not retried, so a slow or failing link shows.
+    pub async fn ping(&mut self) -> Result<Duration, ClientError> {
+        let mut manager = self.manager.clone();
+        let start = std::time::Instant::now();
+        metrics::timed("get_version_info", async move {
+            manager.get_version_info(GetVersionInfoRequest {}).await
+        })
+        .await
+        .map_err(|status| ClientError::Grpc {
+            method: "get_version_info".into(),
+            status,
+        })?;
+        Ok(start.elapsed())
+    }
+
+    /// Endpoint, auth mode and MinKNOW version of the manager. The version
+    /// is left out if it cannot be fetched.
+    pub async fn server_info(&mut self) -> ServerInfo {
+        let version = match self.get_version_info().await {
+            Ok(version) => Some(version),
+            Err(e) => {
+                tracing::debug!(error = %e.display_message(), "Failed to get MinKNOW version");
+                None
+            }
+        };
+        ServerInfo {
+            endpoint: self.endpoint.trim_start_matches("https://").to_string(),
+            authenticated: self.has_auth_token(),
+            version,
+        }
+    }
+
     pub async fn connect_position(
         &self,
         position: Position,
     ) -> Result<PositionClient, ClientError> {
-        PositionClient::connect_with_timeouts(
+        PositionClient::connect_with_options(
             position,
             &self.host,
             self.auth_token.clone(),
-            self.connect_timeout,
-            self.request_timeout,
+            &self.options,
         )
         .await
     }
@@ -345,6 +562,45 @@ impl Client {
         Ok(positions)
     }
 
+    /// Watches positions come and go. The first update lists every known
+    /// position; later ones carry only what changed. MinKNOW before 3.6
+    /// answers with an error for which
+    /// [`ClientError::is_unimplemented`] holds.
+    #[allow(clippy::result_large_err)]
+    pub async fn watch_positions(
+        &mut self,
+    ) -> Result<impl futures::Stream<Item = Result<PositionChanges, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
+
+        let stream = self
+            .manager
+            .watch_flow_cell_positions(WatchFlowCellPositionsRequest {})
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "watch_flow_cell_positions".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| PositionChanges {
+                    updated: response
+                        .additions
+                        .into_iter()
+                        .chain(response.changes)
+                        .map(Position::from_proto)
+                        .collect(),
+                    removed: response.removals,
+                })
+                .map_err(|status| ClientError::Grpc {
+                    method: "watch_flow_cell_positions".into(),
+                    status,
+                })
+        }))
+    }
+
     pub async fn list_devices(&mut self) -> Result<Vec<Device>, ClientError> {
         let positions = self.list_positions().await?;
 
@@ -494,4 +750,42 @@ mod tests {
         assert!(max_seen <= Duration::from_secs(11));
         assert!(min_seen != max_seen);
     }
+
+    #[tokio::test]
+    async fn test_retry_transient_failures() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::ZERO,
+            max_attempts: Some(2),
+            ..ReconnectPolicy::default()
+        };
+        let calls = std::cell::Cell::new(0);
+        let failing = |code: tonic::Code, failures: u32| {
+            calls.set(0);
+            let calls = &calls;
+            move || {
+                calls.set(calls.get() + 1);
+                let result = if calls.get() <= failures {
+                    Err(tonic::Status::new(code, "down"))
+                } else {
+                    Ok(calls.get())
+                };
+                async move { result }
+            }
+        };
+
+        let result = policy
+            .retry("m", failing(tonic::Code::Unavailable, 2))
+            .await;
+        assert_eq!(result.unwrap(), 3);
+
+        let result = policy
+            .retry("m", failing(tonic::Code::DeadlineExceeded, 3))
+            .await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::DeadlineExceeded);
+        assert_eq!(calls.get(), 3);
+
+        let result = policy.retry("m", failing(tonic::Code::NotFound, 1)).await;
+        assert_eq!(result.unwrap_err().code(), tonic::Code::NotFound);
+        assert_eq!(calls.get(), 1);
+    }
 }
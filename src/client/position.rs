@@ -1,33 +1,65 @@
 //! Position-specific client for acquisition and statistics services.
 
+use super::subscription::{HistogramOptions, DEFAULT_HISTOGRAM_INTERVAL};
 use super::{
-    ChannelState, ClientError, DutyTimeSnapshot, FlowCellInfo, Position, ReadLengthHistogram,
-    RunState, StatsSnapshot, YieldDataPoint,
+    BarcodeYield, ChannelState, ClientError, ConnectOptions, DiskSpaceInfo, DutyTimeBucket,
+    DutyTimeSnapshot, FlowCellInfo, LastRun, MessageSeverity, MuxScanResult, OutputInfo,
+    PhaseEvent, PhaseKind, Position, ProtocolInfo, QScoreHistogram, ReadLengthHistogram,
+    ReconnectPolicy, RunState, RunSummary, RunUntilCriteria, SignalChunk, SpeedDataPoint,
+    StatsSnapshot, StopDataAction, StopOptions, UserMessage, YieldDataPoint,
 };
 use crate::proto::minknow_api::acquisition::{
-    acquisition_service_client::AcquisitionServiceClient, CurrentStatusRequest,
-    GetAcquisitionRunInfoRequest, MinknowStatus, StopRequest,
+    acquisition_service_client::AcquisitionServiceClient, stop_request::DataAction,
+    CurrentStatusRequest, GetAcquisitionRunInfoRequest, MinknowStatus, StopRequest,
 };
 use crate::proto::minknow_api::data::{
-    data_service_client::DataServiceClient, GetChannelStatesRequest,
+    data_service_client::DataServiceClient, GetChannelStatesRequest, GetSignalBytesRequest,
 };
 use crate::proto::minknow_api::device::{
     device_service_client::DeviceServiceClient, GetChannelsLayoutRequest, GetFlowCellInfoRequest,
 };
+use crate::proto::minknow_api::instance::{
+    instance_service_client::InstanceServiceClient, GetDiskSpaceInfoRequest,
+};
+use crate::proto::minknow_api::log::{
+    log_service_client::LogServiceClient, GetUserMessagesRequest, Severity,
+};
 use crate::proto::minknow_api::protocol::{
-    protocol_service_client::ProtocolServiceClient, GetCurrentProtocolRunRequest,
-    PauseProtocolRequest, ProtocolPhase, ResumeProtocolRequest, StopProtocolRequest,
+    protocol_info::tag_value::TagValue, protocol_service_client::ProtocolServiceClient,
+    FilteringInfo, GetCurrentProtocolRunRequest, GetRunInfoRequest, ListProtocolRunsRequest,
+    ListProtocolsRequest, PauseProtocolRequest, ProtocolPhase, ProtocolRunInfo,
+    ProtocolRunUserInfo, ProtocolState, ResumeProtocolRequest, StartProtocolRequest,
+    StopProtocolRequest,
+};
+use crate::proto::minknow_api::run_until::{
+    run_until_service_client::RunUntilServiceClient, CriteriaValues, StreamTargetCriteriaRequest,
+    StreamUpdatesRequest, WriteTargetCriteriaRequest,
 };
 use crate::proto::minknow_api::statistics::{
-    statistics_service_client::StatisticsServiceClient, stream_boxplot_request, DataSelection,
-    ReadLengthType, StreamAcquisitionOutputRequest, StreamBoxplotRequest, StreamDutyTimeRequest,
+    statistics_service_client::StatisticsServiceClient, stream_boxplot_request,
+    AcquisitionOutputSnapshot, AcquisitionOutputSplit, BucketValueType, DataSelection,
+    ReadLengthType, StreamAcquisitionOutputRequest, StreamAcquisitionOutputResponse,
+    StreamBoxplotRequest, StreamDutyTimeRequest, StreamQScoreHistogramRequest,
     StreamReadLengthHistogramRequest,
 };
+use chrono::{DateTime, Utc};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tonic::service::Interceptor;
 use tonic::transport::{Certificate, Channel, ClientTlsConfig};
 
+/// Maximum number of past protocol runs fetched for the run history.
+const RUN_HISTORY_LIMIT: i32 = 50;
+
+/// How long to wait for a finished run's read length histogram.
+const RUN_HISTORY_N50_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Converts a stream's resend interval to MinKNOW's whole seconds.
+fn poll_seconds(interval: Duration) -> u32 {
+    interval.as_secs().clamp(1, u32::MAX as u64) as u32
+}
+
 #[derive(Clone)]
 struct AuthInterceptor {
     token: Option<Arc<str>>,
@@ -50,6 +82,7 @@ impl Interceptor for AuthInterceptor {
 
 type InterceptedChannel = tonic::service::interceptor::InterceptedService<Channel, AuthInterceptor>;
 
+#[derive(Clone)]
 pub struct PositionClient {
     pub position: Position,
     acquisition: AcquisitionServiceClient<InterceptedChannel>,
@@ -57,6 +90,10 @@ pub struct PositionClient {
     data: DataServiceClient<InterceptedChannel>,
     device: DeviceServiceClient<InterceptedChannel>,
     protocol: ProtocolServiceClient<InterceptedChannel>,
+    instance: InstanceServiceClient<InterceptedChannel>,
+    run_until: RunUntilServiceClient<InterceptedChannel>,
+    log: LogServiceClient<InterceptedChannel>,
+    retry: ReconnectPolicy,
 }
 
 impl PositionClient {
@@ -65,22 +102,14 @@ impl PositionClient {
         host: &str,
         auth_token: Option<Arc<str>>,
     ) -> Result<Self, ClientError> {
-        Self::connect_with_timeouts(
-            position,
-            host,
-            auth_token,
-            super::DEFAULT_CONNECT_TIMEOUT,
-            super::DEFAULT_REQUEST_TIMEOUT,
-        )
-        .await
+        Self::connect_with_options(position, host, auth_token, &ConnectOptions::default()).await
     }
 
-    pub async fn connect_with_timeouts(
+    pub async fn connect_with_options(
         position: Position,
         host: &str,
         auth_token: Option<Arc<str>>,
-        connect_timeout: Duration,
-        request_timeout: Duration,
+        options: &ConnectOptions,
     ) -> Result<Self, ClientError> {
         if position.grpc_port == 0 {
             return Err(ClientError::Connection {
@@ -97,7 +126,7 @@ impl PositionClient {
         );
 
         let tls_domain = super::tls_domain_for_host(&endpoint, host)?;
-        let ca_cert = super::load_ca_cert(&endpoint).await?;
+        let ca_cert = super::load_ca_cert(&endpoint, options.ca_cert.as_deref()).await?;
 
         let tls_config = ClientTlsConfig::new()
             .ca_certificate(Certificate::from_pem(&ca_cert))
@@ -109,18 +138,19 @@ impl PositionClient {
                 source: Box::new(e),
             })?
             .tls_config(tls_config)
-            .map_err(|e| ClientError::Connection {
-                endpoint: endpoint.clone(),
-                source: Box::new(e),
-            })?
-            .connect_timeout(connect_timeout)
-            .timeout(request_timeout)
-            .connect()
-            .await
             .map_err(|e| ClientError::Connection {
                 endpoint: endpoint.clone(),
                 source: Box::new(e),
             })?;
+        let channel =
+            options
+                .configure(channel)
+                .connect()
+                .await
+                .map_err(|e| ClientError::Connection {
+                    endpoint: endpoint.clone(),
+                    source: Box::new(e),
+                })?;
 
         let interceptor = AuthInterceptor { token: auth_token };
         let acquisition =
@@ -129,7 +159,13 @@ impl PositionClient {
             StatisticsServiceClient::with_interceptor(channel.clone(), interceptor.clone());
         let data = DataServiceClient::with_interceptor(channel.clone(), interceptor.clone());
         let device = DeviceServiceClient::with_interceptor(channel.clone(), interceptor.clone());
-        let protocol = ProtocolServiceClient::with_interceptor(channel, interceptor);
+        let protocol =
+            ProtocolServiceClient::with_interceptor(channel.clone(), interceptor.clone());
+        let instance =
+            InstanceServiceClient::with_interceptor(channel.clone(), interceptor.clone());
+        let run_until =
+            RunUntilServiceClient::with_interceptor(channel.clone(), interceptor.clone());
+        let log = LogServiceClient::with_interceptor(channel, interceptor);
 
         tracing::info!(position = %position.name, "Connected to position services");
         Ok(Self {
@@ -139,13 +175,44 @@ impl PositionClient {
             data,
             device,
             protocol,
+            instance,
+            run_until,
+            log,
+            retry: options.request_retry.clone(),
         })
     }
 
+    /// Makes an idempotent unary call, retrying it while MinKNOW is briefly
+    /// unavailable. Each attempt gets its own handle to the service and
+    /// copy of the request.
+    async fn unary<S, R, T, F, Fut>(
+        &self,
+        method: &str,
+        service: &S,
+        request: R,
+        call: F,
+    ) -> Result<tonic::Response<T>, tonic::Status>
+    where
+        S: Clone,
+        R: Clone,
+        F: Fn(S, R) -> Fut,
+        Fut: Future<Output = Result<tonic::Response<T>, tonic::Status>>,
+    {
+        self.retry
+            .retry(method, || {
+                super::metrics::timed(method, call(service.clone(), request.clone()))
+            })
+            .await
+    }
+
     pub async fn get_run_state(&mut self) -> Result<RunState, ClientError> {
         let response = self
-            .acquisition
-            .current_status(CurrentStatusRequest {})
+            .unary(
+                "current_status",
+                &self.acquisition,
+                CurrentStatusRequest {},
+                |mut s, r| async move { s.current_status(r).await },
+            )
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "current_status".into(),
@@ -174,8 +241,12 @@ impl PositionClient {
 
     async fn get_protocol_phase_state(&mut self) -> Option<RunState> {
         let response = self
-            .protocol
-            .get_current_protocol_run(GetCurrentProtocolRunRequest {})
+            .unary(
+                "get_current_protocol_run",
+                &self.protocol,
+                GetCurrentProtocolRunRequest {},
+                |mut s, r| async move { s.get_current_protocol_run(r).await },
+            )
             .await
             .ok()?
             .into_inner();
@@ -204,30 +275,24 @@ impl PositionClient {
 
     pub async fn get_run_info(&mut self) -> Result<Option<super::RunInfo>, ClientError> {
         let response = self
-            .protocol
-            .get_current_protocol_run(GetCurrentProtocolRunRequest {})
+            .unary(
+                "get_current_protocol_run",
+                &self.protocol,
+                GetCurrentProtocolRunRequest {},
+                |mut s, r| async move { s.get_current_protocol_run(r).await },
+            )
             .await;
 
         match response {
             Ok(resp) => {
-                let info = resp.into_inner();
-                let user_info = info.user_info;
-
-                let experiment_group = user_info
-                    .as_ref()
-                    .and_then(|ui| ui.protocol_group_id.clone())
-                    .filter(|s| !s.is_empty());
-
-                let sample_id = user_info
-                    .as_ref()
-                    .and_then(|ui| ui.sample_id.clone())
-                    .filter(|s| !s.is_empty());
-
-                if experiment_group.is_some() || sample_id.is_some() {
-                    Ok(Some(super::RunInfo {
-                        experiment_group,
-                        sample_id,
-                    }))
+                let info = run_info(&resp.into_inner());
+
+                if info.run_id.is_some()
+                    || info.experiment_group.is_some()
+                    || info.sample_id.is_some()
+                    || info.start_time.is_some()
+                {
+                    Ok(Some(info))
                 } else {
                     Ok(None)
                 }
@@ -240,10 +305,302 @@ impl PositionClient {
         }
     }
 
+    /// Returns the finished protocol runs on this position, newest first.
+    ///
+    /// Yields come from the last acquisition of each run. The N50 is taken
+    /// from the first read length histogram message, and left empty if none
+    /// arrives within a few seconds.
+    pub async fn list_run_history(&mut self) -> Result<Vec<RunSummary>, ClientError> {
+        let request = ListProtocolRunsRequest {
+            filter_info: Some(FilteringInfo {
+                count: Some(RUN_HISTORY_LIMIT),
+                ..Default::default()
+            }),
+        };
+        let run_ids = self
+            .unary(
+                "list_protocol_runs",
+                &self.protocol,
+                request,
+                |mut s, r| async move { s.list_protocol_runs(r).await },
+            )
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "list_protocol_runs".into(),
+                status,
+            })?
+            .into_inner()
+            .run_ids;
+
+        let mut runs = Vec::new();
+        for run_id in run_ids {
+            let request = GetRunInfoRequest { run_id };
+            let protocol_run = self
+                .unary(
+                    "get_run_info",
+                    &self.protocol,
+                    request,
+                    |mut s, r| async move { s.get_run_info(r).await },
+                )
+                .await
+                .map_err(|status| ClientError::Grpc {
+                    method: "get_run_info".into(),
+                    status,
+                })?
+                .into_inner();
+
+            let Some(mut summary) = finished_run(&protocol_run) else {
+                continue;
+            };
+            if let Some(acquisition_run_id) = summary.acquisition_run_id.clone() {
+                self.fill_run_yield(&mut summary, acquisition_run_id).await;
+            }
+
+            runs.push(summary);
+        }
+
+        runs.sort_by_key(|run| std::cmp::Reverse(run.info.start_time));
+        Ok(runs)
+    }
+
+    /// Returns the most recently started protocol run if it has finished,
+    /// with the final yield history and read length histogram of its last
+    /// acquisition. Each is fetched once rather than streamed.
+    pub async fn get_last_run(&mut self) -> Result<Option<LastRun>, ClientError> {
+        let response = self
+            .unary(
+                "get_run_info",
+                &self.protocol,
+                // An empty run ID asks for the most recently started run.
+                GetRunInfoRequest::default(),
+                |mut s, r| async move { s.get_run_info(r).await },
+            )
+            .await;
+        let protocol_run = match response {
+            Ok(response) => response.into_inner(),
+            // Nothing has run on this position yet.
+            Err(status) if status.code() == tonic::Code::FailedPrecondition => return Ok(None),
+            Err(status) if status.code() == tonic::Code::NotFound => return Ok(None),
+            Err(status) => {
+                return Err(ClientError::Grpc {
+                    method: "get_run_info".into(),
+                    status,
+                })
+            }
+        };
+        let Some(summary) = finished_run(&protocol_run) else {
+            return Ok(None);
+        };
+
+        let mut run = LastRun {
+            summary,
+            ..Default::default()
+        };
+        if let Some(acquisition_run_id) = run.summary.acquisition_run_id.clone() {
+            run.histogram = self
+                .fill_run_yield(&mut run.summary, acquisition_run_id.clone())
+                .await;
+            match self.get_yield_history(&acquisition_run_id).await {
+                Ok(points) => run.yield_history = points,
+                Err(e) => {
+                    tracing::debug!(run_id = %acquisition_run_id, error = %e.display_message(), "No yield history for past run");
+                }
+            }
+        }
+        Ok(Some(run))
+    }
+
+    /// Adds the yield and N50 of a finished acquisition to `summary`, and
+    /// returns the read length histogram the N50 came from.
+    ///
+    /// Both are best effort: older runs may no longer have statistics.
+    async fn fill_run_yield(
+        &mut self,
+        summary: &mut RunSummary,
+        acquisition_run_id: String,
+    ) -> Option<ReadLengthHistogram> {
+        use futures::StreamExt;
+
+        let request = GetAcquisitionRunInfoRequest {
+            run_id: acquisition_run_id.clone(),
+        };
+        match self
+            .unary(
+                "get_acquisition_info",
+                &self.acquisition,
+                request,
+                |mut s, r| async move { s.get_acquisition_info(r).await },
+            )
+            .await
+        {
+            Ok(response) => {
+                if let Some(yield_summary) = response.into_inner().yield_summary {
+                    summary.reads = yield_summary.read_count as u64;
+                    summary.bases_passed = yield_summary.basecalled_pass_bases as u64;
+                    summary.bases_failed = yield_summary.basecalled_fail_bases as u64;
+                }
+            }
+            Err(status) => {
+                tracing::debug!(run_id = %acquisition_run_id, error = %status, "No acquisition info for past run");
+            }
+        }
+
+        let first_histogram = async {
+            let mut stream = self
+                .stream_read_length_histogram(
+                    &acquisition_run_id,
+                    HistogramOptions::default(),
+                    DEFAULT_HISTOGRAM_INTERVAL,
+                )
+                .await
+                .ok()?;
+            stream.next().await?.ok()
+        };
+        let histogram = tokio::time::timeout(RUN_HISTORY_N50_TIMEOUT, first_histogram)
+            .await
+            .ok()
+            .flatten()?;
+        summary.n50 = Some(histogram.n50 as u64).filter(|&n50| n50 > 0);
+        Some(histogram)
+    }
+
+    /// Returns the phase transitions of the current protocol run, oldest first.
+    pub async fn get_phase_history(&mut self) -> Result<Vec<PhaseEvent>, ClientError> {
+        let response = self
+            .unary(
+                "get_current_protocol_run",
+                &self.protocol,
+                GetCurrentProtocolRunRequest {},
+                |mut s, r| async move { s.get_current_protocol_run(r).await },
+            )
+            .await;
+
+        match response {
+            Ok(resp) => {
+                let mut events: Vec<PhaseEvent> = resp
+                    .into_inner()
+                    .phase_history
+                    .into_iter()
+                    .filter_map(|snapshot| {
+                        let timestamp = snapshot.timestamp.and_then(timestamp_to_utc)?;
+                        let phase = ProtocolPhase::try_from(snapshot.phase).ok();
+                        Some(PhaseEvent {
+                            timestamp,
+                            phase: phase.map_or("Unknown", phase_label).to_string(),
+                            kind: phase.map(phase_kind).unwrap_or_default(),
+                        })
+                    })
+                    .collect();
+                events.sort_by_key(|e| e.timestamp);
+                Ok(events)
+            }
+            Err(status) if status.code() == tonic::Code::FailedPrecondition => Ok(Vec::new()),
+            Err(status) => Err(ClientError::Grpc {
+                method: "get_current_protocol_run".into(),
+                status,
+            }),
+        }
+    }
+
+    /// Returns the output location and writer progress of the current
+    /// acquisition, or `None` when there is none.
+    pub async fn get_output_info(&mut self) -> Result<Option<OutputInfo>, ClientError> {
+        let response = match self
+            .unary(
+                "get_acquisition_info",
+                &self.acquisition,
+                GetAcquisitionRunInfoRequest::default(),
+                |mut s, r| async move { s.get_acquisition_info(r).await },
+            )
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::FailedPrecondition => return Ok(None),
+            Err(status) => {
+                return Err(ClientError::Grpc {
+                    method: "get_acquisition_info".into(),
+                    status,
+                })
+            }
+        };
+        if response.run_id.is_empty() {
+            return Ok(None);
+        }
+
+        let config = response.config_summary.unwrap_or_default();
+        let formats = [
+            (config.pod5_reads_enabled, "pod5"),
+            (config.fastq_reads_enabled, "fastq"),
+            (config.bam_reads_enabled, "bam"),
+            (config.fast5_reads_enabled, "fast5"),
+        ]
+        .into_iter()
+        .filter(|&(enabled, _)| enabled)
+        .map(|(_, format)| format.to_string())
+        .collect();
+        let writer = response.writer_summary.unwrap_or_default();
+        let bytes = |value: i64| value.max(0) as u64;
+
+        Ok(Some(OutputInfo {
+            reads_directory: Some(config.reads_directory).filter(|s| !s.is_empty()),
+            formats,
+            bytes_written: bytes(writer.bytes_to_write_completed),
+            bytes_pending: bytes(
+                writer.bytes_to_write_produced
+                    - writer.bytes_to_write_completed
+                    - writer.bytes_to_write_failed,
+            ),
+            bytes_failed: bytes(writer.bytes_to_write_failed),
+        }))
+    }
+
+    /// Returns the pore scan results of the current acquisition, oldest
+    /// first. Empty when there is no acquisition or the protocol reports
+    /// none.
+    pub async fn get_mux_scan_results(&mut self) -> Result<Vec<MuxScanResult>, ClientError> {
+        let response = match self
+            .unary(
+                "get_acquisition_info",
+                &self.acquisition,
+                GetAcquisitionRunInfoRequest::default(),
+                |mut s, r| async move { s.get_acquisition_info(r).await },
+            )
+            .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(status) if status.code() == tonic::Code::FailedPrecondition => {
+                return Ok(Vec::new())
+            }
+            Err(status) => {
+                return Err(ClientError::Grpc {
+                    method: "get_acquisition_info".into(),
+                    status,
+                })
+            }
+        };
+
+        let mut scans: Vec<MuxScanResult> = response
+            .bream_info
+            .map(|info| info.mux_scan_results)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|result| MuxScanResult {
+                seconds: result.mux_scan_timestamp,
+                counts: result.counts.into_iter().collect(),
+            })
+            .collect();
+        scans.sort_by_key(|scan| scan.seconds);
+        Ok(scans)
+    }
+
     pub async fn get_flow_cell_info(&mut self) -> Result<FlowCellInfo, ClientError> {
         let response = self
-            .device
-            .get_flow_cell_info(GetFlowCellInfoRequest {})
+            .unary(
+                "get_flow_cell_info",
+                &self.device,
+                GetFlowCellInfoRequest {},
+                |mut s, r| async move { s.get_flow_cell_info(r).await },
+            )
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "get_flow_cell_info".into(),
@@ -268,12 +625,50 @@ impl PositionClient {
         })
     }
 
+    /// Returns the disk usage of each file-system MinKNOW writes to.
+    pub async fn get_disk_space_info(&mut self) -> Result<Vec<DiskSpaceInfo>, ClientError> {
+        // Deprecated in favour of the manager's RPC of the same name, but
+        // still served on each position's port.
+        #[allow(deprecated)]
+        let response = self
+            .unary(
+                "get_disk_space_info",
+                &self.instance,
+                GetDiskSpaceInfoRequest {},
+                |mut s, r| async move { s.get_disk_space_info(r).await },
+            )
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "get_disk_space_info".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(response
+            .filesystem_disk_space_info
+            .into_iter()
+            .map(|fs| DiskSpaceInfo {
+                filesystem_id: fs.filesystem_id,
+                bytes_available: fs.bytes_available,
+                bytes_capacity: fs.bytes_capacity,
+                bytes_to_stop_cleanly: fs.bytes_to_stop_cleanly,
+                bytes_per_second: fs.bytes_per_second,
+                recommend_alert: fs.recommend_alert,
+                recommend_stop: fs.recommend_stop,
+            })
+            .collect())
+    }
+
     pub async fn get_acquisition_info(&mut self) -> Result<AcquisitionInfo, ClientError> {
         let state = self.get_run_state().await?;
 
         let response = self
-            .acquisition
-            .get_acquisition_info(GetAcquisitionRunInfoRequest::default())
+            .unary(
+                "get_acquisition_info",
+                &self.acquisition,
+                GetAcquisitionRunInfoRequest::default(),
+                |mut s, r| async move { s.get_acquisition_info(r).await },
+            )
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "get_acquisition_info".into(),
@@ -286,6 +681,7 @@ impl PositionClient {
         Ok(AcquisitionInfo {
             run_id: response.run_id,
             state,
+            start_time: response.start_time.and_then(timestamp_to_utc),
             reads_processed: yield_summary.map(|y| y.read_count as u64).unwrap_or(0),
             reads_passed: yield_summary
                 .map(|y| y.basecalled_pass_read_count as u64)
@@ -316,6 +712,7 @@ impl PositionClient {
 
         Ok(StatsSnapshot {
             timestamp: None,
+            acquisition_start: info.start_time,
             reads_processed: info.reads_processed,
             bases_called: total_bases,
             throughput_bps: 0.0,
@@ -332,8 +729,7 @@ impl PositionClient {
 
     /// Stops the current acquisition.
     pub async fn stop(&mut self) -> Result<(), ClientError> {
-        self.acquisition
-            .stop(StopRequest::default())
+        super::metrics::timed("stop", self.acquisition.stop(StopRequest::default()))
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "stop".into(),
@@ -346,13 +742,16 @@ impl PositionClient {
     ///
     /// This will only succeed if the protocol supports pausing (can_pause = true).
     pub async fn pause(&mut self) -> Result<(), ClientError> {
-        self.protocol
-            .pause_protocol(PauseProtocolRequest::default())
-            .await
-            .map_err(|status| ClientError::Grpc {
-                method: "pause_protocol".into(),
-                status,
-            })?;
+        super::metrics::timed(
+            "pause_protocol",
+            self.protocol
+                .pause_protocol(PauseProtocolRequest::default()),
+        )
+        .await
+        .map_err(|status| ClientError::Grpc {
+            method: "pause_protocol".into(),
+            status,
+        })?;
         Ok(())
     }
 
@@ -360,22 +759,33 @@ impl PositionClient {
     ///
     /// This will only succeed if the protocol is currently paused.
     pub async fn resume(&mut self) -> Result<(), ClientError> {
-        self.protocol
-            .resume_protocol(ResumeProtocolRequest::default())
-            .await
-            .map_err(|status| ClientError::Grpc {
-                method: "resume_protocol".into(),
-                status,
-            })?;
+        super::metrics::timed(
+            "resume_protocol",
+            self.protocol
+                .resume_protocol(ResumeProtocolRequest::default()),
+        )
+        .await
+        .map_err(|status| ClientError::Grpc {
+            method: "resume_protocol".into(),
+            status,
+        })?;
         Ok(())
     }
 
     /// Stops the current protocol (not just acquisition).
     ///
     /// This stops the entire protocol run, not just the acquisition.
-    pub async fn stop_protocol(&mut self) -> Result<(), ClientError> {
-        self.protocol
-            .stop_protocol(StopProtocolRequest::default())
+    /// `options` say what happens to reads that are not basecalled yet.
+    pub async fn stop_protocol(&mut self, options: StopOptions) -> Result<(), ClientError> {
+        let data_action = match options.data_action {
+            StopDataAction::FinishProcessing => DataAction::StopFinishProcessing,
+            StopDataAction::KeepAllData => DataAction::StopKeepAllData,
+        };
+        let request = StopProtocolRequest {
+            data_action_on_stop: data_action as i32,
+            ..Default::default()
+        };
+        super::metrics::timed("stop_protocol", self.protocol.stop_protocol(request))
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "stop_protocol".into(),
@@ -384,10 +794,89 @@ impl PositionClient {
         Ok(())
     }
 
+    /// Lists the protocols that can be started on this position, sorted by name.
+    pub async fn list_protocols(&mut self) -> Result<Vec<ProtocolInfo>, ClientError> {
+        let response = self
+            .unary(
+                "list_protocols",
+                &self.protocol,
+                ListProtocolsRequest::default(),
+                |mut s, r| async move { s.list_protocols(r).await },
+            )
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "list_protocols".into(),
+                status,
+            })?
+            .into_inner();
+
+        let mut protocols: Vec<ProtocolInfo> = response
+            .protocols
+            .into_iter()
+            .map(|protocol| {
+                let tag = |name: &str| match protocol
+                    .tags
+                    .get(name)
+                    .and_then(|value| value.tag_value.as_ref())
+                {
+                    Some(TagValue::StringValue(value)) if !value.is_empty() => Some(value.clone()),
+                    _ => None,
+                };
+                ProtocolInfo {
+                    experiment_type: tag("experiment type"),
+                    kit: tag("kit"),
+                    flow_cell: tag("flow cell"),
+                    name: if protocol.name.is_empty() {
+                        protocol.identifier.clone()
+                    } else {
+                        protocol.name.clone()
+                    },
+                    identifier: protocol.identifier,
+                }
+            })
+            .collect();
+        protocols.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(protocols)
+    }
+
+    /// Starts a protocol and returns the new protocol run ID.
+    ///
+    /// Empty sample IDs and experiment groups are left unset so MinKNOW
+    /// applies its own defaults.
+    pub async fn start_protocol(
+        &mut self,
+        identifier: &str,
+        sample_id: &str,
+        experiment_group: &str,
+    ) -> Result<String, ClientError> {
+        let non_empty = |s: &str| (!s.trim().is_empty()).then(|| s.trim().to_string());
+        let request = StartProtocolRequest {
+            identifier: identifier.to_string(),
+            user_info: Some(ProtocolRunUserInfo {
+                sample_id: non_empty(sample_id),
+                protocol_group_id: non_empty(experiment_group),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let response =
+            super::metrics::timed("start_protocol", self.protocol.start_protocol(request))
+                .await
+                .map_err(|status| ClientError::Grpc {
+                    method: "start_protocol".into(),
+                    status,
+                })?;
+        Ok(response.into_inner().run_id)
+    }
+
     pub async fn get_current_run_id(&mut self) -> Result<Option<String>, ClientError> {
         match self
-            .acquisition
-            .get_acquisition_info(GetAcquisitionRunInfoRequest::default())
+            .unary(
+                "get_acquisition_info",
+                &self.acquisition,
+                GetAcquisitionRunInfoRequest::default(),
+                |mut s, r| async move { s.get_acquisition_info(r).await },
+            )
             .await
         {
             Ok(response) => {
@@ -406,6 +895,7 @@ impl PositionClient {
         }
     }
 
+    #[allow(clippy::result_large_err)]
     pub async fn stream_acquisition_output(
         &mut self,
         run_id: &str,
@@ -478,37 +968,96 @@ impl PositionClient {
             })?
             .into_inner();
 
-        let mut points = Vec::new();
+        let points = match stream.next().await {
+            Some(Ok(response)) => yield_points(&response),
+            _ => Vec::new(),
+        };
 
-        if let Some(Ok(response)) = stream.next().await {
-            for filtered in &response.snapshots {
-                for snapshot in &filtered.snapshots {
-                    if let Some(yield_summary) = &snapshot.yield_summary {
-                        let reads_passed = yield_summary.basecalled_pass_read_count as u64;
-                        let reads_failed = yield_summary.basecalled_fail_read_count as u64;
-                        let bases_passed = yield_summary.basecalled_pass_bases as u64;
-                        let bases_failed = yield_summary.basecalled_fail_bases as u64;
-
-                        points.push(YieldDataPoint {
-                            seconds: snapshot.seconds,
-                            reads: reads_passed + reads_failed,
-                            bases: bases_passed + bases_failed,
-                            reads_passed,
-                            reads_failed,
-                            bases_passed,
-                            bases_failed,
-                        });
-                    }
-                }
-            }
-        }
+        Ok(points)
+    }
 
-        points.sort_by_key(|p| p.seconds);
-        points.dedup_by_key(|p| p.seconds);
+    /// Opens the acquisition output stream and yields the cumulative yield
+    /// history carried by each message.
+    ///
+    /// The first message covers the run so far; later messages carry the
+    /// buckets MinKNOW has updated since.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_yield_history(
+        &mut self,
+        run_id: &str,
+    ) -> Result<impl futures::Stream<Item = Result<Vec<YieldDataPoint>, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
 
-        Ok(points)
+        let request = StreamAcquisitionOutputRequest {
+            acquisition_run_id: run_id.to_string(),
+            ..Default::default()
+        };
+
+        let stream = self
+            .statistics
+            .stream_acquisition_output(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_acquisition_output".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| yield_points(&response))
+                .map_err(|status| ClientError::Grpc {
+                    method: "stream_acquisition_output".into(),
+                    status,
+                })
+        }))
+    }
+
+    /// Opens the acquisition output stream split by barcode and yields the
+    /// cumulative yield history of each barcode carried by each message.
+    ///
+    /// As with [`Self::stream_yield_history`], the first message covers the
+    /// run so far and later ones carry only updated buckets. Runs without
+    /// barcoding report a single group.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_barcode_yield(
+        &mut self,
+        run_id: &str,
+    ) -> Result<impl futures::Stream<Item = Result<Vec<BarcodeYield>, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
+
+        let request = StreamAcquisitionOutputRequest {
+            acquisition_run_id: run_id.to_string(),
+            split: Some(AcquisitionOutputSplit {
+                barcode_name: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let stream = self
+            .statistics
+            .stream_acquisition_output(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_acquisition_output".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| barcode_yields(&response))
+                .map_err(|status| ClientError::Grpc {
+                    method: "stream_acquisition_output".into(),
+                    status,
+                })
+        }))
     }
 
+    #[allow(clippy::result_large_err)]
     pub async fn stream_duty_time(
         &mut self,
         run_id: &str,
@@ -559,19 +1108,22 @@ impl PositionClient {
                     }
 
                     let mut state_times: HashMap<ChannelState, u64> = HashMap::new();
+                    snapshot.buckets = response
+                        .bucket_ranges
+                        .iter()
+                        .map(|range| DutyTimeBucket {
+                            start: range.start,
+                            end: range.end,
+                            state_times: HashMap::new(),
+                        })
+                        .collect();
                     for (name, data) in &response.channel_states {
-                        let state = match name.as_str() {
-                            "strand" | "sequencing" => ChannelState::Strand,
-                            "pore" | "single_pore" => ChannelState::Pore,
-                            "adapter" => ChannelState::Adapter,
-                            "unavailable" | "inactive" | "saturated" | "zero" | "multiple" => {
-                                ChannelState::Unavailable
-                            }
-                            "unblock" | "unblocking" => ChannelState::Unblock,
-                            _ => ChannelState::Other,
-                        };
+                        let state = ChannelState::from_name(name);
                         let total: u64 = data.state_times.iter().sum();
                         *state_times.entry(state).or_insert(0) += total;
+                        for (bucket, &time) in snapshot.buckets.iter_mut().zip(&data.state_times) {
+                            *bucket.state_times.entry(state).or_insert(0) += time;
+                        }
                     }
                     snapshot.state_times = state_times;
                     snapshot.pore_occupancy = response.pore_occupancy;
@@ -585,28 +1137,48 @@ impl PositionClient {
         }))
     }
 
+    /// Streams the read length histogram, which MinKNOW resends every
+    /// `poll_interval` (rounded to whole seconds). With `weight_by_bases`
+    /// each bucket sums the bases of its reads instead of counting them.
+    #[allow(clippy::result_large_err)]
     pub async fn stream_read_length_histogram(
         &mut self,
         run_id: &str,
-        exclude_outliers: bool,
-        range: Option<(u64, u64)>,
+        options: HistogramOptions,
+        poll_interval: Duration,
     ) -> Result<impl futures::Stream<Item = Result<ReadLengthHistogram, ClientError>>, ClientError>
     {
         use futures::StreamExt;
 
-        let outlier_percent = if exclude_outliers { 0.01 } else { 0.0 };
+        let HistogramOptions {
+            exclude_outliers,
+            weight_by_bases,
+            range,
+            ..
+        } = options;
+        // MinKNOW takes the part to discard as a fraction.
+        let outlier_percent = if exclude_outliers {
+            options.outlier_percent / 100.0
+        } else {
+            0.0
+        };
 
         let data_selection = range.map(|(min, max)| DataSelection {
             start: min as i64,
             end: max as i64,
-            step: 0,
+            step: options.step(),
         });
 
         let request = StreamReadLengthHistogramRequest {
             acquisition_run_id: run_id.to_string(),
             read_length_type: ReadLengthType::EstimatedBases as i32,
             discard_outlier_percent: outlier_percent,
-            poll_time_seconds: 30,
+            bucket_value_type: if weight_by_bases {
+                BucketValueType::ReadLengths
+            } else {
+                BucketValueType::ReadCounts
+            } as i32,
+            poll_time_seconds: poll_seconds(poll_interval),
             data_selection,
             ..Default::default()
         };
@@ -642,6 +1214,8 @@ impl PositionClient {
                         n50,
                         outliers_excluded: exclude_outliers,
                         outlier_percent,
+                        weighted_by_bases: response.bucket_value_type
+                            == BucketValueType::ReadLengths as i32,
                         requested_range: range,
                         source_data_end: response.source_data_end,
                     }
@@ -653,6 +1227,304 @@ impl PositionClient {
         }))
     }
 
+    /// Streams the distribution of mean read q-scores, resent every
+    /// `poll_interval`. Fails with `FAILED_PRECONDITION` when basecalling is
+    /// off.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_qscore_histogram(
+        &mut self,
+        run_id: &str,
+        poll_interval: Duration,
+    ) -> Result<impl futures::Stream<Item = Result<QScoreHistogram, ClientError>>, ClientError>
+    {
+        use crate::proto::minknow_api::statistics::q_score_histogram_key::ReadType;
+        use futures::StreamExt;
+
+        let request = StreamQScoreHistogramRequest {
+            acquisition_run_id: run_id.to_string(),
+            poll_time_seconds: poll_seconds(poll_interval),
+            ..Default::default()
+        };
+
+        let stream = self
+            .statistics
+            .stream_q_score_histogram(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_q_score_histogram".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| {
+                    // With duplex basecalling there is one histogram per read
+                    // type; the overall one covers them all.
+                    let data = response
+                        .histogram_data
+                        .iter()
+                        .find(|data| {
+                            data.filtering
+                                .iter()
+                                .all(|key| key.read_type == ReadType::All as i32)
+                        })
+                        .or(response.histogram_data.first());
+
+                    QScoreHistogram {
+                        bucket_ranges: response
+                            .bucket_ranges
+                            .iter()
+                            .map(|r| (r.start, r.end))
+                            .collect(),
+                        bucket_values: data.map(|d| d.bucket_values.clone()).unwrap_or_default(),
+                        modal_q_score: data.map_or(0.0, |d| d.modal_q_score),
+                    }
+                })
+                .map_err(|status| ClientError::Grpc {
+                    method: "stream_q_score_histogram".into(),
+                    status,
+                })
+        }))
+    }
+
+    /// Streams the translocation speed of the run in ten-minute stretches,
+    /// oldest first. Every message carries the whole run so far. Needs
+    /// basecalling.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_translocation_speed(
+        &mut self,
+        run_id: &str,
+    ) -> Result<impl futures::Stream<Item = Result<Vec<SpeedDataPoint>, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
+
+        const DATASET_MINUTES: u32 = 10;
+        let request = StreamBoxplotRequest {
+            acquisition_run_id: run_id.to_string(),
+            data_type: stream_boxplot_request::BoxplotType::BasesPerSecond as i32,
+            dataset_width: DATASET_MINUTES,
+            poll_time: 60,
+        };
+
+        let stream = self
+            .statistics
+            .stream_basecall_boxplots(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_basecall_boxplots".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| {
+                    response
+                        .datasets
+                        .iter()
+                        .enumerate()
+                        .map(|(i, dataset)| SpeedDataPoint {
+                            seconds: (i as u32 + 1) * DATASET_MINUTES * 60,
+                            median: dataset.q50,
+                            q25: dataset.q25,
+                            q75: dataset.q75,
+                        })
+                        .collect()
+                })
+                .map_err(|status| ClientError::Grpc {
+                    method: "stream_basecall_boxplots".into(),
+                    status,
+                })
+        }))
+    }
+
+    /// Streams the run-until criteria that stop the acquisition: the current
+    /// ones first, then the new set whenever they are changed.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_run_until_targets(
+        &mut self,
+        run_id: &str,
+    ) -> Result<impl futures::Stream<Item = Result<RunUntilCriteria, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
+
+        let request = StreamTargetCriteriaRequest {
+            acquisition_run_id: run_id.to_string(),
+        };
+
+        let stream = self
+            .run_until
+            .stream_target_criteria(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_target_criteria".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|response| run_until_criteria(response.stop_criteria.as_ref()))
+                .map_err(|status| ClientError::Grpc {
+                    method: "stream_target_criteria".into(),
+                    status,
+                })
+        }))
+    }
+
+    /// Streams the run's progress towards its run-until criteria, as
+    /// reported by the run-until script. Updates that carry no progress are
+    /// skipped.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_run_until_progress(
+        &mut self,
+        run_id: &str,
+    ) -> Result<impl futures::Stream<Item = Result<RunUntilCriteria, ClientError>>, ClientError>
+    {
+        use futures::StreamExt;
+
+        let request = StreamUpdatesRequest {
+            acquisition_run_id: run_id.to_string(),
+            ..Default::default()
+        };
+
+        let stream = self
+            .run_until
+            .stream_updates(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_updates".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.filter_map(|result| async move {
+            match result {
+                Ok(response) => {
+                    let progress = response.update?.current_progress_update?;
+                    Some(Ok(run_until_criteria(Some(&progress))))
+                }
+                Err(status) => Some(Err(ClientError::Grpc {
+                    method: "stream_updates".into(),
+                    status,
+                })),
+            }
+        }))
+    }
+
+    /// Streams the messages MinKNOW shows its users for this position, such
+    /// as warnings and the reasons a run paused. With `include_old`, the
+    /// messages MinKNOW kept from before the call come first. The stream
+    /// stays open for new messages.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_user_messages(
+        &mut self,
+        include_old: bool,
+    ) -> Result<impl futures::Stream<Item = Result<UserMessage, ClientError>>, ClientError> {
+        use futures::StreamExt;
+
+        let stream = self
+            .log
+            .get_user_messages(GetUserMessagesRequest {
+                include_old_messages: include_old,
+            })
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "get_user_messages".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(|result| {
+            result
+                .map(|message| UserMessage {
+                    time: message.time.and_then(timestamp_to_utc),
+                    severity: match message.severity() {
+                        Severity::MessageSeverityTrace => MessageSeverity::Trace,
+                        Severity::MessageSeverityInfo => MessageSeverity::Info,
+                        Severity::MessageSeverityWarning => MessageSeverity::Warning,
+                        Severity::MessageSeverityError => MessageSeverity::Error,
+                    },
+                    identifier: message.identifier,
+                    message: message.user_message,
+                    extra_data: message.extra_data.into_iter().collect(),
+                })
+                .map_err(|status| ClientError::Grpc {
+                    method: "get_user_messages".into(),
+                    status,
+                })
+        }))
+    }
+
+    /// Replaces the standard run-until criteria that stop the acquisition.
+    ///
+    /// MinKNOW replaces all criteria on every write, so the current ones are
+    /// read first and pause criteria and criteria of custom run-until
+    /// scripts are kept.
+    pub async fn set_run_until_targets(
+        &mut self,
+        run_id: &str,
+        targets: &RunUntilCriteria,
+    ) -> Result<(), ClientError> {
+        let current = self
+            .run_until
+            .stream_target_criteria(StreamTargetCriteriaRequest {
+                acquisition_run_id: run_id.to_string(),
+            })
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_target_criteria".into(),
+                status,
+            })?
+            .into_inner()
+            .message()
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "stream_target_criteria".into(),
+                status,
+            })?
+            .unwrap_or_default();
+
+        let mut stop = current.stop_criteria.unwrap_or_default();
+        for (name, value) in [
+            ("runtime", targets.runtime.map(|v| pack_criterion(&v))),
+            (
+                "estimated_bases",
+                targets.estimated_bases.map(|v| pack_criterion(&v)),
+            ),
+            (
+                "passed_basecalled_bases",
+                targets.passed_basecalled_bases.map(|v| pack_criterion(&v)),
+            ),
+            (
+                "available_pores",
+                targets.available_pores.map(|v| pack_criterion(&v)),
+            ),
+        ] {
+            match value {
+                Some(value) => stop.criteria.insert(name.to_string(), value),
+                None => stop.criteria.remove(name),
+            };
+        }
+
+        let request = WriteTargetCriteriaRequest {
+            acquisition_run_id: run_id.to_string(),
+            pause_criteria: current.pause_criteria,
+            stop_criteria: Some(stop),
+        };
+        super::metrics::timed(
+            "write_target_criteria",
+            self.run_until.write_target_criteria(request),
+        )
+        .await
+        .map_err(|status| ClientError::Grpc {
+            method: "write_target_criteria".into(),
+            status,
+        })?;
+        Ok(())
+    }
+
     pub async fn get_mean_quality(&mut self, run_id: &str) -> Result<Option<f32>, ClientError> {
         use futures::StreamExt;
 
@@ -752,12 +1624,59 @@ impl PositionClient {
         })
     }
 
+    /// Streams live calibrated signal for one channel (1-based) until the
+    /// stream is dropped.
+    #[allow(clippy::result_large_err)]
+    pub async fn stream_signal(
+        &mut self,
+        channel: u32,
+    ) -> Result<impl futures::Stream<Item = Result<SignalChunk, ClientError>>, ClientError> {
+        use futures::StreamExt;
+
+        let request = GetSignalBytesRequest {
+            first_channel: channel,
+            last_channel: channel,
+            calibrated_data: true,
+            ..Default::default()
+        };
+
+        let stream = self
+            .data
+            .get_signal_bytes(request)
+            .await
+            .map_err(|status| ClientError::Grpc {
+                method: "get_signal_bytes".into(),
+                status,
+            })?
+            .into_inner();
+
+        Ok(stream.map(move |result| {
+            result
+                .map(|response| {
+                    let data = response
+                        .channels
+                        .first()
+                        .map(|c| c.data.as_slice())
+                        .unwrap_or_default();
+                    SignalChunk::decode(channel, response.samples_since_start, data)
+                })
+                .map_err(|status| ClientError::Grpc {
+                    method: "get_signal_bytes".into(),
+                    status,
+                })
+        }))
+    }
+
     pub async fn get_channel_layout(&mut self) -> Result<super::ChannelLayout, ClientError> {
         use std::collections::BTreeSet;
 
         let response = self
-            .device
-            .get_channels_layout(GetChannelsLayoutRequest {})
+            .unary(
+                "get_channels_layout",
+                &self.device,
+                GetChannelsLayoutRequest {},
+                |mut s, r| async move { s.get_channels_layout(r).await },
+            )
             .await
             .map_err(|status| ClientError::Grpc {
                 method: "get_channels_layout".into(),
@@ -819,9 +1738,207 @@ impl std::fmt::Debug for PositionClient {
 pub struct AcquisitionInfo {
     pub run_id: String,
     pub state: RunState,
+    pub start_time: Option<DateTime<Utc>>,
     pub reads_processed: u64,
     pub reads_passed: u64,
     pub reads_failed: u64,
     pub bases_passed: u64,
     pub bases_failed: u64,
 }
+
+/// Reads the standard criteria from a run-until criteria map. Values may be
+/// packed as any numeric wrapper type; others are ignored.
+fn run_until_criteria(values: Option<&CriteriaValues>) -> RunUntilCriteria {
+    let number = |name: &str| -> Option<f64> {
+        let any = values?.criteria.get(name)?;
+        any.to_msg::<u64>()
+            .map(|v| v as f64)
+            .or_else(|_| any.to_msg::<i64>().map(|v| v as f64))
+            .or_else(|_| any.to_msg::<u32>().map(f64::from))
+            .or_else(|_| any.to_msg::<f64>())
+            .or_else(|_| any.to_msg::<f32>().map(f64::from))
+            .ok()
+    };
+    RunUntilCriteria {
+        runtime: number("runtime").map(|v| v as u64),
+        estimated_bases: number("estimated_bases").map(|v| v as u64),
+        passed_basecalled_bases: number("passed_basecalled_bases").map(|v| v as u64),
+        available_pores: number("available_pores").map(|v| v as f32),
+    }
+}
+
+/// Packs a criterion value as its protobuf wrapper type (e.g.
+/// `google.protobuf.UInt64Value`), the way MinKNOW's own clients do.
+fn pack_criterion<M: prost::Name>(value: &M) -> prost_types::Any {
+    prost_types::Any {
+        type_url: M::type_url(),
+        value: prost::Message::encode_to_vec(value),
+    }
+}
+
+/// Extracts yield data points from an acquisition output message, oldest first.
+fn yield_points(response: &StreamAcquisitionOutputResponse) -> Vec<YieldDataPoint> {
+    sorted_points(
+        response
+            .snapshots
+            .iter()
+            .flat_map(|filtered| &filtered.snapshots),
+    )
+}
+
+/// Extracts per-barcode yield from an acquisition output message split by
+/// barcode name.
+fn barcode_yields(response: &StreamAcquisitionOutputResponse) -> Vec<BarcodeYield> {
+    response
+        .snapshots
+        .iter()
+        .map(|filtered| BarcodeYield {
+            barcode: filtered
+                .filtering
+                .first()
+                .map(|key| key.barcode_name.clone())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "unclassified".to_string()),
+            points: sorted_points(&filtered.snapshots),
+        })
+        .collect()
+}
+
+fn sorted_points<'a>(
+    snapshots: impl IntoIterator<Item = &'a AcquisitionOutputSnapshot>,
+) -> Vec<YieldDataPoint> {
+    let mut points: Vec<YieldDataPoint> = snapshots
+        .into_iter()
+        .filter_map(|snapshot| {
+            let yield_summary = snapshot.yield_summary.as_ref()?;
+            let reads_passed = yield_summary.basecalled_pass_read_count as u64;
+            let reads_failed = yield_summary.basecalled_fail_read_count as u64;
+            let bases_passed = yield_summary.basecalled_pass_bases as u64;
+            let bases_failed = yield_summary.basecalled_fail_bases as u64;
+
+            Some(YieldDataPoint {
+                seconds: snapshot.seconds,
+                reads: reads_passed + reads_failed,
+                bases: bases_passed + bases_failed,
+                reads_passed,
+                reads_failed,
+                bases_passed,
+                bases_failed,
+            })
+        })
+        .collect();
+
+    points.sort_by_key(|p| p.seconds);
+    points.dedup_by_key(|p| p.seconds);
+    points
+}
+
+/// Extracts the identifying details of a protocol run.
+fn run_info(info: &ProtocolRunInfo) -> super::RunInfo {
+    let user_info = info.user_info.as_ref();
+
+    super::RunInfo {
+        run_id: Some(info.run_id.clone()).filter(|s| !s.is_empty()),
+        experiment_group: user_info
+            .and_then(|ui| ui.protocol_group_id.clone())
+            .filter(|s| !s.is_empty()),
+        sample_id: user_info
+            .and_then(|ui| ui.sample_id.clone())
+            .filter(|s| !s.is_empty()),
+        protocol_name: info
+            .meta_info
+            .as_ref()
+            .map(|meta| meta.name.clone())
+            .filter(|s| !s.is_empty())
+            .or_else(|| Some(info.protocol_id.clone()).filter(|s| !s.is_empty())),
+        flow_cell_product_code: info
+            .flow_cell
+            .as_ref()
+            .map(|fc| fc.product_code.clone())
+            .filter(|s| !s.is_empty())
+            .or_else(|| user_info.and_then(|ui| ui.user_specified_product_code.clone()))
+            .filter(|s| !s.is_empty()),
+        start_time: info.start_time.and_then(timestamp_to_utc),
+        end_time: info.end_time.and_then(timestamp_to_utc),
+    }
+}
+
+/// Returns why a protocol run ended, or `None` if it hasn't.
+/// Summarizes a protocol run, or returns `None` while it is still running.
+fn finished_run(protocol_run: &ProtocolRunInfo) -> Option<RunSummary> {
+    let end_reason = match ProtocolState::try_from(protocol_run.state) {
+        Ok(state) => protocol_end_reason(state)?,
+        Err(_) => "Unknown",
+    };
+    Some(RunSummary {
+        info: run_info(protocol_run),
+        protocol_id: protocol_run.protocol_id.clone(),
+        end_reason: end_reason.to_string(),
+        acquisition_run_id: protocol_run.acquisition_run_ids.last().cloned(),
+        ..Default::default()
+    })
+}
+
+fn protocol_end_reason(state: ProtocolState) -> Option<&'static str> {
+    Some(match state {
+        ProtocolState::ProtocolRunning
+        | ProtocolState::ProtocolWaitingForTemperature
+        | ProtocolState::ProtocolWaitingForAcquisition
+        | ProtocolState::ProtocolWaitingForResource => return None,
+        ProtocolState::ProtocolCompleted => "Completed",
+        ProtocolState::ProtocolStoppedByUser => "Stopped by user",
+        ProtocolState::ProtocolFinishedWithError => "Error",
+        ProtocolState::ProtocolFinishedWithDeviceError => "Device error",
+        ProtocolState::ProtocolFinishedUnableToSendTelemetry => "Telemetry error",
+        ProtocolState::ProtocolFinishedWithFlowCellDisconnect => "Flow cell disconnected",
+        ProtocolState::ProtocolFinishedWithDeviceDisconnect => "Device disconnected",
+        ProtocolState::ProtocolFinishedWithErrorCalibration => "Calibration error",
+        ProtocolState::ProtocolFinishedWithErrorBasecallSettings => "Invalid basecall settings",
+        ProtocolState::ProtocolFinishedWithErrorTemperatureRequired => "Temperature required",
+        ProtocolState::ProtocolFinishedWithErrorNoDiskSpace => "No disk space",
+        ProtocolState::ProtocolFinishedWithErrorTemperatureOutOfRange => "Temperature out of range",
+        ProtocolState::ProtocolFinishedWithErrorBasecallerCommunication => "Basecaller error",
+        ProtocolState::ProtocolFinishedWithNoFlowcellForAcquisition => "No flow cell",
+        ProtocolState::ProtocolFinishedWithErrorBasecallerUnavailable => "Basecaller unavailable",
+    })
+}
+
+/// Converts a protobuf timestamp into a UTC `DateTime`.
+fn timestamp_to_utc(ts: prost_types::Timestamp) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32)
+}
+
+/// Returns a short display label for a protocol phase.
+fn phase_label(phase: ProtocolPhase) -> &'static str {
+    match phase {
+        ProtocolPhase::PhaseUnknown => "Unknown",
+        ProtocolPhase::PhaseInitialising => "Initialising",
+        ProtocolPhase::PhaseSequencing => "Sequencing",
+        ProtocolPhase::PhasePreparingForMuxScan => "Preparing for pore scan",
+        ProtocolPhase::PhaseMuxScan => "Pore scan",
+        ProtocolPhase::PhasePaused => "Paused",
+        ProtocolPhase::PhasePausing => "Pausing",
+        ProtocolPhase::PhaseResuming => "Resuming",
+        ProtocolPhase::PhaseCompleted => "Completed",
+        ProtocolPhase::PhaseBadTemperatureAutomaticPause => "Paused (temperature)",
+        ProtocolPhase::PhaseFlowcellDisconnectAutomaticPause => "Paused (flow cell disconnected)",
+        ProtocolPhase::PhaseFlowcellMismatchAutomaticPause => "Paused (flow cell mismatch)",
+        ProtocolPhase::PhaseDeviceErrorAutomaticPause => "Paused (device error)",
+        ProtocolPhase::PhaseLowDiskSpaceAutomaticPause => "Paused (low disk space)",
+    }
+}
+
+/// Classifies a protocol phase for the yield chart markers. Only the start
+/// of a scan or pause is marked, not the phases leading into it.
+fn phase_kind(phase: ProtocolPhase) -> PhaseKind {
+    match phase {
+        ProtocolPhase::PhaseMuxScan => PhaseKind::PoreScan,
+        ProtocolPhase::PhaseBadTemperatureAutomaticPause => PhaseKind::TemperaturePause,
+        ProtocolPhase::PhasePaused
+        | ProtocolPhase::PhaseFlowcellDisconnectAutomaticPause
+        | ProtocolPhase::PhaseFlowcellMismatchAutomaticPause
+        | ProtocolPhase::PhaseDeviceErrorAutomaticPause
+        | ProtocolPhase::PhaseLowDiskSpaceAutomaticPause => PhaseKind::Pause,
+        _ => PhaseKind::Other,
+    }
+}
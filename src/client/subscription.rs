@@ -0,0 +1,383 @@
+//! Long-lived statistics stream subscriptions.
+//!
+//! MinKNOW's statistics RPCs are server-streaming: the first message carries
+//! everything so far and later messages arrive as MinKNOW publishes them. A
+//! [`RunSubscription`] keeps the acquisition output (overall and per barcode),
+//! duty time, read length histogram, q-score histogram, translocation speed,
+//! and run-until streams for one acquisition run open and forwards every
+//! message over a channel. It can also carry the live raw signal of one channel
+//! while the signal chart is open. Streams that end or fail are reopened after
+//! [`RETRY_DELAY`]; dropping the subscription closes them.
+
+use super::{
+    BarcodeYield, ClientError, DutyTimeSnapshot, PositionClient, QScoreHistogram,
+    ReadLengthHistogram, RunUntilCriteria, SignalChunk, SpeedDataPoint, YieldDataPoint,
+};
+use futures::{Stream, StreamExt};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Delay before a stream that ended or failed is reopened.
+pub const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How often MinKNOW resends the read length and q-score histograms unless
+/// configured otherwise.
+pub const DEFAULT_HISTOGRAM_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Percent of the longest reads left out of the read length histogram
+/// while outliers are excluded, unless configured otherwise.
+pub const DEFAULT_OUTLIER_PERCENT: f32 = 1.0;
+
+/// Read length histogram request options.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistogramOptions {
+    pub exclude_outliers: bool,
+    /// Percent of the longest reads left out while `exclude_outliers` is
+    /// set.
+    pub outlier_percent: f32,
+    /// Weights buckets by bases rather than reads.
+    pub weight_by_bases: bool,
+    pub range: Option<(u64, u64)>,
+    /// Buckets to split `range` into; MinKNOW picks the width when unset
+    /// or without a range.
+    pub buckets: Option<u32>,
+}
+
+impl Default for HistogramOptions {
+    fn default() -> Self {
+        Self {
+            exclude_outliers: false,
+            outlier_percent: DEFAULT_OUTLIER_PERCENT,
+            weight_by_bases: false,
+            range: None,
+            buckets: None,
+        }
+    }
+}
+
+impl HistogramOptions {
+    /// The `data_selection` step that splits `range` into `buckets`.
+    pub fn step(&self) -> u64 {
+        match (self.range, self.buckets) {
+            (Some((min, max)), Some(buckets)) => max.saturating_sub(min).div_ceil(buckets.into()),
+            _ => 0,
+        }
+    }
+}
+
+/// Payload of a [`StreamUpdate`].
+#[derive(Debug, Clone)]
+pub enum StreamData {
+    YieldHistory(Vec<YieldDataPoint>),
+    BarcodeYield(Vec<BarcodeYield>),
+    DutyTime(DutyTimeSnapshot),
+    Histogram(ReadLengthHistogram),
+    QScoreHistogram(QScoreHistogram),
+    TranslocationSpeed(Vec<SpeedDataPoint>),
+    RunUntilTargets(RunUntilCriteria),
+    RunUntilProgress(RunUntilCriteria),
+    Signal(SignalChunk),
+}
+
+/// A message received on one of a run's streams.
+#[derive(Debug, Clone)]
+pub struct StreamUpdate {
+    pub position: String,
+    pub run_id: String,
+    /// Set on the first message after a stream is (re)opened, which carries
+    /// the full history rather than an increment.
+    pub initial: bool,
+    pub data: StreamData,
+}
+
+/// Open statistics streams for one acquisition run on one position.
+#[derive(Debug)]
+pub struct RunSubscription {
+    run_id: String,
+    histogram_options: HistogramOptions,
+    histogram_interval: Duration,
+    output: JoinHandle<()>,
+    barcodes: JoinHandle<()>,
+    duty_time: JoinHandle<()>,
+    histogram: JoinHandle<()>,
+    qscore: JoinHandle<()>,
+    speed: JoinHandle<()>,
+    run_until_targets: JoinHandle<()>,
+    run_until_progress: JoinHandle<()>,
+    /// Channel (1-based) whose signal is streamed, with its task.
+    signal: Option<(u32, JoinHandle<()>)>,
+}
+
+impl RunSubscription {
+    /// Opens all streams for `run_id`, sending updates to `tx`. The
+    /// histograms are resent every `histogram_interval`.
+    pub fn start(
+        client: &PositionClient,
+        run_id: &str,
+        histogram_options: HistogramOptions,
+        histogram_interval: Duration,
+        tx: mpsc::UnboundedSender<StreamUpdate>,
+    ) -> Self {
+        let sink = Sink::new(client, run_id, tx);
+        let output = Self::spawn_output(client, sink.clone());
+        let barcodes = Self::spawn_barcodes(client, sink.clone());
+        let duty_time = Self::spawn_duty_time(client, sink.clone());
+        let histogram =
+            Self::spawn_histogram(client, sink.clone(), histogram_options, histogram_interval);
+        let qscore = Self::spawn_qscore(client, sink.clone(), histogram_interval);
+        let speed = Self::spawn_speed(client, sink.clone());
+        let run_until_targets = Self::spawn_run_until_targets(client, sink.clone());
+        let run_until_progress = Self::spawn_run_until_progress(client, sink);
+
+        Self {
+            run_id: run_id.to_string(),
+            histogram_options,
+            histogram_interval,
+            output,
+            barcodes,
+            duty_time,
+            histogram,
+            qscore,
+            speed,
+            run_until_targets,
+            run_until_progress,
+            signal: None,
+        }
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    pub fn histogram_options(&self) -> HistogramOptions {
+        self.histogram_options
+    }
+
+    /// Reopens only the histogram stream with new request options.
+    pub fn set_histogram_options(
+        &mut self,
+        client: &PositionClient,
+        options: HistogramOptions,
+        tx: mpsc::UnboundedSender<StreamUpdate>,
+    ) {
+        if options == self.histogram_options {
+            return;
+        }
+        self.histogram.abort();
+        let sink = Sink::new(client, &self.run_id, tx);
+        self.histogram = Self::spawn_histogram(client, sink, options, self.histogram_interval);
+        self.histogram_options = options;
+    }
+
+    pub fn signal_channel(&self) -> Option<u32> {
+        self.signal.as_ref().map(|(channel, _)| *channel)
+    }
+
+    /// Starts streaming the raw signal of `channel`, replacing any other
+    /// channel, or stops the signal stream for `None`.
+    pub fn set_signal_channel(
+        &mut self,
+        client: &PositionClient,
+        channel: Option<u32>,
+        tx: mpsc::UnboundedSender<StreamUpdate>,
+    ) {
+        if channel == self.signal_channel() {
+            return;
+        }
+        if let Some((_, task)) = self.signal.take() {
+            task.abort();
+        }
+        if let Some(channel) = channel {
+            let sink = Sink::new(client, &self.run_id, tx);
+            self.signal = Some((channel, Self::spawn_signal(client, sink, channel)));
+        }
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_output(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_yield_history(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::YieldHistory)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_barcodes(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_barcode_yield(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::BarcodeYield)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_duty_time(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_duty_time(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::DutyTime)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_histogram(
+        client: &PositionClient,
+        sink: Sink,
+        options: HistogramOptions,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), move |mut c, run_id| async move {
+            c.stream_read_length_histogram(&run_id, options, interval)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::Histogram)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_qscore(client: &PositionClient, sink: Sink, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), move |mut c, run_id| async move {
+            c.stream_qscore_histogram(&run_id, interval)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::QScoreHistogram)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_speed(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_translocation_speed(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::TranslocationSpeed)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_run_until_targets(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_run_until_targets(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::RunUntilTargets)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_run_until_progress(client: &PositionClient, sink: Sink) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), |mut c, run_id| async move {
+            c.stream_run_until_progress(&run_id)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::RunUntilProgress)).boxed())
+        }))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn spawn_signal(client: &PositionClient, sink: Sink, channel: u32) -> JoinHandle<()> {
+        tokio::spawn(sink.run(client.clone(), move |mut c, _run_id| async move {
+            c.stream_signal(channel)
+                .await
+                .map(|s| s.map(|r| r.map(StreamData::Signal)).boxed())
+        }))
+    }
+}
+
+impl Drop for RunSubscription {
+    fn drop(&mut self) {
+        self.output.abort();
+        self.barcodes.abort();
+        self.duty_time.abort();
+        self.histogram.abort();
+        self.qscore.abort();
+        self.speed.abort();
+        self.run_until_targets.abort();
+        self.run_until_progress.abort();
+        if let Some((_, task)) = &self.signal {
+            task.abort();
+        }
+    }
+}
+
+type DataStream = futures::stream::BoxStream<'static, Result<StreamData, ClientError>>;
+
+/// Where a stream task sends its messages.
+#[derive(Clone)]
+struct Sink {
+    position: String,
+    run_id: String,
+    tx: mpsc::UnboundedSender<StreamUpdate>,
+}
+
+impl Sink {
+    fn new(client: &PositionClient, run_id: &str, tx: mpsc::UnboundedSender<StreamUpdate>) -> Self {
+        Self {
+            position: client.position.name.clone(),
+            run_id: run_id.to_string(),
+            tx,
+        }
+    }
+
+    /// Keeps a stream open, reopening it when it ends, until the receiver
+    /// is dropped.
+    async fn run<F, Fut>(self, client: PositionClient, open: F)
+    where
+        F: Fn(PositionClient, String) -> Fut,
+        Fut: std::future::Future<Output = Result<DataStream, ClientError>>,
+    {
+        loop {
+            match open(client.clone(), self.run_id.clone()).await {
+                Ok(stream) => {
+                    if !self.forward(stream).await {
+                        return;
+                    }
+                    tracing::debug!(position = %self.position, "Statistics stream ended; reopening");
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        position = %self.position,
+                        error = %e.display_message(),
+                        "Failed to open statistics stream"
+                    );
+                }
+            }
+
+            if self.tx.is_closed() {
+                return;
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+
+    /// Forwards messages until the stream ends. Returns `false` once the
+    /// receiver is gone.
+    async fn forward(&self, stream: impl Stream<Item = Result<StreamData, ClientError>>) -> bool {
+        futures::pin_mut!(stream);
+        let mut initial = true;
+
+        while let Some(item) = stream.next().await {
+            match item {
+                Ok(data) => {
+                    let update = StreamUpdate {
+                        position: self.position.clone(),
+                        run_id: self.run_id.clone(),
+                        initial,
+                        data,
+                    };
+                    if self.tx.send(update).is_err() {
+                        return false;
+                    }
+                    initial = false;
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        position = %self.position,
+                        error = %e.display_message(),
+                        "Statistics stream error"
+                    );
+                    break;
+                }
+            }
+        }
+
+        true
+    }
+}
@@ -6,7 +6,8 @@
 use crate::proto::minknow_api::device::get_device_info_response::DeviceType as ProtoDeviceType;
 use crate::proto::minknow_api::manager::{flow_cell_position, FlowCellPosition};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// A connected sequencing device.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -39,7 +40,7 @@ pub enum DeviceState {
 }
 
 /// Type of sequencing device.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum DeviceType {
     #[default]
     Unknown,
@@ -78,7 +79,7 @@ impl DeviceType {
 }
 
 /// A sequencing position on a device.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Position {
     /// Position identifier.
     pub id: String,
@@ -100,9 +101,31 @@ pub struct Position {
 
     /// Type of device (MinION, GridION, etc.).
     pub device_type: DeviceType,
+
+    /// Label of the MinKNOW host the position belongs to, set when several
+    /// hosts are watched at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
 }
 
 impl Position {
+    /// Moves the position under `host`, prefixing its name so positions
+    /// with the same name on different hosts stay distinct.
+    pub fn on_host(mut self, host: &str) -> Self {
+        self.name = format!("{}/{}", host, self.name);
+        self.host = Some(host.to_string());
+        self
+    }
+
+    /// The name MinKNOW gives the position, without any host prefix.
+    pub fn local_name(&self) -> &str {
+        self.host
+            .as_deref()
+            .and_then(|host| self.name.strip_prefix(host))
+            .and_then(|name| name.strip_prefix('/'))
+            .unwrap_or(&self.name)
+    }
+
     /// Convert from proto FlowCellPosition to domain Position.
     pub(crate) fn from_proto(proto: FlowCellPosition) -> Self {
         let state = match flow_cell_position::State::try_from(proto.state) {
@@ -136,12 +159,22 @@ impl Position {
             grpc_port,
             is_simulated: proto.is_simulated,
             device_type,
+            host: None,
         }
     }
 }
 
+/// One update from the manager's position watch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PositionChanges {
+    /// Positions that appeared or changed state.
+    pub updated: Vec<Position>,
+    /// Names of positions that went away, e.g. unplugged MinIONs.
+    pub removed: Vec<String>,
+}
+
 /// Position state.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum PositionState {
     /// Position is idle, no run active.
     #[default]
@@ -158,13 +191,28 @@ pub enum PositionState {
 }
 
 /// Protocol run information for display purposes.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RunInfo {
+    /// The protocol run ID.
+    pub run_id: Option<String>,
+
     /// The experiment/protocol group ID (user-specified when starting protocol).
     pub experiment_group: Option<String>,
 
     /// The sample ID (user-specified when starting protocol).
     pub sample_id: Option<String>,
+
+    /// Display name of the protocol, or its identifier if it has none.
+    pub protocol_name: Option<String>,
+
+    /// Product code of the flow cell the run is on, e.g. "FLO-MIN114".
+    pub flow_cell_product_code: Option<String>,
+
+    /// When the protocol run started (UTC).
+    pub start_time: Option<DateTime<Utc>>,
+
+    /// When the protocol run ended (UTC), if it has finished.
+    pub end_time: Option<DateTime<Utc>>,
 }
 
 impl RunInfo {
@@ -182,8 +230,66 @@ impl RunInfo {
     }
 }
 
+/// A finished protocol run, as listed on the run history screen.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Run ID, sample, experiment group, and start/end times.
+    pub info: RunInfo,
+
+    /// The protocol that was run.
+    pub protocol_id: String,
+
+    /// Why the protocol ended (e.g., "Completed", "Stopped by user").
+    pub end_reason: String,
+
+    /// The acquisition run that produced the sequencing data, if any.
+    pub acquisition_run_id: Option<String>,
+
+    pub reads: u64,
+    pub bases_passed: u64,
+    pub bases_failed: u64,
+
+    /// Read length N50 in bases, if MinKNOW reported one.
+    pub n50: Option<u64>,
+}
+
+/// The most recent finished run of a position, shown in the detail view
+/// once the position is idle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LastRun {
+    pub summary: RunSummary,
+    /// Cumulative yield of the run's last acquisition.
+    pub yield_history: Vec<YieldDataPoint>,
+    /// Read length histogram at the end of the run's last acquisition.
+    pub histogram: Option<ReadLengthHistogram>,
+}
+
+impl RunSummary {
+    /// Time between the start and end of the protocol run.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        Some(self.info.end_time? - self.info.start_time?)
+    }
+
+    pub fn total_bases(&self) -> u64 {
+        self.bases_passed + self.bases_failed
+    }
+}
+
+/// A protocol script that can be started on a position.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolInfo {
+    /// Identifier passed to `start_protocol` (e.g., "sequencing/sequencing_MIN114_DNA_e8_2_400K").
+    pub identifier: String,
+    /// Display name; falls back to the identifier when MinKNOW reports none.
+    pub name: String,
+    /// Protocol tags, if present.
+    pub experiment_type: Option<String>,
+    pub kit: Option<String>,
+    pub flow_cell: Option<String>,
+}
+
 /// Flow cell information for a position.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FlowCellInfo {
     pub has_flow_cell: bool,
     pub flow_cell_id: Option<String>,
@@ -192,8 +298,134 @@ pub struct FlowCellInfo {
     pub channel_count: u32,
 }
 
+impl FlowCellInfo {
+    /// Product code and ID of the inserted flow cell, e.g.
+    /// "FLO-MIN114 PAW12345", as far as they are known; `None` when there is
+    /// no flow cell or neither is known.
+    pub fn label(&self) -> Option<String> {
+        if !self.has_flow_cell {
+            return None;
+        }
+        let parts: Vec<&str> = [&self.product_code, &self.flow_cell_id]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Version of the MinKNOW installation the manager belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub major: i32,
+    pub minor: i32,
+    pub patch: i32,
+
+    /// Full core version, including any pre-release or variant suffix.
+    pub full: String,
+
+    /// Version of the distribution (e.g. the MinKNOW package release) the
+    /// core belongs to, or "unknown".
+    pub distribution: String,
+}
+
+impl VersionInfo {
+    /// Core version, followed by the distribution version when known, e.g.
+    /// "6.2.5 (distribution 24.11.8)".
+    pub fn label(&self) -> String {
+        if self.distribution.is_empty() || self.distribution == "unknown" {
+            self.full.clone()
+        } else {
+            format!("{} (distribution {})", self.full, self.distribution)
+        }
+    }
+}
+
+/// The MinKNOW manager a client is connected to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    /// Manager address, as `host:port`.
+    pub endpoint: String,
+
+    /// Whether requests carry a local auth token rather than relying on
+    /// MinKNOW's guest mode.
+    pub authenticated: bool,
+
+    /// `None` if the manager would not say.
+    pub version: Option<VersionInfo>,
+}
+
+/// Disk usage of one file-system that MinKNOW writes to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskSpaceInfo {
+    pub filesystem_id: String,
+    pub bytes_available: u64,
+    pub bytes_capacity: u64,
+
+    /// Free space MinKNOW needs to stop an experiment without losing data.
+    pub bytes_to_stop_cleanly: u64,
+
+    /// Rate at which free space is being used; 0 if unknown.
+    pub bytes_per_second: i64,
+
+    /// MinKNOW recommends alerting someone about the disk usage.
+    pub recommend_alert: bool,
+
+    /// MinKNOW recommends stopping the experiment.
+    pub recommend_stop: bool,
+}
+
+impl DiskSpaceInfo {
+    /// Free space as a percentage of capacity.
+    pub fn free_percent(&self) -> f64 {
+        if self.bytes_capacity == 0 {
+            return 100.0;
+        }
+        self.bytes_available as f64 / self.bytes_capacity as f64 * 100.0
+    }
+
+    /// Returns true if free space is below `threshold_percent` or MinKNOW
+    /// itself is warning about it.
+    pub fn is_low(&self, threshold_percent: f64) -> bool {
+        self.recommend_alert || self.recommend_stop || self.free_percent() < threshold_percent
+    }
+
+    /// Estimated time until the disk is full at the current rate of use.
+    pub fn time_until_full(&self) -> Option<std::time::Duration> {
+        if self.bytes_per_second <= 0 {
+            return None;
+        }
+        Some(std::time::Duration::from_secs(
+            self.bytes_available / self.bytes_per_second as u64,
+        ))
+    }
+}
+
+/// Where an acquisition writes its reads, and how much it has written.
+///
+/// MinKNOW reports written data in bytes; it does not count output files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutputInfo {
+    /// Directory reads are written to.
+    pub reads_directory: Option<String>,
+
+    /// Read file formats enabled for the run, e.g. "pod5", "fastq".
+    pub formats: Vec<String>,
+
+    /// Bytes written to the reads directory.
+    pub bytes_written: u64,
+
+    /// Bytes produced but not yet written.
+    pub bytes_pending: u64,
+
+    /// Bytes that could not be written to the reads directory and were
+    /// left in a fallback location instead.
+    pub bytes_failed: u64,
+}
+
 /// Acquisition run state.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum RunState {
     /// No acquisition in progress.
     #[default]
@@ -246,11 +478,14 @@ impl RunState {
 }
 
 /// A snapshot of acquisition statistics.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StatsSnapshot {
     /// Timestamp of this snapshot.
     pub timestamp: Option<DateTime<Utc>>,
 
+    /// When the current acquisition started.
+    pub acquisition_start: Option<DateTime<Utc>>,
+
     /// Total reads processed.
     pub reads_processed: u64,
 
@@ -298,7 +533,7 @@ impl StatsSnapshot {
 }
 
 /// A time-series data point for yield tracking.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct YieldDataPoint {
     /// Seconds since start of acquisition.
     pub seconds: u32,
@@ -316,13 +551,123 @@ pub struct YieldDataPoint {
     pub bases_failed: u64,
 }
 
-#[derive(Debug, Clone, Default)]
+/// Cumulative yield of one barcode in a barcoded run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BarcodeYield {
+    /// Barcode name as reported by MinKNOW (e.g., "barcode01", "unclassified").
+    pub barcode: String,
+    /// Cumulative yield over time, oldest first.
+    pub points: Vec<YieldDataPoint>,
+}
+
+impl BarcodeYield {
+    pub fn reads(&self) -> u64 {
+        self.points.last().map_or(0, |p| p.reads)
+    }
+
+    pub fn bases(&self) -> u64 {
+        self.points.last().map_or(0, |p| p.bases)
+    }
+}
+
+/// A protocol phase transition reported by MinKNOW.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PhaseEvent {
+    /// When the phase change happened (UTC).
+    pub timestamp: DateTime<Utc>,
+    /// Human-readable name of the phase entered.
+    pub phase: String,
+    /// What the phase entered means for the run.
+    #[serde(default)]
+    pub kind: PhaseKind,
+}
+
+/// Phases that interrupt sequencing, marked on the yield chart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhaseKind {
+    /// Any other phase, such as sequencing or initialising.
+    #[default]
+    Other,
+    PoreScan,
+    Pause,
+    /// Paused by MinKNOW because the flow cell is outside its temperature
+    /// range.
+    TemperaturePause,
+}
+
+impl PhaseKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PhaseKind::Other => "Other",
+            PhaseKind::PoreScan => "Pore scan",
+            PhaseKind::Pause => "Pause",
+            PhaseKind::TemperaturePause => "Temperature pause",
+        }
+    }
+}
+
+/// Severity of a MinKNOW user message, lowest first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageSeverity {
+    Trace,
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl MessageSeverity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MessageSeverity::Trace => "trace",
+            MessageSeverity::Info => "info",
+            MessageSeverity::Warning => "warning",
+            MessageSeverity::Error => "error",
+        }
+    }
+}
+
+impl std::str::FromStr for MessageSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "trace" => Ok(MessageSeverity::Trace),
+            "info" => Ok(MessageSeverity::Info),
+            "warn" | "warning" => Ok(MessageSeverity::Warning),
+            "error" => Ok(MessageSeverity::Error),
+            _ => Err("expected one of trace, info, warning, error".to_string()),
+        }
+    }
+}
+
+/// A message MinKNOW shows its users, such as why a run paused.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserMessage {
+    /// When MinKNOW emitted the message (UTC).
+    pub time: Option<DateTime<Utc>>,
+    pub severity: MessageSeverity,
+    /// Stable identifier of the kind of message, e.g.
+    /// `"protocol_paused_by_run_until"`.
+    pub identifier: String,
+    pub message: String,
+    /// Extra values attached to the message, such as the temperature that
+    /// triggered a warning.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub extra_data: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReadLengthHistogram {
     pub bucket_ranges: Vec<(u64, u64)>,
     pub bucket_values: Vec<u64>,
     pub n50: f32,
     pub outliers_excluded: bool,
     pub outlier_percent: f32,
+    /// Buckets hold the bases of their reads rather than the read count.
+    pub weighted_by_bases: bool,
     /// The requested range (min, max) if a specific range was requested, None for full range.
     pub requested_range: Option<(u64, u64)>,
     /// The actual data range end (source_data_end from response).
@@ -341,8 +686,71 @@ impl ReadLengthHistogram {
     }
 }
 
+/// Distribution of mean read q-scores.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QScoreHistogram {
+    /// Half-open q-score range `[start, end)` of each bucket.
+    pub bucket_ranges: Vec<(f32, f32)>,
+    /// Reads per bucket.
+    pub bucket_values: Vec<u64>,
+    pub modal_q_score: f32,
+}
+
+impl QScoreHistogram {
+    /// Returns the maximum bucket value for scaling.
+    pub fn max_value(&self) -> u64 {
+        self.bucket_values.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the total count across all buckets.
+    pub fn total_count(&self) -> u64 {
+        self.bucket_values.iter().sum()
+    }
+
+    /// Share of reads in buckets at or above `cutoff`, or `None` without
+    /// reads.
+    pub fn fraction_passing(&self, cutoff: f64) -> Option<f64> {
+        let total = self.total_count();
+        if total == 0 {
+            return None;
+        }
+        let passing: u64 = self
+            .bucket_ranges
+            .iter()
+            .zip(&self.bucket_values)
+            .filter(|((start, _), _)| *start as f64 >= cutoff)
+            .map(|(_, count)| count)
+            .sum();
+        Some(passing as f64 / total as f64)
+    }
+}
+
+/// Well counts from one pore (mux) scan.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MuxScanResult {
+    /// Seconds since the start of the acquisition.
+    pub seconds: u64,
+    /// Wells per category, e.g. `single_pore` or `unavailable`.
+    pub counts: std::collections::BTreeMap<String, u32>,
+}
+
+impl MuxScanResult {
+    /// Wells with a usable pore: single pores, sequencing now or held in
+    /// reserve.
+    pub fn available_pores(&self) -> u32 {
+        self.counts
+            .iter()
+            .filter(|(name, _)| {
+                let n = name.to_lowercase();
+                n.contains("single") || n.contains("reserved")
+            })
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
 /// Channel state for duty time tracking.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChannelState {
     /// Pore is sequencing (strand moving through).
     Strand,
@@ -384,8 +792,24 @@ impl ChannelState {
     }
 }
 
+impl ChannelState {
+    /// Maps a MinKNOW channel state name onto a duty time state.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "strand" | "sequencing" => ChannelState::Strand,
+            "pore" | "single_pore" => ChannelState::Pore,
+            "adapter" => ChannelState::Adapter,
+            "unavailable" | "inactive" | "saturated" | "zero" | "multiple" => {
+                ChannelState::Unavailable
+            }
+            "unblock" | "unblocking" => ChannelState::Unblock,
+            _ => ChannelState::Other,
+        }
+    }
+}
+
 /// Duty time data for channel states.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DutyTimeSnapshot {
     /// Time range for this bucket (start, end) in seconds.
     pub time_range: (u32, u32),
@@ -393,6 +817,30 @@ pub struct DutyTimeSnapshot {
     pub state_times: std::collections::HashMap<ChannelState, u64>,
     /// Pore occupancy values per channel (0.0-1.0).
     pub pore_occupancy: Vec<f32>,
+    /// State times per bucket of the run, oldest first.
+    #[serde(default)]
+    pub buckets: Vec<DutyTimeBucket>,
+}
+
+/// Time all channels spent in each state during one bucket of the run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DutyTimeBucket {
+    /// Seconds since the start of the acquisition, `[start, end)`.
+    pub start: u32,
+    pub end: u32,
+    /// Time spent in each state (in samples).
+    pub state_times: std::collections::HashMap<ChannelState, u64>,
+}
+
+impl DutyTimeBucket {
+    /// Share of the bucket's time spent in `state`; 0.0 for an empty bucket.
+    pub fn fraction(&self, state: ChannelState) -> f64 {
+        let total: u64 = self.state_times.values().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        self.state_times.get(&state).copied().unwrap_or(0) as f64 / total as f64
+    }
 }
 
 /// Pore category based on occupancy level.
@@ -497,7 +945,7 @@ impl DutyTimeSnapshot {
 }
 
 /// Physical layout of channels on the flow cell.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChannelLayout {
     pub channel_count: usize,
     pub width: u32,
@@ -506,7 +954,7 @@ pub struct ChannelLayout {
     pub coords: Vec<(u32, u32)>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ChannelStatesSnapshot {
     pub channel_count: usize,
     pub states: Vec<String>,
@@ -514,6 +962,14 @@ pub struct ChannelStatesSnapshot {
 }
 
 impl ChannelStatesSnapshot {
+    /// Whether `channel` (0-based) is currently in a strand.
+    pub fn is_sequencing(&self, channel: usize) -> bool {
+        self.states.get(channel).is_some_and(|state| {
+            let s = state.to_lowercase();
+            s.contains("strand") || s.contains("sequencing")
+        })
+    }
+
     pub fn sequencing_count(&self) -> usize {
         self.state_counts
             .iter()
@@ -567,6 +1023,94 @@ impl ChannelStatesSnapshot {
     }
 }
 
+/// Translocation speed over one stretch of the run, from the basecall
+/// speed boxplots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeedDataPoint {
+    /// Seconds since the start of the acquisition at the end of the stretch.
+    pub seconds: u32,
+    /// Median speed in bases per second.
+    pub median: f32,
+    /// Lower and upper quartiles in bases per second.
+    pub q25: f32,
+    pub q75: f32,
+}
+
+/// What happens to reads that are not basecalled yet when a protocol is
+/// stopped. MinKNOW offers no way to discard the run's data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopDataAction {
+    /// Basecalling catches up on all reads before the run ends.
+    #[default]
+    FinishProcessing,
+    /// Basecalling stops at once; reads not yet basecalled are kept as
+    /// skipped.
+    KeepAllData,
+}
+
+impl StopDataAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StopDataAction::FinishProcessing => "Finish basecalling",
+            StopDataAction::KeepAllData => "Stop basecalling, keep reads",
+        }
+    }
+}
+
+/// Options for stopping a protocol.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopOptions {
+    pub data_action: StopDataAction,
+}
+
+/// Standard run-until criteria. As targets, MinKNOW stops the run once any
+/// one of them is met; as progress, they hold the values reached so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunUntilCriteria {
+    /// Acquisition runtime in seconds.
+    pub runtime: Option<u64>,
+    /// Estimated bases, from the raw signal.
+    pub estimated_bases: Option<u64>,
+    /// Basecalled bases that pass filtering.
+    pub passed_basecalled_bases: Option<u64>,
+    /// Percentage of pores available at the latest pore scan. As a target,
+    /// it is met when availability drops below this value.
+    pub available_pores: Option<f32>,
+}
+
+impl RunUntilCriteria {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A block of calibrated raw signal for one channel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalChunk {
+    /// Channel number (1-based).
+    pub channel: u32,
+    /// Sample index of the first value since acquisition started.
+    pub samples_since_start: u64,
+    /// Current in picoamps, one value per sample.
+    pub picoamps: Vec<f32>,
+}
+
+impl SignalChunk {
+    /// Decodes the little-endian `f32` samples MinKNOW sends for calibrated
+    /// signal. A trailing partial sample is ignored.
+    pub fn decode(channel: u32, samples_since_start: u64, data: &[u8]) -> Self {
+        let picoamps = data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Self {
+            channel,
+            samples_since_start,
+            picoamps,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -609,6 +1153,27 @@ mod tests {
         assert_eq!(stats.pass_rate(), 100.0);
     }
 
+    #[test]
+    fn test_flow_cell_label() {
+        let mut info = FlowCellInfo {
+            has_flow_cell: true,
+            flow_cell_id: Some("PAW12345".into()),
+            product_code: Some("FLO-MIN114".into()),
+            ..Default::default()
+        };
+        assert_eq!(info.label().as_deref(), Some("FLO-MIN114 PAW12345"));
+
+        info.product_code = None;
+        assert_eq!(info.label().as_deref(), Some("PAW12345"));
+
+        info.flow_cell_id = None;
+        assert_eq!(info.label(), None);
+
+        info.flow_cell_id = Some("PAW12345".into());
+        info.has_flow_cell = false;
+        assert_eq!(info.label(), None);
+    }
+
     #[test]
     fn test_stats_pass_rate_all_failed() {
         let stats = StatsSnapshot {
@@ -643,4 +1208,114 @@ mod tests {
     fn test_run_state_default() {
         assert_eq!(RunState::default(), RunState::Idle);
     }
+
+    #[test]
+    fn test_disk_space_low_below_threshold() {
+        let disk = DiskSpaceInfo {
+            bytes_available: 50,
+            bytes_capacity: 1_000,
+            ..Default::default()
+        };
+        assert!((disk.free_percent() - 5.0).abs() < f64::EPSILON);
+        assert!(disk.is_low(10.0));
+        assert!(!disk.is_low(5.0));
+    }
+
+    #[test]
+    fn test_disk_space_low_when_minknow_recommends_alert() {
+        let disk = DiskSpaceInfo {
+            bytes_available: 900,
+            bytes_capacity: 1_000,
+            recommend_alert: true,
+            ..Default::default()
+        };
+        assert!(disk.is_low(10.0));
+    }
+
+    #[test]
+    fn test_disk_space_time_until_full() {
+        let disk = DiskSpaceInfo {
+            bytes_available: 3_600_000,
+            bytes_capacity: 10_000_000,
+            bytes_per_second: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            disk.time_until_full(),
+            Some(std::time::Duration::from_secs(3_600))
+        );
+        assert_eq!(
+            DiskSpaceInfo::default().time_until_full(),
+            None,
+            "unknown rate has no estimate"
+        );
+    }
+
+    #[test]
+    fn test_signal_chunk_decode() {
+        let mut data = Vec::new();
+        for value in [1.5f32, -2.0, 250.25] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(0xff);
+
+        let chunk = SignalChunk::decode(7, 4000, &data);
+        assert_eq!(chunk.channel, 7);
+        assert_eq!(chunk.samples_since_start, 4000);
+        assert_eq!(chunk.picoamps, vec![1.5, -2.0, 250.25]);
+    }
+
+    #[test]
+    fn test_qscore_fraction_passing() {
+        let histogram = QScoreHistogram {
+            bucket_ranges: vec![(7.0, 8.0), (8.0, 9.0), (9.0, 10.0), (10.0, 11.0)],
+            bucket_values: vec![10, 30, 40, 20],
+            modal_q_score: 9.5,
+        };
+        assert_eq!(histogram.total_count(), 100);
+        assert_eq!(histogram.max_value(), 40);
+        assert_eq!(histogram.fraction_passing(9.0), Some(0.6));
+        assert_eq!(histogram.fraction_passing(0.0), Some(1.0));
+        assert_eq!(QScoreHistogram::default().fraction_passing(9.0), None);
+    }
+
+    #[test]
+    fn test_duty_time_bucket_fraction() {
+        let bucket = DutyTimeBucket {
+            start: 0,
+            end: 60,
+            state_times: [(ChannelState::Strand, 300), (ChannelState::Pore, 100)]
+                .into_iter()
+                .collect(),
+        };
+        assert_eq!(bucket.fraction(ChannelState::Strand), 0.75);
+        assert_eq!(bucket.fraction(ChannelState::Unblock), 0.0);
+        assert_eq!(
+            DutyTimeBucket::default().fraction(ChannelState::Strand),
+            0.0
+        );
+        assert_eq!(
+            ChannelState::from_name("multiple"),
+            ChannelState::Unavailable
+        );
+        assert_eq!(ChannelState::from_name("unblocking"), ChannelState::Unblock);
+    }
+
+    #[test]
+    fn test_mux_scan_available_pores() {
+        let scan = MuxScanResult {
+            seconds: 5400,
+            counts: [
+                ("single_pore", 1200),
+                ("reserved_pore", 300),
+                ("unavailable", 400),
+                ("multiple", 100),
+            ]
+            .into_iter()
+            .map(|(name, count)| (name.to_string(), count))
+            .collect(),
+        };
+        assert_eq!(scan.available_pores(), 1500);
+        assert_eq!(MuxScanResult::default().available_pores(), 0);
+    }
 }
@@ -6,12 +6,16 @@
 //! 3. Config file
 //! 4. Defaults
 
+mod sources;
 mod types;
 
+pub use sources::{ConfigEntry, ConfigReport, ConfigSource, DEFAULT_CONFIG};
 pub use types::*;
 
-use crate::cli::Cli;
-use std::path::PathBuf;
+use crate::cli::{Cli, Commands};
+use crate::tui::theme::{parse_hex_color, Theme, AUTO_THEME};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 impl Config {
@@ -20,10 +24,9 @@ impl Config {
         // Start with defaults
         let mut config = Config::default();
 
-        // Load config file if it exists
-        if let Some(file_config) = Self::load_file(cli)? {
-            config.merge(file_config)?;
-        }
+        // Load config file if it exists, with the selected profile on top
+        let file_config = Self::load_file(cli)?.unwrap_or_default();
+        config.merge_with_profile(file_config, cli.profile.as_deref())?;
 
         // Apply environment variables
         config.apply_env()?;
@@ -34,57 +37,174 @@ impl Config {
         // Validate final config
         config.validate()?;
 
+        config.file = Self::file_path(cli);
+        config.notes_file = Some(
+            dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("termion/notes.toml"),
+        );
         Ok(config)
     }
 
-    fn load_file(cli: &Cli) -> Result<Option<FileConfig>, ConfigError> {
-        let path = cli
-            .config
+    /// The config file to read: `--config`, then `TERMION_CONFIG`, then
+    /// the XDG config directory.
+    pub fn file_path(cli: &Cli) -> Option<PathBuf> {
+        cli.config
             .clone()
             .or_else(|| std::env::var("TERMION_CONFIG").ok().map(PathBuf::from))
-            .or_else(|| dirs::config_dir().map(|d| d.join("termion/config.toml")));
+            .or_else(|| dirs::config_dir().map(|d| d.join("termion/config.toml")))
+    }
 
-        match path {
+    /// Reads the config file, if it exists.
+    fn read_file(cli: &Cli) -> Result<Option<(PathBuf, String)>, ConfigError> {
+        match Self::file_path(cli) {
             Some(p) if p.exists() => {
                 tracing::debug!(path = %p.display(), "Loading config file");
                 let content = std::fs::read_to_string(&p).map_err(|e| ConfigError::Read {
                     path: p.clone(),
                     source: e,
                 })?;
-                let file_config: FileConfig = toml::from_str(&content)
-                    .map_err(|e| ConfigError::Parse { path: p, source: e })?;
-                Ok(Some(file_config))
+                Ok(Some((p, content)))
             }
             _ => Ok(None),
         }
     }
 
+    fn load_file(cli: &Cli) -> Result<Option<FileConfig>, ConfigError> {
+        match Self::read_file(cli)? {
+            Some((path, content)) => {
+                let file_config: FileConfig =
+                    toml::from_str(&content).map_err(|e| ConfigError::Parse { path, source: e })?;
+                Ok(Some(file_config))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Merges a config file, then the `[profiles]` entry named `profile`
+    /// over its connection settings.
+    fn merge_with_profile(
+        &mut self,
+        mut file: FileConfig,
+        profile: Option<&str>,
+    ) -> Result<(), ConfigError> {
+        let selected = match profile {
+            Some(name) => Some(
+                file.profiles
+                    .as_mut()
+                    .and_then(|profiles| profiles.remove(name))
+                    .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?,
+            ),
+            None => None,
+        };
+        self.merge(file)?;
+        if let Some(conn) = selected {
+            self.merge_connection(conn);
+            self.connection.profile = profile.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    fn merge_connection(&mut self, conn: FileConnectionConfig) {
+        if let Some(host) = conn.host {
+            self.connection.host = host;
+        }
+        if let Some(port) = conn.port {
+            self.connection.port = port;
+        }
+        if let Some(timeout) = conn.connect_timeout {
+            self.connection.connect_timeout = Duration::from_secs(timeout);
+        }
+        if let Some(timeout) = conn.request_timeout {
+            self.connection.request_timeout = Duration::from_secs(timeout);
+        }
+        if let Some(limit) = conn.max_concurrent_positions {
+            self.connection.max_concurrent_positions = limit;
+        }
+        if let Some(path) = conn.ca_cert {
+            self.connection.ca_cert = Some(expand_tilde(&path));
+        }
+        if let Some(hosts) = conn.hosts {
+            self.connection.hosts = hosts;
+        }
+        if let Some(interval) = conn.keepalive_interval {
+            self.connection.keepalive_interval =
+                (interval > 0).then(|| Duration::from_secs(interval));
+        }
+        if let Some(timeout) = conn.keepalive_timeout {
+            self.connection.keepalive_timeout = Duration::from_secs(timeout);
+        }
+        if let Some(nodelay) = conn.tcp_nodelay {
+            self.connection.tcp_nodelay = nodelay;
+        }
+    }
+
     fn merge(&mut self, file: FileConfig) -> Result<(), ConfigError> {
         if let Some(conn) = file.connection {
-            if let Some(host) = conn.host {
-                self.connection.host = host;
-            }
-            if let Some(port) = conn.port {
-                self.connection.port = port;
-            }
-            if let Some(timeout) = conn.connect_timeout {
-                self.connection.connect_timeout = Duration::from_secs(timeout);
-            }
-            if let Some(timeout) = conn.request_timeout {
-                self.connection.request_timeout = Duration::from_secs(timeout);
-            }
+            self.merge_connection(conn);
         }
 
         if let Some(tui) = file.tui {
             if let Some(interval) = tui.refresh_interval {
                 self.tui.refresh_interval = Duration::from_millis(interval);
             }
+            if let Some(interval) = tui.idle_refresh_interval {
+                self.tui.idle_refresh_interval = Duration::from_millis(interval);
+            }
+            if let Some(interval) = tui.stats_interval {
+                self.tui.stats_interval = Duration::from_millis(interval);
+            }
+            if let Some(interval) = tui.histogram_interval {
+                self.tui.histogram_interval = Duration::from_millis(interval);
+            }
+            if let Some(buckets) = tui.histogram_buckets {
+                self.tui.histogram_buckets = (buckets > 0).then_some(buckets);
+            }
+            if let Some(percent) = tui.histogram_outlier_percent {
+                self.tui.histogram_outlier_percent = percent;
+            }
+            if let Some(interval) = tui.channel_states_interval {
+                self.tui.channel_states_interval = Duration::from_millis(interval);
+            }
             if let Some(history) = tui.chart_history {
                 self.tui.chart_history = Duration::from_secs(history);
             }
             if let Some(theme) = tui.theme {
                 self.tui.theme = theme;
             }
+            if let Some(tz) = tui.timezone {
+                self.tui.timezone = tz.parse().map_err(|_| ConfigError::InvalidTimezone(tz))?;
+            }
+            if let Some(percent) = tui.disk_warning_percent {
+                self.tui.disk_warning_percent = percent;
+            }
+            if let Some(qscore) = tui.min_qscore {
+                self.tui.min_qscore = qscore;
+            }
+            if let Some(hours) = tui.expected_run_hours {
+                self.tui.expected_run_hours = hours;
+            }
+            if let Some(pause) = tui.pause_unfocused {
+                self.tui.pause_unfocused = pause;
+            }
+            if let Some(pinned) = tui.pinned {
+                self.tui.pinned = pinned;
+            }
+            if let Some(hide) = tui.hide_simulated {
+                self.tui.hide_simulated = hide;
+            }
+            if let Some(hide) = tui.hide_idle {
+                self.tui.hide_idle = hide;
+            }
+            if let Some(clock_time) = tui.clock_time {
+                self.tui.clock_time = clock_time;
+            }
+            if let Some(highlight) = tui.highlight_changes {
+                self.tui.highlight_changes = highlight;
+            }
+            if let Some(dir) = tui.export_dir {
+                self.tui.export_dir = expand_tilde(&dir);
+            }
         }
 
         if let Some(reconnect) = file.reconnect {
@@ -110,12 +230,176 @@ impl Config {
             }
         }
 
+        if let Some(history) = file.history {
+            if let Some(enabled) = history.enabled {
+                self.history.enabled = enabled;
+            }
+            if let Some(path) = history.path {
+                self.history.path = expand_tilde(&path);
+            }
+            if let Some(interval) = history.record_interval {
+                self.history.record_interval = Duration::from_secs(interval);
+            }
+            // A limit of 0 disables that limit.
+            if let Some(days) = history.max_age_days {
+                self.history.max_age = (days > 0).then(|| Duration::from_secs(days * 24 * 3600));
+            }
+            if let Some(mb) = history.max_size_mb {
+                self.history.max_size = (mb > 0).then(|| mb * 1024 * 1024);
+            }
+        }
+
+        if let Some(storage) = file.storage {
+            if let Some(enabled) = storage.enabled {
+                self.storage.enabled = enabled;
+            }
+            if let Some(path) = storage.path {
+                self.storage.path = expand_tilde(&path);
+            }
+            if let Some(interval) = storage.record_interval {
+                self.storage.record_interval = Duration::from_secs(interval);
+            }
+            // A limit of 0 keeps runs forever.
+            if let Some(days) = storage.max_age_days {
+                self.storage.max_age = (days > 0).then(|| Duration::from_secs(days * 24 * 3600));
+            }
+        }
+
+        if let Some(alerts) = file.alerts {
+            if let Some(rules) = alerts.rules {
+                self.alerts.rules = rules
+                    .into_iter()
+                    .map(|r| r.parse().map_err(|_| ConfigError::InvalidAlertRule(r)))
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        if let Some(themes) = file.themes {
+            self.tui.themes = themes
+                .into_iter()
+                .map(|(name, theme)| user_theme(name, theme))
+                .collect::<Result<_, _>>()?;
+        }
+
+        if let Some(listen) = file.daemon.and_then(|d| d.listen) {
+            self.daemon.listen = if listen.is_empty() {
+                None
+            } else {
+                Some(
+                    listen
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidListenAddress(listen))?,
+                )
+            };
+        }
+
+        if let Some(metrics) = file.metrics {
+            if let Some(url) = metrics.url {
+                self.metrics.url = (!url.is_empty()).then_some(url);
+            }
+            if let Some(format) = metrics.format {
+                self.metrics.format = format
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidMetricsFormat(format))?;
+            }
+            if let Some(token) = metrics.token {
+                self.metrics.token = (!token.is_empty()).then_some(token);
+            }
+            if let Some(interval) = metrics.interval {
+                self.metrics.interval = Duration::from_secs(interval);
+            }
+            if let Some(measurement) = metrics.measurement {
+                self.metrics.measurement = measurement;
+            }
+        }
+
+        if let Some(notifications) = file.notifications {
+            if let Some(url) = notifications.webhook_url {
+                self.notifications.webhook_url = (!url.is_empty()).then_some(url);
+            }
+            if let Some(desktop) = notifications.desktop {
+                self.notifications.desktop = desktop;
+            }
+            if let Some(events) = notifications.events {
+                self.notifications.events = events
+                    .into_iter()
+                    .map(|e| {
+                        e.parse()
+                            .map_err(|_| ConfigError::InvalidNotificationEvent(e))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            if let Some(timeout) = notifications.timeout {
+                self.notifications.timeout = Duration::from_secs(timeout);
+            }
+            if let Some(attempts) = notifications.max_attempts {
+                self.notifications.max_attempts = attempts;
+            }
+            if let Some(email) = notifications.email {
+                self.merge_email(email)?;
+            }
+            if let Some(slack) = notifications.slack {
+                if let Some(url) = slack.webhook_url {
+                    self.notifications.slack.webhook_url = (!url.is_empty()).then_some(url);
+                }
+                if let Some(format) = slack.format {
+                    self.notifications.slack.format = Some(
+                        format
+                            .parse()
+                            .map_err(|_| ConfigError::InvalidChatFormat(format))?,
+                    );
+                }
+                if let Some(severity) = slack.min_severity {
+                    self.notifications.slack.min_severity = severity
+                        .parse()
+                        .map_err(|_| ConfigError::InvalidSeverity(severity))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn merge_email(&mut self, file: FileEmailConfig) -> Result<(), ConfigError> {
+        let email = &mut self.notifications.email;
+        let non_empty = |value: String| (!value.is_empty()).then_some(value);
+        if let Some(host) = file.smtp_host {
+            email.smtp_host = non_empty(host);
+        }
+        if let Some(port) = file.smtp_port {
+            email.smtp_port = Some(port);
+        }
+        if let Some(tls) = file.tls {
+            email.tls = tls.parse().map_err(|_| ConfigError::InvalidSmtpTls(tls))?;
+        }
+        if let Some(username) = file.username {
+            email.username = non_empty(username);
+        }
+        if let Some(password) = file.password {
+            email.password = non_empty(password);
+        }
+        if let Some(from) = file.from {
+            email.from = non_empty(from);
+        }
+        if let Some(to) = file.to {
+            email.to = to;
+        }
+        if let Some(events) = file.events {
+            email.events = events
+                .into_iter()
+                .map(|e| {
+                    e.parse()
+                        .map_err(|_| ConfigError::InvalidNotificationEvent(e))
+                })
+                .collect::<Result<_, _>>()?;
+        }
         Ok(())
     }
 
     fn apply_env(&mut self) -> Result<(), ConfigError> {
         if let Ok(host) = std::env::var("TERMION_HOST") {
             self.connection.host = host;
+            self.connection.hosts.clear();
         }
         if let Ok(port) = std::env::var("TERMION_PORT") {
             if let Ok(p) = port.parse() {
@@ -130,12 +414,18 @@ impl Config {
         if let Ok(file) = std::env::var("TERMION_LOG_FILE") {
             self.logging.file = PathBuf::from(file);
         }
+        // Keeps the SMTP password out of the config file.
+        if let Ok(password) = std::env::var("TERMION_SMTP_PASSWORD") {
+            self.notifications.email.password = (!password.is_empty()).then_some(password);
+        }
         Ok(())
     }
 
     fn apply_cli(&mut self, cli: &Cli) {
+        // An explicit host replaces the configured host list.
         if let Some(ref host) = cli.host {
             self.connection.host = host.clone();
+            self.connection.hosts.clear();
         }
         if let Some(port) = cli.port {
             self.connection.port = port;
@@ -150,6 +440,15 @@ impl Config {
         if let Some(ref log) = cli.log {
             self.logging.file = log.clone();
         }
+        if let Some(Commands::Daemon { listen: Some(addr) }) = cli.command {
+            self.daemon.listen = Some(addr);
+        }
+        self.demo = cli.demo;
+        self.record = cli.record.clone();
+        self.replay = cli.replay.as_ref().map(|path| ReplayConfig {
+            path: path.clone(),
+            speed: cli.replay_speed,
+        });
     }
 
     /// Validate the configuration.
@@ -162,60 +461,219 @@ impl Config {
             return Err(ConfigError::InvalidTimeout("connect_timeout"));
         }
 
+        if self.connection.keepalive_interval.is_some()
+            && self.connection.keepalive_timeout.is_zero()
+        {
+            return Err(ConfigError::InvalidTimeout("keepalive_timeout"));
+        }
+
+        if self.connection.max_concurrent_positions == 0 {
+            return Err(ConfigError::InvalidConcurrency);
+        }
+
+        let hosts = &self.connection.hosts;
+        for (i, entry) in hosts.iter().enumerate() {
+            if parse_host_entry(entry, self.connection.port).is_none() || hosts[..i].contains(entry)
+            {
+                return Err(ConfigError::InvalidHost(entry.clone()));
+            }
+        }
+
         let refresh = self.tui.refresh_interval.as_millis();
         if !(100..=60_000).contains(&refresh) {
             return Err(ConfigError::InvalidRefreshInterval);
         }
 
+        let idle = self.tui.idle_refresh_interval;
+        if idle < self.tui.refresh_interval || idle > Duration::from_secs(600) {
+            return Err(ConfigError::InvalidIdleRefreshInterval);
+        }
+
+        for (name, interval) in [
+            ("stats_interval", self.tui.stats_interval),
+            ("histogram_interval", self.tui.histogram_interval),
+            ("channel_states_interval", self.tui.channel_states_interval),
+        ] {
+            if !(100..=600_000).contains(&interval.as_millis()) {
+                return Err(ConfigError::InvalidFetchInterval(name));
+            }
+        }
+
+        if !(0.0..=100.0).contains(&self.tui.disk_warning_percent) {
+            return Err(ConfigError::InvalidDiskWarning);
+        }
+
+        if !(0.0..=60.0).contains(&self.tui.min_qscore) {
+            return Err(ConfigError::InvalidMinQscore);
+        }
+
+        if !(1.0..=168.0).contains(&self.tui.expected_run_hours) {
+            return Err(ConfigError::InvalidRunHours);
+        }
+
+        if let Some(buckets) = self.tui.histogram_buckets {
+            if !(2..=MAX_HISTOGRAM_BUCKETS).contains(&buckets) {
+                return Err(ConfigError::InvalidHistogramBuckets);
+            }
+        }
+
+        let outliers = self.tui.histogram_outlier_percent;
+        if !(outliers > 0.0 && outliers <= 50.0) {
+            return Err(ConfigError::InvalidOutlierPercent);
+        }
+
         if self.reconnect.multiplier <= 1.0 {
             return Err(ConfigError::InvalidMultiplier);
         }
 
+        if self.history.record_interval.is_zero() {
+            return Err(ConfigError::InvalidRecordInterval);
+        }
+
+        if self.storage.record_interval.is_zero() {
+            return Err(ConfigError::InvalidStorageInterval);
+        }
+
+        let webhooks = [
+            &self.notifications.webhook_url,
+            &self.notifications.slack.webhook_url,
+        ];
+        for url in webhooks.into_iter().flatten() {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(ConfigError::InvalidWebhookUrl(url.clone()));
+            }
+        }
+
+        if self.notifications.timeout.is_zero() {
+            return Err(ConfigError::InvalidTimeout("notifications.timeout"));
+        }
+
+        if self.notifications.max_attempts == 0 {
+            return Err(ConfigError::InvalidMaxAttempts);
+        }
+
+        if let Some(ref url) = self.metrics.url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(ConfigError::InvalidMetricsUrl(url.clone()));
+            }
+        }
+
+        if self.metrics.interval.is_zero() {
+            return Err(ConfigError::InvalidTimeout("metrics.interval"));
+        }
+
+        if self.metrics.measurement.is_empty() {
+            return Err(ConfigError::InvalidMeasurement);
+        }
+
+        let email = &self.notifications.email;
+        if email.smtp_host.is_some() {
+            if email.from.is_none() {
+                return Err(ConfigError::InvalidEmail("from is required"));
+            }
+            if email.to.is_empty() {
+                return Err(ConfigError::InvalidEmail("to needs at least one address"));
+            }
+            if email.username.is_some() != email.password.is_some() {
+                return Err(ConfigError::InvalidEmail(
+                    "username and password must be set together",
+                ));
+            }
+        }
+
         Ok(())
     }
 
+    /// Whether the TUI shows simulated or recorded data rather than a live
+    /// MinKNOW. Such sessions must not feed the history or notifications.
+    pub fn is_offline(&self) -> bool {
+        self.demo || self.replay.is_some()
+    }
+
     /// Save theme preference to the config file, preserving other settings.
-    pub fn save_theme(theme_name: &str) -> Result<(), ConfigError> {
-        let path = config_path();
+    pub fn save_theme(&self, theme_name: &str) -> Result<(), ConfigError> {
+        self.save_tui_setting("theme", theme_name.into())
+    }
 
-        let mut file_config: FileConfig = if path.exists() {
-            let content = std::fs::read_to_string(&path).map_err(|e| ConfigError::Read {
-                path: path.clone(),
-                source: e,
-            })?;
-            toml::from_str(&content).map_err(|e| ConfigError::Parse {
-                path: path.clone(),
-                source: e,
-            })?
-        } else {
-            FileConfig::default()
-        };
+    /// Save pinned positions to the config file, preserving other settings.
+    pub fn save_pinned(&self, pinned: &[String]) -> Result<(), ConfigError> {
+        self.save_tui_setting("pinned", pinned.iter().collect())
+    }
 
-        file_config
-            .tui
-            .get_or_insert_with(FileTuiConfig::default)
-            .theme = Some(theme_name.to_string());
+    /// Load position notes by name; none if the notes file doesn't exist.
+    pub fn load_notes(&self) -> Result<BTreeMap<String, String>, ConfigError> {
+        let Some(path) = self.notes_file.clone() else {
+            return Ok(BTreeMap::new());
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(ConfigError::Read { path, source: e }),
+        };
+        toml::from_str(&content).map_err(|e| ConfigError::Parse { path, source: e })
+    }
 
+    /// Save position notes to the notes file, replacing its contents.
+    pub fn save_notes(&self, notes: &BTreeMap<String, String>) -> Result<(), ConfigError> {
+        let path = self.notes_file.clone().ok_or(ConfigError::NoNotesFile)?;
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).map_err(|e| ConfigError::Write {
                 path: path.clone(),
                 source: e,
             })?;
         }
+        let content = toml::to_string(notes)?;
+        std::fs::write(&path, content).map_err(|e| ConfigError::Write { path, source: e })
+    }
 
-        let content = toml::to_string_pretty(&file_config)?;
-        std::fs::write(&path, content).map_err(|e| ConfigError::Write { path, source: e })?;
-
-        Ok(())
+    fn save_tui_setting(&self, key: &str, value: toml_edit::Value) -> Result<(), ConfigError> {
+        let path = self.file.as_deref().ok_or(ConfigError::NoConfigFile)?;
+        update_file(path, key, value)
     }
 }
 
-fn config_path() -> PathBuf {
-    std::env::var("TERMION_CONFIG")
-        .ok()
-        .map(PathBuf::from)
-        .or_else(|| dirs::config_dir().map(|d| d.join("termion/config.toml")))
-        .unwrap_or_else(|| PathBuf::from("config.toml"))
+/// Sets `key` in the `[tui]` table of the config file at `path`, creating
+/// the file if needed. The file is edited in place, so comments, layout and
+/// other settings are kept.
+fn update_file(path: &Path, key: &str, value: toml_edit::Value) -> Result<(), ConfigError> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(ConfigError::Read {
+                path: path.to_path_buf(),
+                source: e,
+            })
+        }
+    };
+    let mut document: toml_edit::DocumentMut =
+        content
+            .parse()
+            .map_err(|e: toml_edit::TomlError| ConfigError::Edit {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            })?;
+
+    let tui = document
+        .entry("tui")
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| ConfigError::Edit {
+            path: path.to_path_buf(),
+            message: "`tui` is not a table".to_string(),
+        })?;
+    tui.insert(key, toml_edit::value(value));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| ConfigError::Write {
+            path: path.to_path_buf(),
+            source: e,
+        })?;
+    }
+    std::fs::write(path, document.to_string()).map_err(|e| ConfigError::Write {
+        path: path.to_path_buf(),
+        source: e,
+    })
 }
 
 fn expand_tilde(path: &str) -> PathBuf {
@@ -227,9 +685,34 @@ fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Builds a theme from a `[themes.<name>]` table.
+fn user_theme(name: String, file: FileThemeConfig) -> Result<Theme, ConfigError> {
+    if Theme::built_in(&name).is_some() || name.eq_ignore_ascii_case(AUTO_THEME) {
+        return Err(ConfigError::ThemeNameTaken(name));
+    }
+    let base = file.base.as_deref().unwrap_or("default");
+    let mut theme = Theme::built_in(base).ok_or_else(|| ConfigError::UnknownThemeBase {
+        theme: name.clone(),
+        base: base.to_string(),
+    })?;
+    for (field, value) in file.colors {
+        let Some(slot) = theme.color_mut(&field) else {
+            return Err(ConfigError::UnknownThemeColor { theme: name, field });
+        };
+        *slot = parse_hex_color(&value).ok_or_else(|| ConfigError::InvalidThemeColor {
+            theme: name.clone(),
+            field: field.clone(),
+            value: value.clone(),
+        })?;
+    }
+    theme.name = name;
+    Ok(theme)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ratatui::style::Color;
 
     #[test]
     fn test_default_config_validates() {
@@ -253,6 +736,59 @@ mod tests {
         assert!(matches!(result, Err(ConfigError::InvalidTimeout(_))));
     }
 
+    #[test]
+    fn test_invalid_max_concurrent_positions_zero() {
+        let mut config = Config::default();
+        config.connection.max_concurrent_positions = 0;
+        let result = config.validate();
+        assert!(matches!(result, Err(ConfigError::InvalidConcurrency)));
+    }
+
+    #[test]
+    fn test_invalid_disk_warning_percent() {
+        let mut config = Config::default();
+        config.tui.disk_warning_percent = 150.0;
+        let result = config.validate();
+        assert!(matches!(result, Err(ConfigError::InvalidDiskWarning)));
+    }
+
+    #[test]
+    fn test_invalid_min_qscore() {
+        let mut config = Config::default();
+        config.tui.min_qscore = -1.0;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidMinQscore)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_expected_run_hours() {
+        let mut config = Config::default();
+        config.tui.expected_run_hours = 0.5;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidRunHours)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_histogram_settings() {
+        let mut config = Config::default();
+        config.tui.histogram_buckets = Some(5000);
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidHistogramBuckets)
+        ));
+
+        let mut config = Config::default();
+        config.tui.histogram_outlier_percent = 0.0;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidOutlierPercent)
+        ));
+    }
+
     #[test]
     fn test_invalid_refresh_interval_too_low() {
         let mut config = Config::default();
@@ -269,6 +805,31 @@ mod tests {
         assert!(matches!(result, Err(ConfigError::InvalidRefreshInterval)));
     }
 
+    #[test]
+    fn test_idle_refresh_interval_not_below_refresh_interval() {
+        let mut config = Config::default();
+        config.tui.idle_refresh_interval = Duration::from_millis(500);
+        let result = config.validate();
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidIdleRefreshInterval)
+        ));
+
+        config.tui.refresh_interval = Duration::from_millis(500);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_fetch_interval() {
+        let mut config = Config::default();
+        config.tui.histogram_interval = Duration::from_secs(3600);
+        let result = config.validate();
+        assert!(matches!(
+            result,
+            Err(ConfigError::InvalidFetchInterval("histogram_interval"))
+        ));
+    }
+
     #[test]
     fn test_invalid_multiplier_too_low() {
         let mut config = Config::default();
@@ -294,10 +855,24 @@ mod tests {
                 port: Some(9999),
                 connect_timeout: Some(10),
                 request_timeout: None,
+                max_concurrent_positions: None,
+                ca_cert: None,
+                hosts: None,
+                keepalive_interval: None,
+                keepalive_timeout: None,
+                tcp_nodelay: None,
             }),
             tui: None,
             reconnect: None,
             logging: None,
+            history: None,
+            storage: None,
+            notifications: None,
+            alerts: None,
+            daemon: None,
+            metrics: None,
+            themes: None,
+            profiles: None,
         };
         config.merge(file).unwrap();
         assert_eq!(config.connection.host, "remote-host");
@@ -306,6 +881,34 @@ mod tests {
         assert_eq!(config.connection.request_timeout, Duration::from_secs(30));
     }
 
+    #[test]
+    fn test_merge_keepalive_config() {
+        let mut config = Config::default();
+        assert_eq!(
+            config.connection.connect_options().keepalive_interval,
+            Some(Duration::from_secs(30))
+        );
+
+        let toml = r#"
+            [connection]
+            keepalive_interval = 0
+            keepalive_timeout = 0
+            tcp_nodelay = false
+        "#;
+        config.merge(toml::from_str(toml).unwrap()).unwrap();
+        let options = config.connection.connect_options();
+        assert_eq!(options.keepalive_interval, None);
+        assert!(!options.tcp_nodelay);
+        // The timeout only matters while pings are sent.
+        config.validate().unwrap();
+
+        config.connection.keepalive_interval = Some(Duration::from_secs(20));
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidTimeout("keepalive_timeout"))
+        ));
+    }
+
     #[test]
     fn test_merge_tui_config() {
         let mut config = Config::default();
@@ -313,15 +916,81 @@ mod tests {
             connection: None,
             tui: Some(FileTuiConfig {
                 refresh_interval: Some(500),
+                idle_refresh_interval: Some(10_000),
+                stats_interval: Some(2000),
+                histogram_interval: Some(60_000),
+                histogram_buckets: Some(0),
+                histogram_outlier_percent: Some(0.5),
+                channel_states_interval: None,
                 chart_history: Some(3600),
                 theme: None,
+                timezone: Some("utc".into()),
+                disk_warning_percent: Some(5.0),
+                min_qscore: Some(10.0),
+                expected_run_hours: Some(48.0),
+                pause_unfocused: Some(true),
+                pinned: Some(vec!["X1".into()]),
+                hide_simulated: Some(true),
+                hide_idle: None,
+                clock_time: Some(true),
+                highlight_changes: Some(false),
+                export_dir: Some("/tmp/exports".into()),
             }),
             reconnect: None,
             logging: None,
+            history: None,
+            storage: None,
+            notifications: None,
+            alerts: None,
+            daemon: None,
+            metrics: None,
+            themes: None,
+            profiles: None,
         };
         config.merge(file).unwrap();
         assert_eq!(config.tui.refresh_interval, Duration::from_millis(500));
+        assert_eq!(config.tui.idle_refresh_interval, Duration::from_secs(10));
+        assert_eq!(config.tui.stats_interval, Duration::from_secs(2));
+        assert_eq!(config.tui.histogram_interval, Duration::from_secs(60));
+        assert_eq!(config.tui.histogram_buckets, None);
+        assert_eq!(config.tui.histogram_outlier_percent, 0.5);
+        assert_eq!(config.tui.channel_states_interval, Duration::from_secs(5));
         assert_eq!(config.tui.chart_history, Duration::from_secs(3600));
+        assert_eq!(config.tui.timezone, DisplayTimezone::Utc);
+        assert_eq!(config.tui.disk_warning_percent, 5.0);
+        assert_eq!(config.tui.min_qscore, 10.0);
+        assert_eq!(config.tui.expected_run_hours, 48.0);
+        assert!(config.tui.pause_unfocused);
+        assert_eq!(config.tui.pinned, ["X1"]);
+        assert!(config.tui.hide_simulated);
+        assert!(!config.tui.hide_idle);
+        assert!(config.tui.clock_time);
+        assert!(!config.tui.highlight_changes);
+        assert_eq!(config.tui.export_dir, PathBuf::from("/tmp/exports"));
+    }
+
+    #[test]
+    fn test_invalid_timezone_in_config() {
+        let mut config = Config::default();
+        let file = FileConfig {
+            connection: None,
+            tui: Some(FileTuiConfig {
+                timezone: Some("Mars/Olympus".into()),
+                ..Default::default()
+            }),
+            reconnect: None,
+            logging: None,
+            history: None,
+            storage: None,
+            notifications: None,
+            alerts: None,
+            daemon: None,
+            metrics: None,
+            themes: None,
+            profiles: None,
+        };
+        let result = config.merge(file);
+        assert!(matches!(result, Err(ConfigError::InvalidTimezone(ref s)) if s == "Mars/Olympus"));
     }
 
     #[test]
@@ -336,6 +1005,14 @@ mod tests {
                 multiplier: Some(3.0),
             }),
             logging: None,
+            history: None,
+            storage: None,
+            notifications: None,
+            alerts: None,
+            daemon: None,
+            metrics: None,
+            themes: None,
+            profiles: None,
         };
         config.merge(file).unwrap();
         assert_eq!(config.reconnect.initial_delay, Duration::from_millis(2000));
@@ -354,11 +1031,411 @@ mod tests {
                 level: Some("invalid_level".into()),
                 file: None,
             }),
+            history: None,
+            storage: None,
+            notifications: None,
+            alerts: None,
+            daemon: None,
+            metrics: None,
+            themes: None,
+            profiles: None,
         };
         let result = config.merge(file);
         assert!(matches!(result, Err(ConfigError::InvalidLogLevel(ref s)) if s == "invalid_level"));
     }
 
+    #[test]
+    fn test_merge_history_config() {
+        let mut config = Config::default();
+        let file = FileConfig {
+            history: Some(FileHistoryConfig {
+                enabled: Some(false),
+                path: Some("/tmp/termion-history".into()),
+                record_interval: Some(10),
+                max_age_days: Some(0),
+                max_size_mb: Some(64),
+            }),
+            ..Default::default()
+        };
+        config.merge(file).unwrap();
+        assert!(!config.history.enabled);
+        assert_eq!(config.history.path, PathBuf::from("/tmp/termion-history"));
+        assert_eq!(config.history.record_interval, Duration::from_secs(10));
+        assert_eq!(config.history.max_age, None);
+        assert_eq!(config.history.max_size, Some(64 * 1024 * 1024));
+    }
+
+    #[test]
+    fn test_merge_storage_config() {
+        let mut config = Config::default();
+        assert!(!config.storage.enabled);
+        let file = FileConfig {
+            storage: Some(FileStorageConfig {
+                enabled: Some(true),
+                path: Some("/tmp/termion.db".into()),
+                record_interval: Some(30),
+                max_age_days: Some(7),
+            }),
+            ..Default::default()
+        };
+        config.merge(file).unwrap();
+        assert!(config.storage.enabled);
+        assert_eq!(config.storage.path, PathBuf::from("/tmp/termion.db"));
+        assert_eq!(config.storage.record_interval, Duration::from_secs(30));
+        assert_eq!(
+            config.storage.max_age,
+            Some(Duration::from_secs(7 * 24 * 3600))
+        );
+
+        config.storage.record_interval = Duration::ZERO;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidStorageInterval)
+        ));
+    }
+
+    #[test]
+    fn test_merge_daemon_listen() {
+        let mut config = Config::default();
+        assert_eq!(config.daemon.listen, None);
+        let file = |listen: &str| FileConfig {
+            daemon: Some(FileDaemonConfig {
+                listen: Some(listen.into()),
+            }),
+            ..Default::default()
+        };
+        config.merge(file("0.0.0.0:9510")).unwrap();
+        assert_eq!(config.daemon.listen, Some("0.0.0.0:9510".parse().unwrap()));
+
+        config.merge(file("")).unwrap();
+        assert_eq!(config.daemon.listen, None);
+
+        assert!(matches!(
+            config.merge(file("localhost")),
+            Err(ConfigError::InvalidListenAddress(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_notifications_config() {
+        let mut config = Config::default();
+        assert_eq!(config.notifications.events, NotificationEvent::ALL);
+        let file = FileConfig {
+            notifications: Some(FileNotificationsConfig {
+                webhook_url: Some("https://hooks.example.com/termion".into()),
+                desktop: Some(true),
+                events: Some(vec!["run_completed".into(), "RUN_ERROR".into()]),
+                timeout: Some(3),
+                max_attempts: Some(2),
+                slack: None,
+                email: None,
+            }),
+            ..Default::default()
+        };
+        config.merge(file).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.notifications.webhook_url.as_deref(),
+            Some("https://hooks.example.com/termion")
+        );
+        assert!(config.notifications.desktop);
+        assert_eq!(
+            config.notifications.events,
+            vec![NotificationEvent::RunCompleted, NotificationEvent::RunError]
+        );
+        assert_eq!(config.notifications.timeout, Duration::from_secs(3));
+        assert_eq!(config.notifications.max_attempts, 2);
+
+        let file = FileConfig {
+            notifications: Some(FileNotificationsConfig {
+                events: Some(vec!["run_exploded".into()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let result = config.merge(file);
+        assert!(
+            matches!(result, Err(ConfigError::InvalidNotificationEvent(ref s)) if s == "run_exploded")
+        );
+    }
+
+    #[test]
+    fn test_merge_slack_config() {
+        let mut config = Config::default();
+        let file = |format: Option<&str>, severity: &str| FileConfig {
+            notifications: Some(FileNotificationsConfig {
+                slack: Some(FileSlackConfig {
+                    webhook_url: Some("https://discord.com/api/webhooks/1/abc".into()),
+                    format: format.map(String::from),
+                    min_severity: Some(severity.into()),
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        config.merge(file(None, "Warning")).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.notifications.slack.format, None);
+        assert_eq!(
+            config.notifications.slack.effective_format(),
+            ChatFormat::Discord
+        );
+        assert_eq!(config.notifications.slack.min_severity, Severity::Warning);
+
+        config.merge(file(Some("slack"), "error")).unwrap();
+        assert_eq!(
+            config.notifications.slack.effective_format(),
+            ChatFormat::Slack
+        );
+
+        assert!(matches!(
+            config.merge(file(Some("teams"), "info")),
+            Err(ConfigError::InvalidChatFormat(_))
+        ));
+        assert!(matches!(
+            config.merge(file(None, "critical")),
+            Err(ConfigError::InvalidSeverity(_))
+        ));
+
+        config.notifications.slack.webhook_url = Some("hooks.slack.com".into());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidWebhookUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_email_config() {
+        let mut config = Config::default();
+        let file = FileConfig {
+            notifications: Some(FileNotificationsConfig {
+                email: Some(FileEmailConfig {
+                    smtp_host: Some("smtp.example.com".into()),
+                    tls: Some("TLS".into()),
+                    from: Some("termion@example.com".into()),
+                    to: Some(vec!["operator@example.com".into()]),
+                    events: Some(vec!["run_error".into()]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        config.merge(file).unwrap();
+        assert!(config.validate().is_ok());
+        let email = &config.notifications.email;
+        assert_eq!(email.tls, SmtpTls::Tls);
+        assert_eq!(email.port(), 465);
+        assert_eq!(email.events, vec![NotificationEvent::RunError]);
+
+        config.notifications.email.username = Some("termion".into());
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidEmail(_))
+        ));
+        config.notifications.email.username = None;
+        config.notifications.email.to.clear();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidEmail(_))
+        ));
+
+        let file = FileConfig {
+            notifications: Some(FileNotificationsConfig {
+                email: Some(FileEmailConfig {
+                    tls: Some("ssl".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            config.merge(file),
+            Err(ConfigError::InvalidSmtpTls(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_metrics_config() {
+        let mut config = Config::default();
+        let file = |format: &str, interval| FileConfig {
+            metrics: Some(FileMetricsConfig {
+                url: Some("http://localhost:8428/api/v1/import".into()),
+                format: Some(format.into()),
+                interval: Some(interval),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        config.merge(file("VictoriaMetrics", 30)).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.metrics.format, MetricsFormat::VictoriaMetrics);
+        assert_eq!(config.metrics.interval, Duration::from_secs(30));
+        assert_eq!(config.metrics.measurement, "termion");
+
+        config.merge(file("influx", 0)).unwrap();
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::InvalidTimeout("metrics.interval"))
+        ));
+        assert!(matches!(
+            config.merge(file("prometheus", 10)),
+            Err(ConfigError::InvalidMetricsFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_profile_overrides_connection() {
+        let toml = r#"
+            [connection]
+            host = "localhost"
+            request_timeout = 60
+
+            [profiles.gridion1]
+            port = 9502
+            ca_cert = "/etc/minknow/gridion1.crt"
+        "#;
+
+        let mut config = Config::default();
+        config
+            .merge_with_profile(toml::from_str(toml).unwrap(), Some("gridion1"))
+            .unwrap();
+        assert_eq!(config.connection.port, 9502);
+        assert_eq!(config.connection.request_timeout, Duration::from_secs(60));
+        assert_eq!(
+            config.connection.ca_cert,
+            Some(PathBuf::from("/etc/minknow/gridion1.crt"))
+        );
+        assert_eq!(config.connection.profile.as_deref(), Some("gridion1"));
+
+        let mut config = Config::default();
+        config
+            .merge_with_profile(toml::from_str(toml).unwrap(), None)
+            .unwrap();
+        assert_eq!(config.connection.port, 9501);
+        assert_eq!(config.connection.profile, None);
+
+        let result =
+            Config::default().merge_with_profile(toml::from_str(toml).unwrap(), Some("promethion"));
+        assert!(matches!(result, Err(ConfigError::UnknownProfile(ref s)) if s == "promethion"));
+    }
+
+    #[test]
+    fn test_hosts_endpoints() {
+        let mut config = Config::default();
+        assert!(!config.connection.is_multi_host());
+        assert_eq!(config.connection.endpoints()[0].label, "localhost");
+
+        let toml = r#"
+            [connection]
+            port = 9501
+            hosts = ["localhost", "localhost:19501"]
+        "#;
+        config.merge(toml::from_str(toml).unwrap()).unwrap();
+        config.validate().unwrap();
+        assert!(config.connection.is_multi_host());
+        let endpoints = config.connection.endpoints();
+        assert_eq!(
+            endpoints
+                .iter()
+                .map(|e| (e.label.as_str(), e.host.as_str(), e.port))
+                .collect::<Vec<_>>(),
+            [
+                ("localhost", "localhost", 9501),
+                ("localhost:19501", "localhost", 19501)
+            ]
+        );
+
+        for bad in ["localhost:x", ":9501", "localhost:19501"] {
+            let mut config = config.clone();
+            config.connection.hosts.push(bad.to_string());
+            assert!(
+                matches!(config.validate(), Err(ConfigError::InvalidHost(ref s)) if s == bad),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge_alerts_config() {
+        let mut config = Config::default();
+        assert!(config.alerts.rules.is_empty());
+        let file = FileConfig {
+            alerts: Some(FileAlertsConfig {
+                rules: Some(vec![
+                    "throughput_gbph < 0.5".into(),
+                    "active_pores<200".into(),
+                ]),
+            }),
+            ..Default::default()
+        };
+        config.merge(file).unwrap();
+        let rules: Vec<String> = config.alerts.rules.iter().map(|r| r.to_string()).collect();
+        assert_eq!(rules, vec!["throughput_gbph < 0.5", "active_pores < 200"]);
+
+        let file = FileConfig {
+            alerts: Some(FileAlertsConfig {
+                rules: Some(vec!["yield > 3".into()]),
+            }),
+            ..Default::default()
+        };
+        let result = config.merge(file);
+        assert!(matches!(result, Err(ConfigError::InvalidAlertRule(ref s)) if s == "yield > 3"));
+    }
+
+    #[test]
+    fn test_merge_user_themes() {
+        let mut config = Config::default();
+        let file: FileConfig = toml::from_str(
+            r##"
+            [themes.mylab]
+            base = "nord"
+            border = "#ff8800"
+            background = "101010"
+            "##,
+        )
+        .unwrap();
+        config.merge(file).unwrap();
+        assert_eq!(config.tui.themes.len(), 1);
+        let theme = &config.tui.themes[0];
+        assert_eq!(theme.name, "mylab");
+        assert_eq!(theme.border, Color::Rgb(255, 136, 0));
+        assert_eq!(theme.background, Color::Rgb(16, 16, 16));
+        assert_eq!(theme.text, Theme::nord().text);
+
+        let invalid = [
+            ("[themes.x]\nborder = \"orange\"", "border = 'orange'"),
+            (
+                "[themes.x]\nborderr = \"#000000\"",
+                "unknown color 'borderr'",
+            ),
+            ("[themes.x]\nbase = \"solarized\"", "unknown base theme"),
+            (
+                "[themes.dracula]\ntext = \"#000000\"",
+                "taken by a built-in",
+            ),
+        ];
+        for (toml, message) in invalid {
+            let file: FileConfig = toml::from_str(toml).unwrap();
+            let err = Config::default().merge(file).unwrap_err();
+            assert!(err.to_string().contains(message), "{toml}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_webhook_url() {
+        let mut config = Config::default();
+        config.notifications.webhook_url = Some("hooks.example.com".into());
+        let result = config.validate();
+        assert!(matches!(result, Err(ConfigError::InvalidWebhookUrl(_))));
+
+        config.notifications.webhook_url = Some("http://localhost:8080/hook".into());
+        config.notifications.max_attempts = 0;
+        let result = config.validate();
+        assert!(matches!(result, Err(ConfigError::InvalidMaxAttempts)));
+    }
+
     #[test]
     fn test_expand_tilde() {
         let path = expand_tilde("/absolute/path");
@@ -372,4 +1449,62 @@ mod tests {
             assert_eq!(path, home.join("logs/test.log"));
         }
     }
+
+    #[test]
+    fn test_save_pinned_keeps_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        std::fs::write(
+            &path,
+            "# Termion settings\n[tui]\n# Dark theme\ntheme = \"default\"\n",
+        )
+        .unwrap();
+
+        let config = Config {
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        config
+            .save_pinned(&["X1".to_string(), "X2".to_string()])
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("# Termion settings"));
+        assert!(content.contains("# Dark theme"));
+        assert!(content.contains("theme = \"default\""));
+        assert!(content.contains("pinned = [\"X1\", \"X2\"]"));
+
+        let config = Config::default();
+        assert!(matches!(
+            config.save_pinned(&[]),
+            Err(ConfigError::NoConfigFile)
+        ));
+    }
+
+    #[test]
+    fn test_save_and_load_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("termion/notes.toml");
+
+        let config = Config {
+            notes_file: Some(path.clone()),
+            ..Default::default()
+        };
+        assert!(config.load_notes().unwrap().is_empty());
+
+        let notes = BTreeMap::from([
+            ("X1".to_string(), "library B".to_string()),
+            ("gridion/X3".to_string(), "flush at 24h".to_string()),
+        ]);
+        config.save_notes(&notes).unwrap();
+        assert_eq!(config.load_notes().unwrap(), notes);
+        assert!(path.exists());
+
+        let config = Config::default();
+        assert!(config.load_notes().unwrap().is_empty());
+        assert!(matches!(
+            config.save_notes(&notes),
+            Err(ConfigError::NoNotesFile)
+        ));
+    }
 }
@@ -0,0 +1,474 @@
+//! The effective value of each setting and the layer it came from, for
+//! `termion config show`.
+
+use super::{Config, ConfigError};
+use crate::cli::Cli;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use toml::Value;
+
+/// Default config file written by `termion config init`: every setting with
+/// its default value, commented out.
+pub const DEFAULT_CONFIG: &str = include_str!("default.toml");
+
+/// Where a setting's effective value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File,
+    /// The named `[profiles]` entry.
+    Profile(String),
+    /// The named environment variable.
+    Env(&'static str),
+    /// The named command-line flag.
+    Cli(&'static str),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File => write!(f, "config file"),
+            ConfigSource::Profile(name) => write!(f, "profile {}", name),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+            ConfigSource::Cli(flag) => write!(f, "{}", flag),
+        }
+    }
+}
+
+/// One setting, keyed as `section.name` like in the config file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    pub key: &'static str,
+    /// The value in config file units, or `None` if unset.
+    pub value: Option<Value>,
+    pub source: ConfigSource,
+}
+
+/// The effective configuration and where each value came from.
+#[derive(Debug, Clone)]
+pub struct ConfigReport {
+    /// Config file that was read, if one exists.
+    pub path: Option<PathBuf>,
+    pub profile: Option<String>,
+    pub entries: Vec<ConfigEntry>,
+}
+
+impl Config {
+    /// Loads the configuration like [`Config::load`] and reports the source
+    /// of each setting.
+    pub fn report(cli: &Cli) -> Result<ConfigReport, ConfigError> {
+        let config = Self::load(cli)?;
+        let file = match Self::read_file(cli)? {
+            Some((path, content)) => {
+                let table = content
+                    .parse::<toml::Table>()
+                    .map_err(|e| ConfigError::Parse {
+                        path: path.clone(),
+                        source: e,
+                    })?;
+                Some((path, table))
+            }
+            None => None,
+        };
+        let table = file.as_ref().map(|(_, table)| table);
+        let profile = cli.profile.clone();
+
+        let entries = config
+            .values()
+            .into_iter()
+            .map(|(key, value)| ConfigEntry {
+                key,
+                value,
+                source: source(key, cli, table, profile.as_deref()),
+            })
+            .collect();
+        Ok(ConfigReport {
+            path: file.map(|(path, _)| path),
+            profile,
+            entries,
+        })
+    }
+
+    /// Every setting with a default, in config file order and units.
+    pub(crate) fn values(&self) -> Vec<(&'static str, Option<Value>)> {
+        let c = &self.connection;
+        let t = &self.tui;
+        let h = &self.history;
+        let s = &self.storage;
+        let n = &self.notifications;
+        vec![
+            ("connection.host", Some(string(&c.host))),
+            ("connection.port", Some(int(c.port))),
+            ("connection.connect_timeout", Some(secs(c.connect_timeout))),
+            ("connection.request_timeout", Some(secs(c.request_timeout))),
+            (
+                "connection.max_concurrent_positions",
+                Some(int(c.max_concurrent_positions as u64)),
+            ),
+            ("connection.ca_cert", c.ca_cert.as_deref().map(path)),
+            ("connection.hosts", Some(array(&c.hosts))),
+            (
+                "connection.keepalive_interval",
+                Some(secs(c.keepalive_interval.unwrap_or_default())),
+            ),
+            (
+                "connection.keepalive_timeout",
+                Some(secs(c.keepalive_timeout)),
+            ),
+            (
+                "connection.tcp_nodelay",
+                Some(Value::Boolean(c.tcp_nodelay)),
+            ),
+            ("tui.refresh_interval", Some(millis(t.refresh_interval))),
+            (
+                "tui.idle_refresh_interval",
+                Some(millis(t.idle_refresh_interval)),
+            ),
+            ("tui.stats_interval", Some(millis(t.stats_interval))),
+            ("tui.histogram_interval", Some(millis(t.histogram_interval))),
+            (
+                "tui.histogram_buckets",
+                Some(int(t.histogram_buckets.unwrap_or_default())),
+            ),
+            (
+                "tui.histogram_outlier_percent",
+                Some(Value::Float(t.histogram_outlier_percent)),
+            ),
+            (
+                "tui.channel_states_interval",
+                Some(millis(t.channel_states_interval)),
+            ),
+            ("tui.chart_history", Some(secs(t.chart_history))),
+            ("tui.theme", Some(string(&t.theme))),
+            ("tui.timezone", Some(string(&t.timezone.to_string()))),
+            (
+                "tui.disk_warning_percent",
+                Some(Value::Float(t.disk_warning_percent)),
+            ),
+            ("tui.min_qscore", Some(Value::Float(t.min_qscore))),
+            (
+                "tui.expected_run_hours",
+                Some(Value::Float(t.expected_run_hours)),
+            ),
+            (
+                "tui.pause_unfocused",
+                Some(Value::Boolean(t.pause_unfocused)),
+            ),
+            ("tui.pinned", Some(array(&t.pinned))),
+            ("tui.hide_simulated", Some(Value::Boolean(t.hide_simulated))),
+            ("tui.hide_idle", Some(Value::Boolean(t.hide_idle))),
+            ("tui.clock_time", Some(Value::Boolean(t.clock_time))),
+            (
+                "tui.highlight_changes",
+                Some(Value::Boolean(t.highlight_changes)),
+            ),
+            ("tui.export_dir", Some(path(&t.export_dir))),
+            (
+                "reconnect.initial_delay",
+                Some(millis(self.reconnect.initial_delay)),
+            ),
+            (
+                "reconnect.max_delay",
+                Some(millis(self.reconnect.max_delay)),
+            ),
+            (
+                "reconnect.multiplier",
+                Some(Value::Float(self.reconnect.multiplier)),
+            ),
+            ("history.enabled", Some(Value::Boolean(h.enabled))),
+            ("history.path", Some(path(&h.path))),
+            ("history.record_interval", Some(secs(h.record_interval))),
+            ("history.max_age_days", Some(days(h.max_age))),
+            (
+                "history.max_size_mb",
+                Some(int(h.max_size.unwrap_or(0) / (1024 * 1024))),
+            ),
+            ("storage.enabled", Some(Value::Boolean(s.enabled))),
+            ("storage.path", Some(path(&s.path))),
+            ("storage.record_interval", Some(secs(s.record_interval))),
+            ("storage.max_age_days", Some(days(s.max_age))),
+            (
+                "alerts.rules",
+                Some(array(self.alerts.rules.iter().map(|r| r.to_string()))),
+            ),
+            (
+                "notifications.webhook_url",
+                n.webhook_url.as_deref().map(string),
+            ),
+            ("notifications.desktop", Some(Value::Boolean(n.desktop))),
+            (
+                "notifications.events",
+                Some(array(n.events.iter().map(|e| e.as_str()))),
+            ),
+            ("notifications.timeout", Some(secs(n.timeout))),
+            ("notifications.max_attempts", Some(int(n.max_attempts))),
+            (
+                "notifications.slack.webhook_url",
+                n.slack.webhook_url.as_deref().map(string),
+            ),
+            (
+                "notifications.slack.format",
+                n.slack.format.map(|f| string(f.as_str())),
+            ),
+            (
+                "notifications.slack.min_severity",
+                Some(string(n.slack.min_severity.as_str())),
+            ),
+            (
+                "notifications.email.smtp_host",
+                n.email.smtp_host.as_deref().map(string),
+            ),
+            ("notifications.email.smtp_port", Some(int(n.email.port()))),
+            (
+                "notifications.email.tls",
+                Some(string(n.email.tls.as_str())),
+            ),
+            (
+                "notifications.email.username",
+                n.email.username.as_deref().map(string),
+            ),
+            // Never echo the password itself.
+            (
+                "notifications.email.password",
+                n.email.password.as_ref().map(|_| string("********")),
+            ),
+            (
+                "notifications.email.from",
+                n.email.from.as_deref().map(string),
+            ),
+            ("notifications.email.to", Some(array(&n.email.to))),
+            (
+                "notifications.email.events",
+                Some(array(n.email.events.iter().map(|e| e.as_str()))),
+            ),
+            ("metrics.url", self.metrics.url.as_deref().map(string)),
+            ("metrics.format", Some(string(self.metrics.format.as_str()))),
+            (
+                "metrics.token",
+                self.metrics.token.as_ref().map(|_| string("********")),
+            ),
+            ("metrics.interval", Some(secs(self.metrics.interval))),
+            (
+                "metrics.measurement",
+                Some(string(&self.metrics.measurement)),
+            ),
+            (
+                "daemon.listen",
+                self.daemon.listen.map(|addr| string(&addr.to_string())),
+            ),
+            ("logging.level", Some(string(self.logging.level.as_str()))),
+            ("logging.file", Some(path(&self.logging.file))),
+        ]
+    }
+}
+
+/// Finds the highest-precedence layer that sets `key`.
+fn source(key: &str, cli: &Cli, file: Option<&toml::Table>, profile: Option<&str>) -> ConfigSource {
+    let env_set = |var: &str| std::env::var(var).is_ok();
+    // Clap fills --host and --port from the environment too, so a flag
+    // matching its variable is attributed to the variable.
+    let flag_or_env = |value: Option<String>, flag, var| match value {
+        Some(value) if std::env::var(var).is_ok_and(|env| env == value) => {
+            Some(ConfigSource::Env(var))
+        }
+        Some(_) => Some(ConfigSource::Cli(flag)),
+        None => None,
+    };
+    let overridden = match key {
+        "connection.host" | "connection.hosts" => {
+            flag_or_env(cli.host.clone(), "--host", "TERMION_HOST")
+                .or_else(|| env_set("TERMION_HOST").then_some(ConfigSource::Env("TERMION_HOST")))
+        }
+        "connection.port" => flag_or_env(cli.port.map(|p| p.to_string()), "--port", "TERMION_PORT")
+            .or_else(|| {
+                std::env::var("TERMION_PORT")
+                    .is_ok_and(|port| port.parse::<u16>().is_ok())
+                    .then_some(ConfigSource::Env("TERMION_PORT"))
+            }),
+        "logging.level" if cli.verbose > 0 => Some(ConfigSource::Cli("--verbose")),
+        "logging.level" => {
+            env_set("TERMION_LOG_LEVEL").then_some(ConfigSource::Env("TERMION_LOG_LEVEL"))
+        }
+        "logging.file" if cli.log.is_some() => Some(ConfigSource::Cli("--log")),
+        "logging.file" => {
+            env_set("TERMION_LOG_FILE").then_some(ConfigSource::Env("TERMION_LOG_FILE"))
+        }
+        "notifications.email.password" => {
+            env_set("TERMION_SMTP_PASSWORD").then_some(ConfigSource::Env("TERMION_SMTP_PASSWORD"))
+        }
+        _ => None,
+    };
+    if let Some(source) = overridden {
+        return source;
+    }
+
+    let Some(file) = file else {
+        return ConfigSource::Default;
+    };
+    let (section, name) = key.split_once('.').unwrap_or((key, ""));
+    if let Some(profile) = profile {
+        let in_profile = file
+            .get("profiles")
+            .and_then(|p| p.get(profile))
+            .is_some_and(|p| p.get(name).is_some());
+        if section == "connection" && in_profile {
+            return ConfigSource::Profile(profile.to_string());
+        }
+    }
+    // Keys in nested tables like `[notifications.slack]` have more parts.
+    let mut parts = key.split('.');
+    let mut value = parts.next().and_then(|part| file.get(part));
+    for part in parts {
+        value = value.and_then(|v| v.get(part));
+    }
+    if value.is_some() {
+        return ConfigSource::File;
+    }
+    ConfigSource::Default
+}
+
+fn string(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+fn int(value: impl Into<u64>) -> Value {
+    Value::Integer(value.into() as i64)
+}
+
+fn secs(duration: Duration) -> Value {
+    int(duration.as_secs())
+}
+
+fn millis(duration: Duration) -> Value {
+    int(duration.as_millis() as u64)
+}
+
+/// A maximum age in days, where 0 means no limit.
+fn days(age: Option<Duration>) -> Value {
+    int(age.map_or(0, |age| age.as_secs() / (24 * 3600)))
+}
+
+fn path(path: &Path) -> Value {
+    string(&path.display().to_string())
+}
+
+fn array<S: AsRef<str>>(items: impl IntoIterator<Item = S>) -> Value {
+    Value::Array(items.into_iter().map(|s| string(s.as_ref())).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FileConfig;
+    use clap::Parser;
+
+    /// The template with every setting uncommented.
+    fn uncommented_template() -> String {
+        DEFAULT_CONFIG
+            .lines()
+            .map(|line| match line.strip_prefix("# ") {
+                Some(rest)
+                    if rest.split_once(" = ").is_some_and(|(key, _)| {
+                        key.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+                    }) =>
+                {
+                    rest
+                }
+                _ => line,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_default_template_matches_defaults() {
+        // As written, the template changes nothing.
+        let file: FileConfig = toml::from_str(DEFAULT_CONFIG).unwrap();
+        let mut config = Config::default();
+        config.merge(file).unwrap();
+        assert_eq!(config.values(), Config::default().values());
+
+        // Uncommented, it lists every setting with its default value. Paths
+        // depend on the platform's directories, and the example webhook and
+        // certificate are not defaults.
+        let file: FileConfig = toml::from_str(&uncommented_template()).unwrap();
+        let mut config = Config::default();
+        config.merge(file).unwrap();
+        let examples = [
+            "connection.ca_cert",
+            "tui.pinned",
+            "tui.export_dir",
+            "notifications.webhook_url",
+            "notifications.slack.webhook_url",
+            "notifications.slack.format",
+            "notifications.email.smtp_host",
+            "notifications.email.username",
+            "notifications.email.password",
+            "notifications.email.from",
+            "notifications.email.to",
+            "daemon.listen",
+            "metrics.url",
+            "metrics.token",
+            "history.path",
+            "storage.path",
+            "logging.file",
+        ];
+        let values = config.values();
+        let defaults = Config::default().values();
+        assert_eq!(values.len(), defaults.len());
+        for ((key, value), (_, default)) in values.iter().zip(&defaults) {
+            if !examples.contains(key) {
+                assert_eq!(value, default, "{key}");
+            }
+        }
+        let table: toml::Table = uncommented_template().parse().unwrap();
+        for (key, _) in &defaults {
+            let mut parts = key.split('.');
+            let mut value = parts.next().and_then(|part| table.get(part));
+            for part in parts {
+                value = value.and_then(|v| v.get(part));
+            }
+            assert!(value.is_some(), "{key} is missing from the template");
+        }
+    }
+
+    #[test]
+    fn test_sources_follow_precedence() {
+        let file: toml::Table = toml::from_str(
+            r#"
+[connection]
+port = 9502
+connect_timeout = 10
+
+[profiles.lab]
+connect_timeout = 20
+
+[notifications.slack]
+min_severity = "error"
+"#,
+        )
+        .unwrap();
+        let cli = Cli::try_parse_from(["termion", "--log", "/tmp/termion.log", "-v"]).unwrap();
+
+        let source = |key| source(key, &cli, Some(&file), Some("lab"));
+        assert_eq!(source("connection.port"), ConfigSource::File);
+        assert_eq!(
+            source("connection.connect_timeout"),
+            ConfigSource::Profile("lab".into())
+        );
+        assert_eq!(source("tui.refresh_interval"), ConfigSource::Default);
+        assert_eq!(
+            source("notifications.slack.min_severity"),
+            ConfigSource::File
+        );
+        assert_eq!(source("notifications.slack.format"), ConfigSource::Default);
+        assert_eq!(source("logging.file"), ConfigSource::Cli("--log"));
+        assert_eq!(source("logging.level"), ConfigSource::Cli("--verbose"));
+        assert_eq!(
+            super::source("connection.port", &cli, None, None),
+            ConfigSource::Default
+        );
+    }
+}
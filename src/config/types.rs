@@ -1,6 +1,12 @@
 //! Configuration types.
 
+use crate::alerts::AlertRule;
+use crate::client::ConnectOptions;
+use crate::tui::theme::{Theme, AUTO_THEME};
+use chrono::{DateTime, FixedOffset, Local, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 use thiserror::Error;
@@ -11,6 +17,36 @@ pub struct Config {
     pub tui: TuiConfig,
     pub reconnect: ReconnectConfig,
     pub logging: LoggingConfig,
+    pub history: HistoryConfig,
+    pub storage: StorageConfig,
+    pub notifications: NotificationsConfig,
+    pub alerts: AlertsConfig,
+    pub daemon: DaemonConfig,
+    pub metrics: MetricsConfig,
+    /// Show simulated data instead of connecting to MinKNOW. Set by
+    /// `--demo`; there is no config file setting.
+    pub demo: bool,
+    /// Session file to record to. Set by `--record`.
+    pub record: Option<PathBuf>,
+    /// Recorded session to show instead of connecting to MinKNOW. Set by
+    /// `--replay` and `--replay-speed`.
+    pub replay: Option<ReplayConfig>,
+    /// The config file, whether or not it exists yet; settings changed in
+    /// the TUI are saved back to it. Set by [`Config::load`].
+    pub file: Option<PathBuf>,
+    /// Where position notes edited in the TUI are kept, with the other
+    /// local data under `termion/`. Set by [`Config::load`].
+    pub notes_file: Option<PathBuf>,
+}
+
+/// Replay settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayConfig {
+    /// Session file written by `--record`.
+    pub path: PathBuf,
+
+    /// Playback speed relative to the recording.
+    pub speed: f64,
 }
 
 /// Connection settings.
@@ -27,6 +63,92 @@ pub struct ConnectionConfig {
 
     /// Request timeout.
     pub request_timeout: Duration,
+
+    /// Maximum number of positions queried at once.
+    pub max_concurrent_positions: usize,
+
+    /// CA certificate to trust, instead of `MINKNOW_TRUSTED_CA` or the
+    /// default MinKNOW locations.
+    pub ca_cert: Option<PathBuf>,
+
+    /// Name of the profile the connection settings came from, if any.
+    pub profile: Option<String>,
+
+    /// MinKNOW managers the TUI watches at once, as `host` or `host:port`.
+    /// Empty to watch only `host`.
+    pub hosts: Vec<String>,
+
+    /// HTTP/2 keepalive ping interval, or `None` to disable pings.
+    pub keepalive_interval: Option<Duration>,
+
+    /// How long a keepalive ping may go unanswered before the connection
+    /// is treated as lost.
+    pub keepalive_timeout: Duration,
+
+    /// Disable Nagle's algorithm on MinKNOW connections.
+    pub tcp_nodelay: bool,
+}
+
+/// A MinKNOW manager the TUI connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostEndpoint {
+    /// Label positions are namespaced under, as written in `hosts`.
+    pub label: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl ConnectionConfig {
+    /// Options for connecting the client with these settings.
+    pub fn connect_options(&self) -> ConnectOptions {
+        ConnectOptions {
+            connect_timeout: self.connect_timeout,
+            request_timeout: self.request_timeout,
+            ca_cert: self.ca_cert.clone(),
+            keepalive_interval: self.keepalive_interval,
+            keepalive_timeout: self.keepalive_timeout,
+            tcp_nodelay: self.tcp_nodelay,
+            ..Default::default()
+        }
+    }
+
+    /// Whether positions from several managers are merged into one view.
+    pub fn is_multi_host(&self) -> bool {
+        self.hosts.len() > 1
+    }
+
+    /// The managers to connect to: each `hosts` entry, or `host` and
+    /// `port` when the list is empty. Entries without a port use `port`.
+    pub fn endpoints(&self) -> Vec<HostEndpoint> {
+        if self.hosts.is_empty() {
+            return vec![HostEndpoint {
+                label: self.host.clone(),
+                host: self.host.clone(),
+                port: self.port,
+            }];
+        }
+        self.hosts
+            .iter()
+            .filter_map(|entry| parse_host_entry(entry, self.port))
+            .collect()
+    }
+}
+
+/// Parses a `host` or `host:port` entry, or returns `None` if it is
+/// malformed.
+pub(crate) fn parse_host_entry(entry: &str, default_port: u16) -> Option<HostEndpoint> {
+    let (host, port) = match entry.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse().ok().filter(|&p| p != 0)?),
+        None => (entry, default_port),
+    };
+    if host.is_empty() || host.contains('/') {
+        return None;
+    }
+    Some(HostEndpoint {
+        label: entry.to_string(),
+        host: host.to_string(),
+        port,
+    })
 }
 
 impl Default for ConnectionConfig {
@@ -36,29 +158,194 @@ impl Default for ConnectionConfig {
             port: 9501,
             connect_timeout: Duration::from_secs(5),
             request_timeout: Duration::from_secs(30),
+            max_concurrent_positions: 8,
+            ca_cert: None,
+            profile: None,
+            hosts: Vec::new(),
+            keepalive_interval: Some(Duration::from_secs(30)),
+            keepalive_timeout: Duration::from_secs(10),
+            tcp_nodelay: true,
         }
     }
 }
 
+/// Most buckets the read length histogram may be split into.
+pub const MAX_HISTOGRAM_BUCKETS: u32 = 1000;
+
 /// TUI settings.
 #[derive(Debug, Clone)]
 pub struct TuiConfig {
     /// Data refresh interval.
     pub refresh_interval: Duration,
 
+    /// Refresh interval for positions without an active run that are not
+    /// open in the detail or run history view.
+    pub idle_refresh_interval: Duration,
+
+    /// How often run statistics are fetched for active runs.
+    pub stats_interval: Duration,
+
+    /// How often MinKNOW resends the read length and q-score histograms,
+    /// and how often the mean q-score is fetched.
+    pub histogram_interval: Duration,
+
+    /// Buckets the read length histogram splits a selected length range
+    /// into. `None` leaves the bucket width to MinKNOW.
+    pub histogram_buckets: Option<u32>,
+
+    /// Percent of the longest reads the read length histogram leaves out
+    /// while outliers are excluded.
+    pub histogram_outlier_percent: f64,
+
+    /// How often the channel states of the position in the detail view are
+    /// fetched.
+    pub channel_states_interval: Duration,
+
     /// Chart history duration.
     pub chart_history: Duration,
 
     /// Color theme name.
     pub theme: String,
+
+    /// Time zone used when displaying timestamps.
+    pub timezone: DisplayTimezone,
+
+    /// Labels chart time axes and the overview with wall-clock times in
+    /// `timezone` instead of run time. Toggled with `C` in the TUI.
+    pub clock_time: bool,
+
+    /// Briefly highlights table and Run Info values that changed since the
+    /// last update, so stalled counters stand out.
+    pub highlight_changes: bool,
+
+    /// Directory `x` in the TUI writes exported chart data to.
+    pub export_dir: PathBuf,
+
+    /// Free disk space, in percent, below which a position is flagged.
+    pub disk_warning_percent: f64,
+
+    /// Mean q-score a read needs to pass basecalling, marked on the q-score
+    /// chart. Should match the basecaller's minimum q-score.
+    pub min_qscore: f64,
+
+    /// Expected length of a sequencing run in hours, which yield
+    /// projections extrapolate to.
+    pub expected_run_hours: f64,
+
+    /// Stops drawing, and fetching data only the detail and run history
+    /// views need, while the terminal reports that it is not focused.
+    pub pause_unfocused: bool,
+
+    /// Positions kept at the top of the overview, by name. Toggled with `f`
+    /// in the TUI, which saves the list back to the config file.
+    pub pinned: Vec<String>,
+
+    /// Leaves positions on simulated devices out of the overview. Toggled
+    /// with `m` in the TUI.
+    pub hide_simulated: bool,
+
+    /// Leaves positions without a run out of the overview. Toggled with `i`
+    /// in the TUI.
+    pub hide_idle: bool,
+
+    /// Themes defined in the `[themes]` table, offered alongside the
+    /// built-in ones.
+    pub themes: Vec<Theme>,
 }
 
 impl Default for TuiConfig {
     fn default() -> Self {
         Self {
             refresh_interval: Duration::from_millis(1000),
+            idle_refresh_interval: Duration::from_secs(30),
+            stats_interval: Duration::from_secs(1),
+            histogram_interval: Duration::from_secs(30),
+            histogram_buckets: None,
+            histogram_outlier_percent: 1.0,
+            channel_states_interval: Duration::from_secs(5),
             chart_history: Duration::from_secs(1800), // 30 minutes
-            theme: "default".to_string(),
+            theme: AUTO_THEME.to_string(),
+            timezone: DisplayTimezone::default(),
+            clock_time: false,
+            highlight_changes: true,
+            export_dir: dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("termion/exports"),
+            disk_warning_percent: 10.0,
+            min_qscore: 9.0,
+            expected_run_hours: 72.0,
+            pause_unfocused: false,
+            pinned: Vec::new(),
+            hide_simulated: false,
+            hide_idle: false,
+            themes: Vec::new(),
+        }
+    }
+}
+
+/// Time zone for displayed timestamps.
+///
+/// MinKNOW reports all timestamps in UTC; they are converted to this zone
+/// before being shown. Named IANA zones are not supported, so remote
+/// sequencers in another zone should use a fixed offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisplayTimezone {
+    /// The local time zone of the machine running Termion.
+    #[default]
+    Local,
+    /// Coordinated Universal Time.
+    Utc,
+    /// A fixed offset from UTC (e.g., `+02:00`).
+    Fixed(FixedOffset),
+}
+
+impl DisplayTimezone {
+    /// Formats a UTC timestamp in this zone using a chrono format string.
+    pub fn format(&self, ts: DateTime<Utc>, fmt: &str) -> String {
+        match self {
+            DisplayTimezone::Local => ts.with_timezone(&Local).format(fmt).to_string(),
+            DisplayTimezone::Utc => ts.format(fmt).to_string(),
+            DisplayTimezone::Fixed(offset) => ts.with_timezone(offset).format(fmt).to_string(),
+        }
+    }
+
+    /// Formats a timestamp for on-screen display, including the UTC offset.
+    pub fn format_display(&self, ts: DateTime<Utc>) -> String {
+        self.format(ts, "%Y-%m-%d %H:%M %:z")
+    }
+
+    /// Formats a timestamp as RFC 3339 with an explicit offset, for exports.
+    pub fn to_rfc3339(&self, ts: DateTime<Utc>) -> String {
+        match self {
+            DisplayTimezone::Local => ts.with_timezone(&Local).to_rfc3339(),
+            DisplayTimezone::Utc => ts.to_rfc3339(),
+            DisplayTimezone::Fixed(offset) => ts.with_timezone(offset).to_rfc3339(),
+        }
+    }
+}
+
+/// Formats the zone as it is written in the config file.
+impl std::fmt::Display for DisplayTimezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisplayTimezone::Local => write!(f, "local"),
+            DisplayTimezone::Utc => write!(f, "utc"),
+            DisplayTimezone::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+impl std::str::FromStr for DisplayTimezone {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "local" => Ok(DisplayTimezone::Local),
+            "utc" | "z" => Ok(DisplayTimezone::Utc),
+            other => other
+                .parse::<FixedOffset>()
+                .map(DisplayTimezone::Fixed)
+                .map_err(|_| ()),
         }
     }
 }
@@ -108,6 +395,413 @@ impl Default for LoggingConfig {
     }
 }
 
+/// Local run history settings.
+#[derive(Debug, Clone)]
+pub struct HistoryConfig {
+    /// Whether the TUI records stats snapshots to the history store.
+    pub enabled: bool,
+
+    /// Directory holding recorded runs.
+    pub path: PathBuf,
+
+    /// How often a snapshot is recorded per position.
+    pub record_interval: Duration,
+
+    /// Recorded runs older than this are removed. `None` keeps them forever.
+    pub max_age: Option<Duration>,
+
+    /// Oldest runs are removed once the store exceeds this many bytes.
+    /// `None` means no limit.
+    pub max_size: Option<u64>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("termion/history"),
+            record_interval: Duration::from_secs(60),
+            max_age: Some(Duration::from_secs(30 * 24 * 3600)), // 30 days
+            max_size: Some(512 * 1024 * 1024),                  // 512 MiB
+        }
+    }
+}
+
+/// SQLite run metrics database settings.
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    /// Whether stats snapshots and run lifecycle events are written to the
+    /// database.
+    pub enabled: bool,
+
+    /// Database file.
+    pub path: PathBuf,
+
+    /// How often a snapshot is recorded per position.
+    pub record_interval: Duration,
+
+    /// Runs that ended longer ago than this are removed. `None` keeps them
+    /// forever.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("termion/termion.db"),
+            record_interval: Duration::from_secs(60),
+            max_age: Some(Duration::from_secs(90 * 24 * 3600)), // 90 days
+        }
+    }
+}
+
+/// Threshold alert settings.
+#[derive(Debug, Clone, Default)]
+pub struct AlertsConfig {
+    /// Rules checked against each position's stats, e.g. `active_pores < 200`.
+    pub rules: Vec<AlertRule>,
+}
+
+/// Webhook and desktop notification settings.
+#[derive(Debug, Clone)]
+pub struct NotificationsConfig {
+    /// URL that receives a JSON POST per event. `None` disables notifications.
+    pub webhook_url: Option<String>,
+
+    /// Show a desktop notification for run completions, errors, pauses and
+    /// disconnections. Needs the `desktop-notifications` build feature.
+    pub desktop: bool,
+
+    /// Events that are sent.
+    pub events: Vec<NotificationEvent>,
+
+    /// Timeout for a single delivery attempt.
+    pub timeout: Duration,
+
+    /// Delivery attempts per notification before it is dropped.
+    pub max_attempts: u32,
+
+    /// Slack or Discord messages.
+    pub slack: SlackConfig,
+
+    /// Emails through an SMTP server.
+    pub email: EmailConfig,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            desktop: false,
+            events: NotificationEvent::ALL.to_vec(),
+            timeout: Duration::from_secs(10),
+            max_attempts: 5,
+            slack: SlackConfig::default(),
+            email: EmailConfig::default(),
+        }
+    }
+}
+
+/// `[notifications.slack]`: formatted messages to a Slack or Discord
+/// incoming webhook.
+#[derive(Debug, Clone, Default)]
+pub struct SlackConfig {
+    /// Incoming webhook URL. `None` disables chat messages.
+    pub webhook_url: Option<String>,
+
+    /// Message layout. `None` picks Discord for Discord URLs and Slack
+    /// otherwise.
+    pub format: Option<ChatFormat>,
+
+    /// Least severe event that is posted.
+    pub min_severity: Severity,
+}
+
+impl SlackConfig {
+    /// The configured format, or the one the webhook URL implies.
+    pub fn effective_format(&self) -> ChatFormat {
+        self.format.unwrap_or_else(|| {
+            let discord = self.webhook_url.as_deref().is_some_and(|url| {
+                url.contains("://discord.com/") || url.contains("://discordapp.com/")
+            });
+            if discord {
+                ChatFormat::Discord
+            } else {
+                ChatFormat::Slack
+            }
+        })
+    }
+}
+
+/// `[notifications.email]`: emails through an SMTP server. Needs the
+/// `email-notifications` build feature.
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    /// SMTP server. `None` disables emails.
+    pub smtp_host: Option<String>,
+
+    /// SMTP port. `None` uses the standard port for `tls`.
+    pub smtp_port: Option<u16>,
+
+    pub tls: SmtpTls,
+
+    pub username: Option<String>,
+
+    pub password: Option<String>,
+
+    /// Sender address, e.g. `Termion <termion@lab.example.com>`.
+    pub from: Option<String>,
+
+    /// Recipient addresses.
+    pub to: Vec<String>,
+
+    /// Events that are emailed.
+    pub events: Vec<NotificationEvent>,
+}
+
+impl EmailConfig {
+    /// The configured port, or the standard one for the TLS mode.
+    pub fn port(&self) -> u16 {
+        self.smtp_port.unwrap_or(match self.tls {
+            SmtpTls::StartTls => 587,
+            SmtpTls::Tls => 465,
+            SmtpTls::None => 25,
+        })
+    }
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: None,
+            smtp_port: None,
+            tls: SmtpTls::default(),
+            username: None,
+            password: None,
+            from: None,
+            to: Vec::new(),
+            events: vec![NotificationEvent::RunCompleted, NotificationEvent::RunError],
+        }
+    }
+}
+
+/// How the SMTP connection is encrypted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS.
+    #[default]
+    StartTls,
+    /// Connect over TLS from the start.
+    Tls,
+    /// No encryption, for relays on localhost.
+    None,
+}
+
+impl SmtpTls {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SmtpTls::StartTls => "starttls",
+            SmtpTls::Tls => "tls",
+            SmtpTls::None => "none",
+        }
+    }
+}
+
+impl std::str::FromStr for SmtpTls {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [SmtpTls::StartTls, SmtpTls::Tls, SmtpTls::None]
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// Layout of chat webhook messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatFormat {
+    /// Slack Block Kit `blocks`.
+    Slack,
+    /// Discord `embeds`.
+    Discord,
+}
+
+impl ChatFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChatFormat::Slack => "slack",
+            ChatFormat::Discord => "discord",
+        }
+    }
+}
+
+impl std::str::FromStr for ChatFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [ChatFormat::Slack, ChatFormat::Discord]
+            .into_iter()
+            .find(|f| f.as_str().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// How urgently a [`NotificationEvent`] needs attention.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Severity::Info, Severity::Warning, Severity::Error]
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// `termion daemon` settings.
+#[derive(Debug, Clone, Default)]
+pub struct DaemonConfig {
+    /// Address the HTTP JSON API listens on. `None` disables the API.
+    pub listen: Option<SocketAddr>,
+}
+
+/// `[metrics]`: pushing stats snapshots to a time-series database.
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    /// Write endpoint. `None` disables pushing.
+    pub url: Option<String>,
+
+    pub format: MetricsFormat,
+
+    /// Sent as `Authorization: Token <token>`, as InfluxDB expects.
+    pub token: Option<String>,
+
+    /// Time between pushes.
+    pub interval: Duration,
+
+    /// Influx measurement, or the metric name prefix for VictoriaMetrics.
+    pub measurement: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            format: MetricsFormat::default(),
+            token: None,
+            interval: Duration::from_secs(10),
+            measurement: "termion".to_string(),
+        }
+    }
+}
+
+/// Wire format of pushed metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// Influx line protocol, also accepted by VictoriaMetrics' `/write`.
+    #[default]
+    Influx,
+    /// VictoriaMetrics' JSON line import format, for `/api/v1/import`.
+    VictoriaMetrics,
+}
+
+impl MetricsFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricsFormat::Influx => "influx",
+            MetricsFormat::VictoriaMetrics => "victoriametrics",
+        }
+    }
+}
+
+impl std::str::FromStr for MetricsFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [MetricsFormat::Influx, MetricsFormat::VictoriaMetrics]
+            .into_iter()
+            .find(|f| f.as_str().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+/// Run lifecycle event that can trigger a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationEvent {
+    RunStarted,
+    RunCompleted,
+    RunPaused,
+    RunError,
+    Disconnected,
+}
+
+impl NotificationEvent {
+    pub const ALL: [NotificationEvent; 5] = [
+        NotificationEvent::RunStarted,
+        NotificationEvent::RunCompleted,
+        NotificationEvent::RunPaused,
+        NotificationEvent::RunError,
+        NotificationEvent::Disconnected,
+    ];
+
+    /// Name used in config files and payloads.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::RunStarted => "run_started",
+            NotificationEvent::RunCompleted => "run_completed",
+            NotificationEvent::RunPaused => "run_paused",
+            NotificationEvent::RunError => "run_error",
+            NotificationEvent::Disconnected => "disconnected",
+        }
+    }
+
+    /// Starts and completions are informational, pauses and lost
+    /// connections warnings, and failed runs errors.
+    pub fn severity(&self) -> Severity {
+        match self {
+            NotificationEvent::RunStarted | NotificationEvent::RunCompleted => Severity::Info,
+            NotificationEvent::RunPaused | NotificationEvent::Disconnected => Severity::Warning,
+            NotificationEvent::RunError => Severity::Error,
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationEvent {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NotificationEvent::ALL
+            .into_iter()
+            .find(|e| e.as_str().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
 /// Log level.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum LogLevel {
@@ -120,6 +814,20 @@ pub enum LogLevel {
     Trace,
 }
 
+impl LogLevel {
+    /// Name used in config files.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
 impl std::str::FromStr for LogLevel {
     type Err = ();
 
@@ -157,6 +865,16 @@ pub struct FileConfig {
     pub tui: Option<FileTuiConfig>,
     pub reconnect: Option<FileReconnectConfig>,
     pub logging: Option<FileLoggingConfig>,
+    pub history: Option<FileHistoryConfig>,
+    pub storage: Option<FileStorageConfig>,
+    pub notifications: Option<FileNotificationsConfig>,
+    pub alerts: Option<FileAlertsConfig>,
+    pub daemon: Option<FileDaemonConfig>,
+    pub metrics: Option<FileMetricsConfig>,
+    pub themes: Option<BTreeMap<String, FileThemeConfig>>,
+    /// Named connection settings, selected with `--profile`, that override
+    /// `[connection]`.
+    pub profiles: Option<BTreeMap<String, FileConnectionConfig>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -165,13 +883,112 @@ pub struct FileConnectionConfig {
     pub port: Option<u16>,
     pub connect_timeout: Option<u64>,
     pub request_timeout: Option<u64>,
+    pub max_concurrent_positions: Option<usize>,
+    pub ca_cert: Option<String>,
+    pub hosts: Option<Vec<String>>,
+    pub keepalive_interval: Option<u64>,
+    pub keepalive_timeout: Option<u64>,
+    pub tcp_nodelay: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct FileTuiConfig {
     pub refresh_interval: Option<u64>,
+    pub idle_refresh_interval: Option<u64>,
+    pub stats_interval: Option<u64>,
+    pub histogram_interval: Option<u64>,
+    pub histogram_buckets: Option<u32>,
+    pub histogram_outlier_percent: Option<f64>,
+    pub channel_states_interval: Option<u64>,
     pub chart_history: Option<u64>,
     pub theme: Option<String>,
+    pub timezone: Option<String>,
+    pub disk_warning_percent: Option<f64>,
+    pub min_qscore: Option<f64>,
+    pub expected_run_hours: Option<f64>,
+    pub pause_unfocused: Option<bool>,
+    pub pinned: Option<Vec<String>>,
+    pub hide_simulated: Option<bool>,
+    pub hide_idle: Option<bool>,
+    pub clock_time: Option<bool>,
+    pub highlight_changes: Option<bool>,
+    pub export_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileHistoryConfig {
+    pub enabled: Option<bool>,
+    pub path: Option<String>,
+    pub record_interval: Option<u64>,
+    pub max_age_days: Option<u64>,
+    pub max_size_mb: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileStorageConfig {
+    pub enabled: Option<bool>,
+    pub path: Option<String>,
+    pub record_interval: Option<u64>,
+    pub max_age_days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileNotificationsConfig {
+    pub webhook_url: Option<String>,
+    pub desktop: Option<bool>,
+    pub events: Option<Vec<String>>,
+    pub timeout: Option<u64>,
+    pub max_attempts: Option<u32>,
+    pub slack: Option<FileSlackConfig>,
+    pub email: Option<FileEmailConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileEmailConfig {
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub tls: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<Vec<String>>,
+    pub events: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileSlackConfig {
+    pub webhook_url: Option<String>,
+    pub format: Option<String>,
+    pub min_severity: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileAlertsConfig {
+    pub rules: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileDaemonConfig {
+    pub listen: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileMetricsConfig {
+    pub url: Option<String>,
+    pub format: Option<String>,
+    pub token: Option<String>,
+    /// Seconds between pushes.
+    pub interval: Option<u64>,
+    pub measurement: Option<String>,
+}
+
+/// A `[themes.<name>]` table: hex colors keyed by [`Theme`] field name,
+/// starting from the `base` built-in theme (`default` if unset).
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct FileThemeConfig {
+    pub base: Option<String>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -212,20 +1029,125 @@ pub enum ConfigError {
     #[error("Failed to serialize config: {}", .0)]
     Serialize(#[from] toml::ser::Error),
 
+    #[error("Failed to edit config file {}: {}", path.display(), message)]
+    Edit { path: PathBuf, message: String },
+
+    #[error("No config file to save to: no config directory, --config or TERMION_CONFIG")]
+    NoConfigFile,
+
+    #[error("No notes file to save to")]
+    NoNotesFile,
+
     #[error("Invalid port: must be non-zero")]
     InvalidPort,
 
     #[error("Invalid timeout: {} must be positive", .0)]
     InvalidTimeout(&'static str),
 
+    #[error("Unknown profile '{}': no [profiles.{}] section in the config file", .0, .0)]
+    UnknownProfile(String),
+
+    #[error("Invalid hosts entry '{}': expected host or host:port, each listed once", .0)]
+    InvalidHost(String),
+
     #[error("Invalid refresh interval: must be between 100ms and 60s")]
     InvalidRefreshInterval,
 
+    #[error("Invalid idle refresh interval: must be between refresh_interval and 10 minutes")]
+    InvalidIdleRefreshInterval,
+
+    #[error("Invalid {}: must be between 100ms and 10 minutes", .0)]
+    InvalidFetchInterval(&'static str),
+
     #[error("Invalid multiplier: must be greater than 1.0")]
     InvalidMultiplier,
 
     #[error("Invalid log level '{}': expected one of off, error, warn, info, debug, trace", .0)]
     InvalidLogLevel(String),
+
+    #[error("Invalid history record_interval: must be at least 1 second")]
+    InvalidRecordInterval,
+
+    #[error("Invalid storage record_interval: must be at least 1 second")]
+    InvalidStorageInterval,
+
+    #[error("Invalid max_concurrent_positions: must be at least 1")]
+    InvalidConcurrency,
+
+    #[error("Invalid timezone '{}': expected local, utc, or an offset like +02:00", .0)]
+    InvalidTimezone(String),
+
+    #[error("Invalid disk_warning_percent: must be between 0 and 100")]
+    InvalidDiskWarning,
+
+    #[error("Invalid min_qscore: must be between 0 and 60")]
+    InvalidMinQscore,
+
+    #[error("Invalid expected_run_hours: must be between 1 and 168")]
+    InvalidRunHours,
+
+    #[error("Invalid histogram_buckets: must be between 2 and 1000, or 0 to let MinKNOW choose")]
+    InvalidHistogramBuckets,
+
+    #[error("Invalid histogram_outlier_percent: must be above 0 and at most 50")]
+    InvalidOutlierPercent,
+
+    #[error("Invalid theme '{}': the name is taken by a built-in theme", .0)]
+    ThemeNameTaken(String),
+
+    #[error("Invalid theme '{}': unknown base theme '{}'", theme, base)]
+    UnknownThemeBase { theme: String, base: String },
+
+    #[error("Invalid theme '{}': unknown color '{}'", theme, field)]
+    UnknownThemeColor { theme: String, field: String },
+
+    #[error(
+        "Invalid theme '{}': {} = '{}' is not a hex color like #1e1e2e",
+        theme,
+        field,
+        value
+    )]
+    InvalidThemeColor {
+        theme: String,
+        field: String,
+        value: String,
+    },
+
+    #[error("Invalid notification event '{}': expected one of run_started, run_completed, run_paused, run_error, disconnected", .0)]
+    InvalidNotificationEvent(String),
+
+    #[error("Invalid chat format '{}': expected slack or discord", .0)]
+    InvalidChatFormat(String),
+
+    #[error("Invalid severity '{}': expected one of info, warning, error", .0)]
+    InvalidSeverity(String),
+
+    #[error("Invalid email tls '{}': expected one of starttls, tls, none", .0)]
+    InvalidSmtpTls(String),
+
+    #[error("Invalid notifications.email: {0}")]
+    InvalidEmail(&'static str),
+
+    #[error("Invalid metrics format '{}': expected influx or victoriametrics", .0)]
+    InvalidMetricsFormat(String),
+
+    #[error("Invalid metrics url '{}': must be an http:// or https:// URL", .0)]
+    InvalidMetricsUrl(String),
+
+    #[error("Invalid metrics measurement: must not be empty")]
+    InvalidMeasurement,
+
+    #[error("Invalid webhook_url '{}': must be an http:// or https:// URL", .0)]
+    InvalidWebhookUrl(String),
+
+    #[error("Invalid daemon listen address '{}': expected an IP address and port like 127.0.0.1:9510", .0)]
+    InvalidListenAddress(String),
+
+    #[error("Invalid notifications max_attempts: must be at least 1")]
+    InvalidMaxAttempts,
+
+    #[error("Invalid alert rule '{}': expected '<metric> < <value>' or '<metric> > <value>' where metric is one of throughput_gbph, active_pores, pass_rate, mean_quality, mean_read_length", .0)]
+    InvalidAlertRule(String),
 }
 
 #[cfg(test)]
@@ -264,6 +1186,38 @@ mod tests {
         assert_eq!(tracing::Level::from(LogLevel::Trace), tracing::Level::TRACE);
     }
 
+    #[test]
+    fn test_timezone_parsing() {
+        assert_eq!(
+            "local".parse::<DisplayTimezone>(),
+            Ok(DisplayTimezone::Local)
+        );
+        assert_eq!("UTC".parse::<DisplayTimezone>(), Ok(DisplayTimezone::Utc));
+        assert_eq!(
+            "+02:00".parse::<DisplayTimezone>(),
+            Ok(DisplayTimezone::Fixed(
+                FixedOffset::east_opt(2 * 3600).unwrap()
+            ))
+        );
+        assert!("Europe/Berlin".parse::<DisplayTimezone>().is_err());
+    }
+
+    #[test]
+    fn test_timezone_formatting() {
+        let ts = DateTime::parse_from_rfc3339("2024-03-01T22:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            DisplayTimezone::Utc.format_display(ts),
+            "2024-03-01 22:30 +00:00"
+        );
+
+        let tz: DisplayTimezone = "+02:00".parse().unwrap();
+        assert_eq!(tz.format_display(ts), "2024-03-02 00:30 +02:00");
+        assert_eq!(tz.to_rfc3339(ts), "2024-03-02T00:30:00+02:00");
+    }
+
     #[test]
     fn test_file_config_deserialization() {
         let toml = r#"
@@ -0,0 +1,46 @@
+//! Number formatting shared by the CLI commands, the TUI and notifications.
+
+pub(crate) fn format_number(n: u64) -> String {
+    if n >= 1_000_000 {
+        format!("{:.2}M", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.2}K", n as f64 / 1_000.0)
+    } else {
+        n.to_string()
+    }
+}
+
+/// Formats a byte count in decimal units (disk capacity, not bases).
+pub(crate) fn format_storage(bytes: u64) -> String {
+    if bytes >= 1_000_000_000_000 {
+        format!("{:.1} TB", bytes as f64 / 1_000_000_000_000.0)
+    } else if bytes >= 1_000_000_000 {
+        format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+    } else {
+        format!("{:.0} MB", bytes as f64 / 1_000_000.0)
+    }
+}
+
+pub(crate) fn format_bases(n: u64) -> String {
+    if n >= 1_000_000_000 {
+        format!("{:.2} Gb", n as f64 / 1_000_000_000.0)
+    } else if n >= 1_000_000 {
+        format!("{:.2} Mb", n as f64 / 1_000_000.0)
+    } else if n >= 1_000 {
+        format!("{:.2} Kb", n as f64 / 1_000.0)
+    } else {
+        format!("{} b", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_storage() {
+        assert_eq!(format_storage(2_500_000_000_000), "2.5 TB");
+        assert_eq!(format_storage(512_000_000_000), "512.0 GB");
+        assert_eq!(format_storage(750_000_000), "750 MB");
+    }
+}
@@ -0,0 +1,367 @@
+//! Local run history store.
+//!
+//! Stats snapshots recorded while the TUI runs are appended to one JSON Lines
+//! file per run, laid out as `<root>/<position>/<run_id>.jsonl`. The store is
+//! deliberately plain files so it can be inspected, copied, or deleted with
+//! ordinary tools.
+//!
+//! A [`RetentionPolicy`] bounds the store by age and total size; it is enforced
+//! by the [`recorder`] in the background and on demand by `termion history prune`.
+
+pub mod recorder;
+
+use crate::client::StatsSnapshot;
+use crate::config::HistoryConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const RECORD_EXTENSION: &str = "jsonl";
+
+/// A single recorded stats snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub timestamp: DateTime<Utc>,
+    pub position: String,
+    pub run_id: String,
+    pub reads_processed: u64,
+    pub reads_passed: u64,
+    pub reads_failed: u64,
+    pub bases_called: u64,
+    pub bases_passed: u64,
+    pub bases_failed: u64,
+    pub throughput_bps: f64,
+    pub mean_quality: f64,
+    pub active_pores: u32,
+}
+
+impl HistoryRecord {
+    pub fn from_stats(position: &str, run_id: &str, stats: &StatsSnapshot) -> Self {
+        Self {
+            timestamp: stats.timestamp.unwrap_or_else(Utc::now),
+            position: position.to_string(),
+            run_id: run_id.to_string(),
+            reads_processed: stats.reads_processed,
+            reads_passed: stats.reads_passed,
+            reads_failed: stats.reads_failed,
+            bases_called: stats.bases_called,
+            bases_passed: stats.bases_passed,
+            bases_failed: stats.bases_failed,
+            throughput_bps: stats.throughput_bps,
+            mean_quality: stats.mean_quality,
+            active_pores: stats.active_pores,
+        }
+    }
+}
+
+/// A recorded run on disk.
+#[derive(Debug, Clone)]
+pub struct RunFile {
+    pub position: String,
+    pub run_id: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+/// Limits applied when pruning the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Runs last written longer ago than this are removed.
+    pub max_age: Option<Duration>,
+    /// Oldest runs are removed until the store is at most this many bytes.
+    pub max_size: Option<u64>,
+}
+
+impl From<&HistoryConfig> for RetentionPolicy {
+    fn from(config: &HistoryConfig) -> Self {
+        Self {
+            max_age: config.max_age,
+            max_size: config.max_size,
+        }
+    }
+}
+
+/// Outcome of a prune pass.
+#[derive(Debug, Clone, Default)]
+pub struct PruneReport {
+    pub removed: Vec<RunFile>,
+    pub bytes_freed: u64,
+    pub bytes_remaining: u64,
+}
+
+/// File-backed history store.
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    root: PathBuf,
+}
+
+impl HistoryStore {
+    /// Opens the store at `root`. The directory is created on first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Appends a record to its run file.
+    pub fn append(&self, record: &HistoryRecord) -> io::Result<()> {
+        let dir = self.root.join(sanitize(&record.position));
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.{}", sanitize(&record.run_id), RECORD_EXTENSION));
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())
+    }
+
+    /// Lists all recorded runs, oldest first.
+    pub fn runs(&self) -> io::Result<Vec<RunFile>> {
+        let mut runs = Vec::new();
+        let positions = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(runs),
+            Err(e) => return Err(e),
+        };
+
+        for position_dir in positions {
+            let position_dir = position_dir?;
+            if !position_dir.file_type()?.is_dir() {
+                continue;
+            }
+            let position = position_dir.file_name().to_string_lossy().into_owned();
+            for entry in fs::read_dir(position_dir.path())? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some(RECORD_EXTENSION) {
+                    continue;
+                }
+                let metadata = entry.metadata()?;
+                runs.push(RunFile {
+                    position: position.clone(),
+                    run_id: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    path,
+                    size: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+        }
+
+        runs.sort_by_key(|r| r.modified);
+        Ok(runs)
+    }
+
+    /// Reads all records of a run, skipping lines that fail to parse.
+    pub fn read_run(&self, run: &RunFile) -> io::Result<Vec<HistoryRecord>> {
+        let reader = BufReader::new(File::open(&run.path)?);
+        let mut records = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            match serde_json::from_str(&line) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    tracing::warn!(path = %run.path.display(), error = %e, "Skipping malformed history record")
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    /// Removes runs that violate the retention policy.
+    ///
+    /// Runs older than `max_age` are removed first; then, if the store is
+    /// still larger than `max_size`, the oldest remaining runs are removed
+    /// until it fits. With `dry_run` set nothing is deleted, but the report
+    /// describes what would have been.
+    pub fn prune(
+        &self,
+        policy: &RetentionPolicy,
+        now: SystemTime,
+        dry_run: bool,
+    ) -> io::Result<PruneReport> {
+        let runs = self.runs()?;
+        let mut report = PruneReport::default();
+        let mut kept = Vec::new();
+
+        for run in runs {
+            let age = now.duration_since(run.modified).unwrap_or_default();
+            if policy.max_age.is_some_and(|max| age > max) {
+                report.removed.push(run);
+            } else {
+                kept.push(run);
+            }
+        }
+
+        let mut total: u64 = kept.iter().map(|r| r.size).sum();
+        if let Some(max_size) = policy.max_size {
+            // `kept` is oldest first.
+            let mut kept_iter = kept.into_iter();
+            while total > max_size {
+                match kept_iter.next() {
+                    Some(run) => {
+                        total -= run.size;
+                        report.removed.push(run);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        report.bytes_remaining = total;
+        report.bytes_freed = report.removed.iter().map(|r| r.size).sum();
+
+        if !dry_run {
+            for run in &report.removed {
+                fs::remove_file(&run.path)?;
+                if let Some(dir) = run.path.parent() {
+                    // Only succeeds once the position directory is empty.
+                    let _ = fs::remove_dir(dir);
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Makes a name safe to use as a single path component.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(position: &str, run_id: &str, reads: u64) -> HistoryRecord {
+        HistoryRecord {
+            timestamp: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            position: position.to_string(),
+            run_id: run_id.to_string(),
+            reads_processed: reads,
+            reads_passed: 0,
+            reads_failed: 0,
+            bases_called: 0,
+            bases_passed: 0,
+            bases_failed: 0,
+            throughput_bps: 0.0,
+            mean_quality: 0.0,
+            active_pores: 0,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path());
+
+        store.append(&record("X1", "run-a", 1)).unwrap();
+        store.append(&record("X1", "run-a", 2)).unwrap();
+        store.append(&record("X2", "run-b", 3)).unwrap();
+
+        let runs = store.runs().unwrap();
+        assert_eq!(runs.len(), 2);
+
+        let run_a = runs.iter().find(|r| r.run_id == "run-a").unwrap();
+        assert_eq!(run_a.position, "X1");
+        let records = store.read_run(run_a).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].reads_processed, 2);
+    }
+
+    #[test]
+    fn test_missing_root_has_no_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().join("missing"));
+        assert!(store.runs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sanitize_path_components() {
+        assert_eq!(sanitize("../etc"), ".._etc");
+        assert_eq!(sanitize("1A"), "1A");
+        assert_eq!(sanitize("a/b c"), "a_b_c");
+    }
+
+    #[test]
+    fn test_prune_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path());
+        store.append(&record("X1", "old", 1)).unwrap();
+
+        let policy = RetentionPolicy {
+            max_age: Some(Duration::from_secs(3600)),
+            max_size: None,
+        };
+
+        let report = store.prune(&policy, SystemTime::now(), false).unwrap();
+        assert!(report.removed.is_empty());
+
+        let later = SystemTime::now() + Duration::from_secs(7200);
+        let report = store.prune(&policy, later, false).unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert!(store.runs().unwrap().is_empty());
+        assert!(!dir.path().join("X1").exists());
+    }
+
+    #[test]
+    fn test_prune_by_size_removes_oldest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path());
+        store.append(&record("X1", "run-1", 1)).unwrap();
+        let first = store.runs().unwrap()[0].clone();
+        // Ensure distinct modification times.
+        let old = SystemTime::now() - Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&first.path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        store.append(&record("X1", "run-2", 2)).unwrap();
+
+        let policy = RetentionPolicy {
+            max_age: None,
+            max_size: Some(first.size),
+        };
+        let report = store.prune(&policy, SystemTime::now(), false).unwrap();
+
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].run_id, "run-1");
+        let remaining = store.runs().unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].run_id, "run-2");
+    }
+
+    #[test]
+    fn test_prune_dry_run_keeps_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path());
+        store.append(&record("X1", "run", 1)).unwrap();
+
+        let policy = RetentionPolicy {
+            max_age: None,
+            max_size: Some(0),
+        };
+        let report = store.prune(&policy, SystemTime::now(), true).unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert!(report.bytes_freed > 0);
+        assert_eq!(store.runs().unwrap().len(), 1);
+    }
+}
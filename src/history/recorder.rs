@@ -0,0 +1,171 @@
+//! Background task that records stats snapshots from the event bus.
+
+use super::{HistoryRecord, HistoryStore, RetentionPolicy};
+use crate::bus::{BusEvent, EventBus, EventFilter, EventKind, Subscription};
+use crate::config::HistoryConfig;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::task::JoinHandle;
+
+/// How often retention is enforced while recording.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Spawns the recorder. It runs until the bus is dropped.
+pub fn spawn(bus: &EventBus, config: &HistoryConfig) -> JoinHandle<()> {
+    let subscription =
+        bus.subscribe(EventFilter::all().kinds(&[EventKind::State, EventKind::Data]));
+    let recorder = Recorder::new(config);
+    tokio::spawn(recorder.run(subscription))
+}
+
+struct Recorder {
+    store: HistoryStore,
+    policy: RetentionPolicy,
+    record_interval: Duration,
+    /// Current run ID per position, learned from run info events.
+    run_ids: HashMap<String, String>,
+    last_recorded: HashMap<String, Instant>,
+}
+
+impl Recorder {
+    fn new(config: &HistoryConfig) -> Self {
+        Self {
+            store: HistoryStore::new(&config.path),
+            policy: RetentionPolicy::from(config),
+            record_interval: config.record_interval,
+            run_ids: HashMap::new(),
+            last_recorded: HashMap::new(),
+        }
+    }
+
+    async fn run(mut self, mut subscription: Subscription) {
+        self.prune();
+        let mut last_prune = Instant::now();
+
+        while let Some(event) = subscription.recv().await {
+            self.handle(&event);
+
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                self.prune();
+                last_prune = Instant::now();
+            }
+        }
+    }
+
+    fn handle(&mut self, event: &BusEvent) {
+        match event {
+            BusEvent::RunInfo { position, info } => {
+                if let Some(run_id) = &info.run_id {
+                    self.run_ids.insert(position.clone(), run_id.clone());
+                }
+            }
+            BusEvent::RunStateChanged { position, state } if !state.is_active() => {
+                self.run_ids.remove(position);
+                self.last_recorded.remove(position);
+            }
+            BusEvent::Stats { position, stats } => {
+                let Some(run_id) = self.run_ids.get(position) else {
+                    return;
+                };
+                if self
+                    .last_recorded
+                    .get(position)
+                    .is_some_and(|t| t.elapsed() < self.record_interval)
+                {
+                    return;
+                }
+
+                let record = HistoryRecord::from_stats(position, run_id, stats);
+                match self.store.append(&record) {
+                    Ok(()) => {
+                        self.last_recorded.insert(position.clone(), Instant::now());
+                    }
+                    Err(e) => {
+                        tracing::warn!(position = %position, error = %e, "Failed to record history")
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn prune(&self) {
+        match self.store.prune(&self.policy, SystemTime::now(), false) {
+            Ok(report) if !report.removed.is_empty() => tracing::info!(
+                runs = report.removed.len(),
+                bytes = report.bytes_freed,
+                "Pruned run history"
+            ),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to prune run history"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{RunInfo, RunState, StatsSnapshot};
+
+    fn config(path: &std::path::Path) -> HistoryConfig {
+        HistoryConfig {
+            enabled: true,
+            path: path.to_path_buf(),
+            record_interval: Duration::from_secs(60),
+            max_age: None,
+            max_size: None,
+        }
+    }
+
+    fn stats(position: &str) -> BusEvent {
+        BusEvent::Stats {
+            position: position.to_string(),
+            stats: StatsSnapshot::default(),
+        }
+    }
+
+    #[test]
+    fn test_records_only_runs_with_known_id_and_throttles() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::new(&config(dir.path()));
+
+        recorder.handle(&stats("X1"));
+        assert!(recorder.store.runs().unwrap().is_empty());
+
+        recorder.handle(&BusEvent::RunInfo {
+            position: "X1".into(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                ..Default::default()
+            },
+        });
+        recorder.handle(&stats("X1"));
+        recorder.handle(&stats("X1"));
+
+        let runs = recorder.store.runs().unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, "run-1");
+        assert_eq!(recorder.store.read_run(&runs[0]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_run_end_forgets_run_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = Recorder::new(&config(dir.path()));
+
+        recorder.handle(&BusEvent::RunInfo {
+            position: "X1".into(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                ..Default::default()
+            },
+        });
+        recorder.handle(&BusEvent::RunStateChanged {
+            position: "X1".into(),
+            state: RunState::Idle,
+        });
+        recorder.handle(&stats("X1"));
+
+        assert!(recorder.store.runs().unwrap().is_empty());
+    }
+}
@@ -11,15 +11,34 @@
 //! ## Modules
 //!
 //! - [`client`] — gRPC client for MinKNOW API
+//! - [`alerts`] — Threshold alert rules for run metrics
+//! - [`analysis`] — Yield projections to the end of a run
+//! - [`bus`] — Internal event bus connecting the client to subscribers
+//! - [`history`] — Local run history store and retention
+//! - [`notify`] — Webhook notifications for run lifecycle events
+//! - [`session`] — Session recording and replay files
+//! - [`sink`] — Metrics pushed to a time-series database
+//! - `storage` — SQLite run metrics database (with the `storage` feature)
 //! - [`tui`] — Terminal user interface
 //! - [`cli`] — Command-line interface commands
 //! - [`config`] — Configuration loading and validation
+//! - [`format`] — Number formatting shared across the crate
 
+pub mod alerts;
+pub mod analysis;
+pub mod bus;
 pub mod cli;
 pub mod client;
 pub mod config;
+pub mod format;
+pub mod history;
 pub mod logging;
+pub mod notify;
 mod proto;
+pub mod session;
+pub mod sink;
+#[cfg(feature = "storage")]
+pub mod storage;
 pub mod tui;
 
 // Re-export commonly used types
@@ -4,7 +4,10 @@
 //! because the TUI owns the terminal screen.
 
 use crate::config::{LogLevel, LoggingConfig};
+use std::backtrace::Backtrace;
 use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
@@ -57,3 +60,46 @@ pub fn init(config: &LoggingConfig) -> anyhow::Result<Option<WorkerGuard>> {
 
     Ok(Some(guard))
 }
+
+/// Appends a panic and its backtrace to the log file.
+///
+/// Written directly rather than through `tracing`, whose background writer
+/// may not get to run before the process exits.
+pub fn log_panic(file: &Path, panic: &str, backtrace: &Backtrace) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(file)?;
+    writeln!(
+        file,
+        "{} ERROR {}\nstack backtrace:\n{}",
+        chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+        panic,
+        backtrace
+    )?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_panic_appends_to_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("termion.log");
+        fs::write(&path, "earlier line\n").unwrap();
+
+        log_panic(
+            &path,
+            "panicked at src/tui/ui.rs:1:1: oops",
+            &Backtrace::disabled(),
+        )
+        .unwrap();
+
+        let log = fs::read_to_string(&path).unwrap();
+        assert!(log.starts_with("earlier line\n"));
+        assert!(log.contains("ERROR panicked at src/tui/ui.rs:1:1: oops"));
+        assert!(log.contains("stack backtrace:"));
+    }
+}
@@ -26,6 +26,8 @@ async fn main() -> ExitCode {
 
     match run(cli).await {
         Ok(()) => Exit::Ok.into(),
+        // A reader that stops early, like `head`, ends the output normally.
+        Err(e) if is_broken_pipe(&e) => Exit::Ok.into(),
         Err(e) => {
             let exit = exit_code_for_error(&e);
             eprintln!("Error: {e}");
@@ -34,7 +36,32 @@ async fn main() -> ExitCode {
     }
 }
 
+fn is_broken_pipe(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+    })
+}
+
 async fn run(cli: Cli) -> anyhow::Result<()> {
+    if cli.command.is_some() {
+        let tui_only = [
+            ("--demo", cli.demo),
+            ("--record", cli.record.is_some()),
+            ("--replay", cli.replay.is_some()),
+        ];
+        if let Some((flag, _)) = tui_only.iter().find(|(_, set)| *set) {
+            anyhow::bail!("Invalid argument: {flag} only applies to the TUI");
+        }
+    }
+
+    // Config commands report on the config itself, so they must work when
+    // it does not load.
+    if let Some(Commands::Config { action }) = &cli.command {
+        return termion::cli::config::run(&cli, action);
+    }
+
     // Load configuration with proper precedence
     let config = Config::load(&cli)?;
 
@@ -42,10 +69,71 @@ async fn run(cli: Cli) -> anyhow::Result<()> {
     let _log_guard = termion::logging::init(&config.logging)?;
 
     match cli.command {
-        Some(Commands::List { json }) => termion::cli::list::run(&config, json).await,
-        Some(Commands::Status { json, position }) => {
-            termion::cli::status::run(&config, json, position).await
+        Some(Commands::List { json, format }) => {
+            termion::cli::list::run(&config, format.or_json(json)).await
+        }
+        Some(Commands::Status {
+            json,
+            format,
+            ndjson,
+            follow,
+            interval,
+            position,
+        }) => {
+            let follow = follow.then(|| std::time::Duration::from_secs(interval));
+            termion::cli::status::run(&config, format.or_json(json), ndjson, follow, position).await
+        }
+        Some(Commands::Watch {
+            ndjson,
+            interval,
+            position,
+        }) => {
+            termion::cli::watch::run(
+                &config,
+                ndjson,
+                std::time::Duration::from_secs(interval),
+                position,
+            )
+            .await
+        }
+        Some(Commands::Logs {
+            position,
+            follow,
+            json,
+            level,
+        }) => termion::cli::logs::run(&config, position, follow, json, level).await,
+        Some(Commands::Start {
+            position,
+            kit,
+            protocol,
+            sample_id,
+            experiment_group,
+            json,
+        }) => {
+            termion::cli::start::run(
+                &config,
+                &position,
+                kit.as_deref(),
+                protocol.as_deref(),
+                &sample_id,
+                &experiment_group,
+                json,
+            )
+            .await
+        }
+        Some(Commands::Report {
+            position,
+            run,
+            format,
+            output,
+        }) => termion::cli::report::run(&config, &position, run.as_deref(), format, output).await,
+        Some(Commands::Snapshot { position, output }) => {
+            termion::cli::snapshot::run(&config, position, output).await
         }
+        Some(Commands::Daemon { .. }) => termion::tui::run_daemon(config).await,
+        Some(Commands::Doctor { json }) => termion::cli::doctor::run(&config, json).await,
+        Some(Commands::History { action }) => termion::cli::history::run(&config, action),
+        Some(Commands::Config { .. }) => unreachable!("config commands run before loading"),
         None => {
             // Default: launch TUI
             termion::tui::run(config).await
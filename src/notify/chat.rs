@@ -0,0 +1,157 @@
+//! Slack and Discord message bodies for lifecycle notifications.
+//!
+//! Both are posted to an incoming webhook like the plain JSON payload, but
+//! laid out for people: a title, then the position, run ID, yield so far
+//! and event type as fields, colored by the event's severity.
+
+use super::Notification;
+use crate::config::{ChatFormat, Severity};
+use crate::format::format_bases;
+use serde_json::{json, Value};
+
+/// Builds the message body for `format`.
+pub(super) fn message(format: ChatFormat, notification: &Notification) -> Value {
+    match format {
+        ChatFormat::Slack => slack(notification),
+        ChatFormat::Discord => discord(notification),
+    }
+}
+
+/// Name and value of each field the notification has.
+fn fields(notification: &Notification) -> Vec<(&'static str, String)> {
+    [
+        ("Position", notification.position.clone()),
+        ("Run ID", notification.run_id.clone()),
+        ("Sample", notification.sample_id.clone()),
+        ("Yield", notification.bases.map(format_bases)),
+        ("Event", Some(notification.event.as_str().to_string())),
+        ("Host", Some(notification.host.clone())),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| Some((name, value?)))
+    .collect()
+}
+
+fn emoji(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => ":white_check_mark:",
+        Severity::Warning => ":warning:",
+        Severity::Error => ":x:",
+    }
+}
+
+/// Embed color, as Discord's decimal RGB.
+fn color(severity: Severity) -> u32 {
+    match severity {
+        Severity::Info => 0x2eb67d,
+        Severity::Warning => 0xecb22e,
+        Severity::Error => 0xe01e5a,
+    }
+}
+
+fn slack(notification: &Notification) -> Value {
+    let title = notification.title();
+    let fields: Vec<_> = fields(notification)
+        .into_iter()
+        .map(|(name, value)| json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", name, value) }))
+        .collect();
+    let mut blocks = vec![
+        json!({
+            "type": "header",
+            "text": {
+                "type": "plain_text",
+                "text": format!("{} {}", emoji(notification.event.severity()), title),
+                "emoji": true,
+            },
+        }),
+        json!({ "type": "section", "fields": fields }),
+    ];
+    if let Some(message) = &notification.message {
+        blocks.push(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!("> {}", message) },
+        }));
+    }
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": notification.timestamp.to_rfc3339() }],
+    }));
+    // `text` is shown in notifications and by clients without block support.
+    json!({ "text": title, "blocks": blocks })
+}
+
+fn discord(notification: &Notification) -> Value {
+    let fields: Vec<_> = fields(notification)
+        .into_iter()
+        .map(|(name, value)| json!({ "name": name, "value": value, "inline": true }))
+        .collect();
+    let mut embed = json!({
+        "title": notification.title(),
+        "color": color(notification.event.severity()),
+        "fields": fields,
+        "timestamp": notification.timestamp.to_rfc3339(),
+    });
+    if let Some(message) = &notification.message {
+        embed["description"] = json!(message);
+    }
+    json!({ "embeds": [embed] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotificationEvent;
+
+    fn completed() -> Notification {
+        Notification {
+            position: Some("X1".into()),
+            run_id: Some("run-1".into()),
+            bases: Some(2_500_000_000),
+            ..Notification::new(NotificationEvent::RunCompleted, "localhost")
+        }
+    }
+
+    #[test]
+    fn test_slack_blocks() {
+        let body = message(ChatFormat::Slack, &completed());
+
+        assert_eq!(body["text"], "Run completed on X1");
+        let blocks = body["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        let fields: Vec<_> = blocks[1]["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["text"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                "*Position*\nX1",
+                "*Run ID*\nrun-1",
+                "*Yield*\n2.50 Gb",
+                "*Event*\nrun_completed",
+                "*Host*\nlocalhost",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discord_embed() {
+        let failed = Notification {
+            message: Some("pore fault".into()),
+            ..Notification {
+                event: NotificationEvent::RunError,
+                ..completed()
+            }
+        };
+        let body = message(ChatFormat::Discord, &failed);
+
+        let embed = &body["embeds"][0];
+        assert_eq!(embed["title"], "Run failed on X1");
+        assert_eq!(embed["description"], "pore fault");
+        assert_eq!(embed["color"], 0xe01e5a);
+        assert_eq!(embed["fields"][0]["name"], "Position");
+        assert_eq!(embed["fields"][0]["value"], "X1");
+    }
+}
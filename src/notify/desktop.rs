@@ -0,0 +1,106 @@
+//! Desktop notifications for critical lifecycle events.
+//!
+//! Shown through the platform's notification service when
+//! `notifications.desktop` is set, so an operator with the TUI in a
+//! background terminal still learns that a run needs attention. Only built
+//! with the `desktop-notifications` feature; without it, enabling them logs
+//! a warning and does nothing.
+
+use super::Notification;
+use crate::bus::EventBus;
+use crate::config::{Config, NotificationEvent};
+use tokio::task::JoinHandle;
+
+/// Events that are worth interrupting the operator for.
+pub const DESKTOP_EVENTS: [NotificationEvent; 4] = [
+    NotificationEvent::RunCompleted,
+    NotificationEvent::RunError,
+    NotificationEvent::RunPaused,
+    NotificationEvent::Disconnected,
+];
+
+/// Spawns the desktop notifier if desktop notifications are enabled.
+///
+/// It runs until the bus is dropped.
+#[cfg(feature = "desktop-notifications")]
+pub fn spawn(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    use super::LifecycleTracker;
+    use crate::bus::{EventFilter, EventKind};
+
+    if !config.notifications.desktop {
+        return None;
+    }
+    let mut subscription =
+        bus.subscribe(EventFilter::all().kinds(&[EventKind::Connection, EventKind::State]));
+    let mut tracker = LifecycleTracker::new(&config.connection.host);
+    Some(tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            for notification in tracker.handle(&event) {
+                if DESKTOP_EVENTS.contains(&notification.event) {
+                    // Showing a notification can block on the session bus.
+                    tokio::task::spawn_blocking(move || show(&notification));
+                }
+            }
+        }
+    }))
+}
+
+/// Spawns nothing: this build has no desktop notification support.
+#[cfg(not(feature = "desktop-notifications"))]
+pub fn spawn(_bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    if config.notifications.desktop {
+        tracing::warn!("Desktop notifications need a build with the desktop-notifications feature");
+    }
+    None
+}
+
+#[cfg(feature = "desktop-notifications")]
+fn show(notification: &Notification) {
+    let result = notify_rust::Notification::new()
+        .appname("Termion")
+        .summary(&notification.title())
+        .body(&body(notification))
+        .show();
+    match result {
+        Ok(_) => tracing::debug!(
+            event = notification.event.as_str(),
+            "Showed desktop notification"
+        ),
+        Err(e) => tracing::warn!(error = %e, "Failed to show desktop notification"),
+    }
+}
+
+/// The run, sample and reason, one per line, leaving out what is unknown.
+#[cfg_attr(not(feature = "desktop-notifications"), allow(dead_code))]
+fn body(notification: &Notification) -> String {
+    [
+        notification.run_id.as_ref().map(|id| format!("Run {}", id)),
+        notification
+            .sample_id
+            .as_ref()
+            .map(|id| format!("Sample {}", id)),
+        notification.message.clone(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let mut notification = Notification::new(NotificationEvent::RunError, "localhost");
+        notification.position = Some("X1".into());
+        notification.run_id = Some("run-1".into());
+        notification.message = Some("pore fault".into());
+
+        assert_eq!(body(&notification), "Run run-1\npore fault");
+
+        let lost = Notification::new(NotificationEvent::Disconnected, "gridion:9501");
+        assert_eq!(body(&lost), "");
+    }
+}
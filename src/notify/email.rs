@@ -0,0 +1,225 @@
+//! Email notifications through an SMTP server.
+//!
+//! Sends a short plain-text summary of each configured lifecycle event, by
+//! default run completions and failures, so whoever left a long run
+//! unattended hears how it went. Only built with the `email-notifications`
+//! feature; without it, configuring an SMTP server logs a warning and does
+//! nothing.
+
+use super::Notification;
+use crate::bus::EventBus;
+use crate::config::Config;
+use crate::format::format_bases;
+use tokio::task::JoinHandle;
+
+/// Spawns the email notifier if an SMTP server is configured.
+///
+/// It runs until the bus is dropped, then finishes sending whatever is
+/// still queued.
+#[cfg(feature = "email-notifications")]
+pub fn spawn(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    use super::LifecycleTracker;
+    use crate::bus::{EventFilter, EventKind};
+
+    let mailer = match smtp::Mailer::new(config) {
+        Ok(mailer) => mailer?,
+        Err(e) => {
+            tracing::warn!(error = %e, "Email notifications disabled");
+            return None;
+        }
+    };
+    // Statistics and histograms give the summary its yield, N50 and pass
+    // rate.
+    let mut subscription = bus.subscribe(EventFilter::all().kinds(&[
+        EventKind::Connection,
+        EventKind::State,
+        EventKind::Data,
+    ]));
+    let mut tracker = LifecycleTracker::new(&config.connection.host);
+    let events = config.notifications.email.events.clone();
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            for notification in tracker.handle(&event) {
+                if events.contains(&notification.event) {
+                    let _ = tx.send(notification);
+                }
+            }
+        }
+    });
+    Some(tokio::spawn(async move {
+        while let Some(notification) = rx.recv().await {
+            mailer.send(&notification).await;
+        }
+    }))
+}
+
+/// Spawns nothing: this build has no email support.
+#[cfg(not(feature = "email-notifications"))]
+pub fn spawn(_bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    if config.notifications.email.smtp_host.is_some() {
+        tracing::warn!("Email notifications need a build with the email-notifications feature");
+    }
+    None
+}
+
+#[cfg(feature = "email-notifications")]
+mod smtp {
+    use super::{body, subject, Notification};
+    use crate::config::{Config, SmtpTls};
+    use lettre::message::header::ContentType;
+    use lettre::message::Mailbox;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+    use std::time::Duration;
+
+    /// Delay before the first retry of a failed send; doubled per attempt.
+    const RETRY_INITIAL_DELAY: Duration = Duration::from_secs(5);
+
+    pub(super) struct Mailer {
+        transport: AsyncSmtpTransport<Tokio1Executor>,
+        from: Mailbox,
+        to: Vec<Mailbox>,
+        max_attempts: u32,
+    }
+
+    impl Mailer {
+        /// Builds the mailer, or `None` if no SMTP server is configured.
+        pub(super) fn new(config: &Config) -> Result<Option<Self>, String> {
+            let email = &config.notifications.email;
+            let Some(host) = &email.smtp_host else {
+                return Ok(None);
+            };
+            let builder = match email.tls {
+                SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+                SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+                SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                    host,
+                )),
+            }
+            .map_err(|e| e.to_string())?;
+            let mut builder = builder
+                .port(email.port())
+                .timeout(Some(config.notifications.timeout));
+            if let (Some(username), Some(password)) = (&email.username, &email.password) {
+                builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+            }
+
+            let parse = |address: &str| {
+                address
+                    .parse::<Mailbox>()
+                    .map_err(|e| format!("invalid address '{}': {}", address, e))
+            };
+            Ok(Some(Self {
+                transport: builder.build(),
+                from: parse(email.from.as_deref().unwrap_or_default())?,
+                to: email
+                    .to
+                    .iter()
+                    .map(|to| parse(to))
+                    .collect::<Result<_, _>>()?,
+                max_attempts: config.notifications.max_attempts,
+            }))
+        }
+
+        /// Sends one email, retrying with backoff before giving up.
+        pub(super) async fn send(&self, notification: &Notification) {
+            let message = self
+                .to
+                .iter()
+                .fold(Message::builder().from(self.from.clone()), |builder, to| {
+                    builder.to(to.clone())
+                })
+                .subject(subject(notification))
+                .header(ContentType::TEXT_PLAIN)
+                .body(body(notification));
+            let message = match message {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to build notification email");
+                    return;
+                }
+            };
+
+            let mut delay = RETRY_INITIAL_DELAY;
+            for attempt in 1..=self.max_attempts {
+                match self.transport.send(message.clone()).await {
+                    Ok(_) => {
+                        tracing::info!(event = notification.event.as_str(), "Sent email");
+                        return;
+                    }
+                    Err(e) if attempt < self.max_attempts => {
+                        tracing::debug!(attempt, error = %e, "Email failed; retrying");
+                        tokio::time::sleep(delay).await;
+                        delay *= 2;
+                    }
+                    Err(e) => tracing::warn!(
+                        event = notification.event.as_str(),
+                        attempts = attempt,
+                        error = %e,
+                        "Dropping email"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "email-notifications"), allow(dead_code))]
+fn subject(notification: &Notification) -> String {
+    format!("[Termion] {}", notification.title())
+}
+
+/// Plain-text summary: what happened, then one line per known detail.
+#[cfg_attr(not(feature = "email-notifications"), allow(dead_code))]
+fn body(notification: &Notification) -> String {
+    let mut text = format!(
+        "{} ({}) at {}.\n\n",
+        notification.title(),
+        notification.host,
+        notification.timestamp.to_rfc3339()
+    );
+    let details = [
+        ("Run ID", notification.run_id.clone()),
+        ("Sample", notification.sample_id.clone()),
+        ("Experiment group", notification.experiment_group.clone()),
+        ("Yield", notification.bases.map(format_bases)),
+        ("N50", notification.n50.map(|n50| format!("{} bp", n50))),
+        (
+            "Pass rate",
+            notification.pass_rate.map(|rate| format!("{:.1}%", rate)),
+        ),
+        ("Reason", notification.message.clone()),
+    ];
+    for (name, value) in details {
+        if let Some(value) = value {
+            text.push_str(&format!("{}: {}\n", name, value));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotificationEvent;
+
+    #[test]
+    fn test_subject_and_body() {
+        let notification = Notification {
+            position: Some("X1".into()),
+            run_id: Some("run-1".into()),
+            bases: Some(12_500_000_000),
+            n50: Some(8_200),
+            pass_rate: Some(91.34),
+            ..Notification::new(NotificationEvent::RunCompleted, "gridion")
+        };
+
+        assert_eq!(subject(&notification), "[Termion] Run completed on X1");
+        let body = body(&notification);
+        assert!(body.starts_with("Run completed on X1 (gridion) at "));
+        assert!(
+            body.ends_with("\n\nRun ID: run-1\nYield: 12.50 Gb\nN50: 8200 bp\nPass rate: 91.3%\n")
+        );
+    }
+}
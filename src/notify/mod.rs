@@ -0,0 +1,389 @@
+//! Webhook and desktop notifications for run lifecycle events.
+//!
+//! A [`LifecycleTracker`] watches run state and connection events from the
+//! bus and turns transitions into [`Notification`]s. The [`notifier`] task
+//! POSTs them as JSON to the configured webhook, and as formatted messages
+//! to a Slack or Discord webhook, retrying failed deliveries; the
+//! [`desktop`] task shows the critical ones as desktop notifications, and
+//! the [`email`] task emails run summaries.
+
+mod chat;
+pub mod desktop;
+pub mod email;
+pub mod notifier;
+pub(crate) mod webhook;
+
+pub use webhook::WebhookError;
+
+use crate::bus::BusEvent;
+use crate::client::{RunInfo, RunState, StatsSnapshot};
+use crate::config::NotificationEvent;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// JSON payload sent to the webhook.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub timestamp: DateTime<Utc>,
+    /// MinKNOW host the event was observed on.
+    pub host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experiment_group: Option<String>,
+    /// Bases called so far in the run, as of the latest statistics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bases: Option<u64>,
+    /// Percentage of reads passing filters, as of the latest statistics.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pass_rate: Option<f64>,
+    /// Read length N50 in bases, once a read length histogram has been
+    /// fetched for the run.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n50: Option<u64>,
+    /// Error or disconnect reason.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl Notification {
+    fn new(event: NotificationEvent, host: &str) -> Self {
+        Self {
+            event,
+            timestamp: Utc::now(),
+            host: host.to_string(),
+            position: None,
+            run_id: None,
+            sample_id: None,
+            experiment_group: None,
+            bases: None,
+            pass_rate: None,
+            n50: None,
+            message: None,
+        }
+    }
+
+    /// One-line summary, e.g. "Run completed on X1".
+    pub fn title(&self) -> String {
+        let position = self.position.as_deref().unwrap_or("?");
+        match self.event {
+            NotificationEvent::RunStarted => format!("Run started on {}", position),
+            NotificationEvent::RunCompleted => format!("Run completed on {}", position),
+            NotificationEvent::RunPaused => format!("Run paused on {}", position),
+            NotificationEvent::RunError => format!("Run failed on {}", position),
+            NotificationEvent::Disconnected => format!("Lost connection to {}", self.host),
+        }
+    }
+
+    fn for_run(
+        event: NotificationEvent,
+        host: &str,
+        position: &str,
+        info: Option<&RunInfo>,
+    ) -> Self {
+        Self {
+            position: Some(position.to_string()),
+            run_id: info.and_then(|i| i.run_id.clone()),
+            sample_id: info.and_then(|i| i.sample_id.clone()),
+            experiment_group: info.and_then(|i| i.experiment_group.clone()),
+            ..Self::new(event, host)
+        }
+    }
+}
+
+/// Derives lifecycle notifications from bus events.
+///
+/// Only transitions are reported: the first state seen for a position is a
+/// baseline, so attaching to a running sequencer does not announce a start.
+#[derive(Debug)]
+pub struct LifecycleTracker {
+    host: String,
+    states: HashMap<String, RunState>,
+    runs: HashMap<String, RunInfo>,
+    /// Latest statistics of each position's current run.
+    stats: HashMap<String, StatsSnapshot>,
+    /// Read length N50 of each position's current run.
+    n50: HashMap<String, u64>,
+    /// Positions whose run started but whose run info has not arrived yet.
+    pending_start: HashSet<String>,
+    connected: bool,
+    /// Connected hosts, when several hosts are watched.
+    connected_hosts: HashSet<String>,
+}
+
+impl LifecycleTracker {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            states: HashMap::new(),
+            runs: HashMap::new(),
+            stats: HashMap::new(),
+            n50: HashMap::new(),
+            pending_start: HashSet::new(),
+            connected: false,
+            connected_hosts: HashSet::new(),
+        }
+    }
+
+    /// Feeds one event, returning the notifications it triggers.
+    pub fn handle(&mut self, event: &BusEvent) -> Vec<Notification> {
+        let mut out = Vec::new();
+        match event {
+            BusEvent::Connected => self.connected = true,
+            BusEvent::Disconnected { reason, .. } if self.connected => {
+                self.connected = false;
+                out.push(Notification {
+                    message: Some(reason.clone()),
+                    ..Notification::new(NotificationEvent::Disconnected, &self.host)
+                });
+            }
+            BusEvent::HostConnected { host } => {
+                self.connected_hosts.insert(host.clone());
+            }
+            BusEvent::HostDisconnected { host, reason } if self.connected_hosts.remove(host) => {
+                out.push(Notification {
+                    message: Some(reason.clone()),
+                    ..Notification::new(NotificationEvent::Disconnected, host)
+                });
+            }
+            BusEvent::Stats { position, stats } => {
+                self.stats.insert(position.clone(), stats.clone());
+            }
+            BusEvent::Histogram {
+                position,
+                histogram,
+            } if histogram.n50 > 0.0 => {
+                self.n50.insert(position.clone(), histogram.n50 as u64);
+            }
+            BusEvent::RunInfo { position, info } => {
+                self.runs.insert(position.clone(), info.clone());
+                if self.pending_start.remove(position) {
+                    out.push(self.run_notification(NotificationEvent::RunStarted, position));
+                }
+            }
+            BusEvent::RunStateChanged { position, state } => {
+                // Run info normally follows the state within the same poll;
+                // if it never came, announce the start without it.
+                if self.pending_start.remove(position) {
+                    out.push(self.run_notification(NotificationEvent::RunStarted, position));
+                }
+
+                let Some(prev) = self.states.insert(position.clone(), state.clone()) else {
+                    return out;
+                };
+                if !prev.is_active() && state.is_active() {
+                    self.forget_run(position);
+                    self.pending_start.insert(position.clone());
+                }
+                match state {
+                    RunState::Idle if prev.is_active() => {
+                        out.push(self.run_notification(NotificationEvent::RunCompleted, position));
+                        self.forget_run(position);
+                    }
+                    RunState::Paused if prev != RunState::Paused => {
+                        out.push(self.run_notification(NotificationEvent::RunPaused, position));
+                    }
+                    RunState::Error(message) if !matches!(prev, RunState::Error(_)) => {
+                        out.push(Notification {
+                            message: Some(message.clone()),
+                            ..self.run_notification(NotificationEvent::RunError, position)
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+
+    fn run_notification(&self, event: NotificationEvent, position: &str) -> Notification {
+        // Positions of one of several hosts are named `<host>/<position>`.
+        let host = position
+            .split_once('/')
+            .map_or(self.host.as_str(), |(host, _)| host);
+        let stats = self.stats.get(position);
+        Notification {
+            bases: stats.map(|s| s.bases_called),
+            pass_rate: stats
+                .filter(|s| s.reads_passed + s.reads_failed > 0)
+                .map(StatsSnapshot::pass_rate),
+            n50: self.n50.get(position).copied(),
+            ..Notification::for_run(event, host, position, self.runs.get(position))
+        }
+    }
+
+    fn forget_run(&mut self, position: &str) {
+        self.runs.remove(position);
+        self.stats.remove(position);
+        self.n50.remove(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(position: &str, state: RunState) -> BusEvent {
+        BusEvent::RunStateChanged {
+            position: position.to_string(),
+            state,
+        }
+    }
+
+    fn info(position: &str, run_id: &str) -> BusEvent {
+        BusEvent::RunInfo {
+            position: position.to_string(),
+            info: RunInfo {
+                run_id: Some(run_id.to_string()),
+                sample_id: Some("sample".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn events(tracker: &mut LifecycleTracker, events: &[BusEvent]) -> Vec<Notification> {
+        events.iter().flat_map(|e| tracker.handle(e)).collect()
+    }
+
+    #[test]
+    fn test_run_lifecycle_transitions() {
+        let mut tracker = LifecycleTracker::new("localhost");
+        let sent = events(
+            &mut tracker,
+            &[
+                state("X1", RunState::Idle),
+                state("X1", RunState::Running),
+                info("X1", "run-1"),
+                state("X1", RunState::Running),
+                info("X1", "run-1"),
+                state("X1", RunState::Paused),
+                state("X1", RunState::Paused),
+                state("X1", RunState::Running),
+                state("X1", RunState::Idle),
+            ],
+        );
+        let kinds: Vec<_> = sent.iter().map(|n| n.event).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NotificationEvent::RunStarted,
+                NotificationEvent::RunPaused,
+                NotificationEvent::RunCompleted,
+            ]
+        );
+        assert!(sent
+            .iter()
+            .all(|n| n.run_id.as_deref() == Some("run-1") && n.position.as_deref() == Some("X1")));
+        assert_eq!(sent[0].sample_id.as_deref(), Some("sample"));
+    }
+
+    #[test]
+    fn test_run_notifications_carry_bases_so_far() {
+        let mut tracker = LifecycleTracker::new("localhost");
+        let stats = |bases_called| BusEvent::Stats {
+            position: "X1".to_string(),
+            stats: StatsSnapshot {
+                bases_called,
+                reads_passed: 9,
+                reads_failed: 1,
+                ..Default::default()
+            },
+        };
+        let sent = events(
+            &mut tracker,
+            &[
+                state("X1", RunState::Running),
+                stats(5_000),
+                state("X1", RunState::Idle),
+                state("X1", RunState::Running),
+                state("X1", RunState::Paused),
+            ],
+        );
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[0].bases, Some(5_000));
+        assert_eq!(sent[0].pass_rate, Some(90.0));
+        assert_eq!(sent[0].title(), "Run completed on X1");
+        assert_eq!(sent[2].bases, None);
+    }
+
+    #[test]
+    fn test_first_state_is_baseline() {
+        let mut tracker = LifecycleTracker::new("localhost");
+        let sent = events(
+            &mut tracker,
+            &[state("X1", RunState::Running), info("X1", "run-1")],
+        );
+        assert!(sent.is_empty());
+    }
+
+    #[test]
+    fn test_start_without_run_info() {
+        let mut tracker = LifecycleTracker::new("localhost");
+        let sent = events(
+            &mut tracker,
+            &[
+                state("X1", RunState::Idle),
+                state("X1", RunState::Starting),
+                state("X1", RunState::Running),
+            ],
+        );
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].event, NotificationEvent::RunStarted);
+        assert_eq!(sent[0].run_id, None);
+    }
+
+    #[test]
+    fn test_error_and_disconnect_reported_once() {
+        let mut tracker = LifecycleTracker::new("localhost");
+        let disconnected = BusEvent::Disconnected {
+            reason: "connection refused".to_string(),
+            class: None,
+        };
+        let sent = events(
+            &mut tracker,
+            &[
+                state("X1", RunState::Running),
+                state("X1", RunState::Error("pore fault".into())),
+                state("X1", RunState::Error("pore fault".into())),
+                state("X1", RunState::Idle),
+                disconnected.clone(),
+                BusEvent::Connected,
+                disconnected.clone(),
+                disconnected,
+            ],
+        );
+        let kinds: Vec<_> = sent.iter().map(|n| n.event).collect();
+        assert_eq!(
+            kinds,
+            vec![NotificationEvent::RunError, NotificationEvent::Disconnected]
+        );
+        assert_eq!(sent[0].message.as_deref(), Some("pore fault"));
+        assert_eq!(sent[1].message.as_deref(), Some("connection refused"));
+        assert_eq!(sent[1].position, None);
+
+        let lost = BusEvent::HostDisconnected {
+            host: "localhost:19501".into(),
+            reason: "connection refused".into(),
+        };
+        let sent = events(
+            &mut tracker,
+            &[
+                lost.clone(),
+                BusEvent::HostConnected {
+                    host: "localhost:19501".into(),
+                },
+                lost.clone(),
+                lost,
+            ],
+        );
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].host, "localhost:19501");
+    }
+}
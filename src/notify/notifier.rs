@@ -0,0 +1,276 @@
+//! Background task that delivers lifecycle notifications to the webhook and
+//! the Slack or Discord webhook.
+
+use super::chat;
+use super::webhook::WebhookClient;
+use super::{LifecycleTracker, Notification};
+use crate::bus::{EventBus, EventFilter, EventKind, Subscription};
+use crate::config::{ChatFormat, Config, NotificationEvent};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Delay before the first retry of a failed delivery; doubled per attempt.
+const RETRY_INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between retries.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Spawns the notifier if a webhook or a Slack or Discord webhook is
+/// configured.
+///
+/// It runs until the bus is dropped, then finishes delivering whatever is
+/// still queued. Each webhook gets its deliveries in order on its own task,
+/// so a slow webhook never holds up the bus or the other webhook.
+pub fn spawn(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    let notifications = &config.notifications;
+    let slack = &notifications.slack;
+    let targets = [
+        (notifications.webhook_url.as_deref(), None),
+        (slack.webhook_url.as_deref(), Some(slack.effective_format())),
+    ];
+    let deliveries: Vec<_> = targets
+        .into_iter()
+        .filter_map(
+            |(url, format)| match WebhookClient::new(url?, notifications.timeout) {
+                Ok(client) => Some(Delivery {
+                    client,
+                    format,
+                    events: notifications
+                        .events
+                        .iter()
+                        .copied()
+                        .filter(|e| format.is_none() || e.severity() >= slack.min_severity)
+                        .collect(),
+                    max_attempts: notifications.max_attempts,
+                    initial_delay: RETRY_INITIAL_DELAY,
+                }),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Webhook notifications disabled");
+                    None
+                }
+            },
+        )
+        .collect();
+    if deliveries.is_empty() {
+        return None;
+    }
+
+    // Statistics give run notifications their yield so far.
+    let subscription = bus.subscribe(EventFilter::all().kinds(&[
+        EventKind::Connection,
+        EventKind::State,
+        EventKind::Data,
+    ]));
+    let tracker = LifecycleTracker::new(&config.connection.host);
+    Some(start(subscription, tracker, deliveries))
+}
+
+fn start(
+    mut subscription: Subscription,
+    mut tracker: LifecycleTracker,
+    deliveries: Vec<Delivery>,
+) -> JoinHandle<()> {
+    let (queues, tasks): (Vec<_>, Vec<_>) = deliveries
+        .into_iter()
+        .map(|delivery| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let events = delivery.events.clone();
+            ((events, tx), tokio::spawn(delivery.run(rx)))
+        })
+        .unzip();
+    tokio::spawn(async move {
+        while let Some(event) = subscription.recv().await {
+            for notification in tracker.handle(&event) {
+                for (events, tx) in &queues {
+                    if events.contains(&notification.event) {
+                        let _ = tx.send(notification.clone());
+                    }
+                }
+            }
+        }
+    });
+    tokio::spawn(async move {
+        for task in tasks {
+            let _ = task.await;
+        }
+    })
+}
+
+struct Delivery {
+    client: WebhookClient,
+    /// Chat layout of the body; `None` posts the [`Notification`] itself.
+    format: Option<ChatFormat>,
+    /// Events delivered to this webhook.
+    events: Vec<NotificationEvent>,
+    max_attempts: u32,
+    initial_delay: Duration,
+}
+
+impl Delivery {
+    async fn run(self, mut rx: mpsc::UnboundedReceiver<Notification>) {
+        while let Some(notification) = rx.recv().await {
+            self.deliver(&notification).await;
+        }
+    }
+
+    async fn deliver(&self, notification: &Notification) {
+        let body = match self.format {
+            Some(format) => serde_json::to_vec(&chat::message(format, notification)),
+            None => serde_json::to_vec(notification),
+        };
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to serialize notification");
+                return;
+            }
+        };
+
+        let mut delay = self.initial_delay;
+        for attempt in 1..=self.max_attempts {
+            match self.client.post(body.clone()).await {
+                Ok(()) => {
+                    tracing::info!(event = notification.event.as_str(), "Sent notification");
+                    return;
+                }
+                Err(e) if attempt < self.max_attempts => {
+                    tracing::debug!(attempt, error = %e, "Notification failed; retrying");
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(RETRY_MAX_DELAY);
+                }
+                Err(e) => tracing::warn!(
+                    event = notification.event.as_str(),
+                    attempts = attempt,
+                    error = %e,
+                    "Dropping notification"
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::BusEvent;
+    use crate::client::RunState;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Accepts connections, answering with `statuses` in turn and sending
+    /// each request body back over the channel.
+    async fn webhook_server(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            break body.to_string();
+                        }
+                    }
+                };
+                let response = format!("HTTP/1.1 {} X\r\ncontent-length: 0\r\n\r\n", status);
+                socket.write_all(response.as_bytes()).await.unwrap();
+                let _ = tx.send(body);
+            }
+        });
+        (url, rx)
+    }
+
+    #[tokio::test]
+    async fn test_notification_retried_until_delivered() {
+        let (url, mut received) = webhook_server(vec![500, 200]).await;
+        let bus = EventBus::default();
+        let subscription = bus.subscribe(EventFilter::all());
+        let delivery = Delivery {
+            client: WebhookClient::new(&url, Duration::from_secs(5)).unwrap(),
+            format: None,
+            events: vec![NotificationEvent::RunCompleted],
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(10),
+        };
+        let handle = start(
+            subscription,
+            LifecycleTracker::new("localhost"),
+            vec![delivery],
+        );
+
+        for state in [RunState::Idle, RunState::Running, RunState::Idle] {
+            bus.publish(BusEvent::RunStateChanged {
+                position: "X1".to_string(),
+                state,
+            });
+        }
+        drop(bus);
+        handle.await.unwrap();
+
+        // Only run_completed is enabled; it failed once and then succeeded.
+        for _ in 0..2 {
+            let body: serde_json::Value =
+                serde_json::from_str(&received.recv().await.unwrap()).unwrap();
+            assert_eq!(body["event"], "run_completed");
+            assert_eq!(body["position"], "X1");
+            assert_eq!(body["host"], "localhost");
+        }
+        assert!(received.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chat_webhook_gets_formatted_events_above_min_severity() {
+        let (url, mut received) = webhook_server(vec![200]).await;
+        let mut config = Config::default();
+        config.notifications.slack.webhook_url = Some(url);
+        config.notifications.slack.format = Some(ChatFormat::Discord);
+        config.notifications.slack.min_severity = crate::config::Severity::Warning;
+        let bus = EventBus::default();
+        let handle = spawn(&bus, &config).unwrap();
+
+        for state in [
+            RunState::Idle,
+            RunState::Running,
+            RunState::Paused,
+            RunState::Idle,
+        ] {
+            bus.publish(BusEvent::RunStateChanged {
+                position: "X1".to_string(),
+                state,
+            });
+        }
+        drop(bus);
+        handle.await.unwrap();
+
+        // Starts and completions are below the minimum severity.
+        let body: serde_json::Value =
+            serde_json::from_str(&received.recv().await.unwrap()).unwrap();
+        assert_eq!(body["embeds"][0]["title"], "Run paused on X1");
+        assert!(received.recv().await.is_none());
+    }
+
+    #[test]
+    fn test_invalid_webhook_url_disables_notifier() {
+        let mut config = Config::default();
+        config.notifications.webhook_url = Some("ftp://example.com".into());
+        let bus = EventBus::default();
+        assert!(spawn(&bus, &config).is_none());
+        config.notifications.webhook_url = None;
+        assert!(spawn(&bus, &config).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+//! Minimal HTTP(S) client for posting webhook payloads and metrics.
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, HOST, USER_AGENT};
+use hyper::{Request, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Invalid webhook URL '{0}'")]
+    InvalidUrl(String),
+
+    #[error("Failed to connect to webhook: {0}")]
+    Connect(#[source] std::io::Error),
+
+    #[error("TLS error: {0}")]
+    Tls(String),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] hyper::Error),
+
+    #[error("Webhook responded with {0}")]
+    Status(StatusCode),
+
+    #[error("Webhook timed out after {0:?}")]
+    Timeout(Duration),
+}
+
+/// Posts bodies, JSON unless set otherwise, to one webhook URL.
+pub(crate) struct WebhookClient {
+    uri: Uri,
+    host: String,
+    port: u16,
+    tls: Option<TlsConnector>,
+    timeout: Duration,
+    content_type: &'static str,
+    authorization: Option<String>,
+}
+
+impl WebhookClient {
+    pub fn new(url: &str, timeout: Duration) -> Result<Self, WebhookError> {
+        let invalid = || WebhookError::InvalidUrl(url.to_string());
+        let uri: Uri = url.parse().map_err(|_| invalid())?;
+        let https = match uri.scheme_str() {
+            Some("https") => true,
+            Some("http") => false,
+            _ => return Err(invalid()),
+        };
+        let host = uri
+            .host()
+            .ok_or_else(invalid)?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+        let tls = https.then(tls_connector).transpose()?;
+
+        Ok(Self {
+            uri,
+            host,
+            port,
+            tls,
+            timeout,
+            content_type: "application/json",
+            authorization: None,
+        })
+    }
+
+    /// Sets the `Content-Type` of posted bodies.
+    pub fn with_content_type(mut self, content_type: &'static str) -> Self {
+        self.content_type = content_type;
+        self
+    }
+
+    /// Sends `authorization` as the `Authorization` header of every post.
+    pub fn with_authorization(mut self, authorization: Option<String>) -> Self {
+        self.authorization = authorization;
+        self
+    }
+
+    /// Posts `body`. Any non-2xx response is an error.
+    pub async fn post(&self, body: Vec<u8>) -> Result<(), WebhookError> {
+        let status = tokio::time::timeout(self.timeout, self.send(body))
+            .await
+            .map_err(|_| WebhookError::Timeout(self.timeout))??;
+        if !status.is_success() {
+            return Err(WebhookError::Status(status));
+        }
+        Ok(())
+    }
+
+    async fn send(&self, body: Vec<u8>) -> Result<StatusCode, WebhookError> {
+        let stream = TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .map_err(WebhookError::Connect)?;
+        match &self.tls {
+            Some(connector) => {
+                let name = ServerName::try_from(self.host.clone())
+                    .map_err(|e| WebhookError::Tls(e.to_string()))?;
+                let stream = connector
+                    .connect(name, stream)
+                    .await
+                    .map_err(|e| WebhookError::Tls(e.to_string()))?;
+                self.request(stream, body).await
+            }
+            None => self.request(stream, body).await,
+        }
+    }
+
+    async fn request<S>(&self, stream: S, body: Vec<u8>) -> Result<StatusCode, WebhookError>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let (mut sender, conn) =
+            hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+        tokio::spawn(async move {
+            if let Err(e) = conn.await {
+                tracing::debug!(error = %e, "Webhook connection closed with error");
+            }
+        });
+
+        let path = self.uri.path_and_query().map_or("/", |p| p.as_str());
+        let authority = self
+            .uri
+            .authority()
+            .map_or(self.host.as_str(), |a| a.as_str());
+        let mut request = Request::post(path)
+            .header(HOST, authority)
+            .header(CONTENT_TYPE, self.content_type)
+            .header(USER_AGENT, concat!("termion/", env!("CARGO_PKG_VERSION")));
+        if let Some(authorization) = &self.authorization {
+            request = request.header(AUTHORIZATION, authorization);
+        }
+        let request = request
+            .body(Full::new(Bytes::from(body)))
+            .map_err(|_| WebhookError::InvalidUrl(self.uri.to_string()))?;
+        let response = sender.send_request(request).await?;
+        Ok(response.status())
+    }
+}
+
+fn tls_connector() -> Result<TlsConnector, WebhookError> {
+    let mut roots = RootCertStore::empty();
+    let native = rustls_native_certs::load_native_certs();
+    for error in &native.errors {
+        tracing::warn!(error = %error, "Failed to load a native root certificate");
+    }
+    let (added, _) = roots.add_parsable_certificates(native.certs);
+    if added == 0 {
+        return Err(WebhookError::Tls("no root certificates found".into()));
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| WebhookError::Tls(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(TlsConnector::from(Arc::new(config)))
+}
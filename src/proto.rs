@@ -60,4 +60,8 @@ pub mod minknow_api {
     pub mod data {
         tonic::include_proto!("minknow_api.data");
     }
+
+    pub mod log {
+        tonic::include_proto!("minknow_api.log");
+    }
 }
@@ -0,0 +1,244 @@
+//! Recorded TUI sessions for `--record` and `--replay`.
+//!
+//! A session file captures everything the TUI receives from MinKNOW, as the
+//! bus events the poller derives from each gRPC response, so a capture
+//! attached to a bug report replays through the app exactly as it was seen.
+//!
+//! The file is newline-delimited JSON: a [`SessionHeader`] line followed by
+//! one [`SessionEntry`] per event, each stamped with the milliseconds since
+//! recording started.
+
+pub mod recorder;
+
+use crate::bus::BusEvent;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use thiserror::Error;
+
+/// Version written to new session files.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// First line of a session file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionHeader {
+    pub version: u32,
+    /// MinKNOW manager the session was recorded from.
+    pub host: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// One recorded event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEntry {
+    /// Milliseconds since recording started.
+    pub at_ms: u64,
+    pub event: BusEvent,
+}
+
+/// A session file read back into memory.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub header: SessionHeader,
+    /// Entries in recording order.
+    pub entries: Vec<SessionEntry>,
+}
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("Failed to access session file {}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid session file {}, line {}: {}", path.display(), line, source)]
+    Parse {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("Session file {} is empty", path.display())]
+    Empty { path: PathBuf },
+
+    #[error("Unsupported session file version {} in {}", version, path.display())]
+    UnsupportedVersion { path: PathBuf, version: u32 },
+}
+
+/// Reads a whole session file.
+pub fn read(path: &Path) -> Result<Session, SessionError> {
+    let io_error = |source| SessionError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let parse_error = |line, source| SessionError::Parse {
+        path: path.to_path_buf(),
+        line,
+        source,
+    };
+
+    let file = File::open(path).map_err(io_error)?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header: SessionHeader = match lines.next() {
+        Some(line) => {
+            serde_json::from_str(&line.map_err(io_error)?).map_err(|e| parse_error(1, e))?
+        }
+        None => {
+            return Err(SessionError::Empty {
+                path: path.to_path_buf(),
+            })
+        }
+    };
+    if header.version != FORMAT_VERSION {
+        return Err(SessionError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version: header.version,
+        });
+    }
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.map_err(io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // A recording cut short by a crash can end in a partial line; keep
+        // everything before it.
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) if e.is_eof() => break,
+            Err(e) => return Err(parse_error(index + 2, e)),
+        }
+    }
+
+    Ok(Session { header, entries })
+}
+
+/// Appends events to a new session file.
+pub struct SessionWriter {
+    path: PathBuf,
+    file: BufWriter<File>,
+    started: Instant,
+}
+
+impl SessionWriter {
+    /// Creates `path`, replacing any existing file, and writes the header.
+    pub fn create(path: &Path, host: &str) -> Result<Self, SessionError> {
+        let file = File::create(path).map_err(|source| SessionError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let mut writer = Self {
+            path: path.to_path_buf(),
+            file: BufWriter::new(file),
+            started: Instant::now(),
+        };
+        let header = SessionHeader {
+            version: FORMAT_VERSION,
+            host: host.to_string(),
+            started_at: Utc::now(),
+        };
+        writer.write_line(&header)?;
+        Ok(writer)
+    }
+
+    /// Writes `event`, stamped with the time since the file was created.
+    pub fn write(&mut self, event: &BusEvent) -> Result<(), SessionError> {
+        let entry = SessionEntry {
+            at_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        self.write_line(&entry)
+    }
+
+    /// Each line is flushed so a session cut short still replays up to the
+    /// last event.
+    fn write_line(&mut self, value: &impl Serialize) -> Result<(), SessionError> {
+        let io_error = |source| SessionError::Io {
+            path: self.path.clone(),
+            source,
+        };
+        serde_json::to_writer(&mut self.file, value).map_err(|e| io_error(e.into()))?;
+        self.file.write_all(b"\n").map_err(io_error)?;
+        self.file.flush().map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::RunState;
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.ndjson");
+
+        let mut writer = SessionWriter::create(&path, "localhost:9501").unwrap();
+        writer.write(&BusEvent::Connected).unwrap();
+        writer
+            .write(&BusEvent::RunStateChanged {
+                position: "X1".to_string(),
+                state: RunState::Running,
+            })
+            .unwrap();
+        drop(writer);
+
+        let session = read(&path).unwrap();
+        assert_eq!(session.header.version, FORMAT_VERSION);
+        assert_eq!(session.header.host, "localhost:9501");
+        assert_eq!(session.entries.len(), 2);
+        assert!(matches!(session.entries[0].event, BusEvent::Connected));
+        assert!(matches!(
+            &session.entries[1].event,
+            BusEvent::RunStateChanged { position, state: RunState::Running } if position == "X1"
+        ));
+        assert!(session.entries[0].at_ms <= session.entries[1].at_ms);
+    }
+
+    #[test]
+    fn test_read_rejects_bad_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.ndjson");
+
+        std::fs::write(&path, "").unwrap();
+        assert!(matches!(read(&path), Err(SessionError::Empty { .. })));
+
+        std::fs::write(
+            &path,
+            "{\"version\":99,\"host\":\"h\",\"started_at\":\"2024-01-01T00:00:00Z\"}\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            read(&path),
+            Err(SessionError::UnsupportedVersion { version: 99, .. })
+        ));
+
+        std::fs::write(
+            &path,
+            "{\"version\":1,\"host\":\"h\",\"started_at\":\"2024-01-01T00:00:00Z\"}\n\
+             {\"at_ms\":0,\"event\":\"Connected\"}\n\
+             {\"at_ms\":5,\"event\":\"Bogus\"}\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            read(&path),
+            Err(SessionError::Parse { line: 3, .. })
+        ));
+
+        // A truncated last line is dropped rather than failing the replay.
+        std::fs::write(
+            &path,
+            "{\"version\":1,\"host\":\"h\",\"started_at\":\"2024-01-01T00:00:00Z\"}\n\
+             {\"at_ms\":0,\"event\":\"Connected\"}\n\
+             {\"at_ms\":5,\"event\":{\"Reconn",
+        )
+        .unwrap();
+        assert_eq!(read(&path).unwrap().entries.len(), 1);
+    }
+}
@@ -0,0 +1,23 @@
+//! Background task that writes every bus event to a session file.
+
+use super::{SessionError, SessionWriter};
+use crate::bus::{EventBus, EventFilter, Subscription};
+use std::path::Path;
+use tokio::task::JoinHandle;
+
+/// Creates the session file and spawns the recorder. It runs until the bus
+/// is dropped.
+pub fn spawn(bus: &EventBus, path: &Path, host: &str) -> Result<JoinHandle<()>, SessionError> {
+    let writer = SessionWriter::create(path, host)?;
+    let subscription = bus.subscribe(EventFilter::all());
+    Ok(tokio::spawn(record(writer, subscription)))
+}
+
+async fn record(mut writer: SessionWriter, mut subscription: Subscription) {
+    while let Some(event) = subscription.recv().await {
+        if let Err(e) = writer.write(&event) {
+            tracing::warn!(error = %e, "Failed to record session; recording stopped");
+            return;
+        }
+    }
+}
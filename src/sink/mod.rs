@@ -0,0 +1,200 @@
+//! Metrics sink for time-series databases.
+//!
+//! The [`pusher`] periodically sends each position's latest statistics to a
+//! write endpoint, so long-term dashboards (Grafana and the like) can chart
+//! runs next to the rest of the lab's infrastructure. Two wire formats are
+//! supported:
+//!
+//! - Influx line protocol, one line per position:
+//!   `termion,host=gridion,position=X1,run_id=abc reads_processed=1234i,... 1700000000000000000`
+//! - VictoriaMetrics' JSON line import format, one line per position and
+//!   field, named `<measurement>_<field>`.
+
+pub mod pusher;
+
+use crate::client::StatsSnapshot;
+use crate::config::MetricsFormat;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+
+/// The latest statistics of one position, ready to encode.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub host: String,
+    pub position: String,
+    pub run_id: Option<String>,
+    pub stats: StatsSnapshot,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// A field value: Influx distinguishes integers from floats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FieldValue {
+    Int(u64),
+    Float(f64),
+}
+
+impl Sample {
+    fn tags(&self) -> Vec<(&'static str, &str)> {
+        let mut tags = vec![("host", self.host.as_str()), ("position", &self.position)];
+        if let Some(run_id) = &self.run_id {
+            tags.push(("run_id", run_id));
+        }
+        tags
+    }
+
+    fn fields(&self) -> [(&'static str, FieldValue); 11] {
+        let s = &self.stats;
+        [
+            ("reads_processed", FieldValue::Int(s.reads_processed)),
+            ("reads_passed", FieldValue::Int(s.reads_passed)),
+            ("reads_failed", FieldValue::Int(s.reads_failed)),
+            ("bases_called", FieldValue::Int(s.bases_called)),
+            ("bases_passed", FieldValue::Int(s.bases_passed)),
+            ("bases_failed", FieldValue::Int(s.bases_failed)),
+            ("throughput_bps", FieldValue::Float(s.throughput_bps)),
+            ("mean_quality", FieldValue::Float(s.mean_quality)),
+            ("mean_read_length", FieldValue::Float(s.mean_read_length)),
+            ("active_pores", FieldValue::Int(s.active_pores.into())),
+            ("pass_rate", FieldValue::Float(s.pass_rate())),
+        ]
+    }
+}
+
+/// Encodes `samples` as one request body in `format`.
+pub fn encode(format: MetricsFormat, measurement: &str, samples: &[Sample]) -> String {
+    let lines: Vec<String> = match format {
+        MetricsFormat::Influx => samples
+            .iter()
+            .map(|sample| influx_line(measurement, sample))
+            .collect(),
+        MetricsFormat::VictoriaMetrics => samples
+            .iter()
+            .flat_map(|sample| victoria_lines(measurement, sample))
+            .collect(),
+    };
+    let mut body = lines.join("\n");
+    body.push('\n');
+    body
+}
+
+/// Content type of a body in `format`.
+pub fn content_type(format: MetricsFormat) -> &'static str {
+    match format {
+        MetricsFormat::Influx => "text/plain; charset=utf-8",
+        MetricsFormat::VictoriaMetrics => "application/json",
+    }
+}
+
+fn influx_line(measurement: &str, sample: &Sample) -> String {
+    let mut line = escape(measurement, &[',', ' ']);
+    for (key, value) in sample.tags() {
+        // Influx rejects empty tag values.
+        if !value.is_empty() {
+            line.push_str(&format!(",{}={}", key, escape(value, &[',', '=', ' '])));
+        }
+    }
+    let fields: Vec<String> = sample
+        .fields()
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            FieldValue::Int(n) => Some(format!("{}={}i", key, n)),
+            FieldValue::Float(x) if x.is_finite() => Some(format!("{}={}", key, x)),
+            FieldValue::Float(_) => None,
+        })
+        .collect();
+    let nanos = sample.timestamp.timestamp_nanos_opt().unwrap_or_default();
+    format!("{} {} {}", line, fields.join(","), nanos)
+}
+
+fn victoria_lines(measurement: &str, sample: &Sample) -> Vec<String> {
+    let timestamp = sample.timestamp.timestamp_millis();
+    sample
+        .fields()
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                FieldValue::Int(n) => n as f64,
+                FieldValue::Float(x) if x.is_finite() => x,
+                FieldValue::Float(_) => return None,
+            };
+            let mut metric = Map::new();
+            metric.insert("__name__".into(), json!(format!("{}_{}", measurement, key)));
+            for (tag, tag_value) in sample.tags() {
+                metric.insert(tag.into(), json!(tag_value));
+            }
+            let line = json!({
+                "metric": Value::Object(metric),
+                "values": [value],
+                "timestamps": [timestamp],
+            });
+            Some(line.to_string())
+        })
+        .collect()
+}
+
+/// Backslash-escapes `special` characters, as line protocol requires.
+fn escape(value: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample() -> Sample {
+        Sample {
+            host: "grid ion".into(),
+            position: "X1".into(),
+            run_id: Some("run,1".into()),
+            stats: StatsSnapshot {
+                reads_processed: 1000,
+                reads_passed: 900,
+                reads_failed: 100,
+                bases_called: 5_000_000,
+                throughput_bps: 1250.5,
+                active_pores: 412,
+                ..Default::default()
+            },
+            timestamp: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_influx_line_protocol() {
+        let body = encode(MetricsFormat::Influx, "termion", &[sample()]);
+
+        assert_eq!(
+            body,
+            "termion,host=grid\\ ion,position=X1,run_id=run\\,1 \
+             reads_processed=1000i,reads_passed=900i,reads_failed=100i,\
+             bases_called=5000000i,bases_passed=0i,bases_failed=0i,\
+             throughput_bps=1250.5,mean_quality=0,mean_read_length=0,\
+             active_pores=412i,pass_rate=90 1700000000000000000\n"
+        );
+    }
+
+    #[test]
+    fn test_victoria_metrics_import_lines() {
+        let body = encode(MetricsFormat::VictoriaMetrics, "termion", &[sample()]);
+
+        let lines: Vec<Value> = body
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0]["metric"]["__name__"], "termion_reads_processed");
+        assert_eq!(lines[0]["metric"]["host"], "grid ion");
+        assert_eq!(lines[0]["metric"]["run_id"], "run,1");
+        assert_eq!(lines[0]["values"][0], 1000.0);
+        assert_eq!(lines[0]["timestamps"][0], 1_700_000_000_000i64);
+    }
+}
@@ -0,0 +1,174 @@
+//! Background task that pushes statistics to the metrics endpoint.
+
+use super::{content_type, encode, Sample};
+use crate::bus::{BusEvent, EventBus, EventFilter, EventKind};
+use crate::config::{Config, MetricsFormat};
+use crate::notify::webhook::WebhookClient;
+use chrono::Utc;
+use std::collections::{BTreeMap, HashMap};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+
+/// Spawns the pusher if a metrics endpoint is configured.
+///
+/// Every `metrics.interval` it pushes the latest statistics of each position
+/// that has new ones. It runs until the bus is dropped, then pushes whatever
+/// arrived since the last push.
+pub fn spawn(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    let metrics = &config.metrics;
+    let client = match WebhookClient::new(metrics.url.as_deref()?, config.notifications.timeout) {
+        Ok(client) => client
+            .with_content_type(content_type(metrics.format))
+            .with_authorization(metrics.token.as_ref().map(|t| format!("Token {}", t))),
+        Err(e) => {
+            tracing::warn!(error = %e, "Metrics sink disabled");
+            return None;
+        }
+    };
+    let mut subscription =
+        bus.subscribe(EventFilter::all().kinds(&[EventKind::State, EventKind::Data]));
+    let mut tracker = Tracker::new(&config.connection.host);
+    let format = metrics.format;
+    let measurement = metrics.measurement.clone();
+    let mut ticks = tokio::time::interval(metrics.interval);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    Some(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                event = subscription.recv() => match event {
+                    Some(event) => tracker.handle(&event),
+                    None => break,
+                },
+                _ = ticks.tick() => push(&client, &measurement, format, tracker.take()).await,
+            }
+        }
+        push(&client, &measurement, format, tracker.take()).await;
+    }))
+}
+
+async fn push(
+    client: &WebhookClient,
+    measurement: &str,
+    format: MetricsFormat,
+    samples: Vec<Sample>,
+) {
+    if samples.is_empty() {
+        return;
+    }
+    let body = encode(format, measurement, &samples);
+    match client.post(body.into_bytes()).await {
+        Ok(()) => tracing::debug!(positions = samples.len(), "Pushed metrics"),
+        Err(e) => tracing::warn!(error = %e, "Failed to push metrics"),
+    }
+}
+
+/// Latest statistics and run of each position, and which changed since
+/// the last push.
+struct Tracker {
+    host: String,
+    runs: HashMap<String, String>,
+    pending: BTreeMap<String, Sample>,
+}
+
+impl Tracker {
+    fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            runs: HashMap::new(),
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn handle(&mut self, event: &BusEvent) {
+        match event {
+            BusEvent::RunInfo { position, info } => match &info.run_id {
+                Some(run_id) => {
+                    self.runs.insert(position.clone(), run_id.clone());
+                }
+                None => {
+                    self.runs.remove(position);
+                }
+            },
+            BusEvent::Stats { position, stats } => {
+                // Positions of one of several hosts are named
+                // `<host>/<position>`.
+                let (host, name) = position
+                    .split_once('/')
+                    .unwrap_or((self.host.as_str(), position.as_str()));
+                let sample = Sample {
+                    host: host.to_string(),
+                    position: name.to_string(),
+                    run_id: self.runs.get(position).cloned(),
+                    stats: stats.clone(),
+                    timestamp: stats.timestamp.unwrap_or_else(Utc::now),
+                };
+                self.pending.insert(position.clone(), sample);
+            }
+            _ => {}
+        }
+    }
+
+    /// Takes the samples that arrived since the last call.
+    fn take(&mut self) -> Vec<Sample> {
+        std::mem::take(&mut self.pending).into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{RunInfo, StatsSnapshot};
+
+    fn stats(position: &str, reads: u64) -> BusEvent {
+        BusEvent::Stats {
+            position: position.to_string(),
+            stats: StatsSnapshot {
+                reads_processed: reads,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_tracker_keeps_latest_stats_per_position() {
+        let mut tracker = Tracker::new("localhost");
+        tracker.handle(&BusEvent::RunInfo {
+            position: "X1".to_string(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                ..Default::default()
+            },
+        });
+        tracker.handle(&stats("X1", 10));
+        tracker.handle(&stats("X1", 20));
+        tracker.handle(&stats("gridion/X2", 5));
+
+        let samples = tracker.take();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(
+            (samples[0].host.as_str(), samples[0].position.as_str()),
+            ("localhost", "X1")
+        );
+        assert_eq!(samples[0].run_id.as_deref(), Some("run-1"));
+        assert_eq!(samples[0].stats.reads_processed, 20);
+        assert_eq!(
+            (samples[1].host.as_str(), samples[1].position.as_str()),
+            ("gridion", "X2")
+        );
+        assert_eq!(samples[1].run_id, None);
+
+        // Nothing new since the last push.
+        assert!(tracker.take().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_url_disables_pusher() {
+        let mut config = Config::default();
+        config.metrics.url = Some("udp://localhost:8089".into());
+        let bus = EventBus::default();
+        assert!(spawn(&bus, &config).is_none());
+        config.metrics.url = None;
+        assert!(spawn(&bus, &config).is_none());
+    }
+}
@@ -0,0 +1,498 @@
+//! SQLite run metrics database.
+//!
+//! An optional alternative to the plain-file [`history`](crate::history)
+//! store: the [`recorder`] writes stats snapshots, run metadata and run
+//! lifecycle events to one SQLite file. Finished runs in the database are
+//! merged into the run history screen, so runs MinKNOW no longer lists are
+//! still shown after Termion restarts, and `termion history export` reads
+//! its records from here when the database is enabled.
+//!
+//! Runs that ended longer ago than `storage.max_age_days` are removed, with
+//! their snapshots and events, by the recorder in the background.
+
+pub mod recorder;
+
+use crate::client::{RunInfo, RunSummary};
+use crate::history::HistoryRecord;
+use crate::notify::Notification;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::HashSet;
+use std::path::Path;
+use thiserror::Error;
+
+/// Schema version stored in `PRAGMA user_version`.
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    position TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    sample_id TEXT,
+    experiment_group TEXT,
+    protocol TEXT,
+    flow_cell_product_code TEXT,
+    start_time TEXT,
+    end_time TEXT,
+    end_reason TEXT,
+    reads INTEGER NOT NULL DEFAULT 0,
+    bases_passed INTEGER NOT NULL DEFAULT 0,
+    bases_failed INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (position, run_id)
+);
+CREATE TABLE IF NOT EXISTS snapshots (
+    position TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    reads_processed INTEGER NOT NULL,
+    reads_passed INTEGER NOT NULL,
+    reads_failed INTEGER NOT NULL,
+    bases_called INTEGER NOT NULL,
+    bases_passed INTEGER NOT NULL,
+    bases_failed INTEGER NOT NULL,
+    throughput_bps REAL NOT NULL,
+    mean_quality REAL NOT NULL,
+    active_pores INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS snapshots_by_run ON snapshots (position, run_id, timestamp);
+CREATE TABLE IF NOT EXISTS events (
+    timestamp TEXT NOT NULL,
+    event TEXT NOT NULL,
+    position TEXT,
+    run_id TEXT,
+    message TEXT
+);
+";
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Failed to create database directory: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Database schema version {0} is newer than this version of Termion supports")]
+    UnsupportedSchema(i32),
+}
+
+/// A run lifecycle event read back from the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Notification event name, e.g. `run_started`.
+    pub event: String,
+    pub position: Option<String>,
+    pub run_id: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Connection to the run metrics database.
+#[derive(Debug)]
+pub struct Storage {
+    conn: Connection,
+}
+
+impl Storage {
+    /// Opens the database at `path`, creating it and its directory if
+    /// needed.
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        // The recorder and the pollers may open the file at the same time.
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(StorageError::UnsupportedSchema(version));
+        }
+        conn.execute_batch(SCHEMA)?;
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        Ok(Self { conn })
+    }
+
+    /// Records a run's metadata, keeping its yield and end if already
+    /// known.
+    pub fn upsert_run(&self, position: &str, info: &RunInfo) -> Result<(), StorageError> {
+        let Some(run_id) = &info.run_id else {
+            return Ok(());
+        };
+        self.conn.execute(
+            "INSERT INTO runs (position, run_id, sample_id, experiment_group, protocol,
+                               flow_cell_product_code, start_time, end_time)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (position, run_id) DO UPDATE SET
+                 sample_id = excluded.sample_id,
+                 experiment_group = excluded.experiment_group,
+                 protocol = excluded.protocol,
+                 flow_cell_product_code = excluded.flow_cell_product_code,
+                 start_time = COALESCE(excluded.start_time, start_time),
+                 end_time = COALESCE(excluded.end_time, end_time)",
+            params![
+                position,
+                run_id,
+                info.sample_id,
+                info.experiment_group,
+                info.protocol_name,
+                info.flow_cell_product_code,
+                info.start_time,
+                info.end_time,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Appends a stats snapshot and updates its run's yield.
+    pub fn record_snapshot(&self, record: &HistoryRecord) -> Result<(), StorageError> {
+        self.conn.execute(
+            "INSERT INTO snapshots (position, run_id, timestamp, reads_processed, reads_passed,
+                                    reads_failed, bases_called, bases_passed, bases_failed,
+                                    throughput_bps, mean_quality, active_pores)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.position,
+                record.run_id,
+                record.timestamp,
+                record.reads_processed as i64,
+                record.reads_passed as i64,
+                record.reads_failed as i64,
+                record.bases_called as i64,
+                record.bases_passed as i64,
+                record.bases_failed as i64,
+                record.throughput_bps,
+                record.mean_quality,
+                record.active_pores,
+            ],
+        )?;
+        self.conn.execute(
+            "UPDATE runs SET reads = ?3, bases_passed = ?4, bases_failed = ?5
+             WHERE position = ?1 AND run_id = ?2",
+            params![
+                record.position,
+                record.run_id,
+                record.reads_processed as i64,
+                record.bases_passed as i64,
+                record.bases_failed as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Appends a run lifecycle event.
+    pub fn record_event(&self, notification: &Notification) -> Result<(), StorageError> {
+        self.conn.execute(
+            "INSERT INTO events (timestamp, event, position, run_id, message)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                notification.timestamp,
+                notification.event.as_str(),
+                notification.position,
+                notification.run_id,
+                notification.message,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Marks a run as ended. A run keeps the first end it is given.
+    pub fn finish_run(
+        &self,
+        position: &str,
+        run_id: &str,
+        end_time: DateTime<Utc>,
+        end_reason: &str,
+    ) -> Result<(), StorageError> {
+        self.conn.execute(
+            "UPDATE runs SET end_time = COALESCE(end_time, ?3),
+                             end_reason = COALESCE(end_reason, ?4)
+             WHERE position = ?1 AND run_id = ?2",
+            params![position, run_id, end_time, end_reason],
+        )?;
+        Ok(())
+    }
+
+    /// Finished runs on `position`, newest first.
+    pub fn finished_runs(&self, position: &str) -> Result<Vec<RunSummary>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT run_id, sample_id, experiment_group, protocol, flow_cell_product_code,
+                    start_time, end_time, end_reason, reads, bases_passed, bases_failed
+             FROM runs
+             WHERE position = ?1 AND end_time IS NOT NULL
+             ORDER BY start_time DESC",
+        )?;
+        let runs = stmt
+            .query_map([position], |row| {
+                let protocol: Option<String> = row.get(3)?;
+                Ok(RunSummary {
+                    info: RunInfo {
+                        run_id: Some(row.get(0)?),
+                        sample_id: row.get(1)?,
+                        experiment_group: row.get(2)?,
+                        protocol_name: protocol.clone(),
+                        flow_cell_product_code: row.get(4)?,
+                        start_time: row.get(5)?,
+                        end_time: row.get(6)?,
+                    },
+                    protocol_id: protocol.unwrap_or_default(),
+                    end_reason: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                    acquisition_run_id: None,
+                    reads: row.get::<_, i64>(8)? as u64,
+                    bases_passed: row.get::<_, i64>(9)? as u64,
+                    bases_failed: row.get::<_, i64>(10)? as u64,
+                    n50: None,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(runs)
+    }
+
+    /// Recorded snapshots, optionally limited to one position or run, in
+    /// time order.
+    pub fn records(
+        &self,
+        position: Option<&str>,
+        run_id: Option<&str>,
+    ) -> Result<Vec<HistoryRecord>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, position, run_id, reads_processed, reads_passed, reads_failed,
+                    bases_called, bases_passed, bases_failed, throughput_bps, mean_quality,
+                    active_pores
+             FROM snapshots
+             WHERE (?1 IS NULL OR position = ?1) AND (?2 IS NULL OR run_id = ?2)
+             ORDER BY position, run_id, timestamp",
+        )?;
+        let records = stmt
+            .query_map(params![position, run_id], |row| {
+                Ok(HistoryRecord {
+                    timestamp: row.get(0)?,
+                    position: row.get(1)?,
+                    run_id: row.get(2)?,
+                    reads_processed: row.get::<_, i64>(3)? as u64,
+                    reads_passed: row.get::<_, i64>(4)? as u64,
+                    reads_failed: row.get::<_, i64>(5)? as u64,
+                    bases_called: row.get::<_, i64>(6)? as u64,
+                    bases_passed: row.get::<_, i64>(7)? as u64,
+                    bases_failed: row.get::<_, i64>(8)? as u64,
+                    throughput_bps: row.get(9)?,
+                    mean_quality: row.get(10)?,
+                    active_pores: row.get(11)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(records)
+    }
+
+    /// Lifecycle events, optionally limited to one position, oldest first.
+    pub fn events(&self, position: Option<&str>) -> Result<Vec<StoredEvent>, StorageError> {
+        let mut stmt = self.conn.prepare(
+            "SELECT timestamp, event, position, run_id, message
+             FROM events
+             WHERE ?1 IS NULL OR position = ?1
+             ORDER BY timestamp",
+        )?;
+        let events = stmt
+            .query_map([position], |row| {
+                Ok(StoredEvent {
+                    timestamp: row.get(0)?,
+                    event: row.get(1)?,
+                    position: row.get(2)?,
+                    run_id: row.get(3)?,
+                    message: row.get(4)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(events)
+    }
+
+    /// Removes runs that ended before `cutoff` with their snapshots and
+    /// events, and any other events older than it. Returns the number of
+    /// runs removed.
+    pub fn prune(&self, cutoff: DateTime<Utc>) -> Result<usize, StorageError> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM snapshots WHERE (position, run_id) IN
+                 (SELECT position, run_id FROM runs WHERE end_time < ?1)",
+            [cutoff],
+        )?;
+        tx.execute(
+            "DELETE FROM events WHERE timestamp < ?1 OR (position, run_id) IN
+                 (SELECT position, run_id FROM runs WHERE end_time < ?1)",
+            [cutoff],
+        )?;
+        let removed = tx.execute("DELETE FROM runs WHERE end_time < ?1", [cutoff])?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Whether a run is recorded.
+    pub fn has_run(&self, position: &str, run_id: &str) -> Result<bool, StorageError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT 1 FROM runs WHERE position = ?1 AND run_id = ?2",
+                [position, run_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+}
+
+/// Adds stored runs that MinKNOW no longer lists to its run history,
+/// keeping the newest first.
+pub fn merge_runs(mut runs: Vec<RunSummary>, stored: Vec<RunSummary>) -> Vec<RunSummary> {
+    let known: HashSet<Option<String>> = runs.iter().map(|r| r.info.run_id.clone()).collect();
+    runs.extend(
+        stored
+            .into_iter()
+            .filter(|r| !known.contains(&r.info.run_id)),
+    );
+    runs.sort_by_key(|run| std::cmp::Reverse(run.info.start_time));
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotificationEvent;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 1, hour, 0, 0).unwrap()
+    }
+
+    fn info(run_id: &str, start: DateTime<Utc>) -> RunInfo {
+        RunInfo {
+            run_id: Some(run_id.into()),
+            sample_id: Some("S1".into()),
+            protocol_name: Some("Ligation Sequencing Kit V14".into()),
+            start_time: Some(start),
+            ..Default::default()
+        }
+    }
+
+    fn record(run_id: &str, timestamp: DateTime<Utc>, bases_passed: u64) -> HistoryRecord {
+        HistoryRecord {
+            timestamp,
+            position: "X1".into(),
+            run_id: run_id.into(),
+            reads_processed: bases_passed / 1000,
+            reads_passed: 0,
+            reads_failed: 0,
+            bases_called: bases_passed,
+            bases_passed,
+            bases_failed: 0,
+            throughput_bps: 1.5,
+            mean_quality: 12.0,
+            active_pores: 400,
+        }
+    }
+
+    #[test]
+    fn test_run_lifecycle_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/termion.db");
+        let storage = Storage::open(&path).unwrap();
+
+        storage.upsert_run("X1", &info("run-1", at(1))).unwrap();
+        storage
+            .record_snapshot(&record("run-1", at(2), 1_000_000))
+            .unwrap();
+        storage
+            .record_snapshot(&record("run-1", at(3), 2_000_000))
+            .unwrap();
+        assert!(storage.finished_runs("X1").unwrap().is_empty());
+
+        storage
+            .finish_run("X1", "run-1", at(4), "Completed")
+            .unwrap();
+        // Later run info without an end keeps the recorded one.
+        storage.upsert_run("X1", &info("run-1", at(1))).unwrap();
+        storage.finish_run("X1", "run-1", at(5), "Error").unwrap();
+
+        // Reopening reads back everything.
+        drop(storage);
+        let storage = Storage::open(&path).unwrap();
+        let runs = storage.finished_runs("X1").unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].info.end_time, Some(at(4)));
+        assert_eq!(runs[0].end_reason, "Completed");
+        assert_eq!(runs[0].bases_passed, 2_000_000);
+        assert_eq!(runs[0].reads, 2000);
+        assert_eq!(runs[0].protocol_id, "Ligation Sequencing Kit V14");
+
+        let records = storage.records(Some("X1"), Some("run-1")).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0], record("run-1", at(2), 1_000_000));
+        assert!(storage.records(Some("X2"), None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_events_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(&dir.path().join("termion.db")).unwrap();
+
+        for (run_id, start) in [("old", at(1)), ("new", at(10))] {
+            storage.upsert_run("X1", &info(run_id, start)).unwrap();
+            storage.record_snapshot(&record(run_id, start, 10)).unwrap();
+            storage
+                .record_event(&Notification {
+                    event: NotificationEvent::RunCompleted,
+                    timestamp: start + chrono::Duration::hours(1),
+                    host: "localhost".into(),
+                    position: Some("X1".into()),
+                    run_id: Some(run_id.into()),
+                    sample_id: None,
+                    experiment_group: None,
+                    bases: None,
+                    pass_rate: None,
+                    n50: None,
+                    message: None,
+                })
+                .unwrap();
+            storage
+                .finish_run(
+                    "X1",
+                    run_id,
+                    start + chrono::Duration::hours(1),
+                    "Completed",
+                )
+                .unwrap();
+        }
+        let events = storage.events(Some("X1")).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "run_completed");
+        assert_eq!(events[0].run_id.as_deref(), Some("old"));
+
+        assert_eq!(storage.prune(at(5)).unwrap(), 1);
+        assert!(!storage.has_run("X1", "old").unwrap());
+        assert!(storage.has_run("X1", "new").unwrap());
+        assert_eq!(storage.records(None, None).unwrap().len(), 1);
+        assert_eq!(storage.events(None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_merge_runs_prefers_minknow_and_sorts() {
+        let summary = |run_id: &str, hour: u32, reason: &str| RunSummary {
+            info: info(run_id, at(hour)),
+            end_reason: reason.into(),
+            ..Default::default()
+        };
+        let merged = merge_runs(
+            vec![summary("b", 5, "MinKNOW")],
+            vec![
+                summary("a", 1, "Stored"),
+                summary("b", 5, "Stored"),
+                summary("c", 9, "Stored"),
+            ],
+        );
+        let ids: Vec<_> = merged
+            .iter()
+            .map(|r| r.info.run_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["c", "b", "a"]);
+        assert_eq!(merged[1].end_reason, "MinKNOW");
+    }
+}
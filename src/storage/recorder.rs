@@ -0,0 +1,228 @@
+//! Background task that writes bus events to the run metrics database.
+
+use super::Storage;
+use crate::bus::{BusEvent, EventBus, EventFilter, EventKind, Subscription};
+use crate::config::{Config, NotificationEvent};
+use crate::history::HistoryRecord;
+use crate::notify::LifecycleTracker;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// How often old runs are removed while recording.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Spawns the recorder, or returns `None` if the database cannot be opened.
+/// It runs until the bus is dropped.
+pub fn spawn(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    let storage = match Storage::open(&config.storage.path) {
+        Ok(storage) => storage,
+        Err(e) => {
+            tracing::warn!(path = %config.storage.path.display(), error = %e, "Run metrics database disabled");
+            return None;
+        }
+    };
+    let subscription = bus.subscribe(EventFilter::all().kinds(&[
+        EventKind::Connection,
+        EventKind::State,
+        EventKind::Data,
+    ]));
+    let recorder = Recorder::new(storage, config);
+    Some(tokio::spawn(recorder.run(subscription)))
+}
+
+struct Recorder {
+    storage: Storage,
+    tracker: LifecycleTracker,
+    record_interval: Duration,
+    max_age: Option<Duration>,
+    /// Current run ID per position, learned from run info events.
+    run_ids: HashMap<String, String>,
+    last_recorded: HashMap<String, Instant>,
+}
+
+impl Recorder {
+    fn new(storage: Storage, config: &Config) -> Self {
+        Self {
+            storage,
+            tracker: LifecycleTracker::new(&config.connection.host),
+            record_interval: config.storage.record_interval,
+            max_age: config.storage.max_age,
+            run_ids: HashMap::new(),
+            last_recorded: HashMap::new(),
+        }
+    }
+
+    async fn run(mut self, mut subscription: Subscription) {
+        self.prune();
+        let mut last_prune = Instant::now();
+
+        while let Some(event) = subscription.recv().await {
+            self.handle(&event);
+
+            if last_prune.elapsed() >= PRUNE_INTERVAL {
+                self.prune();
+                last_prune = Instant::now();
+            }
+        }
+    }
+
+    fn handle(&mut self, event: &BusEvent) {
+        for notification in self.tracker.handle(event) {
+            if let Err(e) = self.storage.record_event(&notification) {
+                tracing::warn!(error = %e, "Failed to record run event");
+            }
+            let end_reason = match notification.event {
+                NotificationEvent::RunCompleted => "Completed".to_string(),
+                NotificationEvent::RunError => notification
+                    .message
+                    .as_ref()
+                    .map_or_else(|| "Error".to_string(), |m| format!("Error: {}", m)),
+                _ => continue,
+            };
+            if let (Some(position), Some(run_id)) = (&notification.position, &notification.run_id) {
+                if let Err(e) =
+                    self.storage
+                        .finish_run(position, run_id, notification.timestamp, &end_reason)
+                {
+                    tracing::warn!(position = %position, error = %e, "Failed to record run end");
+                }
+            }
+        }
+
+        match event {
+            BusEvent::RunInfo { position, info } => {
+                let Some(run_id) = &info.run_id else {
+                    return;
+                };
+                self.run_ids.insert(position.clone(), run_id.clone());
+                if let Err(e) = self.storage.upsert_run(position, info) {
+                    tracing::warn!(position = %position, error = %e, "Failed to record run");
+                }
+            }
+            BusEvent::RunStateChanged { position, state } if !state.is_active() => {
+                self.run_ids.remove(position);
+                self.last_recorded.remove(position);
+            }
+            BusEvent::Stats { position, stats } => {
+                let Some(run_id) = self.run_ids.get(position) else {
+                    return;
+                };
+                if self
+                    .last_recorded
+                    .get(position)
+                    .is_some_and(|t| t.elapsed() < self.record_interval)
+                {
+                    return;
+                }
+
+                let record = HistoryRecord::from_stats(position, run_id, stats);
+                match self.storage.record_snapshot(&record) {
+                    Ok(()) => {
+                        self.last_recorded.insert(position.clone(), Instant::now());
+                    }
+                    Err(e) => {
+                        tracing::warn!(position = %position, error = %e, "Failed to record snapshot")
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn prune(&self) {
+        let Some(max_age) = self
+            .max_age
+            .and_then(|age| chrono::Duration::from_std(age).ok())
+        else {
+            return;
+        };
+        match self.storage.prune(Utc::now() - max_age) {
+            Ok(0) => {}
+            Ok(runs) => tracing::info!(runs, "Pruned run metrics database"),
+            Err(e) => tracing::warn!(error = %e, "Failed to prune run metrics database"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{RunInfo, RunState, StatsSnapshot};
+
+    fn recorder(dir: &std::path::Path) -> Recorder {
+        let storage = Storage::open(&dir.join("termion.db")).unwrap();
+        Recorder::new(storage, &Config::default())
+    }
+
+    fn state(state: RunState) -> BusEvent {
+        BusEvent::RunStateChanged {
+            position: "X1".into(),
+            state,
+        }
+    }
+
+    fn stats() -> BusEvent {
+        BusEvent::Stats {
+            position: "X1".into(),
+            stats: StatsSnapshot {
+                bases_passed: 5_000,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_records_run_snapshots_and_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = recorder(dir.path());
+
+        recorder.handle(&state(RunState::Idle));
+        recorder.handle(&stats());
+        recorder.handle(&state(RunState::Running));
+        recorder.handle(&BusEvent::RunInfo {
+            position: "X1".into(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                ..Default::default()
+            },
+        });
+        recorder.handle(&stats());
+        recorder.handle(&stats());
+        recorder.handle(&state(RunState::Idle));
+
+        let storage = &recorder.storage;
+        assert_eq!(storage.records(None, None).unwrap().len(), 1);
+        let runs = storage.finished_runs("X1").unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].end_reason, "Completed");
+        assert_eq!(runs[0].bases_passed, 5_000);
+        let events: Vec<_> = storage
+            .events(Some("X1"))
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        assert_eq!(events, ["run_started", "run_completed"]);
+    }
+
+    #[test]
+    fn test_run_error_records_end_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = recorder(dir.path());
+
+        recorder.handle(&state(RunState::Running));
+        recorder.handle(&BusEvent::RunInfo {
+            position: "X1".into(),
+            info: RunInfo {
+                run_id: Some("run-1".into()),
+                ..Default::default()
+            },
+        });
+        recorder.handle(&state(RunState::Error("Flow cell overheated".into())));
+
+        let runs = recorder.storage.finished_runs("X1").unwrap();
+        assert_eq!(runs[0].end_reason, "Error: Flow cell overheated");
+    }
+}
@@ -0,0 +1,264 @@
+//! HTTP JSON API served by `termion daemon`.
+//!
+//! Keeps an [`App`] up to date from the event bus, exactly as the TUI does,
+//! and answers read-only requests from it, so dashboards and LIMS
+//! integrations get Termion's view of MinKNOW without speaking gRPC:
+//!
+//! - `GET /positions` — every position with its run state and current run
+//! - `GET /positions/<name>/stats` — the latest statistics of one position
+//!
+//! Errors are JSON objects with an `error` message. There is no
+//! authentication.
+
+use super::App;
+use crate::bus::{EventBus, EventFilter};
+use crate::client::{
+    DutyTimeSnapshot, FlowCellInfo, OutputInfo, Position, RunInfo, RunState, StatsSnapshot,
+    YieldDataPoint,
+};
+use crate::config::Config;
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::CONTENT_TYPE;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, serde::Serialize)]
+struct PositionEntry<'a> {
+    #[serde(flatten)]
+    position: &'a Position,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_state: Option<&'a RunState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run: Option<&'a RunInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flow_cell: Option<&'a FlowCellInfo>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct PositionStats<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_state: Option<&'a RunState>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stats: Option<&'a StatsSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duty_time: Option<&'a DutyTimeSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<&'a OutputInfo>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    yield_history: &'a [YieldDataPoint],
+}
+
+#[derive(Debug, serde::Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Binds `addr` and serves the API until the returned task is aborted.
+///
+/// The cache it answers from follows the bus until the bus is dropped.
+pub(super) async fn spawn(
+    bus: &EventBus,
+    config: &Config,
+    addr: SocketAddr,
+) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!(addr = %listener.local_addr()?, "Daemon API listening");
+
+    let app = Arc::new(RwLock::new(App::new(config.clone())));
+    let mut updates = bus.subscribe(EventFilter::all());
+    let cache = app.clone();
+    tokio::spawn(async move {
+        while let Some(event) = updates.recv().await {
+            cache
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .apply(&event);
+        }
+    });
+
+    Ok(tokio::spawn(serve(listener, app)))
+}
+
+async fn serve(listener: TcpListener, app: Arc<RwLock<App>>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to accept API connection");
+                continue;
+            }
+        };
+        let app = app.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request: Request<Incoming>| {
+                let app = app.clone();
+                async move { Ok::<_, Infallible>(handle(&app, &request)) }
+            });
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!(%peer, error = %e, "API connection closed with error");
+            }
+        });
+    }
+}
+
+fn handle(app: &RwLock<App>, request: &Request<Incoming>) -> Response<Full<Bytes>> {
+    let app = app.read().unwrap_or_else(|e| e.into_inner());
+    let (status, body) = respond(&app, request.method(), request.uri().path());
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body)))
+        .expect("static response parts are valid")
+}
+
+/// Answers one request from the cache: the status code and JSON body.
+fn respond(app: &App, method: &Method, path: &str) -> (StatusCode, String) {
+    if method != Method::GET {
+        return error(StatusCode::METHOD_NOT_ALLOWED, "Only GET is supported");
+    }
+    let path = path.trim_end_matches('/');
+    if path == "/positions" {
+        let positions: Vec<_> = app
+            .positions
+            .iter()
+            .map(|position| PositionEntry {
+                position,
+                run_state: app.run_states.get(&position.name),
+                run: app.run_info.get(&position.name),
+                flow_cell: app.flow_cell_info.get(&position.name),
+            })
+            .collect();
+        return json(&positions);
+    }
+
+    // Names of positions on one of several hosts contain a slash, so the
+    // name is everything between the prefix and the suffix.
+    let Some(name) = path
+        .strip_prefix("/positions/")
+        .and_then(|rest| rest.strip_suffix("/stats"))
+    else {
+        return error(StatusCode::NOT_FOUND, format!("No such endpoint: {}", path));
+    };
+    if !app.positions.iter().any(|p| p.name == name) {
+        return error(
+            StatusCode::NOT_FOUND,
+            format!("Position '{}' not found", name),
+        );
+    }
+    json(&PositionStats {
+        name,
+        run_state: app.run_states.get(name),
+        stats: app.stats_cache.get(name),
+        duty_time: app.duty_time.get(name),
+        output: app.output.get(name),
+        yield_history: app.yield_history.get(name).map_or(&[], Vec::as_slice),
+    })
+}
+
+fn json(value: &impl serde::Serialize) -> (StatusCode, String) {
+    match serde_json::to_string(value) {
+        Ok(body) => (StatusCode::OK, body),
+        Err(e) => error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    }
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, String) {
+    let body = ErrorBody {
+        error: message.into(),
+    };
+    let body = serde_json::to_string(&body).unwrap_or_else(|_| "{}".to_string());
+    (status, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::BusEvent;
+    use crate::client::{DeviceType, PositionState};
+
+    fn app_with_run() -> App {
+        let mut app = App::new(Config::default());
+        let position = |name: &str| Position {
+            id: name.to_string(),
+            name: name.to_string(),
+            device_id: "DEV001".to_string(),
+            state: PositionState::Running,
+            grpc_port: 8000,
+            is_simulated: false,
+            device_type: DeviceType::MinION,
+            host: None,
+        };
+        app.apply(&BusEvent::PositionsUpdated(vec![
+            position("X1"),
+            position("X2"),
+        ]));
+        app.apply(&BusEvent::RunStateChanged {
+            position: "X1".to_string(),
+            state: RunState::Running,
+        });
+        app.apply(&BusEvent::Stats {
+            position: "X1".to_string(),
+            stats: StatsSnapshot {
+                reads_processed: 1234,
+                ..Default::default()
+            },
+        });
+        app
+    }
+
+    fn get(app: &App, path: &str) -> (StatusCode, serde_json::Value) {
+        let (status, body) = respond(app, &Method::GET, path);
+        (status, serde_json::from_str(&body).expect("body is JSON"))
+    }
+
+    #[test]
+    fn test_positions_lists_every_position_with_run_state() {
+        let (status, body) = get(&app_with_run(), "/positions");
+
+        assert_eq!(status, StatusCode::OK);
+        let positions = body.as_array().expect("array of positions");
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[0]["name"], "X1");
+        assert_eq!(positions[0]["run_state"], "Running");
+        assert!(positions[1].get("run_state").is_none());
+    }
+
+    #[test]
+    fn test_position_stats() {
+        let app = app_with_run();
+
+        let (status, body) = get(&app, "/positions/X1/stats");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "X1");
+        assert_eq!(body["stats"]["reads_processed"], 1234);
+
+        let (status, body) = get(&app, "/positions/X2/stats/");
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.get("stats").is_none());
+    }
+
+    #[test]
+    fn test_unknown_paths_and_methods_are_errors() {
+        let app = app_with_run();
+
+        let (status, body) = get(&app, "/positions/X9/stats");
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "Position 'X9' not found");
+        assert_eq!(get(&app, "/metrics").0, StatusCode::NOT_FOUND);
+
+        let (status, _) = respond(&app, &Method::POST, "/positions");
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
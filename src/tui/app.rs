@@ -1,18 +1,82 @@
 //! Application state and core logic.
 
+use super::downsample;
 use super::theme::Theme;
+use crate::alerts::{self, AlertMetric, Breach};
+use crate::analysis::{self, Projection, ProjectionModel};
+use crate::bus::{BusEvent, EventKind};
+use crate::client::diagnostics::{version_warning, CheckResult, ErrorClass};
+use crate::client::metrics::RpcStats;
+use crate::client::ClientError;
 use crate::client::{
-    ChannelLayout, ChannelStatesSnapshot, DutyTimeSnapshot, FlowCellInfo, Position,
-    ReadLengthHistogram, RunInfo, RunState, StatsSnapshot, YieldDataPoint,
+    BarcodeYield, ChannelLayout, ChannelStatesSnapshot, DiskSpaceInfo, DutyTimeBucket,
+    DutyTimeSnapshot, FlowCellInfo, LastRun, MuxScanResult, OutputInfo, PhaseEvent, PhaseKind,
+    Position, PositionState, ProtocolInfo, QScoreHistogram, ReadLengthHistogram, RunInfo, RunState,
+    RunSummary, RunUntilCriteria, ServerInfo, SignalChunk, SpeedDataPoint, StatsSnapshot,
+    StopDataAction, StopOptions, YieldDataPoint,
 };
-use crate::config::Config;
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use crate::config::{Config, MAX_HISTOGRAM_BUCKETS};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Toasts shown at once; older ones are dropped first.
+const MAX_TOASTS: usize = 4;
+
+/// Events held back while the display is frozen; reaching this many
+/// unfreezes it, so a forgotten freeze cannot grow without bound.
+const MAX_HELD_EVENTS: usize = 50_000;
+
+/// Longest position note, in characters.
+pub const MAX_NOTE_LEN: usize = 80;
+
+/// Errors kept for the error log; the oldest are dropped first.
+pub const MAX_ERROR_LOG: usize = 200;
+
+/// Channel state updates remembered for each channel's occupancy.
+pub const OCCUPANCY_WINDOW: usize = 32;
+
+/// How long a changed value stays highlighted.
+pub const CHANGE_HIGHLIGHT: Duration = Duration::from_millis(1500);
+
+/// Channel map frames kept for time-lapse playback. When full, every other
+/// frame is dropped and frames are taken half as often, so the frames
+/// always span the whole run.
+pub const TIMELAPSE_FRAMES: usize = 480;
+
+/// Time between channel map frames at the start of a run.
+const TIMELAPSE_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// Median translocation speed, in bases per second, below which a run
+/// usually needs a nuclease flush.
+pub const SLOW_TRANSLOCATION_SPEED: f32 = 300.0;
+
+/// Raw signal samples kept for the signal chart, a few seconds at
+/// MinKNOW's usual sampling rates.
+pub const SIGNAL_WINDOW: usize = 20_000;
+
+/// Yield points kept per position or barcode. Past this the history is
+/// downsampled to half as many, so memory stays flat on multi-day runs.
+const YIELD_HISTORY_LIMIT: usize = 2048;
+
+/// Samples a [`ChartBuffer`] holds at most, however long its time span.
+const MAX_CHART_POINTS: usize = 2048;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Screen {
     Overview,
-    PositionDetail { position_idx: usize },
+    PositionDetail {
+        position_idx: usize,
+    },
+    /// Finished runs on a position.
+    RunHistory {
+        position_idx: usize,
+    },
+    /// Read-only summary of one finished run.
+    RunHistoryDetail {
+        position_idx: usize,
+        run_idx: usize,
+    },
 }
 
 /// Which chart to display in the position detail view.
@@ -25,6 +89,60 @@ pub enum DetailChart {
     ReadLength,
     /// Pore activity visualization.
     PoreActivity,
+    /// Per-barcode yield table and stacked chart.
+    Barcodes,
+    /// Live raw signal of one channel.
+    Signal,
+    /// Mean read q-score distribution.
+    QScore,
+    /// Available pores at each pore scan of the run.
+    PoreScans,
+    /// Active pore count sampled while the TUI runs.
+    ActivePores,
+    /// Share of time channels spent in each state over the run.
+    DutyTime,
+}
+
+/// Sort order of the barcode table; every order is descending except name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BarcodeSort {
+    #[default]
+    Bases,
+    Reads,
+    Name,
+}
+
+impl BarcodeSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BarcodeSort::Bases => "Bases",
+            BarcodeSort::Reads => "Reads",
+            BarcodeSort::Name => "Name",
+        }
+    }
+}
+
+/// Column the overview table is sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverviewSort {
+    #[default]
+    Position,
+    State,
+    Yield,
+    Throughput,
+    PassRate,
+}
+
+impl OverviewSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OverviewSort::Position => "Position",
+            OverviewSort::State => "State",
+            OverviewSort::Yield => "Yield",
+            OverviewSort::Throughput => "Throughput",
+            OverviewSort::PassRate => "Pass",
+        }
+    }
 }
 
 /// Unit for yield chart display.
@@ -35,11 +153,98 @@ pub enum YieldUnit {
     Reads,
 }
 
+/// A line of the yield and throughput charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YieldSeries {
+    Total,
+    Passed,
+    Failed,
+}
+
+impl YieldSeries {
+    pub fn label(&self) -> &'static str {
+        match self {
+            YieldSeries::Total => "Total",
+            YieldSeries::Passed => "Passed",
+            YieldSeries::Failed => "Failed",
+        }
+    }
+}
+
+/// Which lines the yield and throughput charts draw.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesVisibility {
+    pub total: bool,
+    pub passed: bool,
+    pub failed: bool,
+}
+
+impl Default for SeriesVisibility {
+    fn default() -> Self {
+        Self {
+            total: true,
+            passed: true,
+            failed: true,
+        }
+    }
+}
+
+impl SeriesVisibility {
+    pub fn is_visible(&self, series: YieldSeries) -> bool {
+        match series {
+            YieldSeries::Total => self.total,
+            YieldSeries::Passed => self.passed,
+            YieldSeries::Failed => self.failed,
+        }
+    }
+
+    pub fn toggle(&mut self, series: YieldSeries) {
+        let visible = match series {
+            YieldSeries::Total => &mut self.total,
+            YieldSeries::Passed => &mut self.passed,
+            YieldSeries::Failed => &mut self.failed,
+        };
+        *visible = !*visible;
+    }
+}
+
+/// Stretch of run time the yield chart shows, counted back from the latest
+/// point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YieldWindow {
+    #[default]
+    Full,
+    LastHour,
+    Last6Hours,
+    Last24Hours,
+}
+
+impl YieldWindow {
+    /// Length of the window, or `None` for the whole run.
+    pub fn seconds(&self) -> Option<u32> {
+        match self {
+            YieldWindow::Full => None,
+            YieldWindow::LastHour => Some(3600),
+            YieldWindow::Last6Hours => Some(6 * 3600),
+            YieldWindow::Last24Hours => Some(24 * 3600),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            YieldWindow::Full => "full run",
+            YieldWindow::LastHour => "last 1h",
+            YieldWindow::Last6Hours => "last 6h",
+            YieldWindow::Last24Hours => "last 24h",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RunControlAction {
     Pause,
     Resume,
-    Stop,
+    Stop(StopOptions),
 }
 
 impl RunControlAction {
@@ -47,7 +252,7 @@ impl RunControlAction {
         match self {
             RunControlAction::Pause => "Pause",
             RunControlAction::Resume => "Resume",
-            RunControlAction::Stop => "Stop",
+            RunControlAction::Stop(_) => "Stop",
         }
     }
 
@@ -55,9 +260,25 @@ impl RunControlAction {
         match self {
             RunControlAction::Pause => "Pause the current run?",
             RunControlAction::Resume => "Resume the paused run?",
-            RunControlAction::Stop => "Stop the current run? This cannot be undone.",
+            RunControlAction::Stop(_) => "Stop the current run? This cannot be undone.",
         }
     }
+
+    /// Whether confirming takes typing the position name or `STOP` rather
+    /// than just `Enter`. A stopped run cannot be resumed.
+    pub fn needs_typed_confirmation(&self) -> bool {
+        matches!(self, RunControlAction::Stop(_))
+    }
+}
+
+/// Word that confirms a Stop in place of the position name.
+pub const STOP_CONFIRMATION: &str = "STOP";
+
+/// Whether `typed` confirms an action on `position_name`: the position name
+/// or [`STOP_CONFIRMATION`], ignoring case and surrounding spaces.
+pub fn confirmation_matches(typed: &str, position_name: &str) -> bool {
+    let typed = typed.trim();
+    typed.eq_ignore_ascii_case(position_name) || typed.eq_ignore_ascii_case(STOP_CONFIRMATION)
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -67,24 +288,254 @@ pub enum Overlay {
     Error {
         message: String,
     },
-    RangeInput {
-        max_input: String,
-    },
+    RangeInput(HistogramForm),
     Confirmation {
         action: RunControlAction,
         position_name: String,
+        /// Text typed so far, for actions that need typed confirmation.
+        typed: String,
     },
     ThemeSelector {
         selected: usize,
     },
+    Diagnostics {
+        results: Vec<CheckResult>,
+    },
+    StartProtocol(StartWizard),
+    RunUntil(RunUntilForm),
+    /// Editing the overview filter; see [`App::position_filter`].
+    Filter,
+    /// Frame times, queue depths and RPC latencies; see [`App::perf`].
+    Debug,
+    /// Recent errors, newest first, scrolled down by `scroll` entries; see
+    /// [`App::errors`].
+    ErrorLog {
+        scroll: usize,
+    },
+    /// Editing the note on a position; see [`App::notes`].
+    Note {
+        position_name: String,
+        text: String,
+    },
+}
+
+/// Steps of the start-protocol wizard, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartStep {
+    SelectProtocol,
+    Details,
+    Confirm,
+}
+
+impl StartStep {
+    pub fn number(&self) -> usize {
+        match self {
+            StartStep::SelectProtocol => 1,
+            StartStep::Details => 2,
+            StartStep::Confirm => 3,
+        }
+    }
+}
+
+/// Text field being edited on the details step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartField {
+    #[default]
+    SampleId,
+    ExperimentGroup,
+}
+
+/// State of the start-protocol wizard overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartWizard {
+    pub position_name: String,
+    pub step: StartStep,
+    /// Protocols available on the position, or `None` while loading.
+    pub protocols: Option<Vec<ProtocolInfo>>,
+    pub selected: usize,
+    pub sample_id: String,
+    pub experiment_group: String,
+    pub field: StartField,
+}
+
+impl StartWizard {
+    fn new(position_name: String) -> Self {
+        Self {
+            position_name,
+            step: StartStep::SelectProtocol,
+            protocols: None,
+            selected: 0,
+            sample_id: String::new(),
+            experiment_group: String::new(),
+            field: StartField::default(),
+        }
+    }
+
+    pub fn selected_protocol(&self) -> Option<&ProtocolInfo> {
+        self.protocols.as_ref()?.get(self.selected)
+    }
+}
+
+/// A protocol start confirmed in the wizard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StartRequest {
+    pub position_name: String,
+    pub identifier: String,
+    pub sample_id: String,
+    pub experiment_group: String,
+}
+
+/// Point read off the yield or q-score chart with the crosshair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartCursor {
+    /// Seconds into the acquisition of the inspected yield point.
+    Yield(u32),
+    /// Index of the inspected q-score bucket.
+    QScore(usize),
+}
+
+/// Yield points the crosshair skips per step: a hundredth of the run, so
+/// it crosses the chart in a hundred key presses whatever the run length.
+const CURSOR_STEPS: usize = 100;
+
+/// Text field being edited in the histogram settings form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistogramField {
+    #[default]
+    MinLength,
+    MaxLength,
+    Buckets,
+    OutlierPercent,
+}
+
+/// State of the histogram settings overlay. An empty min length starts at
+/// zero and an empty max length shows the full range, an empty bucket count
+/// leaves the bucket width to MinKNOW, and an empty outlier percent restores
+/// the configured one.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HistogramForm {
+    pub min_length: String,
+    pub max_length: String,
+    pub buckets: String,
+    pub outlier_percent: String,
+    pub field: HistogramField,
+    /// Set when a field holds a value out of range.
+    pub invalid: bool,
+}
+
+/// Text field being edited in the run-until form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RunUntilField {
+    #[default]
+    Hours,
+    Gigabases,
+}
+
+/// State of the run-until overlay, which edits the runtime and estimated
+/// yield targets of a running position. Empty fields clear the target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunUntilForm {
+    pub position_name: String,
+    pub hours: String,
+    pub gigabases: String,
+    pub field: RunUntilField,
+    /// Set when a field does not hold a positive number.
+    pub invalid: bool,
+}
+
+/// New run-until targets confirmed in the run-until overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunUntilRequest {
+    pub position_name: String,
+    pub targets: RunUntilCriteria,
+}
+
+/// Severity of a toast, which sets its color and how long it stays up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    pub fn duration(&self) -> Duration {
+        match self {
+            ToastLevel::Info => Duration::from_secs(4),
+            ToastLevel::Warning => Duration::from_secs(6),
+            ToastLevel::Error => Duration::from_secs(10),
+        }
+    }
+}
+
+/// Render loop timings, queue depths and RPC latencies shown on the debug
+/// overlay. Only kept up to date while the overlay is open, apart from the
+/// frame times.
+#[derive(Debug, Clone, Default)]
+pub struct PerfStats {
+    pub frames: u64,
+    pub last_frame: Duration,
+    /// Exponentially weighted mean over roughly the last 20 frames.
+    pub mean_frame: Duration,
+    pub max_frame: Duration,
+    /// Bus events waiting to be applied.
+    pub bus_backlog: usize,
+    /// Bus events skipped because the TUI fell behind.
+    pub bus_dropped: u64,
+    pub rpc: Vec<RpcStats>,
+}
+
+impl PerfStats {
+    pub fn record_frame(&mut self, elapsed: Duration) {
+        self.mean_frame = if self.frames == 0 {
+            elapsed
+        } else {
+            self.mean_frame.mul_f64(0.95) + elapsed.mul_f64(0.05)
+        };
+        self.frames += 1;
+        self.last_frame = elapsed;
+        self.max_frame = self.max_frame.max(elapsed);
+    }
+}
+
+/// A failure reported to the user, kept in the error log after its toast
+/// has gone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedError {
+    /// When it last happened.
+    pub at: chrono::DateTime<chrono::Utc>,
+    pub position: Option<String>,
+    pub message: String,
+    /// How many times in a row it happened; repeats update `at` instead of
+    /// adding entries.
+    pub count: u32,
+}
+
+/// A short message shown in the corner of the screen until it expires.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub level: ToastLevel,
+    pub message: String,
+    pub created: Instant,
 }
 
 #[derive(Debug, Clone)]
 pub enum ConnectionState {
     Connected,
     Connecting,
-    Disconnected { since: Instant, reason: String },
-    Reconnecting { attempt: u32 },
+    Disconnected {
+        since: Instant,
+        reason: String,
+        /// The number of the next automatic attempt and when it starts,
+        /// once the backoff delay is known.
+        next_attempt: Option<(u32, Instant)>,
+    },
+    /// An automatic or manual attempt is under way; `reason` is why the
+    /// last one failed.
+    Reconnecting {
+        attempt: u32,
+        reason: String,
+    },
 }
 
 pub struct App {
@@ -93,34 +544,381 @@ pub struct App {
     pub screen: Screen,
     pub overlay: Overlay,
     pub connection: ConnectionState,
+    /// Category of the most recent connection failure, cleared on connect.
+    pub connection_error_class: Option<ErrorClass>,
+    /// Connection state of each watched host, in config order, when several
+    /// hosts are watched. Empty otherwise.
+    pub hosts: Vec<(String, ConnectionState)>,
+    /// Why MinKNOW refused control requests, once it has. Run control is
+    /// disabled until the next connect.
+    pub read_only: Option<String>,
+    /// The manager connected to, once connected. Unknown when several
+    /// hosts are watched.
+    pub server: Option<ServerInfo>,
+    /// Latest round-trip time to the manager while connected.
+    pub latency: Option<Duration>,
+    /// Whether the terminal window has focus, as last reported by the
+    /// terminal. Terminals that do not report focus stay focused.
+    pub focused: bool,
     pub positions: Vec<Position>,
-    pub selected_position: usize,
+    /// Name of the selected position. It is resolved to a row when needed,
+    /// so re-listed or removed positions never move the highlight onto a
+    /// different flow cell.
+    pub selected: Option<String>,
     pub stats_cache: HashMap<String, StatsSnapshot>,
+    /// When each position's stat values last changed, while highlighted.
+    stat_changes: HashMap<String, HashMap<StatValue, Instant>>,
     pub run_states: HashMap<String, RunState>,
     pub chart_data: HashMap<String, ChartBuffer>,
     pub should_quit: bool,
-    pub last_error: Option<String>,
+    /// Set when data or UI state changed since the last frame; the event
+    /// loop only redraws while it is set.
+    pub dirty: bool,
+    /// Errors reported since startup, oldest first, up to
+    /// [`MAX_ERROR_LOG`].
+    pub errors: VecDeque<LoggedError>,
     pub detail_chart: DetailChart,
     pub yield_unit: YieldUnit,
+    pub yield_window: YieldWindow,
+    /// Plots reads and bases together on two axes instead of `yield_unit`.
+    pub yield_dual_axis: bool,
+    /// Plots output per hour instead of the cumulative yield.
+    pub yield_rates: bool,
+    /// Lines drawn on the yield and throughput charts.
+    pub yield_series: SeriesVisibility,
+    /// Names the lines of the yield and throughput charts in a legend.
+    pub show_legend: bool,
     pub exclude_outliers: bool,
+    /// Weights the read length histogram by bases instead of reads.
+    pub histogram_by_bases: bool,
+    /// Buckets the read length histogram splits `histogram_range` into.
+    pub histogram_buckets: Option<u32>,
+    /// Percent of the longest reads left out while outliers are excluded.
+    pub outlier_percent: f32,
+    /// Draws the projected yield to the end of the run on the yield chart.
+    pub show_projection: bool,
     /// Optional user-specified range for read length histogram (min, max) in bases.
     /// When set, the API will be queried with this range to get finer bins.
     pub histogram_range: Option<(u64, u64)>,
     pub yield_history: HashMap<String, Vec<YieldDataPoint>>,
+    /// Per-barcode yield history for each position's current run.
+    pub barcode_yield: HashMap<String, Vec<BarcodeYield>>,
+    pub barcode_sort: BarcodeSort,
+    pub overview_sort: OverviewSort,
+    /// Reverses the overview sort. Positions without a value for the sort
+    /// column stay at the bottom either way.
+    pub overview_sort_reversed: bool,
+    /// Names of pinned positions, which the overview lists first.
+    pub pinned: Vec<String>,
+    /// Notes on positions by name, shown in the detail header.
+    pub notes: BTreeMap<String, String>,
+    /// Whether the overview leaves out simulated positions.
+    pub hide_simulated: bool,
+    /// Whether the overview leaves out positions without a run.
+    pub hide_idle: bool,
+    /// Labels time axes and the overview with wall-clock times instead of
+    /// run time.
+    pub clock_time: bool,
+    /// Case-insensitive text the overview table is narrowed to; matches the
+    /// position name, device, or flow cell ID. Empty shows every position.
+    pub position_filter: String,
     pub histograms: HashMap<String, ReadLengthHistogram>,
+    pub qscore_histograms: HashMap<String, QScoreHistogram>,
+    /// Pore scan results of each position's current run, oldest first.
+    pub mux_scans: HashMap<String, Vec<MuxScanResult>>,
+    /// Translocation speed of each position's current run, oldest first.
+    pub translocation_speed: HashMap<String, Vec<SpeedDataPoint>>,
+    /// Run-until criteria that stop each position's current run.
+    pub run_until_targets: HashMap<String, RunUntilCriteria>,
+    /// Progress towards the run-until criteria, as reported by the
+    /// run-until script.
+    pub run_until_progress: HashMap<String, RunUntilCriteria>,
+    /// Active pore counts of each position's current run against seconds
+    /// since the TUI started, covering `tui.chart_history`.
+    pub pore_history: HashMap<String, ChartBuffer>,
+    /// Throughput in Gb/h of each position's current run against seconds
+    /// since the TUI started, covering `tui.chart_history`.
+    pub throughput_history: HashMap<String, ChartBuffer>,
     pub duty_time: HashMap<String, DutyTimeSnapshot>,
+    /// Duty time buckets of each position's current run, oldest first.
+    pub duty_history: HashMap<String, Vec<DutyTimeBucket>>,
     pub channel_states: HashMap<String, ChannelStatesSnapshot>,
     pub channel_layouts: HashMap<String, ChannelLayout>,
     pub run_info: HashMap<String, RunInfo>,
+    /// Protocol phase transitions for each position's current run.
+    pub phase_history: HashMap<String, Vec<PhaseEvent>>,
     pub flow_cell_info: HashMap<String, FlowCellInfo>,
-    /// Tracks when throughput was last calculated for each position (for throttling).
-    pub throughput_last_calc: HashMap<String, Instant>,
+    pub disk_space: HashMap<String, DiskSpaceInfo>,
+    /// Output location and writer progress of each position's current run.
+    pub output: HashMap<String, OutputInfo>,
     pub channel_map_scroll_offset: usize,
+    /// Colors the channel map by each channel's pore occupancy over the
+    /// run instead of its current state.
+    pub occupancy_colors: bool,
+    /// Channel (0-based) highlighted on the channel map while inspecting
+    /// single channels; `None` outside inspection mode.
+    pub channel_cursor: Option<usize>,
+    /// Point inspected on the yield or q-score chart; `None` outside
+    /// inspection mode.
+    pub chart_cursor: Option<ChartCursor>,
+    pub channel_occupancy: HashMap<String, ChannelOccupancy>,
+    /// Channel map frames of each position's current run.
+    pub channel_timelapse: HashMap<String, ChannelTimelapse>,
+    /// Time of the time-lapse frame shown on the channel map instead of the
+    /// live states; `None` while live.
+    pub timelapse_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Channel (1-based) plotted on the signal chart.
+    pub signal_channel: u32,
+    /// Most recent raw signal of each position's signal channel.
+    pub signal: HashMap<String, SignalTrace>,
+    /// Finished runs for each position, newest first. Only fetched while the
+    /// run history screen is open.
+    pub run_history: HashMap<String, Vec<RunSummary>>,
+    /// Last finished run of idle positions, or `None` for positions that
+    /// have none. Only fetched while the position is in the detail view.
+    pub last_runs: HashMap<String, Option<LastRun>>,
+    /// Selected row on the run history screen.
+    pub history_selected: usize,
+    /// Alert rules currently breached on each position.
+    pub alerts: HashMap<String, Vec<Breach>>,
+    /// Transient messages, oldest first.
+    pub toasts: VecDeque<Toast>,
+    pub perf: PerfStats,
+    /// When the display was frozen; `None` while it is live. Frozen, state
+    /// and data events are held back so values stay put on screen.
+    pub frozen_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Events held back while frozen, oldest first.
+    held: Vec<BusEvent>,
+    /// When the app was created; time zero of sampled chart data.
+    started: Instant,
+}
+
+/// A stat shown in the overview and Run Info that is highlighted for a
+/// moment when it changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatValue {
+    Reads,
+    Bases,
+    Throughput,
+    PassRate,
+    ActivePores,
+}
+
+impl StatValue {
+    const ALL: [StatValue; 5] = [
+        StatValue::Reads,
+        StatValue::Bases,
+        StatValue::Throughput,
+        StatValue::PassRate,
+        StatValue::ActivePores,
+    ];
+
+    fn of(self, stats: &StatsSnapshot) -> f64 {
+        match self {
+            StatValue::Reads => stats.reads_processed as f64,
+            StatValue::Bases => stats.bases_called as f64,
+            StatValue::Throughput => stats.throughput_gbph,
+            StatValue::PassRate => stats.pass_rate(),
+            StatValue::ActivePores => stats.active_pores as f64,
+        }
+    }
+}
+
+/// Recent sequencing history of every channel on a position.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelOccupancy {
+    /// Per channel, bit `i` is set if the channel was in a strand `i`
+    /// updates ago.
+    history: Vec<u32>,
+    /// Updates recorded, up to [`OCCUPANCY_WINDOW`].
+    samples: usize,
+}
+
+impl ChannelOccupancy {
+    fn record(&mut self, states: &ChannelStatesSnapshot) {
+        if self.history.len() != states.states.len() {
+            self.history = vec![0; states.states.len()];
+            self.samples = 0;
+        }
+        for (channel, bits) in self.history.iter_mut().enumerate() {
+            *bits = (*bits << 1) | u32::from(states.is_sequencing(channel));
+        }
+        self.samples = (self.samples + 1).min(OCCUPANCY_WINDOW);
+    }
+
+    /// Share of the recorded updates in which `channel` was in a strand.
+    pub fn fraction(&self, channel: usize) -> Option<f64> {
+        let bits = self.history.get(channel)?;
+        if self.samples == 0 {
+            return None;
+        }
+        let sequencing = self.recent_bits(*bits).count_ones();
+        Some(sequencing as f64 / self.samples as f64)
+    }
+
+    /// Whether `channel` was in a strand at each recorded update, oldest
+    /// first.
+    pub fn recent(&self, channel: usize) -> Vec<bool> {
+        let Some(&bits) = self.history.get(channel) else {
+            return Vec::new();
+        };
+        (0..self.samples)
+            .rev()
+            .map(|i| bits & (1 << i) != 0)
+            .collect()
+    }
+
+    fn recent_bits(&self, bits: u32) -> u32 {
+        if self.samples >= OCCUPANCY_WINDOW {
+            bits
+        } else {
+            bits & ((1 << self.samples) - 1)
+        }
+    }
+}
+
+/// Channel map frames of a run, oldest first, for stepping back through
+/// how the pores changed.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelTimelapse {
+    /// State names that frames refer to by index.
+    names: Vec<String>,
+    frames: Vec<(chrono::DateTime<chrono::Utc>, Vec<u16>)>,
+    /// Time between frames, doubled each time the frames are thinned.
+    interval: chrono::TimeDelta,
+}
+
+impl ChannelTimelapse {
+    fn record(&mut self, states: &ChannelStatesSnapshot, at: chrono::DateTime<chrono::Utc>) {
+        let channels = self.frames.last().map(|(_, frame)| frame.len());
+        if channels.is_some_and(|channels| channels != states.states.len()) {
+            *self = Self::default();
+        }
+        if self.interval.is_zero() {
+            self.interval = TIMELAPSE_INTERVAL;
+        }
+        if self
+            .frames
+            .last()
+            .is_some_and(|&(last, _)| at - last < self.interval)
+        {
+            return;
+        }
+        let frame = states
+            .states
+            .iter()
+            .map(
+                |state| match self.names.iter().position(|name| name == state) {
+                    Some(idx) => idx as u16,
+                    None => {
+                        self.names.push(state.clone());
+                        (self.names.len() - 1) as u16
+                    }
+                },
+            )
+            .collect();
+        self.frames.push((at, frame));
+        if self.frames.len() > TIMELAPSE_FRAMES {
+            let mut idx = 0;
+            self.frames.retain(|_| {
+                idx += 1;
+                idx % 2 == 1
+            });
+            self.interval = self.interval * 2;
+        }
+    }
+
+    /// Number of frames recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Index of the latest frame taken at or before `at`, or the first.
+    pub fn index_at(&self, at: chrono::DateTime<chrono::Utc>) -> usize {
+        self.frames
+            .partition_point(|&(time, _)| time <= at)
+            .saturating_sub(1)
+    }
+
+    /// When frame `idx` was taken.
+    pub fn time(&self, idx: usize) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.frames.get(idx).map(|&(time, _)| time)
+    }
+
+    /// Time missing before frame `idx`, if it was taken well over a frame
+    /// interval after the one before. Frames are only taken while the
+    /// position's detail view is open, so leaving it leaves a gap.
+    pub fn gap_before(&self, idx: usize) -> Option<chrono::TimeDelta> {
+        let previous = self.time(idx.checked_sub(1)?)?;
+        let gap = self.time(idx)? - previous;
+        (gap > self.interval * 2).then_some(gap)
+    }
+
+    /// The channel states of frame `idx`.
+    pub fn snapshot(&self, idx: usize) -> Option<ChannelStatesSnapshot> {
+        let (_, frame) = self.frames.get(idx)?;
+        let states: Vec<String> = frame
+            .iter()
+            .map(|&name| self.names[name as usize].clone())
+            .collect();
+        let mut state_counts = HashMap::new();
+        for state in &states {
+            *state_counts.entry(state.clone()).or_insert(0) += 1;
+        }
+        Some(ChannelStatesSnapshot {
+            channel_count: states.len(),
+            states,
+            state_counts,
+        })
+    }
+}
+
+/// A channel map frame picked for time-lapse playback.
+#[derive(Debug, Clone)]
+pub struct TimelapseFrame {
+    /// Index of the frame, oldest first.
+    pub index: usize,
+    /// Number of frames recorded.
+    pub frames: usize,
+    /// When the frame was taken.
+    pub at: chrono::DateTime<chrono::Utc>,
+    /// Time without frames just before this one; see
+    /// [`ChannelTimelapse::gap_before`].
+    pub gap: Option<chrono::TimeDelta>,
+    pub states: ChannelStatesSnapshot,
+}
+
+/// Scrolling window of raw signal from one channel.
+#[derive(Debug, Clone, Default)]
+pub struct SignalTrace {
+    /// Channel (1-based) the samples come from.
+    pub channel: u32,
+    /// Current in picoamps, oldest first, up to [`SIGNAL_WINDOW`] samples.
+    pub samples: VecDeque<f32>,
+}
+
+impl SignalTrace {
+    fn push(&mut self, chunk: &SignalChunk) {
+        if chunk.channel != self.channel {
+            self.channel = chunk.channel;
+            self.samples.clear();
+        }
+        self.samples.extend(chunk.picoamps.iter().copied());
+        let excess = self.samples.len().saturating_sub(SIGNAL_WINDOW);
+        self.samples.drain(..excess);
+    }
 }
 
 pub struct ChartBuffer {
     pub data: VecDeque<(f64, f64)>,
     pub max_points: usize,
+    /// Spacing kept between samples; a sample closer than this to the one
+    /// before the latest replaces the latest instead of being added.
+    pub min_interval: f64,
 }
 
 impl ChartBuffer {
@@ -128,10 +926,32 @@ impl ChartBuffer {
         Self {
             data: VecDeque::with_capacity(max_points),
             max_points,
+            min_interval: 0.0,
+        }
+    }
+
+    /// A buffer covering `span` seconds of samples taken every `interval`
+    /// seconds. Spans needing more than [`MAX_CHART_POINTS`] samples are
+    /// covered at a coarser spacing.
+    pub fn spanning(span: f64, interval: f64) -> Self {
+        let needed = (span / interval.max(0.001)).ceil() as usize;
+        if needed <= MAX_CHART_POINTS {
+            return Self::new(needed.max(2));
+        }
+        Self {
+            min_interval: span / MAX_CHART_POINTS as f64,
+            ..Self::new(MAX_CHART_POINTS)
         }
     }
 
     pub fn push(&mut self, timestamp: f64, value: f64) {
+        let len = self.data.len();
+        // The latest point follows new samples until it is `min_interval`
+        // past the one before it.
+        if len >= 2 && self.data[len - 1].0 - self.data[len - 2].0 < self.min_interval {
+            self.data[len - 1] = (timestamp, value);
+            return;
+        }
         if self.data.len() >= self.max_points {
             self.data.pop_front();
         }
@@ -143,90 +963,512 @@ impl ChartBuffer {
     }
 }
 
+/// Downsamples a yield history that has grown past
+/// [`YIELD_HISTORY_LIMIT`] points, keeping the shape of its total bases.
+fn bound_yield_points(points: &mut Vec<YieldDataPoint>) {
+    if points.len() <= YIELD_HISTORY_LIMIT {
+        return;
+    }
+    let series: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| (p.seconds as f64, p.bases as f64))
+        .collect();
+    let keep = downsample::lttb_indices(&series, YIELD_HISTORY_LIMIT / 2);
+    let mut keep = keep.into_iter().peekable();
+    let mut index = 0;
+    points.retain(|_| {
+        let kept = keep.next_if_eq(&index).is_some();
+        index += 1;
+        kept
+    });
+}
+
 impl App {
     pub fn new(config: Config) -> Self {
         let theme = Theme::by_name(&config.tui.theme).unwrap_or_default();
+        let pinned = config.tui.pinned.clone();
+        let notes = config.load_notes().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to load position notes");
+            BTreeMap::new()
+        });
+        let (hide_simulated, hide_idle) = (config.tui.hide_simulated, config.tui.hide_idle);
+        let clock_time = config.tui.clock_time;
+        let histogram_buckets = config.tui.histogram_buckets;
+        let outlier_percent = config.tui.histogram_outlier_percent as f32;
+        let hosts = if config.connection.is_multi_host() {
+            config
+                .connection
+                .endpoints()
+                .into_iter()
+                .map(|endpoint| (endpoint.label, ConnectionState::Connecting))
+                .collect()
+        } else {
+            Vec::new()
+        };
         Self {
             config,
             theme,
             screen: Screen::Overview,
             overlay: Overlay::None,
             connection: ConnectionState::Connecting,
+            connection_error_class: None,
+            hosts,
+            read_only: None,
+            server: None,
+            latency: None,
+            focused: true,
             positions: Vec::new(),
-            selected_position: 0,
+            selected: None,
             stats_cache: HashMap::new(),
+            stat_changes: HashMap::new(),
             run_states: HashMap::new(),
             chart_data: HashMap::new(),
             should_quit: false,
-            last_error: None,
+            dirty: true,
+            errors: VecDeque::new(),
             detail_chart: DetailChart::default(),
             yield_unit: YieldUnit::default(),
+            yield_window: YieldWindow::default(),
+            yield_dual_axis: false,
+            yield_rates: false,
+            yield_series: SeriesVisibility::default(),
+            show_legend: false,
             exclude_outliers: true,
+            histogram_by_bases: false,
+            histogram_buckets,
+            outlier_percent,
+            show_projection: false,
             histogram_range: None,
             yield_history: HashMap::new(),
+            barcode_yield: HashMap::new(),
+            barcode_sort: BarcodeSort::default(),
+            overview_sort: OverviewSort::default(),
+            overview_sort_reversed: false,
+            pinned,
+            notes,
+            hide_simulated,
+            hide_idle,
+            clock_time,
+            position_filter: String::new(),
             histograms: HashMap::new(),
+            qscore_histograms: HashMap::new(),
+            mux_scans: HashMap::new(),
+            translocation_speed: HashMap::new(),
+            run_until_targets: HashMap::new(),
+            run_until_progress: HashMap::new(),
+            pore_history: HashMap::new(),
+            throughput_history: HashMap::new(),
             duty_time: HashMap::new(),
+            duty_history: HashMap::new(),
             channel_states: HashMap::new(),
             channel_layouts: HashMap::new(),
             run_info: HashMap::new(),
+            phase_history: HashMap::new(),
             flow_cell_info: HashMap::new(),
-            throughput_last_calc: HashMap::new(),
+            disk_space: HashMap::new(),
+            output: HashMap::new(),
             channel_map_scroll_offset: 0,
+            occupancy_colors: false,
+            channel_cursor: None,
+            chart_cursor: None,
+            channel_occupancy: HashMap::new(),
+            channel_timelapse: HashMap::new(),
+            timelapse_at: None,
+            signal_channel: 1,
+            signal: HashMap::new(),
+            run_history: HashMap::new(),
+            last_runs: HashMap::new(),
+            history_selected: 0,
+            alerts: HashMap::new(),
+            toasts: VecDeque::new(),
+            perf: PerfStats::default(),
+            frozen_at: None,
+            held: Vec::new(),
+            started: Instant::now(),
         }
     }
 
-    pub fn update_flow_cell_info(&mut self, position_name: &str, info: FlowCellInfo) {
-        self.flow_cell_info.insert(position_name.to_string(), info);
-    }
-
-    pub fn update_channel_states(&mut self, position_name: &str, states: ChannelStatesSnapshot) {
-        self.channel_states
-            .insert(position_name.to_string(), states);
-    }
-
-    pub fn update_channel_layout(&mut self, position_name: &str, layout: ChannelLayout) {
-        self.channel_layouts
-            .insert(position_name.to_string(), layout);
-    }
-
-    pub fn select_next(&mut self) {
-        if !self.positions.is_empty() {
-            self.selected_position = (self.selected_position + 1) % self.positions.len();
-            self.reset_channel_map_scroll();
-        }
-    }
-
-    pub fn select_previous(&mut self) {
-        if !self.positions.is_empty() {
-            self.selected_position = self
-                .selected_position
-                .checked_sub(1)
-                .unwrap_or(self.positions.len() - 1);
-            self.reset_channel_map_scroll();
-        }
-    }
-
-    pub fn enter_detail(&mut self) {
-        if let Some(pos) = self.positions.get(self.selected_position) {
-            let is_active = self
-                .run_states
-                .get(&pos.name)
-                .map(|s| s.is_active())
-                .unwrap_or(false);
-            if is_active {
-                self.screen = Screen::PositionDetail {
-                    position_idx: self.selected_position,
-                };
+    /// Applies an event from the bus to the application state.
+    pub fn apply(&mut self, event: &BusEvent) {
+        // Connection changes and alerts still show while frozen.
+        if self.frozen_at.is_some() && matches!(event.kind(), EventKind::State | EventKind::Data) {
+            self.held.push(event.clone());
+            if self.held.len() >= MAX_HELD_EVENTS {
+                self.unfreeze();
+                self.push_toast(
+                    ToastLevel::Warning,
+                    "Too many updates held back; display unfrozen".into(),
+                );
             }
+            return;
+        }
+        self.dirty = true;
+        match event {
+            BusEvent::Connected => {
+                let recovering = matches!(
+                    self.connection,
+                    ConnectionState::Disconnected { .. } | ConnectionState::Reconnecting { .. }
+                );
+                self.set_connected();
+                if recovering {
+                    self.push_toast(ToastLevel::Info, "Reconnected".into());
+                }
+            }
+            BusEvent::Disconnected { reason, class } => {
+                self.connection_error_class = *class;
+                self.log_error(None, &format!("Connection failed: {}", reason));
+                self.set_disconnected(reason.clone());
+            }
+            BusEvent::Reconnecting { attempt } => self.set_reconnecting(*attempt),
+            BusEvent::ReconnectScheduled { attempt, delay } => {
+                self.schedule_reconnect(*attempt, *delay)
+            }
+            BusEvent::ReadOnly { reason } => self.set_read_only(reason.clone()),
+            BusEvent::ServerInfo { info } => self.set_server(info.clone()),
+            BusEvent::Latency { rtt } => self.latency = Some(*rtt),
+            BusEvent::PositionsUpdated(positions) => self.update_positions(positions.clone()),
+            BusEvent::HostConnected { host } => {
+                self.set_host_connection(host, ConnectionState::Connected)
+            }
+            BusEvent::HostDisconnected { host, reason } => self.set_host_connection(
+                host,
+                ConnectionState::Disconnected {
+                    since: Instant::now(),
+                    reason: reason.clone(),
+                    next_attempt: None,
+                },
+            ),
+            BusEvent::HostPositions { host, positions } => {
+                self.update_host_positions(host, positions)
+            }
+            BusEvent::PositionsChanged { updated, removed } => {
+                self.change_positions(updated, removed)
+            }
+            BusEvent::RunStateChanged { position, state } => {
+                self.update_run_state(position, state.clone())
+            }
+            BusEvent::FlowCellInfo { position, info } => {
+                self.update_flow_cell_info(position, info.clone());
+                self.keep_selection_visible();
+            }
+            BusEvent::RunInfo { position, info } => self.update_run_info(position, info.clone()),
+            BusEvent::Stats { position, stats } => {
+                let before = self.stat_values(position);
+                self.update_stats(position, stats.clone());
+                self.note_stat_changes(position, before);
+                self.evaluate_alerts(
+                    position,
+                    &[AlertMetric::PassRate, AlertMetric::MeanReadLength],
+                );
+            }
+            BusEvent::Throughput {
+                position,
+                bases_per_second,
+            } => {
+                let before = self.stat_values(position);
+                let gbph = bases_per_second * 3600.0 / 1_000_000_000.0;
+                if let Some(stats) = self.stats_cache.get_mut(position) {
+                    stats.throughput_bps = *bases_per_second;
+                    stats.throughput_gbph = gbph;
+                }
+                self.note_stat_changes(position, before);
+                let seconds = self.started.elapsed().as_secs_f64();
+                self.record_throughput(position, gbph, seconds);
+                self.evaluate_alerts(position, &[AlertMetric::ThroughputGbph]);
+            }
+            BusEvent::ActivePores { position, count } => {
+                let before = self.stat_values(position);
+                if let Some(stats) = self.stats_cache.get_mut(position) {
+                    stats.active_pores = *count;
+                }
+                self.note_stat_changes(position, before);
+                let seconds = self.started.elapsed().as_secs_f64();
+                self.record_active_pores(position, *count, seconds);
+                self.evaluate_alerts(position, &[AlertMetric::ActivePores]);
+            }
+            BusEvent::MeanQuality { position, quality } => {
+                if let Some(stats) = self.stats_cache.get_mut(position) {
+                    stats.mean_quality = *quality;
+                }
+                self.evaluate_alerts(position, &[AlertMetric::MeanQuality]);
+            }
+            BusEvent::YieldHistory {
+                position,
+                points,
+                merge,
+            } => {
+                if *merge {
+                    self.merge_yield_history(position, points.clone());
+                } else {
+                    self.update_yield_history(position, points.clone());
+                }
+            }
+            BusEvent::BarcodeYield {
+                position,
+                barcodes,
+                merge,
+            } => {
+                if *merge {
+                    self.merge_barcode_yield(position, barcodes.clone());
+                } else {
+                    self.barcode_yield
+                        .insert(position.clone(), barcodes.clone());
+                }
+            }
+            BusEvent::PhaseHistory { position, events } => {
+                self.merge_phase_history(position, events.clone())
+            }
+            BusEvent::Histogram {
+                position,
+                histogram,
+            } => self.update_histogram(position, histogram.clone()),
+            BusEvent::DiskSpace { position, info } => {
+                self.update_disk_space(position, info.clone())
+            }
+            BusEvent::Output { position, info } => {
+                self.output.insert(position.clone(), info.clone());
+            }
+            BusEvent::RunHistory { position, runs } => {
+                self.update_run_history(position, runs.clone())
+            }
+            BusEvent::LastRun { position, run } => {
+                self.last_runs
+                    .insert(position.clone(), run.as_deref().cloned());
+            }
+            BusEvent::Protocols {
+                position,
+                protocols,
+            } => self.update_protocols(position, protocols.clone()),
+            BusEvent::DutyTime {
+                position,
+                duty_time,
+            } => self.update_duty_time(position, duty_time.clone()),
+            BusEvent::ChannelStates { position, states } => {
+                self.update_channel_states(position, states.clone())
+            }
+            BusEvent::ChannelLayout { position, layout } => {
+                self.update_channel_layout(position, layout.clone())
+            }
+            BusEvent::QScoreHistogram {
+                position,
+                histogram,
+            } => {
+                self.qscore_histograms
+                    .insert(position.clone(), histogram.clone());
+            }
+            BusEvent::MuxScans { position, scans } => {
+                self.mux_scans.insert(position.clone(), scans.clone());
+            }
+            BusEvent::TranslocationSpeed { position, points } => {
+                self.translocation_speed
+                    .insert(position.clone(), points.clone());
+            }
+            BusEvent::RunUntilTargets { position, targets } => {
+                self.run_until_targets.insert(position.clone(), *targets);
+            }
+            BusEvent::RunUntilProgress { position, progress } => {
+                self.run_until_progress.insert(position.clone(), *progress);
+            }
+            BusEvent::Signal { position, chunk } => self.update_signal(position, chunk),
+            BusEvent::Alert { position, message } => {
+                self.log_error(position.as_deref(), message);
+                self.push_toast(ToastLevel::Error, message.clone());
+            }
+        }
+
+        if matches!(
+            event,
+            BusEvent::RunStateChanged { .. } | BusEvent::Stats { .. } | BusEvent::Throughput { .. }
+        ) {
+            self.sort_positions();
+        }
+    }
+
+    pub fn update_flow_cell_info(&mut self, position_name: &str, info: FlowCellInfo) {
+        self.flow_cell_info.insert(position_name.to_string(), info);
+    }
+
+    pub fn update_disk_space(&mut self, position_name: &str, info: DiskSpaceInfo) {
+        self.disk_space.insert(position_name.to_string(), info);
+    }
+
+    /// Returns the position's disk usage if it is below the warning threshold.
+    pub fn low_disk_space(&self, position_name: &str) -> Option<&DiskSpaceInfo> {
+        self.disk_space
+            .get(position_name)
+            .filter(|disk| disk.is_low(self.config.tui.disk_warning_percent))
+    }
+
+    pub fn update_channel_states(&mut self, position_name: &str, states: ChannelStatesSnapshot) {
+        self.channel_occupancy
+            .entry(position_name.to_string())
+            .or_default()
+            .record(&states);
+        self.channel_timelapse
+            .entry(position_name.to_string())
+            .or_default()
+            .record(&states, chrono::Utc::now());
+        self.channel_states
+            .insert(position_name.to_string(), states);
+    }
+
+    pub fn update_channel_layout(&mut self, position_name: &str, layout: ChannelLayout) {
+        self.channel_layouts
+            .insert(position_name.to_string(), layout);
+    }
+
+    pub fn update_signal(&mut self, position_name: &str, chunk: &SignalChunk) {
+        self.signal
+            .entry(position_name.to_string())
+            .or_default()
+            .push(chunk);
+    }
+
+    /// Samples of the signal channel received for `position_name`, or
+    /// `None` before the first arrives.
+    pub fn signal_trace(&self, position_name: &str) -> Option<&VecDeque<f32>> {
+        self.signal
+            .get(position_name)
+            .filter(|trace| trace.channel == self.signal_channel && !trace.samples.is_empty())
+            .map(|trace| &trace.samples)
+    }
+
+    /// Moves the signal chart to a neighbouring channel, staying within
+    /// the detail position's channel count once it is known.
+    pub fn step_signal_channel(&mut self, delta: i64) {
+        let position = match self.screen {
+            Screen::PositionDetail { position_idx } => self.positions.get(position_idx),
+            _ => None,
+        };
+        let channel_count = position.and_then(|position| {
+            self.channel_layouts
+                .get(&position.name)
+                .map(|layout| layout.channel_count)
+                .or_else(|| {
+                    self.channel_states
+                        .get(&position.name)
+                        .map(|states| states.states.len())
+                })
+        });
+        let max = channel_count.map_or(u32::MAX as i64, |count| count.max(1) as i64);
+        self.signal_channel = (self.signal_channel as i64 + delta).clamp(1, max) as u32;
+    }
+
+    pub fn select_next(&mut self) {
+        if let Screen::RunHistory { .. } = self.screen {
+            let count = self.history_runs().map_or(0, |runs| runs.len());
+            if count > 0 {
+                self.history_selected = (self.history_selected + 1) % count;
+            }
+            return;
+        }
+        self.step_selection(true);
+    }
+
+    pub fn select_previous(&mut self) {
+        if let Screen::RunHistory { .. } = self.screen {
+            let count = self.history_runs().map_or(0, |runs| runs.len());
+            if count > 0 {
+                self.history_selected = self.history_selected.checked_sub(1).unwrap_or(count - 1);
+            }
+            return;
+        }
+        self.step_selection(false);
+    }
+
+    /// Moves the position selection one row through the filtered table,
+    /// wrapping at either end.
+    fn step_selection(&mut self, forward: bool) {
+        let visible = self.visible_positions();
+        if visible.is_empty() {
+            return;
+        }
+        let selected = self.selected_index();
+        let row = match visible.iter().position(|&idx| Some(idx) == selected) {
+            Some(row) if forward => (row + 1) % visible.len(),
+            Some(row) => row.checked_sub(1).unwrap_or(visible.len() - 1),
+            None => 0,
+        };
+        self.select_position(visible[row]);
+        self.reset_channel_map_scroll();
+    }
+
+    /// Selects a row of the table on the current screen, ignoring rows past
+    /// the end.
+    pub fn select_row(&mut self, row: usize) {
+        if let Screen::RunHistory { .. } = self.screen {
+            if row < self.history_runs().map_or(0, |runs| runs.len()) {
+                self.history_selected = row;
+            }
+            return;
+        }
+        if let Some(&idx) = self.visible_positions().get(row) {
+            if Some(idx) != self.selected_index() {
+                self.select_position(idx);
+                self.reset_channel_map_scroll();
+            }
+        }
+    }
+
+    pub fn enter_detail(&mut self) {
+        if let Screen::RunHistory { position_idx } = self.screen {
+            if self.history_selected < self.history_runs().map_or(0, |runs| runs.len()) {
+                self.screen = Screen::RunHistoryDetail {
+                    position_idx,
+                    run_idx: self.history_selected,
+                };
+            }
+            return;
+        }
+        // Idle positions open on their last finished run.
+        if let (Some(_), Some(position_idx)) = (self.selected_position(), self.selected_index()) {
+            self.screen = Screen::PositionDetail { position_idx };
+        }
+    }
+
+    /// Moves the detail view to the next or previous position with an
+    /// active run, in overview order, keeping the chart tab.
+    pub fn step_detail_position(&mut self, forward: bool) {
+        let Screen::PositionDetail { position_idx } = self.screen else {
+            return;
+        };
+        let active: Vec<usize> = self
+            .visible_positions()
+            .into_iter()
+            .filter(|&idx| {
+                self.run_states
+                    .get(&self.positions[idx].name)
+                    .is_some_and(|s| s.is_active())
+            })
+            .collect();
+        let next = match active.iter().position(|&idx| idx == position_idx) {
+            Some(row) if forward => active.get((row + 1) % active.len()),
+            Some(row) => active.get(row.checked_sub(1).unwrap_or(active.len() - 1)),
+            None if forward => active.first(),
+            None => active.last(),
+        };
+        if let Some(&idx) = next.filter(|&&idx| idx != position_idx) {
+            self.screen = Screen::PositionDetail { position_idx: idx };
+            self.select_position(idx);
+            self.reset_channel_map_scroll();
         }
     }
 
     pub fn back(&mut self) {
+        if self.overlay == Overlay::None
+            && (self.channel_cursor.take().is_some() || self.chart_cursor.take().is_some())
+        {
+            return;
+        }
         match self.overlay {
+            Overlay::None if self.screen == Screen::Overview => self.position_filter.clear(),
             Overlay::None => {
-                if self.screen != Screen::Overview {
-                    self.screen = Screen::Overview;
-                }
+                self.screen = match self.screen {
+                    Screen::RunHistoryDetail { position_idx, .. } => {
+                        Screen::RunHistory { position_idx }
+                    }
+                    _ => Screen::Overview,
+                };
             }
             _ => {
                 self.overlay = Overlay::None;
@@ -234,6 +1476,26 @@ impl App {
         }
     }
 
+    /// Opens the run history of the selected position.
+    pub fn open_run_history(&mut self) {
+        if let (Some(_), Some(position_idx)) = (self.selected_position(), self.selected_index()) {
+            self.screen = Screen::RunHistory { position_idx };
+            self.history_selected = 0;
+        }
+    }
+
+    /// Returns the runs listed on the run history screen, if loaded.
+    pub fn history_runs(&self) -> Option<&[RunSummary]> {
+        let position_idx = match self.screen {
+            Screen::RunHistory { position_idx } | Screen::RunHistoryDetail { position_idx, .. } => {
+                position_idx
+            }
+            _ => return None,
+        };
+        let position = self.positions.get(position_idx)?;
+        self.run_history.get(&position.name).map(Vec::as_slice)
+    }
+
     pub fn toggle_help(&mut self) {
         self.overlay = match self.overlay {
             Overlay::Help => Overlay::None,
@@ -241,10 +1503,57 @@ impl App {
         };
     }
 
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.dirty = true;
+    }
+
+    /// Whether drawing and view-specific fetches are paused because the
+    /// terminal lost focus; see `tui.pause_unfocused`.
+    pub fn is_paused(&self) -> bool {
+        !self.focused && self.config.tui.pause_unfocused
+    }
+
+    /// Types pasted text into the field being edited, if any. Line breaks
+    /// and other control characters are dropped, so a paste never confirms
+    /// a dialog; outside text fields pastes are ignored rather than run as
+    /// key commands.
+    pub fn paste(&mut self, text: &str) {
+        use crossterm::event::KeyCode;
+
+        for c in text.chars().filter(|c| !c.is_control()) {
+            let key = KeyCode::Char(c);
+            match &self.overlay {
+                Overlay::Filter => self.handle_filter_key(key),
+                Overlay::Note { .. } => {
+                    self.handle_note_key(key);
+                }
+                Overlay::RangeInput { .. } => self.handle_range_input_key(key),
+                Overlay::RunUntil(_) => {
+                    self.handle_run_until_key(key);
+                }
+                Overlay::Confirmation { .. } => {
+                    self.handle_confirmation_key(key);
+                }
+                Overlay::StartProtocol(wizard) if wizard.step == StartStep::Details => {
+                    self.handle_start_wizard_key(key);
+                }
+                _ => return,
+            }
+        }
+    }
+
+    pub fn toggle_debug_overlay(&mut self) {
+        self.overlay = match self.overlay {
+            Overlay::Debug => Overlay::None,
+            _ => Overlay::Debug,
+        };
+    }
+
     pub fn open_theme_selector(&mut self) {
         let current_idx = Theme::available_themes()
             .iter()
-            .position(|&name| Theme::by_name(name).map(|t| t.name) == Some(self.theme.name))
+            .position(|name| Theme::by_name(name).is_some_and(|t| t.name == self.theme.name))
             .unwrap_or(0);
         self.overlay = Overlay::ThemeSelector {
             selected: current_idx,
@@ -268,10 +1577,10 @@ impl App {
     pub fn apply_selected_theme(&mut self) {
         if let Overlay::ThemeSelector { selected } = &self.overlay {
             let themes = Theme::available_themes();
-            if let Some(&name) = themes.get(*selected) {
+            if let Some(name) = themes.get(*selected) {
                 if let Some(theme) = Theme::by_name(name) {
                     self.theme = theme;
-                    if let Err(e) = Config::save_theme(name) {
+                    if let Err(e) = self.config.save_theme(name) {
                         tracing::warn!(error = %e, "Failed to save theme preference");
                     }
                 }
@@ -284,441 +1593,2813 @@ impl App {
         self.should_quit = true;
     }
 
+    /// Reports a failure as an error toast without interrupting the user.
     pub fn set_error(&mut self, message: String) {
-        self.last_error = Some(message.clone());
-        self.overlay = Overlay::Error { message };
+        self.log_error(None, &message);
+        self.push_toast(ToastLevel::Error, message);
     }
 
-    pub fn set_connected(&mut self) {
-        self.connection = ConnectionState::Connected;
+    /// Reports a failure in a modal dialog that must be dismissed. Reserved
+    /// for problems the user has to act on; use [`App::set_error`] otherwise.
+    pub fn set_fatal_error(&mut self, message: String) {
+        self.log_error(None, &message);
+        self.overlay = Overlay::Error { message };
     }
 
-    pub fn set_disconnected(&mut self, reason: String) {
-        self.connection = ConnectionState::Disconnected {
-            since: Instant::now(),
-            reason,
-        };
+    /// Adds an error to the error log. The same error as the latest only
+    /// counts again, so a failure repeated every poll takes one entry.
+    pub fn log_error(&mut self, position: Option<&str>, message: &str) {
+        let at = chrono::Utc::now();
+        if let Some(last) = self.errors.back_mut() {
+            if last.position.as_deref() == position && last.message == message {
+                last.at = at;
+                last.count += 1;
+                return;
+            }
+        }
+        if self.errors.len() >= MAX_ERROR_LOG {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(LoggedError {
+            at,
+            position: position.map(str::to_string),
+            message: message.to_string(),
+            count: 1,
+        });
     }
 
-    pub fn set_reconnecting(&mut self, attempt: u32) {
-        self.connection = ConnectionState::Reconnecting { attempt };
+    pub fn toggle_error_log(&mut self) {
+        self.overlay = match self.overlay {
+            Overlay::ErrorLog { .. } => Overlay::None,
+            _ => Overlay::ErrorLog { scroll: 0 },
+        };
     }
 
-    pub fn update_positions(&mut self, positions: Vec<Position>) {
-        self.positions = positions;
-        if self.selected_position >= self.positions.len() && !self.positions.is_empty() {
-            self.selected_position = self.positions.len() - 1;
+    /// Scrolls the error log by `delta` entries, towards older ones when
+    /// positive.
+    pub fn scroll_error_log(&mut self, delta: isize) {
+        let last = self.errors.len().saturating_sub(1);
+        if let Overlay::ErrorLog { scroll } = &mut self.overlay {
+            *scroll = scroll.saturating_add_signed(delta).min(last);
         }
     }
 
-    pub fn update_stats(&mut self, position_name: &str, mut stats: StatsSnapshot) {
-        // Preserve existing throughput values (calculated separately from yield history)
-        if let Some(existing) = self.stats_cache.get(position_name) {
-            stats.throughput_bps = existing.throughput_bps;
-            stats.throughput_gbph = existing.throughput_gbph;
+    /// Re-checks the alert rules watching `metrics` against a position's
+    /// stats. Rules are only checked while the run is sequencing; newly
+    /// breached rules are logged and shown as a toast.
+    pub fn evaluate_alerts(&mut self, position_name: &str, metrics: &[AlertMetric]) {
+        if self.config.alerts.rules.is_empty()
+            || self.run_states.get(position_name) != Some(&RunState::Running)
+        {
+            return;
         }
-        self.stats_cache.insert(position_name.to_string(), stats);
-    }
+        let Some(stats) = self.stats_cache.get(position_name) else {
+            return;
+        };
+        let breaches = alerts::evaluate(&self.config.alerts.rules, metrics, stats);
 
-    pub fn update_run_state(&mut self, position_name: &str, state: RunState) {
-        if !state.is_active() {
-            self.stats_cache.remove(position_name);
-            self.yield_history.remove(position_name);
-            self.histograms.remove(position_name);
-            self.duty_time.remove(position_name);
-            self.channel_states.remove(position_name);
-            self.run_info.remove(position_name);
+        let current = self.alerts.entry(position_name.to_string()).or_default();
+        let (rechecked, kept): (Vec<Breach>, Vec<Breach>) = current
+            .drain(..)
+            .partition(|b| metrics.contains(&b.rule.metric));
+        let mut new_breaches = Vec::new();
+        for breach in &breaches {
+            if !rechecked.iter().any(|b| b.rule == breach.rule) {
+                tracing::warn!(
+                    position = %position_name,
+                    rule = %breach.rule,
+                    value = breach.value,
+                    "Alert threshold breached"
+                );
+                new_breaches.push(breach.summary());
+            }
+        }
+        for cleared in rechecked
+            .iter()
+            .filter(|b| !breaches.iter().any(|n| n.rule == b.rule))
+        {
+            tracing::info!(position = %position_name, rule = %cleared.rule, "Alert cleared");
+        }
+        *current = kept;
+        current.extend(breaches);
+        if current.is_empty() {
+            self.alerts.remove(position_name);
         }
-        self.run_states.insert(position_name.to_string(), state);
-    }
 
-    pub fn update_run_info(&mut self, position_name: &str, info: RunInfo) {
-        self.run_info.insert(position_name.to_string(), info);
+        for summary in new_breaches {
+            self.push_toast(
+                ToastLevel::Warning,
+                format!("⚠ {}: {}", position_name, summary),
+            );
+        }
     }
 
-    pub fn get_run_state(&self, position_name: &str) -> Option<&RunState> {
-        self.run_states.get(position_name)
+    /// Breached alert rules on a position, if any.
+    pub fn position_alerts(&self, position_name: &str) -> &[Breach] {
+        self.alerts.get(position_name).map_or(&[], |b| b.as_slice())
     }
 
-    pub fn selected_position(&self) -> Option<&Position> {
-        self.positions.get(self.selected_position)
+    pub fn push_toast(&mut self, level: ToastLevel, message: String) {
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            level,
+            message,
+            created: Instant::now(),
+        });
     }
 
-    pub fn is_connected(&self) -> bool {
-        matches!(self.connection, ConnectionState::Connected)
+    fn stat_values(&self, position_name: &str) -> Option<[f64; 5]> {
+        let stats = self.stats_cache.get(position_name)?;
+        Some(StatValue::ALL.map(|value| value.of(stats)))
     }
 
-    pub fn cycle_detail_chart(&mut self) {
-        self.detail_chart = match self.detail_chart {
-            DetailChart::Yield => DetailChart::ReadLength,
-            DetailChart::ReadLength => DetailChart::PoreActivity,
-            DetailChart::PoreActivity => DetailChart::Yield,
+    /// Starts highlighting the stat values of a position that differ from
+    /// `before`, its values ahead of an update. Nothing is highlighted on a
+    /// position's first stats.
+    fn note_stat_changes(&mut self, position_name: &str, before: Option<[f64; 5]>) {
+        if !self.config.tui.highlight_changes {
+            return;
+        }
+        let (Some(before), Some(after)) = (before, self.stat_values(position_name)) else {
+            return;
         };
-        if self.detail_chart != DetailChart::PoreActivity {
-            self.reset_channel_map_scroll();
+        let now = Instant::now();
+        for (value, (old, new)) in StatValue::ALL
+            .into_iter()
+            .zip(before.into_iter().zip(after))
+        {
+            if old != new {
+                self.stat_changes
+                    .entry(position_name.to_string())
+                    .or_default()
+                    .insert(value, now);
+            }
         }
     }
 
-    pub fn set_detail_chart(&mut self, chart: DetailChart) {
-        self.detail_chart = chart;
-        if chart != DetailChart::PoreActivity {
-            self.reset_channel_map_scroll();
-        }
+    /// Whether a position's stat value changed within the last
+    /// [`CHANGE_HIGHLIGHT`].
+    pub fn stat_changed(&self, position_name: &str, value: StatValue) -> bool {
+        self.stat_changes
+            .get(position_name)
+            .and_then(|changes| changes.get(&value))
+            .is_some_and(|changed| changed.elapsed() < CHANGE_HIGHLIGHT)
     }
 
-    pub fn toggle_yield_unit(&mut self) {
-        self.yield_unit = match self.yield_unit {
-            YieldUnit::Bases => YieldUnit::Reads,
-            YieldUnit::Reads => YieldUnit::Bases,
-        };
-        tracing::debug!(new_unit = ?self.yield_unit, "Toggled yield unit");
+    /// Drops highlights of changed values that have been shown long enough.
+    pub fn expire_stat_changes(&mut self) {
+        let mut expired = false;
+        self.stat_changes.retain(|_, changes| {
+            let before = changes.len();
+            changes.retain(|_, changed| changed.elapsed() < CHANGE_HIGHLIGHT);
+            expired |= changes.len() != before;
+            !changes.is_empty()
+        });
+        if expired {
+            self.dirty = true;
+        }
     }
 
-    pub fn toggle_outliers(&mut self) {
-        self.exclude_outliers = !self.exclude_outliers;
-        tracing::info!(exclude_outliers = self.exclude_outliers, "Toggled outliers");
+    /// Drops toasts that have been shown long enough.
+    pub fn expire_toasts(&mut self) {
+        let before = self.toasts.len();
+        self.toasts
+            .retain(|toast| toast.created.elapsed() < toast.level.duration());
+        if self.toasts.len() != before {
+            self.dirty = true;
+        }
     }
 
-    pub fn set_histogram_range(&mut self, min: u64, max: u64) {
-        if min < max {
-            self.histogram_range = Some((min, max));
-            tracing::info!(min, max, "Set histogram range");
+    pub fn set_connected(&mut self) {
+        self.connection = ConnectionState::Connected;
+        self.connection_error_class = None;
+        // A new connection loads the authentication token again.
+        if self.hosts.is_empty() {
+            self.read_only = None;
         }
     }
 
-    pub fn clear_histogram_range(&mut self) {
-        self.histogram_range = None;
+    /// Records the manager connected to, warning once per MinKNOW version if
+    /// Termion may not support it.
+    fn set_server(&mut self, info: ServerInfo) {
+        let known = self.server.as_ref().and_then(|s| s.version.as_ref());
+        if let Some(version) = info.version.as_ref().filter(|&v| known != Some(v)) {
+            if let Some(warning) = version_warning(version) {
+                self.push_toast(ToastLevel::Warning, warning);
+            }
+        }
+        self.server = Some(info);
     }
 
-    pub fn has_histogram_range(&self) -> bool {
-        self.histogram_range.is_some()
+    /// Disables run control after MinKNOW refused a request, closing any
+    /// control overlay that can no longer succeed.
+    fn set_read_only(&mut self, reason: String) {
+        if matches!(
+            self.overlay,
+            Overlay::Confirmation { .. } | Overlay::StartProtocol(_) | Overlay::RunUntil(_)
+        ) {
+            self.overlay = Overlay::None;
+        }
+        if self.read_only.is_none() {
+            self.push_toast(
+                ToastLevel::Warning,
+                "Read-only: MinKNOW refused run control (? for details)".into(),
+            );
+        }
+        self.read_only = Some(reason);
     }
 
-    pub fn open_range_input(&mut self) {
-        if self.detail_chart != DetailChart::ReadLength {
-            return;
+    /// Whether run control is disabled, telling the user so if it is.
+    fn refuse_read_only(&mut self) -> bool {
+        if self.read_only.is_some() {
+            self.push_toast(
+                ToastLevel::Warning,
+                "Read-only: run control is disabled (? for details)".into(),
+            );
         }
+        self.read_only.is_some()
+    }
 
-        let max_str = match self.histogram_range {
-            Some((_, max)) => max.to_string(),
-            None => String::new(),
-        };
+    /// Marks the connection as failed, recording the error category so the
+    /// UI can offer targeted troubleshooting steps.
+    pub fn set_connection_failed(&mut self, err: &ClientError) {
+        self.connection_error_class = Some(ErrorClass::classify(err));
+        self.set_disconnected(err.display_message());
+    }
 
-        self.overlay = Overlay::RangeInput { max_input: max_str };
+    pub fn show_diagnostics(&mut self, results: Vec<CheckResult>) {
+        self.overlay = Overlay::Diagnostics { results };
     }
 
-    pub fn request_run_control(&mut self, action: RunControlAction) {
-        let Some(pos) = self.selected_position() else {
-            return;
+    pub fn set_disconnected(&mut self, reason: String) {
+        self.connection = ConnectionState::Disconnected {
+            since: Instant::now(),
+            reason,
+            next_attempt: None,
         };
+        self.latency = None;
+    }
 
-        let run_state = self.run_states.get(&pos.name);
-
-        let is_valid = match action {
-            RunControlAction::Pause => matches!(run_state, Some(RunState::Running)),
-            RunControlAction::Resume => matches!(run_state, Some(RunState::Paused)),
-            RunControlAction::Stop => {
-                matches!(run_state, Some(RunState::Running | RunState::MuxScanning))
-            }
+    pub fn set_reconnecting(&mut self, attempt: u32) {
+        let reason = match &mut self.connection {
+            ConnectionState::Disconnected { reason, .. }
+            | ConnectionState::Reconnecting { reason, .. } => std::mem::take(reason),
+            _ => String::new(),
         };
+        self.connection = ConnectionState::Reconnecting { attempt, reason };
+    }
 
-        if is_valid {
-            self.overlay = Overlay::Confirmation {
-                action,
-                position_name: pos.name.clone(),
-            };
+    /// Notes when the next automatic attempt starts, for the countdown in
+    /// the connection banner.
+    pub fn schedule_reconnect(&mut self, attempt: u32, delay: Duration) {
+        if let ConnectionState::Disconnected { next_attempt, .. } = &mut self.connection {
+            *next_attempt = Some((attempt, Instant::now() + delay));
         }
     }
 
-    pub fn pending_confirmation(&self) -> Option<(RunControlAction, String)> {
-        if let Overlay::Confirmation {
-            action,
-            position_name,
-        } = &self.overlay
-        {
-            Some((*action, position_name.clone()))
-        } else {
-            None
+    /// Whether a reconnection countdown is on screen, so it is redrawn
+    /// every tick.
+    pub fn awaiting_reconnect(&self) -> bool {
+        self.hosts.is_empty()
+            && matches!(
+                self.connection,
+                ConnectionState::Disconnected {
+                    next_attempt: Some(_),
+                    ..
+                }
+            )
+    }
+
+    /// Records the connection state of one of several watched hosts. The
+    /// overall state is connected while any host is, and disconnected once
+    /// every host has failed.
+    fn set_host_connection(&mut self, host: &str, state: ConnectionState) {
+        let Some((_, current)) = self.hosts.iter_mut().find(|(label, _)| label == host) else {
+            return;
+        };
+        let previous = std::mem::replace(current, state);
+        match (&previous, &current) {
+            (ConnectionState::Disconnected { .. }, ConnectionState::Connected) => {
+                self.push_toast(ToastLevel::Info, format!("Reconnected to {}", host));
+            }
+            (ConnectionState::Connected, ConnectionState::Disconnected { reason, .. }) => {
+                let message = format!("Lost connection to {}: {}", host, reason);
+                self.log_error(None, &message);
+                self.push_toast(ToastLevel::Warning, message);
+            }
+            _ => {}
+        }
+
+        let states = || self.hosts.iter().map(|(_, state)| state);
+        if states().any(|s| matches!(s, ConnectionState::Connected)) {
+            self.set_connected();
+        } else if states().all(|s| matches!(s, ConnectionState::Disconnected { .. })) {
+            self.set_disconnected("No MinKNOW host is reachable".into());
         }
     }
 
-    pub fn clear_confirmation(&mut self) {
-        if matches!(self.overlay, Overlay::Confirmation { .. }) {
-            self.overlay = Overlay::None;
+    /// The connection state of the host `position` belongs to, when several
+    /// hosts are watched.
+    pub fn host_connection(&self, position: &Position) -> Option<&ConnectionState> {
+        let host = position.host.as_deref()?;
+        self.hosts
+            .iter()
+            .find(|(label, _)| label == host)
+            .map(|(_, state)| state)
+    }
+
+    /// Replaces the positions listed for one of several watched hosts,
+    /// keeping those of the other hosts.
+    fn update_host_positions(&mut self, host: &str, positions: &[Position]) {
+        let mut merged: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|p| p.host.as_deref() != Some(host))
+            .cloned()
+            .collect();
+        merged.extend(positions.iter().cloned());
+        self.update_positions(merged);
+    }
+
+    /// Applies positions that appeared, changed or went away, keeping the
+    /// rest.
+    fn change_positions(&mut self, updated: &[Position], removed: &[String]) {
+        let mut positions: Vec<Position> = self
+            .positions
+            .iter()
+            .filter(|p| !removed.contains(&p.name) && !updated.iter().any(|u| u.name == p.name))
+            .cloned()
+            .collect();
+        positions.extend(updated.iter().cloned());
+        self.update_positions(positions);
+    }
+
+    pub fn update_positions(&mut self, positions: Vec<Position>) {
+        self.replace_positions(positions);
+        self.keep_selection_visible();
+    }
+
+    fn position_index(&self, name: &str) -> Option<usize> {
+        self.positions.iter().position(|p| p.name == name)
+    }
+
+    pub fn cycle_overview_sort(&mut self) {
+        self.overview_sort = match self.overview_sort {
+            OverviewSort::Position => OverviewSort::State,
+            OverviewSort::State => OverviewSort::Yield,
+            OverviewSort::Yield => OverviewSort::Throughput,
+            OverviewSort::Throughput => OverviewSort::PassRate,
+            OverviewSort::PassRate => OverviewSort::Position,
+        };
+        self.sort_positions();
+    }
+
+    pub fn reverse_overview_sort(&mut self) {
+        self.overview_sort_reversed = !self.overview_sort_reversed;
+        self.sort_positions();
+    }
+
+    pub fn is_pinned(&self, position_name: &str) -> bool {
+        self.pinned.iter().any(|name| name == position_name)
+    }
+
+    /// Pins or unpins the selected position. Returns whether anything
+    /// changed, so the caller can save the new list.
+    pub fn toggle_pin(&mut self) -> bool {
+        if self.screen != Screen::Overview {
+            return false;
         }
+        let Some(name) = self.selected_position().map(|p| p.name.clone()) else {
+            return false;
+        };
+        let message = if self.is_pinned(&name) {
+            self.pinned.retain(|pinned| *pinned != name);
+            format!("Unpinned {}", name)
+        } else {
+            self.pinned.push(name.clone());
+            format!("Pinned {}", name)
+        };
+        self.push_toast(ToastLevel::Info, message);
+        self.sort_positions();
+        true
     }
 
-    pub fn apply_range_input(&mut self) -> bool {
-        if let Overlay::RangeInput { ref max_input, .. } = self.overlay {
-            let max_input = max_input.clone();
+    fn sort_positions(&mut self) {
+        self.replace_positions(self.positions.clone());
+    }
 
-            if max_input.is_empty() {
-                self.histogram_range = None;
-                self.overlay = Overlay::None;
-                return true;
+    /// Stores `positions` in overview sort order, keeping the selection and
+    /// the open screen on the same positions. Ties fall back to the position
+    /// name so rows do not shuffle between refreshes.
+    fn replace_positions(&mut self, mut positions: Vec<Position>) {
+        let name_at = |app: &Self, idx: usize| app.positions.get(idx).map(|p| p.name.clone());
+        let viewed = match self.screen {
+            Screen::Overview => None,
+            Screen::PositionDetail { position_idx }
+            | Screen::RunHistory { position_idx }
+            | Screen::RunHistoryDetail { position_idx, .. } => name_at(self, position_idx),
+        };
+
+        positions.sort_by(|a, b| {
+            let pinned = self.is_pinned(&b.name).cmp(&self.is_pinned(&a.name));
+            pinned
+                .then_with(|| self.compare_positions(a, b))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        self.positions = positions;
+
+        match viewed.map(|name| self.position_index(&name)) {
+            None => {}
+            Some(Some(idx)) => match &mut self.screen {
+                Screen::Overview => {}
+                Screen::PositionDetail { position_idx }
+                | Screen::RunHistory { position_idx }
+                | Screen::RunHistoryDetail { position_idx, .. } => *position_idx = idx,
+            },
+            // The viewed position went away; showing whichever took its row
+            // would be misleading.
+            Some(None) => {
+                self.screen = Screen::Overview;
+                self.channel_cursor = None;
+            }
+        }
+    }
+
+    fn compare_positions(&self, a: &Position, b: &Position) -> Ordering {
+        let directed = |ord: Ordering| {
+            if self.overview_sort_reversed {
+                ord.reverse()
+            } else {
+                ord
+            }
+        };
+        let metric = |pos: &Position| -> Option<f64> {
+            let stats = self
+                .stats_cache
+                .get(&pos.name)
+                .filter(|_| self.get_run_state(&pos.name).is_some_and(|s| s.is_active()))?;
+            match self.overview_sort {
+                OverviewSort::Yield => Some(stats.bases_called as f64),
+                OverviewSort::Throughput => Some(stats.throughput_gbph),
+                OverviewSort::PassRate if stats.reads_passed + stats.reads_failed > 0 => {
+                    Some(stats.pass_rate())
+                }
+                _ => None,
             }
+        };
 
-            if let Ok(max_val) = max_input.parse::<u64>() {
-                if max_val > 0 {
-                    self.histogram_range = Some((0, max_val));
-                    self.overlay = Overlay::None;
-                    tracing::info!(max = max_val, "Applied histogram max range");
-                    return true;
+        match self.overview_sort {
+            OverviewSort::Position => directed(a.name.cmp(&b.name)),
+            OverviewSort::State => directed(self.state_rank(a).cmp(&self.state_rank(b))),
+            // Highest first; missing values last regardless of direction.
+            OverviewSort::Yield | OverviewSort::Throughput | OverviewSort::PassRate => {
+                match (metric(a), metric(b)) {
+                    (Some(x), Some(y)) => directed(y.total_cmp(&x)),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
                 }
             }
-            tracing::warn!("Invalid range input");
+        }
+    }
+
+    /// Sort rank for the state column: problems first, idle last.
+    fn state_rank(&self, pos: &Position) -> u8 {
+        match self.get_run_state(&pos.name) {
+            Some(RunState::Error(_)) => 0,
+            _ if pos.state == PositionState::Error => 0,
+            Some(RunState::Paused) => 1,
+            Some(RunState::Running) => 2,
+            Some(RunState::MuxScanning) => 3,
+            Some(RunState::Starting) => 4,
+            Some(RunState::Finishing) => 5,
+            Some(RunState::Idle) | None => 6,
+        }
+    }
+
+    pub fn update_stats(&mut self, position_name: &str, mut stats: StatsSnapshot) {
+        // Preserve existing throughput values (calculated separately from yield history)
+        if let Some(existing) = self.stats_cache.get(position_name) {
+            stats.throughput_bps = existing.throughput_bps;
+            stats.throughput_gbph = existing.throughput_gbph;
+        }
+        self.stats_cache.insert(position_name.to_string(), stats);
+    }
+
+    pub fn update_run_state(&mut self, position_name: &str, state: RunState) {
+        if let RunState::Error(message) = &state {
+            if self.run_states.get(position_name) != Some(&state) {
+                self.log_error(Some(position_name), message);
+            }
+        }
+        // Pauses and pore scans depress throughput and pore counts by design.
+        if state != RunState::Running {
+            self.alerts.remove(position_name);
+        }
+        if !state.is_active() {
+            self.stats_cache.remove(position_name);
+            self.stat_changes.remove(position_name);
+            self.yield_history.remove(position_name);
+            self.barcode_yield.remove(position_name);
+            self.histograms.remove(position_name);
+            self.qscore_histograms.remove(position_name);
+            self.mux_scans.remove(position_name);
+            self.translocation_speed.remove(position_name);
+            self.run_until_targets.remove(position_name);
+            self.run_until_progress.remove(position_name);
+            self.pore_history.remove(position_name);
+            self.throughput_history.remove(position_name);
+            self.signal.remove(position_name);
+            self.duty_time.remove(position_name);
+            self.duty_history.remove(position_name);
+            self.channel_states.remove(position_name);
+            self.channel_occupancy.remove(position_name);
+            self.channel_timelapse.remove(position_name);
+            self.run_info.remove(position_name);
+            self.phase_history.remove(position_name);
+            self.output.remove(position_name);
+        } else {
+            self.last_runs.remove(position_name);
+        }
+        self.run_states.insert(position_name.to_string(), state);
+    }
+
+    /// Adds an active pore sample taken `seconds` after the app started.
+    /// Samples covering `tui.chart_history` are kept, thinned out for long
+    /// histories; the oldest are dropped first.
+    pub fn record_active_pores(&mut self, position_name: &str, count: u32, seconds: f64) {
+        let refresh = self.config.tui.refresh_interval.as_secs_f64().max(0.1);
+        let span = self.config.tui.chart_history.as_secs_f64();
+        self.pore_history
+            .entry(position_name.to_string())
+            .or_insert_with(|| ChartBuffer::spanning(span, refresh))
+            .push(seconds, count as f64);
+    }
+
+    /// Adds a throughput sample, in Gb/h, to the position's recent history
+    /// for the overview's trend column.
+    pub fn record_throughput(&mut self, position_name: &str, gbph: f64, seconds: f64) {
+        let refresh = self.config.tui.refresh_interval.as_secs_f64().max(0.1);
+        let span = self.config.tui.chart_history.as_secs_f64();
+        self.throughput_history
+            .entry(position_name.to_string())
+            .or_insert_with(|| ChartBuffer::spanning(span, refresh))
+            .push(seconds, gbph);
+    }
+
+    /// Median translocation speed over the latest stretch of the run.
+    pub fn current_speed(&self, position_name: &str) -> Option<f32> {
+        self.translocation_speed
+            .get(position_name)?
+            .last()
+            .map(|point| point.median)
+    }
+
+    /// Values the position's run has reached for each run-until criterion.
+    /// Those the run-until script has not reported are taken from the yield
+    /// history and statistics.
+    pub fn run_until_reached(&self, position_name: &str) -> RunUntilCriteria {
+        let reported = self
+            .run_until_progress
+            .get(position_name)
+            .copied()
+            .unwrap_or_default();
+        let latest = self.yield_history.get(position_name).and_then(|p| p.last());
+        RunUntilCriteria {
+            runtime: reported.runtime.or(latest.map(|p| p.seconds as u64)),
+            estimated_bases: reported.estimated_bases.or(latest.map(|p| p.bases)),
+            passed_basecalled_bases: reported
+                .passed_basecalled_bases
+                .or(self.stats_cache.get(position_name).map(|s| s.bases_passed)),
+            available_pores: reported.available_pores,
+        }
+    }
+
+    /// How far, from 0 to 1, the position's run is towards the runtime or
+    /// yield target it will meet first. `None` without such targets.
+    pub fn run_until_fraction(&self, position_name: &str) -> Option<f64> {
+        let targets = self.run_until_targets.get(position_name)?;
+        let reached = self.run_until_reached(position_name);
+        let fraction = |target: Option<u64>, value: Option<u64>| {
+            let target = target.filter(|&t| t > 0)?;
+            Some((value.unwrap_or(0) as f64 / target as f64).min(1.0))
+        };
+        [
+            fraction(targets.runtime, reached.runtime),
+            fraction(targets.estimated_bases, reached.estimated_bases),
+            fraction(
+                targets.passed_basecalled_bases,
+                reached.passed_basecalled_bases,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .max_by(f64::total_cmp)
+    }
+
+    /// The most recent pore scan of the position's current run.
+    pub fn latest_mux_scan(&self, position_name: &str) -> Option<&MuxScanResult> {
+        self.mux_scans.get(position_name)?.last()
+    }
+
+    pub fn update_run_info(&mut self, position_name: &str, info: RunInfo) {
+        self.run_info.insert(position_name.to_string(), info);
+    }
+
+    pub fn get_run_state(&self, position_name: &str) -> Option<&RunState> {
+        self.run_states.get(position_name)
+    }
+
+    /// Returns the selected position, unless the filter hides it.
+    pub fn selected_position(&self) -> Option<&Position> {
+        self.positions
+            .get(self.selected_index()?)
+            .filter(|pos| self.matches_filter(pos))
+    }
+
+    /// Returns the row of the selected position, if it is still listed.
+    /// Until a position is selected, the first visible row is.
+    pub fn selected_index(&self) -> Option<usize> {
+        match &self.selected {
+            Some(name) => self.position_index(name),
+            None => self.visible_positions().first().copied(),
+        }
+    }
+
+    /// Selects the position at `idx`.
+    pub fn select_position(&mut self, idx: usize) {
+        if let Some(pos) = self.positions.get(idx) {
+            self.selected = Some(pos.name.clone());
+        }
+    }
+
+    /// Returns whether a position passes the overview filter and is not
+    /// hidden.
+    pub fn matches_filter(&self, pos: &Position) -> bool {
+        if self.is_hidden(pos) {
             return false;
         }
-        false
+        let filter = self.position_filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+        let flow_cell = self
+            .flow_cell_info
+            .get(&pos.name)
+            .and_then(|fc| fc.flow_cell_id.as_deref())
+            .unwrap_or_default();
+        [
+            pos.name.as_str(),
+            pos.device_id.as_str(),
+            pos.device_type.label(),
+            flow_cell,
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&filter))
     }
 
-    pub fn handle_range_input_key(&mut self, key_code: crossterm::event::KeyCode) {
+    /// Whether the hide toggles leave `pos` out of the overview. Pinned
+    /// positions and positions with an error are always shown.
+    pub fn is_hidden(&self, pos: &Position) -> bool {
+        if self.is_pinned(&pos.name) {
+            return false;
+        }
+        let idle = matches!(self.get_run_state(&pos.name), Some(RunState::Idle) | None)
+            && pos.state != PositionState::Error;
+        (self.hide_simulated && pos.is_simulated) || (self.hide_idle && idle)
+    }
+
+    pub fn toggle_hide_simulated(&mut self) {
+        self.hide_simulated = !self.hide_simulated;
+        let message = if self.hide_simulated {
+            "Hiding simulated positions"
+        } else {
+            "Showing simulated positions"
+        };
+        self.push_toast(ToastLevel::Info, message.into());
+        self.keep_selection_visible();
+    }
+
+    pub fn toggle_hide_idle(&mut self) {
+        self.hide_idle = !self.hide_idle;
+        let message = if self.hide_idle {
+            "Hiding idle positions"
+        } else {
+            "Showing idle positions"
+        };
+        self.push_toast(ToastLevel::Info, message.into());
+        self.keep_selection_visible();
+    }
+
+    /// Freezes the display, or unfreezes it and applies the updates held
+    /// back meanwhile.
+    pub fn toggle_freeze(&mut self) {
+        if self.frozen_at.is_some() {
+            let applied = self.unfreeze();
+            self.push_toast(
+                ToastLevel::Info,
+                format!("Live again, {} updates applied", applied),
+            );
+        } else {
+            self.frozen_at = Some(chrono::Utc::now());
+            self.push_toast(ToastLevel::Info, "Display frozen; Space resumes".into());
+        }
+    }
+
+    /// Unfreezes the display and applies the held events in order,
+    /// returning how many there were.
+    fn unfreeze(&mut self) -> usize {
+        self.frozen_at = None;
+        let held = std::mem::take(&mut self.held);
+        for event in &held {
+            self.apply(event);
+        }
+        held.len()
+    }
+
+    pub fn toggle_clock_time(&mut self) {
+        self.clock_time = !self.clock_time;
+        let message = if self.clock_time {
+            "Showing clock times"
+        } else {
+            "Showing run times"
+        };
+        self.push_toast(ToastLevel::Info, message.into());
+    }
+
+    /// Clock time `seconds` into a position's acquisition, which chart time
+    /// axes count from. `None` until the acquisition start is known.
+    pub fn clock_at(
+        &self,
+        position_name: &str,
+        seconds: f64,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        let start = self.stats_cache.get(position_name)?.acquisition_start?;
+        Some(start + chrono::Duration::milliseconds((seconds * 1000.0) as i64))
+    }
+
+    /// Indices into `positions` of the rows shown in the overview table.
+    pub fn visible_positions(&self) -> Vec<usize> {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, pos)| self.matches_filter(pos))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    pub fn open_filter(&mut self) {
+        if self.screen == Screen::Overview {
+            self.overlay = Overlay::Filter;
+        }
+    }
+
+    /// Handles a key while the filter is being edited. The table narrows as
+    /// the user types; Enter keeps the filter and Esc clears it.
+    pub fn handle_filter_key(&mut self, key_code: crossterm::event::KeyCode) {
         use crossterm::event::KeyCode;
 
-        if let Overlay::RangeInput { ref mut max_input } = self.overlay {
-            match key_code {
-                KeyCode::Char(c) if c.is_ascii_digit() => {
-                    max_input.push(c);
-                }
-                KeyCode::Backspace => {
-                    max_input.pop();
-                }
-                KeyCode::Up => {
-                    let current: u64 = max_input.parse().unwrap_or(0);
-                    *max_input = (current + 1000).to_string();
-                }
-                KeyCode::Down => {
-                    let current: u64 = max_input.parse().unwrap_or(0);
-                    *max_input = current.saturating_sub(1000).to_string();
-                }
-                _ => {}
+        match key_code {
+            KeyCode::Char(c) => self.position_filter.push(c),
+            KeyCode::Backspace => {
+                self.position_filter.pop();
+            }
+            KeyCode::Up => return self.select_previous(),
+            KeyCode::Down => return self.select_next(),
+            KeyCode::Enter => {
+                self.overlay = Overlay::None;
+                return;
             }
+            KeyCode::Esc => {
+                self.position_filter.clear();
+                self.overlay = Overlay::None;
+            }
+            _ => return,
         }
+        self.keep_selection_visible();
     }
 
-    pub fn update_yield_history(&mut self, position_name: &str, data: Vec<YieldDataPoint>) {
-        self.yield_history.insert(position_name.to_string(), data);
+    /// Moves the selection to the first visible row if the filter hides it.
+    ///
+    /// A selected position that went away stays selected without a row, so
+    /// the highlight does not land on another flow cell; moving the selection
+    /// picks a row again.
+    fn keep_selection_visible(&mut self) {
+        let removed = self.selected.is_some() && self.selected_index().is_none();
+        if self.selected_position().is_none() && !removed {
+            if let Some(&idx) = self.visible_positions().first() {
+                self.select_position(idx);
+                self.reset_channel_map_scroll();
+            }
+        }
     }
 
-    pub fn update_histogram(&mut self, position_name: &str, histogram: ReadLengthHistogram) {
-        self.histograms.insert(position_name.to_string(), histogram);
+    pub fn is_connected(&self) -> bool {
+        matches!(self.connection, ConnectionState::Connected)
     }
 
-    pub fn update_duty_time(&mut self, position_name: &str, duty_time: DutyTimeSnapshot) {
-        self.duty_time.insert(position_name.to_string(), duty_time);
+    pub fn cycle_detail_chart(&mut self) {
+        self.chart_cursor = None;
+        self.detail_chart = match self.detail_chart {
+            DetailChart::Yield => DetailChart::ReadLength,
+            DetailChart::ReadLength => DetailChart::PoreActivity,
+            DetailChart::PoreActivity => DetailChart::Barcodes,
+            DetailChart::Barcodes => DetailChart::Signal,
+            DetailChart::Signal => DetailChart::QScore,
+            DetailChart::QScore => DetailChart::PoreScans,
+            DetailChart::PoreScans => DetailChart::ActivePores,
+            DetailChart::ActivePores => DetailChart::DutyTime,
+            DetailChart::DutyTime => DetailChart::Yield,
+        };
+        if self.detail_chart != DetailChart::PoreActivity {
+            self.reset_channel_map_scroll();
+        }
     }
 
-    /// Returns true if throughput should be calculated for this position.
-    /// Throttles calculation to once every 5 seconds per position.
-    pub fn should_calc_throughput(&self, position: &str) -> bool {
-        self.throughput_last_calc
-            .get(position)
-            .map(|t| t.elapsed() >= std::time::Duration::from_secs(5))
-            .unwrap_or(true)
+    /// Switches the detail chart. Opening the signal chart while inspecting
+    /// a channel plots that channel.
+    pub fn set_detail_chart(&mut self, chart: DetailChart) {
+        if let (DetailChart::Signal, Some(cursor)) = (chart, self.channel_cursor) {
+            self.signal_channel = cursor as u32 + 1;
+        }
+        if chart != self.detail_chart {
+            self.chart_cursor = None;
+        }
+        self.detail_chart = chart;
+        if chart != DetailChart::PoreActivity {
+            self.reset_channel_map_scroll();
+        }
     }
 
-    /// Marks that throughput was just calculated for this position.
-    pub fn mark_throughput_calculated(&mut self, position: &str) {
-        self.throughput_last_calc
-            .insert(position.to_string(), Instant::now());
+    pub fn toggle_yield_unit(&mut self) {
+        self.yield_unit = match self.yield_unit {
+            YieldUnit::Bases => YieldUnit::Reads,
+            YieldUnit::Reads => YieldUnit::Bases,
+        };
+        tracing::debug!(new_unit = ?self.yield_unit, "Toggled yield unit");
     }
 
-    /// Resets the channel map scroll offset to the top
-    pub fn reset_channel_map_scroll(&mut self) {
-        self.channel_map_scroll_offset = 0;
+    pub fn toggle_yield_dual_axis(&mut self) {
+        self.yield_dual_axis = !self.yield_dual_axis;
+        self.yield_rates = false;
+        tracing::debug!(dual_axis = self.yield_dual_axis, "Toggled yield dual axis");
     }
 
-    /// Clamps the channel map scroll offset to valid range
-    pub fn clamp_channel_map_scroll(&mut self, total_rows: usize, visible_rows: usize) {
-        let max_offset = total_rows.saturating_sub(visible_rows);
-        self.channel_map_scroll_offset = self.channel_map_scroll_offset.min(max_offset);
+    pub fn toggle_legend(&mut self) {
+        self.show_legend = !self.show_legend;
+    }
+
+    /// Switches the channel map between state and occupancy coloring.
+    pub fn toggle_occupancy_colors(&mut self) {
+        if self.should_scroll_channel_map() {
+            self.occupancy_colors = !self.occupancy_colors;
+        }
+    }
+
+    /// Shows or hides one line of the yield and throughput charts.
+    pub fn toggle_yield_series(&mut self, series: YieldSeries) {
+        self.yield_series.toggle(series);
+        tracing::debug!(
+            ?series,
+            visible = self.yield_series.is_visible(series),
+            "Toggled yield series"
+        );
+    }
+
+    /// Switches the yield chart between cumulative yield and output per
+    /// hour.
+    pub fn toggle_yield_rates(&mut self) {
+        self.yield_rates = !self.yield_rates;
+        self.yield_dual_axis = false;
+        tracing::debug!(rates = self.yield_rates, "Toggled yield rates");
+    }
+
+    /// Cycles the yield chart through the last 1h, 6h and 24h and the
+    /// full run.
+    pub fn cycle_yield_window(&mut self) {
+        self.yield_window = match self.yield_window {
+            YieldWindow::Full => YieldWindow::LastHour,
+            YieldWindow::LastHour => YieldWindow::Last6Hours,
+            YieldWindow::Last6Hours => YieldWindow::Last24Hours,
+            YieldWindow::Last24Hours => YieldWindow::Full,
+        };
+        // The crosshair may be outside the new window.
+        self.chart_cursor = None;
+        tracing::debug!(window = ?self.yield_window, "Cycled yield window");
+    }
+
+    pub fn cycle_barcode_sort(&mut self) {
+        self.barcode_sort = match self.barcode_sort {
+            BarcodeSort::Bases => BarcodeSort::Reads,
+            BarcodeSort::Reads => BarcodeSort::Name,
+            BarcodeSort::Name => BarcodeSort::Bases,
+        };
+    }
+
+    /// Returns a position's barcodes in the current sort order.
+    pub fn sorted_barcodes(&self, position_name: &str) -> Vec<&BarcodeYield> {
+        let mut barcodes: Vec<&BarcodeYield> = self
+            .barcode_yield
+            .get(position_name)
+            .map(|b| b.iter().collect())
+            .unwrap_or_default();
+        match self.barcode_sort {
+            BarcodeSort::Bases => barcodes.sort_by_key(|b| std::cmp::Reverse(b.bases())),
+            BarcodeSort::Reads => barcodes.sort_by_key(|b| std::cmp::Reverse(b.reads())),
+            BarcodeSort::Name => barcodes.sort_by(|a, b| a.barcode.cmp(&b.barcode)),
+        }
+        barcodes
+    }
+
+    pub fn toggle_outliers(&mut self) {
+        self.exclude_outliers = !self.exclude_outliers;
+        tracing::info!(exclude_outliers = self.exclude_outliers, "Toggled outliers");
+    }
+
+    pub fn toggle_histogram_weight(&mut self) {
+        self.histogram_by_bases = !self.histogram_by_bases;
+        tracing::info!(
+            histogram_by_bases = self.histogram_by_bases,
+            "Toggled histogram weighting"
+        );
+    }
+
+    pub fn toggle_projection(&mut self) {
+        self.show_projection = !self.show_projection;
+        tracing::debug!(show_projection = self.show_projection, "Toggled projection");
+    }
+
+    /// Time since the acquisition of a position's run started, as of its
+    /// latest stats.
+    pub fn run_elapsed(&self, position_name: &str) -> Option<chrono::Duration> {
+        let stats = self.stats_cache.get(position_name)?;
+        let start = stats.acquisition_start?;
+        let now = stats.timestamp.unwrap_or_else(chrono::Utc::now);
+        Some((now - start).max(chrono::Duration::zero()))
+    }
+
+    /// Estimated time until a position's run ends: at its run-until runtime
+    /// target, or the configured run length without one, unless the yield
+    /// projection meets its yield target sooner.
+    pub fn run_remaining(&self, position_name: &str) -> Option<chrono::Duration> {
+        let elapsed = self.run_elapsed(position_name)?;
+        let targets = self
+            .run_until_targets
+            .get(position_name)
+            .copied()
+            .unwrap_or_default();
+        let run_length = targets
+            .runtime
+            .map(|seconds| seconds as f64)
+            .unwrap_or(self.config.tui.expected_run_hours * 3600.0);
+        let mut remaining = chrono::Duration::seconds(run_length as i64) - elapsed;
+
+        let projection = targets.estimated_bases.and_then(|target| {
+            let project = |model| self.yield_projection(position_name, YieldUnit::Bases, model);
+            let projection =
+                project(ProjectionModel::Decay).or_else(|| project(ProjectionModel::Linear))?;
+            let &(from, _) = projection.curve.first()?;
+            let &(at, _) = projection
+                .curve
+                .iter()
+                .find(|&&(_, total)| total >= target)?;
+            Some(chrono::Duration::seconds((at - from).into()))
+        });
+        if let Some(until_target) = projection {
+            remaining = remaining.min(until_target);
+        }
+        Some(remaining.max(chrono::Duration::zero()))
+    }
+
+    /// Projects a position's yield, in the given unit, to the end of a run
+    /// of the configured length.
+    pub fn yield_projection(
+        &self,
+        position_name: &str,
+        unit: YieldUnit,
+        model: ProjectionModel,
+    ) -> Option<Projection> {
+        let samples: Vec<(u32, u64)> = self
+            .yield_history
+            .get(position_name)?
+            .iter()
+            .map(|p| {
+                let total = match unit {
+                    YieldUnit::Bases => p.bases,
+                    YieldUnit::Reads => p.reads,
+                };
+                (p.seconds, total)
+            })
+            .collect();
+        let run_length = (self.config.tui.expected_run_hours * 3600.0) as u32;
+        analysis::project(&samples, run_length, model)
+    }
+
+    pub fn set_histogram_range(&mut self, min: u64, max: u64) {
+        if min < max {
+            self.histogram_range = Some((min, max));
+            tracing::info!(min, max, "Set histogram range");
+        }
+    }
+
+    pub fn clear_histogram_range(&mut self) {
+        self.histogram_range = None;
+    }
+
+    pub fn has_histogram_range(&self) -> bool {
+        self.histogram_range.is_some()
+    }
+
+    pub fn open_range_input(&mut self) {
+        if self.detail_chart != DetailChart::ReadLength {
+            return;
+        }
+
+        let (min_length, max_length) = match self.histogram_range {
+            Some((min, max)) => (
+                Some(min).filter(|&min| min > 0).map(|min| min.to_string()),
+                max.to_string(),
+            ),
+            None => (None, String::new()),
+        };
+        let buckets = self
+            .histogram_buckets
+            .map(|n| n.to_string())
+            .unwrap_or_default();
+
+        self.overlay = Overlay::RangeInput(HistogramForm {
+            min_length: min_length.unwrap_or_default(),
+            max_length,
+            buckets,
+            outlier_percent: self.outlier_percent.to_string(),
+            ..Default::default()
+        });
+    }
+
+    pub fn request_run_control(&mut self, action: RunControlAction) {
+        if self.refuse_read_only() {
+            return;
+        }
+        let Some(pos) = self.selected_position() else {
+            return;
+        };
+
+        let run_state = self.run_states.get(&pos.name);
+
+        let is_valid = match action {
+            RunControlAction::Pause => matches!(run_state, Some(RunState::Running)),
+            RunControlAction::Resume => matches!(run_state, Some(RunState::Paused)),
+            RunControlAction::Stop(_) => {
+                matches!(run_state, Some(RunState::Running | RunState::MuxScanning))
+            }
+        };
+
+        if is_valid {
+            self.overlay = Overlay::Confirmation {
+                action,
+                position_name: pos.name.clone(),
+                typed: String::new(),
+            };
+        }
+    }
+
+    /// Opens the start-protocol wizard for the selected position.
+    ///
+    /// Returns the position name if the wizard opened, so the caller can
+    /// load its protocols. Positions with a run in progress are skipped.
+    pub fn open_start_protocol(&mut self) -> Option<String> {
+        if self.refuse_read_only() {
+            return None;
+        }
+        let pos = self.selected_position()?;
+        if !matches!(self.run_states.get(&pos.name), None | Some(RunState::Idle)) {
+            return None;
+        }
+        let name = pos.name.clone();
+        self.overlay = Overlay::StartProtocol(StartWizard::new(name.clone()));
+        Some(name)
+    }
+
+    /// Opens the note editor for the selected position. Notes are kept in a
+    /// local file, never sent to MinKNOW, so read-only mode allows them.
+    pub fn open_note(&mut self) {
+        let Some(pos) = self.selected_position() else {
+            return;
+        };
+        let text = self.notes.get(&pos.name).cloned().unwrap_or_default();
+        self.overlay = Overlay::Note {
+            position_name: pos.name.clone(),
+            text,
+        };
+    }
+
+    /// Handles a key while a note is being edited. Returns `true` when
+    /// Enter changed the notes, which are then worth saving; an empty note
+    /// is removed.
+    pub fn handle_note_key(&mut self, key_code: crossterm::event::KeyCode) -> bool {
+        use crossterm::event::KeyCode;
+
+        let Overlay::Note {
+            position_name,
+            text,
+        } = &mut self.overlay
+        else {
+            return false;
+        };
+        match key_code {
+            KeyCode::Char(c) if text.chars().count() < MAX_NOTE_LEN => text.push(c),
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Esc => self.overlay = Overlay::None,
+            KeyCode::Enter => {
+                let name = std::mem::take(position_name);
+                let note = text.trim().to_string();
+                self.overlay = Overlay::None;
+                return if note.is_empty() {
+                    self.notes.remove(&name).is_some()
+                } else {
+                    self.notes.insert(name, note.clone()) != Some(note)
+                };
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Opens the run-until overlay for the selected position, filled in
+    /// with its current targets. Only positions with a run in progress can
+    /// have their targets changed.
+    pub fn open_run_until(&mut self) {
+        if self.refuse_read_only() {
+            return;
+        }
+        let Some(pos) = self.selected_position() else {
+            return;
+        };
+        if !self
+            .run_states
+            .get(&pos.name)
+            .is_some_and(|state| state.is_active())
+        {
+            return;
+        }
+        let targets = self
+            .run_until_targets
+            .get(&pos.name)
+            .copied()
+            .unwrap_or_default();
+        let decimal = |value: f64| {
+            let text = format!("{:.2}", value);
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        };
+        self.overlay = Overlay::RunUntil(RunUntilForm {
+            position_name: pos.name.clone(),
+            hours: targets
+                .runtime
+                .map(|s| decimal(s as f64 / 3600.0))
+                .unwrap_or_default(),
+            gigabases: targets
+                .estimated_bases
+                .map(|b| decimal(b as f64 / 1e9))
+                .unwrap_or_default(),
+            field: RunUntilField::default(),
+            invalid: false,
+        });
+    }
+
+    /// Handles a key press in the run-until overlay. Returns the new targets
+    /// once they are confirmed with `Enter`.
+    pub fn handle_run_until_key(
+        &mut self,
+        key_code: crossterm::event::KeyCode,
+    ) -> Option<RunUntilRequest> {
+        use crossterm::event::KeyCode;
+
+        let Overlay::RunUntil(form) = &mut self.overlay else {
+            return None;
+        };
+        let text = match form.field {
+            RunUntilField::Hours => &mut form.hours,
+            RunUntilField::Gigabases => &mut form.gigabases,
+        };
+
+        match key_code {
+            KeyCode::Esc => self.overlay = Overlay::None,
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+                form.field = match form.field {
+                    RunUntilField::Hours => RunUntilField::Gigabases,
+                    RunUntilField::Gigabases => RunUntilField::Hours,
+                };
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                form.invalid = false;
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() || c == '.' => {
+                text.push(c);
+                form.invalid = false;
+            }
+            KeyCode::Enter => {
+                let parse = |text: &str, scale: f64| -> Result<Option<u64>, ()> {
+                    if text.is_empty() {
+                        return Ok(None);
+                    }
+                    match text.parse::<f64>() {
+                        Ok(value) if value > 0.0 => Ok(Some((value * scale).round() as u64)),
+                        _ => Err(()),
+                    }
+                };
+                let (Ok(runtime), Ok(estimated_bases)) =
+                    (parse(&form.hours, 3600.0), parse(&form.gigabases, 1e9))
+                else {
+                    form.invalid = true;
+                    return None;
+                };
+                let position_name = form.position_name.clone();
+                let current = self
+                    .run_until_targets
+                    .get(&position_name)
+                    .copied()
+                    .unwrap_or_default();
+                self.overlay = Overlay::None;
+                return Some(RunUntilRequest {
+                    position_name,
+                    targets: RunUntilCriteria {
+                        runtime,
+                        estimated_bases,
+                        ..current
+                    },
+                });
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn update_protocols(&mut self, position_name: &str, protocols: Vec<ProtocolInfo>) {
+        if let Overlay::StartProtocol(wizard) = &mut self.overlay {
+            if wizard.position_name == position_name {
+                wizard.selected = wizard.selected.min(protocols.len().saturating_sub(1));
+                wizard.protocols = Some(protocols);
+            }
+        }
+    }
+
+    /// Handles a key press in the start-protocol wizard.
+    ///
+    /// `Esc` steps back, closing the wizard from the first step. Returns the
+    /// request once the final step is confirmed.
+    pub fn handle_start_wizard_key(
+        &mut self,
+        key_code: crossterm::event::KeyCode,
+    ) -> Option<StartRequest> {
+        use crossterm::event::KeyCode;
+
+        let Overlay::StartProtocol(wizard) = &mut self.overlay else {
+            return None;
+        };
+
+        match (wizard.step, key_code) {
+            (StartStep::SelectProtocol, KeyCode::Esc) => self.overlay = Overlay::None,
+            (StartStep::SelectProtocol, KeyCode::Up | KeyCode::Char('k')) => {
+                wizard.selected = wizard.selected.saturating_sub(1);
+            }
+            (StartStep::SelectProtocol, KeyCode::Down | KeyCode::Char('j')) => {
+                let count = wizard.protocols.as_ref().map_or(0, Vec::len);
+                if wizard.selected + 1 < count {
+                    wizard.selected += 1;
+                }
+            }
+            (StartStep::SelectProtocol, KeyCode::Enter) if wizard.selected_protocol().is_some() => {
+                wizard.step = StartStep::Details;
+            }
+            (StartStep::Details, KeyCode::Esc) => wizard.step = StartStep::SelectProtocol,
+            (StartStep::Details, KeyCode::Enter) => wizard.step = StartStep::Confirm,
+            (StartStep::Details, KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down) => {
+                wizard.field = match wizard.field {
+                    StartField::SampleId => StartField::ExperimentGroup,
+                    StartField::ExperimentGroup => StartField::SampleId,
+                };
+            }
+            (StartStep::Details, KeyCode::Backspace) => {
+                match wizard.field {
+                    StartField::SampleId => wizard.sample_id.pop(),
+                    StartField::ExperimentGroup => wizard.experiment_group.pop(),
+                };
+            }
+            (StartStep::Details, KeyCode::Char(c)) if !c.is_control() => match wizard.field {
+                StartField::SampleId => wizard.sample_id.push(c),
+                StartField::ExperimentGroup => wizard.experiment_group.push(c),
+            },
+            (StartStep::Confirm, KeyCode::Esc) => wizard.step = StartStep::Details,
+            (StartStep::Confirm, KeyCode::Enter) => {
+                let request = StartRequest {
+                    position_name: wizard.position_name.clone(),
+                    identifier: wizard.selected_protocol()?.identifier.clone(),
+                    sample_id: wizard.sample_id.trim().to_string(),
+                    experiment_group: wizard.experiment_group.trim().to_string(),
+                };
+                self.overlay = Overlay::None;
+                return Some(request);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Handles a key press in the confirmation overlay. Returns the action
+    /// and its position once confirmed: with `Enter`, after typing the
+    /// position name or `STOP` for actions that need it.
+    pub fn handle_confirmation_key(
+        &mut self,
+        key_code: crossterm::event::KeyCode,
+    ) -> Option<(RunControlAction, String)> {
+        use crossterm::event::KeyCode;
+
+        let Overlay::Confirmation {
+            action,
+            position_name,
+            typed,
+        } = &mut self.overlay
+        else {
+            return None;
+        };
+        match key_code {
+            KeyCode::Esc => self.overlay = Overlay::None,
+            KeyCode::Enter => {
+                if action.needs_typed_confirmation() && !confirmation_matches(typed, position_name)
+                {
+                    return None;
+                }
+                let confirmed = (*action, position_name.clone());
+                self.overlay = Overlay::None;
+                return Some(confirmed);
+            }
+            // Letters go to the typed text, so the stop options are
+            // picked with the arrow keys.
+            KeyCode::Up | KeyCode::Down | KeyCode::Tab | KeyCode::BackTab => {
+                if let RunControlAction::Stop(options) = action {
+                    options.data_action = match options.data_action {
+                        StopDataAction::FinishProcessing => StopDataAction::KeepAllData,
+                        StopDataAction::KeepAllData => StopDataAction::FinishProcessing,
+                    };
+                }
+            }
+            KeyCode::Char(c) if action.needs_typed_confirmation() => typed.push(c),
+            KeyCode::Backspace => {
+                typed.pop();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn apply_range_input(&mut self) -> bool {
+        let Overlay::RangeInput(form) = &mut self.overlay else {
+            return false;
+        };
+        let min_length = match form.min_length.as_str() {
+            "" => Some(0),
+            text => text.parse::<u64>().ok(),
+        };
+        // A min length needs a max above it; MinKNOW is asked for exactly
+        // this range.
+        let range = match (min_length, form.max_length.as_str()) {
+            (Some(0), "") => Some(None),
+            (Some(min), text) => text
+                .parse::<u64>()
+                .ok()
+                .filter(|&max| max > min)
+                .map(|max| Some((min, max))),
+            (None, _) => None,
+        };
+        let buckets = match form.buckets.as_str() {
+            "" => Some(None),
+            text => text
+                .parse::<u32>()
+                .ok()
+                .filter(|n| (2..=MAX_HISTOGRAM_BUCKETS).contains(n))
+                .map(Some),
+        };
+        let outlier_percent = match form.outlier_percent.as_str() {
+            "" => Some(self.config.tui.histogram_outlier_percent as f32),
+            text => text.parse::<f32>().ok().filter(|&p| p > 0.0 && p <= 50.0),
+        };
+        let (Some(range), Some(buckets), Some(outlier_percent)) = (range, buckets, outlier_percent)
+        else {
+            form.invalid = true;
+            tracing::warn!("Invalid histogram settings");
+            return false;
+        };
+
+        self.histogram_range = range;
+        self.histogram_buckets = buckets;
+        self.outlier_percent = outlier_percent;
+        self.overlay = Overlay::None;
+        tracing::info!(
+            range = ?range,
+            buckets = ?buckets,
+            outlier_percent,
+            "Applied histogram settings"
+        );
+        true
+    }
+
+    pub fn handle_range_input_key(&mut self, key_code: crossterm::event::KeyCode) {
+        use crossterm::event::KeyCode;
+
+        let Overlay::RangeInput(form) = &mut self.overlay else {
+            return;
+        };
+        // Up and Down step the focused field by this much.
+        let (text, step) = match form.field {
+            HistogramField::MinLength => (&mut form.min_length, 1000.0),
+            HistogramField::MaxLength => (&mut form.max_length, 1000.0),
+            HistogramField::Buckets => (&mut form.buckets, 10.0),
+            HistogramField::OutlierPercent => (&mut form.outlier_percent, 0.5),
+        };
+        match key_code {
+            KeyCode::Tab => {
+                form.field = match form.field {
+                    HistogramField::MinLength => HistogramField::MaxLength,
+                    HistogramField::MaxLength => HistogramField::Buckets,
+                    HistogramField::Buckets => HistogramField::OutlierPercent,
+                    HistogramField::OutlierPercent => HistogramField::MinLength,
+                };
+            }
+            KeyCode::BackTab => {
+                form.field = match form.field {
+                    HistogramField::MinLength => HistogramField::OutlierPercent,
+                    HistogramField::MaxLength => HistogramField::MinLength,
+                    HistogramField::Buckets => HistogramField::MaxLength,
+                    HistogramField::OutlierPercent => HistogramField::Buckets,
+                };
+            }
+            KeyCode::Char(c)
+                if c.is_ascii_digit()
+                    || (c == '.' && form.field == HistogramField::OutlierPercent) =>
+            {
+                text.push(c);
+                form.invalid = false;
+            }
+            KeyCode::Backspace => {
+                text.pop();
+                form.invalid = false;
+            }
+            KeyCode::Up | KeyCode::Down => {
+                let current: f64 = text.parse().unwrap_or(0.0);
+                let next = if key_code == KeyCode::Up {
+                    current + step
+                } else {
+                    (current - step).max(0.0)
+                };
+                *text = next.to_string();
+                form.invalid = false;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn update_yield_history(&mut self, position_name: &str, mut data: Vec<YieldDataPoint>) {
+        bound_yield_points(&mut data);
+        self.yield_history.insert(position_name.to_string(), data);
+    }
+
+    /// Merges backfilled yield points into the existing history.
+    ///
+    /// Points are keyed by acquisition time; where both sides have a point for
+    /// the same second, the backfilled one wins since it reflects MinKNOW's
+    /// final view of the run.
+    pub fn merge_yield_history(&mut self, position_name: &str, data: Vec<YieldDataPoint>) {
+        let history = self
+            .yield_history
+            .entry(position_name.to_string())
+            .or_default();
+        let mut merged: Vec<YieldDataPoint> = data;
+        merged.append(history);
+        // Stable sort keeps backfilled points ahead of existing ones at equal times.
+        merged.sort_by_key(|p| p.seconds);
+        merged.dedup_by_key(|p| p.seconds);
+        bound_yield_points(&mut merged);
+        *history = merged;
+    }
+
+    /// Merges incremental per-barcode yield into the existing history.
+    pub fn merge_barcode_yield(&mut self, position_name: &str, data: Vec<BarcodeYield>) {
+        let existing = self
+            .barcode_yield
+            .entry(position_name.to_string())
+            .or_default();
+        for update in data {
+            match existing.iter_mut().find(|b| b.barcode == update.barcode) {
+                Some(barcode) => {
+                    let mut merged = update.points;
+                    merged.append(&mut barcode.points);
+                    merged.sort_by_key(|p| p.seconds);
+                    merged.dedup_by_key(|p| p.seconds);
+                    bound_yield_points(&mut merged);
+                    barcode.points = merged;
+                }
+                None => {
+                    let mut update = update;
+                    bound_yield_points(&mut update.points);
+                    existing.push(update);
+                }
+            }
+        }
+    }
+
+    /// Merges protocol phase transitions into the existing history.
+    pub fn merge_phase_history(&mut self, position_name: &str, events: Vec<PhaseEvent>) {
+        let history = self
+            .phase_history
+            .entry(position_name.to_string())
+            .or_default();
+        history.extend(events);
+        history.sort_by_key(|e| e.timestamp);
+        history.dedup();
+    }
+
+    pub fn update_histogram(&mut self, position_name: &str, histogram: ReadLengthHistogram) {
+        self.histograms.insert(position_name.to_string(), histogram);
+    }
+
+    pub fn update_run_history(&mut self, position_name: &str, runs: Vec<RunSummary>) {
+        self.run_history.insert(position_name.to_string(), runs);
+        if let Some(count) = self.history_runs().map(|runs| runs.len()) {
+            self.history_selected = self.history_selected.min(count.saturating_sub(1));
+        }
+    }
+
+    /// Stores the latest duty time and merges its buckets into the run's
+    /// history, replacing buckets that start at the same time.
+    pub fn update_duty_time(&mut self, position_name: &str, duty_time: DutyTimeSnapshot) {
+        let history = self
+            .duty_history
+            .entry(position_name.to_string())
+            .or_default();
+        for bucket in &duty_time.buckets {
+            match history.binary_search_by_key(&bucket.start, |b| b.start) {
+                Ok(idx) => history[idx] = bucket.clone(),
+                Err(idx) => history.insert(idx, bucket.clone()),
+            }
+        }
+        self.duty_time.insert(position_name.to_string(), duty_time);
+    }
+
+    /// Yield points charted for a position: those of its current run, or
+    /// of its last finished run while idle, within the yield window.
+    pub fn yield_points(&self, position_name: &str) -> Option<&[YieldDataPoint]> {
+        let points = self
+            .yield_history
+            .get(position_name)
+            .or_else(|| {
+                self.last_runs
+                    .get(position_name)?
+                    .as_ref()
+                    .map(|run| &run.yield_history)
+            })
+            .map(Vec::as_slice)
+            .filter(|points| !points.is_empty())?;
+        let Some(window) = self.yield_window.seconds() else {
+            return Some(points);
+        };
+        let start = points.last()?.seconds.saturating_sub(window);
+        Some(&points[points.partition_point(|p| p.seconds < start)..])
+    }
+
+    /// Pore scans and pauses of a position's run, as seconds since the
+    /// acquisition started, oldest first.
+    pub fn run_markers(&self, position_name: &str) -> Vec<(u32, PhaseKind)> {
+        let (Some(phases), Some(start)) = (
+            self.phase_history.get(position_name),
+            self.stats_cache
+                .get(position_name)
+                .and_then(|stats| stats.acquisition_start),
+        ) else {
+            return Vec::new();
+        };
+        phases
+            .iter()
+            .filter(|event| event.kind != PhaseKind::Other && event.timestamp >= start)
+            .map(|event| {
+                let seconds = (event.timestamp - start).num_seconds();
+                (u32::try_from(seconds).unwrap_or(u32::MAX), event.kind)
+            })
+            .collect()
+    }
+
+    /// Starts or stops reading values off the yield or q-score chart. The
+    /// crosshair starts on the latest yield point or the modal q-score.
+    pub fn toggle_chart_cursor(&mut self) {
+        if self.chart_cursor.take().is_some() {
+            return;
+        }
+        let Some(position) = self.selected_position() else {
+            return;
+        };
+        self.chart_cursor = match self.detail_chart {
+            DetailChart::Yield => self
+                .yield_points(&position.name)
+                .and_then(|points| points.last())
+                .map(|point| ChartCursor::Yield(point.seconds)),
+            DetailChart::QScore => self
+                .qscore_histograms
+                .get(&position.name)
+                .filter(|histogram| histogram.total_count() > 0)
+                .and_then(|histogram| {
+                    let values = &histogram.bucket_values;
+                    (0..values.len()).max_by_key(|&i| values[i])
+                })
+                .map(ChartCursor::QScore),
+            _ => None,
+        };
+    }
+
+    /// Moves the crosshair `steps` to the right (left if negative),
+    /// stopping at either end of the data.
+    pub fn move_chart_cursor(&mut self, steps: i64) {
+        let Some(position) = self.selected_position() else {
+            return;
+        };
+        self.chart_cursor = match self.chart_cursor {
+            Some(ChartCursor::Yield(seconds)) => {
+                let Some(points) = self.yield_points(&position.name) else {
+                    return;
+                };
+                let current = points
+                    .partition_point(|p| p.seconds < seconds)
+                    .min(points.len() - 1);
+                let stride = (points.len() / CURSOR_STEPS).max(1) as i64;
+                let idx = (current as i64 + steps * stride).clamp(0, points.len() as i64 - 1);
+                Some(ChartCursor::Yield(points[idx as usize].seconds))
+            }
+            Some(ChartCursor::QScore(bucket)) => {
+                let Some(histogram) = self.qscore_histograms.get(&position.name) else {
+                    return;
+                };
+                let last = histogram.bucket_values.len().saturating_sub(1) as i64;
+                Some(ChartCursor::QScore(
+                    (bucket as i64 + steps).clamp(0, last) as usize
+                ))
+            }
+            None => None,
+        };
+    }
+
+    /// Scrolls the channel map back to the top and leaves inspection mode
+    /// and time-lapse playback.
+    pub fn reset_channel_map_scroll(&mut self) {
+        self.channel_map_scroll_offset = 0;
+        self.channel_cursor = None;
+        self.timelapse_at = None;
+    }
+
+    /// Steps the channel map one time-lapse frame back or forward. Stepping
+    /// back from the live map shows the latest frame, and stepping forward
+    /// past it returns to live.
+    pub fn step_timelapse(&mut self, forward: bool) {
+        if !self.should_scroll_channel_map() {
+            return;
+        }
+        let Some(timelapse) = self
+            .selected_position()
+            .and_then(|position| self.channel_timelapse.get(&position.name))
+            .filter(|timelapse| !timelapse.is_empty())
+        else {
+            return;
+        };
+        let last = timelapse.len() - 1;
+        let idx = match (self.timelapse_at, forward) {
+            (None, false) => Some(last),
+            (None, true) => None,
+            (Some(at), false) => Some(timelapse.index_at(at).saturating_sub(1)),
+            (Some(at), true) => Some(timelapse.index_at(at) + 1).filter(|&idx| idx <= last),
+        };
+        self.timelapse_at = idx.and_then(|idx| timelapse.time(idx));
+    }
+
+    /// The time-lapse frame shown on the detail position's channel map, or
+    /// `None` while live.
+    pub fn timelapse_frame(&self) -> Option<TimelapseFrame> {
+        let at = self.timelapse_at?;
+        let timelapse = self
+            .channel_timelapse
+            .get(&self.selected_position()?.name)?;
+        let index = timelapse.index_at(at);
+        Some(TimelapseFrame {
+            index,
+            frames: timelapse.len(),
+            at: timelapse.time(index)?,
+            gap: timelapse.gap_before(index),
+            states: timelapse.snapshot(index)?,
+        })
+    }
+
+    /// Starts or stops inspecting single channels on the channel map.
+    pub fn toggle_channel_inspector(&mut self) {
+        if !self.should_scroll_channel_map() {
+            return;
+        }
+        self.channel_cursor = match self.channel_cursor {
+            Some(_) => None,
+            None => Some(0),
+        };
+    }
+
+    /// Inspects `channel` (0-based), e.g. after it was clicked.
+    pub fn inspect_channel(&mut self, channel: usize) {
+        if self.should_scroll_channel_map() {
+            self.channel_cursor = Some(channel);
+        }
+    }
+
+    /// Moves the inspected channel by one cell on the flow cell grid,
+    /// skipping cells without a channel. Stays put at the edge.
+    pub fn move_channel_cursor(&mut self, dx: i64, dy: i64) {
+        let Some(cursor) = self.channel_cursor else {
+            return;
+        };
+        let Screen::PositionDetail { position_idx } = self.screen else {
+            return;
+        };
+        let Some(position) = self.positions.get(position_idx) else {
+            return;
+        };
+        let grid = match (
+            self.channel_layouts.get(&position.name),
+            self.channel_states.get(&position.name),
+        ) {
+            (Some(layout), _) => ChannelGrid::Layout(layout),
+            (None, Some(states)) if !states.states.is_empty() => {
+                ChannelGrid::Square(states.states.len())
+            }
+            _ => return,
+        };
+
+        let Some((mut x, mut y)) = grid.coords(cursor) else {
+            return;
+        };
+        let (width, height) = grid.size();
+        loop {
+            x += dx;
+            y += dy;
+            if x < 0 || y < 0 || x >= width || y >= height {
+                return;
+            }
+            if let Some(channel) = grid.channel_at(x, y) {
+                self.channel_cursor = Some(channel);
+                return;
+            }
+        }
+    }
+
+    /// Clamps the channel map scroll offset to valid range
+    pub fn clamp_channel_map_scroll(&mut self, total_rows: usize, visible_rows: usize) {
+        let max_offset = total_rows.saturating_sub(visible_rows);
+        self.channel_map_scroll_offset = self.channel_map_scroll_offset.min(max_offset);
+    }
+
+    /// Returns true if channel map scrolling should be active
+    pub fn should_scroll_channel_map(&self) -> bool {
+        matches!(self.screen, Screen::PositionDetail { .. })
+            && self.detail_chart == DetailChart::PoreActivity
+    }
+}
+
+/// Channel positions on the flow cell, from the channel layout or, before
+/// it has loaded, a square grid in channel order as the channel map draws.
+enum ChannelGrid<'a> {
+    Layout(&'a ChannelLayout),
+    Square(usize),
+}
+
+impl ChannelGrid<'_> {
+    fn side(count: usize) -> i64 {
+        (count as f64).sqrt().ceil() as i64
+    }
+
+    fn size(&self) -> (i64, i64) {
+        match self {
+            ChannelGrid::Layout(layout) => (layout.width as i64, layout.height as i64),
+            ChannelGrid::Square(count) => (Self::side(*count), Self::side(*count)),
+        }
+    }
+
+    fn coords(&self, channel: usize) -> Option<(i64, i64)> {
+        match self {
+            ChannelGrid::Layout(layout) => {
+                let &(x, y) = layout.coords.get(channel)?;
+                Some((x as i64, y as i64))
+            }
+            ChannelGrid::Square(count) => {
+                let side = Self::side(*count);
+                (channel < *count).then(|| (channel as i64 % side, channel as i64 / side))
+            }
+        }
+    }
+
+    fn channel_at(&self, x: i64, y: i64) -> Option<usize> {
+        match self {
+            ChannelGrid::Layout(layout) => layout
+                .coords
+                .iter()
+                .position(|&(cx, cy)| cx as i64 == x && cy as i64 == y),
+            ChannelGrid::Square(count) => {
+                let channel = (y * Self::side(*count) + x) as usize;
+                (channel < *count).then_some(channel)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ChannelState, DeviceType, PositionState};
+
+    fn test_config() -> Config {
+        Config::default()
+    }
+
+    fn test_position(name: &str) -> Position {
+        Position {
+            id: name.to_string(),
+            name: name.to_string(),
+            device_id: "DEV001".to_string(),
+            state: PositionState::Idle,
+            grpc_port: 8000,
+            is_simulated: false,
+            device_type: DeviceType::MinION,
+            host: None,
+        }
+    }
+
+    #[test]
+    fn test_chart_buffer_new() {
+        let buf = ChartBuffer::new(100);
+        assert!(buf.data.is_empty());
+        assert_eq!(buf.max_points, 100);
+    }
+
+    #[test]
+    fn test_chart_buffer_push() {
+        let mut buf = ChartBuffer::new(3);
+        buf.push(1.0, 10.0);
+        buf.push(2.0, 20.0);
+        assert_eq!(buf.data.len(), 2);
+        assert_eq!(buf.data[0], (1.0, 10.0));
+        assert_eq!(buf.data[1], (2.0, 20.0));
+    }
+
+    #[test]
+    fn test_chart_buffer_overflow() {
+        let mut buf = ChartBuffer::new(3);
+        buf.push(1.0, 10.0);
+        buf.push(2.0, 20.0);
+        buf.push(3.0, 30.0);
+        buf.push(4.0, 40.0);
+        assert_eq!(buf.data.len(), 3);
+        assert_eq!(buf.data[0], (2.0, 20.0));
+        assert_eq!(buf.data[2], (4.0, 40.0));
+    }
+
+    #[test]
+    fn test_chart_buffer_clear() {
+        let mut buf = ChartBuffer::new(10);
+        buf.push(1.0, 10.0);
+        buf.push(2.0, 20.0);
+        buf.clear();
+        assert!(buf.data.is_empty());
+    }
+
+    #[test]
+    fn test_chart_buffer_spanning_bounds_points() {
+        let short = ChartBuffer::spanning(60.0, 2.0);
+        assert_eq!(short.max_points, 30);
+        assert_eq!(short.min_interval, 0.0);
+
+        // Three days at one sample a second stays within the point limit.
+        let span = 3.0 * 86_400.0;
+        let mut buf = ChartBuffer::spanning(span, 1.0);
+        assert_eq!(buf.max_points, MAX_CHART_POINTS);
+        for second in 0..86_400 {
+            buf.push(second as f64, 1.0);
+        }
+        // One day of samples fills a third of the buffer, ending on the latest.
+        assert!(buf.data.len() <= MAX_CHART_POINTS / 3 + 1);
+        assert_eq!(buf.data.back(), Some(&(86_399.0, 1.0)));
+    }
+
+    #[test]
+    fn test_app_initial_state() {
+        let app = App::new(test_config());
+        assert_eq!(app.screen, Screen::Overview);
+        assert_eq!(app.overlay, Overlay::None);
+        assert!(app.positions.is_empty());
+        assert_eq!(app.selected_index(), None);
+        assert!(!app.should_quit);
+    }
+
+    #[test]
+    fn test_select_next_empty() {
+        let mut app = App::new(test_config());
+        app.select_next();
+        assert_eq!(app.selected_index(), None);
+    }
+
+    #[test]
+    fn test_select_next_wraps() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A"), test_position("B"), test_position("C")];
+        app.select_position(2);
+        app.select_next();
+        assert_eq!(app.selected_index(), Some(0));
+    }
+
+    #[test]
+    fn test_select_previous_empty() {
+        let mut app = App::new(test_config());
+        app.select_previous();
+        assert_eq!(app.selected_index(), None);
+    }
+
+    #[test]
+    fn test_select_previous_wraps() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A"), test_position("B"), test_position("C")];
+        app.select_position(0);
+        app.select_previous();
+        assert_eq!(app.selected_index(), Some(2));
+    }
+
+    #[test]
+    fn test_enter_detail_with_run() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.run_states.insert("A".to_string(), RunState::Running);
+        app.enter_detail();
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+    }
+
+    #[test]
+    fn test_enter_detail_idle_position() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.enter_detail();
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+    }
+
+    #[test]
+    fn test_last_run_dropped_when_a_run_starts() {
+        let mut app = App::new(test_config());
+        app.update_positions(vec![test_position("A")]);
+        app.apply(&BusEvent::LastRun {
+            position: "A".into(),
+            run: Some(Box::default()),
+        });
+        app.apply(&BusEvent::RunStateChanged {
+            position: "A".into(),
+            state: RunState::Idle,
+        });
+        assert!(app.last_runs["A"].is_some());
+
+        app.apply(&BusEvent::RunStateChanged {
+            position: "A".into(),
+            state: RunState::Starting,
+        });
+        assert!(!app.last_runs.contains_key("A"));
+    }
+
+    #[test]
+    fn test_enter_detail_empty() {
+        let mut app = App::new(test_config());
+        app.enter_detail();
+        assert_eq!(app.screen, Screen::Overview);
+    }
+
+    #[test]
+    fn test_back_from_detail() {
+        let mut app = App::new(test_config());
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.back();
+        assert_eq!(app.screen, Screen::Overview);
+    }
+
+    fn finished_run(run_id: &str) -> RunSummary {
+        RunSummary {
+            info: RunInfo {
+                run_id: Some(run_id.to_string()),
+                ..Default::default()
+            },
+            end_reason: "Completed".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_open_run_history_for_idle_position() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A"), test_position("B")];
+        app.select_position(1);
+        app.history_selected = 3;
+        app.open_run_history();
+        assert_eq!(app.screen, Screen::RunHistory { position_idx: 1 });
+        assert_eq!(app.history_selected, 0);
+        assert!(app.history_runs().is_none());
+    }
+
+    fn protocol(identifier: &str) -> ProtocolInfo {
+        ProtocolInfo {
+            identifier: identifier.to_string(),
+            name: identifier.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_start_protocol_wizard_flow() {
+        use crossterm::event::KeyCode;
+
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        assert_eq!(app.open_start_protocol().as_deref(), Some("A"));
+
+        // Nothing to select until the protocols arrive.
+        assert_eq!(app.handle_start_wizard_key(KeyCode::Enter), None);
+        app.update_protocols("B", vec![protocol("ignored")]);
+        app.update_protocols("A", vec![protocol("qc"), protocol("seq")]);
+        app.handle_start_wizard_key(KeyCode::Down);
+        app.handle_start_wizard_key(KeyCode::Enter);
+
+        for c in "s1".chars() {
+            app.handle_start_wizard_key(KeyCode::Char(c));
+        }
+        app.handle_start_wizard_key(KeyCode::Tab);
+        for c in "grp".chars() {
+            app.handle_start_wizard_key(KeyCode::Char(c));
+        }
+        app.handle_start_wizard_key(KeyCode::Backspace);
+        app.handle_start_wizard_key(KeyCode::Enter);
+        let Overlay::StartProtocol(wizard) = &app.overlay else {
+            panic!("wizard closed early");
+        };
+        assert_eq!(wizard.step, StartStep::Confirm);
+
+        // Esc steps back one page at a time.
+        app.handle_start_wizard_key(KeyCode::Esc);
+        app.handle_start_wizard_key(KeyCode::Enter);
+
+        let request = app.handle_start_wizard_key(KeyCode::Enter).unwrap();
+        assert_eq!(
+            request,
+            StartRequest {
+                position_name: "A".to_string(),
+                identifier: "seq".to_string(),
+                sample_id: "s1".to_string(),
+                experiment_group: "gr".to_string(),
+            }
+        );
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn test_start_protocol_skipped_while_running() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.update_run_state("A", RunState::Running);
+        assert_eq!(app.open_start_protocol(), None);
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn test_stop_needs_typed_confirmation() {
+        use crossterm::event::KeyCode;
+
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.update_run_state("X1", RunState::Running);
+
+        app.request_run_control(RunControlAction::Pause);
+        assert_eq!(
+            app.handle_confirmation_key(KeyCode::Enter),
+            Some((RunControlAction::Pause, "X1".to_string()))
+        );
+        assert_eq!(app.overlay, Overlay::None);
+
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        assert_eq!(app.handle_confirmation_key(KeyCode::Enter), None);
+        app.paste("X2");
+        assert_eq!(app.handle_confirmation_key(KeyCode::Enter), None);
+        assert!(matches!(app.overlay, Overlay::Confirmation { .. }));
+
+        app.handle_confirmation_key(KeyCode::Backspace);
+        app.handle_confirmation_key(KeyCode::Char('1'));
+        assert_eq!(
+            app.handle_confirmation_key(KeyCode::Enter),
+            Some((
+                RunControlAction::Stop(StopOptions::default()),
+                "X1".to_string()
+            ))
+        );
+
+        // The arrow keys pick what happens to reads not yet basecalled.
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        app.handle_confirmation_key(KeyCode::Down);
+        app.paste("stop");
+        let keep_all = StopOptions {
+            data_action: StopDataAction::KeepAllData,
+        };
+        assert_eq!(
+            app.handle_confirmation_key(KeyCode::Enter),
+            Some((RunControlAction::Stop(keep_all), "X1".to_string()))
+        );
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        app.handle_confirmation_key(KeyCode::Esc);
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn test_read_only_disables_run_control() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.update_run_state("A", RunState::Running);
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        assert!(matches!(app.overlay, Overlay::Confirmation { .. }));
+
+        app.apply(&BusEvent::ReadOnly {
+            reason: "stop_protocol: permission denied".into(),
+        });
+        assert_eq!(app.overlay, Overlay::None);
+        assert_eq!(app.toasts.len(), 1);
+
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        app.open_run_until();
+        assert_eq!(app.overlay, Overlay::None);
+        assert_eq!(app.toasts.len(), 3);
+
+        // Reconnecting fetches a token again.
+        app.apply(&BusEvent::Connected);
+        assert_eq!(app.read_only, None);
+    }
+
+    #[test]
+    fn test_unsupported_minknow_version_warns_once() {
+        let mut app = App::new(test_config());
+        let server = |major, full: &str| BusEvent::ServerInfo {
+            info: ServerInfo {
+                endpoint: "gridion:9502".into(),
+                authenticated: false,
+                version: Some(crate::client::VersionInfo {
+                    major,
+                    full: full.into(),
+                    ..Default::default()
+                }),
+            },
+        };
+
+        app.apply(&server(6, "6.2.5"));
+        assert!(app.toasts.is_empty());
+
+        for _ in 0..2 {
+            app.apply(&server(7, "7.0.1"));
+        }
+        assert_eq!(app.toasts.len(), 1);
+        let version = app.server.as_ref().and_then(|s| s.version.as_ref());
+        assert_eq!(version.unwrap().full, "7.0.1");
+    }
+
+    #[test]
+    fn test_run_until_form_keeps_other_targets() {
+        use crossterm::event::KeyCode;
+
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.open_run_until();
+        assert_eq!(app.overlay, Overlay::None);
+
+        app.update_run_state("A", RunState::Running);
+        app.apply(&BusEvent::RunUntilTargets {
+            position: "A".to_string(),
+            targets: RunUntilCriteria {
+                runtime: Some(72 * 3600),
+                available_pores: Some(10.0),
+                ..Default::default()
+            },
+        });
+        app.open_run_until();
+        let Overlay::RunUntil(form) = &app.overlay else {
+            panic!("run-until overlay not open");
+        };
+        assert_eq!(form.hours, "72");
+        assert_eq!(form.gigabases, "");
+
+        app.handle_run_until_key(KeyCode::Backspace);
+        app.handle_run_until_key(KeyCode::Char('.'));
+        app.handle_run_until_key(KeyCode::Char('5'));
+        app.handle_run_until_key(KeyCode::Tab);
+        app.handle_run_until_key(KeyCode::Char('.'));
+        assert_eq!(app.handle_run_until_key(KeyCode::Enter), None);
+        assert!(matches!(&app.overlay, Overlay::RunUntil(form) if form.invalid));
+
+        app.handle_run_until_key(KeyCode::Backspace);
+        for c in "12".chars() {
+            app.handle_run_until_key(KeyCode::Char(c));
+        }
+        let request = app.handle_run_until_key(KeyCode::Enter).unwrap();
+        assert_eq!(request.position_name, "A");
+        assert_eq!(
+            request.targets,
+            RunUntilCriteria {
+                runtime: Some(27_000),
+                estimated_bases: Some(12_000_000_000),
+                passed_basecalled_bases: None,
+                available_pores: Some(10.0),
+            }
+        );
+        assert_eq!(app.overlay, Overlay::None);
+    }
+
+    #[test]
+    fn test_run_until_fraction_uses_nearest_target() {
+        let mut app = App::new(test_config());
+        assert_eq!(app.run_until_fraction("A"), None);
+
+        app.update_yield_history(
+            "A",
+            vec![YieldDataPoint {
+                seconds: 3600,
+                bases: 5_000_000_000,
+                ..Default::default()
+            }],
+        );
+        app.apply(&BusEvent::RunUntilTargets {
+            position: "A".to_string(),
+            targets: RunUntilCriteria {
+                runtime: Some(4 * 3600),
+                estimated_bases: Some(10_000_000_000),
+                ..Default::default()
+            },
+        });
+        assert_eq!(app.run_until_fraction("A"), Some(0.5));
+
+        // Reported progress takes precedence over the yield history.
+        app.apply(&BusEvent::RunUntilProgress {
+            position: "A".to_string(),
+            progress: RunUntilCriteria {
+                runtime: Some(3 * 3600),
+                ..Default::default()
+            },
+        });
+        assert_eq!(app.run_until_fraction("A"), Some(0.75));
+
+        app.update_run_state("A", RunState::Idle);
+        assert_eq!(app.run_until_fraction("A"), None);
+    }
+
+    #[test]
+    fn test_run_history_navigation_and_back() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.open_run_history();
+        app.apply(&BusEvent::RunHistory {
+            position: "A".to_string(),
+            runs: vec![finished_run("r2"), finished_run("r1")],
+        });
+
+        app.select_previous();
+        assert_eq!(app.history_selected, 1);
+        app.select_next();
+        assert_eq!(app.history_selected, 0);
+        assert_eq!(app.selected_index(), Some(0));
+
+        app.select_next();
+        app.enter_detail();
+        assert_eq!(
+            app.screen,
+            Screen::RunHistoryDetail {
+                position_idx: 0,
+                run_idx: 1
+            }
+        );
+
+        app.back();
+        assert_eq!(app.screen, Screen::RunHistory { position_idx: 0 });
+        app.back();
+        assert_eq!(app.screen, Screen::Overview);
     }
 
-    /// Returns true if channel map scrolling should be active
-    pub fn should_scroll_channel_map(&self) -> bool {
-        matches!(self.screen, Screen::PositionDetail { .. })
-            && self.detail_chart == DetailChart::PoreActivity
+    #[test]
+    fn test_enter_on_empty_run_history_stays() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("A")];
+        app.open_run_history();
+        app.update_run_history("A", Vec::new());
+        app.enter_detail();
+        assert_eq!(app.screen, Screen::RunHistory { position_idx: 0 });
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::client::{DeviceType, PositionState};
+    #[test]
+    fn test_low_disk_space_uses_configured_threshold() {
+        let mut config = test_config();
+        config.tui.disk_warning_percent = 20.0;
+        let mut app = App::new(config);
+        app.apply(&BusEvent::DiskSpace {
+            position: "A".to_string(),
+            info: DiskSpaceInfo {
+                bytes_available: 150,
+                bytes_capacity: 1_000,
+                ..Default::default()
+            },
+        });
 
-    fn test_config() -> Config {
-        Config::default()
-    }
+        assert!(app.low_disk_space("A").is_some());
+        assert!(app.low_disk_space("B").is_none());
 
-    fn test_position(name: &str) -> Position {
-        Position {
-            id: name.to_string(),
-            name: name.to_string(),
-            device_id: "DEV001".to_string(),
-            state: PositionState::Idle,
-            grpc_port: 8000,
-            is_simulated: false,
-            device_type: DeviceType::MinION,
-        }
+        app.config.tui.disk_warning_percent = 10.0;
+        assert!(app.low_disk_space("A").is_none());
     }
 
     #[test]
-    fn test_chart_buffer_new() {
-        let buf = ChartBuffer::new(100);
-        assert!(buf.data.is_empty());
-        assert_eq!(buf.max_points, 100);
+    fn test_back_closes_overlay() {
+        let mut app = App::new(test_config());
+        app.overlay = Overlay::Help;
+        app.back();
+        assert_eq!(app.overlay, Overlay::None);
     }
 
     #[test]
-    fn test_chart_buffer_push() {
-        let mut buf = ChartBuffer::new(3);
-        buf.push(1.0, 10.0);
-        buf.push(2.0, 20.0);
-        assert_eq!(buf.data.len(), 2);
-        assert_eq!(buf.data[0], (1.0, 10.0));
-        assert_eq!(buf.data[1], (2.0, 20.0));
+    fn test_toggle_help() {
+        let mut app = App::new(test_config());
+        app.toggle_help();
+        assert_eq!(app.overlay, Overlay::Help);
+        app.toggle_help();
+        assert_eq!(app.overlay, Overlay::None);
     }
 
     #[test]
-    fn test_chart_buffer_overflow() {
-        let mut buf = ChartBuffer::new(3);
-        buf.push(1.0, 10.0);
-        buf.push(2.0, 20.0);
-        buf.push(3.0, 30.0);
-        buf.push(4.0, 40.0);
-        assert_eq!(buf.data.len(), 3);
-        assert_eq!(buf.data[0], (2.0, 20.0));
-        assert_eq!(buf.data[2], (4.0, 40.0));
-    }
+    fn test_debug_overlay_and_frame_times() {
+        let mut app = App::new(test_config());
+        app.toggle_debug_overlay();
+        assert_eq!(app.overlay, Overlay::Debug);
+        app.back();
+        assert_eq!(app.overlay, Overlay::None);
 
-    #[test]
-    fn test_chart_buffer_clear() {
-        let mut buf = ChartBuffer::new(10);
-        buf.push(1.0, 10.0);
-        buf.push(2.0, 20.0);
-        buf.clear();
-        assert!(buf.data.is_empty());
+        app.perf.record_frame(Duration::from_millis(10));
+        app.perf.record_frame(Duration::from_millis(30));
+        assert_eq!(app.perf.frames, 2);
+        assert_eq!(app.perf.last_frame, Duration::from_millis(30));
+        assert_eq!(app.perf.max_frame, Duration::from_millis(30));
+        assert_eq!(app.perf.mean_frame, Duration::from_millis(11));
     }
 
     #[test]
-    fn test_app_initial_state() {
-        let app = App::new(test_config());
-        assert_eq!(app.screen, Screen::Overview);
-        assert_eq!(app.overlay, Overlay::None);
-        assert!(app.positions.is_empty());
-        assert_eq!(app.selected_position, 0);
+    fn test_quit() {
+        let mut app = App::new(test_config());
         assert!(!app.should_quit);
+        app.quit();
+        assert!(app.should_quit);
     }
 
     #[test]
-    fn test_select_next_empty() {
+    fn test_set_error_shows_toast() {
         let mut app = App::new(test_config());
-        app.select_next();
-        assert_eq!(app.selected_position, 0);
+        app.toggle_help();
+        app.set_error("Something went wrong".into());
+        assert_eq!(app.errors.back().unwrap().message, "Something went wrong");
+        // Transient errors leave the current overlay alone.
+        assert_eq!(app.overlay, Overlay::Help);
+        assert_eq!(app.toasts.len(), 1);
+        assert_eq!(app.toasts[0].level, ToastLevel::Error);
+        assert_eq!(app.toasts[0].message, "Something went wrong");
     }
 
     #[test]
-    fn test_select_next_wraps() {
+    fn test_error_log_keeps_recent_errors() {
         let mut app = App::new(test_config());
-        app.positions = vec![test_position("A"), test_position("B"), test_position("C")];
-        app.selected_position = 2;
-        app.select_next();
-        assert_eq!(app.selected_position, 0);
+        app.apply(&BusEvent::Alert {
+            position: Some("X1".into()),
+            message: "Failed to load run history".into(),
+        });
+        app.apply(&BusEvent::Alert {
+            position: Some("X1".into()),
+            message: "Failed to load run history".into(),
+        });
+        app.apply(&BusEvent::RunStateChanged {
+            position: "X2".into(),
+            state: RunState::Error("Flow cell overheated".into()),
+        });
+        assert_eq!(app.errors.len(), 2);
+        assert_eq!(app.errors[0].position.as_deref(), Some("X1"));
+        assert_eq!(app.errors[0].count, 2);
+        assert_eq!(app.errors[1].message, "Flow cell overheated");
+
+        for i in 0..MAX_ERROR_LOG {
+            app.set_error(format!("error {}", i));
+        }
+        assert_eq!(app.errors.len(), MAX_ERROR_LOG);
+        assert_eq!(app.errors[0].message, "error 0");
+
+        app.toggle_error_log();
+        app.scroll_error_log(5);
+        app.scroll_error_log(-2);
+        assert_eq!(app.overlay, Overlay::ErrorLog { scroll: 3 });
+        app.scroll_error_log(isize::MAX);
+        assert_eq!(
+            app.overlay,
+            Overlay::ErrorLog {
+                scroll: MAX_ERROR_LOG - 1
+            }
+        );
+        app.toggle_error_log();
+        assert_eq!(app.overlay, Overlay::None);
     }
 
     #[test]
-    fn test_select_previous_empty() {
+    fn test_set_fatal_error() {
         let mut app = App::new(test_config());
-        app.select_previous();
-        assert_eq!(app.selected_position, 0);
+        app.set_fatal_error("Something went wrong".into());
+        assert_eq!(app.errors.back().unwrap().message, "Something went wrong");
+        assert!(app.toasts.is_empty());
+        assert_eq!(
+            app.overlay,
+            Overlay::Error {
+                message: "Something went wrong".into()
+            }
+        );
     }
 
     #[test]
-    fn test_select_previous_wraps() {
+    fn test_update_positions_adjusts_selection() {
         let mut app = App::new(test_config());
         app.positions = vec![test_position("A"), test_position("B"), test_position("C")];
-        app.selected_position = 0;
-        app.select_previous();
-        assert_eq!(app.selected_position, 2);
+        app.select_position(2);
+        app.update_positions(vec![test_position("X")]);
+        // Nothing else is highlighted in place of the removed position.
+        assert_eq!(app.selected_index(), None);
+        assert!(app.selected_position().is_none());
+        app.select_next();
+        assert_eq!(app.selected_position().unwrap().name, "X");
     }
 
     #[test]
-    fn test_enter_detail_with_run() {
+    fn test_step_detail_position_skips_inactive() {
         let mut app = App::new(test_config());
-        app.positions = vec![test_position("A")];
+        app.update_positions(vec![
+            test_position("A"),
+            test_position("B"),
+            test_position("C"),
+        ]);
         app.run_states.insert("A".to_string(), RunState::Running);
+        app.run_states.insert("C".to_string(), RunState::Paused);
         app.enter_detail();
+        app.set_detail_chart(DetailChart::Barcodes);
+
+        app.step_detail_position(true);
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 2 });
+        assert_eq!(app.selected_position().unwrap().name, "C");
+        assert_eq!(app.detail_chart, DetailChart::Barcodes);
+        app.step_detail_position(true);
         assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+        app.step_detail_position(false);
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 2 });
+
+        // Only on the detail screen.
+        app.back();
+        app.step_detail_position(true);
+        assert_eq!(app.screen, Screen::Overview);
     }
 
     #[test]
-    fn test_enter_detail_no_run() {
+    fn test_selection_follows_position_across_relisting() {
         let mut app = App::new(test_config());
-        app.positions = vec![test_position("A")];
+        app.update_positions(vec![
+            test_position("A"),
+            test_position("B"),
+            test_position("C"),
+        ]);
+        app.select_position(1);
+        app.run_states.insert("B".to_string(), RunState::Running);
         app.enter_detail();
+
+        // Re-listed in a different order with A gone.
+        app.update_positions(vec![test_position("C"), test_position("B")]);
+        assert_eq!(app.selected_position().unwrap().name, "B");
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+
+        // The viewed position goes away too.
+        app.update_positions(vec![test_position("C")]);
+        assert!(app.selected_position().is_none());
         assert_eq!(app.screen, Screen::Overview);
+        app.request_run_control(RunControlAction::Stop(StopOptions::default()));
+        assert_eq!(app.overlay, Overlay::None);
+
+        // It comes back selected.
+        app.update_positions(vec![test_position("B"), test_position("C")]);
+        assert_eq!(app.selected_position().unwrap().name, "B");
     }
 
     #[test]
-    fn test_enter_detail_empty() {
+    fn test_positions_changed_applies_incrementally() {
         let mut app = App::new(test_config());
-        app.enter_detail();
-        assert_eq!(app.screen, Screen::Overview);
+        app.update_positions(vec![test_position("A"), test_position("B")]);
+        app.select_position(1);
+
+        let mut failed = test_position("B");
+        failed.state = PositionState::Error;
+        app.apply(&BusEvent::PositionsChanged {
+            updated: vec![failed, test_position("C")],
+            removed: vec!["A".into()],
+        });
+        let names: Vec<_> = app.positions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["B", "C"]);
+        assert_eq!(app.positions[0].state, PositionState::Error);
+        // The selection stays on B.
+        assert_eq!(app.selected_index(), Some(0));
     }
 
     #[test]
-    fn test_back_from_detail() {
+    fn test_overview_sort_follows_selection() {
         let mut app = App::new(test_config());
-        app.screen = Screen::PositionDetail { position_idx: 0 };
-        app.back();
-        assert_eq!(app.screen, Screen::Overview);
+        app.update_positions(vec![
+            test_position("C"),
+            test_position("A"),
+            test_position("B"),
+        ]);
+        let names =
+            |app: &App| -> Vec<String> { app.positions.iter().map(|p| p.name.clone()).collect() };
+        assert_eq!(names(&app), ["A", "B", "C"]);
+
+        for (name, bases) in [("A", 100), ("B", 300)] {
+            app.update_run_state(name, RunState::Running);
+            app.apply(&BusEvent::Stats {
+                position: name.to_string(),
+                stats: StatsSnapshot {
+                    bases_called: bases,
+                    ..Default::default()
+                },
+            });
+        }
+        app.select_position(0);
+        app.enter_detail();
+        assert_eq!(app.selected_position().unwrap().name, "A");
+
+        // Highest yield first; C has no run so stays last in both directions.
+        app.cycle_overview_sort();
+        app.cycle_overview_sort();
+        assert_eq!(app.overview_sort, OverviewSort::Yield);
+        assert_eq!(names(&app), ["B", "A", "C"]);
+        assert_eq!(app.selected_position().unwrap().name, "A");
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 1 });
+
+        app.reverse_overview_sort();
+        assert_eq!(names(&app), ["A", "B", "C"]);
+
+        // Refreshing with the server's order keeps the sort.
+        app.update_positions(vec![
+            test_position("C"),
+            test_position("B"),
+            test_position("A"),
+        ]);
+        assert_eq!(names(&app), ["A", "B", "C"]);
+        assert_eq!(app.selected_index(), Some(0));
     }
 
     #[test]
-    fn test_back_closes_overlay() {
+    fn test_pinned_positions_sort_first() {
+        let mut config = test_config();
+        config.tui.pinned = vec!["C".into()];
+        let mut app = App::new(config);
+        app.update_positions(vec![
+            test_position("A"),
+            test_position("B"),
+            test_position("C"),
+        ]);
+        let names =
+            |app: &App| -> Vec<String> { app.positions.iter().map(|p| p.name.clone()).collect() };
+        assert_eq!(names(&app), ["C", "A", "B"]);
+
+        // Pinning keeps the selection on the pinned position.
+        app.select_position(2);
+        assert!(app.toggle_pin());
+        assert_eq!(app.pinned, ["C", "B"]);
+        assert_eq!(names(&app), ["B", "C", "A"]);
+        assert_eq!(app.selected_position().unwrap().name, "B");
+
+        // Pins stay on top whichever way the rest is sorted.
+        app.reverse_overview_sort();
+        assert_eq!(names(&app), ["C", "B", "A"]);
+
+        app.select_position(0);
+        assert!(app.toggle_pin());
+        assert_eq!(app.pinned, ["B"]);
+        assert_eq!(names(&app), ["B", "C", "A"]);
+    }
+
+    #[test]
+    fn test_edit_position_note() {
+        use crossterm::event::KeyCode;
+
         let mut app = App::new(test_config());
-        app.overlay = Overlay::Help;
-        app.back();
+        app.notes.insert("A".into(), "library B".into());
+        app.update_positions(vec![test_position("A")]);
+        app.select_position(0);
+
+        app.open_note();
+        assert_eq!(
+            app.overlay,
+            Overlay::Note {
+                position_name: "A".into(),
+                text: "library B".into()
+            }
+        );
+        app.paste(", flush at 24h");
+        assert!(!app.handle_note_key(KeyCode::Char('!')) && app.handle_note_key(KeyCode::Enter));
+        assert_eq!(app.notes["A"], "library B, flush at 24h!");
         assert_eq!(app.overlay, Overlay::None);
+
+        // Saving unchanged changes nothing; an empty note is removed.
+        app.open_note();
+        assert!(!app.handle_note_key(KeyCode::Enter));
+        app.open_note();
+        app.paste(&"x".repeat(MAX_NOTE_LEN));
+        if let Overlay::Note { text, .. } = &app.overlay {
+            assert_eq!(text.chars().count(), MAX_NOTE_LEN);
+        }
+        app.handle_note_key(KeyCode::Esc);
+        assert_eq!(app.notes["A"], "library B, flush at 24h!");
+        app.open_note();
+        for _ in 0..MAX_NOTE_LEN {
+            app.handle_note_key(KeyCode::Backspace);
+        }
+        assert!(app.handle_note_key(KeyCode::Enter));
+        assert!(app.notes.is_empty());
     }
 
     #[test]
-    fn test_toggle_help() {
+    fn test_hide_simulated_and_idle_positions() {
+        let mut config = test_config();
+        config.tui.hide_simulated = true;
+        let mut app = App::new(config);
+        let simulated = Position {
+            is_simulated: true,
+            ..test_position("MS1")
+        };
+        app.update_positions(vec![test_position("A"), test_position("B"), simulated]);
+        app.update_run_state("A", RunState::Running);
+        let visible = |app: &App| -> Vec<String> {
+            app.visible_positions()
+                .into_iter()
+                .map(|idx| app.positions[idx].name.clone())
+                .collect()
+        };
+        assert_eq!(visible(&app), ["A", "B"]);
+
+        app.toggle_hide_idle();
+        assert_eq!(visible(&app), ["A"]);
+
+        // Pinned positions are always shown.
+        app.pinned.push("B".into());
+        assert_eq!(visible(&app), ["A", "B"]);
+
+        app.toggle_hide_simulated();
+        app.toggle_hide_idle();
+        assert_eq!(visible(&app), ["A", "B", "MS1"]);
+    }
+
+    #[test]
+    fn test_clock_at_counts_from_acquisition_start() {
         let mut app = App::new(test_config());
-        app.toggle_help();
-        assert_eq!(app.overlay, Overlay::Help);
-        app.toggle_help();
-        assert_eq!(app.overlay, Overlay::None);
+        assert!(!app.clock_time);
+        assert_eq!(app.clock_at("X1", 60.0), None);
+
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        app.update_stats(
+            "X1",
+            StatsSnapshot {
+                acquisition_start: Some(start),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            app.clock_at("X1", 90.5),
+            Some(start + chrono::Duration::milliseconds(90_500))
+        );
+        app.toggle_clock_time();
+        assert!(app.clock_time);
     }
 
     #[test]
-    fn test_quit() {
+    fn test_freeze_holds_data_until_unfrozen() {
         let mut app = App::new(test_config());
+        let stats = |reads| BusEvent::Stats {
+            position: "X1".to_string(),
+            stats: StatsSnapshot {
+                reads_processed: reads,
+                ..Default::default()
+            },
+        };
+        app.apply(&stats(10));
+        app.toggle_freeze();
+        assert!(app.frozen_at.is_some());
+
+        app.apply(&stats(20));
+        app.apply(&stats(30));
+        app.apply(&BusEvent::Reconnecting { attempt: 2 });
+        assert_eq!(app.stats_cache["X1"].reads_processed, 10);
+        // Connection changes are not held back.
+        assert!(matches!(
+            app.connection,
+            ConnectionState::Reconnecting { attempt: 2, .. }
+        ));
+
+        app.toggle_freeze();
+        assert!(app.frozen_at.is_none());
+        assert_eq!(app.stats_cache["X1"].reads_processed, 30);
+        assert!(app.held.is_empty());
+    }
+
+    #[test]
+    fn test_paste_types_into_text_fields_only() {
+        let mut app = App::new(test_config());
+        app.update_positions(vec![test_position("X1"), test_position("X2")]);
+
+        // Pasted text is not run as key commands.
+        app.paste("q");
         assert!(!app.should_quit);
-        app.quit();
-        assert!(app.should_quit);
+
+        app.open_filter();
+        app.paste("x2\n");
+        assert_eq!(app.position_filter, "x2");
+        assert_eq!(app.overlay, Overlay::Filter);
     }
 
     #[test]
-    fn test_set_error() {
+    fn test_pause_unfocused_only_when_configured() {
         let mut app = App::new(test_config());
-        app.set_error("Something went wrong".into());
-        assert_eq!(app.last_error, Some("Something went wrong".into()));
-        assert_eq!(
-            app.overlay,
-            Overlay::Error {
-                message: "Something went wrong".into()
-            }
-        );
+        app.set_focused(false);
+        assert!(!app.is_paused());
+
+        app.config.tui.pause_unfocused = true;
+        assert!(app.is_paused());
+        app.set_focused(true);
+        assert!(!app.is_paused());
     }
 
     #[test]
-    fn test_update_positions_adjusts_selection() {
+    fn test_filter_narrows_positions_and_keeps_selection() {
+        use crossterm::event::KeyCode;
+
         let mut app = App::new(test_config());
-        app.positions = vec![test_position("A"), test_position("B"), test_position("C")];
-        app.selected_position = 2;
-        app.update_positions(vec![test_position("X")]);
-        assert_eq!(app.selected_position, 0);
+        app.update_positions(vec![
+            test_position("X1"),
+            test_position("X2"),
+            test_position("P2S_01A"),
+        ]);
+        app.update_flow_cell_info(
+            "X2",
+            FlowCellInfo {
+                has_flow_cell: true,
+                flow_cell_id: Some("FAW12345".into()),
+                ..Default::default()
+            },
+        );
+        app.select_position(0);
+        assert_eq!(app.selected_position().unwrap().name, "P2S_01A");
+
+        app.open_filter();
+        assert_eq!(app.overlay, Overlay::Filter);
+        for c in "x".chars() {
+            app.handle_filter_key(KeyCode::Char(c));
+        }
+        // The selection moves off the hidden row onto the first match.
+        assert_eq!(app.visible_positions(), [1, 2]);
+        assert_eq!(app.selected_position().unwrap().name, "X1");
+
+        app.handle_filter_key(KeyCode::Down);
+        app.handle_filter_key(KeyCode::Down);
+        assert_eq!(app.selected_position().unwrap().name, "X1");
+
+        // Flow cell IDs match too, case-insensitively.
+        app.handle_filter_key(KeyCode::Backspace);
+        for c in "faw".chars() {
+            app.handle_filter_key(KeyCode::Char(c));
+        }
+        assert_eq!(app.selected_position().unwrap().name, "X2");
+        app.select_row(0);
+        assert_eq!(app.selected_position().unwrap().name, "X2");
+
+        app.handle_filter_key(KeyCode::Enter);
+        assert_eq!(app.overlay, Overlay::None);
+        assert_eq!(app.position_filter, "faw");
+
+        app.back();
+        assert!(app.position_filter.is_empty());
+        assert_eq!(app.visible_positions().len(), 3);
+        assert_eq!(app.selected_position().unwrap().name, "X2");
     }
 
     #[test]
@@ -727,7 +4408,7 @@ mod tests {
         assert!(app.selected_position().is_none());
 
         app.positions = vec![test_position("A"), test_position("B")];
-        app.selected_position = 1;
+        app.select_position(1);
         assert_eq!(app.selected_position().unwrap().name, "B");
     }
 
@@ -752,27 +4433,151 @@ mod tests {
         app.cycle_detail_chart();
         assert_eq!(app.detail_chart, DetailChart::PoreActivity);
 
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::Barcodes);
+
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::Signal);
+
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::QScore);
+
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::PoreScans);
+
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::ActivePores);
+
+        app.cycle_detail_chart();
+        assert_eq!(app.detail_chart, DetailChart::DutyTime);
+
         app.cycle_detail_chart();
         assert_eq!(app.detail_chart, DetailChart::Yield);
     }
 
     #[test]
-    fn test_set_detail_chart() {
+    fn test_set_detail_chart() {
+        let mut app = App::new(test_config());
+        app.set_detail_chart(DetailChart::PoreActivity);
+        assert_eq!(app.detail_chart, DetailChart::PoreActivity);
+    }
+
+    fn barcode(name: &str, points: &[(u32, u64)]) -> BarcodeYield {
+        BarcodeYield {
+            barcode: name.to_string(),
+            points: points
+                .iter()
+                .map(|&(seconds, bases)| YieldDataPoint {
+                    seconds,
+                    bases,
+                    reads: bases / 1_000,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_barcode_yield_merge_and_sort() {
+        let mut app = App::new(test_config());
+        app.apply(&BusEvent::BarcodeYield {
+            position: "A".to_string(),
+            barcodes: vec![
+                barcode("barcode01", &[(60, 5_000)]),
+                barcode("barcode02", &[(60, 8_000)]),
+            ],
+            merge: false,
+        });
+        app.apply(&BusEvent::BarcodeYield {
+            position: "A".to_string(),
+            barcodes: vec![
+                barcode("barcode01", &[(120, 20_000)]),
+                barcode("barcode03", &[(120, 1_000)]),
+            ],
+            merge: true,
+        });
+
+        let names = |app: &App| -> Vec<String> {
+            app.sorted_barcodes("A")
+                .iter()
+                .map(|b| b.barcode.clone())
+                .collect()
+        };
+        assert_eq!(names(&app), ["barcode01", "barcode02", "barcode03"]);
+        assert_eq!(app.barcode_yield["A"][0].points.len(), 2);
+
+        app.cycle_barcode_sort();
+        assert_eq!(app.barcode_sort, BarcodeSort::Reads);
+        app.cycle_barcode_sort();
+        assert_eq!(app.barcode_sort, BarcodeSort::Name);
+        assert_eq!(names(&app), ["barcode01", "barcode02", "barcode03"]);
+
+        app.update_run_state("A", RunState::Idle);
+        assert!(app.sorted_barcodes("A").is_empty());
+    }
+
+    #[test]
+    fn test_toggle_yield_unit() {
+        let mut app = App::new(test_config());
+        assert_eq!(app.yield_unit, YieldUnit::Reads);
+
+        app.toggle_yield_unit();
+        assert_eq!(app.yield_unit, YieldUnit::Bases);
+
+        app.toggle_yield_unit();
+        assert_eq!(app.yield_unit, YieldUnit::Reads);
+    }
+
+    #[test]
+    fn test_yield_rates_and_dual_axis_are_exclusive() {
+        let mut app = App::new(test_config());
+        app.toggle_yield_dual_axis();
+        app.toggle_yield_rates();
+        assert!(app.yield_rates);
+        assert!(!app.yield_dual_axis);
+        app.toggle_yield_dual_axis();
+        assert!(!app.yield_rates);
+        assert!(app.yield_dual_axis);
+    }
+
+    #[test]
+    fn test_toggle_yield_series() {
         let mut app = App::new(test_config());
-        app.set_detail_chart(DetailChart::PoreActivity);
-        assert_eq!(app.detail_chart, DetailChart::PoreActivity);
+        assert!(app.yield_series.is_visible(YieldSeries::Failed));
+
+        app.toggle_yield_series(YieldSeries::Failed);
+        assert!(!app.yield_series.is_visible(YieldSeries::Failed));
+        assert!(app.yield_series.is_visible(YieldSeries::Total));
+        assert!(app.yield_series.is_visible(YieldSeries::Passed));
+
+        app.toggle_yield_series(YieldSeries::Failed);
+        assert_eq!(app.yield_series, SeriesVisibility::default());
     }
 
     #[test]
-    fn test_toggle_yield_unit() {
+    fn test_yield_window_limits_points() {
         let mut app = App::new(test_config());
-        assert_eq!(app.yield_unit, YieldUnit::Reads);
+        let data = (0..=180)
+            .map(|minute| YieldDataPoint {
+                seconds: minute * 60,
+                ..Default::default()
+            })
+            .collect();
+        app.update_yield_history("X1", data);
+        assert_eq!(app.yield_points("X1").unwrap().len(), 181);
 
-        app.toggle_yield_unit();
-        assert_eq!(app.yield_unit, YieldUnit::Bases);
+        app.cycle_yield_window();
+        assert_eq!(app.yield_window, YieldWindow::LastHour);
+        let points = app.yield_points("X1").unwrap();
+        assert_eq!(points.len(), 61);
+        assert_eq!(points[0].seconds, 7200);
 
-        app.toggle_yield_unit();
-        assert_eq!(app.yield_unit, YieldUnit::Reads);
+        // A window longer than the run shows all of it.
+        app.cycle_yield_window();
+        assert_eq!(app.yield_points("X1").unwrap().len(), 181);
+        app.cycle_yield_window();
+        app.cycle_yield_window();
+        assert_eq!(app.yield_window, YieldWindow::Full);
     }
 
     #[test]
@@ -787,6 +4592,147 @@ mod tests {
         assert!(app.exclude_outliers);
     }
 
+    #[test]
+    fn test_histogram_settings_form() {
+        use crossterm::event::KeyCode;
+
+        let mut app = App::new(test_config());
+        app.open_range_input();
+        assert_eq!(app.overlay, Overlay::None, "only on the read length chart");
+
+        app.set_detail_chart(DetailChart::ReadLength);
+        app.open_range_input();
+        app.paste("2000");
+        app.handle_range_input_key(KeyCode::Tab);
+        app.paste("1000");
+        assert!(!app.apply_range_input(), "min must be below max");
+        assert!(matches!(&app.overlay, Overlay::RangeInput(form) if form.invalid));
+
+        for _ in 0..4 {
+            app.handle_range_input_key(KeyCode::Backspace);
+        }
+        app.paste("5000");
+        app.handle_range_input_key(KeyCode::Tab);
+        app.paste("1");
+        assert!(!app.apply_range_input());
+
+        app.paste("00");
+        app.handle_range_input_key(KeyCode::Tab);
+        assert!(matches!(&app.overlay, Overlay::RangeInput(form) if form.outlier_percent == "1"));
+        app.handle_range_input_key(KeyCode::Up);
+        assert!(app.apply_range_input());
+        assert_eq!(app.histogram_range, Some((2000, 5000)));
+        assert_eq!(app.histogram_buckets, Some(100));
+        assert_eq!(app.outlier_percent, 1.5);
+        assert_eq!(app.overlay, Overlay::None);
+
+        // Empty fields fall back to the full range and MinKNOW's buckets,
+        // but a min length needs a max.
+        app.open_range_input();
+        assert!(matches!(&app.overlay, Overlay::RangeInput(form) if form.min_length == "2000"));
+        app.handle_range_input_key(KeyCode::Tab);
+        for _ in 0..4 {
+            app.handle_range_input_key(KeyCode::Backspace);
+        }
+        app.handle_range_input_key(KeyCode::Tab);
+        for _ in 0..3 {
+            app.handle_range_input_key(KeyCode::Backspace);
+        }
+        assert!(!app.apply_range_input());
+        app.handle_range_input_key(KeyCode::BackTab);
+        app.handle_range_input_key(KeyCode::BackTab);
+        for _ in 0..4 {
+            app.handle_range_input_key(KeyCode::Backspace);
+        }
+        assert!(app.apply_range_input());
+        assert_eq!(app.histogram_range, None);
+        assert_eq!(app.histogram_buckets, None);
+    }
+
+    #[test]
+    fn test_toggle_histogram_weight() {
+        let mut app = App::new(test_config());
+        assert!(!app.histogram_by_bases);
+
+        app.toggle_histogram_weight();
+        assert!(app.histogram_by_bases);
+    }
+
+    #[test]
+    fn test_yield_projection_uses_expected_run_length() {
+        let mut config = test_config();
+        config.tui.expected_run_hours = 2.0;
+        let mut app = App::new(config);
+        assert!(app
+            .yield_projection("X1", YieldUnit::Bases, ProjectionModel::Linear)
+            .is_none());
+
+        let data = (0..=60)
+            .map(|minute| YieldDataPoint {
+                seconds: minute * 60,
+                reads: minute as u64 * 10,
+                bases: minute as u64 * 60_000,
+                ..Default::default()
+            })
+            .collect();
+        app.update_yield_history("X1", data);
+
+        let bases = app
+            .yield_projection("X1", YieldUnit::Bases, ProjectionModel::Linear)
+            .unwrap();
+        assert_eq!(bases.remaining_seconds, 3600);
+        assert_eq!(bases.final_total, 7_200_000);
+        let reads = app
+            .yield_projection("X1", YieldUnit::Reads, ProjectionModel::Linear)
+            .unwrap();
+        assert_eq!(reads.final_total, 1200);
+    }
+
+    #[test]
+    fn test_run_remaining_from_targets_and_projection() {
+        let mut config = test_config();
+        config.tui.expected_run_hours = 2.0;
+        let mut app = App::new(config);
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        app.update_stats(
+            "X1",
+            StatsSnapshot {
+                timestamp: Some(start + chrono::Duration::hours(1)),
+                acquisition_start: Some(start),
+                ..Default::default()
+            },
+        );
+        assert_eq!(app.run_elapsed("X1"), Some(chrono::Duration::hours(1)));
+        assert_eq!(app.run_remaining("X1"), Some(chrono::Duration::hours(1)));
+
+        let mut targets = RunUntilCriteria {
+            runtime: Some(5400),
+            ..Default::default()
+        };
+        app.apply(&BusEvent::RunUntilTargets {
+            position: "X1".to_string(),
+            targets,
+        });
+        assert_eq!(app.run_remaining("X1"), Some(chrono::Duration::minutes(30)));
+
+        // 1,000 bases per second reaches a 4.2 Mb target ten minutes out.
+        let data = (0..=60)
+            .map(|minute| YieldDataPoint {
+                seconds: minute * 60,
+                bases: minute as u64 * 60_000,
+                ..Default::default()
+            })
+            .collect();
+        app.update_yield_history("X1", data);
+        targets.estimated_bases = Some(4_200_000);
+        app.apply(&BusEvent::RunUntilTargets {
+            position: "X1".to_string(),
+            targets,
+        });
+        let remaining = app.run_remaining("X1").unwrap().num_seconds();
+        assert!((600..700).contains(&remaining), "{remaining}");
+    }
+
     #[test]
     fn test_update_yield_history() {
         let mut app = App::new(test_config());
@@ -823,6 +4769,7 @@ mod tests {
             n50: 1500.0,
             outliers_excluded: false,
             outlier_percent: 0.0,
+            weighted_by_bases: false,
             requested_range: None,
             source_data_end: 2000,
         };
@@ -837,12 +4784,38 @@ mod tests {
             time_range: (0, 60),
             state_times: std::collections::HashMap::new(),
             pore_occupancy: vec![0.5, 0.8, 0.3],
+            buckets: Vec::new(),
         };
         app.update_duty_time("X1", duty_time);
         assert!(app.duty_time.contains_key("X1"));
         assert_eq!(app.duty_time.get("X1").unwrap().pore_occupancy.len(), 3);
     }
 
+    #[test]
+    fn test_duty_time_buckets_accumulate() {
+        let bucket = |start: u32, strand: u64| DutyTimeBucket {
+            start,
+            end: start + 60,
+            state_times: [(ChannelState::Strand, strand)].into_iter().collect(),
+        };
+        let snapshot = |buckets: Vec<DutyTimeBucket>| DutyTimeSnapshot {
+            buckets,
+            ..Default::default()
+        };
+        let mut app = App::new(test_config());
+        app.update_duty_time("X1", snapshot(vec![bucket(0, 10), bucket(60, 20)]));
+        // A later update revises the open bucket and adds a new one.
+        app.update_duty_time("X1", snapshot(vec![bucket(60, 25), bucket(120, 30)]));
+
+        let history = &app.duty_history["X1"];
+        let starts: Vec<_> = history.iter().map(|b| b.start).collect();
+        assert_eq!(starts, [0, 60, 120]);
+        assert_eq!(history[1], bucket(60, 25));
+
+        app.update_run_state("X1", RunState::Idle);
+        assert!(!app.duty_history.contains_key("X1"));
+    }
+
     #[test]
     fn test_scroll_offset_initializes_to_zero() {
         let app = App::new(test_config());
@@ -940,4 +4913,669 @@ mod tests {
         app.channel_map_scroll_offset = app.channel_map_scroll_offset.saturating_sub(1);
         assert_eq!(app.channel_map_scroll_offset, 0); // unchanged
     }
+
+    fn channel_states(states: &[&str]) -> ChannelStatesSnapshot {
+        ChannelStatesSnapshot {
+            channel_count: states.len(),
+            states: states.iter().map(|s| s.to_string()).collect(),
+            state_counts: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_channel_cursor_moves_over_layout() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.detail_chart = DetailChart::PoreActivity;
+        // 3×2 grid with no channel at (1, 0).
+        app.update_channel_layout(
+            "X1",
+            ChannelLayout {
+                channel_count: 5,
+                width: 3,
+                height: 2,
+                coords: vec![(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)],
+            },
+        );
+
+        app.move_channel_cursor(1, 0);
+        assert_eq!(app.channel_cursor, None);
+
+        app.toggle_channel_inspector();
+        assert_eq!(app.channel_cursor, Some(0));
+        app.move_channel_cursor(1, 0);
+        assert_eq!(app.channel_cursor, Some(1), "skips the empty cell");
+        app.move_channel_cursor(1, 0);
+        assert_eq!(app.channel_cursor, Some(1), "stays at the edge");
+        app.move_channel_cursor(0, 1);
+        assert_eq!(app.channel_cursor, Some(4));
+        app.move_channel_cursor(-1, 0);
+        assert_eq!(app.channel_cursor, Some(3));
+
+        app.back();
+        assert_eq!(app.channel_cursor, None);
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+
+        app.inspect_channel(2);
+        assert_eq!(app.channel_cursor, Some(2));
+        app.set_detail_chart(DetailChart::Yield);
+        assert_eq!(app.channel_cursor, None);
+        app.toggle_channel_inspector();
+        assert_eq!(app.channel_cursor, None);
+    }
+
+    #[test]
+    fn test_chart_cursor_moves_over_yield_points() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.detail_chart = DetailChart::Yield;
+        let data = (0..=300)
+            .map(|minute| YieldDataPoint {
+                seconds: minute * 60,
+                bases: minute as u64 * 1000,
+                ..Default::default()
+            })
+            .collect();
+        app.update_yield_history("X1", data);
+
+        app.toggle_chart_cursor();
+        assert_eq!(app.chart_cursor, Some(ChartCursor::Yield(18_000)));
+        app.move_chart_cursor(1);
+        assert_eq!(app.chart_cursor, Some(ChartCursor::Yield(18_000)));
+        // 301 points move in strides of 3.
+        app.move_chart_cursor(-2);
+        assert_eq!(app.chart_cursor, Some(ChartCursor::Yield(17_640)));
+
+        app.back();
+        assert_eq!(app.chart_cursor, None);
+        assert_eq!(app.screen, Screen::PositionDetail { position_idx: 0 });
+
+        app.set_detail_chart(DetailChart::QScore);
+        app.qscore_histograms.insert(
+            "X1".to_string(),
+            QScoreHistogram {
+                bucket_ranges: vec![(0.0, 5.0), (5.0, 10.0), (10.0, 15.0)],
+                bucket_values: vec![1, 20, 5],
+                modal_q_score: 7.5,
+            },
+        );
+        app.toggle_chart_cursor();
+        assert_eq!(app.chart_cursor, Some(ChartCursor::QScore(1)));
+        app.move_chart_cursor(5);
+        assert_eq!(app.chart_cursor, Some(ChartCursor::QScore(2)));
+        app.cycle_detail_chart();
+        assert_eq!(app.chart_cursor, None);
+    }
+
+    #[test]
+    fn test_channel_cursor_without_layout_uses_square_grid() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.detail_chart = DetailChart::PoreActivity;
+        app.update_channel_states("X1", channel_states(&["pore"; 5]));
+
+        app.inspect_channel(1);
+        app.move_channel_cursor(0, 1);
+        assert_eq!(app.channel_cursor, Some(4));
+        app.move_channel_cursor(1, 0);
+        assert_eq!(app.channel_cursor, Some(4), "no channel 6 in a 3×3 grid");
+    }
+
+    #[test]
+    fn test_signal_chart_follows_inspected_channel() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.detail_chart = DetailChart::PoreActivity;
+        app.update_channel_states("X1", channel_states(&["pore"; 5]));
+
+        app.inspect_channel(3);
+        app.set_detail_chart(DetailChart::Signal);
+        assert_eq!(app.signal_channel, 4);
+        assert_eq!(app.channel_cursor, None);
+
+        app.step_signal_channel(1);
+        assert_eq!(app.signal_channel, 5);
+        app.step_signal_channel(1);
+        assert_eq!(app.signal_channel, 5, "stays within the channel count");
+        app.step_signal_channel(-10);
+        assert_eq!(app.signal_channel, 1);
+    }
+
+    #[test]
+    fn test_signal_trace_keeps_recent_samples_of_one_channel() {
+        let mut app = App::new(test_config());
+        let chunk = |channel, picoamps: Vec<f32>| BusEvent::Signal {
+            position: "X1".to_string(),
+            chunk: SignalChunk {
+                channel,
+                samples_since_start: 0,
+                picoamps,
+            },
+        };
+
+        assert!(app.signal_trace("X1").is_none());
+        app.apply(&chunk(1, vec![200.0; SIGNAL_WINDOW - 10]));
+        app.apply(&chunk(1, vec![90.0; 20]));
+        let trace = app.signal_trace("X1").unwrap();
+        assert_eq!(trace.len(), SIGNAL_WINDOW);
+        assert_eq!(trace.back(), Some(&90.0));
+
+        // Samples from another channel replace the trace and are hidden
+        // until the chart switches to that channel.
+        app.apply(&chunk(2, vec![100.0; 5]));
+        assert!(app.signal_trace("X1").is_none());
+        app.signal_channel = 2;
+        assert_eq!(app.signal_trace("X1").unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_channel_occupancy_tracks_recent_updates() {
+        let mut app = App::new(test_config());
+        app.update_channel_states("X1", channel_states(&["strand", "pore"]));
+        app.update_channel_states("X1", channel_states(&["pore", "pore"]));
+        app.update_channel_states("X1", channel_states(&["strand", "strand"]));
+
+        let occupancy = &app.channel_occupancy["X1"];
+        assert_eq!(occupancy.recent(0), vec![true, false, true]);
+        assert_eq!(occupancy.fraction(0), Some(2.0 / 3.0));
+        assert_eq!(occupancy.fraction(1), Some(1.0 / 3.0));
+        assert_eq!(occupancy.fraction(2), None);
+
+        for _ in 0..OCCUPANCY_WINDOW {
+            app.update_channel_states("X1", channel_states(&["strand", "pore"]));
+        }
+        let occupancy = &app.channel_occupancy["X1"];
+        assert_eq!(occupancy.recent(0).len(), OCCUPANCY_WINDOW);
+        assert_eq!(occupancy.fraction(0), Some(1.0));
+        assert_eq!(occupancy.fraction(1), Some(0.0));
+
+        app.update_run_state("X1", RunState::Idle);
+        assert!(!app.channel_occupancy.contains_key("X1"));
+    }
+
+    #[test]
+    fn test_channel_timelapse_thins_frames_to_span_the_run() {
+        let start = chrono::Utc::now();
+        let mut timelapse = ChannelTimelapse::default();
+        timelapse.record(&channel_states(&["strand", "pore"]), start);
+        // Updates within the interval are not kept.
+        timelapse.record(
+            &channel_states(&["pore", "pore"]),
+            start + TIMELAPSE_INTERVAL / 2,
+        );
+        assert_eq!(timelapse.len(), 1);
+
+        for i in 1..=TIMELAPSE_FRAMES as i32 {
+            timelapse.record(
+                &channel_states(&["pore", "pore"]),
+                start + TIMELAPSE_INTERVAL * i,
+            );
+        }
+        assert_eq!(timelapse.len(), TIMELAPSE_FRAMES / 2 + 1);
+        assert_eq!(timelapse.time(0), Some(start));
+        assert_eq!(timelapse.time(1), Some(start + TIMELAPSE_INTERVAL * 2));
+        assert_eq!(timelapse.interval, TIMELAPSE_INTERVAL * 2);
+
+        let first = timelapse.snapshot(0).unwrap();
+        assert_eq!(first.states, vec!["strand", "pore"]);
+        assert_eq!(first.state_counts["pore"], 1);
+        assert_eq!(timelapse.index_at(start + TIMELAPSE_INTERVAL * 3), 1);
+        assert_eq!(timelapse.gap_before(1), None);
+
+        // Time away from the detail view shows as a gap.
+        let last = timelapse.time(timelapse.len() - 1).unwrap();
+        timelapse.record(
+            &channel_states(&["pore", "pore"]),
+            last + TIMELAPSE_INTERVAL * 30,
+        );
+        assert_eq!(
+            timelapse.gap_before(timelapse.len() - 1),
+            Some(TIMELAPSE_INTERVAL * 30)
+        );
+
+        // Another flow cell starts over.
+        timelapse.record(
+            &channel_states(&["pore"; 3]),
+            start + TIMELAPSE_INTERVAL * 1000,
+        );
+        assert_eq!(timelapse.len(), 1);
+    }
+
+    #[test]
+    fn test_timelapse_steps_back_from_live_and_returns() {
+        let mut app = App::new(test_config());
+        app.positions = vec![test_position("X1")];
+        app.select_position(0);
+        app.screen = Screen::PositionDetail { position_idx: 0 };
+        app.detail_chart = DetailChart::PoreActivity;
+        let start = chrono::Utc::now();
+        let timelapse = app.channel_timelapse.entry("X1".to_string()).or_default();
+        for (i, state) in ["strand", "pore", "unavailable"].into_iter().enumerate() {
+            timelapse.record(
+                &channel_states(&[state]),
+                start + TIMELAPSE_INTERVAL * i as i32,
+            );
+        }
+
+        app.step_timelapse(true);
+        assert!(app.timelapse_frame().is_none(), "already live");
+        app.step_timelapse(false);
+        assert_eq!(app.timelapse_frame().unwrap().index, 2);
+        app.step_timelapse(false);
+        app.step_timelapse(false);
+        app.step_timelapse(false);
+        let frame = app.timelapse_frame().unwrap();
+        assert_eq!((frame.index, frame.frames), (0, 3));
+        assert_eq!(frame.states.states, vec!["strand"]);
+
+        app.step_timelapse(true);
+        assert_eq!(app.timelapse_frame().unwrap().index, 1);
+        app.step_timelapse(true);
+        app.step_timelapse(true);
+        assert!(app.timelapse_frame().is_none(), "past the latest is live");
+
+        app.step_timelapse(false);
+        app.set_detail_chart(DetailChart::Yield);
+        assert!(app.timelapse_at.is_none());
+    }
+
+    #[test]
+    fn test_changed_stats_are_highlighted() {
+        let mut app = App::new(test_config());
+        let stats = |reads| BusEvent::Stats {
+            position: "X1".to_string(),
+            stats: StatsSnapshot {
+                reads_processed: reads,
+                ..Default::default()
+            },
+        };
+        app.apply(&stats(100));
+        assert!(!app.stat_changed("X1", StatValue::Reads), "first stats");
+
+        app.apply(&stats(100));
+        assert!(!app.stat_changed("X1", StatValue::Reads), "stalled");
+        app.apply(&stats(150));
+        assert!(app.stat_changed("X1", StatValue::Reads));
+        assert!(!app.stat_changed("X1", StatValue::Bases));
+
+        app.apply(&BusEvent::ActivePores {
+            position: "X1".to_string(),
+            count: 400,
+        });
+        assert!(app.stat_changed("X1", StatValue::ActivePores));
+
+        // Back-date the changes past the highlight.
+        for changed in app.stat_changes.get_mut("X1").unwrap().values_mut() {
+            *changed -= CHANGE_HIGHLIGHT;
+        }
+        app.dirty = false;
+        app.expire_stat_changes();
+        assert!(app.dirty);
+        assert!(!app.stat_changed("X1", StatValue::Reads));
+        assert!(app.stat_changes.is_empty());
+
+        app.config.tui.highlight_changes = false;
+        app.apply(&stats(200));
+        assert!(!app.stat_changed("X1", StatValue::Reads));
+    }
+
+    #[test]
+    fn test_active_pores_history_covers_chart_history() {
+        let mut config = test_config();
+        config.tui.chart_history = Duration::from_secs(5);
+        config.tui.refresh_interval = Duration::from_secs(1);
+        let mut app = App::new(config);
+        for i in 0..8 {
+            app.record_active_pores("X1", 500 - i * 10, i as f64);
+        }
+
+        let history = &app.pore_history["X1"].data;
+        assert_eq!(history.len(), 5);
+        assert_eq!(history.front(), Some(&(3.0, 470.0)));
+        assert_eq!(history.back(), Some(&(7.0, 430.0)));
+
+        app.apply(&BusEvent::ActivePores {
+            position: "X2".into(),
+            count: 300,
+        });
+        assert_eq!(app.pore_history["X2"].data.len(), 1);
+
+        app.update_run_state("X1", RunState::Idle);
+        assert!(!app.pore_history.contains_key("X1"));
+    }
+
+    fn yield_point(seconds: u32, bases: u64) -> YieldDataPoint {
+        YieldDataPoint {
+            seconds,
+            bases,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_yield_history_fills_gap() {
+        let mut app = App::new(test_config());
+        app.update_yield_history("X1", vec![yield_point(60, 100), yield_point(600, 900)]);
+
+        app.merge_yield_history(
+            "X1",
+            vec![
+                yield_point(60, 100),
+                yield_point(300, 500),
+                yield_point(600, 1000),
+            ],
+        );
+
+        let history = &app.yield_history["X1"];
+        let seconds: Vec<u32> = history.iter().map(|p| p.seconds).collect();
+        assert_eq!(seconds, vec![60, 300, 600]);
+        assert_eq!(history[2].bases, 1000); // backfilled value wins
+    }
+
+    #[test]
+    fn test_update_yield_history_is_bounded() {
+        let mut app = App::new(test_config());
+        let points: Vec<YieldDataPoint> = (0..YIELD_HISTORY_LIMIT as u32 + 1)
+            .map(|i| yield_point(i * 60, i as u64 * 1_000))
+            .collect();
+        app.update_yield_history("X1", points.clone());
+
+        let history = &app.yield_history["X1"];
+        assert_eq!(history.len(), YIELD_HISTORY_LIMIT / 2);
+        assert_eq!(history[0].seconds, 0);
+        assert_eq!(
+            history.last().unwrap().seconds,
+            points.last().unwrap().seconds
+        );
+    }
+
+    #[test]
+    fn test_merge_yield_history_into_empty() {
+        let mut app = App::new(test_config());
+        app.merge_yield_history("X1", vec![yield_point(60, 100)]);
+        assert_eq!(app.yield_history["X1"].len(), 1);
+    }
+
+    #[test]
+    fn test_merge_phase_history_dedups() {
+        let mut app = App::new(test_config());
+        let at = |secs| chrono::DateTime::from_timestamp(secs, 0).unwrap();
+        let event = |secs, phase: &str| PhaseEvent {
+            timestamp: at(secs),
+            phase: phase.to_string(),
+            kind: PhaseKind::Other,
+        };
+
+        app.merge_phase_history("X1", vec![event(10, "Initialising")]);
+        app.merge_phase_history(
+            "X1",
+            vec![event(10, "Initialising"), event(20, "Sequencing")],
+        );
+
+        let phases: Vec<&str> = app.phase_history["X1"]
+            .iter()
+            .map(|e| e.phase.as_str())
+            .collect();
+        assert_eq!(phases, vec!["Initialising", "Sequencing"]);
+    }
+
+    #[test]
+    fn test_inactive_run_clears_phase_history() {
+        let mut app = App::new(test_config());
+        app.merge_phase_history(
+            "X1",
+            vec![PhaseEvent {
+                timestamp: chrono::Utc::now(),
+                phase: "Sequencing".into(),
+                kind: PhaseKind::Other,
+            }],
+        );
+        app.update_run_state("X1", RunState::Idle);
+        assert!(!app.phase_history.contains_key("X1"));
+    }
+
+    #[test]
+    fn test_run_markers_relative_to_acquisition_start() {
+        let mut app = App::new(test_config());
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let event = |secs, phase: &str, kind| PhaseEvent {
+            timestamp: start + chrono::Duration::seconds(secs),
+            phase: phase.to_string(),
+            kind,
+        };
+        app.merge_phase_history(
+            "X1",
+            vec![
+                event(-60, "Initialising", PhaseKind::Other),
+                event(0, "Pore scan", PhaseKind::PoreScan),
+                event(300, "Sequencing", PhaseKind::Other),
+                event(3600, "Paused (temperature)", PhaseKind::TemperaturePause),
+                event(3900, "Paused", PhaseKind::Pause),
+            ],
+        );
+        assert!(app.run_markers("X1").is_empty(), "needs the start time");
+
+        app.update_stats(
+            "X1",
+            StatsSnapshot {
+                acquisition_start: Some(start),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            app.run_markers("X1"),
+            vec![
+                (0, PhaseKind::PoreScan),
+                (3600, PhaseKind::TemperaturePause),
+                (3900, PhaseKind::Pause),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_connection_failure_records_error_class() {
+        let mut app = App::new(test_config());
+        let err = ClientError::Connection {
+            endpoint: "https://localhost:9501".into(),
+            source: Box::new(std::io::Error::new(
+                std::io::ErrorKind::ConnectionRefused,
+                "refused",
+            )),
+        };
+        app.set_connection_failed(&err);
+        assert_eq!(app.connection_error_class, Some(ErrorClass::Refused));
+        assert!(matches!(
+            app.connection,
+            ConnectionState::Disconnected { .. }
+        ));
+
+        app.set_connected();
+        assert_eq!(app.connection_error_class, None);
+    }
+
+    #[test]
+    fn test_apply_stats_then_throughput() {
+        let mut app = App::new(test_config());
+        app.apply(&BusEvent::Stats {
+            position: "X1".into(),
+            stats: StatsSnapshot::default(),
+        });
+        app.apply(&BusEvent::Throughput {
+            position: "X1".into(),
+            bases_per_second: 1_000_000.0,
+        });
+
+        let stats = &app.stats_cache["X1"];
+        assert_eq!(stats.throughput_bps, 1_000_000.0);
+        assert!((stats.throughput_gbph - 3.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_alert_breach_raises_toast_once_and_clears() {
+        let mut config = test_config();
+        config.alerts.rules = vec![
+            "throughput_gbph < 0.5".parse().unwrap(),
+            "active_pores < 200".parse().unwrap(),
+        ];
+        let mut app = App::new(config);
+        app.apply(&BusEvent::RunStateChanged {
+            position: "X1".into(),
+            state: RunState::Running,
+        });
+        app.apply(&BusEvent::Stats {
+            position: "X1".into(),
+            stats: StatsSnapshot::default(),
+        });
+        // Stats alone only re-check read-based metrics.
+        assert!(app.position_alerts("X1").is_empty());
+
+        for _ in 0..2 {
+            app.apply(&BusEvent::Throughput {
+                position: "X1".into(),
+                bases_per_second: 100_000.0,
+            });
+        }
+        app.apply(&BusEvent::ActivePores {
+            position: "X1".into(),
+            count: 450,
+        });
+        assert_eq!(app.position_alerts("X1").len(), 1);
+        assert_eq!(app.toasts.len(), 1);
+        assert!(app.toasts[0]
+            .message
+            .contains("X1: throughput_gbph 0.36 < 0.5"));
+
+        app.apply(&BusEvent::Throughput {
+            position: "X1".into(),
+            bases_per_second: 1_000_000.0,
+        });
+        assert!(app.position_alerts("X1").is_empty());
+
+        app.apply(&BusEvent::ActivePores {
+            position: "X1".into(),
+            count: 50,
+        });
+        assert_eq!(app.position_alerts("X1").len(), 1);
+        app.apply(&BusEvent::RunStateChanged {
+            position: "X1".into(),
+            state: RunState::MuxScanning,
+        });
+        assert!(app.position_alerts("X1").is_empty());
+        app.apply(&BusEvent::ActivePores {
+            position: "X1".into(),
+            count: 10,
+        });
+        assert!(app.position_alerts("X1").is_empty());
+        assert_eq!(app.toasts.len(), 2);
+    }
+
+    #[test]
+    fn test_toast_queue_is_bounded_and_expires() {
+        let mut app = App::new(test_config());
+        for i in 0..(MAX_TOASTS + 2) {
+            app.push_toast(ToastLevel::Info, format!("toast {}", i));
+        }
+        assert_eq!(app.toasts.len(), MAX_TOASTS);
+        assert_eq!(app.toasts[0].message, "toast 2");
+
+        app.toasts[0].created = Instant::now() - ToastLevel::Info.duration();
+        app.expire_toasts();
+        assert_eq!(app.toasts.len(), MAX_TOASTS - 1);
+    }
+
+    #[test]
+    fn test_dirty_flag_tracks_changes() {
+        let mut app = App::new(test_config());
+        assert!(app.dirty);
+
+        app.dirty = false;
+        app.expire_toasts();
+        assert!(!app.dirty);
+
+        app.apply(&BusEvent::Connected);
+        assert!(app.dirty);
+
+        app.push_toast(ToastLevel::Info, "done".into());
+        app.toasts[0].created = Instant::now() - ToastLevel::Info.duration();
+        app.dirty = false;
+        app.expire_toasts();
+        assert!(app.dirty);
+    }
+
+    #[test]
+    fn test_apply_connection_events() {
+        let mut app = App::new(test_config());
+        app.apply(&BusEvent::Connected);
+        assert!(app.toasts.is_empty());
+        app.apply(&BusEvent::Disconnected {
+            reason: "refused".into(),
+            class: Some(ErrorClass::Refused),
+        });
+        assert!(!app.is_connected());
+        assert_eq!(app.connection_error_class, Some(ErrorClass::Refused));
+
+        app.apply(&BusEvent::Connected);
+        assert!(app.is_connected());
+        assert_eq!(app.connection_error_class, None);
+        assert_eq!(app.toasts.len(), 1);
+        assert_eq!(app.toasts[0].message, "Reconnected");
+    }
+
+    #[test]
+    fn test_multi_host_positions_and_connections() {
+        let mut config = test_config();
+        config.connection.hosts = vec!["localhost".into(), "localhost:19501".into()];
+        let mut app = App::new(config);
+        assert_eq!(app.hosts.len(), 2);
+
+        let host_positions = |host: &str, names: &[&str]| BusEvent::HostPositions {
+            host: host.into(),
+            positions: names
+                .iter()
+                .map(|name| test_position(name).on_host(host))
+                .collect(),
+        };
+        app.apply(&BusEvent::HostConnected {
+            host: "localhost".into(),
+        });
+        app.apply(&host_positions("localhost", &["X1", "X2"]));
+        app.apply(&host_positions("localhost:19501", &["X1"]));
+        let names: Vec<_> = app.positions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["localhost/X1", "localhost/X2", "localhost:19501/X1"]
+        );
+        assert_eq!(app.positions[2].local_name(), "X1");
+
+        // Relisting one host leaves the other's positions alone.
+        app.apply(&host_positions("localhost", &["X2"]));
+        let names: Vec<_> = app.positions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["localhost/X2", "localhost:19501/X1"]);
+
+        // The overall state is connected while any host is.
+        assert!(app.is_connected());
+        app.apply(&BusEvent::HostDisconnected {
+            host: "localhost:19501".into(),
+            reason: "refused".into(),
+        });
+        assert!(app.is_connected());
+        app.apply(&BusEvent::HostDisconnected {
+            host: "localhost".into(),
+            reason: "refused".into(),
+        });
+        assert!(!app.is_connected());
+        assert_eq!(app.toasts.len(), 1);
+        assert_eq!(
+            app.toasts[0].message,
+            "Lost connection to localhost: refused"
+        );
+        assert!(matches!(
+            app.host_connection(&app.positions[0]),
+            Some(ConnectionState::Disconnected { .. })
+        ));
+    }
 }
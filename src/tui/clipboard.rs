@@ -0,0 +1,125 @@
+//! Copying run details to the system clipboard.
+//!
+//! `y` copies the selected position's run ID and `Y` a one-line summary of
+//! its run, for pasting into tickets and chat. Only built with the
+//! `clipboard` feature, since headless systems have no clipboard; without
+//! it, copying says so in a toast.
+
+use super::app::{App, ToastLevel};
+use crate::format::{format_bases, format_number};
+
+/// Copies the selected position's run ID, or with `summary` a summary of
+/// its run, and reports the outcome in a toast.
+pub fn yank(app: &mut App, summary: bool) {
+    let Some(name) = app.selected_position().map(|p| p.name.clone()) else {
+        return;
+    };
+    let (what, text) = if summary {
+        ("Summary", run_summary(app, &name))
+    } else {
+        (
+            "Run ID",
+            app.run_info.get(&name).and_then(|info| info.run_id.clone()),
+        )
+    };
+    let Some(text) = text else {
+        app.push_toast(ToastLevel::Warning, format!("{} has no run", name));
+        return;
+    };
+    match copy(&text) {
+        Ok(()) => app.push_toast(ToastLevel::Info, format!("{} copied", what)),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to copy to the clipboard");
+            app.push_toast(ToastLevel::Error, format!("Copy failed: {}", e));
+        }
+    }
+}
+
+/// One line naming a position's run and its yield so far, leaving out what
+/// is unknown. `None` while the position has no run.
+pub fn run_summary(app: &App, position_name: &str) -> Option<String> {
+    let info = app.run_info.get(position_name)?;
+    let run_id = info.run_id.as_ref()?;
+    let mut parts = vec![position_name.to_string(), format!("run {}", run_id)];
+    if let Some(sample) = info.sample_id.as_ref().filter(|s| !s.is_empty()) {
+        parts.push(format!("sample {}", sample));
+    }
+    if let Some(stats) = app.stats_cache.get(position_name) {
+        parts.push(format!(
+            "{} reads ({:.1}% passed)",
+            format_number(stats.reads_processed),
+            stats.pass_rate()
+        ));
+        parts.push(format_bases(stats.bases_called));
+        if stats.mean_quality > 0.0 {
+            parts.push(format!("Q{:.1}", stats.mean_quality));
+        }
+        parts.push(format!("{} pores", stats.active_pores));
+    }
+    Some(parts.join(" · "))
+}
+
+#[cfg(feature = "clipboard")]
+fn copy(text: &str) -> Result<(), String> {
+    use std::cell::RefCell;
+
+    thread_local! {
+        // On X11 and Wayland the copied text is served by its owner, so the
+        // clipboard is kept for as long as the TUI runs.
+        static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+    }
+    CLIPBOARD.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let clipboard = match cell.take() {
+            Some(clipboard) => clipboard,
+            None => arboard::Clipboard::new().map_err(|e| e.to_string())?,
+        };
+        cell.insert(clipboard)
+            .set_text(text)
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy(_text: &str) -> Result<(), String> {
+    Err("needs a build with the clipboard feature".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{RunInfo, StatsSnapshot};
+    use crate::config::Config;
+
+    #[test]
+    fn test_run_summary_leaves_out_unknowns() {
+        let mut app = App::new(Config::default());
+        assert_eq!(run_summary(&app, "X1"), None);
+
+        app.run_info.insert(
+            "X1".to_string(),
+            RunInfo {
+                run_id: Some("0f3c9a".into()),
+                sample_id: Some(String::new()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(run_summary(&app, "X1").unwrap(), "X1 · run 0f3c9a");
+
+        app.stats_cache.insert(
+            "X1".to_string(),
+            StatsSnapshot {
+                reads_processed: 12_500,
+                reads_passed: 9,
+                reads_failed: 1,
+                bases_called: 2_500_000_000,
+                active_pores: 412,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            run_summary(&app, "X1").unwrap(),
+            "X1 · run 0f3c9a · 12.50K reads (90.0% passed) · 2.50 Gb · 412 pores"
+        );
+    }
+}
@@ -0,0 +1,1635 @@
+//! Simulated sequencer for `termion --demo`.
+//!
+//! [`DemoSource`] drives the TUI from a [`Simulation`] of a GridION and a
+//! P2 Solo instead of MinKNOW: runs move through their phases, yield curves
+//! grow while pores slowly wear out, and the channel map evolves. Run
+//! control and the start wizard act on the simulation. Simulated time runs
+//! [`TIME_SCALE`] times faster than real time so the charts visibly move.
+
+use super::poller::{AbortOnDrop, PollContext};
+use super::source::DataSource;
+use super::{throughput_from_yield, RunControlAction, StartRequest};
+use crate::bus::{BusEvent, EventBus};
+use crate::client::subscription::HistogramOptions;
+use crate::client::{
+    BarcodeYield, ChannelLayout, ChannelState, ChannelStatesSnapshot, DeviceType, DiskSpaceInfo,
+    DutyTimeBucket, DutyTimeSnapshot, FlowCellInfo, LastRun, MuxScanResult, OutputInfo, PhaseEvent,
+    PhaseKind, Position, PositionState, ProtocolInfo, QScoreHistogram, ReadLengthHistogram,
+    RunInfo, RunState, RunSummary, RunUntilCriteria, SignalChunk, SpeedDataPoint, StatsSnapshot,
+    StopDataAction, YieldDataPoint,
+};
+use crate::config::Config;
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::{watch, Notify};
+use tokio::time::Instant;
+
+/// Simulated seconds that pass per real second.
+const TIME_SCALE: f64 = 60.0;
+
+/// Fixed seed so every demo starts from the same fleet.
+const SEED: u64 = 0x7e41_0d3e;
+
+/// Longest simulation step; also the spacing of yield points.
+const STEP: f64 = 60.0;
+
+/// Spacing of barcode yield points, in seconds of sequencing.
+const BARCODE_STEP: f64 = 600.0;
+
+/// Sequencing time after which a run completes, unless its run-until
+/// targets are changed.
+const RUN_LENGTH: f64 = 72.0 * 3600.0;
+
+const STARTUP_DURATION: f64 = 10.0 * 60.0;
+const FINISHING_DURATION: f64 = 3.0 * 60.0;
+const MUX_SCAN_INTERVAL: f64 = 90.0 * 60.0;
+const MUX_SCAN_DURATION: f64 = 5.0 * 60.0;
+
+/// Sequencing time over which half of the working pores fail.
+const PORE_HALF_LIFE: f64 = 30.0 * 3600.0;
+
+/// Share of working pores that are in a strand at any moment.
+const STRAND_SHARE: f64 = 0.85;
+
+/// Translocation speed of one pore, in bases per second.
+const BASES_PER_SECOND: f64 = 400.0;
+
+/// Disk space written per sequenced base (raw signal plus basecalls).
+const BYTES_PER_BASE: f64 = 2.0;
+
+const HISTOGRAM_BUCKETS: u64 = 40;
+
+/// Buckets of the q-score histogram, one per q-score from zero.
+const QSCORE_BUCKETS: u32 = 30;
+
+/// Sequencing time covered by each translocation speed point.
+const SPEED_STEP: f64 = 10.0 * 60.0;
+
+/// Sequencing time covered by each duty time bucket.
+const DUTY_BUCKET: f64 = 10.0 * 60.0;
+
+/// Raw signal samples per channel per second; the unit of duty time.
+const SAMPLE_RATE: f64 = 4_000.0;
+
+/// Raw signal samples sent for the signal channel per update.
+const SIGNAL_SAMPLES: usize = 2_000;
+
+/// Publishes a [`Simulation`] on the bus in place of MinKNOW.
+pub(super) struct DemoSource {
+    _task: AbortOnDrop,
+    simulation: Arc<Mutex<Simulation>>,
+    bus: EventBus,
+    context: watch::Sender<PollContext>,
+    refresh: Arc<Notify>,
+}
+
+impl DemoSource {
+    /// Starts simulating and publishing every refresh interval.
+    pub(super) fn spawn(config: &Config, bus: &EventBus) -> Self {
+        let simulation = Arc::new(Mutex::new(Simulation::new(Utc::now(), SEED)));
+        let (context_tx, mut context_rx) = watch::channel(PollContext::default());
+        let refresh = Arc::new(Notify::new());
+        let interval = config.tui.refresh_interval;
+
+        let task = tokio::spawn({
+            let simulation = simulation.clone();
+            let bus = bus.clone();
+            let refresh = refresh.clone();
+            async move {
+                bus.publish(BusEvent::Connected);
+                let mut last = Instant::now();
+                loop {
+                    let now = Instant::now();
+                    let context = context_rx.borrow_and_update().clone();
+                    let events = {
+                        let mut simulation = lock(&simulation);
+                        simulation.advance((now - last).as_secs_f64() * TIME_SCALE);
+                        simulation.events(&context)
+                    };
+                    last = now;
+                    for event in events {
+                        bus.publish(event);
+                    }
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(interval) => {}
+                        _ = refresh.notified() => {}
+                        changed = context_rx.changed() => {
+                            if changed.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            _task: AbortOnDrop(task),
+            simulation,
+            bus: bus.clone(),
+            context: context_tx,
+            refresh,
+        }
+    }
+
+    /// Publishes a failed request as an alert, or shows its effect right away.
+    fn finish_request(&self, result: Result<(), String>, failure: &str) {
+        match result {
+            Ok(()) => self.refresh.notify_one(),
+            Err(e) => self
+                .bus
+                .publish(BusEvent::alert(format!("{}: {}", failure, e))),
+        }
+    }
+}
+
+impl DataSource for DemoSource {
+    fn set_context(&self, context: PollContext) {
+        self.context.send_if_modified(|current| {
+            if *current == context {
+                return false;
+            }
+            *current = context;
+            true
+        });
+    }
+
+    fn refresh(&self) {
+        self.refresh.notify_one();
+    }
+
+    fn run_control(&self, position: Position, action: RunControlAction) {
+        let failure = match action {
+            RunControlAction::Pause => "Failed to pause run",
+            RunControlAction::Resume => "Failed to resume run",
+            RunControlAction::Stop(_) => "Failed to stop run",
+        };
+        let result = lock(&self.simulation).run_control(&position.name, action);
+        self.finish_request(result, failure);
+    }
+
+    fn load_protocols(&self, position: Position) {
+        let protocols = lock(&self.simulation).protocols(&position.name);
+        self.bus.publish(BusEvent::Protocols {
+            position: position.name,
+            protocols,
+        });
+    }
+
+    fn start_protocol(&self, position: Position, request: StartRequest) {
+        let result = lock(&self.simulation).start_run(&position.name, &request);
+        self.finish_request(result, "Failed to start protocol");
+    }
+
+    fn set_run_until(&self, position: Position, targets: RunUntilCriteria) {
+        let result = lock(&self.simulation).set_run_until(&position.name, targets);
+        self.finish_request(result, "Failed to set run-until targets");
+    }
+}
+
+/// The simulation holds no invariants a panic could break, so a poisoned
+/// lock is still usable.
+fn lock(simulation: &Mutex<Simulation>) -> MutexGuard<'_, Simulation> {
+    simulation.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FlowCellKind {
+    MinION,
+    PromethION,
+}
+
+impl FlowCellKind {
+    fn product_code(self) -> &'static str {
+        match self {
+            FlowCellKind::MinION => "FLO-MIN114",
+            FlowCellKind::PromethION => "FLO-PRO114M",
+        }
+    }
+
+    fn layout(self) -> ChannelLayout {
+        let (width, height) = match self {
+            FlowCellKind::MinION => (32, 16),
+            FlowCellKind::PromethION => (120, 25),
+        };
+        ChannelLayout {
+            channel_count: (width * height) as usize,
+            width,
+            height,
+            coords: (0..width * height)
+                .map(|i| (i % width, i / width))
+                .collect(),
+        }
+    }
+}
+
+/// A protocol the simulated positions can run.
+struct DemoProtocol {
+    identifier: &'static str,
+    name: &'static str,
+    kit: &'static str,
+    flow_cell: FlowCellKind,
+    /// Barcodes in use, or 0 for a run without barcoding.
+    barcodes: usize,
+}
+
+const PROTOCOLS: &[DemoProtocol] = &[
+    DemoProtocol {
+        identifier: "sequencing/sequencing_MIN114_DNA_e8_2_400K:FLO-MIN114:SQK-LSK114",
+        name: "Ligation Sequencing Kit V14",
+        kit: "SQK-LSK114",
+        flow_cell: FlowCellKind::MinION,
+        barcodes: 0,
+    },
+    DemoProtocol {
+        identifier: "sequencing/sequencing_MIN114_DNA_e8_2_400K:FLO-MIN114:SQK-NBD114-24",
+        name: "Native Barcoding Kit 24 V14",
+        kit: "SQK-NBD114.24",
+        flow_cell: FlowCellKind::MinION,
+        barcodes: 12,
+    },
+    DemoProtocol {
+        identifier: "sequencing/sequencing_MIN114_DNA_e8_2_400K:FLO-MIN114:SQK-RBK114-24",
+        name: "Rapid Barcoding Kit 24 V14",
+        kit: "SQK-RBK114.24",
+        flow_cell: FlowCellKind::MinION,
+        barcodes: 8,
+    },
+    DemoProtocol {
+        identifier: "sequencing/sequencing_PRO114_DNA_e8_2_400K:FLO-PRO114M:SQK-LSK114",
+        name: "Ligation Sequencing Kit V14",
+        kit: "SQK-LSK114",
+        flow_cell: FlowCellKind::PromethION,
+        barcodes: 0,
+    },
+    DemoProtocol {
+        identifier: "sequencing/sequencing_PRO114_DNA_e8_2_400K:FLO-PRO114M:SQK-NBD114-24",
+        name: "Native Barcoding Kit 24 V14",
+        kit: "SQK-NBD114.24",
+        flow_cell: FlowCellKind::PromethION,
+        barcodes: 24,
+    },
+];
+
+fn find_protocol(identifier: &str) -> Option<&'static DemoProtocol> {
+    PROTOCOLS.iter().find(|p| p.identifier == identifier)
+}
+
+/// Cumulative read and base counts.
+#[derive(Debug, Clone, Copy, Default)]
+struct Totals {
+    reads: f64,
+    bases: f64,
+    reads_passed: f64,
+    bases_passed: f64,
+}
+
+impl Totals {
+    fn add(&mut self, reads: f64, bases: f64, pass_fraction: f64) {
+        self.reads += reads;
+        self.bases += bases;
+        self.reads_passed += reads * pass_fraction;
+        self.bases_passed += bases * pass_fraction;
+    }
+
+    fn point(&self, seconds: f64) -> YieldDataPoint {
+        YieldDataPoint {
+            seconds: seconds as u32,
+            reads: self.reads as u64,
+            bases: self.bases as u64,
+            reads_passed: self.reads_passed as u64,
+            reads_failed: (self.reads - self.reads_passed) as u64,
+            bases_passed: self.bases_passed as u64,
+            bases_failed: (self.bases - self.bases_passed) as u64,
+        }
+    }
+}
+
+struct SimBarcode {
+    name: String,
+    /// Fraction of all reads carrying this barcode.
+    share: f64,
+    totals: Totals,
+    points: Vec<YieldDataPoint>,
+}
+
+/// How a run behaves, fixed when it starts.
+struct RunProfile {
+    /// Fraction of channels with a working pore at the start.
+    performance: f64,
+    /// Mean read length in bases.
+    read_length: f64,
+    /// Mean read quality at the start.
+    quality: f64,
+}
+
+struct SimRun {
+    info: RunInfo,
+    protocol_id: String,
+    acquisition_id: String,
+    state: RunState,
+    profile: RunProfile,
+    /// Sequencing time so far; the x axis of the yield chart.
+    acquired: f64,
+    /// Time left in the current startup, pore scan, or finishing phase.
+    phase_left: f64,
+    next_mux_scan: f64,
+    end_reason: &'static str,
+    totals: Totals,
+    points: Vec<YieldDataPoint>,
+    barcodes: Vec<SimBarcode>,
+    phases: Vec<PhaseEvent>,
+    /// A channel works while the active pore fraction is above its value.
+    lifetimes: Vec<f64>,
+    channels: Vec<&'static str>,
+    /// Raw signal samples produced so far.
+    signal_samples: u64,
+    /// Results of the pore scans so far, oldest first.
+    mux_scans: Vec<MuxScanResult>,
+    /// Channel state times per [`DUTY_BUCKET`] of sequencing, oldest first.
+    duty: Vec<DutyTimeBucket>,
+    /// Translocation speed per [`SPEED_STEP`] of sequencing, oldest first.
+    speed: Vec<SpeedDataPoint>,
+    /// Run-until criteria that complete the run.
+    targets: RunUntilCriteria,
+}
+
+impl SimRun {
+    fn new(
+        protocol: &DemoProtocol,
+        sample: &str,
+        group: &str,
+        profile: RunProfile,
+        start: DateTime<Utc>,
+        rng: &mut StdRng,
+    ) -> Self {
+        let channel_count = protocol.flow_cell.layout().channel_count;
+        let mut shares: Vec<f64> = (0..protocol.barcodes)
+            .map(|_| rng.random_range(0.3..1.0))
+            .collect();
+        let total: f64 = shares.iter().sum::<f64>() / 0.96;
+        shares.iter_mut().for_each(|s| *s /= total);
+        let mut barcodes: Vec<SimBarcode> = shares
+            .into_iter()
+            .enumerate()
+            .map(|(i, share)| SimBarcode {
+                name: format!("barcode{:02}", i + 1),
+                share,
+                totals: Totals::default(),
+                points: Vec::new(),
+            })
+            .collect();
+        if !barcodes.is_empty() {
+            barcodes.push(SimBarcode {
+                name: "unclassified".to_string(),
+                share: 0.04,
+                totals: Totals::default(),
+                points: Vec::new(),
+            });
+        }
+
+        Self {
+            info: RunInfo {
+                run_id: Some(random_id(rng)),
+                experiment_group: Some(group.to_string()),
+                sample_id: Some(sample.to_string()),
+                protocol_name: Some(protocol.name.to_string()),
+                flow_cell_product_code: Some(protocol.flow_cell.product_code().to_string()),
+                start_time: Some(start),
+                end_time: None,
+            },
+            protocol_id: protocol.identifier.to_string(),
+            acquisition_id: random_id(rng),
+            state: RunState::Starting,
+            profile,
+            acquired: 0.0,
+            phase_left: STARTUP_DURATION,
+            next_mux_scan: MUX_SCAN_INTERVAL,
+            end_reason: "Completed",
+            totals: Totals::default(),
+            points: Vec::new(),
+            barcodes,
+            phases: vec![PhaseEvent {
+                timestamp: start,
+                phase: "Initialising".to_string(),
+                kind: PhaseKind::Other,
+            }],
+            lifetimes: (0..channel_count).map(|_| rng.random()).collect(),
+            channels: vec!["pore"; channel_count],
+            signal_samples: 0,
+            mux_scans: Vec::new(),
+            duty: Vec::new(),
+            speed: Vec::new(),
+            targets: RunUntilCriteria {
+                runtime: Some(RUN_LENGTH as u64),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn set_phase(&mut self, state: RunState, phase: &str, clock: DateTime<Utc>) {
+        let kind = match state {
+            RunState::MuxScanning => PhaseKind::PoreScan,
+            RunState::Paused => PhaseKind::Pause,
+            _ => PhaseKind::Other,
+        };
+        self.state = state;
+        self.phases.push(PhaseEvent {
+            timestamp: clock,
+            phase: phase.to_string(),
+            kind,
+        });
+    }
+
+    /// Whether the run has reached its runtime or yield target.
+    fn targets_met(&self) -> bool {
+        let progress = self.run_until_progress();
+        let met = |target: Option<u64>, value: Option<u64>| {
+            target.is_some_and(|t| value.unwrap_or(0) >= t)
+        };
+        met(self.targets.runtime, progress.runtime)
+            || met(self.targets.estimated_bases, progress.estimated_bases)
+            || met(
+                self.targets.passed_basecalled_bases,
+                progress.passed_basecalled_bases,
+            )
+    }
+
+    fn run_until_progress(&self) -> RunUntilCriteria {
+        RunUntilCriteria {
+            runtime: Some(self.acquired as u64),
+            estimated_bases: Some(self.totals.bases as u64),
+            passed_basecalled_bases: Some(self.totals.bases_passed as u64),
+            available_pores: None,
+        }
+    }
+
+    /// Reads written as pod5 and fastq: about a byte of signal and two of
+    /// fastq per base, with a sliver still in the writer queue.
+    fn output(&self) -> OutputInfo {
+        let produced = self.totals.bases * 3.0;
+        let pending = produced * 0.01;
+        OutputInfo {
+            reads_directory: Some(format!(
+                "/data/{}/{}/{}",
+                self.info.experiment_group.as_deref().unwrap_or_default(),
+                self.info.sample_id.as_deref().unwrap_or_default(),
+                self.acquisition_id
+            )),
+            formats: vec!["pod5".to_string(), "fastq".to_string()],
+            bytes_written: (produced - pending) as u64,
+            bytes_pending: pending as u64,
+            bytes_failed: 0,
+        }
+    }
+
+    fn finish(&mut self, reason: &'static str, clock: DateTime<Utc>) {
+        self.end_reason = reason;
+        self.phase_left = FINISHING_DURATION;
+        self.set_phase(RunState::Finishing, "Completed", clock);
+    }
+
+    /// Advances the run by `step` seconds ending at `clock`. Returns true
+    /// once the run has ended.
+    fn step(&mut self, step: f64, clock: DateTime<Utc>, rng: &mut StdRng) -> bool {
+        match self.state {
+            RunState::Starting => {
+                self.phase_left -= step;
+                if self.phase_left <= 0.0 {
+                    self.record_mux_scan();
+                    self.set_phase(RunState::Running, "Sequencing", clock);
+                }
+            }
+            RunState::Running => {
+                self.sequence(step, rng);
+                if self.targets_met() {
+                    self.finish("Completed", clock);
+                } else if self.acquired >= self.next_mux_scan {
+                    self.next_mux_scan += MUX_SCAN_INTERVAL;
+                    self.phase_left = MUX_SCAN_DURATION;
+                    self.set_phase(RunState::MuxScanning, "Pore scan", clock);
+                }
+            }
+            RunState::MuxScanning => {
+                self.phase_left -= step;
+                if self.phase_left <= 0.0 {
+                    self.record_mux_scan();
+                    self.set_phase(RunState::Running, "Sequencing", clock);
+                }
+            }
+            RunState::Finishing => {
+                self.phase_left -= step;
+                if self.phase_left <= 0.0 {
+                    self.info.end_time = Some(clock);
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Records a pore scan over every well (four per channel), finding
+    /// fewer single pores as the pores wear out.
+    fn record_mux_scan(&mut self) {
+        let wells = (self.lifetimes.len() * 4) as f64;
+        let active = self.active_fraction();
+        let single = wells * active * 0.85;
+        let reserved = wells * active * 0.1;
+        let unavailable = (wells - single - reserved) * 0.6;
+        let counts = [
+            ("single_pore", single),
+            ("reserved_pore", reserved),
+            ("unavailable", unavailable),
+            ("zero", wells - single - reserved - unavailable),
+        ];
+        self.mux_scans.push(MuxScanResult {
+            seconds: self.acquired as u64,
+            counts: counts
+                .into_iter()
+                .map(|(name, count)| (name.to_string(), count as u32))
+                .collect(),
+        });
+    }
+
+    fn sequence(&mut self, step: f64, rng: &mut StdRng) {
+        let bases = self.bases_per_second() * step * rng.random_range(0.93..1.07);
+        let reads = bases / self.profile.read_length;
+        let pass_fraction = self.pass_fraction();
+        self.totals.add(reads, bases, pass_fraction);
+        for barcode in &mut self.barcodes {
+            barcode
+                .totals
+                .add(reads * barcode.share, bases * barcode.share, pass_fraction);
+        }
+
+        self.record_duty_time(step);
+        let before = self.acquired;
+        self.acquired += step;
+        if crossed(before, self.acquired, STEP) {
+            self.points.push(self.totals.point(self.acquired));
+        }
+        if crossed(before, self.acquired, SPEED_STEP) {
+            let median = self.translocation_speed() * rng.random_range(0.97..1.03);
+            self.speed.push(SpeedDataPoint {
+                seconds: self.acquired as u32,
+                median: median as f32,
+                q25: (median * 0.9) as f32,
+                q75: (median * 1.1) as f32,
+            });
+        }
+        if crossed(before, self.acquired, BARCODE_STEP) {
+            for barcode in &mut self.barcodes {
+                barcode.points.push(barcode.totals.point(self.acquired));
+            }
+        }
+    }
+
+    /// Adds `step` seconds of channel time to the current duty time bucket:
+    /// working channels split between strand, pore, adapter, and unblock,
+    /// the rest unavailable.
+    fn record_duty_time(&mut self, step: f64) {
+        let start = ((self.acquired / DUTY_BUCKET).floor() * DUTY_BUCKET) as u32;
+        if self.duty.last().is_none_or(|bucket| bucket.start != start) {
+            self.duty.push(DutyTimeBucket {
+                start,
+                end: start + DUTY_BUCKET as u32,
+                state_times: HashMap::new(),
+            });
+        }
+        let samples = self.lifetimes.len() as f64 * step * SAMPLE_RATE;
+        let active = self.active_fraction();
+        let shares = [
+            (ChannelState::Strand, active * STRAND_SHARE),
+            (ChannelState::Pore, active * (0.95 - STRAND_SHARE)),
+            (ChannelState::Adapter, active * 0.03),
+            (ChannelState::Unblock, active * 0.02),
+            (ChannelState::Unavailable, 1.0 - active),
+        ];
+        if let Some(bucket) = self.duty.last_mut() {
+            for (state, share) in shares {
+                *bucket.state_times.entry(state).or_insert(0) += (samples * share) as u64;
+            }
+        }
+    }
+
+    /// Fraction of channels with a working pore.
+    fn active_fraction(&self) -> f64 {
+        self.profile.performance * 0.5f64.powf(self.acquired / PORE_HALF_LIFE)
+    }
+
+    /// Share of the run so far each channel spent in a strand: the strand
+    /// share for the part of the run before its pore died.
+    fn pore_occupancy(&self) -> Vec<f32> {
+        let active = self.active_fraction();
+        self.lifetimes
+            .iter()
+            .map(|&lifetime| {
+                let alive = if lifetime < active || self.acquired <= 0.0 {
+                    1.0
+                } else {
+                    // The active fraction fell to `lifetime` at this time.
+                    let died = PORE_HALF_LIFE * (self.profile.performance / lifetime).log2();
+                    (died / self.acquired).clamp(0.0, 1.0)
+                };
+                (STRAND_SHARE * alive) as f32
+            })
+            .collect()
+    }
+
+    fn bases_per_second(&self) -> f64 {
+        self.lifetimes.len() as f64 * self.active_fraction() * STRAND_SHARE * BASES_PER_SECOND
+    }
+
+    /// Median translocation speed, slowing as the pores clog over the run.
+    fn translocation_speed(&self) -> f64 {
+        BASES_PER_SECOND * (1.0 - 0.25 * (self.acquired / RUN_LENGTH))
+    }
+
+    fn pass_fraction(&self) -> f64 {
+        0.93 - 0.08 * (self.acquired / RUN_LENGTH)
+    }
+
+    fn mean_quality(&self) -> f64 {
+        self.profile.quality - 1.5 * (self.acquired / RUN_LENGTH)
+    }
+
+    fn active_pores(&self) -> u32 {
+        if self.state == RunState::Running {
+            (self.lifetimes.len() as f64 * self.active_fraction() * STRAND_SHARE) as u32
+        } else {
+            0
+        }
+    }
+
+    fn n50(&self) -> f64 {
+        // Read lengths are gamma distributed with shape 2, whose base-weighted
+        // median is about 1.34 times the mean.
+        self.profile.read_length * 1.337
+    }
+
+    fn stats(&self, clock: DateTime<Utc>) -> StatsSnapshot {
+        let t = &self.totals;
+        StatsSnapshot {
+            timestamp: Some(clock),
+            acquisition_start: self.info.start_time,
+            reads_processed: t.reads as u64,
+            bases_called: t.bases as u64,
+            reads_passed: t.reads_passed as u64,
+            reads_failed: (t.reads - t.reads_passed) as u64,
+            bases_passed: t.bases_passed as u64,
+            bases_failed: (t.bases - t.bases_passed) as u64,
+            mean_quality: self.mean_quality(),
+            mean_read_length: self.profile.read_length,
+            active_pores: self.active_pores(),
+            ..Default::default()
+        }
+    }
+
+    fn summary(&self) -> RunSummary {
+        RunSummary {
+            info: self.info.clone(),
+            protocol_id: self.protocol_id.clone(),
+            end_reason: self.end_reason.to_string(),
+            acquisition_run_id: Some(self.acquisition_id.clone()),
+            reads: self.totals.reads as u64,
+            bases_passed: self.totals.bases_passed as u64,
+            bases_failed: (self.totals.bases - self.totals.bases_passed) as u64,
+            n50: Some(self.n50() as u64),
+        }
+    }
+
+    fn barcode_yield(&self) -> Vec<BarcodeYield> {
+        self.barcodes
+            .iter()
+            .map(|b| BarcodeYield {
+                barcode: b.name.clone(),
+                points: b.points.clone(),
+            })
+            .collect()
+    }
+
+    /// Moves some channels to a new state: dead channels stay dead, working
+    /// ones spend most of their time in a strand.
+    fn update_channels(&mut self, rng: &mut StdRng) -> ChannelStatesSnapshot {
+        let active = self.active_fraction();
+        for (i, state) in self.channels.iter_mut().enumerate() {
+            let lifetime = self.lifetimes[i];
+            *state = if lifetime >= active {
+                match i % 3 {
+                    _ if lifetime < active + 0.03 => "unavailable",
+                    0 => "multiple",
+                    _ => "zero",
+                }
+            } else if self.state != RunState::Running {
+                "pore"
+            } else if *state == "strand" && rng.random_bool(0.75) {
+                "strand"
+            } else {
+                match rng.random::<f64>() {
+                    x if x < STRAND_SHARE => "strand",
+                    x if x < 0.95 => "pore",
+                    x if x < 0.98 => "adapter",
+                    _ => "unblocking",
+                }
+            };
+        }
+
+        let mut state_counts = HashMap::new();
+        for state in &self.channels {
+            *state_counts.entry(state.to_string()).or_insert(0) += 1;
+        }
+        ChannelStatesSnapshot {
+            channel_count: self.channels.len(),
+            states: self.channels.iter().map(|s| s.to_string()).collect(),
+            state_counts,
+        }
+    }
+
+    /// Raw signal for `channel` (1-based) shaped by its state: an open pore
+    /// sits at a steady high current, a strand steps between lower levels
+    /// as bases pass through, and a dead channel reads near zero.
+    fn signal(&mut self, channel: u32, rng: &mut StdRng) -> SignalChunk {
+        let state = self
+            .channels
+            .get(channel.saturating_sub(1) as usize)
+            .copied()
+            .unwrap_or("zero");
+        let mut level = 0.0;
+        let mut picoamps = Vec::with_capacity(SIGNAL_SAMPLES);
+        for i in 0..SIGNAL_SAMPLES {
+            let (base, noise) = match state {
+                "strand" => {
+                    if i % 10 == 0 {
+                        level = rng.random_range(65.0..125.0);
+                    }
+                    (level, 2.5)
+                }
+                "pore" | "adapter" | "unblocking" => (215.0, 3.0),
+                _ => (0.0, 1.0),
+            };
+            picoamps.push(base + rng.random_range(-noise..noise));
+        }
+        let chunk = SignalChunk {
+            channel,
+            samples_since_start: self.signal_samples,
+            picoamps,
+        };
+        self.signal_samples += SIGNAL_SAMPLES as u64;
+        chunk
+    }
+
+    /// Mean q-score distribution of the reads so far: a bell around the
+    /// run's mean quality, one bucket per q-score.
+    fn qscore_histogram(&self) -> QScoreHistogram {
+        let mean = self.mean_quality();
+        // Logistic approximation of a normal spread of about 2.5.
+        let cdf = |q: f64| 1.0 / (1.0 + (-(q - mean) / 1.4).exp());
+        let bucket_ranges: Vec<(f32, f32)> = (0..QSCORE_BUCKETS)
+            .map(|q| (q as f32, q as f32 + 1.0))
+            .collect();
+        let bucket_values = bucket_ranges
+            .iter()
+            .map(|&(lo, hi)| (self.totals.reads * (cdf(hi as f64) - cdf(lo as f64))) as u64)
+            .collect();
+        QScoreHistogram {
+            bucket_ranges,
+            bucket_values,
+            modal_q_score: mean.floor() as f32 + 0.5,
+        }
+    }
+
+    /// Read length distribution of the reads so far.
+    fn histogram(&self, options: HistogramOptions) -> ReadLengthHistogram {
+        let scale = self.profile.read_length / 2.0;
+        let round_up = |x: f64| ((x / 1000.0).ceil() * 1000.0) as u64;
+        let data_end = round_up(10.0 * scale);
+        let cdf = |x: u64| {
+            let x = x as f64 / scale;
+            1.0 - (-x).exp() * (1.0 + x)
+        };
+        let (start, end) = match options.range {
+            Some(range) => range,
+            None if options.exclude_outliers => {
+                // Bisect for the length the kept reads are shorter than.
+                let kept = 1.0 - f64::from(options.outlier_percent) / 100.0;
+                let (mut lo, mut hi) = (0, data_end);
+                while hi - lo > 1 {
+                    let mid = (lo + hi) / 2;
+                    if cdf(mid) < kept {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                (0, round_up(hi as f64))
+            }
+            None => (0, data_end),
+        };
+        // Like MinKNOW, only a selected range is split into a set number
+        // of buckets.
+        let buckets = match options.range {
+            Some(_) => options.buckets.map_or(HISTOGRAM_BUCKETS, u64::from),
+            None => HISTOGRAM_BUCKETS,
+        };
+        let width = end.saturating_sub(start).div_ceil(buckets).max(1);
+        // Weighting each read by its length raises the gamma shape by one.
+        let bases_cdf = |x: u64| {
+            let x = x as f64 / scale;
+            1.0 - (-x).exp() * (1.0 + x + x * x / 2.0)
+        };
+
+        let bucket_ranges: Vec<(u64, u64)> = (0..buckets)
+            .map(|i| (start + i * width, start + (i + 1) * width))
+            .collect();
+        let bucket_values = bucket_ranges
+            .iter()
+            .map(|&(lo, hi)| {
+                if options.weight_by_bases {
+                    (self.totals.bases * (bases_cdf(hi) - bases_cdf(lo))) as u64
+                } else {
+                    (self.totals.reads * (cdf(hi) - cdf(lo))) as u64
+                }
+            })
+            .collect();
+        ReadLengthHistogram {
+            bucket_ranges,
+            bucket_values,
+            n50: self.n50() as f32,
+            outliers_excluded: options.exclude_outliers,
+            outlier_percent: if options.exclude_outliers {
+                options.outlier_percent / 100.0
+            } else {
+                0.0
+            },
+            weighted_by_bases: options.weight_by_bases,
+            requested_range: options.range,
+            source_data_end: data_end,
+        }
+    }
+}
+
+/// Whether a multiple of `interval` lies in `(before, after]`.
+fn crossed(before: f64, after: f64, interval: f64) -> bool {
+    (after / interval).floor() > (before / interval).floor()
+}
+
+fn random_id(rng: &mut StdRng) -> String {
+    let a: u64 = rng.random();
+    let b: u64 = rng.random();
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        a >> 32,
+        (a >> 16) & 0xffff,
+        a & 0xffff,
+        b >> 48,
+        b & 0xffff_ffff_ffff
+    )
+}
+
+struct SimFlowCell {
+    id: String,
+    kind: FlowCellKind,
+    layout: ChannelLayout,
+}
+
+struct SimPosition {
+    position: Position,
+    flow_cell: Option<SimFlowCell>,
+    disk_capacity: f64,
+    disk_used: f64,
+    run: Option<SimRun>,
+    /// Finished runs, newest first.
+    history: Vec<RunSummary>,
+    /// Charts of the newest finished run.
+    last_run: Option<LastRun>,
+}
+
+impl SimPosition {
+    fn step(&mut self, step: f64, clock: DateTime<Utc>, rng: &mut StdRng) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let before = run.totals.bases;
+        let finished = run.step(step, clock, rng);
+        self.disk_used += (run.totals.bases - before) * BYTES_PER_BASE;
+        if finished {
+            self.history.insert(0, run.summary());
+            self.last_run = Some(LastRun {
+                summary: run.summary(),
+                yield_history: run.points.clone(),
+                histogram: Some(run.histogram(HistogramOptions::default())),
+            });
+            self.run = None;
+        }
+    }
+
+    fn disk_space(&self) -> DiskSpaceInfo {
+        let rate = self
+            .run
+            .as_ref()
+            .filter(|r| r.state == RunState::Running)
+            .map_or(0.0, |r| r.bases_per_second() * BYTES_PER_BASE);
+        let available = (self.disk_capacity - self.disk_used).max(0.0);
+        DiskSpaceInfo {
+            filesystem_id: "/data".to_string(),
+            bytes_available: available as u64,
+            bytes_capacity: self.disk_capacity as u64,
+            bytes_to_stop_cleanly: 20_000_000_000,
+            bytes_per_second: rate as i64,
+            recommend_alert: available < self.disk_capacity * 0.1,
+            recommend_stop: available < self.disk_capacity * 0.02,
+        }
+    }
+
+    fn flow_cell_info(&self) -> FlowCellInfo {
+        match &self.flow_cell {
+            Some(flow_cell) => FlowCellInfo {
+                has_flow_cell: true,
+                flow_cell_id: Some(flow_cell.id.clone()),
+                product_code: Some(flow_cell.kind.product_code().to_string()),
+                has_adapter: false,
+                channel_count: flow_cell.layout.channel_count as u32,
+            },
+            None => FlowCellInfo::default(),
+        }
+    }
+}
+
+/// Starting point of a simulated run.
+struct Scenario {
+    position: &'static str,
+    protocol: usize,
+    sample: &'static str,
+    group: &'static str,
+    /// How long ago the run started.
+    hours_ago: f64,
+    profile: RunProfile,
+}
+
+/// A GridION and a P2 Solo whose runs advance on a simulated clock.
+pub(super) struct Simulation {
+    clock: DateTime<Utc>,
+    rng: StdRng,
+    positions: Vec<SimPosition>,
+}
+
+impl Simulation {
+    /// Builds the demo fleet as it looks at `clock`: runs at various stages,
+    /// one paused, an idle position with past runs, and an empty one.
+    pub(super) fn new(clock: DateTime<Utc>, seed: u64) -> Self {
+        let mut sim = Self {
+            clock,
+            rng: StdRng::seed_from_u64(seed),
+            positions: Vec::new(),
+        };
+
+        for (i, name) in ["X1", "X2", "X3", "X4", "X5"].into_iter().enumerate() {
+            let flow_cell = (name != "X5").then(|| sim.flow_cell(FlowCellKind::MinION, "FA"));
+            sim.add_position(
+                name,
+                "GXB02001",
+                DeviceType::GridION,
+                8000 + i as u16 * 2,
+                flow_cell,
+                1.4e12,
+                0.35,
+            );
+        }
+        for (i, name) in ["P2S_00112-A", "P2S_00112-B"].into_iter().enumerate() {
+            let flow_cell = Some(sim.flow_cell(FlowCellKind::PromethION, "PA"));
+            let used = if i == 0 { 0.62 } else { 0.3 };
+            sim.add_position(
+                name,
+                "P2S-00112",
+                DeviceType::P2Solo,
+                8100 + i as u16 * 2,
+                flow_cell,
+                7.0e12,
+                used,
+            );
+        }
+
+        let profile = |performance, read_length, quality| RunProfile {
+            performance,
+            read_length,
+            quality,
+        };
+        let scenarios = [
+            Scenario {
+                position: "X4",
+                protocol: 0,
+                sample: "HG002_rep1",
+                group: "giab_benchmark",
+                hours_ago: 170.0,
+                profile: profile(0.9, 8_500.0, 18.5),
+            },
+            Scenario {
+                position: "X4",
+                protocol: 2,
+                sample: "isolates_batch3",
+                group: "clinical_isolates",
+                hours_ago: 80.0,
+                profile: profile(0.88, 5_200.0, 17.8),
+            },
+            Scenario {
+                position: "X1",
+                protocol: 1,
+                sample: "ZymoMock_01",
+                group: "microbial_community",
+                hours_ago: 26.0,
+                profile: profile(0.95, 6_500.0, 18.2),
+            },
+            Scenario {
+                position: "X2",
+                protocol: 0,
+                sample: "HG002",
+                group: "human_wgs",
+                hours_ago: 3.5,
+                profile: profile(0.9, 9_000.0, 18.9),
+            },
+            Scenario {
+                position: "X3",
+                protocol: 0,
+                sample: "plasmid_QC",
+                group: "plasmids",
+                hours_ago: 12.0,
+                profile: profile(0.85, 5_000.0, 17.5),
+            },
+            Scenario {
+                position: "P2S_00112-A",
+                protocol: 3,
+                sample: "HG002_UL",
+                group: "human_ultralong",
+                hours_ago: 40.0,
+                profile: profile(0.92, 28_000.0, 18.0),
+            },
+            Scenario {
+                position: "P2S_00112-B",
+                protocol: 4,
+                sample: "AMR_surveillance",
+                group: "amr_batch_07",
+                hours_ago: 1.5,
+                profile: profile(0.35, 4_500.0, 16.4),
+            },
+        ];
+        for scenario in scenarios {
+            sim.replay(scenario);
+        }
+        let _ = sim.run_control("X3", RunControlAction::Pause);
+        sim
+    }
+
+    fn flow_cell(&mut self, kind: FlowCellKind, prefix: &str) -> SimFlowCell {
+        SimFlowCell {
+            id: format!("{}W{:05}", prefix, self.rng.random_range(10_000..100_000)),
+            kind,
+            layout: kind.layout(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add_position(
+        &mut self,
+        name: &str,
+        device_id: &str,
+        device_type: DeviceType,
+        grpc_port: u16,
+        flow_cell: Option<SimFlowCell>,
+        disk_capacity: f64,
+        disk_used: f64,
+    ) {
+        self.positions.push(SimPosition {
+            position: Position {
+                id: name.to_string(),
+                name: name.to_string(),
+                device_id: device_id.to_string(),
+                state: PositionState::Idle,
+                grpc_port,
+                is_simulated: true,
+                device_type,
+                host: None,
+            },
+            flow_cell,
+            disk_capacity,
+            disk_used: disk_capacity * disk_used,
+            run: None,
+            history: Vec::new(),
+            last_run: None,
+        });
+    }
+
+    /// Starts a scenario's run in the past and plays it forward to now.
+    fn replay(&mut self, scenario: Scenario) {
+        let protocol = &PROTOCOLS[scenario.protocol];
+        let seconds = scenario.hours_ago * 3600.0;
+        let start = self.clock - Duration::seconds(seconds as i64);
+        let run = SimRun::new(
+            protocol,
+            scenario.sample,
+            scenario.group,
+            scenario.profile,
+            start,
+            &mut self.rng,
+        );
+        let steps = (seconds / STEP) as i64;
+        let rng = &mut self.rng;
+        let Some(pos) = self
+            .positions
+            .iter_mut()
+            .find(|p| p.position.name == scenario.position)
+        else {
+            return;
+        };
+        pos.run = Some(run);
+        for i in 1..=steps {
+            pos.step(STEP, start + Duration::seconds(i * STEP as i64), rng);
+        }
+    }
+
+    fn position_mut(&mut self, name: &str) -> Option<&mut SimPosition> {
+        self.positions.iter_mut().find(|p| p.position.name == name)
+    }
+
+    /// Moves the clock forward, in steps short enough for every phase
+    /// change to land on time.
+    pub(super) fn advance(&mut self, seconds: f64) {
+        let mut remaining = seconds;
+        while remaining > 0.0 {
+            let step = remaining.min(STEP);
+            remaining -= step;
+            self.clock += Duration::milliseconds((step * 1000.0) as i64);
+            for pos in &mut self.positions {
+                pos.step(step, self.clock, &mut self.rng);
+            }
+        }
+    }
+
+    /// Events describing the current state, in the order the poller would
+    /// publish them. Chart and channel data is only produced for the
+    /// position in the detail view.
+    pub(super) fn events(&mut self, context: &PollContext) -> Vec<BusEvent> {
+        for pos in &mut self.positions {
+            let active = pos.run.as_ref().is_some_and(|r| r.state.is_active());
+            pos.position.state = if active {
+                PositionState::Running
+            } else {
+                PositionState::Idle
+            };
+        }
+        let mut events = vec![BusEvent::PositionsUpdated(
+            self.positions.iter().map(|p| p.position.clone()).collect(),
+        )];
+
+        for pos in &mut self.positions {
+            let name = pos.position.name.clone();
+            events.push(BusEvent::DiskSpace {
+                position: name.clone(),
+                info: pos.disk_space(),
+            });
+            events.push(BusEvent::FlowCellInfo {
+                position: name.clone(),
+                info: pos.flow_cell_info(),
+            });
+            events.push(BusEvent::RunStateChanged {
+                position: name.clone(),
+                state: pos.run.as_ref().map_or(RunState::Idle, |r| r.state.clone()),
+            });
+            if context.history_position.as_deref() == Some(name.as_str()) {
+                events.push(BusEvent::RunHistory {
+                    position: name.clone(),
+                    runs: pos.history.clone(),
+                });
+            }
+
+            let Some(run) = &mut pos.run else {
+                if context.detail_position.as_deref() == Some(name.as_str()) {
+                    events.push(BusEvent::LastRun {
+                        position: name,
+                        run: pos.last_run.clone().map(Box::new),
+                    });
+                }
+                continue;
+            };
+            events.push(BusEvent::Stats {
+                position: name.clone(),
+                stats: run.stats(self.clock),
+            });
+            if let Some(bases_per_second) = throughput_from_yield(&run.points) {
+                events.push(BusEvent::Throughput {
+                    position: name.clone(),
+                    bases_per_second,
+                });
+            }
+            events.push(BusEvent::RunInfo {
+                position: name.clone(),
+                info: run.info.clone(),
+            });
+
+            events.push(BusEvent::ActivePores {
+                position: name.clone(),
+                count: run.active_pores(),
+            });
+            if context.detail_position.as_deref() != Some(name.as_str()) {
+                continue;
+            }
+            let states = run.update_channels(&mut self.rng);
+            events.push(BusEvent::YieldHistory {
+                position: name.clone(),
+                points: run.points.clone(),
+                merge: false,
+            });
+            if !run.barcodes.is_empty() {
+                events.push(BusEvent::BarcodeYield {
+                    position: name.clone(),
+                    barcodes: run.barcode_yield(),
+                    merge: false,
+                });
+            }
+            events.push(BusEvent::PhaseHistory {
+                position: name.clone(),
+                events: run.phases.clone(),
+            });
+            events.push(BusEvent::MuxScans {
+                position: name.clone(),
+                scans: run.mux_scans.clone(),
+            });
+            events.push(BusEvent::TranslocationSpeed {
+                position: name.clone(),
+                points: run.speed.clone(),
+            });
+            events.push(BusEvent::RunUntilTargets {
+                position: name.clone(),
+                targets: run.targets,
+            });
+            events.push(BusEvent::RunUntilProgress {
+                position: name.clone(),
+                progress: run.run_until_progress(),
+            });
+            events.push(BusEvent::Output {
+                position: name.clone(),
+                info: run.output(),
+            });
+            events.push(BusEvent::DutyTime {
+                position: name.clone(),
+                duty_time: DutyTimeSnapshot {
+                    buckets: run.duty.clone(),
+                    pore_occupancy: run.pore_occupancy(),
+                    ..Default::default()
+                },
+            });
+            events.push(BusEvent::Histogram {
+                position: name.clone(),
+                histogram: run.histogram(context.histogram),
+            });
+            events.push(BusEvent::QScoreHistogram {
+                position: name.clone(),
+                histogram: run.qscore_histogram(),
+            });
+            if let Some(flow_cell) = &pos.flow_cell {
+                events.push(BusEvent::ChannelLayout {
+                    position: name.clone(),
+                    layout: flow_cell.layout.clone(),
+                });
+            }
+            events.push(BusEvent::ChannelStates {
+                position: name.clone(),
+                states,
+            });
+            if let Some(channel) = context.signal_channel_for(&name) {
+                events.push(BusEvent::Signal {
+                    position: name.clone(),
+                    chunk: run.signal(channel, &mut self.rng),
+                });
+            }
+            events.push(BusEvent::MeanQuality {
+                position: name,
+                quality: run.mean_quality(),
+            });
+        }
+        events
+    }
+
+    /// Protocols that fit the flow cell in `position`.
+    pub(super) fn protocols(&self, position: &str) -> Vec<ProtocolInfo> {
+        let Some(flow_cell) = self
+            .positions
+            .iter()
+            .find(|p| p.position.name == position)
+            .and_then(|p| p.flow_cell.as_ref())
+        else {
+            return Vec::new();
+        };
+        PROTOCOLS
+            .iter()
+            .filter(|p| p.flow_cell == flow_cell.kind)
+            .map(|p| ProtocolInfo {
+                identifier: p.identifier.to_string(),
+                name: p.name.to_string(),
+                experiment_type: Some("sequencing".to_string()),
+                kit: Some(p.kit.to_string()),
+                flow_cell: Some(p.flow_cell.product_code().to_string()),
+            })
+            .collect()
+    }
+
+    pub(super) fn start_run(
+        &mut self,
+        position: &str,
+        request: &StartRequest,
+    ) -> Result<(), String> {
+        let clock = self.clock;
+        let pos = self
+            .positions
+            .iter_mut()
+            .find(|p| p.position.name == position)
+            .ok_or("position not found")?;
+        let flow_cell = pos.flow_cell.as_ref().ok_or("no flow cell inserted")?;
+        if pos.run.as_ref().is_some_and(|r| r.state.is_active()) {
+            return Err("a run is already in progress".to_string());
+        }
+        let protocol = find_protocol(&request.identifier)
+            .filter(|p| p.flow_cell == flow_cell.kind)
+            .ok_or("protocol does not fit this flow cell")?;
+
+        let profile = RunProfile {
+            performance: self.rng.random_range(0.8..0.97),
+            read_length: self.rng.random_range(5_000.0..12_000.0),
+            quality: self.rng.random_range(17.5..19.0),
+        };
+        pos.run = Some(SimRun::new(
+            protocol,
+            &request.sample_id,
+            &request.experiment_group,
+            profile,
+            clock,
+            &mut self.rng,
+        ));
+        Ok(())
+    }
+
+    pub(super) fn set_run_until(
+        &mut self,
+        position: &str,
+        targets: RunUntilCriteria,
+    ) -> Result<(), String> {
+        let run = self
+            .position_mut(position)
+            .and_then(|p| p.run.as_mut())
+            .filter(|r| r.state.is_active())
+            .ok_or("no run in progress")?;
+        run.targets = targets;
+        Ok(())
+    }
+
+    pub(super) fn run_control(
+        &mut self,
+        position: &str,
+        action: RunControlAction,
+    ) -> Result<(), String> {
+        let clock = self.clock;
+        let run = self
+            .position_mut(position)
+            .and_then(|p| p.run.as_mut())
+            .ok_or("no run in progress")?;
+        match (action, &run.state) {
+            (RunControlAction::Pause, RunState::Running | RunState::MuxScanning) => {
+                run.set_phase(RunState::Paused, "Paused", clock)
+            }
+            (RunControlAction::Pause, _) => return Err("run is not sequencing".to_string()),
+            (RunControlAction::Resume, RunState::Paused) => {
+                run.set_phase(RunState::Running, "Sequencing", clock)
+            }
+            (RunControlAction::Resume, _) => return Err("run is not paused".to_string()),
+            (RunControlAction::Stop(_), RunState::Finishing) => {
+                return Err("run is already stopping".to_string())
+            }
+            (RunControlAction::Stop(options), _) => {
+                run.finish("Stopped by user", clock);
+                // Nothing is left to basecall.
+                if options.data_action == StopDataAction::KeepAllData {
+                    run.phase_left = 0.0;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::StopOptions;
+
+    fn simulation() -> Simulation {
+        Simulation::new(Utc::now(), SEED)
+    }
+
+    fn run<'a>(sim: &'a Simulation, position: &str) -> Option<&'a SimRun> {
+        sim.positions
+            .iter()
+            .find(|p| p.position.name == position)
+            .and_then(|p| p.run.as_ref())
+    }
+
+    #[test]
+    fn test_fleet_starts_mid_run() {
+        let sim = simulation();
+        assert_eq!(sim.positions.len(), 7);
+        assert_eq!(run(&sim, "X3").unwrap().state, RunState::Paused);
+        assert!(run(&sim, "X4").is_none());
+        let x4 = sim
+            .positions
+            .iter()
+            .find(|p| p.position.name == "X4")
+            .unwrap();
+        assert_eq!(x4.history.len(), 2);
+        assert!(x4.history[0].info.start_time > x4.history[1].info.start_time);
+
+        let x1 = run(&sim, "X1").unwrap();
+        assert!(x1.points.len() > 20 * 60);
+        assert!(x1.points.windows(2).all(|w| w[1].bases >= w[0].bases));
+        assert_eq!(x1.barcodes.len(), 13);
+        assert!(x1.phases.iter().any(|p| p.phase == "Pore scan"));
+
+        assert!(x1.mux_scans.len() >= 2, "initial scan plus periodic ones");
+        assert!(x1.mux_scans.windows(2).all(
+            |w| w[1].seconds > w[0].seconds && w[1].available_pores() <= w[0].available_pores()
+        ));
+
+        assert!(x1.speed.len() > 20 * 6);
+        assert!(x1.speed.last().unwrap().median < x1.speed[0].median);
+
+        let starts: Vec<_> = x1.duty.iter().map(|bucket| bucket.start).collect();
+        assert!(starts.windows(2).all(|w| w[1] == w[0] + DUTY_BUCKET as u32));
+        let (first, last) = (&x1.duty[0], x1.duty.last().unwrap());
+        assert!(last.fraction(ChannelState::Strand) < first.fraction(ChannelState::Strand));
+
+        let qscore = x1.qscore_histogram();
+        assert!((qscore.modal_q_score as f64 - x1.mean_quality()).abs() <= 1.0);
+        assert!(qscore.total_count() as f64 <= x1.totals.reads);
+
+        // Long reads carry more of the bases than of the reads.
+        let peak = |h: &ReadLengthHistogram| {
+            (0..h.bucket_values.len())
+                .max_by_key(|&i| h.bucket_values[i])
+                .unwrap()
+        };
+        let by_reads = x1.histogram(HistogramOptions::default());
+        let by_bases = x1.histogram(HistogramOptions {
+            weight_by_bases: true,
+            ..Default::default()
+        });
+        assert!(by_bases.weighted_by_bases);
+        assert!(peak(&by_bases) > peak(&by_reads));
+        assert!(by_bases.total_count() as f64 <= x1.totals.bases);
+    }
+
+    #[test]
+    fn test_chart_data_only_for_detail_position() {
+        let mut sim = simulation();
+        let context = PollContext {
+            detail_position: Some("X1".into()),
+            ..Default::default()
+        };
+        let events = sim.events(&context);
+        let channel_states: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                BusEvent::ChannelStates { position, states } => Some((position, states)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(channel_states.len(), 1);
+        assert_eq!(channel_states[0].0, "X1");
+        assert_eq!(channel_states[0].1.channel_count, 512);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, BusEvent::Stats { position, .. } if position == "X2")));
+    }
+
+    #[test]
+    fn test_signal_only_while_signal_chart_open() {
+        let mut sim = simulation();
+        let signal = |events: &[BusEvent]| {
+            events
+                .iter()
+                .filter_map(|e| match e {
+                    BusEvent::Signal { position, chunk } => Some((position.clone(), chunk.clone())),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut context = PollContext {
+            detail_position: Some("X1".into()),
+            ..Default::default()
+        };
+        assert!(signal(&sim.events(&context)).is_empty());
+
+        context.signal_channel = Some(3);
+        let first = signal(&sim.events(&context));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].0, "X1");
+        assert_eq!(first[0].1.channel, 3);
+        assert_eq!(first[0].1.picoamps.len(), SIGNAL_SAMPLES);
+
+        let second = signal(&sim.events(&context));
+        assert_eq!(second[0].1.samples_since_start, SIGNAL_SAMPLES as u64);
+    }
+
+    #[test]
+    fn test_run_control_and_completion() {
+        let mut sim = simulation();
+        let bases = |sim: &Simulation| run(sim, "X2").unwrap().totals.bases;
+
+        sim.run_control("X2", RunControlAction::Pause).unwrap();
+        let paused = bases(&sim);
+        sim.advance(3600.0);
+        assert_eq!(bases(&sim), paused);
+        assert!(sim.run_control("X2", RunControlAction::Pause).is_err());
+
+        sim.run_control("X2", RunControlAction::Resume).unwrap();
+        sim.advance(3600.0);
+        assert!(bases(&sim) > paused);
+
+        sim.run_control("X2", RunControlAction::Stop(StopOptions::default()))
+            .unwrap();
+        sim.advance(FINISHING_DURATION);
+        assert!(run(&sim, "X2").is_none());
+        let x2 = sim
+            .positions
+            .iter()
+            .find(|p| p.position.name == "X2")
+            .unwrap();
+        assert_eq!(x2.history[0].end_reason, "Stopped by user");
+        assert!(sim
+            .run_control("X2", RunControlAction::Stop(StopOptions::default()))
+            .is_err());
+
+        // Its charts stay available to the detail view.
+        let context = PollContext {
+            detail_position: Some("X2".into()),
+            ..Default::default()
+        };
+        let last_run = sim
+            .events(&context)
+            .into_iter()
+            .find_map(|event| match event {
+                BusEvent::LastRun { run, .. } => run.map(|run| *run),
+                _ => None,
+            });
+        let last_run = last_run.expect("finished run is published");
+        assert_eq!(last_run.summary.end_reason, "Stopped by user");
+        assert!(!last_run.yield_history.is_empty());
+    }
+
+    #[test]
+    fn test_run_until_target_completes_run() {
+        let mut sim = simulation();
+        let acquired = run(&sim, "X2").unwrap().acquired as u64;
+
+        let targets = RunUntilCriteria {
+            runtime: Some(acquired + 60),
+            ..Default::default()
+        };
+        sim.set_run_until("X2", targets).unwrap();
+        assert!(sim.set_run_until("X4", targets).is_err());
+
+        sim.advance(120.0);
+        let x2 = run(&sim, "X2").unwrap();
+        assert_eq!(x2.state, RunState::Finishing);
+        assert_eq!(x2.end_reason, "Completed");
+    }
+
+    #[test]
+    fn test_start_protocol_checks_flow_cell() {
+        let mut sim = simulation();
+        let protocols = sim.protocols("X4");
+        assert_eq!(protocols.len(), 3);
+        assert!(sim.protocols("X5").is_empty());
+
+        let request = |identifier: &str| StartRequest {
+            position_name: "X4".into(),
+            identifier: identifier.into(),
+            sample_id: "demo".into(),
+            experiment_group: "group".into(),
+        };
+        assert!(sim
+            .start_run("X4", &request(PROTOCOLS[3].identifier))
+            .is_err());
+        sim.start_run("X4", &request(&protocols[0].identifier))
+            .unwrap();
+        assert!(sim
+            .start_run("X4", &request(&protocols[0].identifier))
+            .is_err());
+        assert_eq!(run(&sim, "X4").unwrap().state, RunState::Starting);
+
+        sim.advance(STARTUP_DURATION);
+        assert_eq!(run(&sim, "X4").unwrap().state, RunState::Running);
+    }
+}
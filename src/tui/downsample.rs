@@ -0,0 +1,90 @@
+//! Largest-triangle-three-buckets (LTTB) downsampling for chart series.
+//!
+//! A line chart cannot show more points than its plot has braille dots
+//! across, and a multi-day run has far more samples than that. LTTB keeps
+//! the first and last points and, from each bucket in between, the point
+//! that forms the largest triangle with the point kept before it and the
+//! average of the next bucket. Peaks and bends survive where plain
+//! decimation would flatten them.
+
+/// Indices of the points LTTB keeps when reducing `points` to at most
+/// `threshold`, in order. Points must be sorted by x.
+pub fn lttb_indices(points: &[(f64, f64)], threshold: usize) -> Vec<usize> {
+    let n = points.len();
+    if n <= threshold.max(2) {
+        return (0..n).collect();
+    }
+    if threshold < 3 {
+        return vec![0, n - 1];
+    }
+
+    // Everything but the first and last point is split into buckets.
+    let bucket_size = (n - 2) as f64 / (threshold - 2) as f64;
+    let bucket_start = |i: usize| ((i as f64 * bucket_size) as usize + 1).min(n - 1);
+
+    let mut kept = Vec::with_capacity(threshold);
+    kept.push(0);
+    let mut previous = 0;
+    for bucket in 0..threshold - 2 {
+        let (start, end) = (bucket_start(bucket), bucket_start(bucket + 1));
+        let next = &points[end..bucket_start(bucket + 2).max(end + 1).min(n)];
+        let (avg_x, avg_y) = next.iter().fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+        let (avg_x, avg_y) = (avg_x / next.len() as f64, avg_y / next.len() as f64);
+
+        let (ax, ay) = points[previous];
+        let chosen = (start..end.max(start + 1))
+            .max_by(|&i, &j| {
+                let area = |k: usize| {
+                    let (bx, by) = points[k];
+                    ((ax - avg_x) * (by - ay) - (ax - bx) * (avg_y - ay)).abs()
+                };
+                area(i).total_cmp(&area(j))
+            })
+            .unwrap_or(start);
+        kept.push(chosen);
+        previous = chosen;
+    }
+    kept.push(n - 1);
+    kept
+}
+
+/// Reduces `points` to at most `threshold` with LTTB.
+pub fn lttb(points: &[(f64, f64)], threshold: usize) -> Vec<(f64, f64)> {
+    lttb_indices(points, threshold)
+        .into_iter()
+        .map(|i| points[i])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_series_unchanged() {
+        let points = [(0.0, 1.0), (1.0, 2.0), (2.0, 3.0)];
+        assert_eq!(lttb(&points, 10), points);
+        assert_eq!(lttb(&points, 3), points);
+        assert!(lttb(&[], 10).is_empty());
+    }
+
+    #[test]
+    fn test_keeps_ends_and_threshold() {
+        let points: Vec<(f64, f64)> = (0..1000).map(|i| (i as f64, (i as f64).sin())).collect();
+        let reduced = lttb(&points, 100);
+        assert_eq!(reduced.len(), 100);
+        assert_eq!(reduced.first(), points.first());
+        assert_eq!(reduced.last(), points.last());
+        assert!(reduced.windows(2).all(|w| w[0].0 < w[1].0));
+
+        assert_eq!(lttb(&points, 2), vec![points[0], points[999]]);
+    }
+
+    #[test]
+    fn test_keeps_spike() {
+        let mut points: Vec<(f64, f64)> = (0..500).map(|i| (i as f64, 0.0)).collect();
+        points[257].1 = 100.0;
+        let reduced = lttb(&points, 20);
+        assert!(reduced.contains(&(257.0, 100.0)));
+    }
+}
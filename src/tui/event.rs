@@ -1,54 +1,87 @@
 //! Event handling for the TUI.
 
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyModifiers};
-use std::time::Duration;
-use tokio::sync::mpsc;
+use crossterm::event::{
+    Event as CrosstermEvent, EventStream, KeyCode, KeyEvent, KeyModifiers, MouseEvent,
+    MouseEventKind,
+};
+use futures::StreamExt;
+use ratatui::layout::{Position, Rect};
+use std::time::{Duration, Instant};
+use tokio::time::{Interval, MissedTickBehavior};
 
 #[derive(Debug, Clone)]
 pub enum Event {
     Key(KeyEvent),
+    Mouse(MouseEvent),
     Tick,
     Resize(u16, u16),
+    FocusGained,
+    FocusLost,
+    /// Text pasted into the terminal while bracketed paste is enabled.
+    Paste(String),
 }
 
-const EVENT_CHANNEL_CAPACITY: usize = 256;
+impl Event {
+    /// Converts a terminal event, dropping the ones the TUI does not use.
+    fn from_terminal(event: CrosstermEvent) -> Option<Self> {
+        match event {
+            CrosstermEvent::Key(key) => Some(Event::Key(key)),
+            // Motion and release events are not used; dropping them keeps
+            // mouse movement from waking the render loop.
+            CrosstermEvent::Mouse(mouse)
+                if matches!(
+                    mouse.kind,
+                    MouseEventKind::Down(_) | MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                ) =>
+            {
+                Some(Event::Mouse(mouse))
+            }
+            CrosstermEvent::Mouse(_) => None,
+            CrosstermEvent::Resize(w, h) => Some(Event::Resize(w, h)),
+            CrosstermEvent::FocusGained => Some(Event::FocusGained),
+            CrosstermEvent::FocusLost => Some(Event::FocusLost),
+            CrosstermEvent::Paste(text) => Some(Event::Paste(text)),
+        }
+    }
+}
 
+/// Terminal input and periodic ticks, read on the tokio runtime.
 pub struct EventHandler {
-    rx: mpsc::Receiver<Event>,
-    _tx: mpsc::Sender<Event>,
+    input: EventStream,
+    ticks: Interval,
 }
 
 impl EventHandler {
     pub fn new(tick_rate: Duration) -> Self {
-        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
-        let _tx = tx.clone();
-
-        let event_tx = tx.clone();
-        std::thread::spawn(move || loop {
-            if event::poll(tick_rate).unwrap_or(false) {
-                match event::read() {
-                    Ok(CrosstermEvent::Key(key)) => {
-                        if event_tx.blocking_send(Event::Key(key)).is_err() {
-                            break;
+        let mut ticks = tokio::time::interval(tick_rate);
+        // A tick is only a prompt to look for new data; catching up on
+        // missed ones would just redraw repeatedly.
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            input: EventStream::new(),
+            ticks,
+        }
+    }
+
+    /// Waits for the next input event or tick. Returns `None` once the
+    /// terminal can no longer be read.
+    pub async fn next(&mut self) -> Option<Event> {
+        loop {
+            tokio::select! {
+                _ = self.ticks.tick() => return Some(Event::Tick),
+                event = self.input.next() => match event? {
+                    Ok(event) => {
+                        if let Some(event) = Event::from_terminal(event) {
+                            return Some(event);
                         }
                     }
-                    Ok(CrosstermEvent::Resize(w, h)) => {
-                        if event_tx.blocking_send(Event::Resize(w, h)).is_err() {
-                            break;
-                        }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to read terminal input");
+                        return None;
                     }
-                    _ => {}
-                }
-            } else if event_tx.blocking_send(Event::Tick).is_err() {
-                break;
+                },
             }
-        });
-
-        Self { rx, _tx }
-    }
-
-    pub async fn next(&mut self) -> Option<Event> {
-        self.rx.recv().await
+        }
     }
 }
 
@@ -57,6 +90,8 @@ pub enum Action {
     Quit,
     Up,
     Down,
+    Left,
+    Right,
     Enter,
     Back,
     Help,
@@ -65,14 +100,54 @@ pub enum Action {
     Resume,
     Stop,
     ToggleYieldUnit,
+    CycleYieldWindow,
+    ToggleDualAxis,
+    ToggleYieldRates,
+    ToggleLegend,
+    ToggleTotalSeries,
+    TogglePassedSeries,
+    ToggleFailedSeries,
     ToggleOutliers,
+    ToggleHistogramWeight,
+    ToggleProjection,
     ChartYield,
     ChartReadLength,
     ChartPoreActivity,
+    ChartBarcodes,
+    ChartSignal,
+    ChartQScore,
+    ChartPoreScans,
+    ChartActivePores,
+    ChartDutyTime,
     CycleChart,
+    PreviousPosition,
+    NextPosition,
+    Inspect,
+    TimelapseBack,
+    TimelapseForward,
+    ToggleOccupancyColors,
     HistogramSetRange,
     HistogramResetRange,
+    CycleBarcodeSort,
+    CycleOverviewSort,
+    ReverseOverviewSort,
+    TogglePin,
+    ToggleHideSimulated,
+    ToggleHideIdle,
+    ToggleClockTime,
+    ExportChart,
+    ToggleFreeze,
+    CopyRunId,
+    CopySummary,
+    Filter,
     ThemeSelector,
+    Diagnostics,
+    RunHistory,
+    StartProtocol,
+    RunUntil,
+    DebugOverlay,
+    ErrorLog,
+    EditNote,
     None,
 }
 
@@ -83,6 +158,8 @@ impl From<KeyEvent> for Action {
             KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Action::Quit,
             KeyCode::Up | KeyCode::Char('k') => Action::Up,
             KeyCode::Down | KeyCode::Char('j') => Action::Down,
+            KeyCode::Left | KeyCode::Char('h') => Action::Left,
+            KeyCode::Right | KeyCode::Char('l') => Action::Right,
             KeyCode::Enter => Action::Enter,
             KeyCode::Esc => Action::Back,
             KeyCode::Char('?') => Action::Help,
@@ -91,15 +168,179 @@ impl From<KeyEvent> for Action {
             KeyCode::Char('r') => Action::Resume,
             KeyCode::Char('s') => Action::Stop,
             KeyCode::Char('t') => Action::ToggleYieldUnit,
+            KeyCode::Char('v') => Action::CycleYieldWindow,
+            KeyCode::Char('a') => Action::ToggleDualAxis,
+            KeyCode::Char('g') => Action::ToggleYieldRates,
+            KeyCode::Char('L') => Action::ToggleLegend,
+            KeyCode::Char('A') => Action::ToggleTotalSeries,
+            KeyCode::Char('P') => Action::TogglePassedSeries,
+            KeyCode::Char('F') => Action::ToggleFailedSeries,
             KeyCode::Char('o') => Action::ToggleOutliers,
+            KeyCode::Char('w') => Action::ToggleHistogramWeight,
+            KeyCode::Char('e') => Action::ToggleProjection,
             KeyCode::Char('1') => Action::ChartYield,
             KeyCode::Char('2') => Action::ChartReadLength,
             KeyCode::Char('3') => Action::ChartPoreActivity,
+            KeyCode::Char('4') => Action::ChartBarcodes,
+            KeyCode::Char('5') => Action::ChartSignal,
+            KeyCode::Char('6') => Action::ChartQScore,
+            KeyCode::Char('7') => Action::ChartPoreScans,
+            KeyCode::Char('8') => Action::ChartActivePores,
+            KeyCode::Char('9') => Action::ChartDutyTime,
             KeyCode::Tab => Action::CycleChart,
+            KeyCode::Char('[') => Action::PreviousPosition,
+            KeyCode::Char(']') => Action::NextPosition,
+            KeyCode::Char('c') => Action::Inspect,
+            KeyCode::Char(',') => Action::TimelapseBack,
+            KeyCode::Char('.') => Action::TimelapseForward,
+            KeyCode::Char('O') => Action::ToggleOccupancyColors,
             KeyCode::Char('z') => Action::HistogramSetRange,
             KeyCode::Char('0') => Action::HistogramResetRange,
+            KeyCode::Char('b') => Action::CycleBarcodeSort,
+            KeyCode::Char('S') => Action::CycleOverviewSort,
+            KeyCode::Char('I') => Action::ReverseOverviewSort,
+            KeyCode::Char('f') => Action::TogglePin,
+            KeyCode::Char('m') => Action::ToggleHideSimulated,
+            KeyCode::Char('i') => Action::ToggleHideIdle,
+            KeyCode::Char('C') => Action::ToggleClockTime,
+            KeyCode::Char('x') => Action::ExportChart,
+            KeyCode::Char(' ') => Action::ToggleFreeze,
+            KeyCode::Char('y') => Action::CopyRunId,
+            KeyCode::Char('Y') => Action::CopySummary,
+            KeyCode::Char('/') => Action::Filter,
             KeyCode::Char('T') => Action::ThemeSelector,
+            KeyCode::Char('d') => Action::Diagnostics,
+            KeyCode::Char('H') => Action::RunHistory,
+            KeyCode::Char('n') => Action::StartProtocol,
+            KeyCode::Char('u') => Action::RunUntil,
+            KeyCode::Char('D') => Action::DebugOverlay,
+            KeyCode::Char('E') => Action::ErrorLog,
+            KeyCode::Char('N') => Action::EditNote,
             _ => Action::None,
         }
     }
 }
+
+/// Maximum gap between two clicks on the same target to count as a double-click.
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// What a clickable screen region does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseTarget {
+    /// A row of the table on the current screen.
+    Row(usize),
+    /// A footer hint or tab that triggers an action.
+    Action(Action),
+    /// A cell of the channel map, by 0-based channel.
+    Channel(usize),
+}
+
+/// Clickable regions of the last drawn frame.
+///
+/// Rendering records each region as it is drawn, so hit-testing always
+/// matches what is on screen. Later regions win where they overlap.
+#[derive(Debug, Default)]
+pub struct HitMap {
+    regions: Vec<(Rect, MouseTarget)>,
+}
+
+impl HitMap {
+    pub fn add(&mut self, area: Rect, target: MouseTarget) {
+        if !area.is_empty() {
+            self.regions.push((area, target));
+        }
+    }
+
+    pub fn target_at(&self, column: u16, row: u16) -> Option<MouseTarget> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(Position::new(column, row)))
+            .map(|(_, target)| *target)
+    }
+}
+
+/// Detects double-clicks, which the terminal reports as two separate clicks.
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    last: Option<(Instant, MouseTarget)>,
+}
+
+impl ClickTracker {
+    /// Records a click, returning true if it completes a double-click.
+    pub fn click(&mut self, target: MouseTarget) -> bool {
+        self.click_at(target, Instant::now())
+    }
+
+    fn click_at(&mut self, target: MouseTarget, now: Instant) -> bool {
+        let double = self.last.is_some_and(|(at, last)| {
+            last == target && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        });
+        // A third click starts a new pair rather than another double-click.
+        self.last = (!double).then_some((now, target));
+        double
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_map_prefers_latest_region() {
+        let mut hits = HitMap::default();
+        hits.add(Rect::new(0, 0, 20, 10), MouseTarget::Row(0));
+        hits.add(Rect::new(5, 2, 5, 1), MouseTarget::Action(Action::Help));
+        hits.add(Rect::new(0, 0, 0, 0), MouseTarget::Row(9));
+
+        assert_eq!(
+            hits.target_at(6, 2),
+            Some(MouseTarget::Action(Action::Help))
+        );
+        assert_eq!(hits.target_at(1, 1), Some(MouseTarget::Row(0)));
+        assert_eq!(hits.target_at(20, 1), None);
+    }
+
+    #[test]
+    fn test_terminal_events_drop_mouse_motion() {
+        use crossterm::event::MouseButton;
+
+        let mouse = |kind| {
+            CrosstermEvent::Mouse(MouseEvent {
+                kind,
+                column: 0,
+                row: 0,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        assert!(Event::from_terminal(mouse(MouseEventKind::Moved)).is_none());
+        assert!(Event::from_terminal(mouse(MouseEventKind::Up(MouseButton::Left))).is_none());
+        assert!(matches!(
+            Event::from_terminal(mouse(MouseEventKind::Down(MouseButton::Left))),
+            Some(Event::Mouse(_))
+        ));
+        assert!(matches!(
+            Event::from_terminal(CrosstermEvent::FocusLost),
+            Some(Event::FocusLost)
+        ));
+        assert!(matches!(
+            Event::from_terminal(CrosstermEvent::Paste("FAW".into())),
+            Some(Event::Paste(text)) if text == "FAW"
+        ));
+    }
+
+    #[test]
+    fn test_double_click_detection() {
+        let mut clicks = ClickTracker::default();
+        let start = Instant::now();
+        let row = MouseTarget::Row(1);
+
+        assert!(!clicks.click_at(row, start));
+        assert!(clicks.click_at(row, start + Duration::from_millis(200)));
+        assert!(!clicks.click_at(row, start + Duration::from_millis(300)));
+
+        // Too slow, or on a different target.
+        assert!(!clicks.click_at(row, start + Duration::from_secs(2)));
+        assert!(!clicks.click_at(MouseTarget::Row(2), start + Duration::from_millis(2100)));
+    }
+}
@@ -0,0 +1,597 @@
+//! Exports of the chart on the detail screen.
+//!
+//! `x` writes the data behind the chart on screen to a CSV file in
+//! `tui.export_dir`, with an SVG rendering next to it for the charts that
+//! have one. Files are named after the position, the chart and the time of
+//! the export, so repeated exports never overwrite each other. Yield exports
+//! follow the window, unit and lines the chart shows.
+
+use super::app::{App, DetailChart, Screen, ToastLevel, YieldSeries, YieldUnit};
+use crate::analysis;
+use crate::cli::csv;
+use crate::cli::report::svg::{self, merge_buckets, Series, BAR_COLOR, FAILED_COLOR, PASSED_COLOR};
+use crate::client::{ChannelState, YieldDataPoint};
+use crate::format::format_bases;
+use chrono::{DateTime, Local};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Bins the exported throughput is measured over, about as many as a wide
+/// terminal plots.
+const RATE_BINS: u32 = 100;
+
+/// Order of the channel state columns in a duty time export.
+const DUTY_STATES: [ChannelState; 6] = [
+    ChannelState::Strand,
+    ChannelState::Pore,
+    ChannelState::Adapter,
+    ChannelState::Unblock,
+    ChannelState::Unavailable,
+    ChannelState::Other,
+];
+
+/// The data behind one chart, ready to write out.
+#[derive(Debug)]
+pub struct ChartExport {
+    /// Names the chart in the file name, e.g. `yield`.
+    pub name: &'static str,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Rendering of the chart, for charts that have one.
+    pub svg: Option<String>,
+}
+
+/// Exports the chart on the detail screen and reports where it went in a
+/// toast.
+pub fn export_chart(app: &mut App) {
+    let Screen::PositionDetail { position_idx } = app.screen else {
+        return;
+    };
+    let Some(position_name) = app.positions.get(position_idx).map(|p| p.name.clone()) else {
+        return;
+    };
+    let Some(export) = chart_export(app, &position_name) else {
+        app.push_toast(ToastLevel::Warning, "No chart data to export yet".into());
+        return;
+    };
+    match write(
+        &app.config.tui.export_dir,
+        &position_name,
+        &export,
+        Local::now(),
+    ) {
+        Ok(path) => {
+            tracing::info!(path = %path.display(), "Exported chart");
+            let also = if export.svg.is_some() { " (+ SVG)" } else { "" };
+            app.push_toast(
+                ToastLevel::Info,
+                format!("Exported {}{}", path.display(), also),
+            );
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to export chart");
+            app.push_toast(ToastLevel::Error, format!("Export failed: {}", e));
+        }
+    }
+}
+
+/// The data behind the chart a position's detail screen shows, or `None`
+/// while it has none.
+pub fn chart_export(app: &App, position_name: &str) -> Option<ChartExport> {
+    match app.detail_chart {
+        DetailChart::Yield => {
+            let points = app.yield_points(position_name).filter(|p| !p.is_empty())?;
+            if app.yield_rates {
+                throughput_export(app, position_name, points)
+            } else {
+                Some(yield_export(app, position_name, points))
+            }
+        }
+        DetailChart::ReadLength => {
+            let histogram = app
+                .histograms
+                .get(position_name)
+                .filter(|h| h.total_count() > 0)?;
+            let buckets = || {
+                histogram
+                    .bucket_ranges
+                    .iter()
+                    .zip(&histogram.bucket_values)
+                    .map(|(&(start, end), &count)| (start as f64, end as f64, count))
+            };
+            let unit = if histogram.weighted_by_bases {
+                "Bases"
+            } else {
+                "Reads"
+            };
+            let bars = merge_buckets(buckets(), |start, _| format_bases(start as u64));
+            Some(ChartExport {
+                name: "read-lengths",
+                header: header(&["start", "end", &unit.to_lowercase()]),
+                rows: buckets()
+                    .map(|(start, end, count)| {
+                        vec![start.to_string(), end.to_string(), count.to_string()]
+                    })
+                    .collect(),
+                svg: Some(svg::bar_chart(
+                    "Read length distribution",
+                    "Read length",
+                    unit,
+                    &bars,
+                    BAR_COLOR,
+                )),
+            })
+        }
+        DetailChart::QScore => {
+            let histogram = app
+                .qscore_histograms
+                .get(position_name)
+                .filter(|h| h.total_count() > 0)?;
+            let buckets = || {
+                histogram
+                    .bucket_ranges
+                    .iter()
+                    .zip(&histogram.bucket_values)
+                    .map(|(&(start, end), &count)| (start as f64, end as f64, count))
+            };
+            let bars = merge_buckets(buckets(), |start, _| format!("Q{:.0}", start));
+            Some(ChartExport {
+                name: "qscores",
+                header: header(&["start", "end", "reads"]),
+                rows: buckets()
+                    .map(|(start, end, count)| {
+                        vec![start.to_string(), end.to_string(), count.to_string()]
+                    })
+                    .collect(),
+                svg: Some(svg::bar_chart(
+                    "Read q-score distribution",
+                    "Mean q-score",
+                    "Reads",
+                    &bars,
+                    BAR_COLOR,
+                )),
+            })
+        }
+        DetailChart::Barcodes => {
+            let barcodes = app.sorted_barcodes(position_name);
+            if barcodes.is_empty() {
+                return None;
+            }
+            let bars: Vec<(String, f64)> = barcodes
+                .iter()
+                .map(|b| (b.barcode.clone(), b.bases() as f64))
+                .collect();
+            Some(ChartExport {
+                name: "barcodes",
+                header: header(&["barcode", "reads", "bases"]),
+                rows: barcodes
+                    .iter()
+                    .map(|b| {
+                        vec![
+                            b.barcode.clone(),
+                            b.reads().to_string(),
+                            b.bases().to_string(),
+                        ]
+                    })
+                    .collect(),
+                svg: Some(svg::bar_chart(
+                    "Yield per barcode",
+                    "Barcode",
+                    "Bases",
+                    &bars,
+                    BAR_COLOR,
+                )),
+            })
+        }
+        DetailChart::PoreScans => {
+            let scans = app.mux_scans.get(position_name).filter(|s| !s.is_empty())?;
+            let mut categories: Vec<&String> = scans.iter().flat_map(|s| s.counts.keys()).collect();
+            categories.sort();
+            categories.dedup();
+            let mut columns = vec!["seconds".to_string(), "time".to_string()];
+            columns.extend(categories.iter().map(|c| c.to_string()));
+            columns.push("available".to_string());
+            let rows = scans
+                .iter()
+                .map(|scan| {
+                    let mut row = vec![
+                        scan.seconds.to_string(),
+                        clock_time(app, position_name, scan.seconds as f64),
+                    ];
+                    row.extend(
+                        categories
+                            .iter()
+                            .map(|&c| scan.counts.get(c).copied().unwrap_or(0).to_string()),
+                    );
+                    row.push(scan.available_pores().to_string());
+                    row
+                })
+                .collect();
+            let points = scans
+                .iter()
+                .map(|s| (s.seconds as f64 / 3600.0, s.available_pores() as f64))
+                .collect();
+            Some(ChartExport {
+                name: "pore-scans",
+                header: columns,
+                rows,
+                svg: Some(line_chart(
+                    "Available pores",
+                    "Hours",
+                    "Pores",
+                    "Available",
+                    points,
+                )),
+            })
+        }
+        DetailChart::ActivePores => {
+            let buffer = app
+                .pore_history
+                .get(position_name)
+                .filter(|b| !b.data.is_empty())?;
+            let first = buffer.data.front().map_or(0.0, |&(x, _)| x);
+            let points = buffer
+                .data
+                .iter()
+                .map(|&(x, pores)| ((x - first) / 3600.0, pores))
+                .collect();
+            Some(ChartExport {
+                name: "active-pores",
+                header: header(&["seconds", "active_pores"]),
+                rows: buffer
+                    .data
+                    .iter()
+                    .map(|&(x, pores)| vec![format!("{:.0}", x - first), pores.to_string()])
+                    .collect(),
+                svg: Some(line_chart(
+                    "Active pores",
+                    "Hours",
+                    "Pores",
+                    "Active",
+                    points,
+                )),
+            })
+        }
+        DetailChart::DutyTime => {
+            let buckets = app
+                .duty_history
+                .get(position_name)
+                .filter(|b| !b.is_empty())?;
+            let mut columns = header(&["start", "end"]);
+            columns.extend(DUTY_STATES.iter().map(|s| s.label().to_lowercase()));
+            Some(ChartExport {
+                name: "duty-time",
+                header: columns,
+                rows: buckets
+                    .iter()
+                    .map(|bucket| {
+                        let mut row = vec![bucket.start.to_string(), bucket.end.to_string()];
+                        row.extend(
+                            DUTY_STATES
+                                .iter()
+                                .map(|&s| format!("{:.4}", bucket.fraction(s))),
+                        );
+                        row
+                    })
+                    .collect(),
+                svg: None,
+            })
+        }
+        DetailChart::Signal => {
+            let samples = app.signal_trace(position_name)?;
+            let points = samples
+                .iter()
+                .enumerate()
+                .map(|(i, &pa)| (i as f64, pa as f64))
+                .collect();
+            Some(ChartExport {
+                name: "signal",
+                header: header(&["sample", "current_pa"]),
+                rows: samples
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pa)| vec![i.to_string(), pa.to_string()])
+                    .collect(),
+                svg: Some(line_chart(
+                    &format!("Raw signal, channel {}", app.signal_channel),
+                    "Sample",
+                    "Current (pA)",
+                    "Signal",
+                    points,
+                )),
+            })
+        }
+        DetailChart::PoreActivity => {
+            let states = app
+                .channel_states
+                .get(position_name)
+                .filter(|s| !s.states.is_empty())?;
+            Some(ChartExport {
+                name: "channels",
+                header: header(&["channel", "state"]),
+                rows: states
+                    .states
+                    .iter()
+                    .enumerate()
+                    .map(|(i, state)| vec![(i + 1).to_string(), state.clone()])
+                    .collect(),
+                svg: None,
+            })
+        }
+    }
+}
+
+/// Writes `export` into `dir`, creating it if needed, and returns the path
+/// of the CSV file.
+pub fn write(
+    dir: &Path,
+    position_name: &str,
+    export: &ChartExport,
+    now: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let stem = format!(
+        "{}-{}-{}",
+        file_name_safe(position_name),
+        export.name,
+        now.format("%Y%m%d-%H%M%S")
+    );
+    let path = dir.join(format!("{stem}.csv"));
+    let mut out = BufWriter::new(File::create(&path)?);
+    csv::write_row(&mut out, &export.header)?;
+    for row in &export.rows {
+        csv::write_row(&mut out, row)?;
+    }
+    out.flush()?;
+    if let Some(svg) = &export.svg {
+        fs::write(dir.join(format!("{stem}.svg")), svg)?;
+    }
+    Ok(path)
+}
+
+fn yield_export(app: &App, position_name: &str, points: &[YieldDataPoint]) -> ChartExport {
+    let first = points[0].seconds;
+    let value = unit_values(app.yield_unit);
+    let series: Vec<Series> = visible_series(app)
+        .map(|(series, color, index)| Series {
+            label: series.label(),
+            color,
+            points: points
+                .iter()
+                .map(|p| {
+                    let hours = (p.seconds - first) as f64 / 3600.0;
+                    (hours, value(p)[index] as f64)
+                })
+                .collect(),
+        })
+        .collect();
+    ChartExport {
+        name: "yield",
+        header: header(&[
+            "seconds",
+            "time",
+            "reads",
+            "reads_passed",
+            "reads_failed",
+            "bases",
+            "bases_passed",
+            "bases_failed",
+        ]),
+        rows: points
+            .iter()
+            .map(|p| {
+                vec![
+                    p.seconds.to_string(),
+                    clock_time(app, position_name, p.seconds as f64),
+                    p.reads.to_string(),
+                    p.reads_passed.to_string(),
+                    p.reads_failed.to_string(),
+                    p.bases.to_string(),
+                    p.bases_passed.to_string(),
+                    p.bases_failed.to_string(),
+                ]
+            })
+            .collect(),
+        svg: Some(svg::line_chart(
+            "Cumulative yield",
+            "Hours",
+            unit_label(app.yield_unit),
+            &series,
+        )),
+    }
+}
+
+/// Output per hour of the total, passed and failed yield in the chart's
+/// unit. `None` until the yield spans one bin.
+fn throughput_export(
+    app: &App,
+    position_name: &str,
+    points: &[YieldDataPoint],
+) -> Option<ChartExport> {
+    let value = unit_values(app.yield_unit);
+    let rates: Vec<Vec<(u32, f64)>> = (0..3)
+        .map(|index| {
+            let samples: Vec<(u32, u64)> = points
+                .iter()
+                .map(|p| (p.seconds, value(p)[index]))
+                .collect();
+            analysis::rates(&samples, RATE_BINS)
+        })
+        .collect();
+    if rates[0].is_empty() {
+        return None;
+    }
+    let first = points[0].seconds;
+    let series: Vec<Series> = visible_series(app)
+        .map(|(series, color, index)| Series {
+            label: series.label(),
+            color,
+            points: rates[index]
+                .iter()
+                .map(|&(seconds, rate)| ((seconds - first) as f64 / 3600.0, rate))
+                .collect(),
+        })
+        .collect();
+    let unit = unit_label(app.yield_unit).to_lowercase();
+    Some(ChartExport {
+        name: "throughput",
+        header: vec![
+            "seconds".to_string(),
+            "time".to_string(),
+            format!("{unit}_per_hour"),
+            format!("{unit}_passed_per_hour"),
+            format!("{unit}_failed_per_hour"),
+        ],
+        rows: (0..rates[0].len())
+            .map(|bin| {
+                let seconds = rates[0][bin].0;
+                vec![
+                    seconds.to_string(),
+                    clock_time(app, position_name, seconds as f64),
+                    format!("{:.1}", rates[0][bin].1),
+                    format!("{:.1}", rates[1][bin].1),
+                    format!("{:.1}", rates[2][bin].1),
+                ]
+            })
+            .collect(),
+        svg: Some(svg::line_chart(
+            "Throughput",
+            "Hours",
+            &format!("{} per hour", unit_label(app.yield_unit)),
+            &series,
+        )),
+    })
+}
+
+/// Total, passed and failed yield of a point in `unit`.
+fn unit_values(unit: YieldUnit) -> fn(&YieldDataPoint) -> [u64; 3] {
+    match unit {
+        YieldUnit::Bases => |p| [p.bases, p.bases_passed, p.bases_failed],
+        YieldUnit::Reads => |p| [p.reads, p.reads_passed, p.reads_failed],
+    }
+}
+
+fn unit_label(unit: YieldUnit) -> &'static str {
+    match unit {
+        YieldUnit::Bases => "Bases",
+        YieldUnit::Reads => "Reads",
+    }
+}
+
+/// The yield lines the chart shows, with their color and index into
+/// [`unit_values`].
+fn visible_series(app: &App) -> impl Iterator<Item = (YieldSeries, &'static str, usize)> + '_ {
+    [
+        (YieldSeries::Total, BAR_COLOR, 0),
+        (YieldSeries::Passed, PASSED_COLOR, 1),
+        (YieldSeries::Failed, FAILED_COLOR, 2),
+    ]
+    .into_iter()
+    .filter(|&(series, _, _)| app.yield_series.is_visible(series))
+}
+
+/// A line chart of a single series.
+fn line_chart(
+    title: &str,
+    x_label: &str,
+    y_label: &str,
+    label: &str,
+    points: Vec<(f64, f64)>,
+) -> String {
+    svg::line_chart(
+        title,
+        x_label,
+        y_label,
+        &[Series {
+            label,
+            color: BAR_COLOR,
+            points,
+        }],
+    )
+}
+
+/// Clock time `seconds` into the acquisition, empty until its start is
+/// known.
+fn clock_time(app: &App, position_name: &str, seconds: f64) -> String {
+    csv::optional(app.clock_at(position_name, seconds).map(|t| t.to_rfc3339()))
+}
+
+fn header(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|c| c.to_string()).collect()
+}
+
+/// `name` with anything but letters, digits, `-` and `_` replaced, since
+/// positions of several hosts are named `<host>/<position>`.
+fn file_name_safe(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_yield_export_writes_csv_and_svg() {
+        let mut app = App::new(Config::default());
+        app.yield_history.insert(
+            "host/X1".to_string(),
+            vec![
+                YieldDataPoint {
+                    seconds: 0,
+                    ..Default::default()
+                },
+                YieldDataPoint {
+                    seconds: 3600,
+                    reads: 100,
+                    reads_passed: 90,
+                    reads_failed: 10,
+                    bases: 5000,
+                    bases_passed: 4500,
+                    bases_failed: 500,
+                },
+            ],
+        );
+        let export = chart_export(&app, "host/X1").unwrap();
+        assert_eq!(export.name, "yield");
+        assert_eq!(export.rows.len(), 2);
+        assert_eq!(
+            export.rows[1],
+            ["3600", "", "100", "90", "10", "5000", "4500", "500"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let now = Local::now();
+        let path = write(dir.path(), "host/X1", &export, now).unwrap();
+        let stem = format!("host_X1-yield-{}", now.format("%Y%m%d-%H%M%S"));
+        assert_eq!(path, dir.path().join(format!("{stem}.csv")));
+        let csv = fs::read_to_string(&path).unwrap();
+        assert!(csv.starts_with("seconds,time,reads,reads_passed,"));
+        assert!(csv.ends_with("3600,,100,90,10,5000,4500,500\r\n"));
+        let svg = fs::read_to_string(dir.path().join(format!("{stem}.svg"))).unwrap();
+        assert!(svg.contains(">Passed<"));
+
+        // Hidden lines are left out of the rendering, not the data.
+        app.toggle_yield_series(YieldSeries::Passed);
+        let export = chart_export(&app, "host/X1").unwrap();
+        assert_eq!(export.header.len(), 8);
+        assert!(!export.svg.unwrap().contains(">Passed<"));
+    }
+
+    #[test]
+    fn test_chart_without_data_has_no_export() {
+        let mut app = App::new(Config::default());
+        assert!(chart_export(&app, "X1").is_none());
+        app.detail_chart = DetailChart::DutyTime;
+        assert!(chart_export(&app, "X1").is_none());
+    }
+}
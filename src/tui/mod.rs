@@ -3,51 +3,138 @@
 //! This module implements the TUI using ratatui and crossterm.
 //! It provides real-time visualization of sequencing data.
 
+mod api;
 mod app;
+mod clipboard;
+mod demo;
+mod downsample;
 mod event;
+mod export;
+mod poller;
+mod replay;
+mod source;
+mod streams;
 pub mod theme;
 mod ui;
 
 pub use app::{
-    App, ChartBuffer, ConnectionState, DetailChart, Overlay, RunControlAction, Screen, YieldUnit,
+    App, ChannelOccupancy, ChannelTimelapse, ChartBuffer, ConnectionState, DetailChart, Overlay,
+    OverviewSort, PerfStats, RunControlAction, RunUntilRequest, Screen, StartRequest, StatValue,
+    TimelapseFrame, Toast, ToastLevel, YieldSeries, YieldUnit,
 };
-pub use event::{Action, Event, EventHandler};
+pub use event::{Action, ClickTracker, Event, EventHandler, HitMap, MouseTarget};
 
-use crate::client::{Client, RunState};
+use crate::bus::{BusEvent, EventBus, EventFilter, Subscription};
+use crate::client::StopOptions;
 use crate::config::Config;
+use crate::history;
+use crate::notify;
+use crate::session;
+use crate::sink;
+#[cfg(feature = "storage")]
+use crate::storage;
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, MouseButton, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+use poller::{PollContext, Poller};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    Terminal,
+};
+use source::DataSource;
 use std::io;
+use theme::Theme;
+use tokio::task::JoinHandle;
 
 pub async fn run(config: Config) -> anyhow::Result<()> {
-    let mut terminal = setup_terminal()?;
-    let result = run_app(&mut terminal, config).await;
-    restore_terminal(&mut terminal)?;
+    Theme::set_user_themes(config.tui.themes.clone());
+    install_panic_hook(&config);
+    let mut terminal = TerminalGuard::new()?;
+    let result = run_app(&mut terminal.terminal, config).await;
+    drop(terminal);
+    // Back to the default hook, which prints to the now usable terminal.
+    let _ = std::panic::take_hook();
     result
 }
 
-fn setup_terminal() -> anyhow::Result<Terminal<CrosstermBackend<io::Stdout>>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let terminal = Terminal::new(backend)?;
-    Ok(terminal)
+/// The TUI's terminal, in raw mode on the alternate screen until dropped.
+///
+/// Restoring on drop leaves the terminal usable however `run` returns,
+/// including by unwinding.
+struct TerminalGuard {
+    terminal: Terminal<CrosstermBackend<io::Stdout>>,
+}
+
+impl TerminalGuard {
+    fn new() -> anyhow::Result<Self> {
+        enable_raw_mode()?;
+        let setup = execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange,
+            EnableBracketedPaste
+        )
+        .and_then(|()| Terminal::new(CrosstermBackend::new(io::stdout())));
+        match setup {
+            Ok(terminal) => Ok(Self { terminal }),
+            Err(e) => {
+                let _ = restore_terminal();
+                Err(e.into())
+            }
+        }
+    }
 }
 
-fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> anyhow::Result<()> {
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if let Err(e) = restore_terminal() {
+            tracing::warn!(error = %e, "Failed to restore terminal");
+        }
+    }
+}
+
+/// Leaves raw mode and the alternate screen. Safe to call more than once.
+fn restore_terminal() -> io::Result<()> {
     disable_raw_mode()?;
     execute!(
-        terminal.backend_mut(),
+        io::stdout(),
         LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
+        DisableMouseCapture,
+        DisableFocusChange,
+        DisableBracketedPaste,
+        crossterm::cursor::Show
+    )
+}
+
+/// Makes any panic, including one in a background task, restore the
+/// terminal, write the panic and a backtrace to the log file, and exit.
+///
+/// Without this a panic leaves the shell in raw mode on the alternate
+/// screen, and one in a background task leaves the TUI running without it.
+fn install_panic_hook(config: &Config) {
+    let log_file =
+        (config.logging.level != crate::config::LogLevel::Off).then(|| config.logging.file.clone());
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_hook(info);
+        if let Some(path) = &log_file {
+            let backtrace = std::backtrace::Backtrace::force_capture();
+            if crate::logging::log_panic(path, &info.to_string(), &backtrace).is_ok() {
+                eprintln!(
+                    "The panic and a backtrace were written to {}",
+                    path.display()
+                );
+            }
+        }
+        std::process::exit(101);
+    }));
 }
 
 async fn run_app(
@@ -55,119 +142,73 @@ async fn run_app(
     config: Config,
 ) -> anyhow::Result<()> {
     let mut app = App::new(config.clone());
-    let tick_rate = config.tui.refresh_interval;
-    let mut events = EventHandler::new(tick_rate);
-    let mut reconnect_attempt = 0u32;
-    let mut last_reconnect = std::time::Instant::now();
-
-    let client_result = Client::connect_with_timeouts(
-        &config.connection.host,
-        config.connection.port,
-        config.connection.connect_timeout,
-        config.connection.request_timeout,
-    )
-    .await;
-
-    let mut client = match client_result {
-        Ok(c) => {
-            app.set_connected();
-            Some(c)
-        }
-        Err(e) => {
-            app.set_disconnected(e.display_message());
-            None
-        }
-    };
-
-    if let Some(ref mut c) = client {
-        match c.list_positions().await {
-            Ok(positions) => app.update_positions(positions),
-            Err(e) => app.set_error(e.display_message()),
-        }
+    let bus = EventBus::default();
+    let mut updates = bus.subscribe(EventFilter::all());
+    // Simulated and replayed runs must not end up in the history or trigger
+    // webhooks.
+    if !config.is_offline() {
+        if config.history.enabled {
+            history::recorder::spawn(&bus, &config.history);
+        }
+        spawn_storage_recorder(&bus, &config);
+        notify::notifier::spawn(&bus, &config);
+        notify::desktop::spawn(&bus, &config);
+        notify::email::spawn(&bus, &config);
+        sink::pusher::spawn(&bus, &config);
     }
+    record_session(&config, &bus)?;
+    let mut events = EventHandler::new(config.tui.refresh_interval);
+    let source = source::spawn(&config, &bus)?;
+    let mut hits = HitMap::default();
+    let mut clicks = ClickTracker::default();
 
     loop {
-        terminal.draw(|frame| ui::render(frame, &app))?;
-
-        if let Some(event) = events.next().await {
-            match event {
-                Event::Key(key) => {
-                    use crossterm::event::KeyCode;
-
-                    if matches!(app.overlay, Overlay::RangeInput { .. }) {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.overlay = Overlay::None;
-                            }
-                            KeyCode::Enter => {
-                                if app.apply_range_input() {
-                                    if let Some(ref mut c) = client {
-                                        trigger_histogram_refresh(&mut app, c).await;
-                                    }
-                                }
-                            }
-                            other => {
-                                app.handle_range_input_key(other);
-                            }
-                        }
-                    } else if matches!(app.overlay, Overlay::ThemeSelector { .. }) {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.overlay = Overlay::None;
-                            }
-                            KeyCode::Enter => {
-                                app.apply_selected_theme();
-                            }
-                            KeyCode::Up | KeyCode::Char('k') => {
-                                app.theme_selector_up();
-                            }
-                            KeyCode::Down | KeyCode::Char('j') => {
-                                app.theme_selector_down();
-                            }
-                            _ => {}
-                        }
-                    } else if let Some((action, position_name)) = app.pending_confirmation() {
-                        match key.code {
-                            KeyCode::Esc => {
-                                app.clear_confirmation();
-                            }
-                            KeyCode::Enter => {
-                                app.clear_confirmation();
-                                execute_run_control(&mut app, &mut client, action, &position_name)
-                                    .await;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        let action = Action::from(key);
-                        handle_action(&mut app, action, &mut client, &config).await;
-
-                        if matches!(action, Action::Refresh) && client.is_none() {
-                            reconnect_attempt = 0;
-                        }
-                    }
+        if app.overlay == Overlay::Debug {
+            app.perf.bus_backlog = updates.pending();
+            app.perf.bus_dropped = updates.dropped();
+            app.perf.rpc = crate::client::metrics::snapshot();
+        }
+        apply_updates(&mut app, &mut updates);
+        app.expire_toasts();
+        app.expire_stat_changes();
+        source.set_context(PollContext::from_app(&app));
+        // Ticks with no new data or input leave the screen as it was, and
+        // nothing is drawn while paused.
+        if app.dirty && !app.is_paused() {
+            let start = std::time::Instant::now();
+            terminal.draw(|frame| hits = ui::render(frame, &app))?;
+            app.perf.record_frame(start.elapsed());
+            app.dirty = false;
+        }
+
+        tokio::select! {
+            event = events.next() => match event {
+                Some(Event::Key(key)) => {
+                    app.dirty = true;
+                    handle_key(&mut app, key, source.as_ref(), &config, &bus).await
                 }
-                Event::Tick => {
-                    if let Some(ref mut c) = client {
-                        refresh_data(&mut app, c).await;
-
-                        if !app.is_connected() {
-                            client = None;
-                            reconnect_attempt = 0;
-                        }
-                    } else {
-                        try_reconnect(
-                            &mut app,
-                            &mut client,
-                            &config,
-                            &mut reconnect_attempt,
-                            &mut last_reconnect,
-                        )
-                        .await;
+                Some(Event::Mouse(mouse)) => {
+                    app.dirty = true;
+                    let target = hits.target_at(mouse.column, mouse.row);
+                    if let Some(action) = mouse_action(&mut app, mouse.kind, target, &mut clicks) {
+                        handle_action(&mut app, action, source.as_ref(), &config, &bus).await;
                     }
                 }
-                Event::Resize(_, _) => {}
-            }
+                Some(Event::Resize(_, _)) => app.dirty = true,
+                Some(Event::FocusGained) => app.set_focused(true),
+                Some(Event::FocusLost) => app.set_focused(false),
+                Some(Event::Paste(text)) => {
+                    app.dirty = true;
+                    app.paste(&text);
+                }
+                // The debug overlay and the reconnection countdown refresh
+                // on every tick.
+                Some(Event::Tick) => {
+                    app.dirty |= app.overlay == Overlay::Debug || app.awaiting_reconnect()
+                }
+                None => break,
+            },
+            Some(update) = updates.recv() => app.apply(&update),
         }
 
         if app.should_quit {
@@ -178,57 +219,307 @@ async fn run_app(
     Ok(())
 }
 
-async fn try_reconnect(
-    app: &mut App,
-    client: &mut Option<Client>,
-    config: &Config,
-    attempt: &mut u32,
-    last_attempt: &mut std::time::Instant,
-) {
-    let backoff = std::time::Duration::from_millis(
-        (config.reconnect.initial_delay.as_millis() as f64
-            * config.reconnect.multiplier.powi(*attempt as i32)) as u64,
-    )
-    .min(config.reconnect.max_delay);
+/// Runs the TUI against an arbitrary backend without reading input.
+///
+/// Polls in the background for `ticks` refresh intervals, drawing after each,
+/// and returns the final application state. The history recorder is flushed
+/// before returning, so anything it wrote is on disk. Used by the integration
+/// tests with ratatui's `TestBackend`.
+pub async fn run_headless<B: Backend>(
+    terminal: &mut Terminal<B>,
+    config: Config,
+    ticks: usize,
+) -> anyhow::Result<App> {
+    Theme::set_user_themes(config.tui.themes.clone());
+    let mut app = App::new(config.clone());
+    let bus = EventBus::default();
+    let mut updates = bus.subscribe(EventFilter::all());
+    let recorder = (config.history.enabled && !config.is_offline())
+        .then(|| history::recorder::spawn(&bus, &config.history));
+    let storage_recorder = (!config.is_offline())
+        .then(|| spawn_storage_recorder(&bus, &config))
+        .flatten();
+    let session_recorder = record_session(&config, &bus)?;
+
+    let source = source::spawn(&config, &bus)?;
+    terminal.draw(|frame| {
+        ui::render(frame, &app);
+    })?;
+
+    for _ in 0..ticks {
+        tokio::time::sleep(config.tui.refresh_interval).await;
+        apply_updates(&mut app, &mut updates);
+        app.expire_toasts();
+        app.expire_stat_changes();
+        source.set_context(PollContext::from_app(&app));
+        terminal.draw(|frame| {
+            ui::render(frame, &app);
+        })?;
+    }
 
-    if last_attempt.elapsed() < backoff {
-        return;
+    // Stopping the source and dropping the bus lets the recorder drain its
+    // queue and exit.
+    drop(source);
+    drop(bus);
+    if let Some(recorder) = recorder {
+        recorder.await?;
+    }
+    if let Some(recorder) = storage_recorder {
+        recorder.await?;
+    }
+    if let Some(recorder) = session_recorder {
+        recorder.await?;
     }
 
-    *attempt += 1;
-    *last_attempt = std::time::Instant::now();
-    app.set_reconnecting(*attempt);
+    Ok(app)
+}
 
-    match Client::connect_with_timeouts(
-        &config.connection.host,
-        config.connection.port,
-        config.connection.connect_timeout,
-        config.connection.request_timeout,
-    )
-    .await
+/// Starts the run metrics database recorder if `storage.enabled` is set.
+/// Only built with the `storage` feature; without it, enabling the database
+/// logs a warning and does nothing.
+fn spawn_storage_recorder(bus: &EventBus, config: &Config) -> Option<JoinHandle<()>> {
+    if !config.storage.enabled {
+        return None;
+    }
+    #[cfg(feature = "storage")]
     {
-        Ok(c) => {
-            *client = Some(c);
-            *attempt = 0;
-            app.set_connected();
-
-            if let Some(ref mut c) = client {
-                if let Ok(positions) = c.list_positions().await {
-                    app.update_positions(positions);
-                }
+        storage::recorder::spawn(bus, config)
+    }
+    #[cfg(not(feature = "storage"))]
+    {
+        let _ = bus;
+        tracing::warn!("The run metrics database needs a build with the storage feature");
+        None
+    }
+}
+
+/// Starts the session recorder if `--record` was given.
+fn record_session(config: &Config, bus: &EventBus) -> anyhow::Result<Option<JoinHandle<()>>> {
+    let Some(path) = &config.record else {
+        return Ok(None);
+    };
+    let host = format!("{}:{}", config.connection.host, config.connection.port);
+    Ok(Some(session::recorder::spawn(bus, path, &host)?))
+}
+
+/// Polls MinKNOW without a terminal until interrupted.
+///
+/// Feeds the history recorder and the webhook notifier exactly as the TUI
+/// does, and serves the HTTP JSON API when `daemon.listen` is set. Used by
+/// `termion daemon`. On shutdown, queued notifications get a short grace
+/// period to be delivered.
+pub async fn run_daemon(config: Config) -> anyhow::Result<()> {
+    let bus = EventBus::default();
+    let recorder = config
+        .history
+        .enabled
+        .then(|| history::recorder::spawn(&bus, &config.history));
+    let storage_recorder = spawn_storage_recorder(&bus, &config);
+    let notifier = notify::notifier::spawn(&bus, &config);
+    let desktop = notify::desktop::spawn(&bus, &config);
+    let email = notify::email::spawn(&bus, &config);
+    let pusher = sink::pusher::spawn(&bus, &config);
+    let server = match config.daemon.listen {
+        Some(addr) => Some(
+            api::spawn(&bus, &config, addr)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to listen on {}: {}", addr, e))?,
+        ),
+        None => None,
+    };
+    if recorder.is_none()
+        && storage_recorder.is_none()
+        && notifier.is_none()
+        && desktop.is_none()
+        && email.is_none()
+        && pusher.is_none()
+        && server.is_none()
+    {
+        anyhow::bail!(
+            "Nothing to do: set daemon.listen, metrics.url, a notifications webhook_url or smtp_host, or enable history, storage or desktop notifications in the config"
+        );
+    }
+
+    tracing::info!(
+        host = %config.connection.host,
+        port = config.connection.port,
+        "Daemon started"
+    );
+    let poller = Poller::spawn(&config, &bus);
+    shutdown_signal().await?;
+    tracing::info!("Daemon stopping");
+
+    if let Some(server) = server {
+        server.abort();
+    }
+    drop(poller);
+    drop(bus);
+    if let Some(recorder) = recorder {
+        recorder.await?;
+    }
+    if let Some(recorder) = storage_recorder {
+        recorder.await?;
+    }
+    for notifier in [notifier, email].into_iter().flatten() {
+        if tokio::time::timeout(DAEMON_SHUTDOWN_GRACE, notifier)
+            .await
+            .is_err()
+        {
+            tracing::warn!("Pending notifications dropped on shutdown");
+        }
+    }
+    if let Some(pusher) = pusher {
+        if tokio::time::timeout(DAEMON_SHUTDOWN_GRACE, pusher)
+            .await
+            .is_err()
+        {
+            tracing::warn!("Pending metrics dropped on shutdown");
+        }
+    }
+    Ok(())
+}
+
+/// How long `run_daemon` waits for queued notifications and metrics on
+/// shutdown.
+const DAEMON_SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Resolves on Ctrl-C, or SIGTERM on Unix.
+async fn shutdown_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut term = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = term.recv() => Ok(()),
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await
+}
+
+/// Applies all queued bus events to the application state.
+fn apply_updates(app: &mut App, updates: &mut Subscription) {
+    while let Some(event) = updates.try_recv() {
+        app.apply(&event);
+    }
+}
+
+async fn handle_key(
+    app: &mut App,
+    key: crossterm::event::KeyEvent,
+    source: &dyn DataSource,
+    config: &Config,
+    bus: &EventBus,
+) {
+    use crossterm::event::KeyCode;
+
+    if matches!(app.overlay, Overlay::RangeInput { .. }) {
+        match key.code {
+            KeyCode::Esc => {
+                app.overlay = Overlay::None;
+            }
+            KeyCode::Enter => {
+                app.apply_range_input();
+            }
+            other => {
+                app.handle_range_input_key(other);
             }
         }
-        Err(e) => {
-            app.set_disconnected(e.display_message());
+    } else if matches!(app.overlay, Overlay::ThemeSelector { .. }) {
+        match key.code {
+            KeyCode::Esc => {
+                app.overlay = Overlay::None;
+            }
+            KeyCode::Enter => {
+                app.apply_selected_theme();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                app.theme_selector_up();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                app.theme_selector_down();
+            }
+            _ => {}
+        }
+    } else if matches!(app.overlay, Overlay::ErrorLog { .. }) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('E') => app.toggle_error_log(),
+            KeyCode::Up | KeyCode::Char('k') => app.scroll_error_log(-1),
+            KeyCode::Down | KeyCode::Char('j') => app.scroll_error_log(1),
+            KeyCode::PageUp => app.scroll_error_log(-10),
+            KeyCode::PageDown => app.scroll_error_log(10),
+            _ => {}
+        }
+    } else if matches!(app.overlay, Overlay::Note { .. }) {
+        // Demo and replayed positions share names with real ones.
+        if app.handle_note_key(key.code) && !app.config.is_offline() {
+            if let Err(e) = app.config.save_notes(&app.notes) {
+                tracing::warn!(error = %e, "Failed to save position notes");
+            }
         }
+    } else if app.overlay == Overlay::Filter {
+        app.handle_filter_key(key.code);
+    } else if matches!(app.overlay, Overlay::StartProtocol(_)) {
+        if let Some(request) = app.handle_start_wizard_key(key.code) {
+            if let Some(position) = request_position(app, &request.position_name, bus) {
+                source.start_protocol(position, request);
+            }
+        }
+    } else if matches!(app.overlay, Overlay::RunUntil(_)) {
+        if let Some(request) = app.handle_run_until_key(key.code) {
+            if let Some(position) = request_position(app, &request.position_name, bus) {
+                source.set_run_until(position, request.targets);
+            }
+        }
+    } else if matches!(app.overlay, Overlay::Confirmation { .. }) {
+        if let Some((action, position_name)) = app.handle_confirmation_key(key.code) {
+            if let Some(position) = request_position(app, &position_name, bus) {
+                source.run_control(position, action);
+            }
+        }
+    } else {
+        handle_action(app, Action::from(key), source, config, bus).await;
+    }
+}
+
+/// Translates a mouse event into an action. Clicking a row selects it and
+/// double-clicking opens it, and clicking a channel map cell inspects that
+/// channel; the wheel moves the selection or scrolls the channel map.
+/// Overlays are keyboard-only.
+fn mouse_action(
+    app: &mut App,
+    kind: MouseEventKind,
+    target: Option<MouseTarget>,
+    clicks: &mut ClickTracker,
+) -> Option<Action> {
+    if app.overlay != Overlay::None {
+        return None;
+    }
+    match kind {
+        MouseEventKind::ScrollUp => Some(Action::Up),
+        MouseEventKind::ScrollDown => Some(Action::Down),
+        MouseEventKind::Down(MouseButton::Left) => match target? {
+            MouseTarget::Row(idx) => {
+                app.select_row(idx);
+                clicks.click(MouseTarget::Row(idx)).then_some(Action::Enter)
+            }
+            MouseTarget::Action(action) => Some(action),
+            MouseTarget::Channel(channel) => {
+                app.inspect_channel(channel);
+                None
+            }
+        },
+        _ => None,
     }
 }
 
 async fn handle_action(
     app: &mut App,
     action: Action,
-    client: &mut Option<Client>,
+    source: &dyn DataSource,
     config: &Config,
+    bus: &EventBus,
 ) {
     if action != Action::None {
         tracing::debug!(?action, "Handling action");
@@ -236,53 +527,34 @@ async fn handle_action(
     match action {
         Action::Quit => app.quit(),
         Action::Up => {
-            if app.should_scroll_channel_map() {
+            if app.channel_cursor.is_some() {
+                app.move_channel_cursor(0, -1);
+            } else if app.should_scroll_channel_map() {
                 app.channel_map_scroll_offset = app.channel_map_scroll_offset.saturating_sub(1);
             } else {
                 app.select_previous();
             }
         }
         Action::Down => {
-            if app.should_scroll_channel_map() {
+            if app.channel_cursor.is_some() {
+                app.move_channel_cursor(0, 1);
+            } else if app.should_scroll_channel_map() {
                 app.channel_map_scroll_offset = app.channel_map_scroll_offset.saturating_add(1);
                 app.clamp_channel_map_scroll(53, 20);
             } else {
                 app.select_next();
             }
         }
+        Action::Left if app.chart_cursor.is_some() => app.move_chart_cursor(-1),
+        Action::Right if app.chart_cursor.is_some() => app.move_chart_cursor(1),
+        Action::Left if app.detail_chart == DetailChart::Signal => app.step_signal_channel(-1),
+        Action::Right if app.detail_chart == DetailChart::Signal => app.step_signal_channel(1),
+        Action::Left => app.move_channel_cursor(-1, 0),
+        Action::Right => app.move_channel_cursor(1, 0),
         Action::Enter => app.enter_detail(),
         Action::Back => app.back(),
         Action::Help => app.toggle_help(),
-        Action::Refresh => {
-            if let Some(ref mut c) = client {
-                match c.list_positions().await {
-                    Ok(positions) => app.update_positions(positions),
-                    Err(e) => app.set_error(e.display_message()),
-                }
-            } else {
-                match Client::connect_with_timeouts(
-                    &config.connection.host,
-                    config.connection.port,
-                    config.connection.connect_timeout,
-                    config.connection.request_timeout,
-                )
-                .await
-                {
-                    Ok(c) => {
-                        *client = Some(c);
-                        app.set_connected();
-                        if let Some(ref mut c) = client {
-                            if let Ok(positions) = c.list_positions().await {
-                                app.update_positions(positions);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        app.set_disconnected(e.display_message());
-                    }
-                }
-            }
-        }
+        Action::Refresh => source.refresh(),
         Action::Pause => {
             app.request_run_control(RunControlAction::Pause);
         }
@@ -290,409 +562,110 @@ async fn handle_action(
             app.request_run_control(RunControlAction::Resume);
         }
         Action::Stop => {
-            app.request_run_control(RunControlAction::Stop);
+            app.request_run_control(RunControlAction::Stop(StopOptions::default()));
         }
         Action::ToggleYieldUnit => app.toggle_yield_unit(),
-        Action::ToggleOutliers => {
-            app.toggle_outliers();
-            if let Some(ref mut c) = client {
-                trigger_histogram_refresh(app, c).await;
-            }
-        }
+        Action::CycleYieldWindow => app.cycle_yield_window(),
+        Action::ToggleDualAxis => app.toggle_yield_dual_axis(),
+        Action::ToggleYieldRates => app.toggle_yield_rates(),
+        Action::ToggleLegend => app.toggle_legend(),
+        Action::ToggleTotalSeries => app.toggle_yield_series(YieldSeries::Total),
+        Action::TogglePassedSeries => app.toggle_yield_series(YieldSeries::Passed),
+        Action::ToggleFailedSeries => app.toggle_yield_series(YieldSeries::Failed),
+        Action::ToggleOutliers => app.toggle_outliers(),
+        Action::ToggleHistogramWeight => app.toggle_histogram_weight(),
+        Action::ToggleProjection => app.toggle_projection(),
         Action::ChartYield => app.set_detail_chart(DetailChart::Yield),
         Action::ChartReadLength => app.set_detail_chart(DetailChart::ReadLength),
         Action::ChartPoreActivity => app.set_detail_chart(DetailChart::PoreActivity),
+        Action::ChartBarcodes => app.set_detail_chart(DetailChart::Barcodes),
+        Action::ChartSignal => app.set_detail_chart(DetailChart::Signal),
+        Action::ChartQScore => app.set_detail_chart(DetailChart::QScore),
+        Action::ChartPoreScans => app.set_detail_chart(DetailChart::PoreScans),
+        Action::ChartActivePores => app.set_detail_chart(DetailChart::ActivePores),
+        Action::ChartDutyTime => app.set_detail_chart(DetailChart::DutyTime),
         Action::CycleChart => app.cycle_detail_chart(),
+        Action::PreviousPosition => app.step_detail_position(false),
+        Action::NextPosition => app.step_detail_position(true),
+        Action::Inspect if matches!(app.detail_chart, DetailChart::Yield | DetailChart::QScore) => {
+            app.toggle_chart_cursor()
+        }
+        Action::Inspect => app.toggle_channel_inspector(),
+        Action::TimelapseBack => app.step_timelapse(false),
+        Action::TimelapseForward => app.step_timelapse(true),
+        Action::ToggleOccupancyColors => app.toggle_occupancy_colors(),
         Action::HistogramSetRange => app.open_range_input(),
-        Action::HistogramResetRange => {
-            app.clear_histogram_range();
-            if let Some(ref mut c) = client {
-                trigger_histogram_refresh(app, c).await;
+        Action::HistogramResetRange => app.clear_histogram_range(),
+        Action::CycleBarcodeSort => app.cycle_barcode_sort(),
+        Action::CycleOverviewSort => app.cycle_overview_sort(),
+        Action::ReverseOverviewSort => app.reverse_overview_sort(),
+        Action::TogglePin => {
+            // Demo and replayed positions share names with real ones.
+            if app.toggle_pin() && !app.config.is_offline() {
+                if let Err(e) = app.config.save_pinned(&app.pinned) {
+                    tracing::warn!(error = %e, "Failed to save pinned positions");
+                }
             }
         }
+        Action::ToggleHideSimulated => app.toggle_hide_simulated(),
+        Action::ToggleHideIdle => app.toggle_hide_idle(),
+        Action::ToggleClockTime => app.toggle_clock_time(),
+        Action::ExportChart => export::export_chart(app),
+        Action::ToggleFreeze => app.toggle_freeze(),
+        Action::CopyRunId => clipboard::yank(app, false),
+        Action::CopySummary => clipboard::yank(app, true),
+        Action::Filter => app.open_filter(),
         Action::ThemeSelector => app.open_theme_selector(),
-        Action::None => {}
-    }
-}
-
-async fn refresh_data(app: &mut App, client: &mut Client) {
-    if !app.is_connected() {
-        return;
-    }
-
-    match client.list_positions().await {
-        Ok(positions) => {
-            let in_detail_view = matches!(app.screen, Screen::PositionDetail { .. });
-            let detail_position_idx = match app.screen {
-                Screen::PositionDetail { position_idx } => Some(position_idx),
-                _ => None,
-            };
-
-            for (idx, pos) in positions.iter().enumerate() {
-                if let Ok(mut pos_client) = client.connect_position(pos.clone()).await {
-                    let fc_info = pos_client.get_flow_cell_info().await.ok();
-                    let has_flow_cell = fc_info.as_ref().map(|f| f.has_flow_cell).unwrap_or(false);
-
-                    if let Some(info) = fc_info {
-                        app.update_flow_cell_info(&pos.name, info);
-                    }
-
-                    if !has_flow_cell {
-                        app.update_run_state(&pos.name, RunState::Idle);
-                        continue;
-                    }
-
-                    let run_state = pos_client.get_run_state().await.ok();
-                    if let Some(ref state) = run_state {
-                        app.update_run_state(&pos.name, state.clone());
-                    }
-
-                    let is_active = run_state.as_ref().map(|s| s.is_active()).unwrap_or(false);
-
-                    if is_active {
-                        if let Ok(stats) = pos_client.get_stats().await {
-                            app.update_stats(&pos.name, stats);
-                        }
-
-                        // Throttled throughput calculation for overview table
-                        if app.should_calc_throughput(&pos.name) {
-                            if let Ok(Some(run_id)) = pos_client.get_current_run_id().await {
-                                match pos_client.get_yield_history(&run_id).await {
-                                    Ok(points) if points.len() >= 2 => {
-                                        if let Some(stats) = app.stats_cache.get_mut(&pos.name) {
-                                            let recent = &points[points.len() - 1];
-                                            let prev = &points[points.len() - 2];
-                                            let time_delta =
-                                                (recent.seconds - prev.seconds).max(1) as f64;
-                                            let bases_delta =
-                                                recent.bases.saturating_sub(prev.bases) as f64;
-                                            stats.throughput_bps = bases_delta / time_delta;
-                                            stats.throughput_gbph =
-                                                stats.throughput_bps * 3600.0 / 1_000_000_000.0;
-                                            tracing::debug!(position = %pos.name, throughput_gbph = stats.throughput_gbph, "Calculated throughput for overview");
-                                        }
-                                    }
-                                    Ok(_) => {
-                                        tracing::debug!(position = %pos.name, "Insufficient yield points for throughput");
-                                    }
-                                    Err(e) => {
-                                        tracing::debug!(position = %pos.name, error = %e.display_message(), "Yield history failed for throughput");
-                                    }
-                                }
-                            }
-                            app.mark_throughput_calculated(&pos.name);
-                        }
-
-                        if let Ok(Some(info)) = pos_client.get_run_info().await {
-                            app.update_run_info(&pos.name, info);
-                        }
-
-                        if in_detail_view && detail_position_idx == Some(idx) {
-                            fetch_detail_data(app, &mut pos_client).await;
-                        }
-                    }
+        Action::RunHistory => app.open_run_history(),
+        Action::StartProtocol => {
+            if let Some(position_name) = app.open_start_protocol() {
+                if let Some(position) = request_position(app, &position_name, bus) {
+                    source.load_protocols(position);
                 }
             }
-            app.update_positions(positions);
         }
-        Err(e) => {
-            app.set_disconnected(e.display_message());
+        Action::RunUntil => app.open_run_until(),
+        Action::DebugOverlay => app.toggle_debug_overlay(),
+        Action::ErrorLog => app.toggle_error_log(),
+        Action::EditNote => app.open_note(),
+        Action::Diagnostics => {
+            let results = crate::client::diagnostics::run_checks(
+                &config.connection.host,
+                config.connection.port,
+                &config.connection.connect_options(),
+            )
+            .await;
+            app.show_diagnostics(results);
         }
+        Action::None => {}
     }
 }
 
-async fn fetch_detail_data(app: &mut App, pos_client: &mut crate::client::PositionClient) {
-    let position_name = pos_client.position.name.clone();
-    tracing::info!(position = %position_name, "Fetching detail data");
-
-    // Check if run is active - streaming endpoints hang for completed runs
-    let run_state = app.run_states.get(&position_name).cloned();
-    let run_is_active = run_state.as_ref().map(|s| s.is_active()).unwrap_or(false);
-
-    let run_id = match pos_client.get_current_run_id().await {
-        Ok(Some(id)) => {
-            tracing::debug!(position = %position_name, run_id = %id, active = run_is_active, "Found run");
-            id
-        }
-        Ok(None) => {
-            tracing::debug!(position = %position_name, "No run, skipping detail data");
-            return;
-        }
-        Err(e) => {
-            tracing::debug!(position = %position_name, error = %e.display_message(), "Failed to get run_id");
-            return;
-        }
-    };
-
-    match pos_client.get_yield_history(&run_id).await {
-        Ok(points) if !points.is_empty() => {
-            tracing::debug!(position = %position_name, points = points.len(), "Got yield history");
-
-            if let Some(stats) = app.stats_cache.get_mut(&position_name) {
-                if points.len() >= 2 {
-                    let recent = &points[points.len() - 1];
-                    let prev = &points[points.len() - 2];
-                    let time_delta = (recent.seconds - prev.seconds).max(1) as f64;
-                    let bases_delta = recent.bases.saturating_sub(prev.bases) as f64;
-                    stats.throughput_bps = bases_delta / time_delta;
-                    stats.throughput_gbph = stats.throughput_bps * 3600.0 / 1_000_000_000.0;
-                }
-            }
-
-            app.update_yield_history(&position_name, points);
-        }
-        Ok(_) => {
-            tracing::debug!(position = %position_name, "No yield data available");
-        }
-        Err(e) => {
-            tracing::debug!(position = %position_name, error = %e.display_message(), "Yield history failed");
-        }
-    }
-
-    use futures::StreamExt;
-    use std::time::Duration;
-
-    tracing::info!(
-        position = %position_name,
-        exclude_outliers = app.exclude_outliers,
-        range = ?app.histogram_range,
-        "Fetching histogram"
-    );
-
-    match pos_client
-        .stream_read_length_histogram(&run_id, app.exclude_outliers, app.histogram_range)
-        .await
-    {
-        Ok(mut stream) => match tokio::time::timeout(Duration::from_secs(5), stream.next()).await {
-            Ok(Some(Ok(histogram))) => {
-                tracing::info!(
-                    position = %position_name,
-                    buckets = histogram.bucket_values.len(),
-                    requested_range = ?histogram.requested_range,
-                    source_data_end = histogram.source_data_end,
-                    "Got histogram"
-                );
-                app.update_histogram(&position_name, histogram);
-            }
-            Ok(Some(Err(e))) => {
-                tracing::debug!(position = %position_name, error = %e.display_message(), "Histogram stream error");
-            }
-            Ok(None) => {
-                tracing::debug!(position = %position_name, "Histogram stream ended");
-            }
-            Err(_) => {
-                tracing::debug!(position = %position_name, "Histogram fetch timed out");
-            }
-        },
-        Err(e) => {
-            tracing::debug!(position = %position_name, error = %e.display_message(), "Histogram stream failed");
-        }
-    }
-
-    let channel_count = if let Some(layout) = app.channel_layouts.get(&position_name) {
-        layout.channel_count as u32
-    } else {
-        match pos_client.get_channel_layout().await {
-            Ok(layout) => {
-                tracing::info!(
-                    position = %position_name,
-                    width = layout.width,
-                    height = layout.height,
-                    channels = layout.channel_count,
-                    "Got channel layout"
-                );
-                let count = layout.channel_count as u32;
-                app.update_channel_layout(&position_name, layout);
-                count
-            }
-            Err(e) => {
-                tracing::debug!(position = %position_name, error = %e.display_message(), "Channel layout failed");
-                512 // Default fallback for MinION
-            }
-        }
-    };
-
-    if run_is_active {
-        match tokio::time::timeout(Duration::from_secs(5), async {
-            let mut stream = pos_client.stream_duty_time(&run_id).await?;
-            stream.next().await.transpose()
-        })
-        .await
-        {
-            Ok(Ok(Some(duty_time))) => {
-                let counts = duty_time.pore_counts();
-                tracing::debug!(
-                    position = %position_name,
-                    total_pores = duty_time.pore_occupancy.len(),
-                    sequencing = counts.sequencing,
-                    pore_available = counts.pore_available,
-                    inactive = counts.inactive,
-                    unavailable = counts.unavailable,
-                    avg_occupancy = %format!("{:.2}", duty_time.average_occupancy()),
-                    "Got duty time"
-                );
-
-                if !duty_time.pore_occupancy.is_empty() {
-                    let sample: Vec<f32> =
-                        duty_time.pore_occupancy.iter().take(10).copied().collect();
-                    tracing::debug!(sample = ?sample, "First 10 occupancy values");
-                }
-
-                if let Some(stats) = app.stats_cache.get_mut(&position_name) {
-                    stats.active_pores = duty_time.active_pores(0.1) as u32;
-                }
-
-                app.update_duty_time(&position_name, duty_time);
-            }
-            Ok(Ok(None)) => {
-                tracing::debug!(position = %position_name, "No duty time data available");
-            }
-            Ok(Err(e)) => {
-                tracing::debug!(position = %position_name, error = %e.display_message(), "Duty time stream failed");
-            }
-            Err(_) => {
-                tracing::debug!(position = %position_name, "Duty time fetch timed out");
-            }
-        }
-        match tokio::time::timeout(Duration::from_secs(5), pos_client.get_mean_quality(&run_id))
-            .await
-        {
-            Ok(Ok(Some(quality))) => {
-                tracing::debug!(position = %position_name, quality = quality, "Got mean quality");
-                if let Some(stats) = app.stats_cache.get_mut(&position_name) {
-                    stats.mean_quality = quality as f64;
-                }
-            }
-            Ok(Ok(None)) => {
-                tracing::debug!(position = %position_name, "No quality data available");
-            }
-            Ok(Err(e)) => {
-                tracing::debug!(position = %position_name, error = %e.display_message(), "Quality boxplot failed");
-            }
-            Err(_) => {
-                tracing::debug!(position = %position_name, "Quality fetch timed out");
-            }
-        }
-
-        match tokio::time::timeout(
-            Duration::from_secs(5),
-            pos_client.get_channel_states(channel_count),
-        )
-        .await
-        {
-            Ok(Ok(channel_states)) => {
-                if let Some(stats) = app.stats_cache.get_mut(&position_name) {
-                    stats.active_pores = channel_states.sequencing_count() as u32;
-                }
-                app.update_channel_states(&position_name, channel_states);
-            }
-            Ok(Err(e)) => {
-                tracing::debug!(position = %position_name, error = %e.display_message(), "Channel states failed");
-            }
-            Err(_) => {
-                tracing::debug!(position = %position_name, "Channel states fetch timed out");
-            }
-        }
-    } else {
-        tracing::debug!(position = %position_name, "Skipping streaming endpoints for inactive run");
+/// Returns bases per second between the two most recent yield points.
+fn throughput_from_yield(points: &[crate::client::YieldDataPoint]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
     }
+    let recent = &points[points.len() - 1];
+    let prev = &points[points.len() - 2];
+    let time_delta = (recent.seconds - prev.seconds).max(1) as f64;
+    let bases_delta = recent.bases.saturating_sub(prev.bases) as f64;
+    Some(bases_delta / time_delta)
 }
 
-async fn execute_run_control(
-    app: &mut App,
-    client: &mut Option<Client>,
-    action: RunControlAction,
+/// Looks up a position for a one-off request, reporting it as an alert if
+/// it is gone.
+fn request_position(
+    app: &App,
     position_name: &str,
-) {
-    let Some(ref mut c) = client else {
-        app.set_error("Not connected to MinKNOW".to_string());
-        return;
-    };
-
-    let position = match app.positions.iter().find(|p| p.name == position_name) {
-        Some(p) => p.clone(),
-        None => {
-            app.set_error(format!("Position {} not found", position_name));
-            return;
-        }
-    };
-
-    let mut pos_client = match c.connect_position(position).await {
-        Ok(pc) => pc,
-        Err(e) => {
-            app.set_error(format!(
-                "Failed to connect to position: {}",
-                e.display_message()
-            ));
-            return;
-        }
-    };
-
-    let result = match action {
-        RunControlAction::Pause => pos_client.pause().await,
-        RunControlAction::Resume => pos_client.resume().await,
-        RunControlAction::Stop => pos_client.stop_protocol().await,
-    };
-
-    if let Err(e) = result {
-        app.set_error(format!(
-            "Failed to {} run: {}",
-            action.label().to_lowercase(),
-            e.display_message()
-        ));
-    } else {
-        tracing::info!(
-            action = action.label(),
-            position = position_name,
-            "Run control action executed"
-        );
-    }
-}
-
-async fn trigger_histogram_refresh(app: &mut App, client: &mut Client) {
-    let position = match app.selected_position() {
-        Some(p) => p.clone(),
-        None => return,
-    };
-
-    let mut pos_client = match client.connect_position(position.clone()).await {
-        Ok(c) => c,
-        Err(e) => {
-            tracing::warn!(error = %e.display_message(), "Failed to connect for histogram refresh");
-            return;
-        }
-    };
-
-    let run_id = match pos_client.get_current_run_id().await {
-        Ok(Some(id)) => id,
-        Ok(None) => return,
-        Err(_) => return,
-    };
-
-    tracing::info!(
-        position = %position.name,
-        exclude_outliers = app.exclude_outliers,
-        range = ?app.histogram_range,
-        "Immediate histogram refresh"
-    );
-
-    match pos_client
-        .stream_read_length_histogram(&run_id, app.exclude_outliers, app.histogram_range)
-        .await
-    {
-        Ok(mut stream) => {
-            use futures::StreamExt;
-            if let Some(Ok(histogram)) = stream.next().await {
-                tracing::info!(
-                    position = %position.name,
-                    buckets = histogram.bucket_values.len(),
-                    "Got histogram (immediate)"
-                );
-                app.update_histogram(&position.name, histogram);
-            }
-        }
-        Err(e) => {
-            tracing::debug!(error = %e.display_message(), "Histogram refresh failed");
-        }
+    bus: &EventBus,
+) -> Option<crate::client::Position> {
+    let position = app.positions.iter().find(|p| p.name == position_name);
+    if position.is_none() {
+        bus.publish(BusEvent::alert(format!(
+            "Position {} not found",
+            position_name
+        )));
     }
+    position.cloned()
 }
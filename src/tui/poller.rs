@@ -0,0 +1,1479 @@
+//! Background data fetching.
+//!
+//! The render loop never awaits MinKNOW. A supervisor task owns the manager
+//! connection: it follows the manager's position watch, so newly plugged
+//! MinIONs appear at once (managers too old to offer it are listed every
+//! refresh interval instead), reconnects with backoff after a failure, and
+//! keeps one poller task per position. When `connection.hosts` lists several
+//! managers, each gets its own supervisor and its positions are named
+//! `<host>/<position>`. Each poller fetches its position's run state and
+//! statistics on the same interval, or on `tui.idle_refresh_interval` while the
+//! position has no active run and is not on screen; statistics, the mean
+//! q-score, and channel states have their own, longer intervals on top of that.
+//! It keeps the statistics streams open while a run is active, and fetches
+//! chart data while its position is shown in the detail view. An idle position
+//! in the detail view gets its last finished run instead, fetched once. Those
+//! fetches are cancelled as soon as the position leaves the detail view.
+//! Everything they learn is published on the event bus, so a slow position
+//! delays nothing but itself. At most `connection.max_concurrent_positions`
+//! pollers query each manager at a time.
+
+use super::source::DataSource;
+use super::streams::{self, PositionStreams};
+use super::{App, DetailChart, RunControlAction, Screen, StartRequest};
+use crate::bus::{BusEvent, EventBus};
+use crate::client::diagnostics::version_warning;
+use crate::client::subscription::{HistogramOptions, StreamUpdate};
+use crate::client::{
+    Client, ClientError, MinknowApi, Position, PositionApi, PositionChanges, PositionClient,
+    RunState, RunSummary, RunUntilCriteria,
+};
+use crate::config::{Config, HostEndpoint, TuiConfig};
+#[cfg(feature = "storage")]
+use crate::storage::{self, Storage};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, Notify, Semaphore};
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, MissedTickBehavior};
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// What the pollers need to know about the UI.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct PollContext {
+    /// Position shown in the detail view, which also gets chart data.
+    pub detail_position: Option<String>,
+    /// Position whose run history is open.
+    pub history_position: Option<String>,
+    pub histogram: HistogramOptions,
+    /// Channel (1-based) whose raw signal the detail view plots.
+    pub signal_channel: Option<u32>,
+}
+
+impl PollContext {
+    pub(super) fn from_app(app: &App) -> Self {
+        // While paused, positions are polled as if the overview were open.
+        if app.is_paused() {
+            return Self::default();
+        }
+        let detail_position = match app.screen {
+            Screen::PositionDetail { position_idx } => {
+                app.positions.get(position_idx).map(|p| p.name.clone())
+            }
+            _ => None,
+        };
+        let history_position = match app.screen {
+            Screen::RunHistory { position_idx } | Screen::RunHistoryDetail { position_idx, .. } => {
+                app.positions.get(position_idx).map(|p| p.name.clone())
+            }
+            _ => None,
+        };
+        let signal_channel = (detail_position.is_some() && app.detail_chart == DetailChart::Signal)
+            .then_some(app.signal_channel);
+        Self {
+            detail_position,
+            history_position,
+            signal_channel,
+            histogram: HistogramOptions {
+                exclude_outliers: app.exclude_outliers,
+                outlier_percent: app.outlier_percent,
+                weight_by_bases: app.histogram_by_bases,
+                range: app.histogram_range,
+                buckets: app.histogram_buckets,
+            },
+        }
+    }
+
+    /// The part of the context a poller for `position` reacts to.
+    fn view_for(&self, position: &str) -> (bool, bool, HistogramOptions, Option<u32>) {
+        (
+            self.detail_position.as_deref() == Some(position),
+            self.history_position.as_deref() == Some(position),
+            self.histogram,
+            self.signal_channel_for(position),
+        )
+    }
+
+    /// Whether `position` is open in the detail or run history view.
+    fn shows(&self, position: &str) -> bool {
+        self.detail_position.as_deref() == Some(position)
+            || self.history_position.as_deref() == Some(position)
+    }
+
+    /// The signal channel to stream for `position`, if it is in the detail
+    /// view.
+    pub(super) fn signal_channel_for(&self, position: &str) -> Option<u32> {
+        self.signal_channel
+            .filter(|_| self.detail_position.as_deref() == Some(position))
+    }
+}
+
+/// Handle to the background tasks. Dropping it stops them.
+pub(super) struct Poller {
+    hosts: Vec<HostTasks>,
+    bus: EventBus,
+    context: watch::Sender<PollContext>,
+}
+
+/// The supervisor of one MinKNOW host.
+struct HostTasks {
+    /// Label the host's positions are namespaced under, when several hosts
+    /// are watched.
+    label: Option<String>,
+    client: watch::Receiver<Option<Client>>,
+    refresh: Arc<Notify>,
+    _task: AbortOnDrop,
+}
+
+impl Poller {
+    /// Starts connecting to MinKNOW and polling in the background.
+    pub(super) fn spawn(config: &Config, bus: &EventBus) -> Self {
+        let (context_tx, context_rx) = watch::channel(PollContext::default());
+        let streams = streams::forward_to_bus(bus);
+        let multi_host = config.connection.is_multi_host();
+
+        let hosts = config
+            .connection
+            .endpoints()
+            .into_iter()
+            .map(|endpoint| {
+                let (client_tx, client_rx) = watch::channel(None);
+                let refresh = Arc::new(Notify::new());
+                let label = multi_host.then(|| endpoint.label.clone());
+                let supervisor = Supervisor {
+                    config: config.clone(),
+                    endpoint,
+                    label: label.clone(),
+                    bus: bus.clone(),
+                    context: context_rx.clone(),
+                    client: client_tx,
+                    refresh: refresh.clone(),
+                    streams: streams.clone(),
+                    permits: Arc::new(Semaphore::new(config.connection.max_concurrent_positions)),
+                    pollers: HashMap::new(),
+                    watch: true,
+                };
+                HostTasks {
+                    label,
+                    client: client_rx,
+                    refresh,
+                    _task: AbortOnDrop(tokio::spawn(supervisor.run())),
+                }
+            })
+            .collect();
+
+        Self {
+            hosts,
+            bus: bus.clone(),
+            context: context_tx,
+        }
+    }
+
+    /// The client of the host `position` belongs to, if it is connected.
+    fn client_for(&self, position: &Position) -> Option<Client> {
+        self.hosts
+            .iter()
+            .find(|host| host.label == position.host)
+            .and_then(|host| host.client.borrow().clone())
+    }
+}
+
+impl DataSource for Poller {
+    /// Pollers affected by the change poll immediately.
+    fn set_context(&self, context: PollContext) {
+        self.context.send_if_modified(|current| {
+            if *current == context {
+                return false;
+            }
+            *current = context;
+            true
+        });
+    }
+
+    /// Re-lists positions now, or retries the connection immediately if it
+    /// is down, on every host.
+    fn refresh(&self) {
+        for host in &self.hosts {
+            host.refresh.notify_one();
+        }
+    }
+
+    fn run_control(&self, position: Position, action: RunControlAction) {
+        let failure = match action {
+            RunControlAction::Pause => "Failed to pause run",
+            RunControlAction::Resume => "Failed to resume run",
+            RunControlAction::Stop(_) => "Failed to stop run",
+        };
+        let client = self.client_for(&position);
+        spawn_request(client, &self.bus, position, failure, move |pos_client| {
+            run_control(pos_client, action)
+        });
+    }
+
+    fn load_protocols(&self, position: Position) {
+        let client = self.client_for(&position);
+        let bus = self.bus.clone();
+        tokio::spawn(async move {
+            let result = match client {
+                Some(client) => match client.connect_position(position.clone()).await {
+                    Ok(mut pos_client) => pos_client.list_protocols().await,
+                    Err(e) => Err(e),
+                },
+                None => Err(ClientError::Disconnected),
+            };
+            // An empty list stops the wizard waiting; the reason is shown as
+            // a toast.
+            bus.publish(BusEvent::Protocols {
+                position: position.name,
+                protocols: result.as_ref().cloned().unwrap_or_default(),
+            });
+            match result {
+                Err(e) if e.is_permission_denied() => bus.publish(BusEvent::ReadOnly {
+                    reason: e.display_message(),
+                }),
+                Err(e) => bus.publish(BusEvent::alert(format!(
+                    "Failed to list protocols: {}",
+                    e.display_message()
+                ))),
+                Ok(_) => {}
+            }
+        });
+    }
+
+    fn start_protocol(&self, position: Position, request: StartRequest) {
+        let client = self.client_for(&position);
+        spawn_request(
+            client,
+            &self.bus,
+            position,
+            "Failed to start protocol",
+            |pos_client| start_protocol(pos_client, request),
+        );
+    }
+
+    fn set_run_until(&self, position: Position, targets: RunUntilCriteria) {
+        let client = self.client_for(&position);
+        spawn_request(
+            client,
+            &self.bus,
+            position,
+            "Failed to set run-until targets",
+            move |pos_client| set_run_until(pos_client, targets),
+        );
+    }
+}
+
+/// Connects to `position` in the background and runs `request` on it.
+/// Failures are published as alerts prefixed with `failure`, except
+/// permission errors, which put the TUI in read-only mode.
+fn spawn_request<C, T, F, Fut>(
+    client: Option<C>,
+    bus: &EventBus,
+    position: Position,
+    failure: &'static str,
+    request: F,
+) -> Option<JoinHandle<()>>
+where
+    C: MinknowApi,
+    T: Send + 'static,
+    F: FnOnce(C::Position) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<T, ClientError>> + Send,
+{
+    let Some(client) = client else {
+        bus.publish(BusEvent::alert("Not connected to MinKNOW"));
+        return None;
+    };
+    let bus = bus.clone();
+    Some(tokio::spawn(async move {
+        let result = match client.connect_position(position).await {
+            Ok(pos_client) => request(pos_client).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Err(e) if e.is_permission_denied() => bus.publish(BusEvent::ReadOnly {
+                reason: e.display_message(),
+            }),
+            Err(e) => bus.publish(BusEvent::alert(format!(
+                "{}: {}",
+                failure,
+                e.display_message()
+            ))),
+            Ok(_) => {}
+        }
+    }))
+}
+
+async fn run_control(
+    mut pos_client: impl PositionApi,
+    action: RunControlAction,
+) -> Result<(), ClientError> {
+    match action {
+        RunControlAction::Pause => pos_client.pause().await,
+        RunControlAction::Resume => pos_client.resume().await,
+        RunControlAction::Stop(options) => pos_client.stop_protocol(options).await,
+    }?;
+    tracing::info!(
+        action = action.label(),
+        position = %pos_client.position().name,
+        "Run control action executed"
+    );
+    Ok(())
+}
+
+async fn start_protocol(
+    mut pos_client: impl PositionApi,
+    request: StartRequest,
+) -> Result<(), ClientError> {
+    let run_id = pos_client
+        .start_protocol(
+            &request.identifier,
+            &request.sample_id,
+            &request.experiment_group,
+        )
+        .await?;
+    tracing::info!(
+        position = %request.position_name,
+        protocol = %request.identifier,
+        run_id = %run_id,
+        "Started protocol"
+    );
+    Ok(())
+}
+
+async fn set_run_until(
+    mut pos_client: impl PositionApi,
+    targets: RunUntilCriteria,
+) -> Result<(), ClientError> {
+    let run_id = pos_client
+        .get_current_run_id()
+        .await?
+        .ok_or_else(|| ClientError::NotFound {
+            resource: "Acquisition run".to_string(),
+            id: pos_client.position().name.clone(),
+        })?;
+    pos_client.set_run_until_targets(&run_id, &targets).await?;
+    tracing::info!(
+        position = %pos_client.position().name,
+        run_id = %run_id,
+        ?targets,
+        "Updated run-until targets"
+    );
+    Ok(())
+}
+
+/// Aborts a task when dropped.
+pub(super) struct AbortOnDrop(pub(super) JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+struct Supervisor {
+    config: Config,
+    endpoint: HostEndpoint,
+    /// Set when several hosts are watched: positions are namespaced under
+    /// it and connection changes are reported per host.
+    label: Option<String>,
+    bus: EventBus,
+    context: watch::Receiver<PollContext>,
+    client: watch::Sender<Option<Client>>,
+    refresh: Arc<Notify>,
+    streams: mpsc::UnboundedSender<StreamUpdate>,
+    /// Limits how many pollers talk to MinKNOW at once.
+    permits: Arc<Semaphore>,
+    pollers: HashMap<String, (Position, AbortOnDrop)>,
+    /// Whether to follow the manager's position watch rather than listing
+    /// positions every refresh interval. Cleared if the manager is too old.
+    watch: bool,
+}
+
+impl Supervisor {
+    async fn run(mut self) {
+        let interval = self.config.tui.refresh_interval;
+        let mut client = self.connect().await;
+        let mut attempt = 0u32;
+
+        loop {
+            match client.as_mut() {
+                Some(c) => {
+                    let connected = if self.watch {
+                        self.watch_positions(c).await
+                    } else {
+                        let listed = self.list_positions(c).await;
+                        if listed {
+                            self.measure_latency(c).await;
+                            self.wait(interval).await;
+                        }
+                        listed
+                    };
+                    if !connected {
+                        client = None;
+                        attempt = 0;
+                        self.pollers.clear();
+                        self.client.send_replace(None);
+                    }
+                }
+                None => {
+                    let delay = self.reconnect_delay(attempt);
+                    if self.label.is_none() {
+                        self.bus.publish(BusEvent::ReconnectScheduled {
+                            attempt: attempt + 1,
+                            delay,
+                        });
+                    }
+                    if self.wait(delay).await {
+                        attempt = 0;
+                    }
+                    attempt += 1;
+                    if self.label.is_none() {
+                        self.bus.publish(BusEvent::Reconnecting { attempt });
+                    }
+                    client = self.connect().await;
+                    if client.is_some() {
+                        attempt = 0;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn connect(&self) -> Option<Client> {
+        match Client::connect_with_options(
+            &self.endpoint.host,
+            self.endpoint.port,
+            self.config.connection.connect_options(),
+        )
+        .await
+        {
+            Ok(c) => {
+                self.bus.publish(match &self.label {
+                    Some(host) => BusEvent::HostConnected { host: host.clone() },
+                    None => BusEvent::Connected,
+                });
+                self.publish_server(c.clone()).await;
+                self.client.send_replace(Some(c.clone()));
+                Some(c)
+            }
+            Err(e) => {
+                self.publish_failure(&e);
+                None
+            }
+        }
+    }
+
+    /// Publishes what the manager is, warning if Termion may not support its
+    /// MinKNOW version.
+    async fn publish_server(&self, mut client: Client) {
+        let info = client.server_info().await;
+        if let Some(version) = &info.version {
+            tracing::info!(host = %self.endpoint.host, version = %version.label(), "MinKNOW version");
+            if let Some(warning) = version_warning(version) {
+                tracing::warn!(host = %self.endpoint.host, "{}", warning);
+            }
+        }
+        if self.label.is_none() {
+            self.bus.publish(BusEvent::ServerInfo { info });
+        }
+    }
+
+    fn publish_failure(&self, err: &ClientError) {
+        self.bus.publish(match &self.label {
+            Some(host) => BusEvent::HostDisconnected {
+                host: host.clone(),
+                reason: err.display_message(),
+            },
+            None => BusEvent::connection_failed(err),
+        });
+    }
+
+    /// Lists positions and starts or stops pollers to match. Returns `false`
+    /// if the connection failed.
+    async fn list_positions(&mut self, client: &mut Client) -> bool {
+        match client.list_positions().await {
+            Ok(positions) => {
+                self.replace_positions(client, positions);
+                true
+            }
+            Err(e) => {
+                self.publish_failure(&e);
+                false
+            }
+        }
+    }
+
+    /// Follows the manager's position watch, so positions appear and go as
+    /// soon as MinKNOW notices. Returns `false` if the connection failed,
+    /// and `true` on a refresh request or when the manager cannot watch
+    /// positions, after which they are listed every refresh interval.
+    async fn watch_positions(&mut self, client: &mut Client) -> bool {
+        let stream = match client.watch_positions().await {
+            Ok(stream) => stream,
+            Err(e) if e.is_unimplemented() => {
+                tracing::info!(
+                    host = %self.endpoint.label,
+                    "Manager cannot watch positions, listing them instead"
+                );
+                self.watch = false;
+                return true;
+            }
+            Err(e) => {
+                self.publish_failure(&e);
+                return false;
+            }
+        };
+        tokio::pin!(stream);
+        let mut pings = tokio::time::interval(self.config.tui.refresh_interval);
+        pings.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut snapshot = true;
+        loop {
+            let changes = tokio::select! {
+                changes = stream.next() => changes,
+                // Reopening the watch lists every position again.
+                _ = self.refresh.notified() => return true,
+                _ = pings.tick() => {
+                    self.measure_latency(client).await;
+                    continue;
+                }
+            };
+            match changes {
+                Some(Ok(changes)) if snapshot => {
+                    self.replace_positions(client, changes.updated);
+                    snapshot = false;
+                }
+                Some(Ok(changes)) => self.apply_changes(client, changes),
+                Some(Err(e)) => {
+                    self.publish_failure(&e);
+                    return false;
+                }
+                None => {
+                    self.wait(self.config.tui.refresh_interval).await;
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Publishes the full position list and starts or stops pollers to
+    /// match.
+    fn replace_positions(&mut self, client: &Client, positions: Vec<Position>) {
+        let positions = self.namespaced(positions);
+        self.pollers
+            .retain(|name, _| positions.iter().any(|p| &p.name == name));
+        for pos in &positions {
+            self.track(client, pos);
+        }
+        self.bus.publish(match &self.label {
+            Some(host) => BusEvent::HostPositions {
+                host: host.clone(),
+                positions,
+            },
+            None => BusEvent::PositionsUpdated(positions),
+        });
+    }
+
+    /// Publishes positions that appeared, changed or went away, and starts
+    /// or stops their pollers.
+    fn apply_changes(&mut self, client: &Client, changes: PositionChanges) {
+        let updated = self.namespaced(changes.updated);
+        let removed: Vec<String> = changes
+            .removed
+            .into_iter()
+            .map(|name| match &self.label {
+                Some(host) => format!("{}/{}", host, name),
+                None => name,
+            })
+            .collect();
+        for name in &removed {
+            self.pollers.remove(name);
+        }
+        for pos in &updated {
+            self.track(client, pos);
+        }
+        self.bus
+            .publish(BusEvent::PositionsChanged { updated, removed });
+    }
+
+    fn namespaced(&self, positions: Vec<Position>) -> Vec<Position> {
+        match &self.label {
+            Some(host) => positions.into_iter().map(|p| p.on_host(host)).collect(),
+            None => positions,
+        }
+    }
+
+    /// Starts a poller for `position` unless one already polls it on the
+    /// same port.
+    fn track(&mut self, client: &Client, position: &Position) {
+        let current = self.pollers.get(&position.name).map(|(p, _)| p.grpc_port);
+        if current != Some(position.grpc_port) {
+            let task = self.spawn_poller(client, position.clone());
+            self.pollers
+                .insert(position.name.clone(), (position.clone(), task));
+        }
+    }
+
+    fn spawn_poller(&self, client: &Client, position: Position) -> AbortOnDrop {
+        let poller = PositionPoller {
+            client: client.clone(),
+            position,
+            bus: self.bus.clone(),
+            context: self.context.clone(),
+            streams: PositionStreams::new(self.streams.clone(), self.config.tui.histogram_interval),
+            permits: self.permits.clone(),
+            interval: self.config.tui.refresh_interval,
+            idle_interval: self.config.tui.idle_refresh_interval,
+            run_active: false,
+            schedule: FetchSchedule::new(&self.config.tui),
+            channel_count: None,
+            history_fetched: false,
+            last_run_fetched: false,
+            detail_session: None,
+            #[cfg(feature = "storage")]
+            storage: self
+                .config
+                .storage
+                .enabled
+                .then(|| self.config.storage.path.clone()),
+        };
+        AbortOnDrop(tokio::spawn(poller.run()))
+    }
+
+    /// Publishes the round-trip time of a ping to the manager. A failed
+    /// ping is left to the position list or watch to report.
+    async fn measure_latency(&self, client: &mut Client) {
+        if self.label.is_some() {
+            return;
+        }
+        match client.ping().await {
+            Ok(rtt) => self.bus.publish(BusEvent::Latency { rtt }),
+            Err(e) => tracing::debug!(error = %e.display_message(), "Ping failed"),
+        }
+    }
+
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let reconnect = &self.config.reconnect;
+        Duration::from_millis(
+            (reconnect.initial_delay.as_millis() as f64 * reconnect.multiplier.powi(attempt as i32))
+                as u64,
+        )
+        .min(reconnect.max_delay)
+    }
+
+    /// Sleeps for `duration`. Returns `true` if cut short by a refresh
+    /// request.
+    async fn wait(&self, duration: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => false,
+            _ = self.refresh.notified() => true,
+        }
+    }
+}
+
+struct PositionPoller {
+    client: Client,
+    position: Position,
+    bus: EventBus,
+    context: watch::Receiver<PollContext>,
+    streams: PositionStreams,
+    permits: Arc<Semaphore>,
+    interval: Duration,
+    /// Poll interval while the position is idle and not on screen.
+    idle_interval: Duration,
+    /// Whether a run was active at the last poll.
+    run_active: bool,
+    schedule: FetchSchedule,
+    /// Channel count from the layout, fetched once.
+    channel_count: Option<u32>,
+    /// Whether the run history has been fetched since it was opened.
+    history_fetched: bool,
+    /// Whether the last finished run has been fetched since the idle
+    /// position was opened in the detail view.
+    last_run_fetched: bool,
+    /// Cancellation for detail data fetches while this position is shown in
+    /// the detail view.
+    detail_session: Option<DetailSession>,
+    /// Run metrics database whose finished runs are added to the run
+    /// history, if enabled.
+    #[cfg(feature = "storage")]
+    storage: Option<std::path::PathBuf>,
+}
+
+impl PositionPoller {
+    async fn run(mut self) {
+        let mut pos_client = None;
+
+        loop {
+            let Ok(permit) = self.permits.clone().acquire_owned().await else {
+                return;
+            };
+
+            if pos_client.is_none() {
+                match self.client.connect_position(self.position.clone()).await {
+                    Ok(c) => pos_client = Some(c),
+                    Err(e) => {
+                        tracing::debug!(position = %self.position.name, error = %e.display_message(), "Position connect failed");
+                    }
+                }
+            }
+
+            let context = self.context.borrow_and_update().clone();
+            if let Some(c) = pos_client.as_mut() {
+                self.poll(c, &context).await;
+            }
+            drop(permit);
+
+            if !self.wait(&context).await {
+                return;
+            }
+        }
+    }
+
+    /// Waits for the next poll: the interval elapsing, or a context change
+    /// that affects this position. Returns `false` once the UI is gone.
+    async fn wait(&mut self, context: &PollContext) -> bool {
+        let name = &self.position.name;
+        let view = context.view_for(name);
+        let interval = if self.run_active || context.shows(name) {
+            self.interval
+        } else {
+            self.idle_interval
+        };
+        let deadline = Instant::now() + interval;
+
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(deadline) => return true,
+                changed = self.context.changed() => {
+                    if changed.is_err() {
+                        return false;
+                    }
+                    if self.context.borrow().view_for(name) != view {
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn poll(&mut self, pos_client: &mut PositionClient, context: &PollContext) {
+        let name = self.position.name.clone();
+
+        if context.history_position.as_deref() == Some(name.as_str()) {
+            if !self.history_fetched {
+                self.fetch_run_history(pos_client).await;
+                self.history_fetched = true;
+            }
+        } else {
+            self.history_fetched = false;
+        }
+
+        let in_detail = context.detail_position.as_deref() == Some(name.as_str());
+        if !in_detail {
+            self.detail_session = None;
+            self.last_run_fetched = false;
+        }
+
+        // A run that just started gets its statistics straight away.
+        let fetch_stats = self.schedule.due(Fetch::Stats, Instant::now()) || !self.run_active;
+        let is_active = publish_status(&self.bus, pos_client, fetch_stats).await;
+        self.run_active = is_active;
+        if !is_active {
+            self.streams.close();
+            if in_detail && !self.last_run_fetched {
+                let session = self.detail_session();
+                self.last_run_fetched = self.fetch_last_run(pos_client, &session).await;
+            }
+            return;
+        }
+        // A run that ends while the position is on screen is fetched then.
+        self.last_run_fetched = false;
+
+        match pos_client.get_current_run_id().await {
+            Ok(Some(run_id)) => self.streams.ensure(
+                pos_client,
+                &run_id,
+                context.histogram,
+                context.signal_channel_for(&name),
+            ),
+            Ok(None) => self.streams.close(),
+            Err(e) => {
+                tracing::debug!(position = %name, error = %e.display_message(), "Failed to get run_id");
+            }
+        }
+
+        if in_detail {
+            let session = self.detail_session();
+            self.fetch_detail_data(pos_client, is_active, &session)
+                .await;
+        }
+    }
+
+    /// The cancellation token of the current detail session, starting a new
+    /// session if there is none or the last one was cancelled.
+    fn detail_session(&mut self) -> CancellationToken {
+        match &self.detail_session {
+            Some(session) if !session.token.is_cancelled() => session.token.clone(),
+            _ => {
+                // Opening the detail view shows fresh data at once.
+                self.schedule.reset(Fetch::MeanQuality);
+                self.schedule.reset(Fetch::ChannelStates);
+                let session = DetailSession::start(self.context.clone(), &self.position.name);
+                let token = session.token.clone();
+                self.detail_session = Some(session);
+                token
+            }
+        }
+    }
+
+    /// Fetches the finished runs for the run history screen.
+    async fn fetch_run_history(&mut self, pos_client: &mut impl PositionApi) {
+        let position = self.position.name.clone();
+        match pos_client.list_run_history().await {
+            Ok(runs) => self.bus.publish(BusEvent::RunHistory {
+                position,
+                runs: self.with_stored_runs(runs),
+            }),
+            Err(e) => {
+                tracing::warn!(position = %position, error = %e.display_message(), "Failed to list run history");
+                self.bus.publish(BusEvent::RunHistory {
+                    position: position.clone(),
+                    runs: self.with_stored_runs(Vec::new()),
+                });
+                self.bus.publish(BusEvent::Alert {
+                    position: Some(position),
+                    message: format!("Failed to load run history: {}", e.display_message()),
+                });
+            }
+        }
+    }
+
+    /// Fetches the last finished run for the detail view of an idle
+    /// position. Returns whether it was published; failures are retried at
+    /// the next poll.
+    async fn fetch_last_run(
+        &mut self,
+        pos_client: &mut PositionClient,
+        session: &CancellationToken,
+    ) -> bool {
+        let position = self.position.name.clone();
+        match session.run_until_cancelled(pos_client.get_last_run()).await {
+            None => false,
+            Some(Ok(run)) => {
+                self.bus.publish(BusEvent::LastRun {
+                    position,
+                    run: run.map(Box::new),
+                });
+                true
+            }
+            Some(Err(e)) => {
+                tracing::debug!(position = %position, error = %e.display_message(), "Failed to get last run");
+                false
+            }
+        }
+    }
+
+    /// Adds the finished runs of this position in the run metrics database
+    /// to `runs`.
+    #[cfg(feature = "storage")]
+    fn with_stored_runs(&self, runs: Vec<RunSummary>) -> Vec<RunSummary> {
+        let Some(path) = &self.storage else {
+            return runs;
+        };
+        match Storage::open(path).and_then(|s| s.finished_runs(&self.position.name)) {
+            Ok(stored) => storage::merge_runs(runs, stored),
+            Err(e) => {
+                tracing::warn!(position = %self.position.name, error = %e, "Failed to read stored runs");
+                runs
+            }
+        }
+    }
+
+    /// Returns `runs`: this build has no run metrics database.
+    #[cfg(not(feature = "storage"))]
+    fn with_stored_runs(&self, runs: Vec<RunSummary>) -> Vec<RunSummary> {
+        runs
+    }
+
+    /// Fetches chart data for the position shown in the detail view.
+    ///
+    /// Yield, duty time, and histogram data arrive through the position's
+    /// [`PositionStreams`]; this covers the remaining polled endpoints.
+    /// `run_is_active` gates the boxplot stream, which hangs for completed runs.
+    /// Returns early, publishing nothing more, once `session` is cancelled.
+    async fn fetch_detail_data(
+        &mut self,
+        pos_client: &mut PositionClient,
+        run_is_active: bool,
+        session: &CancellationToken,
+    ) {
+        let position_name = pos_client.position.name.clone();
+        tracing::info!(position = %position_name, "Fetching detail data");
+
+        let Some(run_id) = session
+            .run_until_cancelled(pos_client.get_current_run_id())
+            .await
+        else {
+            return;
+        };
+        let run_id = match run_id {
+            Ok(Some(id)) => {
+                tracing::debug!(position = %position_name, run_id = %id, active = run_is_active, "Found run");
+                id
+            }
+            Ok(None) => {
+                tracing::debug!(position = %position_name, "No run, skipping detail data");
+                return;
+            }
+            Err(e) => {
+                tracing::debug!(position = %position_name, error = %e.display_message(), "Failed to get run_id");
+                return;
+            }
+        };
+
+        let Some(phases) = session
+            .run_until_cancelled(pos_client.get_phase_history())
+            .await
+        else {
+            return;
+        };
+        if let Ok(events) = phases {
+            self.bus.publish(BusEvent::PhaseHistory {
+                position: position_name.clone(),
+                events,
+            });
+        }
+
+        let Some(scans) = session
+            .run_until_cancelled(pos_client.get_mux_scan_results())
+            .await
+        else {
+            return;
+        };
+        match scans {
+            Ok(scans) => self.bus.publish(BusEvent::MuxScans {
+                position: position_name.clone(),
+                scans,
+            }),
+            Err(e) => {
+                tracing::debug!(position = %position_name, error = %e.display_message(), "Mux scan results failed");
+            }
+        }
+
+        let Some(output) = session
+            .run_until_cancelled(pos_client.get_output_info())
+            .await
+        else {
+            return;
+        };
+        match output {
+            Ok(Some(info)) => self.bus.publish(BusEvent::Output {
+                position: position_name.clone(),
+                info,
+            }),
+            Ok(None) => {}
+            Err(e) => {
+                tracing::debug!(position = %position_name, error = %e.display_message(), "Output info failed");
+            }
+        }
+
+        let channel_count = match self.channel_count {
+            Some(count) => count,
+            None => match session
+                .run_until_cancelled(pos_client.get_channel_layout())
+                .await
+            {
+                None => return,
+                Some(Ok(layout)) => {
+                    tracing::info!(
+                        position = %position_name,
+                        width = layout.width,
+                        height = layout.height,
+                        channels = layout.channel_count,
+                        "Got channel layout"
+                    );
+                    let count = layout.channel_count as u32;
+                    self.channel_count = Some(count);
+                    self.bus.publish(BusEvent::ChannelLayout {
+                        position: position_name.clone(),
+                        layout,
+                    });
+                    count
+                }
+                Some(Err(e)) => {
+                    tracing::debug!(position = %position_name, error = %e.display_message(), "Channel layout failed");
+                    512 // Default fallback for MinION
+                }
+            },
+        };
+
+        if !run_is_active {
+            tracing::debug!(position = %position_name, "Skipping streaming endpoints for inactive run");
+            return;
+        }
+
+        if self.schedule.due(Fetch::MeanQuality, Instant::now()) {
+            self.fetch_mean_quality(pos_client, &run_id, session).await;
+        }
+        if self.schedule.due(Fetch::ChannelStates, Instant::now()) {
+            self.fetch_channel_states(pos_client, channel_count, session)
+                .await;
+        }
+    }
+
+    async fn fetch_mean_quality(
+        &self,
+        pos_client: &mut PositionClient,
+        run_id: &str,
+        session: &CancellationToken,
+    ) {
+        let position_name = pos_client.position.name.clone();
+        let Some(quality) = session
+            .run_until_cancelled(tokio::time::timeout(
+                Duration::from_secs(5),
+                pos_client.get_mean_quality(run_id),
+            ))
+            .await
+        else {
+            return;
+        };
+        match quality {
+            Ok(Ok(Some(quality))) => {
+                tracing::debug!(position = %position_name, quality = quality, "Got mean quality");
+                self.bus.publish(BusEvent::MeanQuality {
+                    position: position_name.clone(),
+                    quality: quality as f64,
+                });
+            }
+            Ok(Ok(None)) => {
+                tracing::debug!(position = %position_name, "No quality data available");
+            }
+            Ok(Err(e)) => {
+                tracing::debug!(position = %position_name, error = %e.display_message(), "Quality boxplot failed");
+            }
+            Err(_) => {
+                tracing::debug!(position = %position_name, "Quality fetch timed out");
+            }
+        }
+    }
+
+    async fn fetch_channel_states(
+        &self,
+        pos_client: &mut PositionClient,
+        channel_count: u32,
+        session: &CancellationToken,
+    ) {
+        let position_name = pos_client.position.name.clone();
+        let Some(channel_states) = session
+            .run_until_cancelled(tokio::time::timeout(
+                Duration::from_secs(5),
+                pos_client.get_channel_states(channel_count),
+            ))
+            .await
+        else {
+            return;
+        };
+        match channel_states {
+            Ok(Ok(channel_states)) => {
+                self.bus.publish(BusEvent::ChannelStates {
+                    position: position_name.clone(),
+                    states: channel_states,
+                });
+            }
+            Ok(Err(e)) => {
+                tracing::debug!(position = %position_name, error = %e.display_message(), "Channel states failed");
+            }
+            Err(_) => {
+                tracing::debug!(position = %position_name, "Channel states fetch timed out");
+            }
+        }
+    }
+}
+
+/// Polled data with its own refresh interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Fetch {
+    Stats,
+    MeanQuality,
+    ChannelStates,
+}
+
+/// Tracks when each kind of polled data was last fetched, so slow-changing
+/// data is not fetched on every poll. Data can only be fetched as often as
+/// the poller wakes, so intervals shorter than the refresh interval act like
+/// it.
+struct FetchSchedule {
+    intervals: HashMap<Fetch, Duration>,
+    last: HashMap<Fetch, Instant>,
+}
+
+impl FetchSchedule {
+    fn new(tui: &TuiConfig) -> Self {
+        Self {
+            intervals: HashMap::from([
+                (Fetch::Stats, tui.stats_interval),
+                (Fetch::MeanQuality, tui.histogram_interval),
+                (Fetch::ChannelStates, tui.channel_states_interval),
+            ]),
+            last: HashMap::new(),
+        }
+    }
+
+    /// Whether `fetch` is due at `now`, marking it fetched if so.
+    fn due(&mut self, fetch: Fetch, now: Instant) -> bool {
+        let interval = self.intervals.get(&fetch).copied().unwrap_or_default();
+        let due = self
+            .last
+            .get(&fetch)
+            .is_none_or(|&last| now.duration_since(last) >= interval);
+        if due {
+            self.last.insert(fetch, now);
+        }
+        due
+    }
+
+    /// Makes `fetch` due at the next poll.
+    fn reset(&mut self, fetch: Fetch) {
+        self.last.remove(&fetch);
+    }
+}
+
+/// One stay of a position in the detail view. Its token is cancelled when
+/// the position leaves the view, or when the session is dropped.
+struct DetailSession {
+    token: CancellationToken,
+    _guard: DropGuard,
+}
+
+impl DetailSession {
+    /// Starts a session for `position`, with a task that cancels it as soon
+    /// as the position leaves the detail view.
+    fn start(context: watch::Receiver<PollContext>, position: &str) -> Self {
+        let token = CancellationToken::new();
+        tokio::spawn(cancel_on_leave(
+            context,
+            position.to_string(),
+            token.clone(),
+        ));
+        Self {
+            _guard: token.clone().drop_guard(),
+            token,
+        }
+    }
+}
+
+/// Cancels `session` once `position` is no longer in the detail view or the
+/// UI is gone. Returns early if the session is cancelled elsewhere.
+async fn cancel_on_leave(
+    mut context: watch::Receiver<PollContext>,
+    position: String,
+    session: CancellationToken,
+) {
+    while context.borrow_and_update().detail_position.as_deref() == Some(position.as_str()) {
+        tokio::select! {
+            changed = context.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            _ = session.cancelled() => return,
+        }
+    }
+    tracing::debug!(position = %position, "Left detail view, cancelling detail fetches");
+    session.cancel();
+}
+
+/// Publishes a position's disk space, flow cell, and run state, plus the
+/// run info of an active run and, if `fetch_stats` is set, its statistics.
+/// Returns whether a run is active.
+async fn publish_status(
+    bus: &EventBus,
+    pos_client: &mut impl PositionApi,
+    fetch_stats: bool,
+) -> bool {
+    let name = pos_client.position().name.clone();
+
+    match pos_client.get_disk_space_info().await {
+        Ok(filesystems) => {
+            let fullest = filesystems
+                .into_iter()
+                .min_by(|a, b| a.free_percent().total_cmp(&b.free_percent()));
+            if let Some(info) = fullest {
+                bus.publish(BusEvent::DiskSpace {
+                    position: name.clone(),
+                    info,
+                });
+            }
+        }
+        Err(e) => {
+            tracing::debug!(position = %name, error = %e.display_message(), "Failed to get disk space");
+        }
+    }
+
+    let fc_info = pos_client.get_flow_cell_info().await.ok();
+    let has_flow_cell = fc_info.as_ref().map(|f| f.has_flow_cell).unwrap_or(false);
+
+    if let Some(info) = fc_info {
+        bus.publish(BusEvent::FlowCellInfo {
+            position: name.clone(),
+            info,
+        });
+    }
+
+    if !has_flow_cell {
+        bus.publish(BusEvent::RunStateChanged {
+            position: name,
+            state: RunState::Idle,
+        });
+        return false;
+    }
+
+    let run_state = pos_client.get_run_state().await.ok();
+    if let Some(ref state) = run_state {
+        bus.publish(BusEvent::RunStateChanged {
+            position: name.clone(),
+            state: state.clone(),
+        });
+    }
+
+    let is_active = run_state.as_ref().map(|s| s.is_active()).unwrap_or(false);
+    if !is_active {
+        return false;
+    }
+
+    // Run info goes first so recorders know the run its statistics belong to.
+    if let Ok(Some(info)) = pos_client.get_run_info().await {
+        bus.publish(BusEvent::RunInfo {
+            position: name.clone(),
+            info,
+        });
+    }
+
+    if fetch_stats {
+        if let Ok(stats) = pos_client.get_stats().await {
+            bus.publish(BusEvent::Stats {
+                position: name,
+                stats,
+            });
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::{MockClient, ScriptedPosition};
+    use crate::client::StopOptions;
+
+    #[test]
+    fn test_view_for_only_tracks_own_detail_view() {
+        let context = PollContext {
+            detail_position: Some("X1".into()),
+            ..Default::default()
+        };
+        let moved = PollContext {
+            detail_position: Some("X2".into()),
+            ..context.clone()
+        };
+
+        assert_ne!(context.view_for("X1"), moved.view_for("X1"));
+        assert_eq!(context.view_for("X3"), moved.view_for("X3"));
+    }
+
+    #[test]
+    fn test_opening_run_history_wakes_only_that_position() {
+        let context = PollContext::default();
+        let opened = PollContext {
+            history_position: Some("X1".into()),
+            ..context.clone()
+        };
+
+        assert_ne!(context.view_for("X1"), opened.view_for("X1"));
+        assert_eq!(context.view_for("X2"), opened.view_for("X2"));
+    }
+
+    #[test]
+    fn test_fetch_schedule_spaces_fetches() {
+        let tui = TuiConfig {
+            channel_states_interval: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let mut schedule = FetchSchedule::new(&tui);
+        let start = Instant::now();
+
+        assert!(schedule.due(Fetch::ChannelStates, start));
+        assert!(!schedule.due(Fetch::ChannelStates, start + Duration::from_secs(1)));
+        assert!(schedule.due(Fetch::Stats, start + Duration::from_secs(1)));
+        assert!(schedule.due(Fetch::ChannelStates, start + Duration::from_secs(5)));
+
+        schedule.reset(Fetch::ChannelStates);
+        assert!(schedule.due(Fetch::ChannelStates, start + Duration::from_secs(6)));
+    }
+
+    #[test]
+    fn test_shows_detail_and_history_positions() {
+        let context = PollContext {
+            detail_position: Some("X1".into()),
+            history_position: Some("X2".into()),
+            ..Default::default()
+        };
+
+        assert!(context.shows("X1"));
+        assert!(context.shows("X2"));
+        assert!(!context.shows("X3"));
+    }
+
+    #[test]
+    fn test_histogram_change_affects_every_position() {
+        let context = PollContext::default();
+        let changed = PollContext {
+            histogram: HistogramOptions {
+                exclude_outliers: true,
+                ..Default::default()
+            },
+            ..context.clone()
+        };
+
+        assert_ne!(context.view_for("X1"), changed.view_for("X1"));
+    }
+
+    #[tokio::test]
+    async fn test_detail_session_cancelled_on_leaving_view() {
+        let detail = |name: &str| PollContext {
+            detail_position: Some(name.into()),
+            ..Default::default()
+        };
+        let (tx, rx) = watch::channel(detail("X1"));
+        let session = DetailSession::start(rx.clone(), "X1");
+
+        // Changing the histogram keeps the position in view.
+        tx.send_modify(|c| c.histogram.exclude_outliers = true);
+        tokio::task::yield_now().await;
+        assert!(!session.token.is_cancelled());
+
+        tx.send_replace(detail("X2"));
+        tokio::time::timeout(Duration::from_secs(1), session.token.cancelled())
+            .await
+            .unwrap();
+
+        let session = DetailSession::start(rx, "X2");
+        let token = session.token.clone();
+        drop(session);
+        assert!(token.is_cancelled());
+    }
+
+    fn drain(subscription: &mut crate::bus::Subscription) -> Vec<std::sync::Arc<BusEvent>> {
+        std::iter::from_fn(|| subscription.try_recv()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_publish_status_follows_run_state() {
+        let client = MockClient::new()
+            .with_position(
+                ScriptedPosition::new("X1", "MN12345")
+                    .with_flow_cell("FAX00001")
+                    .with_run("run-1"),
+            )
+            .with_position(ScriptedPosition::new("X2", "MN12345"));
+        let bus = EventBus::default();
+        let mut subscription = bus.subscribe(crate::bus::EventFilter::all());
+
+        let x1 = client.position("X1").unwrap().position;
+        let mut pos_client = client.connect_position(x1).await.unwrap();
+        assert!(publish_status(&bus, &mut pos_client, true).await);
+        let events = drain(&mut subscription);
+        assert!(events.iter().any(
+            |e| matches!(&**e, BusEvent::Stats { stats, .. } if stats.reads_processed == 10_000)
+        ));
+        assert!(events
+            .iter()
+            .any(|e| matches!(&**e, BusEvent::RunInfo { .. })));
+
+        // Statistics that are not due are left out.
+        assert!(publish_status(&bus, &mut pos_client, false).await);
+        assert!(!drain(&mut subscription)
+            .iter()
+            .any(|e| matches!(&**e, BusEvent::Stats { .. })));
+
+        let x2 = client.position("X2").unwrap().position;
+        let mut pos_client = client.connect_position(x2).await.unwrap();
+        assert!(!publish_status(&bus, &mut pos_client, true).await);
+        let events = drain(&mut subscription);
+        assert!(events.iter().any(|e| matches!(
+            &**e,
+            BusEvent::RunStateChanged {
+                state: RunState::Idle,
+                ..
+            }
+        )));
+        assert!(!events
+            .iter()
+            .any(|e| matches!(&**e, BusEvent::Stats { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_run_control_requests_report_failures() {
+        let client = MockClient::new().with_position(
+            ScriptedPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run("run-1"),
+        );
+        let bus = EventBus::default();
+        let mut subscription = bus.subscribe(crate::bus::EventFilter::all());
+        let x1 = client.position("X1").unwrap().position;
+        let request = |action| {
+            spawn_request(
+                Some(client.clone()),
+                &bus,
+                x1.clone(),
+                "Failed to resume run",
+                move |pos_client| run_control(pos_client, action),
+            )
+            .unwrap()
+        };
+
+        request(RunControlAction::Pause).await.unwrap();
+        assert_eq!(client.position("X1").unwrap().run_state, RunState::Paused);
+        assert!(drain(&mut subscription).is_empty());
+
+        client.fail("resume", ClientError::Disconnected);
+        request(RunControlAction::Resume).await.unwrap();
+        assert_eq!(client.position("X1").unwrap().run_state, RunState::Paused);
+        let events = drain(&mut subscription);
+        assert!(matches!(
+            &*events[0],
+            BusEvent::Alert { message, .. } if message == "Failed to resume run: Connection lost"
+        ));
+
+        client.fail(
+            "resume",
+            ClientError::Grpc {
+                method: "resume_protocol".into(),
+                status: tonic::Status::permission_denied("guest access is read-only"),
+            },
+        );
+        request(RunControlAction::Resume).await.unwrap();
+        assert!(matches!(
+            &*drain(&mut subscription)[0],
+            BusEvent::ReadOnly { reason } if reason == "resume_protocol: guest access is read-only"
+        ));
+
+        let handle = spawn_request(
+            None::<MockClient>,
+            &bus,
+            x1.clone(),
+            "Failed",
+            |pos_client| run_control(pos_client, RunControlAction::Stop(StopOptions::default())),
+        );
+        assert!(handle.is_none());
+        assert!(matches!(
+            &*drain(&mut subscription)[0],
+            BusEvent::Alert { message, .. } if message == "Not connected to MinKNOW"
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+//! Playback of a recorded session for `termion --replay`.
+//!
+//! [`ReplaySource`] publishes the events of a [`Session`] with their
+//! original spacing, divided by the replay speed. A replay is read-only:
+//! run control and the start wizard only explain why nothing happens.
+
+use super::poller::{AbortOnDrop, PollContext};
+use super::source::DataSource;
+use super::{RunControlAction, StartRequest};
+use crate::bus::{BusEvent, EventBus};
+use crate::client::{Position, RunUntilCriteria};
+use crate::config::ReplayConfig;
+use crate::session::{self, Session, SessionError};
+use std::time::Duration;
+use tokio::time::Instant;
+
+const READ_ONLY: &str = "Replaying a recorded session; runs cannot be controlled";
+
+/// Publishes a recorded session on the bus in place of MinKNOW.
+pub(super) struct ReplaySource {
+    _task: AbortOnDrop,
+    bus: EventBus,
+}
+
+impl ReplaySource {
+    /// Reads the session file and starts playing it back.
+    pub(super) fn spawn(config: &ReplayConfig, bus: &EventBus) -> Result<Self, SessionError> {
+        let session = session::read(&config.path)?;
+        tracing::info!(
+            path = %config.path.display(),
+            host = %session.header.host,
+            recorded = %session.header.started_at,
+            events = session.entries.len(),
+            "Replaying session"
+        );
+        let task = tokio::spawn(play(session, config.speed, bus.clone()));
+        Ok(Self {
+            _task: AbortOnDrop(task),
+            bus: bus.clone(),
+        })
+    }
+}
+
+async fn play(session: Session, speed: f64, bus: EventBus) {
+    let start = Instant::now();
+    for entry in session.entries {
+        let offset = Duration::from_millis(entry.at_ms).div_f64(speed);
+        tokio::time::sleep_until(start + offset).await;
+        bus.publish(entry.event);
+    }
+    bus.publish(BusEvent::alert("Replay finished"));
+}
+
+impl DataSource for ReplaySource {
+    fn set_context(&self, _context: PollContext) {}
+
+    fn refresh(&self) {}
+
+    fn run_control(&self, _position: Position, _action: RunControlAction) {
+        self.bus.publish(BusEvent::alert(READ_ONLY));
+    }
+
+    fn load_protocols(&self, position: Position) {
+        self.bus.publish(BusEvent::Protocols {
+            position: position.name,
+            protocols: Vec::new(),
+        });
+        self.bus.publish(BusEvent::alert(READ_ONLY));
+    }
+
+    fn start_protocol(&self, _position: Position, _request: StartRequest) {
+        self.bus.publish(BusEvent::alert(READ_ONLY));
+    }
+
+    fn set_run_until(&self, _position: Position, _targets: RunUntilCriteria) {
+        self.bus.publish(BusEvent::alert(READ_ONLY));
+    }
+}
@@ -0,0 +1,52 @@
+//! Where the TUI's data comes from.
+//!
+//! The TUI only consumes bus events, so any [`DataSource`] that publishes
+//! them can drive it: the [`Poller`] talks to MinKNOW over gRPC, and the
+//! [`DemoSource`] simulates a sequencer for demos and screenshots, and the
+//! [`ReplaySource`] plays back a recorded session.
+
+use super::demo::DemoSource;
+use super::poller::{PollContext, Poller};
+use super::replay::ReplaySource;
+use super::{RunControlAction, StartRequest};
+use crate::bus::EventBus;
+use crate::client::{Position, RunUntilCriteria};
+use crate::config::Config;
+
+/// Background producer of position data that also carries out the user's
+/// requests.
+///
+/// A source publishes everything it learns on the bus and stops when
+/// dropped. Requests return immediately; their failures are published as
+/// alerts.
+pub(super) trait DataSource {
+    /// Updates what the UI is showing, so the source can fetch detail data
+    /// for the right position.
+    fn set_context(&self, context: PollContext);
+
+    /// Fetches fresh data now, or retries the connection if it is down.
+    fn refresh(&self);
+
+    fn run_control(&self, position: Position, action: RunControlAction);
+
+    /// Publishes the protocols that can be started on `position`.
+    fn load_protocols(&self, position: Position);
+
+    fn start_protocol(&self, position: Position, request: StartRequest);
+
+    /// Replaces the run-until targets of the run on `position`.
+    fn set_run_until(&self, position: Position, targets: RunUntilCriteria);
+}
+
+/// Starts the demo source if `config.demo` is set, the replay if
+/// `config.replay` is, else the MinKNOW poller. Fails if the session to
+/// replay cannot be read.
+pub(super) fn spawn(config: &Config, bus: &EventBus) -> anyhow::Result<Box<dyn DataSource>> {
+    if config.demo {
+        Ok(Box::new(DemoSource::spawn(config, bus)))
+    } else if let Some(replay) = &config.replay {
+        Ok(Box::new(ReplaySource::spawn(replay, bus)?))
+    } else {
+        Ok(Box::new(Poller::spawn(config, bus)))
+    }
+}
@@ -0,0 +1,145 @@
+//! Statistics stream subscriptions for active runs.
+//!
+//! Each position poller keeps a [`PositionStreams`] open while its position
+//! has an active run; everything received is republished on the event bus.
+
+use super::throughput_from_yield;
+use crate::bus::{BusEvent, EventBus};
+use crate::client::subscription::{HistogramOptions, RunSubscription, StreamData, StreamUpdate};
+use crate::client::PositionClient;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Spawns the task that publishes stream updates on `bus`.
+///
+/// The task exits once every sender has been dropped.
+pub(super) fn forward_to_bus(bus: &EventBus) -> mpsc::UnboundedSender<StreamUpdate> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = bus.clone();
+    tokio::spawn(async move {
+        while let Some(update) = rx.recv().await {
+            publish(&bus, update);
+        }
+    });
+    tx
+}
+
+/// The statistics streams of one position.
+pub(super) struct PositionStreams {
+    subscription: Option<RunSubscription>,
+    tx: mpsc::UnboundedSender<StreamUpdate>,
+    /// How often MinKNOW resends the histograms.
+    histogram_interval: Duration,
+}
+
+impl PositionStreams {
+    pub(super) fn new(
+        tx: mpsc::UnboundedSender<StreamUpdate>,
+        histogram_interval: Duration,
+    ) -> Self {
+        Self {
+            subscription: None,
+            tx,
+            histogram_interval,
+        }
+    }
+
+    /// Makes sure the streams are open for `run_id`, restarting them if the
+    /// run changed and reopening the histogram if its options did. The raw
+    /// signal of `signal_channel` (1-based) is streamed alongside, if set.
+    pub(super) fn ensure(
+        &mut self,
+        pos_client: &PositionClient,
+        run_id: &str,
+        histogram: HistogramOptions,
+        signal_channel: Option<u32>,
+    ) {
+        let sub = match self.subscription.as_mut() {
+            Some(sub) if sub.run_id() == run_id => {
+                sub.set_histogram_options(pos_client, histogram, self.tx.clone());
+                sub
+            }
+            _ => {
+                tracing::info!(position = %pos_client.position.name, run_id = %run_id, "Opening statistics streams");
+                self.subscription.insert(RunSubscription::start(
+                    pos_client,
+                    run_id,
+                    histogram,
+                    self.histogram_interval,
+                    self.tx.clone(),
+                ))
+            }
+        };
+        sub.set_signal_channel(pos_client, signal_channel, self.tx.clone());
+    }
+
+    /// Closes the streams, if open.
+    pub(super) fn close(&mut self) {
+        if let Some(sub) = self.subscription.take() {
+            tracing::info!(run_id = %sub.run_id(), "Closed statistics streams");
+        }
+    }
+}
+
+fn publish(bus: &EventBus, update: StreamUpdate) {
+    let position = update.position;
+    match update.data {
+        StreamData::YieldHistory(points) => {
+            if points.is_empty() {
+                return;
+            }
+            if let Some(bps) = throughput_from_yield(&points) {
+                bus.publish(BusEvent::Throughput {
+                    position: position.clone(),
+                    bases_per_second: bps,
+                });
+            }
+            bus.publish(BusEvent::YieldHistory {
+                position,
+                points,
+                merge: !update.initial,
+            });
+        }
+        StreamData::BarcodeYield(barcodes) => {
+            bus.publish(BusEvent::BarcodeYield {
+                position,
+                barcodes,
+                merge: !update.initial,
+            });
+        }
+        StreamData::DutyTime(duty_time) => {
+            bus.publish(BusEvent::ActivePores {
+                position: position.clone(),
+                count: duty_time.active_pores(0.1) as u32,
+            });
+            bus.publish(BusEvent::DutyTime {
+                position,
+                duty_time,
+            });
+        }
+        StreamData::Histogram(histogram) => {
+            bus.publish(BusEvent::Histogram {
+                position,
+                histogram,
+            });
+        }
+        StreamData::QScoreHistogram(histogram) => {
+            bus.publish(BusEvent::QScoreHistogram {
+                position,
+                histogram,
+            });
+        }
+        StreamData::TranslocationSpeed(points) => {
+            bus.publish(BusEvent::TranslocationSpeed { position, points });
+        }
+        StreamData::RunUntilTargets(targets) => {
+            bus.publish(BusEvent::RunUntilTargets { position, targets });
+        }
+        StreamData::RunUntilProgress(progress) => {
+            bus.publish(BusEvent::RunUntilProgress { position, progress });
+        }
+        StreamData::Signal(chunk) => {
+            bus.publish(BusEvent::Signal { position, chunk });
+        }
+    }
+}
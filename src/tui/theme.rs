@@ -1,15 +1,38 @@
 //! Theme definitions for the TUI.
 //!
 //! This module provides color themes that can be swapped to change
-//! the visual appearance of the interface.
-
-use ratatui::style::Color;
+//! the visual appearance of the interface. Besides the built-in themes,
+//! users can define their own in the config file; those are registered at
+//! startup with [`Theme::set_user_themes`].
+
+use ratatui::style::{Color, Modifier, Style};
+use std::sync::RwLock;
+
+/// Themes defined in the config file, in the order they were loaded.
+static USER_THEMES: RwLock<Vec<Theme>> = RwLock::new(Vec::new());
+
+/// Names of the built-in themes, in selector order.
+const BUILT_IN_THEMES: &[&str] = &[
+    "default",
+    "catppuccin",
+    "dracula",
+    "tokyo-night",
+    "gruvbox",
+    "nord",
+    "neon",
+    "color-blind",
+    "solarized-light",
+    "gruvbox-light",
+];
+
+/// Theme name that picks a dark or light theme to suit the terminal.
+pub const AUTO_THEME: &str = "auto";
 
 /// A complete color theme for the TUI.
 #[derive(Debug, Clone)]
 pub struct Theme {
     /// Name of the theme
-    pub name: &'static str,
+    pub name: String,
 
     // === Borders ===
     /// Primary border color
@@ -78,6 +101,12 @@ pub struct Theme {
     pub key_hint: Color,
     /// Background color
     pub background: Color,
+    /// Background of every other table row
+    pub row_alt_bg: Color,
+    /// Border of the pane that has keyboard focus
+    pub border_focused: Color,
+    /// Border of panes without focus (e.g., behind an overlay)
+    pub border_inactive: Color,
 }
 
 impl Default for Theme {
@@ -90,7 +119,7 @@ impl Theme {
     /// Default theme - cyan accent with standard terminal colors
     pub fn default_theme() -> Self {
         Self {
-            name: "Default",
+            name: "Default".to_string(),
 
             border: Color::Cyan,
             border_dim: Color::DarkGray,
@@ -125,13 +154,16 @@ impl Theme {
             selection_fg: Color::White,
             key_hint: Color::Yellow,
             background: Color::Black,
+            row_alt_bg: Color::Indexed(235),
+            border_focused: Color::Cyan,
+            border_inactive: Color::DarkGray,
         }
     }
 
     /// Catppuccin Mocha - warm pastel theme
     pub fn catppuccin_mocha() -> Self {
         Self {
-            name: "Catppuccin Mocha",
+            name: "Catppuccin Mocha".to_string(),
 
             border: Color::Rgb(180, 190, 254),           // Lavender
             border_dim: Color::Rgb(88, 91, 112),         // Surface2
@@ -166,13 +198,16 @@ impl Theme {
             selection_fg: Color::Rgb(205, 214, 244), // Text
             key_hint: Color::Rgb(249, 226, 175),     // Yellow
             background: Color::Rgb(30, 30, 46),      // Base
+            row_alt_bg: Color::Rgb(49, 50, 68),
+            border_focused: Color::Rgb(203, 166, 247),
+            border_inactive: Color::Rgb(88, 91, 112),
         }
     }
 
     /// Dracula - purple/pink dark theme
     pub fn dracula() -> Self {
         Self {
-            name: "Dracula",
+            name: "Dracula".to_string(),
 
             border: Color::Rgb(189, 147, 249),           // Purple
             border_dim: Color::Rgb(68, 71, 90),          // Current Line
@@ -207,13 +242,16 @@ impl Theme {
             selection_fg: Color::Rgb(248, 248, 242), // Foreground
             key_hint: Color::Rgb(241, 250, 140),  // Yellow
             background: Color::Rgb(40, 42, 54),   // Background
+            row_alt_bg: Color::Rgb(52, 55, 70),
+            border_focused: Color::Rgb(255, 121, 198),
+            border_inactive: Color::Rgb(68, 71, 90),
         }
     }
 
     /// Tokyo Night - cool blue/purple theme
     pub fn tokyo_night() -> Self {
         Self {
-            name: "Tokyo Night",
+            name: "Tokyo Night".to_string(),
 
             border: Color::Rgb(122, 162, 247),           // Blue
             border_dim: Color::Rgb(59, 66, 97),          // Comment
@@ -248,13 +286,16 @@ impl Theme {
             selection_fg: Color::Rgb(192, 202, 245), // Foreground
             key_hint: Color::Rgb(224, 175, 104),     // Orange
             background: Color::Rgb(26, 27, 38),      // Background
+            row_alt_bg: Color::Rgb(36, 40, 59),
+            border_focused: Color::Rgb(125, 207, 255),
+            border_inactive: Color::Rgb(59, 66, 97),
         }
     }
 
     /// Gruvbox Dark - warm retro theme
     pub fn gruvbox() -> Self {
         Self {
-            name: "Gruvbox",
+            name: "Gruvbox".to_string(),
 
             border: Color::Rgb(254, 128, 25),            // Orange
             border_dim: Color::Rgb(80, 73, 69),          // Bg2
@@ -289,13 +330,16 @@ impl Theme {
             selection_fg: Color::Rgb(235, 219, 178), // Fg
             key_hint: Color::Rgb(250, 189, 47),      // Yellow
             background: Color::Rgb(40, 40, 40),      // Bg0
+            row_alt_bg: Color::Rgb(50, 48, 47),
+            border_focused: Color::Rgb(142, 192, 124),
+            border_inactive: Color::Rgb(80, 73, 69),
         }
     }
 
     /// Nord - cool, muted arctic theme
     pub fn nord() -> Self {
         Self {
-            name: "Nord",
+            name: "Nord".to_string(),
 
             border: Color::Rgb(136, 192, 208),   // Frost cyan
             border_dim: Color::Rgb(76, 86, 106), // Polar Night 3
@@ -330,13 +374,16 @@ impl Theme {
             selection_fg: Color::Rgb(236, 239, 244), // Snow Storm 0
             key_hint: Color::Rgb(235, 203, 139),  // Aurora yellow
             background: Color::Rgb(46, 52, 64),   // Polar Night 0
+            row_alt_bg: Color::Rgb(52, 59, 74),
+            border_focused: Color::Rgb(143, 188, 187),
+            border_inactive: Color::Rgb(76, 86, 106),
         }
     }
 
     /// High contrast neon theme
     pub fn neon() -> Self {
         Self {
-            name: "Neon",
+            name: "Neon".to_string(),
 
             border: Color::Rgb(0, 255, 255),           // Cyan
             border_dim: Color::Rgb(64, 64, 64),        // Gray
@@ -371,11 +418,202 @@ impl Theme {
             selection_fg: Color::Rgb(255, 255, 255), // White
             key_hint: Color::Rgb(255, 255, 0),       // Yellow
             background: Color::Rgb(0, 0, 0),         // Black
+            row_alt_bg: Color::Rgb(20, 20, 20),
+            border_focused: Color::Rgb(255, 0, 255),
+            border_inactive: Color::Rgb(64, 64, 64),
+        }
+    }
+
+    /// Color-blind safe theme - the Okabe-Ito palette, which stays apart
+    /// under red-green and blue-yellow color blindness. Passed and failed,
+    /// and sequencing and unavailable, are blue against vermillion rather
+    /// than green against red.
+    pub fn color_blind() -> Self {
+        Self {
+            name: "Color-blind Safe".to_string(),
+
+            border: Color::Rgb(86, 180, 233),          // Sky blue
+            border_dim: Color::Rgb(96, 96, 96),        // Gray
+            border_highlight: Color::Rgb(230, 159, 0), // Orange
+
+            text: Color::Rgb(235, 235, 235),      // Off-white
+            text_dim: Color::Rgb(140, 140, 140),  // Gray
+            text_title: Color::Rgb(86, 180, 233), // Sky blue
+
+            success: Color::Rgb(86, 180, 233),  // Sky blue
+            warning: Color::Rgb(240, 228, 66),  // Yellow
+            error: Color::Rgb(213, 94, 0),      // Vermillion
+            info: Color::Rgb(0, 158, 115),      // Bluish green
+            idle: Color::Rgb(140, 140, 140),    // Gray
+            special: Color::Rgb(204, 121, 167), // Reddish purple
+
+            chart_line: Color::Rgb(240, 228, 66),   // Yellow
+            chart_fill: Color::Rgb(0, 114, 178),    // Blue
+            chart_passed: Color::Rgb(86, 180, 233), // Sky blue
+            chart_failed: Color::Rgb(213, 94, 0),   // Vermillion
+            chart_axis: Color::Rgb(140, 140, 140),  // Gray
+
+            channel_sequencing: Color::Rgb(86, 180, 233), // Sky blue
+            channel_pore: Color::Rgb(0, 114, 178),        // Blue
+            channel_unavailable: Color::Rgb(213, 94, 0),  // Vermillion
+            channel_inactive: Color::Rgb(204, 121, 167),  // Reddish purple
+            channel_adapter: Color::Rgb(230, 159, 0),     // Orange
+            channel_other: Color::Rgb(140, 140, 140),     // Gray
+            channel_empty: Color::Rgb(0, 0, 0),           // Black
+
+            selection_bg: Color::Rgb(64, 64, 64),    // Dark gray
+            selection_fg: Color::Rgb(255, 255, 255), // White
+            key_hint: Color::Rgb(230, 159, 0),       // Orange
+            background: Color::Rgb(0, 0, 0),         // Black
+            row_alt_bg: Color::Rgb(24, 24, 24),
+            border_focused: Color::Rgb(230, 159, 0),
+            border_inactive: Color::Rgb(64, 64, 64),
         }
     }
 
-    /// Get a theme by name
+    /// Solarized Light - low-contrast theme for light terminal backgrounds
+    pub fn solarized_light() -> Self {
+        Self {
+            name: "Solarized Light".to_string(),
+
+            border: Color::Rgb(38, 139, 210),           // Blue
+            border_dim: Color::Rgb(147, 161, 161),      // Base1
+            border_highlight: Color::Rgb(42, 161, 152), // Cyan
+
+            text: Color::Rgb(88, 110, 117),       // Base01
+            text_dim: Color::Rgb(147, 161, 161),  // Base1
+            text_title: Color::Rgb(38, 139, 210), // Blue
+
+            success: Color::Rgb(133, 153, 0),   // Green
+            warning: Color::Rgb(181, 137, 0),   // Yellow
+            error: Color::Rgb(220, 50, 47),     // Red
+            info: Color::Rgb(38, 139, 210),     // Blue
+            idle: Color::Rgb(147, 161, 161),    // Base1
+            special: Color::Rgb(108, 113, 196), // Violet
+
+            chart_line: Color::Rgb(38, 139, 210),  // Blue
+            chart_fill: Color::Rgb(42, 161, 152),  // Cyan
+            chart_passed: Color::Rgb(133, 153, 0), // Green
+            chart_failed: Color::Rgb(220, 50, 47), // Red
+            chart_axis: Color::Rgb(147, 161, 161), // Base1
+
+            channel_sequencing: Color::Rgb(133, 153, 0), // Green
+            channel_pore: Color::Rgb(38, 139, 210),      // Blue
+            channel_unavailable: Color::Rgb(211, 54, 130), // Magenta
+            channel_inactive: Color::Rgb(42, 161, 152),  // Cyan
+            channel_adapter: Color::Rgb(181, 137, 0),    // Yellow
+            channel_other: Color::Rgb(147, 161, 161),    // Base1
+            channel_empty: Color::Rgb(238, 232, 213),    // Base2
+
+            selection_bg: Color::Rgb(238, 232, 213), // Base2
+            selection_fg: Color::Rgb(7, 54, 66),     // Base02
+            key_hint: Color::Rgb(203, 75, 22),       // Orange
+            background: Color::Rgb(253, 246, 227),   // Base3
+            row_alt_bg: Color::Rgb(245, 239, 220),
+            border_focused: Color::Rgb(203, 75, 22),
+            border_inactive: Color::Rgb(147, 161, 161),
+        }
+    }
+
+    /// Gruvbox Light - warm retro theme for light terminal backgrounds
+    pub fn gruvbox_light() -> Self {
+        Self {
+            name: "Gruvbox Light".to_string(),
+
+            border: Color::Rgb(175, 58, 3),            // Orange
+            border_dim: Color::Rgb(213, 196, 161),     // Bg2
+            border_highlight: Color::Rgb(66, 123, 88), // Aqua
+
+            text: Color::Rgb(60, 56, 54),        // Fg
+            text_dim: Color::Rgb(146, 131, 116), // Gray
+            text_title: Color::Rgb(175, 58, 3),  // Orange
+
+            success: Color::Rgb(121, 116, 14), // Green
+            warning: Color::Rgb(181, 118, 20), // Yellow
+            error: Color::Rgb(157, 0, 6),      // Red
+            info: Color::Rgb(7, 102, 120),     // Blue
+            idle: Color::Rgb(146, 131, 116),   // Gray
+            special: Color::Rgb(143, 63, 113), // Purple
+
+            chart_line: Color::Rgb(66, 123, 88),    // Aqua
+            chart_fill: Color::Rgb(7, 102, 120),    // Blue
+            chart_passed: Color::Rgb(121, 116, 14), // Green
+            chart_failed: Color::Rgb(157, 0, 6),    // Red
+            chart_axis: Color::Rgb(146, 131, 116),  // Gray
+
+            channel_sequencing: Color::Rgb(121, 116, 14), // Green
+            channel_pore: Color::Rgb(7, 102, 120),        // Blue
+            channel_unavailable: Color::Rgb(143, 63, 113), // Purple
+            channel_inactive: Color::Rgb(66, 123, 88),    // Aqua
+            channel_adapter: Color::Rgb(181, 118, 20),    // Yellow
+            channel_other: Color::Rgb(189, 174, 147),     // Bg3
+            channel_empty: Color::Rgb(235, 219, 178),     // Bg1
+
+            selection_bg: Color::Rgb(235, 219, 178), // Bg1
+            selection_fg: Color::Rgb(40, 40, 40),    // Bg0 (dark)
+            key_hint: Color::Rgb(181, 118, 20),      // Yellow
+            background: Color::Rgb(251, 241, 199),   // Bg0
+            row_alt_bg: Color::Rgb(242, 229, 188),
+            border_focused: Color::Rgb(66, 123, 88),
+            border_inactive: Color::Rgb(213, 196, 161),
+        }
+    }
+
+    /// The theme for [`AUTO_THEME`]: Solarized Light when the terminal
+    /// reports a light background, otherwise the default dark theme.
+    pub fn auto() -> Self {
+        let light = std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| colorfgbg_is_light(&value))
+            .unwrap_or(false);
+        if light {
+            Self::solarized_light()
+        } else {
+            Self::default_theme()
+        }
+    }
+
+    /// Border style for a pane, depending on whether it has focus.
+    pub fn pane_border(&self, focused: bool) -> Style {
+        if focused {
+            Style::default().fg(self.border_focused)
+        } else {
+            Style::default().fg(self.border_inactive)
+        }
+    }
+
+    /// Accent for a value that just changed.
+    pub fn changed(&self) -> Style {
+        Style::default()
+            .fg(self.border_highlight)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Base style for a table row, striping every other row.
+    pub fn row_style(&self, idx: usize) -> Style {
+        if idx % 2 == 1 {
+            Style::default().bg(self.row_alt_bg)
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Get a built-in or user-defined theme by name, or with [`AUTO_THEME`]
+    /// the one suiting the terminal background
     pub fn by_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case(AUTO_THEME) {
+            return Some(Self::auto());
+        }
+        Self::built_in(name).or_else(|| {
+            user_themes()
+                .iter()
+                .find(|theme| theme.name.eq_ignore_ascii_case(name))
+                .cloned()
+        })
+    }
+
+    /// Get a built-in theme by name
+    pub fn built_in(name: &str) -> Option<Self> {
         match name.to_lowercase().as_str() {
             "default" => Some(Self::default_theme()),
             "catppuccin" | "catppuccin-mocha" | "catppuccin_mocha" => {
@@ -386,20 +624,166 @@ impl Theme {
             "gruvbox" => Some(Self::gruvbox()),
             "nord" => Some(Self::nord()),
             "neon" => Some(Self::neon()),
+            "color-blind" | "color_blind" | "colorblind" | "okabe-ito" => Some(Self::color_blind()),
+            "solarized-light" | "solarized_light" => Some(Self::solarized_light()),
+            "gruvbox-light" | "gruvbox_light" => Some(Self::gruvbox_light()),
             _ => None,
         }
     }
 
-    /// List all available theme names
-    pub fn available_themes() -> &'static [&'static str] {
-        &[
-            "default",
-            "catppuccin",
-            "dracula",
-            "tokyo-night",
-            "gruvbox",
-            "nord",
-            "neon",
-        ]
+    /// List all available theme names: the built-ins, then user themes
+    pub fn available_themes() -> Vec<String> {
+        BUILT_IN_THEMES
+            .iter()
+            .map(|name| name.to_string())
+            .chain(user_themes().iter().map(|theme| theme.name.clone()))
+            .collect()
+    }
+
+    /// Replace the user-defined themes that [`Theme::by_name`] and
+    /// [`Theme::available_themes`] offer alongside the built-ins.
+    pub fn set_user_themes(themes: Vec<Theme>) {
+        *USER_THEMES.write().unwrap_or_else(|e| e.into_inner()) = themes;
+    }
+
+    /// Names of the color fields, as used in the config file.
+    pub const COLOR_FIELDS: &'static [&'static str] = &[
+        "border",
+        "border_dim",
+        "border_highlight",
+        "text",
+        "text_dim",
+        "text_title",
+        "success",
+        "warning",
+        "error",
+        "info",
+        "idle",
+        "special",
+        "chart_line",
+        "chart_fill",
+        "chart_passed",
+        "chart_failed",
+        "chart_axis",
+        "channel_sequencing",
+        "channel_pore",
+        "channel_unavailable",
+        "channel_inactive",
+        "channel_adapter",
+        "channel_other",
+        "channel_empty",
+        "selection_bg",
+        "selection_fg",
+        "key_hint",
+        "background",
+        "row_alt_bg",
+        "border_focused",
+        "border_inactive",
+    ];
+
+    /// Mutable access to a color field by its name in [`Theme::COLOR_FIELDS`].
+    pub fn color_mut(&mut self, field: &str) -> Option<&mut Color> {
+        let color = match field {
+            "border" => &mut self.border,
+            "border_dim" => &mut self.border_dim,
+            "border_highlight" => &mut self.border_highlight,
+            "text" => &mut self.text,
+            "text_dim" => &mut self.text_dim,
+            "text_title" => &mut self.text_title,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "info" => &mut self.info,
+            "idle" => &mut self.idle,
+            "special" => &mut self.special,
+            "chart_line" => &mut self.chart_line,
+            "chart_fill" => &mut self.chart_fill,
+            "chart_passed" => &mut self.chart_passed,
+            "chart_failed" => &mut self.chart_failed,
+            "chart_axis" => &mut self.chart_axis,
+            "channel_sequencing" => &mut self.channel_sequencing,
+            "channel_pore" => &mut self.channel_pore,
+            "channel_unavailable" => &mut self.channel_unavailable,
+            "channel_inactive" => &mut self.channel_inactive,
+            "channel_adapter" => &mut self.channel_adapter,
+            "channel_other" => &mut self.channel_other,
+            "channel_empty" => &mut self.channel_empty,
+            "selection_bg" => &mut self.selection_bg,
+            "selection_fg" => &mut self.selection_fg,
+            "key_hint" => &mut self.key_hint,
+            "background" => &mut self.background,
+            "row_alt_bg" => &mut self.row_alt_bg,
+            "border_focused" => &mut self.border_focused,
+            "border_inactive" => &mut self.border_inactive,
+            _ => return None,
+        };
+        Some(color)
+    }
+}
+
+fn user_themes() -> std::sync::RwLockReadGuard<'static, Vec<Theme>> {
+    USER_THEMES.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Parses a hex color such as `#1e1e2e` (the `#` is optional).
+pub fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// Whether a `COLORFGBG` value (`fg;bg`, set by rxvt, Konsole and some
+/// other terminals) names a light background: white, light gray or one of
+/// the bright colors other than dark gray. `None` when it names no color.
+fn colorfgbg_is_light(value: &str) -> Option<bool> {
+    let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+    Some(matches!(background, 7 | 9..=15))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#1e1e2e"), Some(Color::Rgb(30, 30, 46)));
+        assert_eq!(parse_hex_color("FFFFFF"), Some(Color::Rgb(255, 255, 255)));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("#12345g"), None);
+        assert_eq!(parse_hex_color("red"), None);
+    }
+
+    #[test]
+    fn test_built_in_themes_resolve() {
+        for name in BUILT_IN_THEMES {
+            assert!(Theme::built_in(name).is_some(), "{name}");
+        }
+        let theme = Theme::built_in("colorblind").unwrap();
+        assert_eq!(theme.name, "Color-blind Safe");
+        assert_ne!(theme.chart_passed, theme.chart_failed);
+    }
+
+    #[test]
+    fn test_colorfgbg_is_light() {
+        assert_eq!(colorfgbg_is_light("0;15"), Some(true));
+        assert_eq!(colorfgbg_is_light("0;default;7"), Some(true));
+        assert_eq!(colorfgbg_is_light("15;0"), Some(false));
+        assert_eq!(colorfgbg_is_light("7;8"), Some(false));
+        assert_eq!(colorfgbg_is_light("default;default"), None);
+        assert_eq!(colorfgbg_is_light(""), None);
+    }
+
+    #[test]
+    fn test_color_fields_are_all_settable() {
+        let mut theme = Theme::default_theme();
+        for field in Theme::COLOR_FIELDS {
+            *theme.color_mut(field).unwrap() = Color::Rgb(1, 2, 3);
+        }
+        assert_eq!(theme.border, Color::Rgb(1, 2, 3));
+        assert_eq!(theme.border_inactive, Color::Rgb(1, 2, 3));
+        assert!(theme.color_mut("bogus").is_none());
     }
 }
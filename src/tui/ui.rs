@@ -1,22 +1,36 @@
 //! UI rendering functions.
 
-use super::app::{App, ConnectionState, DetailChart, Overlay, RunControlAction, Screen, YieldUnit};
+use super::app::{
+    confirmation_matches, App, ChannelOccupancy, ChartBuffer, ChartCursor, ConnectionState,
+    DetailChart, HistogramField, HistogramForm, Overlay, OverviewSort, PerfStats, RunControlAction,
+    RunUntilField, RunUntilForm, Screen, StartField, StartStep, StartWizard, StatValue, ToastLevel,
+    YieldSeries, YieldUnit, YieldWindow, MAX_NOTE_LEN, SLOW_TRANSLOCATION_SPEED, STOP_CONFIRMATION,
+};
+use super::downsample;
+use super::event::{Action, HitMap, MouseTarget};
 use super::theme::Theme;
+use crate::analysis::{self, Projection, ProjectionModel};
+use crate::client::diagnostics::{CheckResult, CheckStatus, ErrorClass};
 use crate::client::{
-    ChannelLayout, ChannelStatesSnapshot, Position, PositionState, ReadLengthHistogram, RunState,
-    StatsSnapshot,
+    ChannelLayout, ChannelState, ChannelStatesSnapshot, DiskSpaceInfo, MuxScanResult, OutputInfo,
+    PhaseKind, Position, PositionState, QScoreHistogram, ReadLengthHistogram, RunInfo, RunState,
+    RunSummary, SpeedDataPoint, StopDataAction,
 };
+use crate::config::{DisplayTimezone, MAX_HISTOGRAM_BUCKETS};
+use crate::format::format_storage;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     symbols,
     text::{Line, Span},
     widgets::{
-        Axis, Bar, BarChart, BarGroup, Block, Borders, Chart, Dataset, GraphType, Paragraph, Row,
-        Table, Wrap,
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Dataset, GraphType,
+        LegendPosition, LineGauge, Paragraph, Row, Table, Wrap,
     },
     Frame,
 };
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
 /// Represents the type of flow cell (device) based on channel count.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -87,21 +101,36 @@ pub struct GridStructure {
     pub cell_width: usize,
 }
 
-pub fn render(frame: &mut Frame, app: &App) {
+pub fn render(frame: &mut Frame, app: &App) -> HitMap {
     let area = frame.area();
     let t = &app.theme;
+    let mut hits = HitMap::default();
+
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        render_too_small(frame, t, area);
+        return hits;
+    }
 
     match &app.screen {
-        Screen::Overview => render_overview(frame, app, area),
+        Screen::Overview => render_overview(frame, app, area, &mut hits),
         Screen::PositionDetail { position_idx } => {
-            render_position_detail(frame, app, *position_idx, area)
+            render_position_detail(frame, app, *position_idx, area, &mut hits)
+        }
+        Screen::RunHistory { position_idx } => {
+            render_run_history(frame, app, *position_idx, area, &mut hits)
         }
+        Screen::RunHistoryDetail {
+            position_idx,
+            run_idx,
+        } => render_run_summary(frame, app, *position_idx, *run_idx, area, &mut hits),
     }
 
     match &app.overlay {
         Overlay::Help => {
-            if let Some(help_area) = centered_rect(50, 45, area) {
-                render_help_overlay(frame, t, help_area);
+            // The read-only explanation needs a few more lines.
+            let height = if app.read_only.is_some() { 59 } else { 49 };
+            if let Some(help_area) = centered_rect(50, height, area) {
+                render_help_overlay(frame, t, app.read_only.as_deref(), help_area);
             }
         }
         Overlay::Error { message } => {
@@ -109,29 +138,140 @@ pub fn render(frame: &mut Frame, app: &App) {
                 render_error_overlay(frame, t, message, error_area);
             }
         }
-        Overlay::RangeInput { max_input } => {
-            if let Some(range_area) = centered_rect(35, 18, area) {
-                render_range_input_overlay(frame, t, max_input, range_area);
+        Overlay::RangeInput(form) => {
+            if let Some(range_area) = centered_fixed_rect(48, 12, area) {
+                render_range_input_overlay(frame, t, form, range_area);
             }
         }
         Overlay::Confirmation {
             action,
             position_name,
+            typed,
         } => {
-            if let Some(confirm_area) = centered_rect(45, 22, area) {
-                render_confirmation_overlay(frame, t, *action, position_name, confirm_area);
+            let height = if action.needs_typed_confirmation() {
+                17
+            } else {
+                9
+            };
+            if let Some(confirm_area) = centered_fixed_rect(50, height, area) {
+                render_confirmation_overlay(frame, t, *action, position_name, typed, confirm_area);
             }
         }
         Overlay::ThemeSelector { selected } => {
-            if let Some(theme_area) = centered_fixed_rect(28, 15, area) {
+            let height = Theme::available_themes().len() as u16 + 8;
+            if let Some(theme_area) = centered_fixed_rect(28, height, area) {
                 render_theme_selector(frame, t, *selected, theme_area);
             }
         }
-        Overlay::None => {}
+        Overlay::Diagnostics { results } => {
+            if let Some(diag_area) = centered_rect(60, 45, area) {
+                render_diagnostics_overlay(frame, t, results, diag_area);
+            }
+        }
+        Overlay::StartProtocol(wizard) => {
+            if let Some(wizard_area) = centered_rect(60, 60, area) {
+                render_start_wizard(frame, t, wizard, wizard_area);
+            }
+        }
+        Overlay::RunUntil(form) => {
+            if let Some(form_area) = centered_fixed_rect(48, 11, area) {
+                render_run_until_overlay(frame, t, form, form_area);
+            }
+        }
+        Overlay::Debug => {
+            if let Some(debug_area) = centered_rect(70, 60, area) {
+                render_debug_overlay(frame, t, &app.perf, debug_area);
+            }
+        }
+        Overlay::Note {
+            position_name,
+            text,
+        } => {
+            if let Some(note_area) = centered_fixed_rect(60, 7, area) {
+                render_note_overlay(frame, t, position_name, text, note_area);
+            }
+        }
+        Overlay::ErrorLog { scroll } => {
+            if let Some(log_area) = centered_rect(70, 60, area) {
+                render_error_log(frame, app, *scroll, log_area);
+            }
+        }
+        // Drawn in place of the overview footer.
+        Overlay::Filter | Overlay::None => {}
+    }
+
+    render_toasts(frame, app, area);
+    hits
+}
+
+/// Smallest terminal the screens are drawn in; below it only a hint to
+/// enlarge the terminal is shown.
+const MIN_WIDTH: u16 = 60;
+const MIN_HEIGHT: u16 = 20;
+
+/// Below these the detail view shrinks Run Info and stacks the pore
+/// activity panels.
+const COMPACT_WIDTH: u16 = 100;
+const COMPACT_HEIGHT: u16 = 30;
+
+fn render_too_small(frame: &mut Frame, t: &Theme, area: Rect) {
+    let lines = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default().bold().fg(t.warning),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{}×{}, needs {}×{}",
+                area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+            ),
+            Style::default().fg(t.text_dim),
+        )),
+    ];
+    let y = area.y + area.height.saturating_sub(2) / 2;
+    let hint_area = Rect::new(area.x, y, area.width, area.height.min(2));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        hint_area,
+    );
+}
+
+/// Stacks active toasts in the top-right corner, newest at the bottom.
+fn render_toasts(frame: &mut Frame, app: &App, area: Rect) {
+    let t = &app.theme;
+    let width = area.width.min(56);
+    let mut y = area.y + 1;
+    for toast in &app.toasts {
+        let color = match toast.level {
+            ToastLevel::Info => t.info,
+            ToastLevel::Warning => t.warning,
+            ToastLevel::Error => t.error,
+        };
+        // Border plus wrapped text.
+        let inner = width.saturating_sub(4).max(1) as usize;
+        let height = (toast.message.chars().count().div_ceil(inner) as u16).max(1) + 2;
+        if y + height > area.bottom() {
+            break;
+        }
+        let toast_area = Rect::new(area.right().saturating_sub(width + 1), y, width, height);
+        let paragraph = Paragraph::new(toast.message.as_str())
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(color))
+                    .padding(ratatui::widgets::Padding::horizontal(1))
+                    .style(Style::default().bg(t.background)),
+            );
+        frame.render_widget(ratatui::widgets::Clear, toast_area);
+        frame.render_widget(paragraph, toast_area);
+        y += height;
     }
 }
 
-fn render_overview(frame: &mut Frame, app: &App, area: Rect) {
+fn render_overview(frame: &mut Frame, app: &App, area: Rect, hits: &mut HitMap) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -142,32 +282,163 @@ fn render_overview(frame: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     render_header(frame, app, chunks[0]);
-    render_position_table(frame, app, chunks[1]);
-    render_footer(frame, app, chunks[2]);
+    // With several hosts the table stays up and marks unreachable ones.
+    let reason = match &app.connection {
+        ConnectionState::Disconnected { reason, .. }
+        | ConnectionState::Reconnecting { reason, .. }
+            if app.hosts.is_empty() =>
+        {
+            Some(reason)
+        }
+        _ => None,
+    };
+    if let Some(reason) = reason {
+        render_connection_banner(frame, app, reason, chunks[1]);
+    } else {
+        render_position_table(frame, app, chunks[1], hits);
+    }
+    if app.overlay == Overlay::Filter {
+        render_filter_input(frame, app, chunks[2]);
+    } else {
+        render_footer(frame, app, chunks[2], hits);
+    }
+}
+
+/// Draws the filter being typed in place of the overview footer, so the
+/// table stays visible as it narrows.
+fn render_filter_input(frame: &mut Frame, app: &App, area: Rect) {
+    let t = &app.theme;
+    let line = Line::from(vec![
+        Span::styled(" / ", Style::default().fg(t.key_hint).bold()),
+        Span::styled(app.position_filter.as_str(), Style::default().fg(t.text)),
+        Span::styled("_", Style::default().fg(t.key_hint)),
+    ]);
+    let input = Paragraph::new(line).block(
+        Block::default()
+            .title(" Filter (name, device, flow cell) ")
+            .title_bottom(Line::from(" [Enter] Keep  [Esc] Clear ").right_aligned())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.special)),
+    );
+    frame.render_widget(input, area);
 }
 
 fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     let t = &app.theme;
+    let connected_hosts = app
+        .hosts
+        .iter()
+        .filter(|(_, state)| matches!(state, ConnectionState::Connected))
+        .count();
     let status = match &app.connection {
+        ConnectionState::Connected if connected_hosts < app.hosts.len() => Span::styled(
+            format!(
+                " ● Connected ({}/{} hosts) ",
+                connected_hosts,
+                app.hosts.len()
+            ),
+            Style::default().fg(t.warning),
+        ),
         ConnectionState::Connected => Span::styled(" ● Connected ", Style::default().fg(t.success)),
         ConnectionState::Connecting => {
             Span::styled(" ◌ Connecting... ", Style::default().fg(t.warning))
         }
-        ConnectionState::Disconnected { reason, .. } => Span::styled(
-            format!(" ○ Disconnected: {} ", reason),
+        ConnectionState::Disconnected {
+            next_attempt: Some((_, at)),
+            ..
+        } => Span::styled(
+            format!(" ○ Disconnected, retrying {} ", countdown(*at)),
             Style::default().fg(t.error),
         ),
-        ConnectionState::Reconnecting { attempt } => Span::styled(
+        ConnectionState::Disconnected { .. } => {
+            Span::styled(" ○ Disconnected ", Style::default().fg(t.error))
+        }
+        ConnectionState::Reconnecting { attempt, .. } => Span::styled(
             format!(" ◌ Reconnecting (attempt {})... ", attempt),
             Style::default().fg(t.warning),
         ),
     };
 
-    let title = Line::from(vec![
+    let mut spans = vec![
         Span::styled(" Termion ", Style::default().bold().fg(t.text)),
         Span::raw("│"),
         status,
-    ]);
+    ];
+    if let (ConnectionState::Connected, Some(rtt)) = (&app.connection, app.latency) {
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(
+            format!(" ● {} ", format_latency(rtt)),
+            Style::default().fg(latency_color(t, rtt)),
+        ));
+    }
+    // Enough to tell from a screenshot which instrument is shown.
+    let dim = Style::default().fg(t.text_dim);
+    if app.hosts.is_empty() && !app.config.is_offline() {
+        let endpoint = app.server.as_ref().map_or_else(
+            || {
+                format!(
+                    "{}:{}",
+                    app.config.connection.host, app.config.connection.port
+                )
+            },
+            |server| server.endpoint.clone(),
+        );
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(format!(" {} ", endpoint), dim));
+    }
+    if let Some(server) = &app.server {
+        if let Some(version) = &server.version {
+            spans.push(Span::raw("│"));
+            spans.push(Span::styled(format!(" MinKNOW {} ", version.full), dim));
+        }
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(
+            if server.authenticated {
+                " token "
+            } else {
+                " guest "
+            },
+            dim,
+        ));
+    }
+    let active_runs = app
+        .positions
+        .iter()
+        .filter(|p| app.get_run_state(&p.name).is_some_and(|s| s.is_active()))
+        .count();
+    spans.push(Span::raw("│"));
+    spans.push(Span::styled(
+        match active_runs {
+            1 => " 1 run active ".to_string(),
+            n => format!(" {} runs active ", n),
+        },
+        dim,
+    ));
+    if let Some(profile) = &app.config.connection.profile {
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(
+            format!(" {} ", profile),
+            Style::default().fg(t.text_dim),
+        ));
+    }
+    if app.read_only.is_some() {
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(
+            " read-only ",
+            Style::default().fg(t.warning).bold(),
+        ));
+    }
+    if let Some(frozen_at) = app.frozen_at {
+        spans.push(Span::raw("│"));
+        spans.push(Span::styled(
+            format!(
+                " ❚❚ frozen at {} ",
+                app.config.tui.timezone.format(frozen_at, "%H:%M:%S")
+            ),
+            Style::default().fg(t.warning).bold(),
+        ));
+    }
+    let title = Line::from(spans);
 
     let header = Paragraph::new(title).block(
         Block::default()
@@ -178,8 +449,114 @@ fn render_header(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(header, area);
 }
 
+/// Round-trip times from which the header's latency indicator turns
+/// yellow, then red.
+const SLOW_LATENCY: Duration = Duration::from_millis(250);
+const BAD_LATENCY: Duration = Duration::from_secs(1);
+
+/// Round-trip time in milliseconds, or seconds from one second up.
+fn format_latency(rtt: Duration) -> String {
+    if rtt < Duration::from_secs(1) {
+        format!("{}ms", rtt.as_millis())
+    } else {
+        format!("{:.1}s", rtt.as_secs_f64())
+    }
+}
+
+fn latency_color(t: &Theme, rtt: Duration) -> Color {
+    if rtt >= BAD_LATENCY {
+        t.error
+    } else if rtt >= SLOW_LATENCY {
+        t.warning
+    } else {
+        t.success
+    }
+}
+
+/// Time until `at` in whole seconds, rounded up, e.g. "in 8s".
+fn countdown(at: Instant) -> String {
+    let remaining = at.saturating_duration_since(Instant::now());
+    format!("in {}s", remaining.as_secs_f64().ceil() as u64)
+}
+
+/// Renders the troubleshooting panel shown in place of the position table
+/// while disconnected.
+fn render_connection_banner(frame: &mut Frame, app: &App, reason: &str, area: Rect) {
+    let t = &app.theme;
+    let host = &app.config.connection.host;
+    let port = app.config.connection.port;
+    let class = app.connection_error_class.unwrap_or(ErrorClass::Other);
+    let label_style = Style::default().fg(t.text_dim);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Endpoint: ", label_style),
+            Span::styled(
+                format!("https://{}:{}", host, port),
+                Style::default().bold(),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  Error:    ", label_style),
+            Span::styled(class.label(), Style::default().fg(t.error).bold()),
+            Span::styled(format!(" — {}", reason), Style::default().fg(t.text)),
+        ]),
+        Line::from(vec![
+            Span::styled("  Retry:    ", label_style),
+            match &app.connection {
+                ConnectionState::Reconnecting { attempt, .. } => Span::styled(
+                    format!("attempt {} under way...", attempt),
+                    Style::default().fg(t.warning),
+                ),
+                ConnectionState::Disconnected {
+                    next_attempt: Some((attempt, at)),
+                    ..
+                } => Span::styled(
+                    format!(
+                        "attempt {} {} — press R to retry now",
+                        attempt,
+                        countdown(*at)
+                    ),
+                    Style::default().fg(t.text),
+                ),
+                _ => Span::styled("press R to retry now", Style::default().fg(t.text)),
+            },
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Next steps:",
+            Style::default().fg(t.text_title).bold(),
+        )),
+    ];
+    for (i, step) in class.troubleshooting_steps(host, port).iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {}. ", i + 1), label_style),
+            Span::styled(step.clone(), Style::default().fg(t.text)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("  [d] ", Style::default().fg(t.key_hint).bold()),
+        Span::styled("Run checks   ", label_style),
+        Span::styled("[R] ", Style::default().fg(t.key_hint).bold()),
+        Span::styled("Retry now", label_style),
+    ]));
+
+    let banner = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(" Not Connected ")
+            .title_style(Style::default().fg(t.error).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.error)),
+    );
+
+    frame.render_widget(banner, area);
+}
+
 struct RowData {
     idx: usize,
+    host: String,
     position: String,
     device: String,
     flow_cell: String,
@@ -188,17 +565,50 @@ struct RowData {
     reads: String,
     bases: String,
     throughput: String,
+    throughput_trend: String,
+    pore_trend: String,
+    pass_rate: String,
+    elapsed: String,
+    eta: String,
     error: String,
+    alerted: bool,
 }
 
-fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
+impl RowData {
+    /// Cell values in column order. The host column is only shown when
+    /// several hosts are watched.
+    fn cells(&self, show_host: bool) -> Vec<&str> {
+        let mut cells = vec![
+            self.state.as_str(),
+            self.device.as_str(),
+            self.position.as_str(),
+            self.flow_cell.as_str(),
+            self.run.as_str(),
+            self.reads.as_str(),
+            self.bases.as_str(),
+            self.throughput.as_str(),
+            self.throughput_trend.as_str(),
+            self.pore_trend.as_str(),
+            self.pass_rate.as_str(),
+            self.elapsed.as_str(),
+            self.eta.as_str(),
+            self.error.as_str(),
+        ];
+        if show_host {
+            cells.insert(2, self.host.as_str());
+        }
+        cells
+    }
+}
+
+fn render_position_table(frame: &mut Frame, app: &App, area: Rect, hits: &mut HitMap) {
     let t = &app.theme;
 
-    let row_data: Vec<RowData> = app
-        .positions
+    let visible = app.visible_positions();
+    let row_data: Vec<RowData> = visible
         .iter()
-        .enumerate()
-        .map(|(idx, pos)| {
+        .map(|&idx| {
+            let pos = &app.positions[idx];
             let run_state = app.run_states.get(&pos.name);
             let is_active = run_state.map(|s| s.is_active()).unwrap_or(false);
 
@@ -241,6 +651,20 @@ fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
                 "--".to_string()
             };
 
+            let (throughput_trend, pore_trend) = if is_active {
+                (
+                    trend_sparkline(app.throughput_history.get(&pos.name), TREND_WIDTH),
+                    trend_sparkline(app.pore_history.get(&pos.name), TREND_WIDTH),
+                )
+            } else {
+                ("--".to_string(), "--".to_string())
+            };
+
+            let pass_rate = stats
+                .filter(|s| is_active && s.reads_passed + s.reads_failed > 0)
+                .map(|s| format!("{:.1}%", s.pass_rate()))
+                .unwrap_or_else(|| "--".to_string());
+
             let run_label = if is_active {
                 app.run_info
                     .get(&pos.name)
@@ -250,30 +674,69 @@ fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
                 "--".to_string()
             };
 
+            // In clock mode these are when the run started and is expected
+            // to end.
+            let tz = &app.config.tui.timezone;
+            let elapsed = app
+                .run_elapsed(&pos.name)
+                .filter(|_| is_active)
+                .map(|elapsed| match app.clock_at(&pos.name, 0.0) {
+                    Some(start) if app.clock_time => tz.format(start, "%a %H:%M"),
+                    _ => format_duration(elapsed),
+                })
+                .unwrap_or_else(|| "--".to_string());
+            let eta = app
+                .run_remaining(&pos.name)
+                .filter(|_| is_active)
+                .map(|remaining| {
+                    if app.clock_time {
+                        tz.format(chrono::Utc::now() + remaining, "%a %H:%M")
+                    } else {
+                        format_duration(remaining)
+                    }
+                })
+                .unwrap_or_else(|| "--".to_string());
+
             let device = pos.device_type.label().to_string();
 
             let flow_cell = app
                 .flow_cell_info
                 .get(&pos.name)
-                .map(|fc| {
-                    if fc.has_flow_cell {
-                        fc.flow_cell_id.as_deref().unwrap_or("✓")
-                    } else {
-                        "✗"
-                    }
+                .map(|fc| match fc.label() {
+                    Some(label) => label,
+                    None if fc.has_flow_cell => "✓".to_string(),
+                    None => "✗".to_string(),
                 })
-                .unwrap_or("--")
-                .to_string();
+                .unwrap_or_else(|| "--".to_string());
 
+            let alerts = app.position_alerts(&pos.name);
             let error = match run_state {
                 Some(RunState::Error(msg)) => msg.clone(),
                 _ if pos.state == PositionState::Error => "Hardware error".to_string(),
-                _ => String::new(),
+                _ if !alerts.is_empty() => match alerts.len() {
+                    1 => format!("⚠ {}", alerts[0].summary()),
+                    n => format!("⚠ {} (+{})", alerts[0].summary(), n - 1),
+                },
+                _ => app
+                    .low_disk_space(&pos.name)
+                    .map(|disk| format!("⚠ Low disk ({:.0}% free)", disk.free_percent()))
+                    .unwrap_or_default(),
+            };
+
+            // A position on a host that went away keeps its last values.
+            let host = match app.host_connection(pos) {
+                Some(ConnectionState::Connected) | None => pos.host.clone().unwrap_or_default(),
+                Some(_) => format!("○ {}", pos.host.as_deref().unwrap_or_default()),
             };
 
             RowData {
                 idx,
-                position: pos.name.clone(),
+                host,
+                position: if app.is_pinned(&pos.name) {
+                    format!("★ {}", pos.local_name())
+                } else {
+                    pos.local_name().to_string()
+                },
                 device,
                 flow_cell,
                 state: state_indicator.to_string(),
@@ -281,7 +744,13 @@ fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
                 reads,
                 bases,
                 throughput,
+                throughput_trend,
+                pore_trend,
+                pass_rate,
+                elapsed,
+                eta,
                 error,
+                alerted: !alerts.is_empty(),
             }
         })
         .collect();
@@ -289,10 +758,18 @@ fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
     const PADDING: u16 = 2;
     let col_width = |header: &str, values: &[&str]| -> u16 {
         let max_content = values.iter().map(|s| s.chars().count()).max().unwrap_or(0);
-        max_content.max(header.len()) as u16 + PADDING
+        max_content.max(header.chars().count()) as u16 + PADDING
     };
 
-    let headers = [
+    let show_host = !app.hosts.is_empty();
+    let sort_header = match app.overview_sort {
+        OverviewSort::State => "State",
+        OverviewSort::Position => "Position",
+        OverviewSort::Yield => "Bases",
+        OverviewSort::Throughput => "Throughput",
+        OverviewSort::PassRate => "Pass",
+    };
+    let mut columns = vec![
         "State",
         "Device",
         "Position",
@@ -301,111 +778,394 @@ fn render_position_table(frame: &mut Frame, app: &App, area: Rect) {
         "Reads",
         "Bases",
         "Throughput",
+        "Trend",
+        "Pores",
+        "Pass",
+        if app.clock_time { "Started" } else { "Elapsed" },
+        if app.clock_time { "Ends" } else { "ETA" },
         "",
     ];
+    if show_host {
+        columns.insert(2, "Host");
+    }
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|&h| {
+            if h == sort_header {
+                format!("{} {}", h, sort_indicator(app))
+            } else {
+                h.to_string()
+            }
+        })
+        .collect();
 
-    let widths: Vec<Constraint> = headers
+    let cells: Vec<Vec<&str>> = row_data.iter().map(|r| r.cells(show_host)).collect();
+    let widths: Vec<u16> = headers
         .iter()
         .enumerate()
-        .map(|(i, &h)| {
-            let values: Vec<&str> = row_data
-                .iter()
-                .map(|r| match i {
-                    0 => r.state.as_str(),
-                    1 => r.device.as_str(),
-                    2 => r.position.as_str(),
-                    3 => r.flow_cell.as_str(),
-                    4 => r.run.as_str(),
-                    5 => r.reads.as_str(),
-                    6 => r.bases.as_str(),
-                    7 => r.throughput.as_str(),
-                    8 => r.error.as_str(),
-                    _ => "",
-                })
-                .collect();
-            Constraint::Length(col_width(h, &values))
+        .map(|(i, h)| {
+            let values: Vec<&str> = cells.iter().map(|row| row[i]).collect();
+            col_width(h, &values)
+        })
+        .collect();
+    let shown: Vec<usize> =
+        fit_columns(&columns, &widths, sort_header, area.width.saturating_sub(2))
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, shown)| shown.then_some(i))
+            .collect();
+    let headers: Vec<&str> = shown.iter().map(|&i| headers[i].as_str()).collect();
+    let stat_values: Vec<Option<StatValue>> = shown
+        .iter()
+        .map(|&i| match columns[i] {
+            "Reads" => Some(StatValue::Reads),
+            "Bases" => Some(StatValue::Bases),
+            "Throughput" => Some(StatValue::Throughput),
+            "Pass" => Some(StatValue::PassRate),
+            _ => None,
         })
         .collect();
+    let cells: Vec<Vec<&str>> = cells
+        .iter()
+        .map(|row| shown.iter().map(|&i| row[i]).collect())
+        .collect();
+    let mut widths: Vec<Constraint> = shown
+        .iter()
+        .map(|&i| Constraint::Length(widths[i]))
+        .collect();
+    // The last column holds errors and alerts, cut to the room left.
+    if let Some(last) = widths.last_mut() {
+        *last = Constraint::Fill(1);
+    }
 
-    let header = Row::new(headers.to_vec())
+    let header = Row::new(headers)
         .style(Style::default().bold())
         .bottom_margin(1);
 
     let rows: Vec<Row> = row_data
-        .into_iter()
-        .map(|r| {
-            let style = if r.idx == app.selected_position {
+        .iter()
+        .zip(cells)
+        .enumerate()
+        .map(|(row, (r, cells))| {
+            let style = if Some(r.idx) == app.selected_index() {
                 Style::default().bg(t.selection_bg).fg(t.selection_fg)
+            } else if r.alerted {
+                t.row_style(row).fg(t.warning)
             } else {
-                Style::default()
+                t.row_style(row)
             };
 
-            Row::new(vec![
-                r.state,
-                r.device,
-                r.position,
-                r.flow_cell,
-                r.run,
-                r.reads,
-                r.bases,
-                r.throughput,
-                r.error,
-            ])
-            .style(style)
-            .height(1)
+            let name = &app.positions[r.idx].name;
+            let cells = cells.into_iter().zip(&stat_values).map(|(text, value)| {
+                let cell = Cell::from(text);
+                match value {
+                    Some(value) if app.stat_changed(name, *value) => cell.style(t.changed()),
+                    _ => cell,
+                }
+            });
+            Row::new(cells).style(style).height(1)
         })
         .collect();
 
+    let hidden = app.positions.iter().filter(|p| app.is_hidden(p)).count();
+    let title = if app.position_filter.is_empty() {
+        match hidden {
+            0 => " Positions ".to_string(),
+            n => format!(" Positions ({} hidden) ", n),
+        }
+    } else {
+        format!(
+            " Positions matching \"{}\" ({}/{}) ",
+            app.position_filter,
+            visible.len(),
+            app.positions.len()
+        )
+    };
+    let focused = matches!(app.overlay, Overlay::None | Overlay::Filter);
+
     let table = Table::new(rows, widths)
         .header(header)
         .block(
             Block::default()
-                .title(" Positions ")
+                .title(title)
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(t.border)),
+                .border_style(t.pane_border(focused)),
         )
         .row_highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
     frame.render_widget(table, area);
+    add_row_hits(hits, area, visible.len());
 }
 
-fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let t = &app.theme;
-    let hints = "[↑↓] Navigate  [Enter] Details  [q] Quit  [?] Help";
+/// Overview columns in the order they are left out when the table is too
+/// narrow for all of them. The others are always shown.
+const DROPPABLE_COLUMNS: &[&str] = &[
+    "Pores",
+    "Trend",
+    "Run",
+    "FlowCell",
+    "Elapsed",
+    "Started",
+    "Throughput",
+    "Host",
+    "Device",
+    "Reads",
+    "ETA",
+    "Ends",
+];
+
+/// Which of `columns` fit in `available` cells, leaving out droppable
+/// columns other than the `sorted` one until the rest fit. The last column
+/// counts only its minimum width, since it is cut to the room left.
+fn fit_columns(columns: &[&str], widths: &[u16], sorted: &str, available: u16) -> Vec<bool> {
+    let mut shown = vec![true; columns.len()];
+    let total = |shown: &[bool]| -> u16 {
+        let last = widths.len().saturating_sub(1);
+        let visible: Vec<u16> = widths
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| shown[i])
+            .map(|(i, &width)| if i == last { width.min(2) } else { width })
+            .collect();
+        // Columns are one cell apart.
+        visible.iter().sum::<u16>() + visible.len().saturating_sub(1) as u16
+    };
+    for &name in DROPPABLE_COLUMNS {
+        if total(&shown) <= available {
+            break;
+        }
+        if name == sorted {
+            continue;
+        }
+        if let Some(i) = columns.iter().position(|&column| column == name) {
+            shown[i] = false;
+        }
+    }
+    shown
+}
+
+/// Records one clickable region per visible row of a bordered table whose
+/// header has a one-line bottom margin.
+fn add_row_hits(hits: &mut HitMap, area: Rect, rows: usize) {
+    let inner = area.inner(ratatui::layout::Margin::new(1, 1));
+    let first = inner.y + 2;
+    for (idx, y) in (first..inner.bottom()).take(rows).enumerate() {
+        hits.add(Rect::new(inner.x, y, inner.width, 1), MouseTarget::Row(idx));
+    }
+}
+
+/// Renders a bordered footer of centered key hints. Hints with an action
+/// can be clicked.
+fn render_hint_bar(
+    frame: &mut Frame,
+    t: &Theme,
+    hints: &[(&str, Option<Action>)],
+    area: Rect,
+    hits: &mut HitMap,
+) {
+    const GAP: &str = "  ";
+    let line = Line::from(
+        hints
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (hint, _))| {
+                let gap = (i > 0).then(|| Span::raw(GAP));
+                gap.into_iter().chain([Span::raw(*hint)])
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    let inner = area.inner(ratatui::layout::Margin::new(1, 1));
+    let mut x = inner.x + inner.width.saturating_sub(line.width() as u16) / 2;
+    for (hint, action) in hints {
+        let width = Span::raw(*hint).width() as u16;
+        if let Some(action) = action {
+            let hint_area = Rect::new(x, inner.y, width, 1).intersection(inner);
+            hits.add(hint_area, MouseTarget::Action(*action));
+        }
+        x = x.saturating_add(width + GAP.len() as u16);
+    }
 
-    let footer = Paragraph::new(hints)
+    let footer = Paragraph::new(line)
         .style(Style::default().fg(t.text_dim))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
-
     frame.render_widget(footer, area);
 }
 
-fn render_detail_footer(frame: &mut Frame, app: &App, area: Rect) {
-    let t = &app.theme;
-    let chart_hints = match app.detail_chart {
-        DetailChart::Yield => "[t] Reads/Bases  ".to_string(),
+/// Arrow for the sorted column; metric columns list the highest first
+/// unless reversed.
+fn sort_indicator(app: &App) -> &'static str {
+    let ascending = matches!(
+        app.overview_sort,
+        OverviewSort::Position | OverviewSort::State
+    );
+    if ascending != app.overview_sort_reversed {
+        "▲"
+    } else {
+        "▼"
+    }
+}
+
+fn render_footer(frame: &mut Frame, app: &App, area: Rect, hits: &mut HitMap) {
+    let sort = format!(
+        "[S] Sort ({} {})",
+        app.overview_sort.label(),
+        sort_indicator(app)
+    );
+    let mut hints = vec![
+        ("[↑↓] Navigate", None),
+        ("[Enter] Details", Some(Action::Enter)),
+        ("[/] Filter", Some(Action::Filter)),
+        (sort.as_str(), Some(Action::CycleOverviewSort)),
+        ("[n] New Run", Some(Action::StartProtocol)),
+        ("[H] History", Some(Action::RunHistory)),
+        ("[q] Quit", Some(Action::Quit)),
+        ("[?] Help", Some(Action::Help)),
+    ];
+    if app.read_only.is_some() {
+        hints.retain(|&(_, action)| action != Some(Action::StartProtocol));
+    }
+    render_hint_bar(frame, &app.theme, &hints, area, hits);
+}
+
+fn render_detail_footer(frame: &mut Frame, app: &App, area: Rect, hits: &mut HitMap) {
+    let range = app
+        .histogram_range
+        .map(|(min, max)| format!("({}-{} bp) [0] Clear", min, max));
+    let sort = format!("[b] Sort ({})", app.barcode_sort.label());
+    let window = format!("[v] Window ({})", app.yield_window.label());
+
+    let mut hints = vec![
+        ("[Esc] Back", Some(Action::Back)),
+        ("[1-9|Tab] Charts", Some(Action::CycleChart)),
+        ("[[ ]] Position", Some(Action::NextPosition)),
+    ];
+    match app.detail_chart {
+        DetailChart::Yield | DetailChart::QScore if app.chart_cursor.is_some() => {
+            hints.push(("[←→] Move", None));
+            hints.push(("[c] Done", Some(Action::Inspect)));
+        }
+        DetailChart::Yield => {
+            hints.push(("[t] Reads/Bases", Some(Action::ToggleYieldUnit)));
+            hints.push((&window, Some(Action::CycleYieldWindow)));
+            hints.push(("[a] Dual Axis", Some(Action::ToggleDualAxis)));
+            hints.push(("[g] Rate", Some(Action::ToggleYieldRates)));
+            hints.push(("[e] Projection", Some(Action::ToggleProjection)));
+            hints.push(("[c] Inspect", Some(Action::Inspect)));
+        }
         DetailChart::ReadLength => {
-            let range_status = match app.histogram_range {
-                Some((min, max)) => format!(" ({}-{} bp) [0] Clear", min, max),
-                None => String::new(),
-            };
-            format!("[o] Outliers  [z] Set Range{}  ", range_status)
+            hints.push(("[o] Outliers", Some(Action::ToggleOutliers)));
+            hints.push(("[w] Reads/Bases", Some(Action::ToggleHistogramWeight)));
+            hints.push(("[z] Set Range", Some(Action::HistogramSetRange)));
+            if let Some(range) = &range {
+                hints.push((range, Some(Action::HistogramResetRange)));
+            }
         }
-        DetailChart::PoreActivity => String::new(),
-    };
+        DetailChart::PoreActivity if app.channel_cursor.is_some() => {
+            hints.push(("[←↑↓→] Move", None));
+            hints.push(("[c] Done", Some(Action::Inspect)));
+        }
+        DetailChart::PoreActivity => {
+            hints.push(("[c] Inspect Channels", Some(Action::Inspect)));
+            hints.push(("[, .] Time-lapse", Some(Action::TimelapseBack)));
+            hints.push(("[O] Occupancy", Some(Action::ToggleOccupancyColors)));
+        }
+        DetailChart::Barcodes => {
+            hints.push((&sort, Some(Action::CycleBarcodeSort)));
+            hints.push(("[t] Reads/Bases", Some(Action::ToggleYieldUnit)));
+        }
+        DetailChart::Signal => hints.push(("[←→] Channel", None)),
+        DetailChart::QScore => hints.push(("[c] Inspect", Some(Action::Inspect))),
+        DetailChart::PoreScans | DetailChart::ActivePores | DetailChart::DutyTime => {}
+    }
+    let run_active = app
+        .selected_position()
+        .and_then(|p| app.get_run_state(&p.name))
+        .is_some_and(|state| state.is_active());
+    if run_active && app.read_only.is_none() {
+        hints.push(("[u] Run Until", Some(Action::RunUntil)));
+    }
+    hints.push(("[x] Export", Some(Action::ExportChart)));
+    hints.push(("[H] History", Some(Action::RunHistory)));
+    hints.push(("[?] Help", Some(Action::Help)));
 
-    let hints = format!("[Esc] Back  [1/2/3|Tab] Charts  {}[?] Help", chart_hints);
+    render_hint_bar(frame, &app.theme, &hints, area, hits);
+}
 
-    let footer = Paragraph::new(hints)
-        .style(Style::default().fg(t.text_dim))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL));
+/// Draws clickable chart tabs into the right of the chart's top border.
+fn render_chart_tabs(frame: &mut Frame, app: &App, area: Rect, hits: &mut HitMap) {
+    const TABS: [(DetailChart, &str, Action); 9] = [
+        (DetailChart::Yield, "1 Yield", Action::ChartYield),
+        (DetailChart::ReadLength, "2 Length", Action::ChartReadLength),
+        (
+            DetailChart::PoreActivity,
+            "3 Pores",
+            Action::ChartPoreActivity,
+        ),
+        (DetailChart::Barcodes, "4 Barcodes", Action::ChartBarcodes),
+        (DetailChart::Signal, "5 Signal", Action::ChartSignal),
+        (DetailChart::QScore, "6 Quality", Action::ChartQScore),
+        (DetailChart::PoreScans, "7 Scans", Action::ChartPoreScans),
+        (
+            DetailChart::ActivePores,
+            "8 Active",
+            Action::ChartActivePores,
+        ),
+        (DetailChart::DutyTime, "9 Duty", Action::ChartDutyTime),
+    ];
+    const SEPARATOR: &str = " │ ";
+    // Leave room for the chart's own title on the left.
+    const TITLE_ROOM: u16 = 24;
 
-    frame.render_widget(footer, area);
+    let t = &app.theme;
+    let tabs_width = |compact: bool| {
+        TABS.iter()
+            .map(|(_, label, _)| if compact { 1 } else { label.len() as u16 })
+            .sum::<u16>()
+            + SEPARATOR.chars().count() as u16 * (TABS.len() as u16 - 1)
+            + 2
+    };
+    // Narrow terminals get the chart numbers only.
+    let compact = area.width < tabs_width(false) + TITLE_ROOM;
+    let width = tabs_width(compact);
+    if area.width < width + TITLE_ROOM || area.height == 0 {
+        return;
+    }
+
+    let tabs_area = Rect::new(area.right() - 1 - width, area.y, width, 1);
+    let mut spans = vec![Span::raw(" ")];
+    let mut x = tabs_area.x + 1;
+    for (i, (chart, label, action)) in TABS.into_iter().enumerate() {
+        let label = if compact { &label[..1] } else { label };
+        if i > 0 {
+            spans.push(Span::styled(SEPARATOR, Style::default().fg(t.border_dim)));
+            x += SEPARATOR.chars().count() as u16;
+        }
+        let style = if app.detail_chart == chart {
+            Style::default().fg(t.text_title).bold()
+        } else {
+            Style::default().fg(t.text_dim)
+        };
+        spans.push(Span::styled(label, style));
+        hits.add(
+            Rect::new(x, area.y, label.len() as u16, 1),
+            MouseTarget::Action(action),
+        );
+        x += label.len() as u16;
+    }
+    spans.push(Span::raw(" "));
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), tabs_area);
 }
 
-fn render_position_detail(frame: &mut Frame, app: &App, position_idx: usize, area: Rect) {
+fn render_position_detail(
+    frame: &mut Frame,
+    app: &App,
+    position_idx: usize,
+    area: Rect,
+    hits: &mut HitMap,
+) {
     let t = &app.theme;
     let position = match app.positions.get(position_idx) {
         Some(p) => p,
@@ -418,25 +1178,56 @@ fn render_position_detail(frame: &mut Frame, app: &App, position_idx: usize, are
         }
     };
 
-    let stats = app.stats_cache.get(&position.name);
-
+    // On short terminals Run Info keeps only its three stat lines.
+    let compact = area.height < COMPACT_HEIGHT;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
-            Constraint::Length(5),
+            Constraint::Length(if compact { 5 } else { 9 }),
             Constraint::Min(10),
             Constraint::Length(3),
         ])
         .split(area);
 
     let run_state = app.get_run_state(&position.name);
-    render_detail_header(frame, t, position, run_state, chunks[0]);
-    let histogram = app.histograms.get(&position.name);
-    render_run_info(frame, t, position, stats, histogram, run_state, chunks[1]);
+    let latest_scan = app.latest_mux_scan(&position.name);
+    match app.disk_space.get(&position.name) {
+        Some(disk) => {
+            let header =
+                Layout::horizontal([Constraint::Min(20), Constraint::Length(34)]).split(chunks[0]);
+            render_detail_header(frame, app, position, latest_scan, header[0]);
+            render_disk_gauge(
+                frame,
+                t,
+                disk,
+                app.config.tui.disk_warning_percent,
+                header[1],
+            );
+        }
+        None => render_detail_header(frame, app, position, latest_scan, chunks[0]),
+    }
+    if !run_state.is_some_and(|state| state.is_active()) {
+        render_last_run(frame, app, &position.name, chunks[1], chunks[2], hits);
+        render_detail_footer(frame, app, chunks[3], hits);
+        return;
+    }
+    render_run_info(frame, app, &position.name, chunks[1], compact);
+
+    let has_targets = app
+        .run_until_targets
+        .get(&position.name)
+        .is_some_and(|targets| !targets.is_empty());
+    let chart_area = if has_targets {
+        let rows = Layout::vertical([Constraint::Length(3), Constraint::Min(7)]).split(chunks[2]);
+        render_run_until_gauge(frame, app, &position.name, rows[0]);
+        rows[1]
+    } else {
+        chunks[2]
+    };
 
     match app.detail_chart {
-        DetailChart::Yield => render_yield_chart(frame, app, &position.name, chunks[2]),
+        DetailChart::Yield => render_yield_chart(frame, app, &position.name, chart_area),
         DetailChart::ReadLength => {
             let histogram = app.histograms.get(&position.name);
             render_histogram_chart(
@@ -444,34 +1235,173 @@ fn render_position_detail(frame: &mut Frame, app: &App, position_idx: usize, are
                 t,
                 histogram,
                 app.exclude_outliers,
+                app.histogram_by_bases,
                 app.histogram_range,
-                chunks[2],
+                chart_area,
             );
         }
         DetailChart::PoreActivity => {
-            let channel_states = app.channel_states.get(&position.name);
-            let channel_layout = app.channel_layouts.get(&position.name);
-            render_pore_activity(
-                frame,
-                t,
-                channel_states,
-                channel_layout,
-                chunks[2],
-                app.channel_map_scroll_offset,
-            );
+            render_pore_activity(frame, app, &position.name, chart_area, hits);
         }
+        DetailChart::Barcodes => render_barcode_panel(frame, app, &position.name, chart_area),
+        DetailChart::Signal => render_signal_chart(frame, app, &position.name, chart_area),
+        DetailChart::QScore => render_qscore_chart(frame, app, &position.name, chart_area),
+        DetailChart::PoreScans => render_pore_scan_chart(frame, app, &position.name, chart_area),
+        DetailChart::ActivePores => {
+            render_active_pores_chart(frame, app, &position.name, chart_area)
+        }
+        DetailChart::DutyTime => render_duty_time_chart(frame, app, &position.name, chart_area),
     }
+    render_chart_tabs(frame, app, chart_area, hits);
 
-    render_detail_footer(frame, app, chunks[3]);
+    render_detail_footer(frame, app, chunks[3], hits);
 }
 
-fn render_detail_header(
+/// Summary and charts of an idle position's last finished run, fetched once
+/// when the detail view opens. Only yield and read length are kept for
+/// finished runs.
+fn render_last_run(
     frame: &mut Frame,
-    t: &Theme,
-    position: &Position,
-    run_state: Option<&RunState>,
+    app: &App,
+    position_name: &str,
+    info_area: Rect,
+    chart_area: Rect,
+    hits: &mut HitMap,
+) {
+    let t = &app.theme;
+    let run = match app.last_runs.get(position_name) {
+        Some(Some(run)) => run,
+        loading => {
+            let message = match loading {
+                None => "Loading last run...",
+                Some(_) => "No finished runs on this position",
+            };
+            let placeholder = Paragraph::new(message)
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .title(" Last Run ")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(t.border)),
+                );
+            frame.render_widget(placeholder, info_area);
+            return;
+        }
+    };
+
+    let summary = &run.summary;
+    let tz = &app.config.tui.timezone;
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "--".to_string());
+    let end_color = match summary.end_reason.as_str() {
+        "Completed" => t.success,
+        "Stopped by user" => t.warning,
+        _ => t.error,
+    };
+    let label = Style::default().fg(t.text_dim);
+    let value = Style::default().bold().fg(t.text_title);
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(run_label(summary), value),
+            Span::styled(format!("  {}", summary.protocol_id), label),
+        ]),
+        Line::from(vec![
+            Span::styled("Started: ", label),
+            Span::styled(
+                or_dash(summary.info.start_time.map(|ts| tz.format_display(ts))),
+                Style::default().fg(t.text),
+            ),
+            Span::styled("  Ended: ", label),
+            Span::styled(
+                or_dash(summary.info.end_time.map(|ts| tz.format_display(ts))),
+                Style::default().fg(t.text),
+            ),
+            Span::styled("  Duration: ", label),
+            Span::styled(
+                or_dash(summary.duration().map(format_duration)),
+                Style::default().fg(t.text),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Reads: ", label),
+            Span::styled(format_number(summary.reads), value),
+        ]),
+        Line::from(vec![
+            Span::styled("Bases: ", label),
+            Span::styled(format_bytes(summary.total_bases()), value),
+            Span::raw("  "),
+            Span::styled(
+                format_bytes(summary.bases_passed),
+                Style::default().fg(t.chart_passed),
+            ),
+            Span::styled(" passed  ", label),
+            Span::styled(
+                format_bytes(summary.bases_failed),
+                Style::default().fg(t.chart_failed),
+            ),
+            Span::styled(" failed", label),
+        ]),
+        Line::from(vec![
+            Span::styled("N50: ", label),
+            Span::styled(
+                or_dash(summary.n50.map(|n50| format!("{} bp", format_number(n50)))),
+                value,
+            ),
+        ]),
+    ];
+    let info = Paragraph::new(lines).block(
+        Block::default()
+            .title(Line::from(vec![
+                Span::raw(" Last Run ── "),
+                Span::styled(
+                    summary.end_reason.clone(),
+                    Style::default().fg(end_color).bold(),
+                ),
+                Span::raw(" "),
+            ]))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border)),
+    );
+    frame.render_widget(info, info_area);
+
+    match app.detail_chart {
+        DetailChart::Yield => render_yield_chart(frame, app, position_name, chart_area),
+        DetailChart::ReadLength => render_histogram_chart(
+            frame,
+            t,
+            run.histogram.as_ref(),
+            false,
+            false,
+            None,
+            chart_area,
+        ),
+        _ => {
+            let placeholder = Paragraph::new("Not kept for finished runs")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(t.border)),
+                );
+            frame.render_widget(placeholder, chart_area);
+        }
+    }
+    render_chart_tabs(frame, app, chart_area, hits);
+}
+
+/// Position name, run state, flow cell and the position's note.
+fn render_detail_header(
+    frame: &mut Frame,
+    app: &App,
+    position: &Position,
+    latest_scan: Option<&MuxScanResult>,
     area: Rect,
 ) {
+    let t = &app.theme;
+    let run_state = app.get_run_state(&position.name);
+    let flow_cell = app.flow_cell_info.get(&position.name);
+    let note = app.notes.get(&position.name);
     let (state_color, state_indicator) = match run_state {
         Some(RunState::Running) => (t.success, "● Running"),
         Some(RunState::MuxScanning) => (t.special, "◉ Pore Scan"),
@@ -485,7 +1415,7 @@ fn render_detail_header(
         },
     };
 
-    let title = Line::from(vec![
+    let mut title = Line::from(vec![
         Span::styled(
             format!(" {} ", position.name),
             Style::default().bold().fg(t.text_title),
@@ -493,6 +1423,31 @@ fn render_detail_header(
         Span::raw("── "),
         Span::styled(state_indicator, Style::default().fg(state_color).bold()),
     ]);
+    if let Some(fc) = flow_cell {
+        title.push_span(Span::raw(" ── "));
+        title.push_span(match fc.label() {
+            Some(label) => Span::styled(label, Style::default().fg(t.text)),
+            None if fc.has_flow_cell => Span::styled("Flow cell", Style::default().fg(t.text)),
+            None => Span::styled("No flow cell", Style::default().fg(t.text_dim)),
+        });
+    }
+    if let Some(scan) = latest_scan {
+        title.push_span(Span::raw(" ── "));
+        title.push_span(Span::styled(
+            format!(
+                "{} pores at last scan",
+                format_number(scan.available_pores() as u64)
+            ),
+            Style::default().fg(t.text_dim),
+        ));
+    }
+    if let Some(note) = note {
+        title.push_span(Span::raw(" ── "));
+        title.push_span(Span::styled(
+            format!("✎ {}", note),
+            Style::default().fg(t.special),
+        ));
+    }
 
     let header = Paragraph::new(title).block(
         Block::default()
@@ -503,27 +1458,136 @@ fn render_detail_header(
     frame.render_widget(header, area);
 }
 
-fn render_run_info(
+/// Progress towards the run-until targets, filled to the target the run
+/// will meet first.
+fn render_run_until_gauge(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let targets = app
+        .run_until_targets
+        .get(position_name)
+        .copied()
+        .unwrap_or_default();
+    let reached = app.run_until_reached(position_name);
+    let ratio = app.run_until_fraction(position_name).unwrap_or(0.0);
+
+    let duration = |seconds: u64| format_duration(chrono::Duration::seconds(seconds as i64));
+    let mut parts = Vec::new();
+    if let Some(target) = targets.runtime {
+        parts.push(format!(
+            "{} / {}",
+            duration(reached.runtime.unwrap_or(0)),
+            duration(target)
+        ));
+    }
+    if let Some(target) = targets.estimated_bases {
+        parts.push(format!(
+            "{} / {}",
+            format_bytes(reached.estimated_bases.unwrap_or(0)),
+            format_bytes(target)
+        ));
+    }
+    if let Some(target) = targets.passed_basecalled_bases {
+        parts.push(format!(
+            "{} / {} passed",
+            format_bytes(reached.passed_basecalled_bases.unwrap_or(0)),
+            format_bytes(target)
+        ));
+    }
+    if let Some(target) = targets.available_pores {
+        match reached.available_pores {
+            Some(pores) => parts.push(format!("pores {:.0}% (stop < {:.0}%)", pores, target)),
+            None => parts.push(format!("pores < {:.0}%", target)),
+        }
+    }
+
+    let color = if ratio >= 1.0 {
+        t.success
+    } else {
+        t.chart_line
+    };
+    let gauge = LineGauge::default()
+        .block(
+            Block::default()
+                .title(format!(" Run Until {:.0}% ", ratio * 100.0))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .filled_style(Style::default().fg(color))
+        .unfilled_style(Style::default().fg(t.text_dim))
+        .label(Span::styled(parts.join(" · "), Style::default().fg(t.text)))
+        .ratio(ratio);
+
+    frame.render_widget(gauge, area);
+}
+
+/// Disk usage bar for the detail header, coloured by how close it is to full.
+fn render_disk_gauge(
     frame: &mut Frame,
     t: &Theme,
-    _position: &Position,
-    stats: Option<&StatsSnapshot>,
-    histogram: Option<&ReadLengthHistogram>,
-    run_state: Option<&RunState>,
+    disk: &DiskSpaceInfo,
+    warning_percent: f64,
     area: Rect,
 ) {
+    let free = disk.free_percent();
+    let color = if disk.recommend_stop || disk.bytes_available <= disk.bytes_to_stop_cleanly {
+        t.error
+    } else if disk.is_low(warning_percent) {
+        t.warning
+    } else {
+        t.success
+    };
+
+    let mut label = format!("{} free", format_storage(disk.bytes_available));
+    if let Some(remaining) = disk.time_until_full() {
+        label.push_str(&format!(" · {}h left", remaining.as_secs() / 3600));
+    }
+
+    let gauge = LineGauge::default()
+        .block(
+            Block::default()
+                .title(format!(" Disk {:.0}% free ", free))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .filled_style(Style::default().fg(color))
+        .unfilled_style(Style::default().fg(t.text_dim))
+        .label(Span::styled(label, Style::default().fg(color)))
+        .ratio((1.0 - free / 100.0).clamp(0.0, 1.0));
+
+    frame.render_widget(gauge, area);
+}
+
+/// Stats of the running position. `compact` leaves out everything but the
+/// read, base and throughput lines.
+fn render_run_info(frame: &mut Frame, app: &App, position_name: &str, area: Rect, compact: bool) {
+    let t = &app.theme;
+    let stats = app.stats_cache.get(position_name);
+    let run_state = app.get_run_state(position_name);
+    let histogram = app.histograms.get(position_name);
+    let speed = app.translocation_speed.get(position_name);
+    let run_info = app.run_info.get(position_name);
+    let timing = run_info.and_then(|info| format_run_timing(info, &app.config.tui.timezone));
+
     let n50_text = histogram
         .filter(|h| h.n50 > 0.0)
         .map(|h| format!("{} bp", format_number(h.n50 as u64)))
         .unwrap_or_else(|| "-".to_string());
 
-    let content = if let Some(s) = stats {
+    // Values that just changed take the accent style.
+    let changed = |value, style: Style| {
+        if app.stat_changed(position_name, value) {
+            style.patch(t.changed())
+        } else {
+            style
+        }
+    };
+    let mut content = if let Some(s) = stats {
         vec![
             Line::from(vec![
                 Span::styled("Reads: ", Style::default().fg(t.text_dim)),
                 Span::styled(
                     format_number(s.reads_processed),
-                    Style::default().bold().fg(t.text_title),
+                    changed(StatValue::Reads, Style::default().bold().fg(t.text_title)),
                 ),
                 Span::raw("  "),
                 Span::styled(
@@ -541,7 +1605,7 @@ fn render_run_info(
                 Span::styled("Bases: ", Style::default().fg(t.text_dim)),
                 Span::styled(
                     format_bytes(s.bases_called),
-                    Style::default().bold().fg(t.text_title),
+                    changed(StatValue::Bases, Style::default().bold().fg(t.text_title)),
                 ),
                 Span::raw("  "),
                 Span::styled(
@@ -562,11 +1626,14 @@ fn render_run_info(
                         Some(RunState::Running) => format_throughput_gbph(s.throughput_gbph),
                         _ => "--".to_string(),
                     },
-                    Style::default().bold(),
+                    changed(StatValue::Throughput, Style::default().bold()),
                 ),
                 Span::raw("    "),
                 Span::styled("Pass Rate: ", Style::default().fg(t.text_dim)),
-                Span::styled(format!("{:.1}%", s.pass_rate()), Style::default().bold()),
+                Span::styled(
+                    format!("{:.1}%", s.pass_rate()),
+                    changed(StatValue::PassRate, Style::default().bold()),
+                ),
                 Span::raw("    "),
                 Span::styled("Active Pores: ", Style::default().fg(t.text_dim)),
                 Span::styled(
@@ -574,7 +1641,7 @@ fn render_run_info(
                         Some(RunState::Running) => format_number(s.active_pores as u64),
                         _ => "--".to_string(),
                     },
-                    Style::default().bold(),
+                    changed(StatValue::ActivePores, Style::default().bold()),
                 ),
                 Span::raw("    "),
                 Span::styled("N50: ", Style::default().fg(t.text_dim)),
@@ -584,153 +1651,1406 @@ fn render_run_info(
     } else {
         vec![Line::from("No data available")]
     };
+    let mut header = Vec::new();
+    if let Some(info) = run_info.filter(|_| !compact) {
+        header.push(run_metadata_line(t, info));
+    }
+    if let Some(output) = app.output.get(position_name).filter(|_| !compact) {
+        header.push(output_line(t, output));
+    }
+    content.splice(0..0, header);
+    let speed = speed.filter(|_| !compact);
+    if let Some((points, latest)) = speed.and_then(|points| Some((points, points.last()?))) {
+        let slow = latest.median < SLOW_TRANSLOCATION_SPEED;
+        let color = if slow { t.warning } else { t.text };
+        let mut line = Line::from(vec![
+            Span::styled("Speed: ", Style::default().fg(t.text_dim)),
+            Span::styled(
+                format!("{:.0} b/s", latest.median),
+                Style::default().bold().fg(color),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                speed_sparkline(points, 24),
+                Style::default().fg(t.chart_line),
+            ),
+        ]);
+        if slow {
+            line.push_span(Span::styled(
+                "  slow, consider a nuclease flush",
+                Style::default().fg(t.warning),
+            ));
+        }
+        content.push(line);
+    }
+    if let (Some(RunState::Running), false) = (run_state, compact) {
+        let project = |model| app.yield_projection(position_name, YieldUnit::Bases, model);
+        if let (Some(linear), decay) = (
+            project(ProjectionModel::Linear),
+            project(ProjectionModel::Decay),
+        ) {
+            content.push(Line::from(vec![
+                Span::styled("Projected: ", Style::default().fg(t.text_dim)),
+                Span::styled(format_bytes(linear.final_total), Style::default().bold()),
+                Span::styled(" linear  ", Style::default().fg(t.text_dim)),
+                Span::styled(
+                    decay
+                        .map(|d| format_bytes(d.final_total))
+                        .unwrap_or_else(|| "--".to_string()),
+                    Style::default().bold(),
+                ),
+                Span::styled(" decay    ", Style::default().fg(t.text_dim)),
+                Span::styled("Ends in: ", Style::default().fg(t.text_dim)),
+                Span::styled(
+                    format_duration(chrono::Duration::seconds(linear.remaining_seconds.into())),
+                    Style::default().bold(),
+                ),
+            ]));
+        }
+    }
 
-    let info = Paragraph::new(content).block(
-        Block::default()
-            .title(" Run Info ")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(t.border)),
-    );
+    let mut block = Block::default()
+        .title(" Run Info ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.border));
+    if let Some(timing) = timing {
+        block = block.title(
+            Line::from(Span::styled(
+                format!(" {} ", timing),
+                Style::default().fg(t.text_dim),
+            ))
+            .right_aligned(),
+        );
+    }
+
+    let info = Paragraph::new(content).block(block);
 
     frame.render_widget(info, area);
 }
 
-fn render_yield_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
-    let t = &app.theme;
-    let yield_data = app.yield_history.get(position_name);
+/// Block characters tracing the median speed of the latest `width`
+/// stretches, scaled between their lowest and highest values.
+fn speed_sparkline(points: &[SpeedDataPoint], width: usize) -> String {
+    let medians: Vec<f64> = points.iter().map(|p| p.median as f64).collect();
+    sparkline(&medians, width)
+}
 
-    let yield_points = match yield_data {
-        Some(points) if !points.is_empty() => points,
-        _ => {
-            let placeholder = Paragraph::new("Waiting for data...")
-                .style(Style::default().fg(t.text_dim))
-                .alignment(Alignment::Center)
-                .block(
-                    Block::default()
-                        .title(" Cumulative Yield ")
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(t.border)),
-                );
-            frame.render_widget(placeholder, area);
-            return;
+/// Block characters tracing the latest `width` values, scaled between the
+/// lowest and highest of them. A flat line sits mid-height.
+fn sparkline(values: &[f64], width: usize) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let recent = &values[values.len().saturating_sub(width)..];
+    let (min, max) = recent
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    recent
+        .iter()
+        .map(|&v| {
+            let level = if max > min {
+                ((v - min) / (max - min) * 7.0).round() as usize
+            } else {
+                3
+            };
+            LEVELS[level.min(7)]
+        })
+        .collect()
+}
+
+/// Width of the overview's trend columns.
+const TREND_WIDTH: usize = 8;
+
+/// Sparkline of a position's recent history for the overview, averaging
+/// the samples into `width` stretches of equal length so it covers the
+/// whole of `tui.chart_history`. `--` until there are two samples.
+fn trend_sparkline(history: Option<&ChartBuffer>, width: usize) -> String {
+    let values: Vec<f64> = history
+        .map(|buffer| buffer.data.iter().map(|&(_, v)| v).collect())
+        .unwrap_or_default();
+    if values.len() < 2 {
+        return "--".to_string();
+    }
+    let means: Vec<f64> = values
+        .chunks(values.len().div_ceil(width))
+        .map(|chunk| chunk.iter().sum::<f64>() / chunk.len() as f64)
+        .collect();
+    sparkline(&means, width)
+}
+
+/// Sample, experiment group, protocol and flow cell type of a run.
+fn run_metadata_line(t: &Theme, info: &RunInfo) -> Line<'static> {
+    let fields = [
+        ("Sample: ", &info.sample_id),
+        ("Group: ", &info.experiment_group),
+        ("Protocol: ", &info.protocol_name),
+        ("Flow Cell: ", &info.flow_cell_product_code),
+    ];
+    let mut spans = Vec::new();
+    for (i, (label, value)) in fields.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("    "));
         }
-    };
+        spans.push(Span::styled(label, Style::default().fg(t.text_dim)));
+        spans.push(Span::styled(
+            value.clone().unwrap_or_else(|| "--".to_string()),
+            Style::default().bold(),
+        ));
+    }
+    Line::from(spans)
+}
 
-    let min_x = yield_points
-        .first()
-        .map(|p| p.seconds as f64)
-        .unwrap_or(0.0);
-    let max_x = yield_points.last().map(|p| p.seconds as f64).unwrap_or(1.0);
+/// Data written by a run, its file formats and where it is going. Data
+/// that could not be written is flagged in the warning color.
+fn output_line(t: &Theme, output: &OutputInfo) -> Line<'static> {
+    let mut spans = vec![
+        Span::styled("Output: ", Style::default().fg(t.text_dim)),
+        Span::styled(
+            format_storage(output.bytes_written),
+            Style::default().bold(),
+        ),
+        Span::styled(" written  ", Style::default().fg(t.text_dim)),
+        Span::styled(
+            format_storage(output.bytes_pending),
+            Style::default().bold(),
+        ),
+        Span::styled(" pending", Style::default().fg(t.text_dim)),
+    ];
+    if output.bytes_failed > 0 {
+        spans.push(Span::styled(
+            format!("  {} failed", format_storage(output.bytes_failed)),
+            Style::default().fg(t.warning),
+        ));
+    }
+    if !output.formats.is_empty() {
+        spans.push(Span::styled(" · ", Style::default().fg(t.text_dim)));
+        spans.push(Span::raw(output.formats.join(", ")));
+    }
+    if let Some(dir) = &output.reads_directory {
+        spans.push(Span::styled(" · ", Style::default().fg(t.text_dim)));
+        spans.push(Span::styled(dir.clone(), Style::default().fg(t.text_title)));
+    }
+    Line::from(spans)
+}
 
-    type ValueFn = fn(&crate::client::YieldDataPoint) -> u64;
-    let (get_total, get_passed, get_failed): (ValueFn, ValueFn, ValueFn) = match app.yield_unit {
-        YieldUnit::Bases => (|p| p.bases, |p| p.bases_passed, |p| p.bases_failed),
-        YieldUnit::Reads => (|p| p.reads, |p| p.reads_passed, |p| p.reads_failed),
+/// Formats the run start/end times in the configured display time zone.
+fn format_run_timing(info: &RunInfo, tz: &DisplayTimezone) -> Option<String> {
+    let started = info.start_time?;
+    let mut label = format!("Started {}", tz.format_display(started));
+    if let Some(ended) = info.end_time {
+        label.push_str(&format!(" · Ended {}", tz.format_display(ended)));
+    }
+    Some(label)
+}
+
+fn render_run_history(
+    frame: &mut Frame,
+    app: &App,
+    position_idx: usize,
+    area: Rect,
+    hits: &mut HitMap,
+) {
+    let t = &app.theme;
+    let Some(position) = app.positions.get(position_idx) else {
+        let msg = Paragraph::new("Position not found")
+            .style(Style::default().fg(t.error))
+            .alignment(Alignment::Center);
+        frame.render_widget(msg, area);
+        return;
     };
 
-    let max_raw_value = yield_points
-        .iter()
-        .map(|p| get_total(p).max(get_passed(p)).max(get_failed(p)))
-        .max()
-        .unwrap_or(0) as f64;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(area);
 
-    let (title, scale_factor): (&str, f64) = match app.yield_unit {
-        YieldUnit::Bases => {
-            if max_raw_value >= 1_000_000_000_000.0 {
-                ("Cumulative Yield (Tb)", 1_000_000_000_000.0)
-            } else if max_raw_value >= 1_000_000_000.0 {
-                ("Cumulative Yield (Gb)", 1_000_000_000.0)
-            } else if max_raw_value >= 1_000_000.0 {
-                ("Cumulative Yield (Mb)", 1_000_000.0)
-            } else if max_raw_value >= 1_000.0 {
-                ("Cumulative Yield (Kb)", 1_000.0)
-            } else {
-                ("Cumulative Yield (b)", 1.0)
-            }
+    render_detail_header(frame, app, position, None, chunks[0]);
+
+    let block = Block::default()
+        .title(" Run History ")
+        .borders(Borders::ALL)
+        .border_style(t.pane_border(app.overlay == Overlay::None));
+
+    match app.run_history.get(&position.name) {
+        None => {
+            let msg = Paragraph::new("Loading run history...")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(msg, chunks[1]);
         }
-        YieldUnit::Reads => {
-            if max_raw_value >= 1_000_000_000.0 {
-                ("Cumulative Yield (B reads)", 1_000_000_000.0)
-            } else if max_raw_value >= 1_000_000.0 {
-                ("Cumulative Yield (M reads)", 1_000_000.0)
-            } else if max_raw_value >= 1_000.0 {
-                ("Cumulative Yield (K reads)", 1_000.0)
-            } else {
-                ("Cumulative Yield (reads)", 1.0)
-            }
+        Some(runs) if runs.is_empty() => {
+            let msg = Paragraph::new("No completed runs")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(msg, chunks[1]);
         }
-    };
+        Some(runs) => {
+            let tz = &app.config.tui.timezone;
+            let header = Row::new(vec![
+                "Started",
+                "Run",
+                "Duration",
+                "Reads",
+                "Bases",
+                "N50",
+                "End Reason",
+            ])
+            .style(Style::default().bold())
+            .bottom_margin(1);
 
-    let total_data: Vec<(f64, f64)> = yield_points
-        .iter()
-        .map(|p| (p.seconds as f64 - min_x, get_total(p) as f64 / scale_factor))
-        .collect();
-    let passed_data: Vec<(f64, f64)> = yield_points
-        .iter()
-        .map(|p| {
-            (
-                p.seconds as f64 - min_x,
-                get_passed(p) as f64 / scale_factor,
-            )
-        })
-        .collect();
-    let failed_data: Vec<(f64, f64)> = yield_points
-        .iter()
-        .map(|p| {
-            (
-                p.seconds as f64 - min_x,
-                get_failed(p) as f64 / scale_factor,
+            let rows: Vec<Row> = runs
+                .iter()
+                .enumerate()
+                .map(|(idx, run)| {
+                    let style = if idx == app.history_selected {
+                        Style::default().bg(t.selection_bg).fg(t.selection_fg)
+                    } else {
+                        t.row_style(idx)
+                    };
+                    Row::new(vec![
+                        run.info
+                            .start_time
+                            .map(|ts| tz.format_display(ts))
+                            .unwrap_or_else(|| "--".to_string()),
+                        run_label(run),
+                        run.duration()
+                            .map(format_duration)
+                            .unwrap_or_else(|| "--".to_string()),
+                        format_number(run.reads),
+                        format_bytes(run.total_bases()),
+                        run.n50
+                            .map(|n50| format!("{} bp", format_number(n50)))
+                            .unwrap_or_else(|| "--".to_string()),
+                        run.end_reason.clone(),
+                    ])
+                    .style(style)
+                })
+                .collect();
+
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(24),
+                    Constraint::Min(16),
+                    Constraint::Length(10),
+                    Constraint::Length(8),
+                    Constraint::Length(11),
+                    Constraint::Length(10),
+                    Constraint::Length(24),
+                ],
             )
-        })
-        .collect();
+            .header(header)
+            .block(block);
 
-    let all_y_values = total_data
-        .iter()
-        .chain(passed_data.iter())
-        .chain(failed_data.iter())
-        .map(|(_, y)| *y);
+            frame.render_widget(table, chunks[1]);
+            add_row_hits(hits, chunks[1], runs.len());
+        }
+    }
 
-    let data_min_y = all_y_values.clone().fold(f64::INFINITY, f64::min);
-    let data_max_y = all_y_values.fold(0.0f64, f64::max);
+    let hints = [
+        ("[↑↓] Navigate", None),
+        ("[Enter] Summary", Some(Action::Enter)),
+        ("[Esc] Back", Some(Action::Back)),
+        ("[?] Help", Some(Action::Help)),
+    ];
+    render_hint_bar(frame, t, &hints, chunks[2], hits);
+}
 
-    let y_range = data_max_y - data_min_y;
-    let y_padding = if y_range > 0.001 {
-        y_range * 0.1
-    } else {
-        data_max_y * 0.1 + 0.001
+/// Read-only summary of one finished run.
+fn render_run_summary(
+    frame: &mut Frame,
+    app: &App,
+    position_idx: usize,
+    run_idx: usize,
+    area: Rect,
+    hits: &mut HitMap,
+) {
+    let t = &app.theme;
+    let (Some(position), Some(run)) = (
+        app.positions.get(position_idx),
+        app.history_runs().and_then(|runs| runs.get(run_idx)),
+    ) else {
+        let msg = Paragraph::new("Run not found")
+            .style(Style::default().fg(t.error))
+            .alignment(Alignment::Center);
+        frame.render_widget(msg, area);
+        return;
     };
 
-    let min_y = (data_min_y - y_padding).max(0.0);
-    let max_y = data_max_y + y_padding;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(5),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    render_detail_header(frame, app, position, None, chunks[0]);
+
+    let tz = &app.config.tui.timezone;
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "--".to_string());
+    let end_color = match run.end_reason.as_str() {
+        "Completed" => t.success,
+        "Stopped by user" => t.warning,
+        _ => t.error,
+    };
+
+    let fields = [
+        ("Run ID", or_dash(run.info.run_id.clone()), t.text),
+        (
+            "Acquisition",
+            or_dash(run.acquisition_run_id.clone()),
+            t.text,
+        ),
+        ("Protocol", run.protocol_id.clone(), t.text),
+        (
+            "Experiment",
+            or_dash(run.info.experiment_group.clone()),
+            t.text,
+        ),
+        ("Sample", or_dash(run.info.sample_id.clone()), t.text),
+        (
+            "Started",
+            or_dash(run.info.start_time.map(|ts| tz.format_display(ts))),
+            t.text,
+        ),
+        (
+            "Ended",
+            or_dash(run.info.end_time.map(|ts| tz.format_display(ts))),
+            t.text,
+        ),
+        (
+            "Duration",
+            or_dash(run.duration().map(format_duration)),
+            t.text,
+        ),
+        ("End Reason", run.end_reason.clone(), end_color),
+        ("Reads", format_number(run.reads), t.text),
+        ("Bases", format_bytes(run.total_bases()), t.text),
+        ("Passed", format_bytes(run.bases_passed), t.chart_passed),
+        ("Failed", format_bytes(run.bases_failed), t.chart_failed),
+        (
+            "N50",
+            or_dash(run.n50.map(|n50| format!("{} bp", format_number(n50)))),
+            t.text,
+        ),
+    ];
+
+    let lines: Vec<Line> = fields
+        .into_iter()
+        .map(|(label, value, color)| {
+            Line::from(vec![
+                Span::styled(format!("  {:<12}", label), Style::default().fg(t.text_dim)),
+                Span::styled(value, Style::default().fg(color).bold()),
+            ])
+        })
+        .collect();
+
+    let summary = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(" {} ", run_label(run)))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border)),
+    );
+    frame.render_widget(summary, chunks[1]);
+
+    let hints = [
+        ("[Esc] Back", Some(Action::Back)),
+        ("[?] Help", Some(Action::Help)),
+    ];
+    render_hint_bar(frame, t, &hints, chunks[2], hits);
+}
+
+/// Labels a past run by experiment and sample, falling back to its run ID.
+fn run_label(run: &RunSummary) -> String {
+    run.info
+        .display_label()
+        .or_else(|| run.info.run_id.clone())
+        .unwrap_or_else(|| "--".to_string())
+}
+
+/// Formats a duration as `HH:MM:SS`.
+fn format_duration(duration: chrono::Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+/// A vertical event marker: its kind and the two ends of the line.
+type MarkerLine = (PhaseKind, Vec<(f64, f64)>);
+
+/// Datasets of the total, passed and failed lines that are not hidden,
+/// failed first so total is drawn on top. Each line has its own marker, so
+/// the three stand apart without telling the colors apart: fine braille for
+/// total, solid half blocks for passed and coarse dots for failed.
+fn series_datasets<'a>(app: &App, data: [&'a [(f64, f64)]; 3]) -> Vec<Dataset<'a>> {
+    let t = &app.theme;
+    let [total, passed, failed] = data;
+    [
+        (
+            YieldSeries::Failed,
+            symbols::Marker::Dot,
+            t.chart_failed,
+            failed,
+        ),
+        (
+            YieldSeries::Passed,
+            symbols::Marker::HalfBlock,
+            t.chart_passed,
+            passed,
+        ),
+        (
+            YieldSeries::Total,
+            symbols::Marker::Braille,
+            t.chart_line,
+            total,
+        ),
+    ]
+    .into_iter()
+    .filter(|(series, ..)| app.yield_series.is_visible(*series))
+    .map(|(series, marker, color, data)| {
+        Dataset::default()
+            .name(series.label())
+            .marker(marker)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(data)
+    })
+    .collect()
+}
+
+/// Legend position of the yield and throughput charts.
+fn legend_position(app: &App) -> Option<LegendPosition> {
+    app.show_legend.then_some(LegendPosition::TopLeft)
+}
+
+/// Vertical lines at the run's pore scans and pauses between `x` run
+/// seconds, offset to start at zero and spanning `y`, with a legend of the
+/// kinds drawn.
+fn event_markers(
+    app: &App,
+    position_name: &str,
+    (min_x, max_x): (f64, f64),
+    (min_y, max_y): (f64, f64),
+) -> (Vec<MarkerLine>, Vec<Span<'static>>) {
+    let t = &app.theme;
+    let markers: Vec<MarkerLine> = app
+        .run_markers(position_name)
+        .into_iter()
+        .map(|(seconds, kind)| (seconds as f64, kind))
+        .filter(|&(x, _)| x >= min_x && x <= max_x)
+        .map(|(x, kind)| (kind, vec![(x - min_x, min_y), (x - min_x, max_y)]))
+        .collect();
+    let mut legend = Vec::new();
+    for kind in [
+        PhaseKind::PoreScan,
+        PhaseKind::Pause,
+        PhaseKind::TemperaturePause,
+    ] {
+        if markers.iter().any(|(k, _)| *k == kind) {
+            legend.push(Span::styled(
+                " │ ",
+                Style::default().fg(marker_color(t, kind)),
+            ));
+            legend.push(Span::styled(kind.label(), Style::default().fg(t.text_dim)));
+        }
+    }
+    if !legend.is_empty() {
+        legend.push(Span::raw(" "));
+    }
+    (markers, legend)
+}
+
+fn marker_color(t: &Theme, kind: PhaseKind) -> Color {
+    match kind {
+        PhaseKind::PoreScan => t.info,
+        PhaseKind::TemperaturePause => t.error,
+        PhaseKind::Pause | PhaseKind::Other => t.idle,
+    }
+}
+
+fn marker_dataset<'a>(t: &Theme, kind: PhaseKind, line: &'a [(f64, f64)]) -> Dataset<'a> {
+    Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(marker_color(t, kind)))
+        .data(line)
+}
+
+fn render_yield_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    // Idle positions chart their last finished run.
+    let yield_points = match app.yield_points(position_name) {
+        Some(points) => points,
+        None => {
+            let placeholder = Paragraph::new("Waiting for data...")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .title(" Cumulative Yield ")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(t.border)),
+                );
+            frame.render_widget(placeholder, area);
+            return;
+        }
+    };
+    if app.yield_rates {
+        render_rate_chart(frame, app, position_name, yield_points, area);
+        return;
+    }
+    if app.yield_dual_axis {
+        render_dual_yield_chart(frame, app, position_name, yield_points, area);
+        return;
+    }
+
+    let min_x = yield_points
+        .first()
+        .map(|p| p.seconds as f64)
+        .unwrap_or(0.0);
+    let max_x = yield_points.last().map(|p| p.seconds as f64).unwrap_or(1.0);
+
+    type ValueFn = fn(&crate::client::YieldDataPoint) -> u64;
+    let (get_total, get_passed, get_failed): (ValueFn, ValueFn, ValueFn) = match app.yield_unit {
+        YieldUnit::Bases => (|p| p.bases, |p| p.bases_passed, |p| p.bases_failed),
+        YieldUnit::Reads => (|p| p.reads, |p| p.reads_passed, |p| p.reads_failed),
+    };
+
+    let max_raw_value = yield_points
+        .iter()
+        .map(|p| get_total(p).max(get_passed(p)).max(get_failed(p)))
+        .max()
+        .unwrap_or(0) as f64;
+
+    // Long runs have far more samples than the plot has dots; thin them
+    // with LTTB on the total line so all three lines share x values.
+    let totals: Vec<(f64, f64)> = yield_points
+        .iter()
+        .map(|p| (p.seconds as f64, get_total(p) as f64))
+        .collect();
+    let all_points = yield_points;
+    let yield_points: Vec<&crate::client::YieldDataPoint> =
+        downsample::lttb_indices(&totals, plot_columns(area))
+            .into_iter()
+            .map(|i| &all_points[i])
+            .collect();
+
+    // Projections run to the end of the run, which would flatten a window.
+    let projections: Vec<Projection> =
+        if app.show_projection && app.yield_window == YieldWindow::Full {
+            [ProjectionModel::Linear, ProjectionModel::Decay]
+                .into_iter()
+                .filter_map(|model| app.yield_projection(position_name, app.yield_unit, model))
+                .collect()
+        } else {
+            Vec::new()
+        };
+    let max_x = projections
+        .iter()
+        .filter_map(|p| p.curve.last())
+        .fold(max_x, |max, &(seconds, _)| max.max(seconds as f64));
+    let max_raw_value = projections
+        .iter()
+        .map(|p| p.final_total as f64)
+        .fold(max_raw_value, f64::max);
+
+    let (unit_label, scale_factor) = yield_scale(app.yield_unit, max_raw_value);
+    // The crosshair reads the nearest stored point, not a thinned one.
+    let cursor = match app.chart_cursor {
+        Some(ChartCursor::Yield(seconds)) => {
+            let idx = all_points.partition_point(|p| p.seconds < seconds);
+            all_points.get(idx).or(all_points.last())
+        }
+        _ => None,
+    };
+    let title = match cursor {
+        Some(point) => format!(
+            "Cumulative Yield ({}) · {} · total {:.2} · passed {:.2} · failed {:.2}",
+            unit_label,
+            point_time_label(app, position_name, point.seconds),
+            get_total(point) as f64 / scale_factor,
+            get_passed(point) as f64 / scale_factor,
+            get_failed(point) as f64 / scale_factor,
+        ),
+        None if app.yield_window != YieldWindow::Full => format!(
+            "Cumulative Yield ({}) · {}",
+            unit_label,
+            app.yield_window.label()
+        ),
+        None if app.show_projection => format!(
+            "Cumulative Yield ({}) · projected to {}h",
+            unit_label, app.config.tui.expected_run_hours
+        ),
+        None => format!("Cumulative Yield ({})", unit_label),
+    };
+
+    // Hidden lines are left empty so the y axis fits the ones shown.
+    let visible = app.yield_series;
+    let total_data: Vec<(f64, f64)> = yield_points
+        .iter()
+        .filter(|_| visible.total)
+        .map(|p| (p.seconds as f64 - min_x, get_total(p) as f64 / scale_factor))
+        .collect();
+    let passed_data: Vec<(f64, f64)> = yield_points
+        .iter()
+        .filter(|_| visible.passed)
+        .map(|p| {
+            (
+                p.seconds as f64 - min_x,
+                get_passed(p) as f64 / scale_factor,
+            )
+        })
+        .collect();
+    let failed_data: Vec<(f64, f64)> = yield_points
+        .iter()
+        .filter(|_| visible.failed)
+        .map(|p| {
+            (
+                p.seconds as f64 - min_x,
+                get_failed(p) as f64 / scale_factor,
+            )
+        })
+        .collect();
+    let projected_data: Vec<(ProjectionModel, Vec<(f64, f64)>)> = projections
+        .iter()
+        .map(|p| {
+            let curve = p
+                .curve
+                .iter()
+                .map(|&(seconds, total)| (seconds as f64 - min_x, total as f64 / scale_factor))
+                .collect();
+            (p.model, curve)
+        })
+        .collect();
+
+    let all_y_values = total_data
+        .iter()
+        .chain(passed_data.iter())
+        .chain(failed_data.iter())
+        .chain(projected_data.iter().flat_map(|(_, curve)| curve.iter()))
+        .map(|(_, y)| *y);
+
+    let data_min_y = all_y_values.clone().fold(f64::INFINITY, f64::min);
+    let data_min_y = if data_min_y.is_finite() {
+        data_min_y
+    } else {
+        0.0
+    };
+    let data_max_y = all_y_values.fold(0.0f64, f64::max);
+
+    let y_range = data_max_y - data_min_y;
+    let y_padding = if y_range > 0.001 {
+        y_range * 0.1
+    } else {
+        data_max_y * 0.1 + 0.001
+    };
+
+    let min_y = (data_min_y - y_padding).max(0.0);
+    let max_y = data_max_y + y_padding;
+    let last_x = all_points.last().map_or(min_x, |p| p.seconds as f64);
+    let (markers, legend) = event_markers(app, position_name, (min_x, last_x), (min_y, max_y));
+    let crosshair: Vec<(f64, f64)> = cursor
+        .map(|point| {
+            let x = point.seconds as f64 - min_x;
+            vec![(x, min_y), (x, max_y)]
+        })
+        .unwrap_or_default();
+
+    // Order matters: later datasets render on top. We want total > passed >
+    // failed, with projections and event markers beneath them all.
+    let mut datasets: Vec<Dataset> = markers
+        .iter()
+        .map(|(kind, line)| marker_dataset(t, *kind, line))
+        .collect();
+    datasets.extend(projected_data.iter().map(|(model, curve)| {
+        let color = match model {
+            ProjectionModel::Linear => t.text_dim,
+            ProjectionModel::Decay => t.special,
+        };
+        Dataset::default()
+            .name(model.label())
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(curve)
+    }));
+    datasets.extend(series_datasets(
+        app,
+        [&total_data, &passed_data, &failed_data],
+    ));
+    if !crosshair.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(t.warning))
+                .data(&crosshair),
+        );
+    }
+
+    // A window starts mid-run, so label both ends with the run time.
+    let (start_label, end_label) = time_axis_labels(
+        app,
+        position_name,
+        (min_x, max_x),
+        app.yield_window == YieldWindow::Full,
+    );
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(format!(" {} ", title))
+                .title_bottom(Line::from(legend).alignment(Alignment::Right))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_x - min_x])
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([min_y, max_y])
+                .labels(vec![
+                    Line::from(format!("{:.1}", min_y)),
+                    Line::from(format!("{:.1}", (min_y + max_y) / 2.0)),
+                    Line::from(format!("{:.1}", max_y)),
+                ]),
+        )
+        .legend_position(legend_position(app));
+
+    frame.render_widget(chart, area);
+}
+
+/// Plots the latest raw signal of the signal channel, scrolling as new
+/// samples arrive.
+fn render_signal_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let block = Block::default()
+        .title(format!(" Signal · Ch {} (pA) ", app.signal_channel))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.border));
+
+    let Some(samples) = app.signal_trace(position_name) else {
+        let placeholder = Paragraph::new("Waiting for signal...")
+            .style(Style::default().fg(t.text_dim))
+            .alignment(Alignment::Center)
+            .block(block);
+        frame.render_widget(placeholder, area);
+        return;
+    };
+
+    let points = downsample_signal(samples, plot_columns(area));
+
+    let (data_min, data_max) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, y)| {
+            (lo.min(*y), hi.max(*y))
+        });
+    let padding = ((data_max - data_min) * 0.1).max(1.0);
+    let (min_y, max_y) = (data_min - padding, data_max + padding);
+    let max_x = points.last().map_or(1.0, |(x, _)| x.max(1.0));
+
+    let datasets = vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(t.chart_line))
+        .data(&points)];
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_x])
+                .labels(vec![
+                    Line::from(format!("-{} samples", samples.len())),
+                    Line::from("now"),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([min_y, max_y])
+                .labels(vec![
+                    Line::from(format!("{:.0}", min_y)),
+                    Line::from(format!("{:.0}", (min_y + max_y) / 2.0)),
+                    Line::from(format!("{:.0}", max_y)),
+                ]),
+        )
+        .legend_position(None);
+
+    frame.render_widget(chart, area);
+}
+
+/// Draws output per hour over time, derived from the cumulative yield, so a
+/// drop in throughput shows up at once instead of as a slight bend.
+fn render_rate_chart(
+    frame: &mut Frame,
+    app: &App,
+    position_name: &str,
+    all_points: &[crate::client::YieldDataPoint],
+    area: Rect,
+) {
+    let t = &app.theme;
+    let min_x = all_points.first().map_or(0.0, |p| p.seconds as f64);
+    let max_x = all_points.last().map_or(1.0, |p| p.seconds as f64);
+
+    type ValueFn = fn(&crate::client::YieldDataPoint) -> u64;
+    let (get_total, get_passed, get_failed): (ValueFn, ValueFn, ValueFn) = match app.yield_unit {
+        YieldUnit::Bases => (|p| p.bases, |p| p.bases_passed, |p| p.bases_failed),
+        YieldUnit::Reads => (|p| p.reads, |p| p.reads_passed, |p| p.reads_failed),
+    };
+    // Half as many bins as plot columns keeps each bin a couple of dots wide.
+    let bins = (plot_columns(area) / 4).max(1) as u32;
+    let series = |value: ValueFn| {
+        let samples: Vec<(u32, u64)> = all_points.iter().map(|p| (p.seconds, value(p))).collect();
+        analysis::rates(&samples, bins)
+    };
+    let (total, passed, failed) = (series(get_total), series(get_passed), series(get_failed));
+
+    if total.is_empty() {
+        let placeholder = Paragraph::new("Waiting for 5 minutes of yield...")
+            .style(Style::default().fg(t.text_dim))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .title(" Throughput ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(t.border)),
+            );
+        frame.render_widget(placeholder, area);
+        return;
+    }
+    // The y axis fits the lines shown.
+    let visible = app.yield_series;
+    let max_rate = [
+        (visible.total, &total),
+        (visible.passed, &passed),
+        (visible.failed, &failed),
+    ]
+    .into_iter()
+    .filter(|(shown, _)| *shown)
+    .flat_map(|(_, rates)| rates.iter().map(|&(_, rate)| rate))
+    .fold(0.0, f64::max);
+    let (unit_label, scale_factor) = yield_scale(app.yield_unit, max_rate);
+    let max_y = (max_rate / scale_factor * 1.1).max(0.001);
+    let to_data = |rates: &[(u32, f64)]| -> Vec<(f64, f64)> {
+        rates
+            .iter()
+            .map(|&(seconds, rate)| (seconds as f64 - min_x, rate / scale_factor))
+            .collect()
+    };
+    let (total_data, passed_data, failed_data) =
+        (to_data(&total), to_data(&passed), to_data(&failed));
+
+    // The crosshair reads the bin whose middle is nearest. All three series
+    // share their bins.
+    let cursor = match app.chart_cursor {
+        Some(ChartCursor::Yield(seconds)) => {
+            let after = total
+                .partition_point(|&(mid, _)| mid < seconds)
+                .min(total.len() - 1);
+            let idx = match after.checked_sub(1) {
+                Some(before)
+                    if seconds - total[before].0 < total[after].0.saturating_sub(seconds) =>
+                {
+                    before
+                }
+                _ => after,
+            };
+            Some((seconds, idx))
+        }
+        _ => None,
+    };
+    let title = match cursor {
+        Some((seconds, idx)) => format!(
+            "Throughput ({}/h) · {} · total {:.2} · passed {:.2} · failed {:.2}",
+            unit_label,
+            point_time_label(app, position_name, seconds),
+            total[idx].1 / scale_factor,
+            passed[idx].1 / scale_factor,
+            failed[idx].1 / scale_factor,
+        ),
+        None if app.yield_window != YieldWindow::Full => format!(
+            "Throughput ({}/h) · {}",
+            unit_label,
+            app.yield_window.label()
+        ),
+        None => format!("Throughput ({}/h)", unit_label),
+    };
+    let crosshair: Vec<(f64, f64)> = cursor
+        .map(|(seconds, ..)| {
+            let x = seconds as f64 - min_x;
+            vec![(x, 0.0), (x, max_y)]
+        })
+        .unwrap_or_default();
+    let (markers, legend) = event_markers(app, position_name, (min_x, max_x), (0.0, max_y));
+
+    let mut datasets: Vec<Dataset> = markers
+        .iter()
+        .map(|(kind, line)| marker_dataset(t, *kind, line))
+        .collect();
+    datasets.extend(series_datasets(
+        app,
+        [&total_data, &passed_data, &failed_data],
+    ));
+    if !crosshair.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(t.warning))
+                .data(&crosshair),
+        );
+    }
+
+    let (start_label, end_label) = time_axis_labels(
+        app,
+        position_name,
+        (min_x, max_x),
+        app.yield_window == YieldWindow::Full,
+    );
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(format!(" {} ", title))
+                .title_bottom(Line::from(legend).alignment(Alignment::Right))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_x - min_x])
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_y])
+                .labels(vec![
+                    Line::from("0.0"),
+                    Line::from(format!("{:.1}", max_y / 2.0)),
+                    Line::from(format!("{:.1}", max_y)),
+                ]),
+        )
+        .legend_position(legend_position(app));
+    frame.render_widget(chart, area);
+}
+
+/// Draws bases against the left axis and reads against a second axis on
+/// the right, so the two can be compared when they diverge.
+fn render_dual_yield_chart(
+    frame: &mut Frame,
+    app: &App,
+    position_name: &str,
+    all_points: &[crate::client::YieldDataPoint],
+    area: Rect,
+) {
+    let t = &app.theme;
+    let min_x = all_points.first().map_or(0.0, |p| p.seconds as f64);
+    let max_x = all_points.last().map_or(1.0, |p| p.seconds as f64);
+    let max_bases = all_points.iter().map(|p| p.bases).max().unwrap_or(0) as f64;
+    let max_reads = all_points.iter().map(|p| p.reads).max().unwrap_or(0) as f64;
+    let (bases_label, bases_factor) = yield_scale(YieldUnit::Bases, max_bases);
+    let (reads_label, reads_factor) = yield_scale(YieldUnit::Reads, max_reads);
+    // Both start at zero and leave 10% headroom; reads are rescaled so their
+    // axis spans the same height as the bases axis.
+    let max_y = (max_bases / bases_factor * 1.1).max(0.001);
+    let reads_max_y = (max_reads / reads_factor * 1.1).max(0.001);
+    let reads_to_y = max_y / reads_max_y;
+
+    let bases: Vec<(f64, f64)> = all_points
+        .iter()
+        .map(|p| (p.seconds as f64, p.bases as f64))
+        .collect();
+    let points: Vec<&crate::client::YieldDataPoint> =
+        downsample::lttb_indices(&bases, plot_columns(area))
+            .into_iter()
+            .map(|i| &all_points[i])
+            .collect();
+    let bases_data: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| (p.seconds as f64 - min_x, p.bases as f64 / bases_factor))
+        .collect();
+    let reads_data: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| {
+            let reads = p.reads as f64 / reads_factor;
+            (p.seconds as f64 - min_x, reads * reads_to_y)
+        })
+        .collect();
+
+    let cursor = match app.chart_cursor {
+        Some(ChartCursor::Yield(seconds)) => {
+            let idx = all_points.partition_point(|p| p.seconds < seconds);
+            all_points.get(idx).or(all_points.last())
+        }
+        _ => None,
+    };
+    let crosshair: Vec<(f64, f64)> = cursor
+        .map(|point| {
+            let x = point.seconds as f64 - min_x;
+            vec![(x, 0.0), (x, max_y)]
+        })
+        .unwrap_or_default();
+    let (markers, legend) = event_markers(app, position_name, (min_x, max_x), (0.0, max_y));
+
+    let mut title = vec![
+        Span::raw(" Cumulative Yield · "),
+        Span::styled(
+            format!("━ Bases ({})", bases_label),
+            Style::default().fg(t.chart_line),
+        ),
+        Span::raw(" · "),
+        Span::styled(
+            format!("━ Reads ({})", reads_label),
+            Style::default().fg(t.special),
+        ),
+    ];
+    match cursor {
+        Some(point) => title.push(Span::raw(format!(
+            " · {} · {:.2} {} · {:.2} {}",
+            point_time_label(app, position_name, point.seconds),
+            point.bases as f64 / bases_factor,
+            bases_label,
+            point.reads as f64 / reads_factor,
+            reads_label,
+        ))),
+        None if app.yield_window != YieldWindow::Full => {
+            title.push(Span::raw(format!(" · {}", app.yield_window.label())))
+        }
+        None => {}
+    }
+    title.push(Span::raw(" "));
+
+    let mut datasets: Vec<Dataset> = markers
+        .iter()
+        .map(|(kind, line)| marker_dataset(t, *kind, line))
+        .collect();
+    datasets.extend([
+        Dataset::default()
+            .name("Reads")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(t.special))
+            .data(&reads_data),
+        Dataset::default()
+            .name("Bases")
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(t.chart_line))
+            .data(&bases_data),
+    ]);
+    if !crosshair.is_empty() {
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(t.warning))
+                .data(&crosshair),
+        );
+    }
+
+    let (start_label, end_label) = time_axis_labels(
+        app,
+        position_name,
+        (min_x, max_x),
+        app.yield_window == YieldWindow::Full,
+    );
+    let reads_labels = [0.0, reads_max_y / 2.0, reads_max_y].map(|y| format!("{:.1}", y));
+    let labels_width = reads_labels.iter().map(|l| l.len()).max().unwrap_or(0) as u16 + 1;
+    let chunks =
+        Layout::horizontal([Constraint::Min(0), Constraint::Length(labels_width)]).split(area);
+    let (chart_area, axis_area) = (chunks[0], chunks[1]);
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(Line::from(title))
+                .title_bottom(Line::from(legend).alignment(Alignment::Right))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_x - min_x])
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_line))
+                .bounds([0.0, max_y])
+                .labels(vec![
+                    Line::from("0.0"),
+                    Line::from(format!("{:.1}", max_y / 2.0)),
+                    Line::from(format!("{:.1}", max_y)),
+                ]),
+        )
+        .legend_position(legend_position(app));
+    frame.render_widget(chart, chart_area);
+
+    // The plot spans from below the top border to above the x axis line
+    // and its labels.
+    let top = axis_area.y + 1;
+    let bottom = axis_area.bottom().saturating_sub(4);
+    if bottom > top {
+        let rows = [bottom, bottom - (bottom - top) / 2, top];
+        for (row, label) in rows.into_iter().zip(reads_labels) {
+            let label = Paragraph::new(format!(" {}", label)).style(Style::default().fg(t.special));
+            frame.render_widget(label, Rect::new(axis_area.x, row, axis_area.width, 1));
+        }
+    }
+}
+
+/// Braille dots across a chart's plot area: two per cell, less room for
+/// the borders and y axis labels.
+fn plot_columns(area: Rect) -> usize {
+    (area.width.saturating_sub(10) as usize * 2).max(2)
+}
+
+/// Reduces `samples` to at most two points per column, the minimum and
+/// maximum of the samples it covers, so spikes survive the downsampling.
+fn downsample_signal(samples: &VecDeque<f32>, columns: usize) -> Vec<(f64, f64)> {
+    let per_column = samples.len().div_ceil(columns).max(1);
+    let mut points = Vec::with_capacity(columns * 2);
+    let mut iter = samples.iter().copied();
+    for column in 0.. {
+        let (lo, hi) = iter
+            .by_ref()
+            .take(per_column)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| {
+                (lo.min(v), hi.max(v))
+            });
+        if lo > hi {
+            break;
+        }
+        points.push((column as f64, lo as f64));
+        if hi > lo {
+            points.push((column as f64, hi as f64));
+        }
+    }
+    points
+}
+
+/// Picks the axis unit label and divisor for a yield chart whose largest value is `max`.
+fn yield_scale(unit: YieldUnit, max: f64) -> (&'static str, f64) {
+    match unit {
+        YieldUnit::Bases => {
+            if max >= 1_000_000_000_000.0 {
+                ("Tb", 1_000_000_000_000.0)
+            } else if max >= 1_000_000_000.0 {
+                ("Gb", 1_000_000_000.0)
+            } else if max >= 1_000_000.0 {
+                ("Mb", 1_000_000.0)
+            } else if max >= 1_000.0 {
+                ("Kb", 1_000.0)
+            } else {
+                ("b", 1.0)
+            }
+        }
+        YieldUnit::Reads => {
+            if max >= 1_000_000_000.0 {
+                ("B reads", 1_000_000_000.0)
+            } else if max >= 1_000_000.0 {
+                ("M reads", 1_000_000.0)
+            } else if max >= 1_000.0 {
+                ("K reads", 1_000.0)
+            } else {
+                ("reads", 1.0)
+            }
+        }
+    }
+}
+
+/// Number of barcodes drawn individually in the stacked chart; the rest are summed as "other".
+const STACKED_BARCODES: usize = 7;
+
+/// Per-barcode table alongside a stacked cumulative yield chart.
+fn render_barcode_panel(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let barcodes = app.sorted_barcodes(position_name);
+    if barcodes.is_empty() {
+        let placeholder = Paragraph::new("Waiting for data...")
+            .style(Style::default().fg(t.text_dim))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .title(" Barcodes ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(t.border)),
+            );
+        frame.render_widget(placeholder, area);
+        return;
+    }
+
+    let chunks = Layout::horizontal([Constraint::Length(48), Constraint::Min(20)]).split(area);
+
+    let total_bases: u64 = barcodes.iter().map(|b| b.bases()).sum();
+    let header = Row::new(vec!["Barcode", "Reads", "Bases", "%"])
+        .style(Style::default().bold())
+        .bottom_margin(1);
+    let rows: Vec<Row> = barcodes
+        .iter()
+        .enumerate()
+        .map(|(idx, barcode)| {
+            let share = if total_bases > 0 {
+                barcode.bases() as f64 / total_bases as f64 * 100.0
+            } else {
+                0.0
+            };
+            Row::new(vec![
+                barcode.barcode.clone(),
+                format_number(barcode.reads()),
+                format_bytes(barcode.bases()),
+                format!("{:.1}", share),
+            ])
+            .style(t.row_style(idx))
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(14),
+            Constraint::Length(8),
+            Constraint::Length(11),
+            Constraint::Length(5),
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .title(format!(" Barcodes (by {}) ", app.barcode_sort.label()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border)),
+    );
+    frame.render_widget(table, chunks[0]);
+
+    let value: fn(&crate::client::YieldDataPoint) -> u64 = match app.yield_unit {
+        YieldUnit::Bases => |p| p.bases,
+        YieldUnit::Reads => |p| p.reads,
+    };
+
+    // Stack the largest barcodes first so the biggest band sits at the bottom.
+    let mut by_size = barcodes.clone();
+    by_size.sort_by_key(|b| std::cmp::Reverse(b.points.last().map(value).unwrap_or(0)));
+    let (top, rest) = by_size.split_at(by_size.len().min(STACKED_BARCODES));
+
+    let mut times: Vec<u32> = barcodes
+        .iter()
+        .flat_map(|b| b.points.iter().map(|p| p.seconds))
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+    let min_x = times.first().copied().unwrap_or(0) as f64;
+    let max_x = times.last().copied().unwrap_or(0) as f64;
+    let (start_label, end_label) = time_axis_labels(app, position_name, (min_x, max_x), true);
+
+    // Value of a barcode at `seconds`, holding its last sample between updates.
+    let at = |barcode: &crate::client::BarcodeYield, seconds: u32| -> u64 {
+        barcode
+            .points
+            .iter()
+            .take_while(|p| p.seconds <= seconds)
+            .last()
+            .map(value)
+            .unwrap_or(0)
+    };
+
+    let mut layers: Vec<(String, Vec<u64>)> = top
+        .iter()
+        .map(|b| (b.barcode.clone(), times.iter().map(|&s| at(b, s)).collect()))
+        .collect();
+    if !rest.is_empty() {
+        let other = times
+            .iter()
+            .map(|&s| rest.iter().map(|b| at(b, s)).sum())
+            .collect();
+        layers.push(("other".to_string(), other));
+    }
+
+    let mut running = vec![0u64; times.len()];
+    let stacked: Vec<(String, Vec<u64>)> = layers
+        .into_iter()
+        .map(|(name, values)| {
+            for (sum, value) in running.iter_mut().zip(values) {
+                *sum += value;
+            }
+            (name, running.clone())
+        })
+        .collect();
+
+    let max_raw_value = running.iter().copied().max().unwrap_or(0) as f64;
+    let (unit_label, scale_factor) = yield_scale(app.yield_unit, max_raw_value);
+    let max_y = (max_raw_value / scale_factor) * 1.1 + 0.001;
 
-    // Order matters: later datasets render on top. We want total > passed > failed.
-    let datasets = vec![
-        Dataset::default()
-            .name("Failed")
-            .marker(symbols::Marker::Braille)
-            .graph_type(GraphType::Line)
-            .style(Style::default().fg(t.chart_failed))
-            .data(&failed_data),
-        Dataset::default()
-            .name("Passed")
-            .marker(symbols::Marker::Braille)
-            .graph_type(GraphType::Line)
-            .style(Style::default().fg(t.chart_passed))
-            .data(&passed_data),
-        Dataset::default()
-            .name("Total")
-            .marker(symbols::Marker::Braille)
-            .graph_type(GraphType::Line)
-            .style(Style::default().fg(t.chart_line))
-            .data(&total_data),
-    ];
+    let series: Vec<Vec<(f64, f64)>> = stacked
+        .iter()
+        .map(|(_, values)| {
+            times
+                .iter()
+                .zip(values)
+                .map(|(&s, &v)| (s as f64 - min_x, v as f64 / scale_factor))
+                .collect()
+        })
+        .collect();
+    // Thin every band at the x values LTTB picks for the top of the stack.
+    let keep = series
+        .last()
+        .map(|top| downsample::lttb_indices(top, plot_columns(area)))
+        .unwrap_or_default();
+    let series: Vec<Vec<(f64, f64)>> = series
+        .iter()
+        .map(|band| keep.iter().map(|&i| band[i]).collect())
+        .collect();
 
-    let time_label = format_time_label(max_x - min_x);
+    let palette = [
+        t.chart_line,
+        t.chart_passed,
+        t.info,
+        t.special,
+        t.warning,
+        t.chart_failed,
+        t.channel_adapter,
+        t.channel_pore,
+    ];
+    // Draw the top of the stack first so lower bands render over it.
+    let datasets: Vec<Dataset> = stacked
+        .iter()
+        .zip(&series)
+        .enumerate()
+        .rev()
+        .map(|(idx, ((name, _), data))| {
+            Dataset::default()
+                .name(name.clone())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(palette[idx % palette.len()]))
+                .data(data)
+        })
+        .collect();
 
     let chart = Chart::new(datasets)
         .block(
             Block::default()
-                .title(format!(" {} ", title))
+                .title(format!(" Stacked Yield ({}) ", unit_label))
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(t.border)),
         )
@@ -738,21 +3058,21 @@ fn render_yield_chart(frame: &mut Frame, app: &App, position_name: &str, area: R
             Axis::default()
                 .style(Style::default().fg(t.chart_axis))
                 .bounds([0.0, max_x - min_x])
-                .labels(vec![Line::from("0"), Line::from(time_label)]),
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
         )
         .y_axis(
             Axis::default()
                 .style(Style::default().fg(t.chart_axis))
-                .bounds([min_y, max_y])
+                .bounds([0.0, max_y])
                 .labels(vec![
-                    Line::from(format!("{:.1}", min_y)),
-                    Line::from(format!("{:.1}", (min_y + max_y) / 2.0)),
+                    Line::from("0.0"),
+                    Line::from(format!("{:.1}", max_y / 2.0)),
                     Line::from(format!("{:.1}", max_y)),
                 ]),
         )
-        .legend_position(None);
+        .legend_position(Some(LegendPosition::TopLeft));
 
-    frame.render_widget(chart, area);
+    frame.render_widget(chart, chunks[1]);
 }
 
 fn render_histogram_chart(
@@ -760,23 +3080,24 @@ fn render_histogram_chart(
     t: &Theme,
     histogram: Option<&ReadLengthHistogram>,
     exclude_outliers: bool,
+    by_bases: bool,
     histogram_range: Option<(u64, u64)>,
     area: Rect,
 ) {
-    let base_title = " Read Length Distribution ";
-    let title = match (exclude_outliers, histogram_range) {
-        (true, Some((min, max))) => format!(
-            " Read Length ({}-{} bp, outliers excluded) ",
-            format_number(min),
-            format_number(max)
-        ),
-        (false, Some((min, max))) => format!(
-            " Read Length ({}-{} bp) ",
-            format_number(min),
-            format_number(max)
-        ),
-        (true, None) => " Read Length Distribution (outliers excluded) ".to_string(),
-        (false, None) => base_title.to_string(),
+    let mut notes = Vec::new();
+    if let Some((min, max)) = histogram_range {
+        notes.push(format!("{}-{} bp", format_number(min), format_number(max)));
+    }
+    if by_bases {
+        notes.push("by bases".to_string());
+    }
+    if exclude_outliers {
+        notes.push("outliers excluded".to_string());
+    }
+    let title = match (histogram_range, notes.is_empty()) {
+        (Some(_), _) => format!(" Read Length ({}) ", notes.join(", ")),
+        (None, true) => " Read Length Distribution ".to_string(),
+        (None, false) => format!(" Read Length Distribution ({}) ", notes.join(", ")),
     };
 
     let histogram = match histogram {
@@ -901,47 +3222,568 @@ fn render_histogram_chart(
     frame.render_widget(bar_chart, chart_area);
 }
 
-fn render_pore_activity(
+/// Plots the available pores found by each pore scan against run time, so
+/// pore loss over the run shows as a falling line.
+fn render_pore_scan_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let block = Block::default()
+        .title(" Pores per Scan ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.border));
+
+    let scans = match app.mux_scans.get(position_name) {
+        Some(scans) if !scans.is_empty() => scans,
+        _ => {
+            let placeholder = Paragraph::new("No pore scan results yet")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(block);
+            frame.render_widget(placeholder, area);
+            return;
+        }
+    };
+
+    let points: Vec<(f64, f64)> = scans
+        .iter()
+        .map(|scan| (scan.seconds as f64, scan.available_pores() as f64))
+        .collect();
+    let max_x = points.last().map_or(0.0, |(x, _)| *x).max(60.0);
+    let (start_label, end_label) = time_axis_labels(app, position_name, (0.0, max_x), true);
+    let max_y = points.iter().map(|(_, y)| *y).fold(0.0, f64::max).max(1.0) * 1.1;
+
+    let datasets = vec![
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(t.chart_line))
+            .data(&points),
+        Dataset::default()
+            .marker(symbols::Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(t.text_title))
+            .data(&points),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_x])
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_y])
+                .labels(vec![
+                    Line::from("0"),
+                    Line::from(format_number((max_y / 2.0) as u64)),
+                    Line::from(format_number(max_y as u64)),
+                ]),
+        )
+        .legend_position(None);
+
+    frame.render_widget(chart, area);
+}
+
+/// Plots the active pore count sampled over the chart history, ending at
+/// the latest sample.
+fn render_active_pores_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let samples = match app.pore_history.get(position_name) {
+        Some(buffer) if buffer.data.len() > 1 => &buffer.data,
+        _ => {
+            let placeholder = Paragraph::new("Waiting for data...")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .title(" Active Pores ")
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(t.border)),
+                );
+            frame.render_widget(placeholder, area);
+            return;
+        }
+    };
+
+    let (latest_x, latest_y) = samples.back().copied().unwrap_or_default();
+    let span = (latest_x - samples.front().map_or(latest_x, |(x, _)| *x)).max(1.0);
+    // Samples are timed from when Termion started, not the run, so clock
+    // times count back from the latest statistics.
+    let now = app
+        .stats_cache
+        .get(position_name)
+        .and_then(|stats| stats.timestamp)
+        .unwrap_or_else(chrono::Utc::now);
+    let (start_label, end_label) = if app.clock_time {
+        let tz = &app.config.tui.timezone;
+        let start = now - chrono::Duration::seconds(span as i64);
+        (tz.format(start, "%H:%M"), tz.format(now, "%H:%M"))
+    } else {
+        (format!("-{}", format_time_label(span)), "now".to_string())
+    };
+    let points: Vec<(f64, f64)> = samples.iter().map(|&(x, y)| (x - latest_x, y)).collect();
+    let points = downsample::lttb(&points, plot_columns(area));
+    let max_y = points.iter().map(|(_, y)| *y).fold(0.0, f64::max).max(1.0) * 1.1;
+
+    let datasets = vec![Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(t.chart_line))
+        .data(&points)];
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(format!(
+                    " Active Pores ({}) ",
+                    format_number(latest_y as u64)
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([-span, 0.0])
+                .labels(vec![Line::from(start_label), Line::from(end_label)]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(t.chart_axis))
+                .bounds([0.0, max_y])
+                .labels(vec![
+                    Line::from("0"),
+                    Line::from(format_number((max_y / 2.0) as u64)),
+                    Line::from(format_number(max_y as u64)),
+                ]),
+        )
+        .legend_position(None);
+
+    frame.render_widget(chart, area);
+}
+
+/// Channel states stacked on the duty time chart, bottom first, with the
+/// fill symbol and color of each band.
+fn duty_time_bands(t: &Theme) -> [(ChannelState, &'static str, Color); 6] {
+    [
+        (ChannelState::Strand, "█", t.channel_sequencing),
+        (ChannelState::Pore, "▓", t.channel_pore),
+        (ChannelState::Adapter, "▚", t.channel_adapter),
+        (ChannelState::Unblock, "▒", t.channel_adapter),
+        (ChannelState::Unavailable, "╳", t.channel_unavailable),
+        (ChannelState::Other, "░", t.channel_other),
+    ]
+}
+
+/// Draws the share of time channels spent in each state as stacked
+/// columns across the run, merging buckets when there are more than fit.
+fn render_duty_time_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let bands = duty_time_bands(t);
+    let buckets = app
+        .duty_history
+        .get(position_name)
+        .map(|buckets| buckets.as_slice())
+        .unwrap_or_default();
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(t.border));
+    let (Some(first), Some(last)) = (buckets.first(), buckets.last()) else {
+        let placeholder = Paragraph::new("Waiting for data...")
+            .style(Style::default().fg(t.text_dim))
+            .alignment(Alignment::Center)
+            .block(block.title(" Duty Time "));
+        frame.render_widget(placeholder, area);
+        return;
+    };
+
+    let (start_label, end_label) = time_axis_labels(
+        app,
+        position_name,
+        (first.start.into(), last.end.into()),
+        true,
+    );
+    block = block.title(format!(" Duty Time ({} – {}) ", start_label, end_label));
+    let mut legend = vec![Span::raw(" ")];
+    for (state, symbol, color) in bands {
+        legend.push(Span::styled(symbol, Style::default().fg(color)));
+        legend.push(Span::styled(
+            format!(" {} {:.0}%  ", state.label(), last.fraction(state) * 100.0),
+            Style::default().fg(t.text_dim),
+        ));
+    }
+    block = block.title_bottom(Line::from(legend).alignment(Alignment::Center));
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    if inner.width == 0 || inner.height == 0 {
+        return;
+    }
+
+    let width = inner.width as usize;
+    let height = inner.height as usize;
+    let columns: Vec<Vec<f64>> = (0..width)
+        .map(|col| {
+            let from = col * buckets.len() / width;
+            let to = ((col + 1) * buckets.len() / width).max(from + 1);
+            let mut times = vec![0u64; bands.len()];
+            for bucket in &buckets[from..to] {
+                for (time, (state, _, _)) in times.iter_mut().zip(&bands) {
+                    *time += bucket.state_times.get(state).copied().unwrap_or(0);
+                }
+            }
+            let total = times.iter().sum::<u64>().max(1) as f64;
+            let mut cumulative = 0.0;
+            times
+                .iter()
+                .map(|&time| {
+                    cumulative += time as f64 / total;
+                    cumulative
+                })
+                .collect()
+        })
+        .collect();
+
+    let lines: Vec<Line> = (0..height)
+        .map(|row| {
+            // Level of the middle of this row, from 0 at the bottom to 1 at the top.
+            let level = (height - row) as f64 / height as f64 - 0.5 / height as f64;
+            let spans: Vec<Span> = columns
+                .iter()
+                .map(|bounds| match bounds.iter().position(|&top| level < top) {
+                    Some(band) => Span::styled(bands[band].1, Style::default().fg(bands[band].2)),
+                    None => Span::raw(" "),
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Draws the mean read q-score distribution, with buckets below the pass
+/// cutoff in the failed color and the cutoff bucket marked underneath.
+fn render_qscore_chart(frame: &mut Frame, app: &App, position_name: &str, area: Rect) {
+    let t = &app.theme;
+    let cutoff = app.config.tui.min_qscore;
+    let histogram = app
+        .qscore_histograms
+        .get(position_name)
+        .filter(|h| h.total_count() > 0);
+
+    let Some(histogram) = histogram else {
+        let placeholder = Paragraph::new("Waiting for q-score data (requires basecalling)...")
+            .style(Style::default().fg(t.text_dim))
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .title(" Q-Score Distribution ")
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(t.border)),
+            );
+        frame.render_widget(placeholder, area);
+        return;
+    };
+
+    let cursor = match app.chart_cursor {
+        Some(ChartCursor::QScore(idx)) => histogram
+            .bucket_ranges
+            .get(idx)
+            .zip(histogram.bucket_values.get(idx)),
+        _ => None,
+    };
+    let title = match cursor {
+        Some((&(start, end), &count)) => {
+            let share = count as f64 / histogram.total_count().max(1) as f64;
+            let verdict = if (start as f64) < cutoff {
+                "fail"
+            } else {
+                "pass"
+            };
+            format!(
+                " Q{:.1}-{:.1} · {} reads · {:.1}% · {} ",
+                start,
+                end,
+                format_number(count),
+                share * 100.0,
+                verdict
+            )
+        }
+        None => format!(" Q-Score · mode Q{:.1} ", histogram.modal_q_score),
+    };
+
+    // One column per bar plus a gap between bars.
+    let max_bars = (area.width.saturating_sub(2) as usize).div_ceil(2).max(1);
+    let buckets = qscore_bars(histogram, max_bars);
+    let (Some(first), Some(last)) = (buckets.first(), buckets.last()) else {
+        return;
+    };
+    let passing = histogram.fraction_passing(cutoff).unwrap_or(0.0);
+    let summary = format!(
+        " Q{:.0}-{:.0} · cutoff Q{} · {:.1}% of reads pass ",
+        first.0,
+        last.1,
+        cutoff,
+        passing * 100.0
+    );
+    let max_count = buckets.iter().map(|b| b.2).max().unwrap_or(0).max(1);
+
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let bar_width = (inner_width / buckets.len()).saturating_sub(1).clamp(1, 4) as u16;
+    let cutoff_bar = buckets
+        .iter()
+        .position(|(start, end, _)| (*start as f64) <= cutoff && cutoff < *end as f64);
+
+    let bars: Vec<Bar> = buckets
+        .iter()
+        .enumerate()
+        .map(|(i, &(start, end, count))| {
+            let inspected = cursor.is_some_and(|(&(q, _), _)| start <= q && q < end);
+            let color = if inspected {
+                t.warning
+            } else if (start as f64) < cutoff {
+                t.chart_failed
+            } else {
+                t.chart_passed
+            };
+            let label = if Some(i) == cutoff_bar { "▲" } else { "" };
+            Bar::default()
+                .value(count)
+                .label(Line::from(label).style(Style::default().fg(t.warning)))
+                .text_value(String::new())
+                .style(Style::default().fg(color))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .title(title)
+                .title_bottom(Line::from(summary).alignment(Alignment::Center))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.border)),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .max(max_count);
+
+    frame.render_widget(chart, area);
+}
+
+/// Buckets of `histogram` as `(start, end, count)`, without the empty
+/// buckets at either end, merged in runs so at most `max_bars` remain.
+fn qscore_bars(histogram: &QScoreHistogram, max_bars: usize) -> Vec<(f32, f32, u64)> {
+    let buckets: Vec<_> = histogram
+        .bucket_ranges
+        .iter()
+        .zip(&histogram.bucket_values)
+        .map(|(&(start, end), &count)| (start, end, count))
+        .collect();
+    let first = buckets.iter().position(|b| b.2 > 0);
+    let last = buckets.iter().rposition(|b| b.2 > 0);
+    let (Some(first), Some(last)) = (first, last) else {
+        return Vec::new();
+    };
+    let buckets = &buckets[first..=last];
+
+    let per_bar = buckets.len().div_ceil(max_bars.max(1));
+    buckets
+        .chunks(per_bar)
+        .map(|run| {
+            let count = run.iter().map(|b| b.2).sum();
+            (run[0].0, run[run.len() - 1].1, count)
+        })
+        .collect()
+}
+
+fn render_pore_activity(
+    frame: &mut Frame,
+    app: &App,
+    position_name: &str,
+    area: Rect,
+    hits: &mut HitMap,
+) {
+    let t = &app.theme;
+    // In time-lapse playback the map and counts show a recorded frame.
+    let timelapse = app.timelapse_frame();
+    let channel_states = match &timelapse {
+        Some(frame) => Some(&frame.states),
+        None => app.channel_states.get(position_name),
+    };
+    let channel_layout = app.channel_layouts.get(position_name);
+    let title = " Pore Activity [3] ";
+
+    let channel_states = match channel_states {
+        Some(cs) if !cs.states.is_empty() => cs,
+        _ => {
+            let placeholder = Paragraph::new("Waiting for channel data...")
+                .style(Style::default().fg(t.text_dim))
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .title(title)
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(t.warning)),
+                );
+            frame.render_widget(placeholder, area);
+            return;
+        }
+    };
+
+    // On narrow terminals the state counts go below the map instead of
+    // beside it, next to the channel inspector, leaving the map most rows.
+    let stacked = area.width < COMPACT_WIDTH;
+    let chunks = if stacked {
+        let counts_height = (area.height / 3).min(11);
+        Layout::vertical([Constraint::Min(0), Constraint::Length(counts_height)]).split(area)
+    } else {
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).split(area)
+    };
+
+    // Time-lapse frames only have states.
+    let occupancy = app
+        .duty_time
+        .get(position_name)
+        .map(|duty| duty.pore_occupancy.as_slice())
+        .filter(|occupancy| app.occupancy_colors && timelapse.is_none() && !occupancy.is_empty());
+    render_pore_grid_from_states(
+        frame,
+        t,
+        channel_states,
+        channel_layout,
+        occupancy,
+        chunks[0],
+        app.channel_map_scroll_offset,
+        app.channel_cursor,
+        hits,
+    );
+    if let Some(playback) = &timelapse {
+        // Frames are only taken while the detail view is open.
+        let gap = playback
+            .gap
+            .map(|gap| {
+                format!(
+                    " · {} not viewed before",
+                    format_time_label(gap.num_seconds() as f64)
+                )
+            })
+            .unwrap_or_default();
+        let label = format!(
+            " ⏪ {} · {}/{}{} ",
+            app.config.tui.timezone.format(playback.at, "%a %H:%M"),
+            playback.index + 1,
+            playback.frames,
+            gap
+        );
+        let map = chunks[0];
+        if map.height > 1 && map.width > 4 {
+            let label_area = Rect::new(map.x + 2, map.bottom() - 1, map.width - 4, 1);
+            frame.render_widget(
+                Paragraph::new(label).style(Style::default().fg(t.warning).bold()),
+                label_area,
+            );
+        }
+    }
+    match app.channel_cursor {
+        Some(channel) => {
+            let side = if stacked {
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[1])
+            } else {
+                Layout::vertical([Constraint::Length(11), Constraint::Min(0)]).split(chunks[1])
+            };
+            match occupancy {
+                Some(occupancy) => render_occupancy_counts(frame, t, occupancy, side[0]),
+                None => render_state_counts(frame, t, channel_states, side[0]),
+            }
+            let occupancy = app.channel_occupancy.get(position_name);
+            render_channel_inspector(
+                frame,
+                t,
+                channel_states,
+                occupancy,
+                channel_layout,
+                channel,
+                side[1],
+            );
+        }
+        None => match occupancy {
+            Some(occupancy) => render_occupancy_counts(frame, t, occupancy, chunks[1]),
+            None => render_state_counts(frame, t, channel_states, chunks[1]),
+        },
+    }
+}
+
+/// Details of the channel under the inspection cursor.
+fn render_channel_inspector(
     frame: &mut Frame,
     t: &Theme,
-    channel_states: Option<&ChannelStatesSnapshot>,
+    channel_states: &ChannelStatesSnapshot,
+    occupancy: Option<&ChannelOccupancy>,
     channel_layout: Option<&ChannelLayout>,
+    channel: usize,
     area: Rect,
-    scroll_offset: usize,
 ) {
-    let title = " Pore Activity [3] ";
+    let label = Style::default().fg(t.text_dim);
+    let state = channel_states
+        .states
+        .get(channel)
+        .map(String::as_str)
+        .unwrap_or("unknown");
+    let (_, state_color) = state_to_symbol(t, state);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Channel ", label),
+            Span::styled(format!("{}", channel + 1), Style::default().bold()),
+        ]),
+        Line::from(vec![
+            Span::styled("State   ", label),
+            Span::styled(state.to_string(), Style::default().fg(state_color)),
+        ]),
+    ];
+    if let Some(&(x, y)) = channel_layout.and_then(|layout| layout.coords.get(channel)) {
+        lines.push(Line::from(vec![
+            Span::styled("Grid    ", label),
+            Span::raw(format!("x {}, y {}", x, y)),
+        ]));
+    }
 
-    let channel_states = match channel_states {
-        Some(cs) if !cs.states.is_empty() => cs,
-        _ => {
-            let placeholder = Paragraph::new("Waiting for channel data...")
-                .style(Style::default().fg(t.text_dim))
-                .alignment(Alignment::Center)
-                .block(
-                    Block::default()
-                        .title(title)
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(t.warning)),
-                );
-            frame.render_widget(placeholder, area);
-            return;
+    let recent = occupancy.map(|o| o.recent(channel)).unwrap_or_default();
+    match occupancy.and_then(|o| o.fraction(channel)) {
+        Some(fraction) => {
+            lines.push(Line::from(vec![
+                Span::styled("In strand ", label),
+                Span::styled(format!("{:.0}%", fraction * 100.0), Style::default().bold()),
+                Span::styled(format!(" of last {} updates", recent.len()), label),
+            ]));
+            let marks: Vec<Span> = recent
+                .iter()
+                .map(|&sequencing| {
+                    if sequencing {
+                        Span::styled("▮", Style::default().fg(t.channel_sequencing))
+                    } else {
+                        Span::styled("▯", Style::default().fg(t.text_dim))
+                    }
+                })
+                .collect();
+            lines.push(Line::from(marks));
         }
-    };
-
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-        .split(area);
+        None => lines.push(Line::from(Span::styled("No occupancy data yet", label))),
+    }
 
-    render_pore_grid_from_states(
-        frame,
-        t,
-        channel_states,
-        channel_layout,
-        chunks[0],
-        scroll_offset,
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Channel ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_highlight)),
     );
-    render_state_counts(frame, t, channel_states, chunks[1]);
+    frame.render_widget(panel, area);
 }
 
 /// Maps original PromethION coordinates (x, y) to vertical layout display coordinates (display_row, display_col).
@@ -980,13 +3822,19 @@ fn map_to_vertical_layout(x: usize, y: usize) -> (usize, usize) {
     }
 }
 
+/// Draws the channel map, coloring each channel by its state or, with
+/// `occupancy`, by its pore occupancy over the run.
+#[allow(clippy::too_many_arguments)]
 fn render_pore_grid_from_states(
     frame: &mut Frame,
     t: &Theme,
     channel_states: &ChannelStatesSnapshot,
     channel_layout: Option<&ChannelLayout>,
+    occupancy: Option<&[f32]>,
     area: Rect,
     scroll_offset: usize,
+    cursor: Option<usize>,
+    hits: &mut HitMap,
 ) {
     let screen_width = area.width as usize;
     let screen_height = area.height as usize;
@@ -1003,13 +3851,33 @@ fn render_pore_grid_from_states(
         (total_channels as f64).sqrt().ceil() as usize
     };
 
+    // Scroll the inspected channel into view.
+    let cursor_row = cursor.zip(channel_layout).and_then(|(channel, layout)| {
+        let &(x, y) = layout.coords.get(channel)?;
+        let gs = calculate_grid_structure(layout, screen_width, screen_height);
+        (gs.block_arrangement == BlockArrangement::FourVertical)
+            .then(|| map_to_vertical_layout(x as usize, y as usize).0)
+    });
+    let scroll_offset = match cursor_row {
+        Some(row) if row < scroll_offset => row,
+        Some(row) if visible_rows > 0 && row >= scroll_offset + visible_rows => {
+            row + 1 - visible_rows
+        }
+        _ => scroll_offset,
+    };
+
     // Build title with scroll indicator if scrolling is active
+    let name = if occupancy.is_some() {
+        "Channel Map · Occupancy"
+    } else {
+        "Channel Map"
+    };
     let title = if total_rows > visible_rows {
         let start_row = scroll_offset + 1; // 1-indexed for users
         let end_row = (scroll_offset + visible_rows).min(total_rows);
-        format!(" Channel Map [{}-{}/{}] ", start_row, end_row, total_rows)
+        format!(" {} [{}-{}/{}] ", name, start_row, end_row, total_rows)
     } else {
-        " Channel Map ".to_string()
+        format!(" {} ", name)
     };
 
     let block = Block::default()
@@ -1161,6 +4029,8 @@ fn render_pore_grid_from_states(
 
         let padding = " ".repeat(offset_x);
         let mut spans: Vec<Span> = vec![Span::raw(padding)];
+        let cell_y = inner_area.y + lines.len() as u16;
+        let mut cell_x = inner_area.x + offset_x as u16;
 
         let mut grid_col = 0usize;
         for display_col in 0..display_cols {
@@ -1175,6 +4045,7 @@ fn render_pore_grid_from_states(
 
             if is_gap_col {
                 let gap_symbol = " ".repeat(cell_char_width);
+                cell_x += cell_char_width as u16;
                 spans.push(Span::raw(gap_symbol));
                 continue;
             }
@@ -1195,14 +4066,28 @@ fn render_pore_grid_from_states(
 
             let (symbol, color) = match channel_idx {
                 Some(idx) if idx < channel_states.states.len() => {
-                    state_to_symbol(t, &channel_states.states[idx])
+                    match occupancy.and_then(|occupancy| occupancy.get(idx)) {
+                        Some(&value) => occupancy_to_symbol(t, value),
+                        None => state_to_symbol(t, &channel_states.states[idx]),
+                    }
                 }
                 _ => {
                     let empty_symbol = if cell_char_width == 2 { "  " } else { " " };
                     (empty_symbol, t.channel_empty)
                 }
             };
-            spans.push(Span::styled(symbol, Style::default().fg(color)));
+            let span = if channel_idx.is_some() && channel_idx == cursor {
+                Span::styled("[]", Style::default().fg(t.selection_fg).bg(color).bold())
+            } else {
+                Span::styled(symbol, Style::default().fg(color))
+            };
+            let width = span.width() as u16;
+            if let Some(idx) = channel_idx {
+                let cell = Rect::new(cell_x, cell_y, width, 1).intersection(inner_area);
+                hits.add(cell, MouseTarget::Channel(idx));
+            }
+            cell_x += width;
+            spans.push(span);
 
             grid_col += 1;
         }
@@ -1250,14 +4135,24 @@ fn render_state_counts(
     let other = total.saturating_sub(sequencing + pore_available + unavailable + inactive);
 
     let categories = [
-        ("Sequencing", sequencing, t.channel_sequencing),
-        ("Pore Available", pore_available, t.channel_pore),
-        ("Unavailable", unavailable, t.channel_unavailable),
-        ("Inactive", inactive, t.channel_inactive),
-        ("Other", other, t.channel_other),
+        (
+            "Sequencing",
+            sequencing,
+            CELL_SEQUENCING,
+            t.channel_sequencing,
+        ),
+        ("Pore Available", pore_available, CELL_PORE, t.channel_pore),
+        (
+            "Unavailable",
+            unavailable,
+            CELL_UNAVAILABLE,
+            t.channel_unavailable,
+        ),
+        ("Inactive", inactive, CELL_INACTIVE, t.channel_inactive),
+        ("Other", other, CELL_OTHER, t.channel_other),
     ];
 
-    for (label, count, color) in &categories {
+    for (label, count, cell, color) in &categories {
         let percent = if total > 0 {
             (*count as f64 / total as f64) * 100.0
         } else {
@@ -1265,7 +4160,7 @@ fn render_state_counts(
         };
 
         lines.push(Line::from(vec![
-            Span::styled("● ", Style::default().fg(*color)),
+            Span::styled(format!("{} ", cell), Style::default().fg(*color)),
             Span::styled(format!("{:>4}", count), Style::default().bold()),
             Span::styled(format!(" {:14}", label), Style::default()),
             Span::styled(format!("{:5.1}%", percent), Style::default().fg(t.text_dim)),
@@ -1289,24 +4184,138 @@ fn render_state_counts(
     frame.render_widget(breakdown, area);
 }
 
+/// Channel map cells of the occupancy coloring, from the lowest quarter of
+/// occupancy to the highest.
+const OCCUPANCY_CELLS: [&str; 4] = ["░░", "▒▒", "▓▓", "██"];
+
+/// Quarter of the 0.0-1.0 occupancy range `value` falls in.
+fn occupancy_band(value: f32) -> usize {
+    ((value.clamp(0.0, 1.0) * 4.0) as usize).min(3)
+}
+
+fn occupancy_colors(t: &Theme) -> [Color; 4] {
+    [t.error, t.warning, t.info, t.success]
+}
+
+fn occupancy_to_symbol(t: &Theme, value: f32) -> (&'static str, Color) {
+    let band = occupancy_band(value);
+    (OCCUPANCY_CELLS[band], occupancy_colors(t)[band])
+}
+
+/// Legend of the occupancy coloring, counting the channels in each band.
+fn render_occupancy_counts(frame: &mut Frame, t: &Theme, occupancy: &[f32], area: Rect) {
+    let total = occupancy.len();
+    let mut counts = [0usize; 4];
+    for &value in occupancy {
+        counts[occupancy_band(value)] += 1;
+    }
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(Span::styled("Pore Occupancy", Style::default().bold())),
+        Line::from(""),
+    ];
+    let colors = occupancy_colors(t);
+    for band in (0..4).rev() {
+        let label = format!("{}-{}%", band * 25, (band + 1) * 25);
+        let percent = if total > 0 {
+            (counts[band] as f64 / total as f64) * 100.0
+        } else {
+            0.0
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", OCCUPANCY_CELLS[band]),
+                Style::default().fg(colors[band]),
+            ),
+            Span::styled(format!("{:>4}", counts[band]), Style::default().bold()),
+            Span::styled(format!(" {:14}", label), Style::default()),
+            Span::styled(format!("{:5.1}%", percent), Style::default().fg(t.text_dim)),
+        ]));
+    }
+
+    let mean = occupancy.iter().sum::<f32>() / total.max(1) as f32;
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("Mean: ", Style::default().fg(t.text_dim)),
+        Span::styled(format!("{:.0}%", mean * 100.0), Style::default().bold()),
+        Span::raw(format!(" of {} channels", total)),
+    ]));
+
+    let breakdown = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Statistics ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.warning)),
+    );
+
+    frame.render_widget(breakdown, area);
+}
+
+/// Channel map cell of each channel state category. Every category has its
+/// own pattern, so the map reads without telling the colors apart.
+const CELL_SEQUENCING: &str = "██";
+const CELL_PORE: &str = "▓▓";
+const CELL_UNAVAILABLE: &str = "╳╳";
+const CELL_INACTIVE: &str = "··";
+const CELL_ADAPTER: &str = "▚▚";
+const CELL_UNBLOCK: &str = "▒▒";
+const CELL_OTHER: &str = "░░";
+
 fn state_to_symbol(t: &Theme, state: &str) -> (&'static str, Color) {
     let s = state.to_lowercase();
     if s.contains("strand") || s.contains("sequencing") {
-        ("██", t.channel_sequencing)
+        (CELL_SEQUENCING, t.channel_sequencing)
     } else if s.contains("pore") || s.contains("single") {
-        ("██", t.channel_pore)
+        (CELL_PORE, t.channel_pore)
     } else if s.contains("unavailable") || s.contains("saturated") {
-        ("░░", t.channel_unavailable)
+        (CELL_UNAVAILABLE, t.channel_unavailable)
     } else if s.contains("inactive") || s.contains("zero") || s.contains("multiple") {
-        ("░░", t.channel_inactive)
+        (CELL_INACTIVE, t.channel_inactive)
     } else if s.contains("adapter") || s.contains("event") {
-        ("▓▓", t.channel_adapter)
+        (CELL_ADAPTER, t.channel_adapter)
     } else if s.contains("unblock") {
-        ("▒▒", t.channel_adapter)
+        (CELL_UNBLOCK, t.channel_adapter)
     } else if s.is_empty() || s == "unknown" {
         ("  ", t.channel_empty)
     } else {
-        ("░░", t.channel_other)
+        (CELL_OTHER, t.channel_other)
+    }
+}
+
+/// Labels for both ends of a time axis from `from` to `to` seconds into the
+/// acquisition: clock times in clock mode once the start is known,
+/// otherwise run time, with a `full` run counted from zero.
+fn time_axis_labels(
+    app: &App,
+    position_name: &str,
+    (from, to): (f64, f64),
+    full: bool,
+) -> (String, String) {
+    if app.clock_time {
+        if let (Some(start), Some(end)) = (
+            app.clock_at(position_name, from),
+            app.clock_at(position_name, to),
+        ) {
+            let tz = &app.config.tui.timezone;
+            return (tz.format(start, "%H:%M"), tz.format(end, "%H:%M"));
+        }
+    }
+    if full {
+        ("0".to_string(), format_time_label(to - from))
+    } else {
+        (format_time_label(from), format_time_label(to))
+    }
+}
+
+/// Time of a chart point `seconds` into the acquisition, as a clock time in
+/// clock mode or as run time.
+fn point_time_label(app: &App, position_name: &str, seconds: u32) -> String {
+    match app
+        .clock_at(position_name, seconds.into())
+        .filter(|_| app.clock_time)
+    {
+        Some(ts) => app.config.tui.timezone.format(ts, "%H:%M:%S"),
+        None => format_duration(chrono::Duration::seconds(seconds.into())),
     }
 }
 
@@ -1320,13 +4329,53 @@ fn format_time_label(seconds: f64) -> String {
     }
 }
 
-fn render_help_overlay(frame: &mut Frame, t: &Theme, area: Rect) {
+/// Draws the key reference. In read-only mode the run control keys are
+/// replaced by why they are unavailable.
+fn render_help_overlay(frame: &mut Frame, t: &Theme, read_only: Option<&str>, area: Rect) {
     let key_style = Style::default().fg(t.key_hint).bold();
     let desc_style = Style::default().fg(t.text);
     let section_style = Style::default().fg(t.text_title).bold();
     let dim_style = Style::default().fg(t.text_dim);
 
-    let help_text = vec![
+    let run_control = match read_only {
+        Some(reason) => vec![
+            Line::from(Span::styled(
+                "  Read-only: MinKNOW refused run control",
+                Style::default().fg(t.warning).bold(),
+            )),
+            Line::from(Span::styled(format!("  ({})", reason), dim_style)),
+            Line::from(Span::styled(
+                "  No local authentication token was found, so Termion",
+                desc_style,
+            )),
+            Line::from(Span::styled(
+                "  connected as a guest. Run it as a user who can read",
+                desc_style,
+            )),
+            Line::from(Span::styled(
+                "  MinKNOW's token file to start, pause or stop runs.",
+                desc_style,
+            )),
+            Line::from(vec![
+                Span::styled("  R ", key_style),
+                Span::styled("Refresh", desc_style),
+            ]),
+        ],
+        None => vec![Line::from(vec![
+            Span::styled("  n ", key_style),
+            Span::styled("Start", desc_style),
+            Span::styled("   p ", key_style),
+            Span::styled("Pause", desc_style),
+            Span::styled("   r ", key_style),
+            Span::styled("Resume", desc_style),
+            Span::styled("   s ", key_style),
+            Span::styled("Stop", desc_style),
+            Span::styled("   R ", key_style),
+            Span::styled("Refresh", desc_style),
+        ])],
+    };
+
+    let mut help_text = vec![
         Line::from(""),
         Line::from(vec![
             Span::styled("─── ", dim_style),
@@ -1341,6 +4390,34 @@ fn render_help_overlay(frame: &mut Frame, t: &Theme, area: Rect) {
             Span::styled("Select", desc_style),
             Span::styled("   Esc ", key_style),
             Span::styled("Back", desc_style),
+            Span::styled("   H ", key_style),
+            Span::styled("History", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  S ", key_style),
+            Span::styled("Sort positions", desc_style),
+            Span::styled("   I ", key_style),
+            Span::styled("Reverse sort", desc_style),
+            Span::styled("   / ", key_style),
+            Span::styled("Filter", desc_style),
+            Span::styled("   f ", key_style),
+            Span::styled("Pin", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  m ", key_style),
+            Span::styled("Hide simulated", desc_style),
+            Span::styled("   i ", key_style),
+            Span::styled("Hide idle", desc_style),
+            Span::styled("   [ ] ", key_style),
+            Span::styled("Previous/next run", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  y ", key_style),
+            Span::styled("Copy run ID", desc_style),
+            Span::styled("   Y ", key_style),
+            Span::styled("Copy summary", desc_style),
+            Span::styled("   Space ", key_style),
+            Span::styled("Freeze", desc_style),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -1356,10 +4433,58 @@ fn render_help_overlay(frame: &mut Frame, t: &Theme, area: Rect) {
             Span::styled("Read Length", desc_style),
             Span::styled("   3 ", key_style),
             Span::styled("Pore Activity", desc_style),
+            Span::styled("   4 ", key_style),
+            Span::styled("Barcodes", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  5 ", key_style),
+            Span::styled("Raw Signal", desc_style),
+            Span::styled("   6 ", key_style),
+            Span::styled("Q-Score", desc_style),
+            Span::styled("   7 ", key_style),
+            Span::styled("Pore Scans", desc_style),
+            Span::styled("   8 ", key_style),
+            Span::styled("Active Pores", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  9 ", key_style),
+            Span::styled("Duty Time", desc_style),
+            Span::styled("   e ", key_style),
+            Span::styled("Yield projection", desc_style),
+            Span::styled("   w ", key_style),
+            Span::styled("Length by bases", desc_style),
         ]),
         Line::from(vec![
             Span::styled("  Tab ", key_style),
             Span::styled("Cycle charts", desc_style),
+            Span::styled("   b ", key_style),
+            Span::styled("Sort barcodes", desc_style),
+            Span::styled("   c ", key_style),
+            Span::styled("Inspect chart", desc_style),
+            Span::styled("   v ", key_style),
+            Span::styled("Yield window", desc_style),
+            Span::styled("   a ", key_style),
+            Span::styled("Dual axis", desc_style),
+            Span::styled("   g ", key_style),
+            Span::styled("Rate", desc_style),
+            Span::styled("   x ", key_style),
+            Span::styled("Export", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  C ", key_style),
+            Span::styled("Clock times on time axes and the overview", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  L ", key_style),
+            Span::styled("Legend", desc_style),
+            Span::styled("   A/P/F ", key_style),
+            Span::styled("Show/hide total, passed, failed", desc_style),
+        ]),
+        Line::from(vec![
+            Span::styled("  , . ", key_style),
+            Span::styled("Pore time-lapse, taken while the view is open", desc_style),
+            Span::styled("   O ", key_style),
+            Span::styled("Occupancy", desc_style),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -1368,29 +4493,30 @@ fn render_help_overlay(frame: &mut Frame, t: &Theme, area: Rect) {
             Span::styled(" ───", dim_style),
         ]),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  p ", key_style),
-            Span::styled("Pause", desc_style),
-            Span::styled("   r ", key_style),
-            Span::styled("Resume", desc_style),
-            Span::styled("   s ", key_style),
-            Span::styled("Stop", desc_style),
-            Span::styled("   R ", key_style),
-            Span::styled("Refresh", desc_style),
-        ]),
+    ];
+    help_text.extend(run_control);
+    help_text.extend([
         Line::from(""),
         Line::from(vec![Span::styled("───────────────────────", dim_style)]),
         Line::from(""),
         Line::from(vec![
             Span::styled("  T ", key_style),
             Span::styled("Theme", desc_style),
+            Span::styled("   d ", key_style),
+            Span::styled("Diagnose", desc_style),
+            Span::styled("   D ", key_style),
+            Span::styled("Debug", desc_style),
+            Span::styled("   E ", key_style),
+            Span::styled("Errors", desc_style),
+            Span::styled("   N ", key_style),
+            Span::styled("Note", desc_style),
             Span::styled("   ? ", key_style),
             Span::styled("Help", desc_style),
             Span::styled("   q ", key_style),
             Span::styled("Quit", desc_style),
         ]),
         Line::from(""),
-    ];
+    ]);
 
     let help = Paragraph::new(help_text)
         .alignment(Alignment::Center)
@@ -1429,34 +4555,347 @@ fn render_error_overlay(frame: &mut Frame, t: &Theme, message: &str, area: Rect)
     frame.render_widget(error, area);
 }
 
-fn render_range_input_overlay(frame: &mut Frame, t: &Theme, max_input: &str, area: Rect) {
-    let max_display = if max_input.is_empty() {
-        "(empty = full range)".to_string()
-    } else {
-        format!("{} bp", max_input)
-    };
+fn render_diagnostics_overlay(frame: &mut Frame, t: &Theme, results: &[CheckResult], area: Rect) {
+    let mut lines = vec![Line::from("")];
+    for result in results {
+        let (symbol, color) = match result.status {
+            CheckStatus::Pass => ("✔", t.success),
+            CheckStatus::Warn => ("!", t.warning),
+            CheckStatus::Fail => ("✖", t.error),
+            CheckStatus::Skipped => ("–", t.text_dim),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", symbol), Style::default().fg(color).bold()),
+            Span::styled(
+                format!("{:<16}", result.name),
+                Style::default().fg(t.text).bold(),
+            ),
+            Span::styled(result.detail.clone(), Style::default().fg(t.text_dim)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  [Esc] Close  [d] Re-run",
+        Style::default().fg(t.text_dim),
+    )));
+
+    let diagnostics = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(" Connection Checks ")
+            .title_style(Style::default().fg(t.text_title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_dim))
+            .style(Style::default().bg(t.background)),
+    );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(diagnostics, area);
+}
+
+fn render_debug_overlay(frame: &mut Frame, t: &Theme, perf: &PerfStats, area: Rect) {
+    let label = Style::default().fg(t.text_dim);
+    let value = Style::default().fg(t.text);
+    let header = Style::default().fg(t.text_title).bold();
+    let ms = |d: Duration| format!("{:.1} ms", d.as_secs_f64() * 1000.0);
 
-    let content = vec![
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("  Frame  ", label),
+            Span::styled(
+                format!(
+                    "last {}  mean {}  max {}  ({} drawn)",
+                    ms(perf.last_frame),
+                    ms(perf.mean_frame),
+                    ms(perf.max_frame),
+                    perf.frames
+                ),
+                value,
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("  Bus    ", label),
+            Span::styled(format!("{} queued  ", perf.bus_backlog), value),
+            Span::styled(
+                format!("{} bus events dropped", perf.bus_dropped),
+                Style::default().fg(if perf.bus_dropped > 0 {
+                    t.warning
+                } else {
+                    t.text
+                }),
+            ),
+        ]),
+        Line::from(""),
         Line::from(Span::styled(
-            "Set Max Read Length",
-            Style::default().bold().fg(t.text_title),
+            format!(
+                "  {:<28} {:>7} {:>6} {:>9} {:>9} {:>9} {:>9}",
+                "RPC", "Calls", "Errors", "Mean", "p50", "p95", "Max"
+            ),
+            header,
         )),
+    ];
+    let rows = area.height.saturating_sub(lines.len() as u16 + 4) as usize;
+    if perf.rpc.is_empty() {
+        lines.push(Line::from(Span::styled("  No RPCs made yet", label)));
+    }
+    for rpc in perf.rpc.iter().take(rows) {
+        let method: String = rpc.method.chars().take(28).collect();
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<28} {:>7} ", method, rpc.calls), value),
+            Span::styled(
+                format!("{:>6}", rpc.errors),
+                Style::default().fg(if rpc.errors > 0 { t.warning } else { t.text }),
+            ),
+            Span::styled(
+                format!(
+                    " {:>9} {:>9} {:>9} {:>9}",
+                    ms(rpc.mean),
+                    ms(rpc.p50),
+                    ms(rpc.p95),
+                    ms(rpc.max)
+                ),
+                value,
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("  [Esc] Close  [D] Toggle", label)));
+
+    let debug = Paragraph::new(lines).block(
+        Block::default()
+            .title(" Debug ")
+            .title_style(Style::default().fg(t.text_title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_dim))
+            .style(Style::default().bg(t.background)),
+    );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(debug, area);
+}
+
+/// Lists logged errors, newest first, from `scroll` entries down.
+fn render_error_log(frame: &mut Frame, app: &App, scroll: usize, area: Rect) {
+    let t = &app.theme;
+    let label = Style::default().fg(t.text_dim);
+    let tz = &app.config.tui.timezone;
+
+    let mut lines = vec![Line::from("")];
+    let rows = area.height.saturating_sub(5) as usize;
+    if app.errors.is_empty() {
+        lines.push(Line::from(Span::styled("  No errors so far", label)));
+    }
+    for error in app.errors.iter().rev().skip(scroll).take(rows) {
+        let mut spans = vec![
+            Span::styled(format!("  {}  ", tz.format(error.at, "%H:%M:%S")), label),
+            Span::styled(
+                format!("{:<8}", error.position.as_deref().unwrap_or("")),
+                Style::default().fg(t.text_title),
+            ),
+            Span::styled(error.message.clone(), Style::default().fg(t.text)),
+        ];
+        if error.count > 1 {
+            spans.push(Span::styled(
+                format!("  ×{}", error.count),
+                Style::default().fg(t.warning),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "  [↑↓] Scroll  [Esc] Close  [E] Toggle",
+        label,
+    )));
+
+    let title = match app.errors.len() {
+        0 => " Errors ".to_string(),
+        n => format!(" Errors ({}/{}) ", scroll.min(n - 1) + 1, n),
+    };
+    let log = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .title_style(Style::default().fg(t.text_title).bold())
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.border_dim))
+            .style(Style::default().bg(t.background)),
+    );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(log, area);
+}
+
+fn render_note_overlay(frame: &mut Frame, t: &Theme, position_name: &str, text: &str, area: Rect) {
+    let key = Style::default().fg(t.key_hint).bold();
+    let dim = Style::default().fg(t.text_dim);
+
+    let lines = vec![
         Line::from(""),
         Line::from(vec![
-            Span::styled("Max: ", Style::default().fg(t.text_dim)),
-            Span::styled(max_display, Style::default().fg(t.key_hint).bold()),
+            Span::styled(text, Style::default().fg(t.text)),
             Span::styled("_", Style::default().fg(t.key_hint)),
         ]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled("[Enter] ", key),
+            Span::styled("Save", dim),
+            Span::raw("  "),
+            Span::styled("[Esc] ", dim.bold()),
+            Span::styled("Cancel", dim),
+            Span::styled(
+                format!("  ({}/{})", text.chars().count(), MAX_NOTE_LEN),
+                dim,
+            ),
+        ]),
+    ];
+
+    let dialog = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(format!(" Note · {} ", position_name))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(t.special))
+                .style(Style::default().bg(t.background)),
+        );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(dialog, area);
+}
+
+fn render_range_input_overlay(frame: &mut Frame, t: &Theme, form: &HistogramForm, area: Rect) {
+    let key = Style::default().fg(t.key_hint).bold();
+    let dim = Style::default().fg(t.text_dim);
+
+    let mut lines = vec![
         Line::from(Span::styled(
-            "Type number | ↑/↓ ±1000 | Enter | Esc",
-            Style::default().fg(t.text_dim),
+            "Read Length Histogram",
+            Style::default().bold().fg(t.text_title),
+        )),
+        Line::from(""),
+    ];
+    for (field, label, value, unit, empty) in [
+        (
+            HistogramField::MinLength,
+            "Min: ",
+            &form.min_length,
+            " bp",
+            "0",
+        ),
+        (
+            HistogramField::MaxLength,
+            "Max: ",
+            &form.max_length,
+            " bp",
+            "full range",
+        ),
+        (
+            HistogramField::Buckets,
+            "Buckets: ",
+            &form.buckets,
+            "",
+            "auto",
+        ),
+        (
+            HistogramField::OutlierPercent,
+            "Outliers: ",
+            &form.outlier_percent,
+            " %",
+            "default",
+        ),
+    ] {
+        let mut spans = vec![Span::styled(label, dim), Span::styled(value.clone(), key)];
+        if field == form.field {
+            spans.push(Span::styled("_", Style::default().fg(t.key_hint)));
+        }
+        if value.is_empty() {
+            spans.push(Span::styled(format!(" ({})", empty), dim));
+        } else {
+            spans.push(Span::styled(unit, dim));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    lines.push(if form.invalid {
+        Line::from(Span::styled(
+            format!(
+                "Min < max, buckets 2-{}, outliers ≤ 50%",
+                MAX_HISTOGRAM_BUCKETS
+            ),
+            Style::default().fg(t.error),
+        ))
+    } else {
+        Line::from(Span::styled("↑/↓ adjust the field", dim))
+    });
+    lines.push(Line::from(vec![
+        Span::styled("[Tab] ", key),
+        Span::styled("Next field", dim),
+        Span::raw("  "),
+        Span::styled("[Enter] ", key),
+        Span::styled("Apply", dim),
+        Span::raw("  "),
+        Span::styled("[Esc] ", dim.bold()),
+        Span::styled("Cancel", dim),
+    ]));
+
+    let dialog = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title(" Histogram ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.special))
+            .style(Style::default().bg(t.background)),
+    );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(dialog, area);
+}
+
+fn render_run_until_overlay(frame: &mut Frame, t: &Theme, form: &RunUntilForm, area: Rect) {
+    let key = Style::default().fg(t.key_hint).bold();
+    let dim = Style::default().fg(t.text_dim);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Stop the run after",
+            Style::default().bold().fg(t.text_title),
         )),
+        Line::from(""),
     ];
+    for (field, label, value, unit) in [
+        (RunUntilField::Hours, "Runtime: ", &form.hours, " h"),
+        (RunUntilField::Gigabases, "Yield: ", &form.gigabases, " Gb"),
+    ] {
+        let mut spans = vec![Span::styled(label, dim), Span::styled(value.clone(), key)];
+        if field == form.field {
+            spans.push(Span::styled("_", Style::default().fg(t.key_hint)));
+        }
+        spans.push(Span::styled(unit, dim));
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+    lines.push(if form.invalid {
+        Line::from(Span::styled(
+            "Enter positive numbers",
+            Style::default().fg(t.error),
+        ))
+    } else {
+        Line::from(Span::styled("Empty fields clear the target", dim))
+    });
+    lines.push(Line::from(vec![
+        Span::styled("[Tab] ", key),
+        Span::styled("Next field", dim),
+        Span::raw("  "),
+        Span::styled("[Enter] ", key),
+        Span::styled("Save", dim),
+        Span::raw("  "),
+        Span::styled("[Esc] ", dim.bold()),
+        Span::styled("Cancel", dim),
+    ]));
 
-    let dialog = Paragraph::new(content).alignment(Alignment::Center).block(
+    let dialog = Paragraph::new(lines).alignment(Alignment::Center).block(
         Block::default()
-            .title(" Range ")
+            .title(format!(" Run Until · {} ", form.position_name))
             .borders(Borders::ALL)
             .border_style(Style::default().fg(t.special))
             .style(Style::default().bg(t.background)),
@@ -1471,15 +4910,16 @@ fn render_confirmation_overlay(
     t: &Theme,
     action: RunControlAction,
     position_name: &str,
+    typed: &str,
     area: Rect,
 ) {
     let (title_color, border_color) = match action {
-        RunControlAction::Stop => (t.error, t.error),
+        RunControlAction::Stop(_) => (t.error, t.error),
         RunControlAction::Pause => (t.warning, t.warning),
         RunControlAction::Resume => (t.success, t.success),
     };
 
-    let content = vec![
+    let mut content = vec![
         Line::from(Span::styled(
             format!("{} Run", action.label()),
             Style::default().bold().fg(title_color),
@@ -1492,14 +4932,58 @@ fn render_confirmation_overlay(
         Line::from(""),
         Line::from(action.confirmation_message()),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("[Enter] ", Style::default().fg(t.key_hint).bold()),
-            Span::styled("Confirm", Style::default()),
-            Span::raw("    "),
-            Span::styled("[Esc] ", Style::default().fg(t.text_dim).bold()),
-            Span::styled("Cancel", Style::default().fg(t.text_dim)),
-        ]),
     ];
+    if let RunControlAction::Stop(options) = action {
+        for data_action in [
+            StopDataAction::FinishProcessing,
+            StopDataAction::KeepAllData,
+        ] {
+            let (prefix, style) = if data_action == options.data_action {
+                ("► ", Style::default().fg(t.text).bold())
+            } else {
+                ("  ", Style::default().fg(t.text_dim))
+            };
+            content.push(Line::from(Span::styled(
+                format!("{}{}", prefix, data_action.label()),
+                style,
+            )));
+        }
+        content.push(Line::from(vec![
+            Span::styled("[↑↓] ", Style::default().fg(t.key_hint).bold()),
+            Span::styled("Choose", Style::default().fg(t.text_dim)),
+        ]));
+        content.push(Line::from(""));
+    }
+    // Enter only confirms once the position name or STOP is typed.
+    let confirm_style = if action.needs_typed_confirmation() {
+        content.push(Line::from(vec![
+            Span::styled("Type ", Style::default().fg(t.text_dim)),
+            Span::styled(position_name.to_string(), Style::default().bold()),
+            Span::styled(" or ", Style::default().fg(t.text_dim)),
+            Span::styled(STOP_CONFIRMATION, Style::default().bold()),
+            Span::styled(" to confirm", Style::default().fg(t.text_dim)),
+        ]));
+        content.push(Line::from(vec![
+            Span::styled("> ", Style::default().fg(t.text_dim)),
+            Span::styled(typed.to_string(), Style::default().fg(t.text_title).bold()),
+            Span::styled("█", Style::default().fg(t.text_dim)),
+        ]));
+        content.push(Line::from(""));
+        if confirmation_matches(typed, position_name) {
+            Style::default().fg(t.key_hint).bold()
+        } else {
+            Style::default().fg(t.text_dim).bold()
+        }
+    } else {
+        Style::default().fg(t.key_hint).bold()
+    };
+    content.push(Line::from(vec![
+        Span::styled("[Enter] ", confirm_style),
+        Span::styled("Confirm", Style::default()),
+        Span::raw("    "),
+        Span::styled("[Esc] ", Style::default().fg(t.text_dim).bold()),
+        Span::styled("Cancel", Style::default().fg(t.text_dim)),
+    ]));
 
     let dialog = Paragraph::new(content).alignment(Alignment::Center).block(
         Block::default()
@@ -1513,6 +4997,155 @@ fn render_confirmation_overlay(
     frame.render_widget(dialog, area);
 }
 
+fn render_start_wizard(frame: &mut Frame, t: &Theme, wizard: &StartWizard, area: Rect) {
+    let key = Style::default().fg(t.key_hint).bold();
+    let dim = Style::default().fg(t.text_dim);
+    let mut lines: Vec<Line> = Vec::new();
+
+    let hints: Vec<Span> = match wizard.step {
+        StartStep::SelectProtocol => {
+            lines.push(Line::from(Span::styled(
+                "Select Protocol",
+                Style::default().bold().fg(t.text_title),
+            )));
+            lines.push(Line::from(""));
+            match &wizard.protocols {
+                None => lines.push(Line::from(Span::styled("Loading protocols...", dim))),
+                Some(protocols) if protocols.is_empty() => {
+                    lines.push(Line::from(Span::styled("No protocols available", dim)))
+                }
+                Some(protocols) => {
+                    // Title, hints, and borders take eight rows; scroll to keep the selection visible.
+                    let visible = (area.height as usize).saturating_sub(8).max(1);
+                    let start = wizard.selected.saturating_sub(visible - 1);
+                    for (idx, protocol) in protocols.iter().enumerate().skip(start).take(visible) {
+                        let is_selected = idx == wizard.selected;
+                        let prefix = if is_selected { "► " } else { "  " };
+                        let style = if is_selected {
+                            Style::default().fg(t.text).bold()
+                        } else {
+                            dim
+                        };
+                        let tags: Vec<&str> = [&protocol.kit, &protocol.flow_cell]
+                            .into_iter()
+                            .flatten()
+                            .map(String::as_str)
+                            .collect();
+                        let mut spans =
+                            vec![Span::styled(format!("{}{}", prefix, protocol.name), style)];
+                        if !tags.is_empty() {
+                            spans.push(Span::styled(format!("  {}", tags.join(" · ")), dim));
+                        }
+                        lines.push(Line::from(spans));
+                    }
+                }
+            }
+            vec![
+                Span::styled("[↑↓] ", key),
+                Span::styled("Select", dim),
+                Span::raw("  "),
+                Span::styled("[Enter] ", key),
+                Span::styled("Next", dim),
+                Span::raw("  "),
+                Span::styled("[Esc] ", dim.bold()),
+                Span::styled("Cancel", dim),
+            ]
+        }
+        StartStep::Details => {
+            lines.push(Line::from(Span::styled(
+                "Run Details",
+                Style::default().bold().fg(t.text_title),
+            )));
+            lines.push(Line::from(""));
+            if let Some(protocol) = wizard.selected_protocol() {
+                lines.push(Line::from(Span::styled(protocol.name.clone(), dim)));
+                lines.push(Line::from(""));
+            }
+            for (field, label, value) in [
+                (StartField::SampleId, "Sample ID: ", &wizard.sample_id),
+                (
+                    StartField::ExperimentGroup,
+                    "Experiment group: ",
+                    &wizard.experiment_group,
+                ),
+            ] {
+                let mut spans = vec![
+                    Span::styled(label, dim),
+                    Span::styled(value.clone(), Style::default().fg(t.key_hint).bold()),
+                ];
+                if field == wizard.field {
+                    spans.push(Span::styled("_", Style::default().fg(t.key_hint)));
+                }
+                lines.push(Line::from(spans));
+            }
+            vec![
+                Span::styled("[Tab] ", key),
+                Span::styled("Next field", dim),
+                Span::raw("  "),
+                Span::styled("[Enter] ", key),
+                Span::styled("Next", dim),
+                Span::raw("  "),
+                Span::styled("[Esc] ", dim.bold()),
+                Span::styled("Back", dim),
+            ]
+        }
+        StartStep::Confirm => {
+            lines.push(Line::from(Span::styled(
+                "Start Run",
+                Style::default().bold().fg(t.success),
+            )));
+            lines.push(Line::from(""));
+            let or_default = |value: &str| {
+                if value.trim().is_empty() {
+                    "(MinKNOW default)".to_string()
+                } else {
+                    value.trim().to_string()
+                }
+            };
+            let protocol = wizard
+                .selected_protocol()
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            for (label, value) in [
+                ("Position: ", wizard.position_name.clone()),
+                ("Protocol: ", protocol),
+                ("Sample ID: ", or_default(&wizard.sample_id)),
+                ("Experiment group: ", or_default(&wizard.experiment_group)),
+            ] {
+                lines.push(Line::from(vec![
+                    Span::styled(label, dim),
+                    Span::styled(value, Style::default().fg(t.text_title)),
+                ]));
+            }
+            vec![
+                Span::styled("[Enter] ", key),
+                Span::styled("Start", Style::default()),
+                Span::raw("    "),
+                Span::styled("[Esc] ", dim.bold()),
+                Span::styled("Back", dim),
+            ]
+        }
+    };
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(hints));
+
+    let dialog = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .title(format!(
+                " Start Protocol · {} · Step {}/3 ",
+                wizard.position_name,
+                wizard.step.number()
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(t.success))
+            .style(Style::default().bg(t.background)),
+    );
+
+    frame.render_widget(ratatui::widgets::Clear, area);
+    frame.render_widget(dialog, area);
+}
+
 fn render_theme_selector(frame: &mut Frame, t: &Theme, selected: usize, area: Rect) {
     use super::theme::Theme as ThemeType;
 
@@ -1525,8 +5158,8 @@ fn render_theme_selector(frame: &mut Frame, t: &Theme, selected: usize, area: Re
         Line::from(""),
     ];
 
-    for (idx, &name) in themes.iter().enumerate() {
-        let display_name = match name {
+    for (idx, name) in themes.iter().enumerate() {
+        let display_name = match name.as_str() {
             "default" => "Default",
             "catppuccin" => "Catppuccin Mocha",
             "dracula" => "Dracula",
@@ -1534,6 +5167,9 @@ fn render_theme_selector(frame: &mut Frame, t: &Theme, selected: usize, area: Re
             "gruvbox" => "Gruvbox",
             "nord" => "Nord",
             "neon" => "Neon",
+            "color-blind" => "Color-blind Safe",
+            "solarized-light" => "Solarized Light",
+            "gruvbox-light" => "Gruvbox Light",
             other => other,
         };
 
@@ -1575,35 +5211,33 @@ fn render_theme_selector(frame: &mut Frame, t: &Theme, selected: usize, area: Re
     frame.render_widget(dialog, area);
 }
 
-fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Option<Rect> {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(area);
-
-    let popup_area = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1]);
+/// Smallest size of an overlay sized as a share of the screen, so it stays
+/// readable on small terminals.
+const MIN_OVERLAY_WIDTH: u16 = 56;
+const MIN_OVERLAY_HEIGHT: u16 = 18;
 
-    Some(popup_area[1])
+/// A centered overlay taking the given percentages of `area`, but no less
+/// than the minimum overlay size where `area` has room for it.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Option<Rect> {
+    let share = |length: u16, percent: u16| (u32::from(length) * u32::from(percent) / 100) as u16;
+    centered_fixed_rect(
+        share(area.width, percent_x).max(MIN_OVERLAY_WIDTH),
+        share(area.height, percent_y).max(MIN_OVERLAY_HEIGHT),
+        area,
+    )
 }
 
+/// A centered overlay of the given size, shrunk to fit `area`. `None` when
+/// `area` is empty.
 fn centered_fixed_rect(width: u16, height: u16, area: Rect) -> Option<Rect> {
-    if area.width < width || area.height < height {
+    if area.is_empty() {
         return None;
     }
+    let width = width.min(area.width);
+    let height = height.min(area.height);
 
-    let x = area.x + (area.width.saturating_sub(width)) / 2;
-    let y = area.y + (area.height.saturating_sub(height)) / 2;
+    let x = area.x + (area.width - width) / 2;
+    let y = area.y + (area.height - height) / 2;
 
     Some(Rect::new(x, y, width, height))
 }
@@ -1790,6 +5424,17 @@ fn calculate_grid_structure(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(chrono::Duration::seconds(59)), "00:00:59");
+        assert_eq!(
+            format_duration(chrono::Duration::seconds(9_252)),
+            "02:34:12"
+        );
+        assert_eq!(format_duration(chrono::Duration::hours(72)), "72:00:00");
+        assert_eq!(format_duration(chrono::Duration::seconds(-5)), "00:00:00");
+    }
+
     #[test]
     fn test_flow_cell_type_from_channel_count_minion_512() {
         assert_eq!(FlowCellType::from_channel_count(512), FlowCellType::MinION);
@@ -1992,8 +5637,11 @@ mod tests {
                     &theme,
                     &channel_states,
                     Some(&layout),
+                    None,
                     area,
                     0,
+                    None,
+                    &mut HitMap::default(),
                 );
             })
             .unwrap();
@@ -2001,11 +5649,323 @@ mod tests {
         // If we get here without panicking, the test passes
         // The grid should be rendered with truncation
         assert!(
-            terminal.backend().buffer().content.len() > 0,
+            !terminal.backend().buffer().content.is_empty(),
             "Buffer should contain rendered content"
         );
     }
 
+    #[test]
+    fn test_channel_map_marks_cursor_and_records_cells() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        let layout = create_minion_layout();
+        let channel_states = ChannelStatesSnapshot {
+            states: vec!["pore".to_string(); 512],
+            channel_count: 512,
+            state_counts: std::collections::HashMap::new(),
+        };
+        let theme = Theme::default();
+        let mut hits = HitMap::default();
+
+        terminal
+            .draw(|frame| {
+                render_pore_grid_from_states(
+                    frame,
+                    &theme,
+                    &channel_states,
+                    Some(&layout),
+                    None,
+                    frame.area(),
+                    0,
+                    Some(40),
+                    &mut hits,
+                );
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let (x, y) = (0..buffer.area.height)
+            .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+            .find(|&(x, y)| buffer[(x, y)].symbol() == "[")
+            .expect("cursor cell is drawn");
+        assert_eq!(hits.target_at(x, y), Some(MouseTarget::Channel(40)));
+        assert_eq!(hits.target_at(x + 2, y), Some(MouseTarget::Channel(41)));
+    }
+
+    #[test]
+    fn test_idle_detail_shows_last_run() {
+        use crate::client::{DeviceType, LastRun, RunSummary};
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        app.update_positions(vec![Position {
+            id: "X1".to_string(),
+            name: "X1".to_string(),
+            device_id: "DEV001".to_string(),
+            state: PositionState::Idle,
+            grpc_port: 8000,
+            is_simulated: false,
+            device_type: DeviceType::MinION,
+            host: None,
+        }]);
+        app.update_run_state("X1", RunState::Idle);
+        app.enter_detail();
+
+        let draw = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            terminal.draw(|frame| _ = render(frame, app)).unwrap();
+            let text: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            text
+        };
+        assert!(draw(&app).contains("Loading last run..."));
+
+        app.last_runs.insert(
+            "X1".into(),
+            Some(LastRun {
+                summary: RunSummary {
+                    protocol_id: "sequencing_MIN114".into(),
+                    end_reason: "Completed".into(),
+                    reads: 1_234_567,
+                    n50: Some(8_500),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        );
+        let text = draw(&app);
+        assert!(text.contains("Last Run ── Completed"));
+        assert!(text.contains("1.2M"));
+        assert!(text.contains("8.5K bp"));
+
+        app.set_detail_chart(DetailChart::Signal);
+        assert!(draw(&app).contains("Not kept for finished runs"));
+    }
+
+    #[test]
+    fn test_fit_columns_drops_low_priority_first() {
+        let columns = ["State", "Run", "Position", "Reads", "Bases", ""];
+        let widths = [11, 8, 10, 7, 7, 40];
+        // 11+8+10+7+7+2 plus five gaps.
+        assert_eq!(fit_columns(&columns, &widths, "Position", 50), [true; 6]);
+        assert_eq!(
+            fit_columns(&columns, &widths, "Position", 49),
+            [true, false, true, true, true, true]
+        );
+        assert_eq!(
+            fit_columns(&columns, &widths, "Reads", 30),
+            [true, false, true, true, true, true]
+        );
+        assert_eq!(
+            fit_columns(&columns, &widths, "Position", 30),
+            [true, false, true, false, true, true]
+        );
+    }
+
+    #[test]
+    fn test_occupancy_bands() {
+        let t = Theme::default();
+        assert_eq!(occupancy_to_symbol(&t, 0.0), ("░░", t.error));
+        assert_eq!(occupancy_to_symbol(&t, 0.3), ("▒▒", t.warning));
+        assert_eq!(occupancy_to_symbol(&t, 0.74), ("▓▓", t.info));
+        assert_eq!(occupancy_to_symbol(&t, 1.0), ("██", t.success));
+        assert_eq!(occupancy_band(f32::NAN), 0);
+        assert_eq!(occupancy_band(1.5), 3);
+    }
+
+    #[test]
+    fn test_small_terminal_shows_size_hint() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let app = App::new(crate::config::Config::default());
+        let draw = |width, height| {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|frame| _ = render(frame, &app)).unwrap();
+            let text: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            text
+        };
+        let text = draw(50, 15);
+        assert!(text.contains("Terminal too small"));
+        assert!(text.contains("50×15, needs 60×20"));
+        assert!(!draw(60, 20).contains("Terminal too small"));
+    }
+
+    #[test]
+    fn test_latency_indicator() {
+        let t = Theme::default();
+        assert_eq!(format_latency(Duration::from_micros(12_400)), "12ms");
+        assert_eq!(format_latency(Duration::from_millis(1500)), "1.5s");
+        assert_eq!(latency_color(&t, Duration::from_millis(12)), t.success);
+        assert_eq!(latency_color(&t, Duration::from_millis(300)), t.warning);
+        assert_eq!(latency_color(&t, Duration::from_secs(2)), t.error);
+    }
+
+    #[test]
+    fn test_connection_banner_counts_down_to_retry() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        let draw = |app: &App| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+            terminal.draw(|frame| _ = render(frame, app)).unwrap();
+            let text: String = terminal
+                .backend()
+                .buffer()
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            text
+        };
+        app.set_disconnected("connection refused".into());
+        app.schedule_reconnect(2, Duration::from_millis(8500));
+        let text = draw(&app);
+        assert!(text.contains("Disconnected, retrying in 9s"));
+        assert!(text.contains("attempt 2 in 9s — press R to retry now"));
+
+        // The banner stays up, with the last failure, while retrying.
+        app.set_reconnecting(2);
+        let text = draw(&app);
+        assert!(text.contains("connection refused"));
+        assert!(text.contains("attempt 2 under way"));
+    }
+
+    #[test]
+    fn test_centered_fixed_rect_shrinks_to_fit() {
+        let area = Rect::new(0, 0, 40, 10);
+        assert_eq!(
+            centered_fixed_rect(20, 4, area),
+            Some(Rect::new(10, 3, 20, 4))
+        );
+        assert_eq!(centered_fixed_rect(50, 12, area), Some(area));
+        assert_eq!(centered_rect(50, 50, area), Some(area));
+        assert_eq!(centered_fixed_rect(20, 4, Rect::new(0, 0, 0, 5)), None);
+    }
+
+    #[test]
+    fn test_detail_header_shows_flow_cell_and_note() {
+        use crate::client::{DeviceType, FlowCellInfo};
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let position = Position {
+            id: "X1".to_string(),
+            name: "X1".to_string(),
+            device_id: "DEV001".to_string(),
+            state: PositionState::Running,
+            grpc_port: 8000,
+            is_simulated: false,
+            device_type: DeviceType::MinION,
+            host: None,
+        };
+        let mut app = App::new(crate::config::Config::default());
+        app.run_states.insert("X1".into(), RunState::Running);
+        app.flow_cell_info.insert(
+            "X1".into(),
+            FlowCellInfo {
+                has_flow_cell: true,
+                flow_cell_id: Some("PAW12345".into()),
+                product_code: Some("FLO-MIN114".into()),
+                ..Default::default()
+            },
+        );
+        app.notes.insert("X1".into(), "flush at 24h".into());
+        let mut terminal = Terminal::new(TestBackend::new(100, 3)).unwrap();
+
+        terminal
+            .draw(|frame| render_detail_header(frame, &app, &position, None, frame.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..buffer.area.width)
+            .map(|x| buffer[(x, 1)].symbol())
+            .collect();
+        assert!(
+            text.contains("Running ── FLO-MIN114 PAW12345 ── ✎ flush at 24h"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_downsample_signal_keeps_extremes() {
+        let mut samples: VecDeque<f32> = std::iter::repeat_n(100.0, 1000).collect();
+        samples[500] = 300.0;
+
+        let points = downsample_signal(&samples, 50);
+        assert!(points.len() <= 100);
+        assert_eq!(points.last().unwrap().0, 49.0);
+        assert!(points.iter().any(|&(_, y)| y == 300.0), "spike survives");
+        assert!(downsample_signal(&VecDeque::new(), 50).is_empty());
+    }
+
+    #[test]
+    fn test_speed_sparkline_scales_recent_points() {
+        let points: Vec<SpeedDataPoint> = [420.0, 400.0, 380.0, 300.0]
+            .into_iter()
+            .map(|median| SpeedDataPoint {
+                median,
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(speed_sparkline(&points, 10), "█▇▆▁");
+        assert_eq!(speed_sparkline(&points, 2), "█▁");
+        assert_eq!(speed_sparkline(&points[..1], 10), "▄");
+        assert_eq!(speed_sparkline(&[], 10), "");
+    }
+
+    #[test]
+    fn test_trend_sparkline_covers_whole_history() {
+        let mut history = ChartBuffer::new(100);
+        assert_eq!(trend_sparkline(Some(&history), 4), "--");
+        history.push(0.0, 500.0);
+        assert_eq!(trend_sparkline(Some(&history), 4), "--");
+
+        // Eight samples fall into four stretches of two, oldest first.
+        for (i, pores) in [500.0, 480.0, 460.0, 400.0, 380.0, 300.0, 300.0]
+            .into_iter()
+            .enumerate()
+        {
+            history.push(i as f64 + 1.0, pores);
+        }
+        assert_eq!(trend_sparkline(Some(&history), 4), "█▇▄▁");
+        assert_eq!(trend_sparkline(None, 4), "--");
+    }
+
+    #[test]
+    fn test_qscore_bars_trim_and_merge() {
+        let histogram = QScoreHistogram {
+            bucket_ranges: (0..10).map(|q| (q as f32, q as f32 + 1.0)).collect(),
+            bucket_values: vec![0, 0, 5, 10, 20, 10, 5, 1, 0, 0],
+            modal_q_score: 4.5,
+        };
+
+        let bars = qscore_bars(&histogram, 10);
+        assert_eq!(bars.first(), Some(&(2.0, 3.0, 5)));
+        assert_eq!(bars.last(), Some(&(7.0, 8.0, 1)));
+        assert_eq!(bars.len(), 6);
+
+        let merged = qscore_bars(&histogram, 3);
+        assert_eq!(merged, vec![(2.0, 4.0, 15), (4.0, 6.0, 30), (6.0, 8.0, 6)]);
+        assert!(qscore_bars(&QScoreHistogram::default(), 10).is_empty());
+    }
+
     #[test]
     fn test_very_small_terminal_message() {
         // 5×3 screen should show "Terminal too small" message
@@ -2034,8 +5994,11 @@ mod tests {
                     &theme,
                     &channel_states,
                     Some(&layout),
+                    None,
                     area,
                     0,
+                    None,
+                    &mut HitMap::default(),
                 );
             })
             .unwrap();
@@ -2043,7 +6006,7 @@ mod tests {
         // If we get here without panicking, the test passes
         // The message should be displayed instead of grid
         assert!(
-            terminal.backend().buffer().content.len() > 0,
+            !terminal.backend().buffer().content.is_empty(),
             "Buffer should contain rendered content"
         );
     }
@@ -2085,4 +6048,219 @@ mod tests {
         assert_eq!(map_to_vertical_layout(63, 12), (40, 0));
         assert_eq!(map_to_vertical_layout(125, 24), (52, 62));
     }
+
+    #[test]
+    fn test_header_identifies_instrument() {
+        use crate::bus::BusEvent;
+        use crate::client::{DeviceType, ServerInfo, VersionInfo};
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        app.apply(&BusEvent::Connected);
+        app.apply(&BusEvent::ServerInfo {
+            info: ServerInfo {
+                endpoint: "gridion:9502".into(),
+                authenticated: false,
+                version: Some(VersionInfo {
+                    major: 6,
+                    full: "6.2.5".into(),
+                    ..Default::default()
+                }),
+            },
+        });
+        let position = |name: &str| Position {
+            id: name.to_string(),
+            name: name.to_string(),
+            device_id: "DEV001".to_string(),
+            state: PositionState::Running,
+            grpc_port: 8000,
+            is_simulated: false,
+            device_type: DeviceType::GridION,
+            host: None,
+        };
+        app.apply(&BusEvent::PositionsUpdated(vec![
+            position("X1"),
+            position("X2"),
+        ]));
+        app.apply(&BusEvent::RunStateChanged {
+            position: "X1".to_string(),
+            state: RunState::Running,
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 3)).unwrap();
+        terminal
+            .draw(|frame| render_header(frame, &app, frame.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..buffer.area.width)
+            .map(|x| buffer[(x, 1)].symbol())
+            .collect();
+        assert!(
+            text.contains("gridion:9502 │ MinKNOW 6.2.5 │ guest │ 1 run active"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_disconnected_overview_shows_troubleshooting() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        let err = crate::client::ClientError::Connection {
+            endpoint: "https://localhost:9501".into(),
+            source: "MinKNOW CA certificate not found".into(),
+        };
+        app.set_connection_failed(&err);
+
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                render(frame, &app);
+            })
+            .unwrap();
+
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("https://localhost:9501"));
+        assert!(text.contains("TLS"));
+        assert!(text.contains("MINKNOW_TRUSTED_CA"));
+        assert!(text.contains("Run checks"));
+    }
+
+    #[test]
+    fn test_debug_overlay_lists_rpc_latencies() {
+        use crate::client::metrics::RpcStats;
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        app.toggle_debug_overlay();
+        app.perf.record_frame(Duration::from_millis(4));
+        app.perf.bus_dropped = 12;
+        app.perf.rpc = vec![RpcStats {
+            method: "get_flow_cell_info".into(),
+            calls: 42,
+            errors: 1,
+            mean: Duration::from_millis(15),
+            p50: Duration::from_millis(10),
+            p95: Duration::from_millis(50),
+            max: Duration::from_millis(80),
+        }];
+
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|frame| {
+                render(frame, &app);
+            })
+            .unwrap();
+
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(text.contains("last 4.0 ms"));
+        assert!(text.contains("12 bus events dropped"));
+        assert!(text.contains("get_flow_cell_info"));
+        assert!(text.contains("50.0 ms"));
+    }
+
+    #[test]
+    fn test_overview_hit_map() {
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        let positions = ["X1", "X2"]
+            .iter()
+            .map(|name| Position {
+                id: name.to_string(),
+                name: name.to_string(),
+                device_id: "DEV001".to_string(),
+                state: PositionState::Idle,
+                grpc_port: 8000,
+                is_simulated: false,
+                device_type: crate::client::DeviceType::MinION,
+                host: None,
+            })
+            .collect();
+        app.apply(&crate::bus::BusEvent::PositionsUpdated(positions));
+
+        let backend = TestBackend::new(120, 30);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let mut hits = HitMap::default();
+        terminal.draw(|frame| hits = render(frame, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let find = |needle: &str| {
+            (0..buffer.area.height).find_map(|y| {
+                let line: String = (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect();
+                line.find(needle)
+                    .map(|i| (line[..i].chars().count() as u16, y))
+            })
+        };
+
+        let (x, y) = find("X2").unwrap();
+        assert_eq!(hits.target_at(x, y), Some(MouseTarget::Row(1)));
+        let (x, y) = find("[?] Help").unwrap();
+        assert_eq!(
+            hits.target_at(x + 1, y),
+            Some(MouseTarget::Action(Action::Help))
+        );
+        assert_eq!(hits.target_at(0, 0), None);
+    }
+
+    #[test]
+    fn test_dual_axis_yield_chart_labels_both_axes() {
+        use crate::client::YieldDataPoint;
+        use ratatui::backend::TestBackend;
+        use ratatui::Terminal;
+
+        let mut app = App::new(crate::config::Config::default());
+        app.toggle_yield_dual_axis();
+        let points = (0..=60)
+            .map(|minute| YieldDataPoint {
+                seconds: minute * 60,
+                reads: minute as u64 * 10_000,
+                bases: minute as u64 * 100_000_000,
+                ..Default::default()
+            })
+            .collect();
+        app.update_yield_history("X1", points);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        terminal
+            .draw(|frame| render_yield_chart(frame, &app, "X1", frame.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| -> String {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        };
+        let text: String = (0..buffer.area.height).map(row).collect();
+        assert!(text.contains("Bases (Gb)"));
+        assert!(text.contains("Reads (K reads)"));
+        // Top of both axes: 6.6 Gb and 660K reads share the first plot row.
+        assert!(row(1).trim_end().ends_with("660.0"), "{}", row(1));
+        assert!(row(1).contains("6.6"));
+        assert!(row(9).contains("3.3") && row(9).trim_end().ends_with("330.0"));
+        assert!(row(16).trim_end().ends_with("0.0"), "{}", row(16));
+    }
 }
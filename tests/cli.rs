@@ -0,0 +1,564 @@
+//! CLI commands run as a subprocess against the mock server.
+
+mod common;
+
+use common::{MockMinKnowServer, MockPosition, MockRun};
+use serde_json::Value;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Runs the `termion` binary with an empty config and the test CA.
+fn termion(config_dir: &Path, args: &[&str]) -> Output {
+    let config = config_dir.join("config.toml");
+    if !config.exists() {
+        std::fs::write(&config, "").unwrap();
+    }
+    Command::new(env!("CARGO_BIN_EXE_termion"))
+        .arg("--config")
+        .arg(&config)
+        .args(args)
+        .env("MINKNOW_TRUSTED_CA", common::ca_cert_path())
+        .env_remove("TERMION_HOST")
+        .env_remove("TERMION_PORT")
+        .env_remove("TERMION_LOG_LEVEL")
+        .output()
+        .unwrap()
+}
+
+fn stdout_json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "termion failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+async fn server() -> common::RunningMockServer {
+    MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run(MockRun::new("run-1")),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_list_json() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "list", "--json"]);
+    let list = stdout_json(&output);
+
+    assert_eq!(list["minknow_version"]["full"], "6.2.5");
+    assert_eq!(list["minknow_version"]["distribution"], "24.11.8");
+    let devices = list["devices"].as_array().unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0]["id"], "MN12345");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_status_json() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("config.toml"),
+        "[tui]\ntimezone = \"utc\"\n",
+    )
+    .unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "status", "--json"]);
+    let status = stdout_json(&output);
+    let positions = status.as_array().unwrap();
+    assert_eq!(positions.len(), 2);
+
+    let x1 = &positions[0];
+    assert_eq!(x1["name"], "X1");
+    assert_eq!(x1["state"], "Running");
+    assert_eq!(x1["run_id"], "run-1");
+    assert_eq!(x1["started_at"], "2023-11-14T22:13:20+00:00");
+    assert_eq!(x1["reads"], 10_000);
+    assert_eq!(x1["bases_passed"], 90_000_000);
+
+    let x2 = &positions[1];
+    assert_eq!(x2["name"], "X2");
+    assert_eq!(x2["state"], "Idle");
+    assert!(x2.get("run_id").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_status_position_filter() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(
+        dir.path(),
+        &["--port", &port, "status", "--json", "--position", "X2"],
+    );
+    let status = stdout_json(&output);
+    assert_eq!(status.as_array().unwrap().len(), 1);
+    assert_eq!(status[0]["name"], "X2");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_csv_format() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "list", "--format", "csv"]);
+    assert!(output.status.success());
+    let text = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<_> = text.lines().collect();
+    assert_eq!(lines[0], "id,name,state");
+    assert!(lines[1].starts_with("MN12345,"));
+
+    let output = termion(dir.path(), &["--port", &port, "status", "--format", "csv"]);
+    assert!(output.status.success());
+    let text = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<_> = text.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("name,state,run_id,"));
+    assert!(lines[1].starts_with("X1,Running,run-1,"));
+    assert!(lines[2].starts_with("X2,Idle,,"));
+
+    let output = termion(
+        dir.path(),
+        &["--port", &port, "status", "--json", "--format", "csv"],
+    );
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_connection_failure_exit_code() {
+    let server = server().await;
+    let port = server.port().to_string();
+    server.shutdown().await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = termion(dir.path(), &["--port", &port, "list"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_doctor_json() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "doctor", "--json"]);
+    let checks = stdout_json(&output);
+    let status = |name: &str| {
+        checks
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .unwrap_or_else(|| panic!("no {name} check"))["status"]
+            .clone()
+    };
+    for name in [
+        "CA certificate",
+        "MinKNOW manager",
+        "Auth token",
+        "Position X1",
+        "Position X2",
+        "Clock",
+    ] {
+        assert_eq!(status(name), "pass", "{name}");
+    }
+    let version = checks
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["name"] == "MinKNOW version")
+        .unwrap();
+    assert_eq!(version["detail"], "6.2.5 (distribution 24.11.8)");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_doctor_reports_failures() {
+    let server = server().await;
+    let port = server.port().to_string();
+    server.shutdown().await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = termion(dir.path(), &["--port", &port, "doctor"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8_lossy(&output.stdout);
+    assert!(report.contains("FAIL  Port"));
+    assert!(report.contains("→ Check that the MinKNOW service is running"));
+    assert!(report.contains("SKIP  MinKNOW manager"));
+}
+
+#[test]
+fn test_config_show_reports_sources() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("config.toml"), "[tui]\ntheme = \"nord\"\n").unwrap();
+
+    let output = termion(dir.path(), &["--port", "9600", "config", "show"]);
+    assert!(output.status.success());
+    let lines = String::from_utf8(output.stdout).unwrap();
+    let line = |key: &str| {
+        lines
+            .lines()
+            .find(|l| l.starts_with(&format!("{key} = ")))
+            .unwrap_or_else(|| panic!("no {key} line"))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    assert_eq!(line("connection.port"), "connection.port = 9600 # --port");
+    assert_eq!(line("tui.theme"), "tui.theme = \"nord\" # config file");
+    assert_eq!(
+        line("tui.refresh_interval"),
+        "tui.refresh_interval = 1000 # default"
+    );
+}
+
+#[test]
+fn test_config_init_writes_default_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("termion/config.toml");
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_termion"))
+            .arg("--config")
+            .arg(&config)
+            .args(args)
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["config", "init"]).status.success());
+    assert!(std::fs::read_to_string(&config)
+        .unwrap()
+        .contains("# port = 9501"));
+    // The written file loads.
+    assert!(run(&["config", "show"]).status.success());
+
+    assert_eq!(run(&["config", "init"]).status.code(), Some(1));
+    assert!(run(&["config", "init", "--force"]).status.success());
+}
+
+#[test]
+fn test_invalid_arguments_exit_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = termion(dir.path(), &["status", "--bogus"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = termion(dir.path(), &["--demo", "list"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--demo"));
+
+    let output = termion(dir.path(), &["--replay", "session.ndjson", "status"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--replay"));
+
+    let output = termion(dir.path(), &["--record", "session.ndjson", "--demo"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = termion(dir.path(), &["--replay-speed", "10"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = termion(
+        dir.path(),
+        &["--replay", "session.ndjson", "--replay-speed", "0"],
+    );
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_watch_ndjson_streams_snapshots() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, "").unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_termion"))
+        .arg("--config")
+        .arg(&config)
+        .args(["--port", &server.port().to_string()])
+        .args(["watch", "--ndjson", "--interval", "1", "--position", "X1"])
+        .env("MINKNOW_TRUSTED_CA", common::ca_cert_path())
+        .env_remove("TERMION_HOST")
+        .env_remove("TERMION_PORT")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let first: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    let second: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    for snapshot in [&first, &second] {
+        assert!(snapshot["timestamp"].is_string());
+        let positions = snapshot["positions"].as_array().unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0]["name"], "X1");
+        assert_eq!(positions[0]["run_id"], "run-1");
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_start_json() {
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_protocols(&[
+                    "sequencing/sequencing_MIN114_DNA_e8_2_400K",
+                    "control/platform_qc",
+                ]),
+        )
+        .start()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(
+        dir.path(),
+        &[
+            "--port",
+            &port,
+            "start",
+            "-P",
+            "X1",
+            "--kit",
+            "SQK-LSK114",
+            "--sample-id",
+            "S1",
+            "--experiment-group",
+            "G1",
+            "--json",
+        ],
+    );
+    let started = stdout_json(&output);
+    assert_eq!(started["position"], "X1");
+    assert_eq!(
+        started["protocol"],
+        "sequencing/sequencing_MIN114_DNA_e8_2_400K"
+    );
+    assert_eq!(started["run_id"], "started-run");
+
+    let run = server.position("X1").run.unwrap();
+    assert_eq!(run.sample_id, "S1");
+    assert_eq!(run.experiment_group, "G1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_start_validation_exit_codes() {
+    let server = MockMinKnowServer::builder()
+        .with_position(MockPosition::new("X1", "MN12345").with_protocols(&["control/platform_qc"]))
+        .start()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(
+        dir.path(),
+        &["--port", &port, "start", "-P", "X9", "--kit", "SQK-LSK114"],
+    );
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = termion(
+        dir.path(),
+        &["--port", &port, "start", "-P", "X1", "--kit", "SQK-LSK114"],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No flow cell"));
+    assert!(server.position("X1").run.is_none());
+
+    let output = termion(dir.path(), &["--port", &port, "start", "-P", "X1"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_report_markdown_and_html() {
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run(MockRun::new("run-1").with_mux_scan(0, &[("single_pore", 1400)])),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "report", "-P", "X1"]);
+    assert!(
+        output.status.success(),
+        "termion failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let markdown = String::from_utf8(output.stdout).unwrap();
+    assert!(markdown.starts_with("# Run report: sample-1\n"));
+    assert!(markdown.contains("| Acquisition ID | run-1 |"));
+    assert!(markdown.contains("| Pass rate | 90.0% |"));
+    assert!(markdown.contains("| Pores available | 1400 |"));
+    assert!(markdown.contains("![Yield](data:image/svg+xml;base64,"));
+
+    let path = dir.path().join("run.html");
+    let output = termion(
+        dir.path(),
+        &[
+            "--port",
+            &port,
+            "report",
+            "-P",
+            "X1",
+            "-o",
+            path.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success());
+    let html = std::fs::read_to_string(&path).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<svg"));
+
+    // X2 has never had a run.
+    let output = termion(dir.path(), &["--port", &port, "report", "-P", "X2"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_logs_prints_messages_in_order() {
+    use common::proto::minknow_api::log::Severity;
+
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_user_message(1_700_000_300, Severity::MessageSeverityInfo, "Run started")
+                .with_user_message(
+                    1_700_000_100,
+                    Severity::MessageSeverityWarning,
+                    "Flow cell temperature high",
+                ),
+        )
+        .with_position(MockPosition::new("X2", "MN12345").with_user_message(
+            1_700_000_200,
+            Severity::MessageSeverityError,
+            "Disk full",
+        ))
+        .start()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(
+        dir.path(),
+        &["--port", &port, "logs", "--json", "--level", "warning"],
+    );
+    assert!(output.status.success());
+    let messages: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["position"], "X1");
+    assert_eq!(messages[0]["severity"], "warning");
+    assert_eq!(messages[1]["position"], "X2");
+    assert_eq!(messages[1]["message"], "Disk full");
+
+    let output = termion(dir.path(), &["--port", &port, "logs", "-P", "X9"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_status_ndjson_follow() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "status", "--ndjson"]);
+    assert!(output.status.success());
+    let lines: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["schema_version"], 1);
+    assert_eq!(lines[0]["name"], "X1");
+    assert_eq!(lines[1]["name"], "X2");
+
+    let config = dir.path().join("config.toml");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_termion"))
+        .arg("--config")
+        .arg(&config)
+        .args(["--port", &port])
+        .args([
+            "status",
+            "--ndjson",
+            "--follow",
+            "--interval",
+            "1",
+            "-P",
+            "X1",
+        ])
+        .env("MINKNOW_TRUSTED_CA", common::ca_cert_path())
+        .env_remove("TERMION_HOST")
+        .env_remove("TERMION_PORT")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let mut lines = BufReader::new(child.stdout.take().unwrap()).lines();
+    let first: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    let second: Value = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    for line in [&first, &second] {
+        assert_eq!(line["schema_version"], 1);
+        assert!(line["timestamp"].is_string());
+        assert_eq!(line["name"], "X1");
+        assert_eq!(line["run_id"], "run-1");
+    }
+
+    let output = termion(dir.path(), &["--port", &port, "status", "--follow"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_json() {
+    let server = server().await;
+    let dir = tempfile::tempdir().unwrap();
+    let port = server.port().to_string();
+
+    let output = termion(dir.path(), &["--port", &port, "snapshot"]);
+    let snapshot = stdout_json(&output);
+    assert_eq!(snapshot["minknow_version"]["major"], 6);
+    let positions = snapshot["positions"].as_array().unwrap();
+    assert_eq!(positions.len(), 2);
+
+    let x1 = &positions[0];
+    assert_eq!(x1["name"], "X1");
+    assert_eq!(x1["acquisition_run_id"], "run-1");
+    assert_eq!(x1["flow_cell"]["flow_cell_id"], "FAX00001");
+    assert_eq!(x1["stats"]["bases_passed"], 90_000_000);
+    assert!(!x1["yield_history"].as_array().unwrap().is_empty());
+
+    let x2 = &positions[1];
+    assert_eq!(x2["name"], "X2");
+    assert!(x2.get("run").is_none());
+    assert!(x2.get("stats").is_none());
+
+    let output = termion(dir.path(), &["--port", &port, "snapshot", "-P", "X9"]);
+    assert_eq!(output.status.code(), Some(4));
+}
@@ -0,0 +1,444 @@
+//! Client ↔ mock server integration tests.
+
+mod common;
+
+use common::{MockMinKnowServer, MockPosition, MockRun};
+use termion::client::{
+    Client, ClientError, RunState, RunUntilCriteria, StopDataAction, StopOptions,
+};
+
+async fn running_server() -> common::RunningMockServer {
+    common::init();
+    MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run(MockRun::new("run-1")),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await
+}
+
+#[tokio::test]
+async fn test_list_positions_and_devices() {
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+
+    let positions = client.list_positions().await.unwrap();
+    let names: Vec<_> = positions.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["X1", "X2"]);
+    assert!(positions.iter().all(|p| p.grpc_port > 0 && p.is_simulated));
+
+    let devices = client.list_devices().await.unwrap();
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].id, "MN12345");
+}
+
+#[tokio::test]
+async fn test_watch_positions_lists_positions_first() {
+    use futures::StreamExt;
+
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+
+    let mut watch = Box::pin(client.watch_positions().await.unwrap());
+    let first = watch.next().await.unwrap().unwrap();
+    let names: Vec<_> = first.updated.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["X1", "X2"]);
+    assert!(first.removed.is_empty());
+}
+
+#[tokio::test]
+async fn test_running_position_reports_stats_and_history() {
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    assert_eq!(pos.get_run_state().await.unwrap(), RunState::Running);
+
+    let stats = pos.get_stats().await.unwrap();
+    assert_eq!(stats.reads_processed, 10_000);
+    assert_eq!(stats.bases_called, 100_000_000);
+    assert!((stats.pass_rate() - 90.0).abs() < 0.01);
+
+    let info = pos.get_run_info().await.unwrap().unwrap();
+    assert_eq!(info.run_id.as_deref(), Some("run-1"));
+    assert_eq!(info.sample_id.as_deref(), Some("sample-1"));
+    assert_eq!(
+        info.protocol_name.as_deref(),
+        Some("Ligation Sequencing Kit V14")
+    );
+    assert_eq!(info.flow_cell_product_code.as_deref(), Some("FLO-MIN114"));
+    assert_eq!(info.start_time.unwrap().timestamp(), 1_700_000_000);
+
+    let output = pos.get_output_info().await.unwrap().unwrap();
+    assert_eq!(
+        output.reads_directory.as_deref(),
+        Some("/data/experiment-1/sample-1")
+    );
+    assert_eq!(output.formats, ["pod5", "fastq"]);
+    assert_eq!(output.bytes_written, 199_000_000);
+    assert_eq!(output.bytes_pending, 1_000_000);
+
+    let run_id = pos.get_current_run_id().await.unwrap().unwrap();
+    let points = pos.get_yield_history(&run_id).await.unwrap();
+    assert_eq!(points.len(), 3);
+    assert_eq!(points.last().unwrap().bases, 100_000_000);
+
+    let phases = pos.get_phase_history().await.unwrap();
+    assert_eq!(phases.len(), 2);
+    assert!(phases[0].timestamp < phases[1].timestamp);
+}
+
+#[tokio::test]
+async fn test_idle_position_has_no_run() {
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(1);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    assert_eq!(pos.get_run_state().await.unwrap(), RunState::Idle);
+    assert!(!pos.get_flow_cell_info().await.unwrap().has_flow_cell);
+    assert!(pos.get_run_info().await.unwrap().is_none());
+    assert!(pos.get_output_info().await.unwrap().is_none());
+    assert!(pos.get_current_run_id().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_run_history_lists_finished_runs_newest_first() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_finished_run(MockRun::finished("run-old", 1_600_000_000, 3_600))
+                .with_finished_run(MockRun::finished("run-new", 1_650_000_000, 7_200))
+                .with_run(MockRun::new("run-current")),
+        )
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let runs = pos.list_run_history().await.unwrap();
+    let ids: Vec<_> = runs.iter().map(|r| r.info.run_id.as_deref()).collect();
+    assert_eq!(ids, [Some("run-new"), Some("run-old")]);
+
+    let latest = &runs[0];
+    assert_eq!(latest.end_reason, "Completed");
+    assert_eq!(latest.duration().unwrap().num_seconds(), 7_200);
+    assert_eq!(latest.acquisition_run_id.as_deref(), Some("run-new"));
+    assert_eq!(latest.reads, 10_000);
+    assert_eq!(latest.total_bases(), 100_000_000);
+    assert_eq!(latest.n50, None);
+}
+
+#[tokio::test]
+async fn test_disk_space_info() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(MockPosition::new("X1", "MN12345").with_disk_space(40, 1_000))
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let filesystems = pos.get_disk_space_info().await.unwrap();
+    assert_eq!(filesystems.len(), 1);
+    assert_eq!(filesystems[0].filesystem_id, "data");
+    assert!((filesystems[0].free_percent() - 4.0).abs() < 1e-9);
+    assert!(filesystems[0].is_low(10.0));
+}
+
+#[tokio::test]
+async fn test_barcode_yield_is_split_per_barcode() {
+    use futures::StreamExt;
+
+    common::init();
+    let server =
+        MockMinKnowServer::builder()
+            .with_position(MockPosition::new("X1", "MN12345").with_run(
+                MockRun::new("run-1").with_barcodes(&[("barcode01", 3), ("barcode02", 1)]),
+            ))
+            .start()
+            .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let mut stream = Box::pin(pos.stream_barcode_yield("run-1").await.unwrap());
+    let barcodes = stream.next().await.unwrap().unwrap();
+
+    let names: Vec<_> = barcodes.iter().map(|b| b.barcode.as_str()).collect();
+    assert_eq!(names, ["barcode01", "barcode02"]);
+    assert_eq!(barcodes[0].points.len(), 3);
+    assert_eq!(barcodes[0].bases(), 75_000_000);
+    assert_eq!(barcodes[1].bases(), 25_000_000);
+}
+
+#[tokio::test]
+async fn test_duty_time_is_bucketed_by_state() {
+    use futures::StreamExt;
+    use termion::client::ChannelState;
+
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345").with_run(
+                MockRun::new("run-1")
+                    .with_duty_bucket(0, 60, &[("strand", 300), ("pore", 100)])
+                    .with_duty_bucket(60, 120, &[("strand", 200), ("multiple", 200)]),
+            ),
+        )
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let mut stream = Box::pin(pos.stream_duty_time("run-1").await.unwrap());
+    let duty_time = stream.next().await.unwrap().unwrap();
+
+    assert_eq!(duty_time.buckets.len(), 2);
+    assert_eq!(
+        (duty_time.buckets[1].start, duty_time.buckets[1].end),
+        (60, 120)
+    );
+    assert_eq!(duty_time.buckets[0].fraction(ChannelState::Strand), 0.75);
+    assert_eq!(
+        duty_time.buckets[1].fraction(ChannelState::Unavailable),
+        0.5
+    );
+    assert_eq!(duty_time.state_times[&ChannelState::Strand], 500);
+}
+
+#[tokio::test]
+async fn test_translocation_speed_per_dataset() {
+    use futures::StreamExt;
+
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_run(MockRun::new("run-1").with_speeds(&[410.0, 390.0, 280.0])),
+        )
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let mut stream = Box::pin(pos.stream_translocation_speed("run-1").await.unwrap());
+    let points = stream.next().await.unwrap().unwrap();
+
+    let seconds: Vec<_> = points.iter().map(|p| p.seconds).collect();
+    assert_eq!(seconds, [600, 1200, 1800]);
+    assert_eq!(points[2].median, 280.0);
+    assert_eq!((points[0].q25, points[0].q75), (390.0, 430.0));
+}
+
+#[tokio::test]
+async fn test_mux_scan_results_oldest_first() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345").with_run(
+                MockRun::new("run-1")
+                    .with_mux_scan(5400, &[("single_pore", 1300), ("unavailable", 500)])
+                    .with_mux_scan(0, &[("single_pore", 1500), ("reserved_pore", 100)]),
+            ),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let mut positions = client.list_positions().await.unwrap().into_iter();
+
+    let mut pos = client
+        .connect_position(positions.next().unwrap())
+        .await
+        .unwrap();
+    let scans = pos.get_mux_scan_results().await.unwrap();
+    let seconds: Vec<_> = scans.iter().map(|s| s.seconds).collect();
+    assert_eq!(seconds, [0, 5400]);
+    assert_eq!(scans[0].available_pores(), 1600);
+    assert_eq!(scans[1].available_pores(), 1300);
+    assert_eq!(scans[1].counts["unavailable"], 500);
+
+    let mut idle = client
+        .connect_position(positions.next().unwrap())
+        .await
+        .unwrap();
+    assert!(idle.get_mux_scan_results().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_list_and_start_protocol() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(MockPosition::new("X1", "MN12345").with_protocols(&[
+            "sequencing/sequencing_MIN114_DNA_e8_2_400K",
+            "control/platform_qc",
+        ]))
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let protocols = pos.list_protocols().await.unwrap();
+    let names: Vec<_> = protocols.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["platform_qc", "sequencing_MIN114_DNA_e8_2_400K"]);
+    assert_eq!(protocols[0].kit.as_deref(), Some("SQK-LSK114"));
+
+    let run_id = pos
+        .start_protocol(&protocols[1].identifier, "my-sample", "")
+        .await
+        .unwrap();
+    assert_eq!(run_id, "started-run");
+    let run = server.position("X1").run.unwrap();
+    assert_eq!(run.sample_id, "my-sample");
+    assert_eq!(run.experiment_group, "experiment-1");
+
+    let err = pos
+        .start_protocol(&protocols[1].identifier, "", "")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Grpc { .. }));
+}
+
+#[tokio::test]
+async fn test_run_control_round_trip() {
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    pos.pause().await.unwrap();
+    assert!(server.position("X1").run.unwrap().paused);
+    assert_eq!(pos.get_run_state().await.unwrap(), RunState::Paused);
+
+    pos.resume().await.unwrap();
+    assert_eq!(pos.get_run_state().await.unwrap(), RunState::Running);
+
+    pos.stop_protocol(StopOptions {
+        data_action: StopDataAction::KeepAllData,
+    })
+    .await
+    .unwrap();
+    let x1 = server.position("X1");
+    assert!(x1.run.is_none());
+    // STOP_KEEP_ALL_DATA
+    assert_eq!(x1.stop_data_action, Some(1));
+    assert_eq!(pos.get_run_state().await.unwrap(), RunState::Idle);
+
+    let err = pos.pause().await.unwrap_err();
+    assert!(matches!(err, ClientError::Grpc { .. }));
+}
+
+#[tokio::test]
+async fn test_run_until_targets_round_trip() {
+    use futures::StreamExt;
+
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345").with_run(
+                MockRun::new("run-1")
+                    .with_run_until(&[("runtime", 3600)], &[("runtime", 259_200)])
+                    .with_run_until_progress(&[("runtime", 7200), ("estimated_bases", 1_000)]),
+            ),
+        )
+        .start()
+        .await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let mut pos = client.connect_position(position).await.unwrap();
+
+    let mut progress = Box::pin(pos.stream_run_until_progress("run-1").await.unwrap());
+    let progress = progress.next().await.unwrap().unwrap();
+    assert_eq!(progress.runtime, Some(7200));
+    assert_eq!(progress.estimated_bases, Some(1_000));
+
+    let targets = RunUntilCriteria {
+        runtime: None,
+        estimated_bases: Some(10_000_000_000),
+        ..Default::default()
+    };
+    pos.set_run_until_targets("run-1", &targets).await.unwrap();
+
+    let mut stream = Box::pin(pos.stream_run_until_targets("run-1").await.unwrap());
+    assert_eq!(stream.next().await.unwrap().unwrap(), targets);
+    // Pause criteria are left as they were.
+    let run = server.position("X1").run.unwrap();
+    assert_eq!(run.pause_criteria.criteria.len(), 1);
+    assert!(run.pause_criteria.criteria.contains_key("runtime"));
+
+    let err = pos
+        .set_run_until_targets("run-2", &targets)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ClientError::Grpc { .. }));
+}
+
+#[tokio::test]
+async fn test_connection_refused_after_shutdown() {
+    let server = running_server().await;
+    let port = server.port();
+    server.shutdown().await;
+
+    let err = Client::connect_with_timeouts(
+        "localhost",
+        port,
+        std::time::Duration::from_secs(2),
+        std::time::Duration::from_secs(2),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(err, ClientError::Connection { .. }));
+}
+
+#[tokio::test]
+async fn test_run_subscription_forwards_stream_messages() {
+    use termion::client::subscription::{
+        HistogramOptions, RunSubscription, StreamData, DEFAULT_HISTOGRAM_INTERVAL,
+    };
+
+    let server = running_server().await;
+    let mut client = Client::connect("localhost", server.port()).await.unwrap();
+    let position = client.list_positions().await.unwrap().remove(0);
+    let pos = client.connect_position(position).await.unwrap();
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let _subscription = RunSubscription::start(
+        &pos,
+        "run-1",
+        HistogramOptions::default(),
+        DEFAULT_HISTOGRAM_INTERVAL,
+        tx,
+    );
+
+    let update = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            let update = rx.recv().await.unwrap();
+            if matches!(update.data, StreamData::YieldHistory(_)) {
+                return update;
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(update.position, "X1");
+    assert_eq!(update.run_id, "run-1");
+    assert!(update.initial);
+    let StreamData::YieldHistory(points) = update.data else {
+        unreachable!()
+    };
+    assert_eq!(points.len(), 3);
+    assert_eq!(points.last().unwrap().bases, 100_000_000);
+}
@@ -0,0 +1,1025 @@
+//! Mock MinKNOW gRPC server.
+//!
+//! Serves the manager and per-position services over TLS on random local
+//! ports, backed by in-memory state that tests can inspect and modify while
+//! the server is running. Only the RPCs Termion calls are implemented; the
+//! rest return `UNIMPLEMENTED`.
+
+use super::proto::minknow_api::{
+    acquisition::{
+        self, acquisition_service_server::AcquisitionService,
+        acquisition_service_server::AcquisitionServiceServer, AcquisitionConfigSummary,
+        AcquisitionRunInfo, AcquisitionWriterSummary, AcquisitionYieldSummary, BreamInfo,
+        CurrentStatusRequest, CurrentStatusResponse, GetAcquisitionRunInfoRequest, MinknowStatus,
+        StopRequest, StopResponse,
+    },
+    device::{
+        device_service_server::{DeviceService, DeviceServiceServer},
+        GetFlowCellInfoRequest, GetFlowCellInfoResponse,
+    },
+    instance::{
+        get_version_info_response::MinknowVersion,
+        instance_service_server::{InstanceService, InstanceServiceServer},
+        FilesystemDiskSpaceInfo, GetDiskSpaceInfoRequest, GetDiskSpaceInfoResponse,
+        GetVersionInfoResponse,
+    },
+    log::{
+        log_service_server::{LogService, LogServiceServer},
+        GetUserMessagesRequest, Severity, UserMessage,
+    },
+    manager::{
+        flow_cell_position,
+        manager_service_server::{ManagerService, ManagerServiceServer},
+        FlowCellPosition, FlowCellPositionsRequest, FlowCellPositionsResponse,
+        GetVersionInfoRequest, LocalAuthenticationTokenPathRequest,
+        LocalAuthenticationTokenPathResponse, WatchFlowCellPositionsRequest,
+        WatchFlowCellPositionsResponse,
+    },
+    protocol::{
+        protocol_info::{tag_value, TagValue},
+        protocol_service_server::{ProtocolService, ProtocolServiceServer},
+        GetCurrentProtocolRunRequest, GetRunInfoRequest, ListProtocolRunsRequest,
+        ListProtocolRunsResponse, ListProtocolsRequest, ListProtocolsResponse,
+        PauseProtocolRequest, PauseProtocolResponse, ProtocolInfo, ProtocolPhase,
+        ProtocolPhaseSnapshot, ProtocolRunInfo, ProtocolRunUserInfo, ProtocolState,
+        ResumeProtocolRequest, ResumeProtocolResponse, StartProtocolRequest, StartProtocolResponse,
+        StopProtocolRequest, StopProtocolResponse,
+    },
+    run_until::{
+        run_until_service_server::{RunUntilService, RunUntilServiceServer},
+        CriteriaValues, StreamTargetCriteriaRequest, StreamTargetCriteriaResponse,
+        StreamUpdatesRequest, StreamUpdatesResponse, Update, WriteTargetCriteriaRequest,
+        WriteTargetCriteriaResponse,
+    },
+    statistics::{
+        boxplot_response::BoxplotDataset,
+        statistics_service_server::{StatisticsService, StatisticsServiceServer},
+        stream_acquisition_output_response::FilteredSnapshots,
+        stream_boxplot_request::BoxplotType,
+        stream_duty_time_response::{BucketRange, ChannelStateData},
+        AcquisitionOutputKey, AcquisitionOutputSnapshot, BoxplotResponse,
+        StreamAcquisitionOutputRequest, StreamAcquisitionOutputResponse, StreamBoxplotRequest,
+        StreamDutyTimeRequest, StreamDutyTimeResponse,
+    },
+};
+use futures::StreamExt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tonic::codegen::BoxStream;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::{Identity, Server, ServerTlsConfig};
+use tonic::{Request, Response, Status};
+
+/// A duty time bucket as `(start, end, samples per channel state)`.
+pub type DutyBucket = (u32, u32, Vec<(String, u64)>);
+
+/// An acquisition run in progress on a mock position.
+#[derive(Debug, Clone)]
+pub struct MockRun {
+    pub run_id: String,
+    pub sample_id: String,
+    pub experiment_group: String,
+    /// Run start, as seconds since the Unix epoch.
+    pub start_time: i64,
+    /// Run end, as seconds since the Unix epoch. Set for finished runs.
+    pub end_time: Option<i64>,
+    pub paused: bool,
+    pub reads_passed: i64,
+    pub reads_failed: i64,
+    pub bases_passed: i64,
+    pub bases_failed: i64,
+    /// Cumulative yield over time as `(seconds since start, total bases)`.
+    pub yield_points: Vec<(u32, i64)>,
+    /// Barcodes and their relative share of the yield. Empty for unbarcoded runs.
+    pub barcodes: Vec<(String, i64)>,
+    /// Pore scans as `(seconds since start, wells per category)`, in any
+    /// order.
+    pub mux_scans: Vec<(u64, Vec<(String, u32)>)>,
+    pub duty_buckets: Vec<DutyBucket>,
+    /// Median translocation speed of each boxplot dataset, oldest first.
+    pub speeds: Vec<f32>,
+    /// Run-until criteria that pause the run.
+    pub pause_criteria: CriteriaValues,
+    /// Run-until criteria that end the run.
+    pub stop_criteria: CriteriaValues,
+    /// Latest run-until progress update, if any.
+    pub run_until_progress: Option<CriteriaValues>,
+}
+
+impl MockRun {
+    pub fn new(run_id: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            sample_id: "sample-1".to_string(),
+            experiment_group: "experiment-1".to_string(),
+            start_time: 1_700_000_000,
+            end_time: None,
+            paused: false,
+            reads_passed: 9_000,
+            reads_failed: 1_000,
+            bases_passed: 90_000_000,
+            bases_failed: 10_000_000,
+            yield_points: vec![(60, 25_000_000), (120, 50_000_000), (180, 100_000_000)],
+            barcodes: Vec::new(),
+            mux_scans: Vec::new(),
+            duty_buckets: Vec::new(),
+            speeds: Vec::new(),
+            pause_criteria: CriteriaValues::default(),
+            stop_criteria: CriteriaValues::default(),
+            run_until_progress: None,
+        }
+    }
+
+    /// Splits the run's yield across `barcodes` in proportion to their weights.
+    pub fn with_barcodes(mut self, barcodes: &[(&str, i64)]) -> Self {
+        self.barcodes = barcodes
+            .iter()
+            .map(|&(name, weight)| (name.to_string(), weight))
+            .collect();
+        self
+    }
+
+    /// Adds a pore scan result taken `seconds` into the run.
+    pub fn with_mux_scan(mut self, seconds: u64, counts: &[(&str, u32)]) -> Self {
+        let counts = counts
+            .iter()
+            .map(|&(name, count)| (name.to_string(), count))
+            .collect();
+        self.mux_scans.push((seconds, counts));
+        self
+    }
+
+    /// Adds a duty time bucket covering seconds `[start, end)` of the run.
+    pub fn with_duty_bucket(mut self, start: u32, end: u32, times: &[(&str, u64)]) -> Self {
+        let times = times
+            .iter()
+            .map(|&(state, time)| (state.to_string(), time))
+            .collect();
+        self.duty_buckets.push((start, end, times));
+        self
+    }
+
+    /// Sets the median translocation speed of each ten-minute dataset.
+    pub fn with_speeds(mut self, speeds: &[f32]) -> Self {
+        self.speeds = speeds.to_vec();
+        self
+    }
+
+    /// Sets the run-until criteria that pause and end the run.
+    pub fn with_run_until(mut self, pause: &[(&str, u64)], stop: &[(&str, u64)]) -> Self {
+        self.pause_criteria = criteria_values(pause);
+        self.stop_criteria = criteria_values(stop);
+        self
+    }
+
+    /// Sets the progress towards the run-until criteria.
+    pub fn with_run_until_progress(mut self, progress: &[(&str, u64)]) -> Self {
+        self.run_until_progress = Some(criteria_values(progress));
+        self
+    }
+
+    /// A run that completed `duration` seconds after it started.
+    pub fn finished(run_id: &str, start_time: i64, duration: i64) -> Self {
+        Self {
+            start_time,
+            end_time: Some(start_time + duration),
+            ..Self::new(run_id)
+        }
+    }
+
+    fn protocol_run_info(&self) -> ProtocolRunInfo {
+        let (state, phase) = match self.end_time {
+            Some(_) => (
+                ProtocolState::ProtocolCompleted,
+                ProtocolPhase::PhaseCompleted,
+            ),
+            None if self.paused => (ProtocolState::ProtocolRunning, ProtocolPhase::PhasePaused),
+            None => (
+                ProtocolState::ProtocolRunning,
+                ProtocolPhase::PhaseSequencing,
+            ),
+        };
+        ProtocolRunInfo {
+            run_id: self.run_id.clone(),
+            protocol_id: "sequencing/sequencing_MIN114_DNA_e8_2_400K".to_string(),
+            acquisition_run_ids: vec![self.run_id.clone()],
+            state: state as i32,
+            phase: phase as i32,
+            phase_history: vec![
+                ProtocolPhaseSnapshot {
+                    phase: ProtocolPhase::PhaseInitialising as i32,
+                    timestamp: timestamp(self.start_time),
+                    ..Default::default()
+                },
+                ProtocolPhaseSnapshot {
+                    phase: ProtocolPhase::PhaseSequencing as i32,
+                    timestamp: timestamp(self.start_time + 30),
+                    ..Default::default()
+                },
+            ],
+            start_time: timestamp(self.start_time),
+            end_time: self.end_time.and_then(timestamp),
+            user_info: Some(ProtocolRunUserInfo {
+                protocol_group_id: Some(self.experiment_group.clone()),
+                sample_id: Some(self.sample_id.clone()),
+                ..Default::default()
+            }),
+            flow_cell: Some(GetFlowCellInfoResponse {
+                has_flow_cell: true,
+                product_code: "FLO-MIN114".to_string(),
+                ..Default::default()
+            }),
+            meta_info: Some(ProtocolInfo {
+                identifier: "sequencing/sequencing_MIN114_DNA_e8_2_400K".to_string(),
+                name: "Ligation Sequencing Kit V14".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// A flow cell position served by the mock.
+#[derive(Debug, Clone)]
+pub struct MockPosition {
+    pub name: String,
+    pub device: String,
+    pub simulated: bool,
+    pub flow_cell_id: Option<String>,
+    pub run: Option<MockRun>,
+    /// Runs that have already finished, in any order.
+    pub finished_runs: Vec<MockRun>,
+    /// Free and total bytes on the output file-system.
+    pub disk_space: (u64, u64),
+    /// Identifiers of the protocols that can be started.
+    pub protocols: Vec<String>,
+    /// Messages MinKNOW has shown the user, replayed to new log streams.
+    pub user_messages: Vec<UserMessage>,
+    /// `data_action_on_stop` of the last stop request.
+    pub stop_data_action: Option<i32>,
+}
+
+impl MockPosition {
+    /// An idle position with no flow cell inserted.
+    pub fn new(name: &str, device: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            device: device.to_string(),
+            simulated: true,
+            flow_cell_id: None,
+            run: None,
+            finished_runs: Vec::new(),
+            disk_space: (500_000_000_000, 1_000_000_000_000),
+            protocols: Vec::new(),
+            user_messages: Vec::new(),
+            stop_data_action: None,
+        }
+    }
+
+    pub fn with_flow_cell(mut self, flow_cell_id: &str) -> Self {
+        self.flow_cell_id = Some(flow_cell_id.to_string());
+        self
+    }
+
+    pub fn with_run(mut self, run: MockRun) -> Self {
+        self.run = Some(run);
+        self
+    }
+
+    pub fn with_finished_run(mut self, run: MockRun) -> Self {
+        self.finished_runs.push(run);
+        self
+    }
+
+    pub fn with_disk_space(mut self, available: u64, capacity: u64) -> Self {
+        self.disk_space = (available, capacity);
+        self
+    }
+
+    pub fn with_protocols(mut self, identifiers: &[&str]) -> Self {
+        self.protocols = identifiers.iter().map(|id| id.to_string()).collect();
+        self
+    }
+
+    pub fn with_user_message(mut self, seconds: i64, severity: Severity, message: &str) -> Self {
+        self.user_messages.push(UserMessage {
+            time: timestamp(seconds),
+            severity: severity.into(),
+            identifier: String::new(),
+            user_message: message.to_string(),
+            extra_data: Default::default(),
+        });
+        self
+    }
+}
+
+/// Builder for [`RunningMockServer`].
+#[derive(Debug, Default)]
+pub struct MockMinKnowServer {
+    positions: Vec<MockPosition>,
+}
+
+impl MockMinKnowServer {
+    pub fn builder() -> Self {
+        Self::default()
+    }
+
+    pub fn with_position(mut self, position: MockPosition) -> Self {
+        self.positions.push(position);
+        self
+    }
+
+    /// Binds every service to a random local port and starts serving.
+    pub async fn start(self) -> RunningMockServer {
+        let mut shutdowns = Vec::new();
+        let mut tasks = Vec::new();
+        let mut states = Vec::new();
+        let mut listings = Vec::new();
+
+        for position in self.positions {
+            let (listener, addr) = bind().await;
+            listings.push((position.name.clone(), position.device.clone(), addr.port()));
+
+            let state = Arc::new(Mutex::new(position));
+            states.push(state.clone());
+
+            let service = PositionService { state };
+            let router = tls_server()
+                .add_service(AcquisitionServiceServer::new(service.clone()))
+                .add_service(DeviceServiceServer::new(service.clone()))
+                .add_service(InstanceServiceServer::new(service.clone()))
+                .add_service(LogServiceServer::new(service.clone()))
+                .add_service(ProtocolServiceServer::new(service.clone()))
+                .add_service(RunUntilServiceServer::new(service.clone()))
+                .add_service(StatisticsServiceServer::new(service));
+
+            let (tx, rx) = oneshot::channel();
+            shutdowns.push(tx);
+            tasks.push(tokio::spawn(async move {
+                let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+                router
+                    .serve_with_incoming_shutdown(incoming, async {
+                        rx.await.ok();
+                    })
+                    .await
+                    .unwrap();
+            }));
+        }
+
+        let manager = ManagerImpl {
+            positions: Arc::new(states.clone()),
+            ports: listings.iter().map(|(_, _, port)| *port).collect(),
+        };
+        let (listener, addr) = bind().await;
+        let router = tls_server().add_service(ManagerServiceServer::new(manager));
+        let (tx, rx) = oneshot::channel();
+        shutdowns.push(tx);
+        tasks.push(tokio::spawn(async move {
+            let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+            router
+                .serve_with_incoming_shutdown(incoming, async {
+                    rx.await.ok();
+                })
+                .await
+                .unwrap();
+        }));
+
+        RunningMockServer {
+            addr,
+            positions: states,
+            shutdowns,
+            tasks,
+        }
+    }
+}
+
+/// Handle to a running mock server. Shuts down when dropped.
+pub struct RunningMockServer {
+    pub addr: SocketAddr,
+    positions: Vec<Arc<Mutex<MockPosition>>>,
+    shutdowns: Vec<oneshot::Sender<()>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl RunningMockServer {
+    /// Port of the manager service.
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Returns a snapshot of a position's current state.
+    pub fn position(&self, name: &str) -> MockPosition {
+        self.find(name).lock().unwrap().clone()
+    }
+
+    /// Modifies a position's state while the server is running.
+    pub fn update_position(&self, name: &str, f: impl FnOnce(&mut MockPosition)) {
+        f(&mut self.find(name).lock().unwrap());
+    }
+
+    /// Stops all services and waits for them to exit.
+    pub async fn shutdown(mut self) {
+        for tx in self.shutdowns.drain(..) {
+            let _ = tx.send(());
+        }
+        for task in self.tasks.drain(..) {
+            let _ = task.await;
+        }
+    }
+
+    fn find(&self, name: &str) -> &Arc<Mutex<MockPosition>> {
+        self.positions
+            .iter()
+            .find(|p| p.lock().unwrap().name == name)
+            .unwrap_or_else(|| panic!("no mock position named {name}"))
+    }
+}
+
+impl Drop for RunningMockServer {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+async fn bind() -> (TcpListener, SocketAddr) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    (listener, addr)
+}
+
+fn tls_server() -> Server {
+    let dir = super::tls_fixtures();
+    let cert = std::fs::read_to_string(dir.join("server.crt")).unwrap();
+    let key = std::fs::read_to_string(dir.join("server.key")).unwrap();
+    Server::builder()
+        .tls_config(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+        .unwrap()
+}
+
+fn timestamp(seconds: i64) -> Option<prost_types::Timestamp> {
+    Some(prost_types::Timestamp { seconds, nanos: 0 })
+}
+
+fn criteria_values(values: &[(&str, u64)]) -> CriteriaValues {
+    CriteriaValues {
+        criteria: values
+            .iter()
+            .map(|&(name, value)| {
+                (
+                    name.to_string(),
+                    prost_types::Any::from_msg(&value).unwrap(),
+                )
+            })
+            .collect(),
+    }
+}
+
+fn one<T: Send + 'static>(item: T) -> BoxStream<T> {
+    Box::pin(futures::stream::iter([Ok(item)]))
+}
+
+struct ManagerImpl {
+    positions: Arc<Vec<Arc<Mutex<MockPosition>>>>,
+    ports: Vec<u16>,
+}
+
+impl ManagerImpl {
+    fn flow_cell_positions(&self) -> Vec<FlowCellPosition> {
+        self.positions
+            .iter()
+            .zip(&self.ports)
+            .map(|(state, port)| {
+                let p = state.lock().unwrap();
+                FlowCellPosition {
+                    name: p.name.clone(),
+                    parent_name: p.device.clone(),
+                    state: flow_cell_position::State::Running as i32,
+                    rpc_ports: Some(flow_cell_position::RpcPorts {
+                        secure: *port as u32,
+                        ..Default::default()
+                    }),
+                    is_simulated: p.simulated,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+}
+
+#[tonic::async_trait]
+impl ManagerService for ManagerImpl {
+    async fn flow_cell_positions(
+        &self,
+        _request: Request<FlowCellPositionsRequest>,
+    ) -> Result<Response<BoxStream<FlowCellPositionsResponse>>, Status> {
+        let positions = self.flow_cell_positions();
+        Ok(Response::new(one(FlowCellPositionsResponse {
+            total_count: positions.len() as i32,
+            positions,
+        })))
+    }
+
+    /// Lists every position, then stays open without further changes.
+    async fn watch_flow_cell_positions(
+        &self,
+        _request: Request<WatchFlowCellPositionsRequest>,
+    ) -> Result<Response<BoxStream<WatchFlowCellPositionsResponse>>, Status> {
+        let snapshot = WatchFlowCellPositionsResponse {
+            additions: self.flow_cell_positions(),
+            ..Default::default()
+        };
+        Ok(Response::new(Box::pin(
+            futures::stream::iter([Ok(snapshot)]).chain(futures::stream::pending()),
+        )))
+    }
+
+    async fn local_authentication_token_path(
+        &self,
+        _request: Request<LocalAuthenticationTokenPathRequest>,
+    ) -> Result<Response<LocalAuthenticationTokenPathResponse>, Status> {
+        // An empty path means guest mode: no token required.
+        Ok(Response::new(
+            LocalAuthenticationTokenPathResponse::default(),
+        ))
+    }
+
+    async fn get_version_info(
+        &self,
+        _request: Request<GetVersionInfoRequest>,
+    ) -> Result<Response<GetVersionInfoResponse>, Status> {
+        Ok(Response::new(GetVersionInfoResponse {
+            minknow: Some(MinknowVersion {
+                major: 6,
+                minor: 2,
+                patch: 5,
+                full: "6.2.5".into(),
+            }),
+            distribution_version: "24.11.8".into(),
+            ..Default::default()
+        }))
+    }
+}
+
+#[derive(Clone)]
+struct PositionService {
+    state: Arc<Mutex<MockPosition>>,
+}
+
+impl PositionService {
+    fn run(&self) -> Option<MockRun> {
+        self.state.lock().unwrap().run.clone()
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn require_run(&self) -> Result<MockRun, Status> {
+        self.run()
+            .ok_or_else(|| Status::failed_precondition("No protocol is running"))
+    }
+
+    /// Looks up a current or finished run by ID.
+    #[allow(clippy::result_large_err)]
+    fn find_run(&self, run_id: &str) -> Result<MockRun, Status> {
+        let state = self.state.lock().unwrap();
+        state
+            .run
+            .iter()
+            .chain(&state.finished_runs)
+            .find(|run| run.run_id == run_id)
+            .cloned()
+            .ok_or_else(|| Status::not_found("Unknown run"))
+    }
+}
+
+#[tonic::async_trait]
+impl DeviceService for PositionService {
+    async fn get_flow_cell_info(
+        &self,
+        _request: Request<GetFlowCellInfoRequest>,
+    ) -> Result<Response<GetFlowCellInfoResponse>, Status> {
+        let state = self.state.lock().unwrap();
+        Ok(Response::new(match &state.flow_cell_id {
+            Some(id) => GetFlowCellInfoResponse {
+                has_flow_cell: true,
+                flow_cell_id: id.clone(),
+                product_code: "FLO-MIN114".to_string(),
+                channel_count: 512,
+                ..Default::default()
+            },
+            None => GetFlowCellInfoResponse::default(),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl InstanceService for PositionService {
+    async fn get_disk_space_info(
+        &self,
+        _request: Request<GetDiskSpaceInfoRequest>,
+    ) -> Result<Response<GetDiskSpaceInfoResponse>, Status> {
+        let (bytes_available, bytes_capacity) = self.state.lock().unwrap().disk_space;
+        Ok(Response::new(GetDiskSpaceInfoResponse {
+            filesystem_disk_space_info: vec![FilesystemDiskSpaceInfo {
+                filesystem_id: "data".to_string(),
+                bytes_available,
+                bytes_capacity,
+                what: vec!["reads".to_string()],
+                bytes_to_stop_cleanly: 1_000_000_000,
+                ..Default::default()
+            }],
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl AcquisitionService for PositionService {
+    async fn current_status(
+        &self,
+        _request: Request<CurrentStatusRequest>,
+    ) -> Result<Response<CurrentStatusResponse>, Status> {
+        let status = if self.run().is_some() {
+            MinknowStatus::Processing
+        } else {
+            MinknowStatus::Ready
+        };
+        Ok(Response::new(CurrentStatusResponse {
+            status: status as i32,
+        }))
+    }
+
+    async fn get_acquisition_info(
+        &self,
+        request: Request<GetAcquisitionRunInfoRequest>,
+    ) -> Result<Response<AcquisitionRunInfo>, Status> {
+        let run_id = request.into_inner().run_id;
+        let run = if run_id.is_empty() {
+            let Some(run) = self.run() else {
+                return Ok(Response::new(AcquisitionRunInfo::default()));
+            };
+            run
+        } else {
+            self.find_run(&run_id)?
+        };
+        Ok(Response::new(AcquisitionRunInfo {
+            run_id: run.run_id,
+            state: acquisition::AcquisitionState::AcquisitionRunning as i32,
+            start_time: timestamp(run.start_time),
+            config_summary: Some(AcquisitionConfigSummary {
+                reads_directory: format!("/data/{}/{}", run.experiment_group, run.sample_id),
+                pod5_reads_enabled: true,
+                fastq_reads_enabled: true,
+                ..Default::default()
+            }),
+            writer_summary: Some(AcquisitionWriterSummary {
+                bytes_to_write_produced: (run.bases_passed + run.bases_failed) * 2,
+                bytes_to_write_completed: (run.bases_passed + run.bases_failed) * 2 - 1_000_000,
+                bytes_to_write_failed: 0,
+            }),
+            yield_summary: Some(AcquisitionYieldSummary {
+                read_count: run.reads_passed + run.reads_failed,
+                basecalled_pass_read_count: run.reads_passed,
+                basecalled_fail_read_count: run.reads_failed,
+                basecalled_pass_bases: run.bases_passed,
+                basecalled_fail_bases: run.bases_failed,
+                ..Default::default()
+            }),
+            bream_info: Some(BreamInfo {
+                mux_scan_results: run
+                    .mux_scans
+                    .iter()
+                    .map(|(seconds, counts)| acquisition::MuxScanResult {
+                        counts: counts.iter().cloned().collect(),
+                        mux_scan_timestamp: *seconds,
+                    })
+                    .collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }))
+    }
+
+    async fn stop(&self, _request: Request<StopRequest>) -> Result<Response<StopResponse>, Status> {
+        self.require_run()?;
+        self.state.lock().unwrap().run = None;
+        Ok(Response::new(StopResponse::default()))
+    }
+}
+
+#[tonic::async_trait]
+impl ProtocolService for PositionService {
+    async fn get_current_protocol_run(
+        &self,
+        _request: Request<GetCurrentProtocolRunRequest>,
+    ) -> Result<Response<ProtocolRunInfo>, Status> {
+        Ok(Response::new(self.require_run()?.protocol_run_info()))
+    }
+
+    async fn list_protocol_runs(
+        &self,
+        _request: Request<ListProtocolRunsRequest>,
+    ) -> Result<Response<ListProtocolRunsResponse>, Status> {
+        let state = self.state.lock().unwrap();
+        let run_ids: Vec<String> = state
+            .finished_runs
+            .iter()
+            .chain(&state.run)
+            .map(|run| run.run_id.clone())
+            .collect();
+        Ok(Response::new(ListProtocolRunsResponse {
+            total_count: run_ids.len() as i32,
+            run_ids,
+            offset: 0,
+        }))
+    }
+
+    async fn get_run_info(
+        &self,
+        request: Request<GetRunInfoRequest>,
+    ) -> Result<Response<ProtocolRunInfo>, Status> {
+        let run = self.find_run(&request.get_ref().run_id)?;
+        Ok(Response::new(run.protocol_run_info()))
+    }
+
+    async fn pause_protocol(
+        &self,
+        _request: Request<PauseProtocolRequest>,
+    ) -> Result<Response<PauseProtocolResponse>, Status> {
+        self.set_paused(true)?;
+        Ok(Response::new(PauseProtocolResponse::default()))
+    }
+
+    async fn resume_protocol(
+        &self,
+        _request: Request<ResumeProtocolRequest>,
+    ) -> Result<Response<ResumeProtocolResponse>, Status> {
+        self.set_paused(false)?;
+        Ok(Response::new(ResumeProtocolResponse::default()))
+    }
+
+    async fn stop_protocol(
+        &self,
+        request: Request<StopProtocolRequest>,
+    ) -> Result<Response<StopProtocolResponse>, Status> {
+        self.require_run()?;
+        let mut state = self.state.lock().unwrap();
+        state.run = None;
+        state.stop_data_action = Some(request.into_inner().data_action_on_stop);
+        Ok(Response::new(StopProtocolResponse::default()))
+    }
+
+    async fn list_protocols(
+        &self,
+        _request: Request<ListProtocolsRequest>,
+    ) -> Result<Response<ListProtocolsResponse>, Status> {
+        let state = self.state.lock().unwrap();
+        let protocols = state
+            .protocols
+            .iter()
+            .map(|identifier| {
+                let (kind, name) = identifier.split_once('/').unwrap_or(("", identifier));
+                let tag = |value: &str| TagValue {
+                    tag_value: Some(tag_value::TagValue::StringValue(value.to_string())),
+                };
+                ProtocolInfo {
+                    identifier: identifier.clone(),
+                    name: name.to_string(),
+                    tags: [
+                        ("experiment type".to_string(), tag(kind)),
+                        ("kit".to_string(), tag("SQK-LSK114")),
+                        ("flow cell".to_string(), tag("FLO-MIN114")),
+                    ]
+                    .into(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        Ok(Response::new(ListProtocolsResponse { protocols }))
+    }
+
+    /// Starts a run immediately, using the request's user info.
+    async fn start_protocol(
+        &self,
+        request: Request<StartProtocolRequest>,
+    ) -> Result<Response<StartProtocolResponse>, Status> {
+        let request = request.into_inner();
+        let mut state = self.state.lock().unwrap();
+        if state.run.is_some() {
+            return Err(Status::failed_precondition("A protocol is already running"));
+        }
+        if !state.protocols.contains(&request.identifier) {
+            return Err(Status::not_found("Unknown protocol"));
+        }
+
+        let user_info = request.user_info.unwrap_or_default();
+        let defaults = MockRun::new("started-run");
+        state.run = Some(MockRun {
+            sample_id: user_info.sample_id.unwrap_or(defaults.sample_id.clone()),
+            experiment_group: user_info
+                .protocol_group_id
+                .unwrap_or(defaults.experiment_group.clone()),
+            ..defaults
+        });
+        Ok(Response::new(StartProtocolResponse {
+            run_id: "started-run".to_string(),
+        }))
+    }
+}
+
+impl PositionService {
+    #[allow(clippy::result_large_err)]
+    fn set_paused(&self, paused: bool) -> Result<(), Status> {
+        let mut state = self.state.lock().unwrap();
+        let run = state
+            .run
+            .as_mut()
+            .ok_or_else(|| Status::failed_precondition("No protocol is running"))?;
+        run.paused = paused;
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl StatisticsService for PositionService {
+    async fn stream_acquisition_output(
+        &self,
+        request: Request<StreamAcquisitionOutputRequest>,
+    ) -> Result<Response<BoxStream<StreamAcquisitionOutputResponse>>, Status> {
+        let run = self.require_run()?;
+        if request.get_ref().acquisition_run_id != run.run_id {
+            return Err(Status::not_found("Unknown acquisition run"));
+        }
+
+        let snapshots = |share: i64, of: i64| -> Vec<AcquisitionOutputSnapshot> {
+            run.yield_points
+                .iter()
+                .map(|&(seconds, bases)| AcquisitionOutputSnapshot {
+                    seconds,
+                    yield_summary: Some(AcquisitionYieldSummary {
+                        basecalled_pass_bases: bases * share / of * 9 / 10,
+                        basecalled_fail_bases: bases * share / of / 10,
+                        ..Default::default()
+                    }),
+                })
+                .collect()
+        };
+
+        let split_by_barcode = request
+            .get_ref()
+            .split
+            .as_ref()
+            .is_some_and(|split| split.barcode_name);
+        let groups = if split_by_barcode && !run.barcodes.is_empty() {
+            let total: i64 = run.barcodes.iter().map(|(_, weight)| weight).sum();
+            run.barcodes
+                .iter()
+                .map(|(name, weight)| FilteredSnapshots {
+                    filtering: vec![AcquisitionOutputKey {
+                        barcode_name: name.clone(),
+                        ..Default::default()
+                    }],
+                    snapshots: snapshots(*weight, total),
+                })
+                .collect()
+        } else {
+            vec![FilteredSnapshots {
+                filtering: Vec::new(),
+                snapshots: snapshots(1, 1),
+            }]
+        };
+
+        Ok(Response::new(one(StreamAcquisitionOutputResponse {
+            snapshots: groups,
+        })))
+    }
+
+    async fn stream_duty_time(
+        &self,
+        request: Request<StreamDutyTimeRequest>,
+    ) -> Result<Response<BoxStream<StreamDutyTimeResponse>>, Status> {
+        let run = self.require_run()?;
+        if request.get_ref().acquisition_run_id != run.run_id {
+            return Err(Status::not_found("Unknown acquisition run"));
+        }
+
+        let mut channel_states = std::collections::HashMap::new();
+        for (i, (_, _, times)) in run.duty_buckets.iter().enumerate() {
+            for (state, time) in times {
+                let data: &mut ChannelStateData = channel_states.entry(state.clone()).or_default();
+                data.state_times.resize(run.duty_buckets.len(), 0);
+                data.state_times[i] = *time;
+            }
+        }
+
+        Ok(Response::new(one(StreamDutyTimeResponse {
+            bucket_ranges: run
+                .duty_buckets
+                .iter()
+                .map(|&(start, end, _)| BucketRange { start, end })
+                .collect(),
+            channel_states,
+            pore_occupancy: Vec::new(),
+        })))
+    }
+
+    async fn stream_basecall_boxplots(
+        &self,
+        request: Request<StreamBoxplotRequest>,
+    ) -> Result<Response<BoxStream<BoxplotResponse>>, Status> {
+        let run = self.require_run()?;
+        if request.get_ref().acquisition_run_id != run.run_id {
+            return Err(Status::not_found("Unknown acquisition run"));
+        }
+
+        let datasets = if request.get_ref().data_type() == BoxplotType::BasesPerSecond {
+            run.speeds
+                .iter()
+                .map(|&median| BoxplotDataset {
+                    q25: median - 20.0,
+                    q50: median,
+                    q75: median + 20.0,
+                    ..Default::default()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Ok(Response::new(one(BoxplotResponse { datasets })))
+    }
+}
+
+#[tonic::async_trait]
+impl LogService for PositionService {
+    async fn get_user_messages(
+        &self,
+        request: Request<GetUserMessagesRequest>,
+    ) -> Result<Response<BoxStream<UserMessage>>, Status> {
+        let old = if request.get_ref().include_old_messages {
+            self.state.lock().unwrap().user_messages.clone()
+        } else {
+            Vec::new()
+        };
+        // MinKNOW keeps the stream open for messages yet to come.
+        let messages =
+            futures::stream::iter(old.into_iter().map(Ok)).chain(futures::stream::pending());
+        Ok(Response::new(Box::pin(messages)))
+    }
+}
+
+#[tonic::async_trait]
+impl RunUntilService for PositionService {
+    async fn write_target_criteria(
+        &self,
+        request: Request<WriteTargetCriteriaRequest>,
+    ) -> Result<Response<WriteTargetCriteriaResponse>, Status> {
+        let request = request.into_inner();
+        let mut state = self.state.lock().unwrap();
+        let run = state
+            .run
+            .as_mut()
+            .filter(|run| run.run_id == request.acquisition_run_id)
+            .ok_or_else(|| Status::not_found("Unknown acquisition run"))?;
+        run.pause_criteria = request.pause_criteria.unwrap_or_default();
+        run.stop_criteria = request.stop_criteria.unwrap_or_default();
+        Ok(Response::new(WriteTargetCriteriaResponse {}))
+    }
+
+    async fn stream_target_criteria(
+        &self,
+        request: Request<StreamTargetCriteriaRequest>,
+    ) -> Result<Response<BoxStream<StreamTargetCriteriaResponse>>, Status> {
+        let run = self.require_run()?;
+        if request.get_ref().acquisition_run_id != run.run_id {
+            return Err(Status::not_found("Unknown acquisition run"));
+        }
+        Ok(Response::new(one(StreamTargetCriteriaResponse {
+            pause_criteria: Some(run.pause_criteria),
+            stop_criteria: Some(run.stop_criteria),
+        })))
+    }
+
+    async fn stream_updates(
+        &self,
+        request: Request<StreamUpdatesRequest>,
+    ) -> Result<Response<BoxStream<StreamUpdatesResponse>>, Status> {
+        let run = self.require_run()?;
+        if request.get_ref().acquisition_run_id != run.run_id {
+            return Err(Status::not_found("Unknown acquisition run"));
+        }
+        let updates = run
+            .run_until_progress
+            .map(|progress| StreamUpdatesResponse {
+                update: Some(Update {
+                    current_progress_update: Some(progress),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+        Ok(Response::new(Box::pin(futures::stream::iter(
+            updates.into_iter().map(Ok),
+        ))))
+    }
+}
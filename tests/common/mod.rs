@@ -0,0 +1,29 @@
+//! Shared infrastructure for the integration tests.
+
+#![allow(dead_code, unused_imports)]
+
+pub mod mock_server;
+
+#[path = "../../src/proto.rs"]
+pub mod proto;
+
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+pub use mock_server::{MockMinKnowServer, MockPosition, MockRun, RunningMockServer};
+
+/// Directory holding the test TLS certificates.
+pub fn tls_fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls")
+}
+
+/// Path to the CA certificate that signed the mock server's certificate.
+pub fn ca_cert_path() -> PathBuf {
+    tls_fixtures().join("ca.crt")
+}
+
+/// Points the client at the test CA. Call before connecting in-process.
+pub fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| std::env::set_var("MINKNOW_TRUSTED_CA", ca_cert_path()));
+}
@@ -0,0 +1,185 @@
+//! Headless TUI sessions against the mock server.
+
+mod common;
+
+use common::{MockMinKnowServer, MockPosition, MockRun};
+use ratatui::{backend::TestBackend, Terminal};
+use std::time::Duration;
+use termion::client::RunState;
+use termion::config::{Config, ReplayConfig};
+use termion::history::HistoryStore;
+
+fn config(port: u16, history: &std::path::Path) -> Config {
+    let mut config = Config::default();
+    config.connection.port = port;
+    config.connection.connect_timeout = Duration::from_secs(2);
+    config.connection.request_timeout = Duration::from_secs(5);
+    config.tui.refresh_interval = Duration::from_millis(250);
+    config.history.path = history.to_path_buf();
+    config.history.record_interval = Duration::from_secs(1);
+    config
+}
+
+fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content
+        .chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn test_headless_session_renders_positions_and_records_history() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run(MockRun::new("run-1")),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await;
+    let history = tempfile::tempdir().unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    let app = termion::tui::run_headless(&mut terminal, config(server.port(), history.path()), 2)
+        .await
+        .unwrap();
+
+    assert!(app.is_connected());
+    assert_eq!(app.positions.len(), 2);
+    assert_eq!(app.get_run_state("X1"), Some(&RunState::Running));
+    assert_eq!(app.get_run_state("X2"), Some(&RunState::Idle));
+    assert_eq!(app.stats_cache["X1"].reads_processed, 10_000);
+    assert_eq!(app.run_info["X1"].run_id.as_deref(), Some("run-1"));
+
+    let screen = screen_text(&terminal);
+    assert!(screen.contains("X1"), "screen:\n{screen}");
+    assert!(screen.contains("X2"), "screen:\n{screen}");
+
+    let store = HistoryStore::new(history.path());
+    let runs = store.runs().unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].position, "X1");
+    assert_eq!(runs[0].run_id, "run-1");
+    let records = store.read_run(&runs[0]).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].bases_passed, 90_000_000);
+}
+
+#[tokio::test]
+async fn test_headless_session_without_server_shows_disconnected() {
+    common::init();
+    let server = MockMinKnowServer::builder().start().await;
+    let port = server.port();
+    server.shutdown().await;
+
+    let history = tempfile::tempdir().unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    let app = termion::tui::run_headless(&mut terminal, config(port, history.path()), 1)
+        .await
+        .unwrap();
+
+    assert!(!app.is_connected());
+    assert!(screen_text(&terminal).contains("Disconnected"));
+    assert!(HistoryStore::new(history.path()).runs().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_demo_session_needs_no_server() {
+    common::init();
+    let server = MockMinKnowServer::builder().start().await;
+    let port = server.port();
+    server.shutdown().await;
+
+    let history = tempfile::tempdir().unwrap();
+    let mut config = config(port, history.path());
+    config.demo = true;
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    let app = termion::tui::run_headless(&mut terminal, config, 1)
+        .await
+        .unwrap();
+
+    assert!(app.is_connected());
+    assert_eq!(app.positions.len(), 7);
+    assert_eq!(app.get_run_state("X1"), Some(&RunState::Running));
+    assert_eq!(app.get_run_state("X3"), Some(&RunState::Paused));
+    assert!(app.stats_cache["X1"].bases_called > 0);
+
+    let screen = screen_text(&terminal);
+    assert!(screen.contains("GridION"), "screen:\n{screen}");
+    assert!(screen.contains("P2 Solo"), "screen:\n{screen}");
+    // Simulated runs stay out of the history.
+    assert!(HistoryStore::new(history.path()).runs().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_recorded_session_replays_without_server() {
+    common::init();
+    let server = MockMinKnowServer::builder()
+        .with_position(
+            MockPosition::new("X1", "MN12345")
+                .with_flow_cell("FAX00001")
+                .with_run(MockRun::new("run-1")),
+        )
+        .with_position(MockPosition::new("X2", "MN12345"))
+        .start()
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let session_path = dir.path().join("session.ndjson");
+    let mut config = config(server.port(), &dir.path().join("history"));
+    config.history.enabled = false;
+    config.record = Some(session_path.clone());
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    termion::tui::run_headless(&mut terminal, config.clone(), 2)
+        .await
+        .unwrap();
+    server.shutdown().await;
+
+    let session = termion::session::read(&session_path).unwrap();
+    assert_eq!(
+        session.header.host,
+        format!("{}:{}", config.connection.host, config.connection.port)
+    );
+    assert!(!session.entries.is_empty());
+
+    config.record = None;
+    config.replay = Some(ReplayConfig {
+        path: session_path,
+        speed: 100.0,
+    });
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    let app = termion::tui::run_headless(&mut terminal, config, 1)
+        .await
+        .unwrap();
+
+    assert!(app.is_connected());
+    assert_eq!(app.positions.len(), 2);
+    assert_eq!(app.get_run_state("X1"), Some(&RunState::Running));
+    assert_eq!(app.stats_cache["X1"].reads_processed, 10_000);
+    let screen = screen_text(&terminal);
+    assert!(screen.contains("X1"), "screen:\n{screen}");
+}
+
+#[tokio::test]
+async fn test_replay_of_missing_session_fails() {
+    common::init();
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = config(1, dir.path());
+    config.replay = Some(ReplayConfig {
+        path: dir.path().join("missing.ndjson"),
+        speed: 1.0,
+    });
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+    let Err(err) = termion::tui::run_headless(&mut terminal, config, 1).await else {
+        panic!("replay of a missing session started");
+    };
+    assert!(err.to_string().contains("missing.ndjson"), "{err}");
+}